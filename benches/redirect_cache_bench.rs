@@ -0,0 +1,106 @@
+// Redirect cache decoding: full Link JSON vs the compact redirect record
+// Run with `cargo bench --bench redirect_cache_bench`; the HTML report under
+// target/criterion shows the per-iteration distribution for comparing tail latency
+
+use chrono::{Duration, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use qck_backend_core::models::link::{Link, RedirectRecord};
+use serde_json::json;
+use uuid::Uuid;
+
+/// A cached link as it looks after a security scan and metadata extraction
+fn sample_link() -> Link {
+    let now = Utc::now();
+    Link {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        short_code: "abc123X".to_string(),
+        original_url: "https://www.example.com/articles/2024/a-fairly-long-path?ref=newsletter"
+            .to_string(),
+        title: Some("An example article with a realistic title".to_string()),
+        description: Some(
+            "Extracted page description that is usually a sentence or two long, taken from \
+             the og:description or meta description tag of the destination page."
+                .to_string(),
+        ),
+        tags: Some(vec![Some("news".to_string()), Some("2024".to_string())]),
+        custom_alias: Some("example-article".to_string()),
+        is_active: true,
+        expires_at: Some(now + Duration::days(30)),
+        password_hash: None,
+        last_accessed_at: Some(now),
+        og_image: Some("https://www.example.com/images/og/article-cover.png".to_string()),
+        favicon_url: Some("https://www.example.com/favicon.ico".to_string()),
+        processing_status: "ready".to_string(),
+        metadata_extracted_at: Some(now),
+        utm_source: None,
+        utm_medium: None,
+        utm_campaign: None,
+        utm_term: None,
+        utm_content: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+        last_scanned_at: Some(now),
+        threat_score: Some(0),
+        quarantined: false,
+        quarantined_at: None,
+        quarantine_reason: None,
+        appeal_status: None,
+        appeal_message: None,
+        appealed_at: None,
+        risk_level: Some("low".to_string()),
+        threats_detected: Some(json!([])),
+        scan_warnings: Some(json!([
+            "Destination uses a query string tracking parameter"
+        ])),
+        tracking_mode: "full".to_string(),
+        organization_id: None,
+        click_count: 0,
+        notes: None,
+        deactivation_reason: None,
+        path_passthrough: false,
+        allowed_referrers: Vec::new(),
+        blocked_referrers: Vec::new(),
+        referrer_strict: false,
+        expiry_policy: "fixed".to_string(),
+        sliding_window_days: None,
+    }
+}
+
+fn bench_cache_decode(c: &mut Criterion) {
+    let link = sample_link();
+    let full = serde_json::to_string(&link).unwrap();
+    let compact = serde_json::to_string(&RedirectRecord::from(&link)).unwrap();
+
+    let mut group = c.benchmark_group("redirect_cache_decode");
+    group.throughput(Throughput::Elements(1));
+
+    // What process_redirect did per click: decode the whole Link, then check it
+    group.bench_function("full_link", |b| {
+        b.iter(|| {
+            let link: Link = serde_json::from_str(black_box(&full)).unwrap();
+            let redirectable = !link.quarantined
+                && link.is_active
+                && link.expires_at.map_or(true, |at| at > Utc::now())
+                && link.password_hash.is_none();
+            black_box((redirectable, link.original_url))
+        });
+    });
+
+    // What resolve_redirect does per click
+    group.bench_function("redirect_record", |b| {
+        b.iter(|| {
+            let record: RedirectRecord = serde_json::from_str(black_box(&compact)).unwrap();
+            let redirectable = record.has(RedirectRecord::ACTIVE)
+                && !record.has(RedirectRecord::QUARANTINED | RedirectRecord::PASSWORD_PROTECTED)
+                && !record.is_expired(Utc::now());
+            black_box((redirectable, record.destination))
+        });
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_cache_decode);
+criterion_main!(benches);
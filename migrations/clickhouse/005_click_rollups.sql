@@ -0,0 +1,75 @@
+-- ============================================================================
+-- ClickHouse Daily Click Rollups
+-- Description: Pre-aggregated per-day stats that outlive raw link_events
+-- Date: 2025-09-07
+-- Purpose: Raw events are pruned after CLICKHOUSE_RAW_RETENTION_DAYS; stats
+--          for older ranges are served from these tables instead
+-- Architecture: Populated by the scheduled rollup job (not a materialized view)
+--          so recent days can be re-rolled as late events arrive.
+--          ReplacingMergeTree(rolled_up_at) keeps the latest roll of each day;
+--          always query with FINAL.
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Clicks per link per day
+CREATE TABLE IF NOT EXISTS link_daily_stats
+(
+    link_id         UUID,
+    date            Date,
+    clicks          UInt64,
+    bot_clicks      UInt64,
+    unique_visitors AggregateFunction(uniq, IPv6),     -- Mergeable across days with uniqMerge
+    rolled_up_at    DateTime('UTC')
+)
+ENGINE = ReplacingMergeTree(rolled_up_at)
+PARTITION BY toYYYYMM(date)
+ORDER BY (link_id, date)
+SETTINGS index_granularity = 8192
+COMMENT 'Daily click rollup per link; replaces link_events for ranges beyond raw retention';
+
+-- Clicks per link per day per country
+CREATE TABLE IF NOT EXISTS link_daily_countries
+(
+    link_id         UUID,
+    date            Date,
+    country_code    LowCardinality(String),
+    clicks          UInt64,
+    rolled_up_at    DateTime('UTC')
+)
+ENGINE = ReplacingMergeTree(rolled_up_at)
+PARTITION BY toYYYYMM(date)
+ORDER BY (link_id, date, country_code)
+SETTINGS index_granularity = 8192;
+
+-- Clicks per link per day per referring host (full referrer URLs are not kept)
+CREATE TABLE IF NOT EXISTS link_daily_referrers
+(
+    link_id         UUID,
+    date            Date,
+    referrer_host   String,
+    clicks          UInt64,
+    rolled_up_at    DateTime('UTC')
+)
+ENGINE = ReplacingMergeTree(rolled_up_at)
+PARTITION BY toYYYYMM(date)
+ORDER BY (link_id, date, referrer_host)
+SETTINGS index_granularity = 8192;
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'link_daily_stats table created' as status
+WHERE exists(
+    SELECT 1 FROM system.tables
+    WHERE database = 'qck_analytics' AND name = 'link_daily_stats'
+);
+
+-- ============================================================================
+-- MIGRATION COMPLETE
+-- ============================================================================
+-- Architecture: link_events → rollup job (hourly) → link_daily_* (ReplacingMergeTree)
+-- Retention: the same job drops link_events partitions older than the raw
+--            retention window once they are fully rolled up
+-- ============================================================================
@@ -0,0 +1,81 @@
+-- ============================================================================
+-- ClickHouse Repeat Click Flag
+-- Description: Marks repeat clicks from the same visitor within the dedup window
+-- Date: 2025-09-13
+-- Purpose: Repeats still redirect and are stored, but default click counts
+--          exclude them; raw totals remain available
+-- Architecture: Events recorded before this migration have is_repeat = false,
+--          so their deduplicated and raw totals are the same.
+--          Aggregates store repeat counts (not deduplicated counts) so existing
+--          rows stay correct without a backfill.
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Buffer tables must be dropped before their destination table is altered
+DROP TABLE IF EXISTS link_events_buffer1;
+DROP TABLE IF EXISTS link_events_buffer2;
+DROP TABLE IF EXISTS link_events_buffer3;
+
+ALTER TABLE link_events
+    ADD COLUMN IF NOT EXISTS is_repeat Bool DEFAULT false;
+
+-- Recreate the buffers with the settings from 001_analytics_events
+CREATE TABLE link_events_buffer1 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer2 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 15, 45, 50, 5000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer3 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+-- ============================================================================
+-- LINK TOTALS
+-- ============================================================================
+
+ALTER TABLE link_totals
+    ADD COLUMN IF NOT EXISTS repeat_clicks AggregateFunction(sum, UInt64);
+
+DROP TABLE IF EXISTS link_totals_mv;
+
+CREATE MATERIALIZED VIEW link_totals_mv TO link_totals AS
+SELECT
+    link_id,
+    sumState(toUInt64(1)) AS total_clicks,
+    uniqState(ip_address) AS unique_visitors,
+    sumState(CASE WHEN user_id IS NOT NULL THEN toUInt64(1) ELSE toUInt64(0) END) AS total_users,
+    sumState(CASE WHEN is_bot = 1 THEN toUInt64(1) ELSE toUInt64(0) END) AS total_bots,
+    minState(timestamp) AS first_click,
+    maxState(timestamp) AS last_click,
+    avgState(toUInt32(response_time)) AS avg_response_time,
+    sumState(CASE WHEN is_repeat = 1 THEN toUInt64(1) ELSE toUInt64(0) END) AS repeat_clicks
+FROM link_events
+GROUP BY link_id;
+
+-- ============================================================================
+-- DAILY ROLLUPS
+-- ============================================================================
+
+-- link_daily_stats keeps raw clicks next to repeats; the country and referrer
+-- rollups only count deduplicated clicks
+ALTER TABLE link_daily_stats
+    ADD COLUMN IF NOT EXISTS repeat_clicks UInt64 DEFAULT 0;
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'is_repeat column added' as status
+WHERE exists(
+    SELECT 1 FROM system.columns
+    WHERE database = 'qck_analytics' AND table = 'link_events' AND name = 'is_repeat'
+);
+
+-- ============================================================================
+-- MIGRATION COMPLETE
+-- ============================================================================
+-- Deduplicated clicks: count() - countIf(is_repeat) on link_events,
+--                      sumMerge(total_clicks) - sumMerge(repeat_clicks) on link_totals,
+--                      sum(clicks) - sum(repeat_clicks) on link_daily_stats
+-- ============================================================================
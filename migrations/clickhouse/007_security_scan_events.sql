@@ -0,0 +1,40 @@
+-- ============================================================================
+-- ClickHouse Security Scan Events
+-- Description: One row per (sampled) URL security scan
+-- Date: 2025-09-16
+-- Purpose: Block rate, threat types and scan latency for operators
+--          (GET /v1/admin/security/metrics)
+-- Architecture: Written in batches by the scan telemetry recorder; sampled by
+--          SECURITY_SCAN_TELEMETRY_SAMPLE_RATE. URLs are stored as SHA-256
+--          hashes only; the domain is kept for top-blocked-domain reports.
+-- ============================================================================
+
+USE qck_analytics;
+
+CREATE TABLE IF NOT EXISTS security_scan_events
+(
+    timestamp       DateTime64(3, 'UTC'),
+    date            Date DEFAULT toDate(timestamp),
+    url_hash        FixedString(64),                   -- Hex SHA-256 of the scanned URL
+    domain          String,
+    threat_score    UInt8,
+    threats         Array(LowCardinality(String)),     -- ThreatType names
+    duration_ms     UInt32,
+    outcome         Enum8('allowed' = 1, 'blocked' = 2, 'trusted' = 3)
+)
+ENGINE = MergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (date, outcome, domain)
+TTL date + INTERVAL 180 DAY
+SETTINGS index_granularity = 8192
+COMMENT 'Sampled URL security scan results; URLs are hashed';
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'security_scan_events table created' as status
+WHERE exists(
+    SELECT 1 FROM system.tables
+    WHERE database = 'qck_analytics' AND name = 'security_scan_events'
+);
@@ -0,0 +1,38 @@
+-- ============================================================================
+-- ClickHouse Click Sub-Path
+-- Description: Records the path requested after the short code on
+--          path-passthrough links (/{short_code}/{sub_path})
+-- Date: 2025-09-17
+-- Architecture: Empty for plain short code redirects and for events recorded
+--          before this migration
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Buffer tables must be dropped before their destination table is altered
+DROP TABLE IF EXISTS link_events_buffer1;
+DROP TABLE IF EXISTS link_events_buffer2;
+DROP TABLE IF EXISTS link_events_buffer3;
+
+ALTER TABLE link_events
+    ADD COLUMN IF NOT EXISTS sub_path String DEFAULT '';
+
+-- Recreate the buffers with the settings from 001_analytics_events
+CREATE TABLE link_events_buffer1 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer2 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 15, 45, 50, 5000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer3 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'sub_path column added' as status
+WHERE exists(
+    SELECT 1 FROM system.columns
+    WHERE database = 'qck_analytics' AND table = 'link_events' AND name = 'sub_path'
+);
@@ -0,0 +1,40 @@
+-- ============================================================================
+-- ClickHouse Rate Limit Offenders
+-- Description: Blocked request counts per rate limit key, endpoint and hour
+-- Date: 2025-09-19
+-- Purpose: Long-term trends of rate-limited IPs, emails and users; recent
+--          windows are served from Redis (GET /v1/admin/rate-limits/offenders)
+-- Architecture: Exported every 15 minutes by the rate_limit_offender_export
+--          task. The last completed hours are re-exported on every run;
+--          ReplacingMergeTree keeps the latest count per (hour, endpoint, key).
+--          Only the top 100 keys per endpoint and hour are exported.
+-- ============================================================================
+
+USE qck_analytics;
+
+CREATE TABLE IF NOT EXISTS rate_limit_offenders
+(
+    hour              DateTime('UTC'),
+    date              Date DEFAULT toDate(hour),
+    endpoint          LowCardinality(String),
+    rate_limit_key    String,
+    key_kind          Enum8('ip' = 1, 'email' = 2, 'user' = 3, 'other' = 4),
+    blocked_requests  UInt64,
+    exported_at       DateTime('UTC') DEFAULT now()
+)
+ENGINE = ReplacingMergeTree(exported_at)
+PARTITION BY toYYYYMM(date)
+ORDER BY (hour, endpoint, rate_limit_key)
+TTL date + INTERVAL 365 DAY
+SETTINGS index_granularity = 8192
+COMMENT 'Hourly blocked counts of the most rate-limited keys per endpoint';
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'rate_limit_offenders table created' as status
+WHERE exists(
+    SELECT 1 FROM system.tables
+    WHERE database = 'qck_analytics' AND name = 'rate_limit_offenders'
+);
@@ -0,0 +1,42 @@
+-- ============================================================================
+-- ClickHouse Referrer Campaign
+-- Description: Records the utm_source, utm_medium and utm_campaign found in
+--          the referrer URL, i.e. the campaign that drove the click to the
+--          short link (GET /v1/links/{id}/stats/campaigns)
+-- Date: 2025-09-21
+-- Architecture: Kept apart from utm_source/utm_medium/utm_campaign, which
+--          describe the destination. Empty when the referrer carries no UTM
+--          parameters and for events recorded before this migration
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Buffer tables must be dropped before their destination table is altered
+DROP TABLE IF EXISTS link_events_buffer1;
+DROP TABLE IF EXISTS link_events_buffer2;
+DROP TABLE IF EXISTS link_events_buffer3;
+
+ALTER TABLE link_events
+    ADD COLUMN IF NOT EXISTS referrer_utm_source LowCardinality(String) DEFAULT '',
+    ADD COLUMN IF NOT EXISTS referrer_utm_medium LowCardinality(String) DEFAULT '',
+    ADD COLUMN IF NOT EXISTS referrer_utm_campaign LowCardinality(String) DEFAULT '';
+
+-- Recreate the buffers with the settings from 001_analytics_events
+CREATE TABLE link_events_buffer1 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer2 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 15, 45, 50, 5000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer3 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'referrer campaign columns added' as status
+WHERE exists(
+    SELECT 1 FROM system.columns
+    WHERE database = 'qck_analytics' AND table = 'link_events' AND name = 'referrer_utm_campaign'
+);
@@ -0,0 +1,42 @@
+-- ============================================================================
+-- ClickHouse Click Entry Point
+-- Description: Records the code a click came in through and whether it was
+--          the link's custom alias rather than its short code, so clicks can
+--          be split by entry point (GET /v1/links/{id}/stats) and the clicked
+--          URL reconstructed after the link is deleted
+-- Date: 2025-09-24
+-- Architecture: short_code is the code as requested. Events recorded before
+--          this migration have an empty short_code and via_alias = false, and
+--          are counted as unknown rather than as short code clicks
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Buffer tables must be dropped before their destination table is altered
+DROP TABLE IF EXISTS link_events_buffer1;
+DROP TABLE IF EXISTS link_events_buffer2;
+DROP TABLE IF EXISTS link_events_buffer3;
+
+ALTER TABLE link_events
+    ADD COLUMN IF NOT EXISTS short_code String DEFAULT '',
+    ADD COLUMN IF NOT EXISTS via_alias Bool DEFAULT false;
+
+-- Recreate the buffers with the settings from 001_analytics_events
+CREATE TABLE link_events_buffer1 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer2 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 15, 45, 50, 5000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer3 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'click entry point columns added' as status
+WHERE exists(
+    SELECT 1 FROM system.columns
+    WHERE database = 'qck_analytics' AND table = 'link_events' AND name = 'via_alias'
+);
@@ -0,0 +1,40 @@
+-- ============================================================================
+-- ClickHouse Click Referrer Block
+-- Description: Flags clicks that a link's referrer restrictions (allowed or
+--          blocked referrer domains, strict mode) turned away with a 403
+--          instead of redirecting
+-- Date: 2025-09-28
+-- Architecture: Blocked clicks are recorded like any other click with
+--          status_code 403. Events recorded before this migration have
+--          blocked_by_referrer = false
+-- ============================================================================
+
+USE qck_analytics;
+
+-- Buffer tables must be dropped before their destination table is altered
+DROP TABLE IF EXISTS link_events_buffer1;
+DROP TABLE IF EXISTS link_events_buffer2;
+DROP TABLE IF EXISTS link_events_buffer3;
+
+ALTER TABLE link_events
+    ADD COLUMN IF NOT EXISTS blocked_by_referrer Bool DEFAULT false;
+
+-- Recreate the buffers with the settings from 001_analytics_events
+CREATE TABLE link_events_buffer1 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer2 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 15, 45, 50, 5000, 10000, 10000000);
+
+CREATE TABLE link_events_buffer3 AS link_events
+ENGINE = Buffer(qck_analytics, link_events, 16, 10, 30, 100, 10000, 10000, 10000000);
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'blocked_by_referrer column added' as status
+WHERE exists(
+    SELECT 1 FROM system.columns
+    WHERE database = 'qck_analytics' AND table = 'link_events' AND name = 'blocked_by_referrer'
+);
@@ -0,0 +1,14 @@
+-- ============================================================================
+-- ClickHouse URLhaus Keep Listed Entries
+-- Description: Removes the 7-day TTL from urlhaus_threats
+-- Date: 2025-09-29
+-- Architecture: The updater now applies the feed as a diff, inserting new
+--          URLs and deleting delisted ones, and skips the write entirely when
+--          the feed did not change. Rows are therefore no longer re-inserted
+--          on every refresh, and the TTL would expire URLs that are still
+--          listed
+-- ============================================================================
+
+USE qck_analytics;
+
+ALTER TABLE urlhaus_threats REMOVE TTL;
@@ -0,0 +1,36 @@
+-- ============================================================================
+-- ClickHouse Link Conversions
+-- Description: One row per conversion pixel hit (GET /v1/pixel/{link_id}.gif)
+-- Date: 2025-10-02
+-- Purpose: Conversions and conversion rate per link
+--          (GET /v1/links/{id}/stats/conversions)
+-- Architecture: Written in batches by the conversion recorder; the pixel never
+--          touches Postgres. visitor_hash is the HMAC visitor hash the
+--          redirect stored in the qck_vid cookie, so repeat conversions by the
+--          same visitor can be told apart. Deleted with the link's click data.
+-- ============================================================================
+
+USE qck_analytics;
+
+CREATE TABLE IF NOT EXISTS link_conversions
+(
+    timestamp       DateTime64(3, 'UTC'),
+    date            Date DEFAULT toDate(timestamp),
+    link_id         UUID,
+    visitor_hash    FixedString(64)                    -- Hex HMAC-SHA256 from the qck_vid cookie
+)
+ENGINE = MergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (link_id, date)
+SETTINGS index_granularity = 8192
+COMMENT 'Conversion pixel hits attributed to a short link';
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'link_conversions table created' as status
+WHERE exists(
+    SELECT 1 FROM system.tables
+    WHERE database = 'qck_analytics' AND name = 'link_conversions'
+);
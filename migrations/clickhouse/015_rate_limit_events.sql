@@ -0,0 +1,44 @@
+-- ============================================================================
+-- ClickHouse Rate Limit Events
+-- Description: Sampled rate limit checks from every replica
+-- Date: 2025-10-06
+-- Purpose: Rate limiting metrics that survive restarts and cover the whole
+--          deployment (GET /v1/metrics/rate-limiting?window_minutes=)
+-- Architecture: Each replica buffers the checks picked by
+--          RATE_LIMIT_ANALYTICS_SAMPLE_RATE and writes them every
+--          RATE_LIMIT_ANALYTICS_FLUSH_INTERVAL_SECONDS. sample_weight is the
+--          number of checks a row stands for, so sum(sample_weight) estimates
+--          the checks made even when replicas sample at different rates.
+-- ============================================================================
+
+USE qck_analytics;
+
+CREATE TABLE IF NOT EXISTS rate_limit_events
+(
+    timestamp         DateTime64(3, 'UTC'),
+    date              Date DEFAULT toDate(timestamp),
+    endpoint          LowCardinality(String),
+    rate_limit_key    String,
+    blocked           UInt8,
+    current_count     UInt32,
+    request_limit     UInt32,
+    user_tier         LowCardinality(String),              -- Empty when unknown
+    check_latency_ms  UInt32,
+    sample_weight     UInt32
+)
+ENGINE = MergeTree()
+PARTITION BY toYYYYMM(date)
+ORDER BY (date, endpoint, timestamp)
+TTL date + INTERVAL 90 DAY
+SETTINGS index_granularity = 8192
+COMMENT 'Sampled rate limit checks; sum(sample_weight) estimates total checks';
+
+-- ============================================================================
+-- VALIDATION
+-- ============================================================================
+
+SELECT 'rate_limit_events table created' as status
+WHERE exists(
+    SELECT 1 FROM system.tables
+    WHERE database = 'qck_analytics' AND name = 'rate_limit_events'
+);
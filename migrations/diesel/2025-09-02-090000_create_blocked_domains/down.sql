@@ -0,0 +1,2 @@
+-- Drop operator-managed domain blocklist
+DROP TABLE IF EXISTS blocked_domains;
@@ -0,0 +1,13 @@
+-- Operator-managed domain blocklist
+-- Entries are merged into DomainSecurityService at runtime alongside data/blocked_domains.json
+
+CREATE TABLE blocked_domains (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    pattern VARCHAR(253) NOT NULL UNIQUE, -- Lowercased domain (example.com) or TLD (zip)
+    entry_type VARCHAR(10) NOT NULL CHECK (entry_type IN ('domain', 'tld')),
+    reason TEXT NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_blocked_domains_created_at ON blocked_domains(created_at DESC);
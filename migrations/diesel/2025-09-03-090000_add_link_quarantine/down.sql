@@ -0,0 +1,12 @@
+DROP INDEX IF EXISTS idx_links_pending_appeals;
+DROP INDEX IF EXISTS idx_links_rescan_queue;
+
+ALTER TABLE links
+    DROP COLUMN IF EXISTS appealed_at,
+    DROP COLUMN IF EXISTS appeal_message,
+    DROP COLUMN IF EXISTS appeal_status,
+    DROP COLUMN IF EXISTS quarantine_reason,
+    DROP COLUMN IF EXISTS quarantined_at,
+    DROP COLUMN IF EXISTS quarantined,
+    DROP COLUMN IF EXISTS threat_score,
+    DROP COLUMN IF EXISTS last_scanned_at;
@@ -0,0 +1,22 @@
+-- Retroactive re-scanning and quarantine of existing links
+-- A URL that was clean at creation can later turn malicious
+
+ALTER TABLE links
+    ADD COLUMN last_scanned_at TIMESTAMPTZ,
+    ADD COLUMN threat_score SMALLINT CHECK (threat_score BETWEEN 0 AND 100),
+    ADD COLUMN quarantined BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN quarantined_at TIMESTAMPTZ,
+    ADD COLUMN quarantine_reason TEXT,
+    ADD COLUMN appeal_status VARCHAR(20) CHECK (appeal_status IN ('pending', 'approved', 'rejected')),
+    ADD COLUMN appeal_message TEXT,
+    ADD COLUMN appealed_at TIMESTAMPTZ;
+
+-- Rolling re-scan picks the oldest-scanned active links first (never-scanned sorts first)
+CREATE INDEX IF NOT EXISTS idx_links_rescan_queue
+    ON links(last_scanned_at NULLS FIRST)
+    WHERE is_active = true AND deleted_at IS NULL AND quarantined = false;
+
+-- Admin review queue
+CREATE INDEX IF NOT EXISTS idx_links_pending_appeals
+    ON links(appealed_at)
+    WHERE appeal_status = 'pending';
@@ -0,0 +1,4 @@
+ALTER TABLE links
+    DROP COLUMN IF EXISTS scan_warnings,
+    DROP COLUMN IF EXISTS threats_detected,
+    DROP COLUMN IF EXISTS risk_level;
@@ -0,0 +1,7 @@
+-- Persist the full security scan result on each link
+-- threat_score and last_scanned_at already exist from the quarantine migration
+
+ALTER TABLE links
+    ADD COLUMN risk_level VARCHAR(20),
+    ADD COLUMN threats_detected JSONB,
+    ADD COLUMN scan_warnings JSONB;
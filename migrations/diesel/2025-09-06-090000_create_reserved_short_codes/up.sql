@@ -0,0 +1,10 @@
+-- Short codes held by the pre-generated pool
+-- Reserved codes count as taken, so custom aliases and on-demand generation never hand them out;
+-- reservations older than SHORT_CODE_POOL_RESERVATION_TTL are recycled
+
+CREATE TABLE reserved_short_codes (
+    code VARCHAR(20) PRIMARY KEY,
+    reserved_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_reserved_short_codes_reserved_at ON reserved_short_codes(reserved_at);
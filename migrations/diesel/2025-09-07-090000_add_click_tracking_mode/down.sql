@@ -0,0 +1,2 @@
+ALTER TABLE links DROP COLUMN IF EXISTS tracking_mode;
+ALTER TABLE users DROP COLUMN IF EXISTS default_tracking_mode;
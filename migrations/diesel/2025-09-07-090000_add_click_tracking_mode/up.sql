@@ -0,0 +1,10 @@
+-- Consent-aware click tracking
+-- full: store IP and user agent; anonymous: count only; respect_dnt: anonymous when DNT or Sec-GPC is sent
+
+ALTER TABLE users
+    ADD COLUMN default_tracking_mode VARCHAR(20) NOT NULL DEFAULT 'full'
+        CHECK (default_tracking_mode IN ('full', 'anonymous', 'respect_dnt'));
+
+ALTER TABLE links
+    ADD COLUMN tracking_mode VARCHAR(20) NOT NULL DEFAULT 'full'
+        CHECK (tracking_mode IN ('full', 'anonymous', 'respect_dnt'));
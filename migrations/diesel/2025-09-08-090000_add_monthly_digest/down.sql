@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_users_monthly_digest;
+ALTER TABLE users DROP COLUMN IF EXISTS last_digest_period;
+ALTER TABLE users DROP COLUMN IF EXISTS monthly_digest_enabled;
@@ -0,0 +1,8 @@
+-- Opt-in monthly usage digest
+-- last_digest_period records the last month (YYYY-MM) a digest was claimed for, so a restarted run skips users already handled
+
+ALTER TABLE users
+    ADD COLUMN monthly_digest_enabled BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN last_digest_period VARCHAR(7);
+
+CREATE INDEX idx_users_monthly_digest ON users (id) WHERE monthly_digest_enabled = TRUE;
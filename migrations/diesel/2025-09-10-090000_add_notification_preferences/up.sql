@@ -0,0 +1,9 @@
+-- Per-user email notification opt-in/out, keyed by notification kind
+-- Missing keys fall back to the defaults in NotificationPreferences; monthly_summary mirrors
+-- monthly_digest_enabled, which stays as the indexed column the digest task scans
+
+ALTER TABLE users
+    ADD COLUMN notification_preferences JSONB NOT NULL DEFAULT '{}'::jsonb;
+
+UPDATE users
+SET notification_preferences = jsonb_build_object('monthly_summary', monthly_digest_enabled);
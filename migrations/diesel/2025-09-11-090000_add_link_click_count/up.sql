@@ -0,0 +1,5 @@
+-- Running click total per link, incremented by the click sync job from the Redis counters
+-- ClickHouse stays the source for detailed analytics; this keeps a total without querying it
+
+ALTER TABLE links
+    ADD COLUMN click_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS link_revisions;
+ALTER TABLE links DROP COLUMN IF EXISTS notes;
@@ -0,0 +1,18 @@
+-- Free-text notes on links, and a per-link history of changes to their mutable fields
+-- Revisions are written in the same transaction as the change they describe
+
+ALTER TABLE links
+    ADD COLUMN notes TEXT;
+
+-- No foreign key to users: history must outlive the accounts that made the changes
+CREATE TABLE link_revisions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    link_id UUID NOT NULL REFERENCES links(id) ON DELETE CASCADE,
+    actor_id UUID,
+    source VARCHAR(20) NOT NULL CHECK (source IN ('update', 'bulk_status')),
+    changes JSONB NOT NULL, -- [{"field", "before", "after"}] for each changed field
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_link_revisions_link_created ON link_revisions(link_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_link_revisions_created_at ON link_revisions(created_at);
@@ -0,0 +1,18 @@
+-- Data portability: account archives built in the background and downloaded with an emailed token
+-- Archives are deleted once their download token expires
+
+CREATE TABLE account_exports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'ready', 'failed')),
+    include_stats BOOLEAN NOT NULL DEFAULT FALSE,
+    archive JSONB,
+    -- Only the SHA-256 of the emailed token is stored
+    token_hash VARCHAR(64) UNIQUE,
+    expires_at TIMESTAMPTZ NOT NULL,
+    completed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_account_exports_user_created ON account_exports(user_id, created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_account_exports_expires_at ON account_exports(expires_at);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_links_alias_short_code_lower;
+DROP INDEX IF EXISTS idx_links_custom_alias_lower;
@@ -0,0 +1,37 @@
+-- Custom aliases match in any case and are stored trimmed and lowercase
+-- Where existing aliases collide once normalized, the oldest live link keeps the alias; the
+-- others stop being aliases but keep their exact short code, so their URLs still resolve
+
+WITH ranked AS (
+    SELECT id,
+           ROW_NUMBER() OVER (
+               PARTITION BY lower(btrim(custom_alias))
+               ORDER BY deleted_at IS NOT NULL, created_at, id
+           ) AS position
+    FROM links
+    WHERE custom_alias IS NOT NULL
+)
+UPDATE links
+SET custom_alias = NULL
+FROM ranked
+WHERE links.id = ranked.id AND ranked.position > 1;
+
+-- An alias link's short code is the alias; it is left as-is if a generated code already
+-- holds the lowercase form (exact short codes win over aliases on lookup)
+UPDATE links
+SET short_code = CASE
+        WHEN short_code = custom_alias AND NOT EXISTS (
+            SELECT 1 FROM links other
+            WHERE other.short_code = lower(btrim(links.custom_alias))
+              AND other.id <> links.id
+        ) THEN lower(btrim(custom_alias))
+        ELSE short_code
+    END,
+    custom_alias = lower(btrim(custom_alias))
+WHERE custom_alias IS NOT NULL
+  AND custom_alias <> lower(btrim(custom_alias));
+
+-- Also serves alias lookups; generated short codes stay case-sensitive
+CREATE UNIQUE INDEX IF NOT EXISTS idx_links_custom_alias_lower ON links(lower(custom_alias));
+CREATE UNIQUE INDEX IF NOT EXISTS idx_links_alias_short_code_lower ON links(lower(short_code))
+    WHERE custom_alias IS NOT NULL;
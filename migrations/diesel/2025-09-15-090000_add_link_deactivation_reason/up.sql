@@ -0,0 +1,5 @@
+-- Why a link was switched off by the system rather than by its owner
+-- The expiry sweep sets 'expired' on active links past expires_at so they stop counting as active
+
+ALTER TABLE links
+    ADD COLUMN deactivation_reason VARCHAR(20);
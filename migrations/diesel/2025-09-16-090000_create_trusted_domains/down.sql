@@ -0,0 +1,2 @@
+-- Drop operator-managed trusted domains
+DROP TABLE IF EXISTS trusted_domains;
@@ -0,0 +1,12 @@
+-- Operator-managed trusted domains
+-- URLs on these domains (and their subdomains) skip the security scan's blocking verdict
+
+CREATE TABLE trusted_domains (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    pattern VARCHAR(253) NOT NULL UNIQUE, -- Lowercased domain (example.com)
+    reason TEXT NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_trusted_domains_created_at ON trusted_domains(created_at DESC);
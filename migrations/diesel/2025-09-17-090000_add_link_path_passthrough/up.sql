@@ -0,0 +1,5 @@
+-- Path-preserving short links
+-- When set, /{short_code}/{rest} redirects to original_url with rest appended to its path
+
+ALTER TABLE links
+    ADD COLUMN path_passthrough BOOLEAN NOT NULL DEFAULT false;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS page_links;
+DROP TABLE IF EXISTS pages;
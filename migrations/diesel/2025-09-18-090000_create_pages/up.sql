@@ -0,0 +1,25 @@
+-- Landing pages (link-in-bio): one short code that lists several of the owner's links
+-- Page short codes share the namespace of link short codes and aliases
+
+CREATE TABLE pages (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    short_code VARCHAR(20) NOT NULL UNIQUE, -- Stored lowercase
+    title VARCHAR(255) NOT NULL,
+    description TEXT,
+    theme VARCHAR(20) NOT NULL DEFAULT 'light' CHECK (theme IN ('light', 'dark')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_pages_user ON pages(user_id, created_at DESC);
+
+-- Buttons on a page, in display order; removing a link removes its button
+CREATE TABLE page_links (
+    page_id UUID NOT NULL REFERENCES pages(id) ON DELETE CASCADE,
+    link_id UUID NOT NULL REFERENCES links(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    PRIMARY KEY (page_id, link_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_page_links_link ON page_links(link_id);
@@ -0,0 +1,2 @@
+-- Drop operator-managed client IP denylist
+DROP TABLE IF EXISTS denied_ips;
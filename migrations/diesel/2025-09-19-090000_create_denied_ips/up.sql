@@ -0,0 +1,13 @@
+-- Operator-managed client IP denylist
+-- Requests from these addresses are rejected with 403 before reaching any handler
+
+CREATE TABLE denied_ips (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    ip VARCHAR(45) NOT NULL UNIQUE, -- Canonical IPv4 or IPv6 address
+    reason TEXT NOT NULL,
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    expires_at TIMESTAMPTZ -- NULL denies the address until the entry is removed
+);
+
+CREATE INDEX IF NOT EXISTS idx_denied_ips_created_at ON denied_ips(created_at DESC);
@@ -0,0 +1,4 @@
+-- Restore the refresh token indexes from before the cleanup job
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_jti_hash ON refresh_tokens(jti_hash);
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_user_id ON refresh_tokens(user_id);
+DROP INDEX IF EXISTS idx_refresh_tokens_revoked_at;
@@ -0,0 +1,14 @@
+-- Indexes for the refresh token cleanup job
+-- The job deletes rows WHERE expires_at < cutoff OR revoked_at < cutoff. expires_at is already
+-- indexed (idx_refresh_tokens_expires_at); a partial index on revoked_at lets Postgres answer the
+-- OR with a bitmap scan instead of reading the whole table. Only revoked rows are indexed, and
+-- those are the rows the job removes, so it stays small.
+CREATE INDEX IF NOT EXISTS idx_refresh_tokens_revoked_at
+    ON refresh_tokens(revoked_at)
+    WHERE revoked_at IS NOT NULL;
+
+-- Duplicates: idx_refresh_tokens_user_active_v2 (user_id, revoked_at, expires_at) serves every
+-- lookup by user_id, and the UNIQUE constraint on jti_hash has its own index. Each one dropped is
+-- one index fewer to maintain on every insert, rotation and delete.
+DROP INDEX IF EXISTS idx_refresh_tokens_user_id;
+DROP INDEX IF EXISTS idx_refresh_tokens_jti_hash;
@@ -0,0 +1,2 @@
+-- Dropped and rewritten tags cannot be restored; nothing to undo
+SELECT 1;
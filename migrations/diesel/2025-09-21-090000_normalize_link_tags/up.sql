@@ -0,0 +1,28 @@
+-- Link tags are stored trimmed, lowercase and deduplicated, at most 20 per link and 50
+-- characters each. Existing arrays are brought in line: tags that are empty, too long or
+-- contain control characters are dropped, the first occurrence of each tag keeps its place,
+-- and only the first 20 are kept. Links left without tags get NULL, as on create.
+
+WITH normalized AS (
+    SELECT id,
+           NULLIF(ARRAY(
+               SELECT tag
+               FROM (
+                   SELECT lower(trimmed) AS tag, min(ord) AS first_seen
+                   FROM unnest(links.tags) WITH ORDINALITY AS submitted(t, ord),
+                        btrim(t, E' \t\n\r') AS trimmed
+                   WHERE char_length(trimmed) BETWEEN 1 AND 50
+                     AND trimmed !~ '[[:cntrl:]]'
+                   GROUP BY lower(trimmed)
+               ) distinct_tags
+               ORDER BY first_seen
+               LIMIT 20
+           ), '{}') AS tags
+    FROM links
+    WHERE tags IS NOT NULL
+)
+UPDATE links
+SET tags = normalized.tags
+FROM normalized
+WHERE links.id = normalized.id
+  AND links.tags IS DISTINCT FROM normalized.tags;
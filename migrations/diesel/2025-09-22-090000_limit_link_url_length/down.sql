@@ -0,0 +1 @@
+ALTER TABLE links DROP CONSTRAINT IF EXISTS links_original_url_length;
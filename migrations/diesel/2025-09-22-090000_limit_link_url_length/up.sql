@@ -0,0 +1,13 @@
+-- Destination URLs are limited to 2048 bytes (url_validator::MAX_URL_LENGTH), the same limit
+-- request validation and the security scanner apply. Links stored before this migration ran,
+-- under the old 8192-byte limit, are exempt so that clicks and edits to them keep working.
+
+DO $$
+BEGIN
+    EXECUTE format(
+        'ALTER TABLE links ADD CONSTRAINT links_original_url_length '
+        'CHECK (octet_length(original_url) <= 2048 OR created_at < %L)',
+        now()
+    );
+END
+$$;
@@ -0,0 +1,5 @@
+-- Timezone for analytics day buckets, as an IANA name (e.g. Asia/Tokyo)
+-- Validated by the API; existing users keep UTC buckets
+
+ALTER TABLE users
+    ADD COLUMN timezone VARCHAR(64) NOT NULL DEFAULT 'UTC';
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS idx_users_links_paused_at;
+ALTER TABLE users DROP COLUMN IF EXISTS links_paused_at;
@@ -0,0 +1,8 @@
+-- Account-wide kill switch: while set, none of the user's links redirect
+-- The redirect path reads it from the Redis key links_paused:{user_id}
+
+ALTER TABLE users
+    ADD COLUMN links_paused_at TIMESTAMPTZ;
+
+CREATE INDEX idx_users_links_paused_at ON users (links_paused_at)
+    WHERE links_paused_at IS NOT NULL;
@@ -0,0 +1,5 @@
+-- Query parameters (names or prefix* patterns) removed from the user's destination URLs
+-- when links are created or edited; links stored earlier keep their URLs
+
+ALTER TABLE users
+    ADD COLUMN strip_query_params TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,4 @@
+ALTER TABLE links
+    DROP COLUMN IF EXISTS referrer_strict,
+    DROP COLUMN IF EXISTS blocked_referrers,
+    DROP COLUMN IF EXISTS allowed_referrers;
@@ -0,0 +1,8 @@
+-- Referrer restrictions per link
+-- Registrable domains whose Referer may (allowed_referrers) or may not (blocked_referrers)
+-- follow the link; referrer_strict also turns away clicks that send no Referer
+
+ALTER TABLE links
+    ADD COLUMN allowed_referrers TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN blocked_referrers TEXT[] NOT NULL DEFAULT '{}',
+    ADD COLUMN referrer_strict BOOLEAN NOT NULL DEFAULT false;
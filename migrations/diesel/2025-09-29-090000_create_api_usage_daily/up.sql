@@ -0,0 +1,13 @@
+-- Daily API request counts per user and endpoint
+-- Requests are counted in Redis during the day; completed days are rolled up here
+
+CREATE TABLE api_usage_daily (
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    day DATE NOT NULL,
+    endpoint VARCHAR(255) NOT NULL, -- "GET /v1/links/{id}"
+    requests BIGINT NOT NULL DEFAULT 0,
+    rate_limited BIGINT NOT NULL DEFAULT 0, -- requests answered with 429
+    PRIMARY KEY (user_id, day, endpoint)
+);
+
+CREATE INDEX IF NOT EXISTS idx_api_usage_daily_day ON api_usage_daily(day);
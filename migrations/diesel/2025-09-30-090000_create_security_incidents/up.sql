@@ -0,0 +1,18 @@
+-- Security incidents detected on a user's account, listed at GET /v1/auth/security-events
+-- Rows are written when a rotated refresh token is replayed and its family is revoked
+
+CREATE TABLE security_incidents (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    kind VARCHAR(50) NOT NULL, -- "refresh_token_reuse"
+    detected_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    ip_address VARCHAR(45), -- client that presented the replayed token
+    user_agent TEXT,
+    device_fingerprint VARCHAR(255),
+    token_family VARCHAR(64), -- revoked refresh token family
+    revoked_tokens INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS idx_security_incidents_user_detected
+    ON security_incidents(user_id, detected_at DESC);
+CREATE INDEX IF NOT EXISTS idx_security_incidents_detected ON security_incidents(detected_at DESC);
@@ -0,0 +1,4 @@
+ALTER TABLE links
+    DROP CONSTRAINT IF EXISTS check_sliding_window,
+    DROP COLUMN IF EXISTS sliding_window_days,
+    DROP COLUMN IF EXISTS expiry_policy;
@@ -0,0 +1,11 @@
+-- Sliding expiry per link
+-- Links with expiry_policy 'sliding' expire sliding_window_days after their last click:
+-- the click sync moves expires_at forward whenever it persists new clicks
+
+ALTER TABLE links
+    ADD COLUMN expiry_policy VARCHAR(10) NOT NULL DEFAULT 'fixed',
+    ADD COLUMN sliding_window_days INTEGER,
+    ADD CONSTRAINT check_sliding_window CHECK (
+        (expiry_policy = 'fixed' AND sliding_window_days IS NULL)
+        OR (expiry_policy = 'sliding' AND sliding_window_days BETWEEN 1 AND 365)
+    );
@@ -0,0 +1,5 @@
+-- Case-insensitive short code lookups
+-- New aliases must not match a generated short code in any case, or a click on the code would
+-- also count for the alias; this index keeps that check from scanning every link
+
+CREATE INDEX IF NOT EXISTS idx_links_short_code_lower ON links(lower(short_code));
@@ -5,7 +5,10 @@ use crate::{
     app_config::AppConfig,
     config::RateLimitingConfig,
     db::DieselPool,
-    services::{EmailService, JwtService, PasswordResetService, RateLimitService},
+    services::{
+        health_probe::HealthCache, EmailService, JwtService, PasswordResetService, RateLimitService,
+    },
+    utils::security_scanner::ThreatLookup,
     RedisPool,
 };
 
@@ -20,7 +23,9 @@ pub struct AppState {
     pub rate_limit_config: Arc<RateLimitingConfig>,
     pub password_reset_service: Arc<PasswordResetService>,
     pub email_service: Arc<EmailService>, // For password reset emails
+    pub threat_lookup: Arc<dyn ThreatLookup>, // External checks of full security scans
     pub clickhouse_analytics:
         Option<Arc<crate::services::clickhouse_analytics::ClickHouseAnalyticsService>>,
     pub max_connections: u32,
+    pub health: Arc<HealthCache>, // Last background probe result per dependency
 }
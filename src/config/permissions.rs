@@ -3,11 +3,35 @@
 
 use std::collections::HashMap;
 
+/// Scope granted to instance operators (configured via ADMIN_EMAILS).
+/// Distinct from the per-user "admin" permission every OSS user receives.
+pub const INSTANCE_ADMIN_SCOPE: &str = "instance:admin";
+
 /// Permission configuration for OSS
 /// Since this is self-hosted, all users have full access
 pub struct PermissionConfig;
 
 impl PermissionConfig {
+    /// Extra scopes for a specific user based on instance configuration
+    pub fn get_instance_scopes(email: &str, admin_emails: &[String]) -> Vec<String> {
+        let email = email.trim().to_lowercase();
+        if admin_emails.contains(&email) {
+            vec![INSTANCE_ADMIN_SCOPE.to_string()]
+        } else {
+            vec![]
+        }
+    }
+
+    /// Default permissions plus any instance scopes for this user
+    pub fn get_permissions_for_email(email: &str) -> Vec<String> {
+        let mut permissions = Self::get_default_permissions();
+        permissions.extend(Self::get_instance_scopes(
+            email,
+            &crate::CONFIG.security.admin_emails,
+        ));
+        permissions
+    }
+
     /// Get permissions for all users (OSS has no tiers)
     pub fn get_default_permissions() -> Vec<String> {
         vec![
@@ -34,6 +34,14 @@ impl ClickHouseClient {
         }
     }
 
+    /// Client for `url` and `database` with the default user, without reading app configuration
+    pub fn new(url: &str, database: &str) -> Self {
+        Self {
+            client: Client::default().with_url(url).with_database(database),
+            database: database.to_string(),
+        }
+    }
+
     /// Get the underlying clickhouse::Client
     pub fn client(&self) -> &Client {
         &self.client
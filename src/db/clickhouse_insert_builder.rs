@@ -50,10 +50,12 @@ impl<'a> LinkEventsInsertBuilder<'a> {
          country, country_code, city, region, device_type, \
          device_brand, device_model, browser, browser_version, \
          os, os_version, is_bot, bot_name, http_method, \
-         response_time, status_code, utm_source, utm_medium, utm_campaign";
+         response_time, status_code, utm_source, utm_medium, utm_campaign, is_repeat, sub_path, \
+         referrer_utm_source, referrer_utm_medium, referrer_utm_campaign, short_code, via_alias, \
+         blocked_by_referrer";
 
     /// Number of columns we're inserting
-    const COLUMN_COUNT: usize = 24;
+    const COLUMN_COUNT: usize = 32;
 
     /// Create a new builder with client and table
     fn new(client: &'a Client, table: impl Into<String>) -> Self {
@@ -174,9 +176,201 @@ impl<'a> LinkEventsInsertBuilder<'a> {
             .bind(&event.utm_source)
             .bind(&event.utm_medium)
             .bind(&event.utm_campaign)
+            .bind(event.is_repeat)
+            .bind(&event.sub_path)
+            .bind(&event.referrer_utm_source)
+            .bind(&event.referrer_utm_medium)
+            .bind(&event.referrer_utm_campaign)
+            .bind(&event.short_code)
+            .bind(event.via_alias)
+            .bind(event.blocked_by_referrer)
     }
 }
 
+// =============================================================================
+// SECURITY SCAN EVENTS
+// =============================================================================
+
+/// Insert sampled security scan results into security_scan_events
+pub async fn insert_security_scan_events(
+    client: &Client,
+    table: &str,
+    events: &[crate::services::security_telemetry::SecurityScanEvent],
+) -> Result<(), clickhouse::error::Error> {
+    if events.is_empty() {
+        debug!("No security scan events to insert");
+        return Ok(());
+    }
+
+    // `date` has DEFAULT toDate(timestamp)
+    const INSERT_COLUMNS: &str =
+        "timestamp, url_hash, domain, threat_score, threats, duration_ms, outcome";
+    const COLUMN_COUNT: usize = 7;
+
+    let single_row = format!("({})", ["?"; COLUMN_COUNT].join(", "));
+    let query = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        INSERT_COLUMNS,
+        vec![single_row; events.len()].join(", ")
+    );
+
+    let mut query_builder = client.query(&query);
+    for event in events {
+        query_builder = query_builder
+            .bind(event.timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .bind(&event.url_hash)
+            .bind(&event.domain)
+            .bind(event.threat_score)
+            .bind(&event.threats)
+            .bind(event.duration_ms)
+            .bind(event.outcome.as_str());
+    }
+    query_builder.execute().await?;
+
+    debug!(
+        "Inserted {} security scan events to {}",
+        events.len(),
+        table
+    );
+    Ok(())
+}
+
+// =============================================================================
+// LINK CONVERSIONS
+// =============================================================================
+
+/// Insert conversion pixel hits into link_conversions
+pub async fn insert_link_conversions(
+    client: &Client,
+    table: &str,
+    conversions: &[crate::services::conversion_tracking::ConversionEvent],
+) -> Result<(), clickhouse::error::Error> {
+    if conversions.is_empty() {
+        debug!("No link conversions to insert");
+        return Ok(());
+    }
+
+    // `date` has DEFAULT toDate(timestamp)
+    const INSERT_COLUMNS: &str = "timestamp, link_id, visitor_hash";
+    const COLUMN_COUNT: usize = 3;
+
+    let single_row = format!("({})", ["?"; COLUMN_COUNT].join(", "));
+    let query = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        INSERT_COLUMNS,
+        vec![single_row; conversions.len()].join(", ")
+    );
+
+    let mut query_builder = client.query(&query);
+    for conversion in conversions {
+        query_builder = query_builder
+            .bind(
+                conversion
+                    .timestamp
+                    .format("%Y-%m-%d %H:%M:%S%.3f")
+                    .to_string(),
+            )
+            .bind(conversion.link_id)
+            .bind(&conversion.visitor_hash);
+    }
+    query_builder.execute().await?;
+
+    debug!(
+        "Inserted {} link conversions to {}",
+        conversions.len(),
+        table
+    );
+    Ok(())
+}
+
+/// Insert hourly rate limit offender counts
+pub async fn insert_rate_limit_offenders(
+    client: &Client,
+    table: &str,
+    rows: &[crate::services::analytics::HourlyOffenderRow],
+) -> Result<(), clickhouse::error::Error> {
+    if rows.is_empty() {
+        debug!("No rate limit offenders to insert");
+        return Ok(());
+    }
+
+    // `date` and `exported_at` have defaults
+    const INSERT_COLUMNS: &str = "hour, endpoint, rate_limit_key, key_kind, blocked_requests";
+    const COLUMN_COUNT: usize = 5;
+
+    let single_row = format!("({})", ["?"; COLUMN_COUNT].join(", "));
+    let query = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        INSERT_COLUMNS,
+        vec![single_row; rows.len()].join(", ")
+    );
+
+    let mut query_builder = client.query(&query);
+    for row in rows {
+        query_builder = query_builder
+            .bind(row.hour.format("%Y-%m-%d %H:%M:%S").to_string())
+            .bind(&row.endpoint)
+            .bind(&row.key)
+            .bind(row.kind.as_str())
+            .bind(row.blocked_requests);
+    }
+    query_builder.execute().await?;
+
+    debug!(
+        "Inserted {} rate limit offender rows to {}",
+        rows.len(),
+        table
+    );
+    Ok(())
+}
+
+/// Insert sampled rate limit checks, each standing for `sample_weight` checks
+pub async fn insert_rate_limit_events(
+    client: &Client,
+    table: &str,
+    events: &[crate::services::analytics::RateLimitEvent],
+    sample_weight: u32,
+) -> Result<(), clickhouse::error::Error> {
+    if events.is_empty() {
+        debug!("No rate limit events to insert");
+        return Ok(());
+    }
+
+    // `date` has DEFAULT toDate(timestamp)
+    const INSERT_COLUMNS: &str = "timestamp, endpoint, rate_limit_key, blocked, current_count, \
+                                  request_limit, user_tier, check_latency_ms, sample_weight";
+    const COLUMN_COUNT: usize = 9;
+
+    let single_row = format!("({})", ["?"; COLUMN_COUNT].join(", "));
+    let query = format!(
+        "INSERT INTO {} ({}) VALUES {}",
+        table,
+        INSERT_COLUMNS,
+        vec![single_row; events.len()].join(", ")
+    );
+
+    let mut query_builder = client.query(&query);
+    for event in events {
+        query_builder = query_builder
+            .bind(event.timestamp.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+            .bind(&event.endpoint)
+            .bind(&event.key)
+            .bind(u8::from(event.blocked))
+            .bind(event.current_count)
+            .bind(event.limit)
+            .bind(event.user_tier.as_deref().unwrap_or(""))
+            .bind(u32::try_from(event.check_latency_ms).unwrap_or(u32::MAX))
+            .bind(sample_weight);
+    }
+    query_builder.execute().await?;
+
+    debug!("Inserted {} rate limit events to {}", events.len(), table);
+    Ok(())
+}
+
 // =============================================================================
 // GENERIC BUILDER FOR FUTURE USE
 // =============================================================================
@@ -229,12 +423,15 @@ mod tests {
         let expected_single = format!(
             "({})",
             std::iter::repeat("?")
-                .take(24)
+                .take(LinkEventsInsertBuilder::COLUMN_COUNT)
                 .collect::<Vec<_>>()
                 .join(", ")
         );
 
         // Just verify the format is correct
-        assert_eq!(expected_single.matches('?').count(), 24);
+        assert_eq!(
+            expected_single.matches('?').count(),
+            LinkEventsInsertBuilder::INSERT_COLUMNS.split(',').count()
+        );
     }
 }
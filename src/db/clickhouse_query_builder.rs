@@ -2,27 +2,101 @@
 // Provides a safe, flexible way to build ClickHouse queries without struct deserialization
 // Uses raw queries with primitive types to bypass clickhouse-rs deserialization issues
 
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use uuid::Uuid;
 
+/// Which tables a date-range stats query reads
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsSource {
+    /// Raw `link_events` (only complete within the raw retention window)
+    Raw,
+    /// Daily `link_daily_*` rollups (kept after raw events are pruned)
+    Rollup,
+}
+
+impl StatsSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatsSource::Raw => "raw",
+            StatsSource::Rollup => "rollup",
+        }
+    }
+}
+
+/// Insert buffers in front of `link_events`
+const LINK_EVENT_BUFFERS: [&str; 3] = [
+    "link_events_buffer1",
+    "link_events_buffer2",
+    "link_events_buffer3",
+];
+
+/// Tables holding per-link click data, raw events first
+const LINK_KEYED_TABLES: [&str; 8] = [
+    "link_events",
+    "link_stats",
+    "link_stats_hourly",
+    "link_totals",
+    "link_daily_stats",
+    "link_daily_countries",
+    "link_daily_referrers",
+    "link_conversions",
+];
+
 /// ClickHouse Query Builder for analytics queries
 /// Bypasses clickhouse-rs deserialization by using primitive types
 pub struct ClickHouseQueryBuilder {
     database: String,
+    /// Zone raw events are counted into days in; rollups always hold UTC days
+    timezone: Tz,
 }
 
 impl ClickHouseQueryBuilder {
     pub fn new(database: &str) -> Self {
         Self {
             database: database.to_string(),
+            timezone: Tz::UTC,
+        }
+    }
+
+    /// The same builder with raw-event date ranges and day buckets in `timezone`
+    pub fn in_timezone(&self, timezone: Tz) -> Self {
+        Self {
+            database: self.database.clone(),
+            timezone,
+        }
+    }
+
+    /// Day a raw event falls on in the builder's zone
+    fn event_day(&self) -> String {
+        match self.timezone {
+            Tz::UTC => "date".to_string(),
+            tz => format!("toDate(timestamp, '{}')", tz.name()),
+        }
+    }
+
+    /// Raw events on the days `[from, to]` in the builder's zone. Outside UTC the stored
+    /// (UTC) `date` is widened by a day each way so partitions are still pruned
+    fn event_days_between(&self, from: NaiveDate, to: NaiveDate) -> String {
+        match self.timezone {
+            Tz::UTC => format!("date BETWEEN toDate('{}') AND toDate('{}')", from, to),
+            _ => format!(
+                "date BETWEEN toDate('{from}') - 1 AND toDate('{to}') + 1 AND {day} BETWEEN toDate('{from}') AND toDate('{to}')",
+                from = from,
+                to = to,
+                day = self.event_day()
+            ),
         }
     }
 
     /// Build a query to get link statistics for a single link
+    /// `total_clicks` excludes repeat clicks, `raw_clicks` counts every click
     pub fn build_single_link_stats(&self, link_id: &Uuid) -> String {
         // Query the new AggregatingMergeTree table using -Merge functions
         format!(
             "SELECT 
-                COALESCE(sumMerge(total_clicks), 0) as total_clicks,
+                COALESCE(sumMerge(total_clicks) - sumMerge(repeat_clicks), 0) as total_clicks,
+                COALESCE(sumMerge(total_clicks), 0) as raw_clicks,
                 COALESCE(uniqMerge(unique_visitors), 0) as unique_visitors,
                 COALESCE(sumMerge(total_bots), 0) as bot_clicks
             FROM {}.link_totals 
@@ -39,7 +113,8 @@ impl ClickHouseQueryBuilder {
         format!(
             "SELECT 
                 link_id,
-                sumMerge(total_clicks) as total_clicks,
+                sumMerge(total_clicks) - sumMerge(repeat_clicks) as total_clicks,
+                sumMerge(total_clicks) as raw_clicks,
                 uniqMerge(unique_visitors) as unique_visitors,
                 sumMerge(total_bots) as bot_clicks
             FROM {}.link_totals 
@@ -68,7 +143,7 @@ impl ClickHouseQueryBuilder {
         format!(
             "SELECT 
                 link_id,
-                COUNT(*) as total_clicks,
+                countIf(NOT is_repeat) as total_clicks,
                 COUNT(DISTINCT ip_address) as unique_visitors,
                 MAX(timestamp) as last_click
             FROM {}.link_events 
@@ -85,7 +160,7 @@ impl ClickHouseQueryBuilder {
         format!(
             "SELECT 
                 toDate(timestamp) as date,
-                COUNT(*) as clicks,
+                countIf(NOT is_repeat) as clicks,
                 COUNT(DISTINCT ip_address) as unique_visitors
             FROM {}.link_events 
             WHERE link_id = '{}' 
@@ -102,7 +177,7 @@ impl ClickHouseQueryBuilder {
             "SELECT 
                 country,
                 country_code,
-                COUNT(*) as clicks,
+                countIf(NOT is_repeat) as clicks,
                 COUNT(DISTINCT ip_address) as unique_visitors
             FROM {}.link_events 
             WHERE link_id = '{}' 
@@ -121,7 +196,7 @@ impl ClickHouseQueryBuilder {
                 device_type,
                 browser,
                 os,
-                COUNT(*) as clicks
+                countIf(NOT is_repeat) as clicks
             FROM {}.link_events 
             WHERE link_id = '{}' 
             GROUP BY device_type, browser, os
@@ -136,7 +211,7 @@ impl ClickHouseQueryBuilder {
         format!(
             "SELECT 
                 referrer,
-                COUNT(*) as clicks,
+                countIf(NOT is_repeat) as clicks,
                 COUNT(DISTINCT ip_address) as unique_visitors
             FROM {}.link_events 
             WHERE link_id = '{}' 
@@ -153,7 +228,7 @@ impl ClickHouseQueryBuilder {
         format!(
             "SELECT 
                 toHour(timestamp) as hour,
-                COUNT(*) as clicks,
+                countIf(NOT is_repeat) as clicks,
                 COUNT(DISTINCT ip_address) as unique_visitors
             FROM {}.link_events 
             WHERE link_id = '{}' 
@@ -164,20 +239,526 @@ impl ClickHouseQueryBuilder {
         )
     }
 
+    // =========================================================================
+    // DATE-RANGE STATS (raw events or daily rollups)
+    // =========================================================================
+
+    /// Build a query for (clicks, raw_clicks, unique_visitors, bot_clicks) over a date range
+    pub fn build_range_stats(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+    ) -> String {
+        match source {
+            StatsSource::Raw => format!(
+                "SELECT countIf(NOT is_repeat) AS clicks, count() AS raw_clicks, uniq(ip_address) AS unique_visitors, countIf(is_bot) AS bot_clicks
+                FROM {}.link_events
+                WHERE link_id = '{}' AND {}",
+                self.database,
+                link_id,
+                self.event_days_between(from, to)
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT sum(clicks) - sum(repeat_clicks) AS clicks, sum(clicks) AS raw_clicks, uniqMerge(unique_visitors) AS unique_visitors, sum(bot_clicks) AS bot_clicks
+                FROM {}.link_daily_stats FINAL
+                WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')",
+                self.database, link_id, from, to
+            ),
+        }
+    }
+
+    /// Build a query for range stats plus (previous_clicks, previous_unique_visitors) over
+    /// `[previous_from, from)`, scanning both periods once with conditional aggregation
+    pub fn build_range_stats_with_comparison(
+        &self,
+        link_id: &Uuid,
+        previous_from: NaiveDate,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+    ) -> String {
+        match source {
+            // Both periods split at the start of `from` in the builder's zone
+            StatsSource::Raw => format!(
+                "SELECT countIf(NOT is_repeat AND {cur}) AS clicks, countIf({cur}) AS raw_clicks, uniqIf(ip_address, {cur}) AS unique_visitors, countIf(is_bot AND {cur}) AS bot_clicks,
+                    countIf(NOT is_repeat AND {prev}) AS previous_clicks, uniqIf(ip_address, {prev}) AS previous_unique_visitors
+                FROM {db}.link_events
+                WHERE link_id = '{id}' AND {days}",
+                cur = format!("{} >= toDate('{}')", self.event_day(), from),
+                prev = format!("{} < toDate('{}')", self.event_day(), from),
+                db = self.database,
+                id = link_id,
+                days = self.event_days_between(previous_from, to)
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT sumIf(clicks, {cur}) - sumIf(repeat_clicks, {cur}) AS clicks, sumIf(clicks, {cur}) AS raw_clicks, uniqMergeIf(unique_visitors, {cur}) AS unique_visitors, sumIf(bot_clicks, {cur}) AS bot_clicks,
+                    sumIf(clicks, {prev}) - sumIf(repeat_clicks, {prev}) AS previous_clicks, uniqMergeIf(unique_visitors, {prev}) AS previous_unique_visitors
+                FROM {db}.link_daily_stats FINAL
+                WHERE link_id = '{id}' AND date BETWEEN toDate('{start}') AND toDate('{end}')",
+                cur = format!("date >= toDate('{}')", from),
+                prev = format!("date < toDate('{}')", from),
+                db = self.database,
+                id = link_id,
+                start = previous_from,
+                end = to
+            ),
+        }
+    }
+
+    /// Build a query for per-day (date, clicks, unique_visitors) over a date range
+    pub fn build_range_daily_clicks(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+    ) -> String {
+        match source {
+            StatsSource::Raw => format!(
+                "SELECT toString({day}) AS day, countIf(NOT is_repeat) AS clicks, uniq(ip_address) AS unique_visitors
+                FROM {db}.link_events
+                WHERE link_id = '{id}' AND {days}
+                GROUP BY day
+                ORDER BY day ASC",
+                day = self.event_day(),
+                db = self.database,
+                id = link_id,
+                days = self.event_days_between(from, to)
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT toString(date) AS day, sum(clicks) - sum(repeat_clicks) AS clicks, uniqMerge(unique_visitors) AS unique_visitors
+                FROM {}.link_daily_stats FINAL
+                WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')
+                GROUP BY date
+                ORDER BY date ASC",
+                self.database, link_id, from, to
+            ),
+        }
+    }
+
+    /// Build a query for the top (country_code, clicks) over a date range
+    pub fn build_range_top_countries(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+        limit: u32,
+    ) -> String {
+        match source {
+            StatsSource::Raw => format!(
+                "SELECT toString(country_code) AS country, count() AS clicks
+                FROM {}.link_events
+                WHERE link_id = '{}' AND {} AND country != '' AND NOT is_repeat
+                GROUP BY country
+                ORDER BY clicks DESC
+                LIMIT {}",
+                self.database,
+                link_id,
+                self.event_days_between(from, to),
+                limit
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT country_code AS country, sum(clicks) AS clicks
+                FROM {}.link_daily_countries FINAL
+                WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')
+                GROUP BY country
+                ORDER BY clicks DESC
+                LIMIT {}",
+                self.database, link_id, from, to, limit
+            ),
+        }
+    }
+
+    /// Build a query for the top (referrer_host, clicks) over a date range
+    pub fn build_range_top_referrers(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+        limit: u32,
+    ) -> String {
+        match source {
+            StatsSource::Raw => format!(
+                "SELECT domain(referrer) AS referrer_host, count() AS clicks
+                FROM {}.link_events
+                WHERE link_id = '{}' AND {} AND referrer != '' AND NOT is_repeat
+                GROUP BY referrer_host
+                ORDER BY clicks DESC
+                LIMIT {}",
+                self.database,
+                link_id,
+                self.event_days_between(from, to),
+                limit
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT referrer_host, sum(clicks) AS clicks
+                FROM {}.link_daily_referrers FINAL
+                WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')
+                GROUP BY referrer_host
+                ORDER BY clicks DESC
+                LIMIT {}",
+                self.database, link_id, from, to, limit
+            ),
+        }
+    }
+
+    /// Build a query for the top (campaign, source, medium, clicks) taken from referrer UTM
+    /// parameters over a date range. Only raw events keep them, so there is no rollup variant
+    pub fn build_referrer_campaigns(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        limit: u32,
+    ) -> String {
+        format!(
+            "SELECT toString(referrer_utm_campaign) AS campaign, toString(referrer_utm_source) AS source, toString(referrer_utm_medium) AS medium, count() AS clicks
+            FROM {}.link_events
+            WHERE link_id = '{}' AND {} AND NOT is_repeat
+                AND (referrer_utm_campaign != '' OR referrer_utm_source != '' OR referrer_utm_medium != '')
+            GROUP BY campaign, source, medium
+            ORDER BY clicks DESC
+            LIMIT {}",
+            self.database,
+            link_id,
+            self.event_days_between(from, to),
+            limit
+        )
+    }
+
+    /// Build a query for (via_short_code, via_alias, unknown) clicks over a date range of raw
+    /// events. Events recorded before the entry point was tracked have an empty short_code
+    /// and count as unknown; only raw events keep it, so there is no rollup variant
+    pub fn build_entry_points(&self, link_id: &Uuid, from: NaiveDate, to: NaiveDate) -> String {
+        format!(
+            "SELECT countIf(short_code != '' AND NOT via_alias) AS via_short_code, countIf(short_code != '' AND via_alias) AS via_alias, countIf(short_code = '') AS unknown
+            FROM {}.link_events
+            WHERE link_id = '{}' AND {} AND NOT is_repeat",
+            self.database,
+            link_id,
+            self.event_days_between(from, to)
+        )
+    }
+
+    /// Build a query for (conversions, unique_converters) over a UTC date range
+    pub fn build_conversion_totals(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> String {
+        format!(
+            "SELECT count() AS conversions, uniq(visitor_hash) AS unique_converters
+            FROM {}.link_conversions
+            WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')",
+            self.database, link_id, from, to
+        )
+    }
+
+    /// Build a query for per-day (date, conversions, unique_converters) over a UTC date range
+    pub fn build_daily_conversions(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> String {
+        format!(
+            "SELECT toString(date) AS day, count() AS conversions, uniq(visitor_hash) AS unique_converters
+            FROM {}.link_conversions
+            WHERE link_id = '{}' AND date BETWEEN toDate('{}') AND toDate('{}')
+            GROUP BY date
+            ORDER BY date ASC",
+            self.database, link_id, from, to
+        )
+    }
+
+    // =========================================================================
+    // ACCOUNT USAGE SUMMARY
+    // =========================================================================
+
+    /// Build the single aggregate query behind a monthly usage summary
+    /// Rows are (dimension, key, clicks, unique_visitors) where dimension is one of
+    /// `total` (empty key), `link` (link_id), `referrer` (host) or `country` (ISO code)
+    pub fn build_usage_summary(
+        &self,
+        link_ids: &[Uuid],
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+    ) -> String {
+        let link_id_list: Vec<String> = link_ids.iter().map(|id| format!("'{}'", id)).collect();
+        let days = match source {
+            StatsSource::Raw => self.event_days_between(from, to),
+            StatsSource::Rollup => format!("date BETWEEN toDate('{}') AND toDate('{}')", from, to),
+        };
+        let filter = format!("link_id IN ({}) AND {}", link_id_list.join(", "), days);
+
+        match source {
+            // Each event fans out to one (dimension, key) pair per breakdown, so one scan covers all
+            StatsSource::Raw => format!(
+                "SELECT dim.1 AS dimension, dim.2 AS key, countIf(NOT is_repeat) AS clicks, uniq(ip_address) AS unique_visitors
+                FROM {}.link_events
+                ARRAY JOIN [
+                    ('total', ''),
+                    ('link', toString(link_id)),
+                    ('referrer', domain(referrer)),
+                    ('country', if(country != '', toString(country_code), ''))
+                ] AS dim
+                WHERE {}
+                GROUP BY dimension, key",
+                self.database, filter
+            ),
+            StatsSource::Rollup => format!(
+                "SELECT 'total' AS dimension, '' AS key, sum(clicks) - sum(repeat_clicks) AS clicks, uniqMerge(unique_visitors) AS unique_visitors
+                FROM {db}.link_daily_stats FINAL WHERE {f}
+                UNION ALL
+                SELECT 'link', toString(link_id), sum(clicks) - sum(repeat_clicks), uniqMerge(unique_visitors)
+                FROM {db}.link_daily_stats FINAL WHERE {f} GROUP BY link_id
+                UNION ALL
+                SELECT 'referrer', referrer_host, sum(clicks), toUInt64(0)
+                FROM {db}.link_daily_referrers FINAL WHERE {f} GROUP BY referrer_host
+                UNION ALL
+                SELECT 'country', country_code, sum(clicks), toUInt64(0)
+                FROM {db}.link_daily_countries FINAL WHERE {f} GROUP BY country_code",
+                db = self.database,
+                f = filter
+            ),
+        }
+    }
+
+    // =========================================================================
+    // ROLLUP AND RETENTION MAINTENANCE
+    // =========================================================================
+
+    /// Build the statements that (re-)roll raw events for every day in `[from, to]`
+    /// Re-rolling a day replaces its previous rollup rows (ReplacingMergeTree)
+    pub fn build_daily_rollups(&self, from: NaiveDate, to: NaiveDate) -> Vec<String> {
+        vec![
+            format!(
+                "INSERT INTO {db}.link_daily_stats
+                    (link_id, date, clicks, bot_clicks, unique_visitors, rolled_up_at, repeat_clicks)
+                SELECT link_id, date, count() AS clicks, countIf(is_bot) AS bot_clicks,
+                    uniqState(ip_address) AS unique_visitors, now() AS rolled_up_at,
+                    countIf(is_repeat) AS repeat_clicks
+                FROM {db}.link_events
+                WHERE date BETWEEN toDate('{from}') AND toDate('{to}')
+                GROUP BY link_id, date",
+                db = self.database,
+                from = from,
+                to = to
+            ),
+            format!(
+                "INSERT INTO {db}.link_daily_countries
+                SELECT link_id, date, toString(country_code) AS country_code, count() AS clicks,
+                    now() AS rolled_up_at
+                FROM {db}.link_events
+                WHERE date BETWEEN toDate('{from}') AND toDate('{to}') AND country != '' AND NOT is_repeat
+                GROUP BY link_id, date, country_code",
+                db = self.database,
+                from = from,
+                to = to
+            ),
+            format!(
+                "INSERT INTO {db}.link_daily_referrers
+                SELECT link_id, date, domain(referrer) AS referrer_host, count() AS clicks,
+                    now() AS rolled_up_at
+                FROM {db}.link_events
+                WHERE date BETWEEN toDate('{from}') AND toDate('{to}') AND referrer != '' AND NOT is_repeat
+                GROUP BY link_id, date, referrer_host",
+                db = self.database,
+                from = from,
+                to = to
+            ),
+        ]
+    }
+
+    /// Build a query for the most recent rolled-up day (`1970-01-01` when nothing is rolled up)
+    pub fn build_latest_rollup_date(&self) -> String {
+        format!(
+            "SELECT toString(max(date)) FROM {}.link_daily_stats",
+            self.database
+        )
+    }
+
+    /// Build a query for the oldest raw event day (`1970-01-01` when there are no events)
+    pub fn build_earliest_event_date(&self) -> String {
+        format!(
+            "SELECT toString(min(date)) FROM {}.link_events",
+            self.database
+        )
+    }
+
+    /// Build a query for `link_events` partitions whose newest day is before `before`
+    pub fn build_prunable_partitions(&self, before: NaiveDate) -> String {
+        format!(
+            "SELECT partition_id
+            FROM system.parts
+            WHERE database = '{}' AND table = 'link_events' AND active
+            GROUP BY partition_id
+            HAVING max(max_date) < toDate('{}')
+            ORDER BY partition_id",
+            self.database, before
+        )
+    }
+
+    /// Build a statement dropping one `link_events` partition
+    pub fn build_drop_partition(&self, partition_id: &str) -> String {
+        format!(
+            "ALTER TABLE {}.link_events DROP PARTITION ID '{}'",
+            self.database,
+            partition_id.replace('\'', "")
+        )
+    }
+
+    /// Build the statements that erase every stored click for one link: flush the insert
+    /// buffers, then delete its rows from raw events and from each aggregate
+    /// (materialized views don't propagate deletes, so each table is cleared directly)
+    pub fn build_delete_link_events(&self, link_id: &Uuid) -> Vec<String> {
+        let flushes = LINK_EVENT_BUFFERS
+            .iter()
+            .map(|buffer| format!("OPTIMIZE TABLE {}.{}", self.database, buffer));
+        let deletes = LINK_KEYED_TABLES.iter().map(|table| {
+            format!(
+                "ALTER TABLE {}.{} DELETE WHERE link_id = '{}'",
+                self.database, table, link_id
+            )
+        });
+        flushes.chain(deletes).collect()
+    }
+
+    // =========================================================================
+    // SECURITY SCAN METRICS
+    // =========================================================================
+
+    /// Build a query for (total_scans, blocked_scans, trusted_scans, p95_duration_ms)
+    /// p95 is 0 when the range has no scans
+    pub fn build_security_scan_summary(&self, from: NaiveDate, to: NaiveDate) -> String {
+        format!(
+            "SELECT count() AS total_scans,
+                countIf(outcome = 'blocked') AS blocked_scans,
+                countIf(outcome = 'trusted') AS trusted_scans,
+                if(count() = 0, 0, toUInt64(round(quantile(0.95)(duration_ms)))) AS p95_duration_ms
+            FROM {}.security_scan_events
+            WHERE date BETWEEN toDate('{}') AND toDate('{}')",
+            self.database, from, to
+        )
+    }
+
+    /// Build a query for (threat_type, scans) over a date range, most frequent first
+    pub fn build_security_scan_threats(&self, from: NaiveDate, to: NaiveDate) -> String {
+        format!(
+            "SELECT toString(threat) AS threat_type, count() AS scans
+            FROM {}.security_scan_events
+            ARRAY JOIN threats AS threat
+            WHERE date BETWEEN toDate('{}') AND toDate('{}')
+            GROUP BY threat_type
+            ORDER BY scans DESC, threat_type",
+            self.database, from, to
+        )
+    }
+
+    /// Build a query for the most-blocked (domain, blocked_scans) over a date range
+    pub fn build_top_blocked_domains(&self, from: NaiveDate, to: NaiveDate, limit: u32) -> String {
+        format!(
+            "SELECT domain, count() AS blocked_scans
+            FROM {}.security_scan_events
+            WHERE date BETWEEN toDate('{}') AND toDate('{}') AND outcome = 'blocked'
+            GROUP BY domain
+            ORDER BY blocked_scans DESC, domain
+            LIMIT {}",
+            self.database, from, to, limit
+        )
+    }
+
+    /// Build a query for sampled rate limit checks in [from, to):
+    /// (total_checks, total_blocked, avg_latency_ms, p95_latency_ms, p99_latency_ms)
+    /// Counts are scaled up by each row's sample weight; latencies are over the samples
+    pub fn build_rate_limit_summary(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+        format!(
+            "SELECT sum(sample_weight) AS total_checks,
+                sumIf(sample_weight, blocked = 1) AS total_blocked,
+                if(count() = 0, 0, avg(check_latency_ms)) AS avg_latency_ms,
+                if(count() = 0, 0, toUInt64(quantile(0.95)(check_latency_ms))) AS p95_latency_ms,
+                if(count() = 0, 0, toUInt64(quantile(0.99)(check_latency_ms))) AS p99_latency_ms
+            FROM {}.rate_limit_events
+            WHERE {}",
+            self.database,
+            rate_limit_window(from, to)
+        )
+    }
+
+    /// Build a query for (endpoint, total_requests, blocked_requests, avg_latency_ms) in [from, to)
+    pub fn build_rate_limit_endpoints(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+        format!(
+            "SELECT endpoint, sum(sample_weight) AS total_requests,
+                sumIf(sample_weight, blocked = 1) AS blocked_requests,
+                avg(check_latency_ms) AS avg_latency_ms
+            FROM {}.rate_limit_events
+            WHERE {}
+            GROUP BY endpoint
+            ORDER BY endpoint",
+            self.database,
+            rate_limit_window(from, to)
+        )
+    }
+
+    /// Build a query for (tier, total_requests, blocked_requests, unique_keys, avg_latency_ms)
+    /// in [from, to), skipping checks without a known tier
+    pub fn build_rate_limit_tiers(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+        format!(
+            "SELECT user_tier, sum(sample_weight) AS total_requests,
+                sumIf(sample_weight, blocked = 1) AS blocked_requests,
+                uniq(rate_limit_key) AS unique_keys,
+                avg(check_latency_ms) AS avg_latency_ms
+            FROM {}.rate_limit_events
+            WHERE {} AND user_tier != ''
+            GROUP BY user_tier
+            ORDER BY user_tier",
+            self.database,
+            rate_limit_window(from, to)
+        )
+    }
+
     /// Build a query to check total events count (for health checks)
     pub fn build_health_check_query(&self) -> String {
         format!("SELECT COUNT(*) FROM {}.link_events", self.database)
     }
+
+    /// Build a query for every redirect ever recorded, repeats included
+    /// Reads link_totals, which keeps its counts after raw events are pruned
+    pub fn build_total_redirects_query(&self) -> String {
+        format!(
+            "SELECT sumMerge(total_clicks) FROM {}.link_totals",
+            self.database
+        )
+    }
+}
+
+/// Filter on `rate_limit_events` for [from, to); the date bound lets ClickHouse skip partitions
+fn rate_limit_window(from: DateTime<Utc>, to: DateTime<Utc>) -> String {
+    format!(
+        "date BETWEEN toDate('{}') AND toDate('{}')
+                AND timestamp >= toDateTime64('{}', 3, 'UTC')
+                AND timestamp < toDateTime64('{}', 3, 'UTC')",
+        from.date_naive(),
+        to.date_naive(),
+        from.format("%Y-%m-%d %H:%M:%S%.3f"),
+        to.format("%Y-%m-%d %H:%M:%S%.3f")
+    )
 }
 
 /// Response structures for different query types
 /// These represent the expected tuple structures for raw queries
 
-/// Single link stats: (total_clicks, unique_visitors, bot_clicks)
-pub type SingleLinkStats = (u64, u64, u64);
+/// Single link stats: (total_clicks, raw_clicks, unique_visitors, bot_clicks)
+pub type SingleLinkStats = (u64, u64, u64, u64);
 
-/// Bulk link stats: (link_id, total_clicks, unique_visitors, bot_clicks)  
-pub type BulkLinkStatsRow = (String, u64, u64, u64); // link_id as String for parsing
+/// Bulk link stats: (link_id, total_clicks, raw_clicks, unique_visitors, bot_clicks)
+pub type BulkLinkStatsRow = (String, u64, u64, u64, u64); // link_id as String for parsing
 
 /// Time series row: (date, clicks, unique_visitors)
 pub type TimeSeriesRow = (String, u64, u64); // date as String
@@ -194,6 +775,40 @@ pub type ReferrerRow = (String, u64, u64);
 /// Hourly row: (hour, clicks, unique_visitors)
 pub type HourlyRow = (u8, u64, u64);
 
+/// Range stats: (clicks, raw_clicks, unique_visitors, bot_clicks)
+pub type RangeStatsRow = (u64, u64, u64, u64);
+
+/// Range stats with the previous period:
+/// (clicks, raw_clicks, unique_visitors, bot_clicks, previous_clicks, previous_unique_visitors)
+pub type RangeComparisonRow = (u64, u64, u64, u64, u64, u64);
+
+/// Range breakdown row: (country_code or referrer_host, clicks)
+pub type BreakdownRow = (String, u64);
+
+/// Referrer campaign row: (campaign, source, medium, clicks)
+pub type CampaignRow = (String, String, String, u64);
+
+/// Entry point row: (via_short_code, via_alias, unknown)
+pub type EntryPointRow = (u64, u64, u64);
+
+/// Conversion totals: (conversions, unique_converters)
+pub type ConversionTotalsRow = (u64, u64);
+
+/// Usage summary row: (dimension, key, clicks, unique_visitors)
+pub type UsageSummaryRow = (String, String, u64, u64);
+
+/// Security scan summary: (total_scans, blocked_scans, trusted_scans, p95_duration_ms)
+pub type SecurityScanSummaryRow = (u64, u64, u64, u64);
+
+/// Rate limit summary: (total_checks, total_blocked, avg_latency_ms, p95_latency_ms, p99_latency_ms)
+pub type RateLimitSummaryRow = (u64, u64, f64, u64, u64);
+
+/// Rate limit endpoint row: (endpoint, total_requests, blocked_requests, avg_latency_ms)
+pub type RateLimitEndpointRow = (String, u64, u64, f64);
+
+/// Rate limit tier row: (tier, total_requests, blocked_requests, unique_keys, avg_latency_ms)
+pub type RateLimitTierRow = (String, u64, u64, u64, f64);
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -222,6 +837,302 @@ mod tests {
         assert!(query.contains(&link_ids[1].to_string()));
     }
 
+    #[test]
+    fn test_range_queries_switch_tables_by_source() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 3, 31).unwrap();
+
+        let raw = builder.build_range_stats(&link_id, from, to, StatsSource::Raw);
+        assert!(raw.contains("FROM analytics.link_events"));
+        assert!(raw.contains("BETWEEN toDate('2024-01-01') AND toDate('2024-03-31')"));
+
+        let rollup = builder.build_range_stats(&link_id, from, to, StatsSource::Rollup);
+        assert!(rollup.contains("FROM analytics.link_daily_stats FINAL"));
+        assert!(rollup.contains("uniqMerge(unique_visitors)"));
+
+        let referrers =
+            builder.build_range_top_referrers(&link_id, from, to, StatsSource::Rollup, 10);
+        assert!(referrers.contains("FROM analytics.link_daily_referrers FINAL"));
+        assert!(referrers.contains("LIMIT 10"));
+    }
+
+    #[test]
+    fn test_range_comparison_scans_both_periods_once() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let previous_from = NaiveDate::from_ymd_opt(2024, 5, 25).unwrap();
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 7).unwrap();
+
+        let raw = builder.build_range_stats_with_comparison(
+            &link_id,
+            previous_from,
+            from,
+            to,
+            StatsSource::Raw,
+        );
+        assert!(raw.contains("FROM analytics.link_events"));
+        assert!(raw.contains("BETWEEN toDate('2024-05-25') AND toDate('2024-06-07')"));
+        assert!(raw.contains("uniqIf(ip_address, date < toDate('2024-06-01'))"));
+        assert!(!raw.contains("UNION"));
+
+        let rollup = builder.build_range_stats_with_comparison(
+            &link_id,
+            previous_from,
+            from,
+            to,
+            StatsSource::Rollup,
+        );
+        assert!(rollup.contains("FROM analytics.link_daily_stats FINAL"));
+        assert!(rollup.contains("uniqMergeIf(unique_visitors, date >= toDate('2024-06-01'))"));
+    }
+
+    #[test]
+    fn test_raw_days_follow_timezone() {
+        let builder = ClickHouseQueryBuilder::new("analytics").in_timezone(Tz::Asia__Tokyo);
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 7).unwrap();
+
+        let daily = builder.build_range_daily_clicks(&link_id, from, to, StatsSource::Raw);
+        assert!(daily.contains("toString(toDate(timestamp, 'Asia/Tokyo')) AS day"));
+        // The UTC date column still prunes partitions, a day wider each way
+        assert!(
+            daily.contains("date BETWEEN toDate('2024-06-01') - 1 AND toDate('2024-06-07') + 1")
+        );
+        assert!(daily.contains(
+            "toDate(timestamp, 'Asia/Tokyo') BETWEEN toDate('2024-06-01') AND toDate('2024-06-07')"
+        ));
+
+        let previous_from = NaiveDate::from_ymd_opt(2024, 5, 25).unwrap();
+        let compared = builder.build_range_stats_with_comparison(
+            &link_id,
+            previous_from,
+            from,
+            to,
+            StatsSource::Raw,
+        );
+        assert!(compared.contains(
+            "uniqIf(ip_address, toDate(timestamp, 'Asia/Tokyo') < toDate('2024-06-01'))"
+        ));
+
+        // Rollups only hold UTC days
+        let rollup = builder.build_range_daily_clicks(&link_id, from, to, StatsSource::Rollup);
+        assert!(!rollup.contains("Asia/Tokyo"));
+        let usage = builder.build_usage_summary(&[link_id], from, to, StatsSource::Rollup);
+        assert!(!usage.contains("Asia/Tokyo"));
+    }
+
+    #[test]
+    fn test_referrer_campaigns_query() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+        let query = builder.build_referrer_campaigns(&link_id, from, to, 25);
+        assert!(query.contains("FROM analytics.link_events"));
+        assert!(query.contains("BETWEEN toDate('2024-06-01') AND toDate('2024-06-30')"));
+        assert!(query.contains("AND NOT is_repeat"));
+        assert!(query.contains("GROUP BY campaign, source, medium"));
+        assert!(query.contains("LIMIT 25"));
+    }
+
+    #[test]
+    fn test_conversion_queries() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+        for query in [
+            builder.build_conversion_totals(&link_id, from, to),
+            builder.build_daily_conversions(&link_id, from, to),
+        ] {
+            assert!(query.contains("FROM analytics.link_conversions"));
+            assert!(query.contains(&format!("link_id = '{}'", link_id)));
+            assert!(query.contains("BETWEEN toDate('2024-06-01') AND toDate('2024-06-30')"));
+            assert!(query.contains("uniq(visitor_hash)"));
+        }
+        assert!(builder
+            .build_daily_conversions(&link_id, from, to)
+            .contains("GROUP BY date"));
+    }
+
+    #[test]
+    fn test_entry_points_query_tolerates_old_events() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+        let query = builder.build_entry_points(&link_id, from, to);
+        assert!(query.contains("FROM analytics.link_events"));
+        assert!(query.contains("BETWEEN toDate('2024-06-01') AND toDate('2024-06-30')"));
+        assert!(query.contains("AND NOT is_repeat"));
+        // Rows without a short code are neither code nor alias clicks
+        assert!(query.contains("countIf(short_code != '' AND NOT via_alias) AS via_short_code"));
+        assert!(query.contains("countIf(short_code = '') AS unknown"));
+    }
+
+    #[test]
+    fn test_usage_summary_query_is_single_aggregate() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_ids = vec![Uuid::new_v4(), Uuid::new_v4()];
+        let from = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 30).unwrap();
+
+        let raw = builder.build_usage_summary(&link_ids, from, to, StatsSource::Raw);
+        assert!(raw.contains("FROM analytics.link_events"));
+        assert!(raw.contains("ARRAY JOIN"));
+        assert!(!raw.contains("UNION ALL"));
+        assert!(raw.contains(&format!("'{}', '{}'", link_ids[0], link_ids[1])));
+        assert!(raw.contains("BETWEEN toDate('2024-06-01') AND toDate('2024-06-30')"));
+
+        let rollup = builder.build_usage_summary(&link_ids, from, to, StatsSource::Rollup);
+        assert!(rollup.contains("analytics.link_daily_referrers FINAL"));
+        assert!(rollup.contains("analytics.link_daily_countries FINAL"));
+        assert!(!rollup.contains("link_events"));
+    }
+
+    #[test]
+    fn test_daily_rollups_cover_every_table() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let day = NaiveDate::from_ymd_opt(2024, 1, 2).unwrap();
+        let statements = builder.build_daily_rollups(day, day);
+
+        assert_eq!(statements.len(), 3);
+        for (statement, table) in statements.iter().zip([
+            "link_daily_stats",
+            "link_daily_countries",
+            "link_daily_referrers",
+        ]) {
+            assert!(statement.starts_with(&format!("INSERT INTO analytics.{}", table)));
+            assert!(statement.contains("BETWEEN toDate('2024-01-02') AND toDate('2024-01-02')"));
+        }
+    }
+
+    #[test]
+    fn test_default_click_counts_exclude_repeats() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let from = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 1, 31).unwrap();
+
+        let raw = builder.build_range_stats(&link_id, from, to, StatsSource::Raw);
+        assert!(raw.contains("countIf(NOT is_repeat) AS clicks, count() AS raw_clicks"));
+        let rollup = builder.build_range_stats(&link_id, from, to, StatsSource::Rollup);
+        assert!(rollup.contains("sum(clicks) - sum(repeat_clicks) AS clicks"));
+        assert!(rollup.contains("sum(clicks) AS raw_clicks"));
+
+        let totals = builder.build_single_link_stats(&link_id);
+        assert!(totals.contains("sumMerge(total_clicks) - sumMerge(repeat_clicks)"));
+        assert!(totals.contains("as raw_clicks"));
+
+        let countries = builder.build_range_top_countries(&link_id, from, to, StatsSource::Raw, 10);
+        assert!(countries.contains("AND NOT is_repeat"));
+        let referrers = builder.build_range_top_referrers(&link_id, from, to, StatsSource::Raw, 10);
+        assert!(referrers.contains("AND NOT is_repeat"));
+
+        // Rollups keep raw clicks next to repeats; breakdowns only roll up deduplicated clicks
+        let statements = builder.build_daily_rollups(from, to);
+        assert!(statements[0].contains("countIf(is_repeat) AS repeat_clicks"));
+        assert!(statements[1].contains("AND NOT is_repeat"));
+        assert!(statements[2].contains("AND NOT is_repeat"));
+    }
+
+    #[test]
+    fn test_partition_pruning_queries() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let before = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+
+        let query = builder.build_prunable_partitions(before);
+        assert!(query.contains("table = 'link_events'"));
+        assert!(query.contains("HAVING max(max_date) < toDate('2024-06-01')"));
+
+        assert_eq!(
+            builder.build_drop_partition("202401"),
+            "ALTER TABLE analytics.link_events DROP PARTITION ID '202401'"
+        );
+    }
+
+    #[test]
+    fn test_delete_link_events_flushes_buffers_first() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let link_id = Uuid::new_v4();
+        let statements = builder.build_delete_link_events(&link_id);
+
+        assert_eq!(statements.len(), 11);
+        assert!(statements[..3]
+            .iter()
+            .all(|s| s.starts_with("OPTIMIZE TABLE analytics.link_events_buffer")));
+        assert_eq!(
+            statements[3],
+            format!(
+                "ALTER TABLE analytics.link_events DELETE WHERE link_id = '{}'",
+                link_id
+            )
+        );
+        assert!(statements
+            .iter()
+            .any(|s| s.starts_with("ALTER TABLE analytics.link_totals DELETE")));
+        assert!(statements
+            .iter()
+            .any(|s| s.starts_with("ALTER TABLE analytics.link_conversions DELETE")));
+    }
+
+    #[test]
+    fn test_security_scan_queries_filter_range() {
+        let builder = ClickHouseQueryBuilder::new("test_db");
+        let from = NaiveDate::from_ymd_opt(2024, 6, 9).unwrap();
+        let to = NaiveDate::from_ymd_opt(2024, 6, 15).unwrap();
+
+        for query in [
+            builder.build_security_scan_summary(from, to),
+            builder.build_security_scan_threats(from, to),
+            builder.build_top_blocked_domains(from, to, 10),
+        ] {
+            assert!(query.contains("test_db.security_scan_events"));
+            assert!(query.contains("BETWEEN toDate('2024-06-09') AND toDate('2024-06-15')"));
+        }
+        assert!(builder
+            .build_top_blocked_domains(from, to, 10)
+            .contains("outcome = 'blocked'"));
+        assert!(builder
+            .build_security_scan_threats(from, to)
+            .contains("ARRAY JOIN threats"));
+    }
+
+    #[test]
+    fn test_rate_limit_queries_filter_window() {
+        let builder = ClickHouseQueryBuilder::new("test_db");
+        let from = DateTime::parse_from_rfc3339("2024-06-14T22:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let to = DateTime::parse_from_rfc3339("2024-06-15T10:30:00.250Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        for query in [
+            builder.build_rate_limit_summary(from, to),
+            builder.build_rate_limit_endpoints(from, to),
+            builder.build_rate_limit_tiers(from, to),
+        ] {
+            assert!(query.contains("test_db.rate_limit_events"));
+            assert!(query.contains("BETWEEN toDate('2024-06-14') AND toDate('2024-06-15')"));
+            assert!(
+                query.contains("timestamp >= toDateTime64('2024-06-14 22:30:00.000', 3, 'UTC')")
+            );
+            assert!(query.contains("timestamp < toDateTime64('2024-06-15 10:30:00.250', 3, 'UTC')"));
+            assert!(query.contains("sum(sample_weight)"));
+        }
+        assert!(builder
+            .build_rate_limit_tiers(from, to)
+            .contains("user_tier != ''"));
+    }
+
     #[test]
     fn test_health_check_query() {
         let builder = ClickHouseQueryBuilder::new("analytics");
@@ -229,4 +1140,15 @@ mod tests {
 
         assert_eq!(query, "SELECT COUNT(*) FROM analytics.link_events");
     }
+
+    #[test]
+    fn test_total_redirects_query() {
+        let builder = ClickHouseQueryBuilder::new("analytics");
+        let query = builder.build_total_redirects_query();
+
+        assert_eq!(
+            query,
+            "SELECT sumMerge(total_clicks) FROM analytics.link_totals"
+        );
+    }
 }
@@ -8,7 +8,12 @@ pub mod redis_pool;
 
 pub use clickhouse_client::{create_clickhouse_client, ClickHouseClient};
 pub use clickhouse_insert_builder::{insert_link_events, ClickHouseInsertBuilder};
-pub use clickhouse_query_builder::{BulkLinkStatsRow, ClickHouseQueryBuilder, SingleLinkStats};
+pub use clickhouse_query_builder::{
+    BreakdownRow, BulkLinkStatsRow, CampaignRow, ClickHouseQueryBuilder, ConversionTotalsRow,
+    EntryPointRow, RangeComparisonRow, RangeStatsRow, RateLimitEndpointRow, RateLimitSummaryRow,
+    RateLimitTierRow, SecurityScanSummaryRow, SingleLinkStats, StatsSource, TimeSeriesRow,
+    UsageSummaryRow,
+};
 pub use config::DatabaseConfig;
 pub use diesel_pool::{
     check_diesel_health, create_diesel_pool, mask_connection_string, DieselDatabaseConfig,
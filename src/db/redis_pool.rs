@@ -437,6 +437,26 @@ impl RedisPool {
             .await
     }
 
+    /// Set a value with expiry only if the key does not exist (SET NX EX).
+    /// Returns true when the key was set.
+    pub async fn set_nx_with_expiry(
+        &self,
+        key: &str,
+        value: String,
+        expiry_seconds: usize,
+    ) -> Result<bool, RedisError> {
+        let mut conn = self.get_connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("NX")
+            .arg("EX")
+            .arg(expiry_seconds)
+            .query_async(&mut conn)
+            .await?;
+        Ok(result.is_some())
+    }
+
     /// Increment a counter with expiry (atomic operation using Lua script)
     /// This ensures that INCR and EXPIRE are performed atomically.
     pub async fn incr(&self, key: &str, expiry_seconds: usize) -> Result<i64, RedisError> {
@@ -463,6 +483,12 @@ impl RedisPool {
         Ok(count)
     }
 
+    /// Get a value and delete its key in one step (GETDEL), so only one caller sees it
+    pub async fn get_del(&self, key: &str) -> Result<Option<String>, RedisError> {
+        let mut conn = self.get_connection().await?;
+        redis::cmd("GETDEL").arg(key).query_async(&mut conn).await
+    }
+
     /// Delete a key from Redis
     pub async fn del(&self, key: &str) -> Result<(), RedisError> {
         let mut conn = self.get_connection().await?;
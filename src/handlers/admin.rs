@@ -0,0 +1,1134 @@
+// Operator-only administration endpoints
+// All routes here sit behind auth_middleware + require_admin
+
+use axum::{
+    extract::{Extension, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    config::permissions::{PermissionConfig, INSTANCE_ADMIN_SCOPE},
+    middleware::{
+        auth::AuthenticatedUser,
+        latency::{latency_metrics, LatencyMetrics},
+    },
+    handlers::audit_logs::query_audit_logs,
+    models::{
+        account_export::AccountArchive,
+        analytics::{
+            EndpointOffenders, OffendersFormat, RateLimitOffenderEntry, RateLimitOffendersQuery,
+            RateLimitOffendersResponse, SecurityMetricsQuery,
+        },
+        audit_log::AuditLogQuery,
+        blocked_domain::{BlockedDomain, BlockedDomainResponse, CreateBlockedDomainRequest},
+        denied_ip::{CreateDeniedIpRequest, DeniedIpResponse},
+        link::{QuarantinedLinkResponse, ResolveAppealRequest},
+        security_incident::{SecurityEventsQuery, SecurityIncident, SECURITY_EVENTS_LIMIT},
+        trusted_domain::{CreateTrustedDomainRequest, TrustedDomainResponse},
+        user::{User, UserError},
+    },
+    services::{
+        account_export::AccountExportService,
+        analytics::offender_window_start,
+        blocklist::BlocklistService,
+        instance_stats::InstanceStatsService,
+        ip_denylist::IpDenylistService,
+        jwt::IMPERSONATION_TOKEN_EXPIRY_SECONDS,
+        quarantine::QuarantineService,
+        self_test::{run_self_test, SelfTestReport},
+    },
+    utils::{
+        api_error::ApiError,
+        audit_logger::{AuditAction, AuditLogger},
+        service_error::ServiceError,
+    },
+};
+
+// =============================================================================
+// BLOCKLIST HANDLERS
+// =============================================================================
+
+/// List operator-managed blocklist entries
+/// GET /v1/admin/blocked-domains
+#[utoipa::path(
+    get,
+    path = "/v1/admin/blocked-domains",
+    tag = "Admin",
+    operation_id = "listBlockedDomains",
+    responses(
+        (status = 200, description = "Blocklist entries", body = [BlockedDomainResponse]),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_blocked_domains(State(state): State<AppState>) -> impl IntoResponse {
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.list().await {
+        Ok(entries) => Json(
+            entries
+                .into_iter()
+                .map(BlockedDomainResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Block a domain or TLD
+/// POST /v1/admin/blocked-domains
+#[utoipa::path(
+    post,
+    path = "/v1/admin/blocked-domains",
+    tag = "Admin",
+    operation_id = "createBlockedDomain",
+    request_body = CreateBlockedDomainRequest,
+    responses(
+        (status = 201, description = "Entry created; matching active links are quarantined in the background", body = BlockedDomainResponse),
+        (status = 400, description = "Invalid pattern or reason", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 409, description = "Pattern is already blocked", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_blocked_domain(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateBlockedDomainRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = request.validate() {
+        return ApiError::from(e)
+            .with_status(StatusCode::BAD_REQUEST)
+            .into_response();
+    }
+
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.add(&request, admin_id).await {
+        Ok(entry) => {
+            info!(
+                "Admin {} blocked {} {}",
+                admin_id, entry.entry_type, entry.pattern
+            );
+            spawn_affected_links_scan(state, entry.clone());
+            (
+                StatusCode::CREATED,
+                Json(BlockedDomainResponse::from(entry)),
+            )
+                .into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Remove a blocklist entry
+/// DELETE /v1/admin/blocked-domains/{id}
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/blocked-domains/{id}",
+    tag = "Admin",
+    operation_id = "deleteBlockedDomain",
+    params(
+        ("id" = Uuid, Path, description = "Blocklist entry ID")
+    ),
+    responses(
+        (status = 204, description = "Entry removed"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 404, description = "Entry not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_blocked_domain(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(entry_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.remove(entry_id, admin_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Quarantine existing active links that point at a newly blocked entry
+fn spawn_affected_links_scan(state: AppState, entry: BlockedDomain) {
+    tokio::spawn(async move {
+        let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+        let affected = match service.find_active_links_for_entry(&entry).await {
+            Ok(links) => links,
+            Err(e) => {
+                error!("Failed to find links affected by {}: {}", entry.pattern, e);
+                return;
+            },
+        };
+
+        if affected.is_empty() {
+            return;
+        }
+
+        warn!(
+            "{} active links point at newly blocked {} {}",
+            affected.len(),
+            entry.entry_type,
+            entry.pattern
+        );
+
+        let quarantine_service = QuarantineService::new(&state);
+        let reason = format!("Destination blocked by operator: {}", entry.reason);
+        for link in &affected {
+            if let Err(e) = quarantine_service
+                .quarantine(link, 100, reason.clone())
+                .await
+            {
+                error!("Failed to quarantine link {}: {}", link.id, e);
+            }
+        }
+    });
+}
+
+// =============================================================================
+// TRUSTED DOMAIN HANDLERS
+// =============================================================================
+
+/// List operator-managed trusted domains
+/// GET /v1/admin/trusted-domains
+#[utoipa::path(
+    get,
+    path = "/v1/admin/trusted-domains",
+    tag = "Admin",
+    operation_id = "listTrustedDomains",
+    responses(
+        (status = 200, description = "Trusted domains", body = [TrustedDomainResponse]),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_trusted_domains(State(state): State<AppState>) -> impl IntoResponse {
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.list_trusted().await {
+        Ok(entries) => Json(
+            entries
+                .into_iter()
+                .map(TrustedDomainResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Trust a domain so its URLs are accepted whatever they score
+/// POST /v1/admin/trusted-domains
+#[utoipa::path(
+    post,
+    path = "/v1/admin/trusted-domains",
+    tag = "Admin",
+    operation_id = "createTrustedDomain",
+    request_body = CreateTrustedDomainRequest,
+    responses(
+        (status = 201, description = "Domain trusted; blocklist entries still take precedence", body = TrustedDomainResponse),
+        (status = 400, description = "Invalid pattern or reason", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 409, description = "Domain is already trusted", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_trusted_domain(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateTrustedDomainRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = request.validate() {
+        return ApiError::from(e)
+            .with_status(StatusCode::BAD_REQUEST)
+            .into_response();
+    }
+
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.add_trusted(&request, admin_id).await {
+        Ok(entry) => {
+            info!("Admin {} trusted {}", admin_id, entry.pattern);
+            (
+                StatusCode::CREATED,
+                Json(TrustedDomainResponse::from(entry)),
+            )
+                .into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Remove a trusted domain
+/// DELETE /v1/admin/trusted-domains/{id}
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/trusted-domains/{id}",
+    tag = "Admin",
+    operation_id = "deleteTrustedDomain",
+    params(
+        ("id" = Uuid, Path, description = "Trusted domain ID")
+    ),
+    responses(
+        (status = 204, description = "Entry removed"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 404, description = "Entry not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_trusted_domain(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(entry_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.remove_trusted(entry_id, admin_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// =============================================================================
+// QUARANTINE REVIEW HANDLERS
+// =============================================================================
+
+/// List quarantined links with a pending owner appeal
+/// GET /v1/admin/link-appeals
+#[utoipa::path(
+    get,
+    path = "/v1/admin/link-appeals",
+    tag = "Admin",
+    operation_id = "listLinkAppeals",
+    responses(
+        (status = 200, description = "Quarantined links awaiting review", body = [QuarantinedLinkResponse]),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_link_appeals(State(state): State<AppState>) -> impl IntoResponse {
+    let service = QuarantineService::new(&state);
+
+    match service.list_pending_appeals().await {
+        Ok(links) => Json(
+            links
+                .into_iter()
+                .map(QuarantinedLinkResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Approve (lift quarantine) or reject a pending appeal
+/// POST /v1/admin/link-appeals/{id}
+#[utoipa::path(
+    post,
+    path = "/v1/admin/link-appeals/{id}",
+    tag = "Admin",
+    operation_id = "resolveLinkAppeal",
+    params(
+        ("id" = Uuid, Path, description = "Link ID")
+    ),
+    request_body = ResolveAppealRequest,
+    responses(
+        (status = 200, description = "Appeal resolved", body = QuarantinedLinkResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 404, description = "Link not found or has no pending appeal", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn resolve_link_appeal(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Json(request): Json<ResolveAppealRequest>,
+) -> impl IntoResponse {
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = QuarantineService::new(&state);
+
+    match service
+        .resolve_appeal(link_id, admin_id, request.approve)
+        .await
+    {
+        Ok(link) => {
+            info!(
+                "Admin {} {} appeal for link {}",
+                admin_id,
+                if request.approve {
+                    "approved"
+                } else {
+                    "rejected"
+                },
+                link.id
+            );
+            Json(QuarantinedLinkResponse::from(link)).into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+// =============================================================================
+// AUDIT LOG HANDLERS
+// =============================================================================
+
+/// Search the audit trail across all users (filters: actor_id, action, resource_type, from, to)
+/// GET /v1/admin/audit-logs
+#[utoipa::path(
+    get,
+    path = "/v1/admin/audit-logs",
+    tag = "Admin",
+    operation_id = "adminListAuditLogs",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Page of audit entries, newest first", body = AuditLogListResponse),
+        (status = 400, description = "Unknown action or 'from' not earlier than 'to'", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_audit_logs(
+    State(state): State<AppState>,
+    Query(query): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    match query.into_filter(None) {
+        Ok(filter) => query_audit_logs(&state, filter).await,
+        Err(msg) => ServiceError::ValidationError(msg).into_response(),
+    }
+}
+
+/// Recent security incidents across all users, e.g. refresh token reuse
+/// GET /v1/admin/security-events
+#[utoipa::path(
+    get,
+    path = "/v1/admin/security-events",
+    tag = "Admin",
+    operation_id = "adminListSecurityEvents",
+    params(SecurityEventsQuery),
+    responses(
+        (status = 200, description = "Most recent 50 security incidents, newest first", body = [SecurityIncident]),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_security_events(
+    State(state): State<AppState>,
+    Query(query): Query<SecurityEventsQuery>,
+) -> impl IntoResponse {
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => return ServiceError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    match SecurityIncident::list_recent(&mut conn, query.user_id, SECURITY_EVENTS_LIMIT).await {
+        Ok(events) => Json(events).into_response(),
+        Err(e) => ServiceError::from(e).into_response(),
+    }
+}
+
+// =============================================================================
+// INSTANCE STATS HANDLERS
+// =============================================================================
+
+/// Instance-wide usage: users, links, redirects and storage counts
+/// GET /v1/admin/stats
+/// Figures whose backing store (ClickHouse, audit log) isn't enabled are null
+#[utoipa::path(
+    get,
+    path = "/v1/admin/stats",
+    tag = "Admin",
+    operation_id = "getInstanceStats",
+    responses(
+        (status = 200, description = "Instance usage", body = InstanceStatsResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_instance_stats(State(state): State<AppState>) -> impl IntoResponse {
+    Json(InstanceStatsService::new(&state).collect().await)
+}
+
+/// Request latency percentiles per route group on this instance
+/// GET /v1/admin/perf
+/// Each replica reports only the requests it served
+#[utoipa::path(
+    get,
+    path = "/v1/admin/perf",
+    tag = "Admin",
+    operation_id = "getLatencyMetrics",
+    responses(
+        (status = 200, description = "p50/p95/p99 per route group, for redirects and for the API over the last one to two windows", body = LatencyMetrics),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_latency_metrics() -> Json<LatencyMetrics> {
+    Json(latency_metrics())
+}
+
+// =============================================================================
+// SELF-TEST HANDLERS
+// =============================================================================
+
+/// Run the link lifecycle self-test against this instance's dependencies
+/// POST /v1/admin/self-test
+/// Same steps as `--self-test`; a temporary user and link are created and removed again
+#[utoipa::path(
+    post,
+    path = "/v1/admin/self-test",
+    tag = "Admin",
+    operation_id = "runSelfTest",
+    responses(
+        (status = 200, description = "Every step passed", body = SelfTestReport),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 503, description = "A step failed; the report names it", body = SelfTestReport)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn run_self_test_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let report: SelfTestReport = run_self_test(&state).await;
+    let status = if report.passed {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(report))
+}
+
+// =============================================================================
+// SECURITY METRICS HANDLERS
+// =============================================================================
+
+/// Block rate, threat types, top blocked domains and p95 scan latency over a date range
+/// GET /v1/admin/security/metrics
+/// Counts cover the scans sampled by SECURITY_SCAN_TELEMETRY_SAMPLE_RATE
+#[utoipa::path(
+    get,
+    path = "/v1/admin/security/metrics",
+    tag = "Admin",
+    operation_id = "getSecurityMetrics",
+    params(SecurityMetricsQuery),
+    responses(
+        (status = 200, description = "Security scan metrics", body = SecurityScanMetricsResponse),
+        (status = 400, description = "'from' after 'to' or range too long", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 503, description = "ClickHouse is not configured or could not be queried", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_security_metrics(
+    State(state): State<AppState>,
+    Query(query): Query<SecurityMetricsQuery>,
+) -> impl IntoResponse {
+    let (from, to) = match query.into_range(chrono::Utc::now().date_naive()) {
+        Ok(range) => range,
+        Err(msg) => return ServiceError::ValidationError(msg).into_response(),
+    };
+
+    let unavailable = || {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "ANALYTICS_UNAVAILABLE",
+            "Security scan metrics are unavailable",
+        )
+        .into_response()
+    };
+
+    let Some(analytics) = state.clickhouse_analytics.as_ref() else {
+        return unavailable();
+    };
+
+    match analytics
+        .get_security_scan_metrics(from, to, state.config.security.scan_telemetry_sample_rate)
+        .await
+    {
+        Ok(metrics) => Json(metrics).into_response(),
+        Err(e) => {
+            error!("Failed to load security scan metrics: {}", e);
+            unavailable()
+        },
+    }
+}
+
+// =============================================================================
+// RATE LIMIT HANDLERS
+// =============================================================================
+
+/// Most rate-limited IPs, emails and users per endpoint over a window
+/// GET /v1/admin/rate-limits/offenders
+/// Counts are kept per hour across all replicas; older hours are exported to ClickHouse
+#[utoipa::path(
+    get,
+    path = "/v1/admin/rate-limits/offenders",
+    tag = "Admin",
+    operation_id = "getRateLimitOffenders",
+    params(RateLimitOffendersQuery),
+    responses(
+        (status = 200, description = "Offenders per endpoint, as JSON or (format=csv) a CSV attachment",
+            content(
+                ("application/json" = RateLimitOffendersResponse),
+                ("text/csv" = String)
+            )
+        ),
+        (status = 400, description = "Invalid window or limit", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 503, description = "Rate limit analytics are disabled or Redis could not be queried", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_rate_limit_offenders(
+    State(state): State<AppState>,
+    Query(query): Query<RateLimitOffendersQuery>,
+) -> impl IntoResponse {
+    let (window_hours, limit) = match (query.window_hours(), query.limit()) {
+        (Ok(window_hours), Ok(limit)) => (window_hours, limit),
+        (Err(msg), _) | (_, Err(msg)) => return ServiceError::ValidationError(msg).into_response(),
+    };
+
+    let unavailable = || {
+        ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "ANALYTICS_UNAVAILABLE",
+            "Rate limit offenders are unavailable",
+        )
+        .into_response()
+    };
+
+    let Some(analytics) = state.rate_limit_service.analytics() else {
+        return unavailable();
+    };
+
+    let offenders = match analytics.top_offenders_in_window(window_hours, limit).await {
+        Ok(offenders) => offenders,
+        Err(e) => {
+            error!("Failed to load rate limit offenders: {}", e);
+            return unavailable();
+        },
+    };
+
+    let mut endpoints: Vec<EndpointOffenders> = offenders
+        .into_iter()
+        .map(|(endpoint, offenders)| EndpointOffenders {
+            endpoint,
+            offenders: offenders
+                .into_iter()
+                .map(|offender| {
+                    RateLimitOffenderEntry::new(offender.key, offender.blocked_requests)
+                })
+                .collect(),
+        })
+        .collect();
+    endpoints.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+
+    let response = RateLimitOffendersResponse {
+        window_start: offender_window_start(window_hours),
+        window_end: chrono::Utc::now(),
+        endpoints,
+    };
+
+    match query.format {
+        OffendersFormat::Json => Json(response).into_response(),
+        OffendersFormat::Csv => (
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                (
+                    header::CONTENT_DISPOSITION,
+                    format!(
+                        "attachment; filename=\"rate-limit-offenders-{}h.csv\"",
+                        window_hours
+                    ),
+                ),
+            ],
+            response.to_csv(),
+        )
+            .into_response(),
+    }
+}
+
+/// List denied client IPs that have not expired
+/// GET /v1/admin/rate-limits/denylist
+#[utoipa::path(
+    get,
+    path = "/v1/admin/rate-limits/denylist",
+    tag = "Admin",
+    operation_id = "listDeniedIps",
+    responses(
+        (status = 200, description = "Denied IPs", body = [DeniedIpResponse]),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_denied_ips(State(state): State<AppState>) -> impl IntoResponse {
+    let service = IpDenylistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.list().await {
+        Ok(entries) => Json(
+            entries
+                .into_iter()
+                .map(DeniedIpResponse::from)
+                .collect::<Vec<_>>(),
+        )
+        .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Deny a client IP, e.g. a rate limit offender; its requests get 403 on every route
+/// POST /v1/admin/rate-limits/denylist
+#[utoipa::path(
+    post,
+    path = "/v1/admin/rate-limits/denylist",
+    tag = "Admin",
+    operation_id = "createDeniedIp",
+    request_body = CreateDeniedIpRequest,
+    responses(
+        (status = 201, description = "IP denied on every replica within the blocklist refresh interval", body = DeniedIpResponse),
+        (status = 400, description = "Invalid IP, reason or expiry", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 409, description = "IP is already denied", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_denied_ip(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateDeniedIpRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = request.validate() {
+        return ApiError::from(e)
+            .with_status(StatusCode::BAD_REQUEST)
+            .into_response();
+    }
+
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = IpDenylistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.add(&request, admin_id).await {
+        Ok(entry) => {
+            info!("Admin {} denied IP {}", admin_id, entry.ip);
+            (StatusCode::CREATED, Json(DeniedIpResponse::from(entry))).into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Lift a client IP denial
+/// DELETE /v1/admin/rate-limits/denylist/{id}
+#[utoipa::path(
+    delete,
+    path = "/v1/admin/rate-limits/denylist/{id}",
+    tag = "Admin",
+    operation_id = "deleteDeniedIp",
+    params(
+        ("id" = Uuid, Path, description = "Denylist entry ID")
+    ),
+    responses(
+        (status = 204, description = "Entry removed"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 404, description = "Entry not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_denied_ip(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(entry_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let service = IpDenylistService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+
+    match service.remove(entry_id, admin_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+// =============================================================================
+// USER DEACTIVATION HANDLERS
+// =============================================================================
+
+/// Deactivate a user and end all of their sessions
+/// POST /v1/admin/users/{id}/deactivate
+/// Refresh tokens are revoked and access tokens already issued stop working at once
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/deactivate",
+    tag = "Admin",
+    operation_id = "deactivateUser",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 204, description = "User deactivated and signed out everywhere"),
+        (status = 400, description = "Admins cannot deactivate themselves", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required or caller is impersonating", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn deactivate_user(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    use crate::schema::users;
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+
+    if let Err(e) = auth_user.forbid_impersonation() {
+        return (*e).into_response();
+    }
+
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+    if admin_id == user_id {
+        return ApiError::bad_request("Admins cannot deactivate themselves").into_response();
+    }
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
+
+    let email = match diesel::update(users::table.find(user_id))
+        .set((
+            users::is_active.eq(false),
+            users::updated_at.eq(chrono::Utc::now()),
+        ))
+        .returning(users::email)
+        .get_result::<String>(&mut conn)
+        .await
+    {
+        Ok(email) => email,
+        Err(diesel::result::Error::NotFound) => {
+            return ApiError::not_found("User not found").into_response()
+        },
+        Err(e) => {
+            error!("Failed to deactivate user {}: {}", user_id, e);
+            return ApiError::internal("Failed to deactivate user").into_response();
+        },
+    };
+
+    if let Err(e) = state
+        .jwt_service
+        .revoke_all_user_tokens(&user_id.to_string())
+        .await
+    {
+        error!(
+            "Failed to revoke tokens of deactivated user {}: {}",
+            user_id, e
+        );
+        return ApiError::internal("User deactivated but revoking their sessions failed")
+            .into_response();
+    }
+
+    warn!("Admin {} deactivated user {}", admin_id, user_id);
+    AuditLogger::log_resource_action(
+        AuditAction::UserDeactivated,
+        admin_id,
+        "user",
+        Some(user_id.to_string()),
+        Some(format!("Deactivated {}", email)),
+    )
+    .await;
+
+    StatusCode::NO_CONTENT.into_response()
+}
+
+// =============================================================================
+// ACCOUNT IMPORT HANDLERS
+// =============================================================================
+
+/// Recreate a user and their links from an account export archive
+/// POST /v1/admin/users/import
+/// New ids are generated; taken short codes get a numeric suffix (`launch` -> `launch-2`)
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/import",
+    tag = "Admin",
+    operation_id = "importUser",
+    request_body = AccountArchive,
+    responses(
+        (status = 201, description = "User created; per-link results included", body = AccountImportResponse),
+        (status = 400, description = "Unsupported archive version", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required", body = ApiError),
+        (status = 409, description = "A user with the archive's email already exists", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn import_user(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(archive): Json<AccountArchive>,
+) -> impl IntoResponse {
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    match AccountExportService::new(&state)
+        .import(archive, admin_id)
+        .await
+    {
+        Ok(report) => {
+            info!(
+                "Admin {} imported user {} ({} links failed)",
+                admin_id, report.user_id, report.links_failed
+            );
+            (StatusCode::CREATED, Json(report)).into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+// =============================================================================
+// IMPERSONATION HANDLERS
+// =============================================================================
+
+/// Short-lived access token for acting as another user; there is no refresh token
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ImpersonationTokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    /// Seconds until the token expires (always 900)
+    #[schema(example = 900)]
+    pub expires_in: u64,
+    pub user_id: String,
+    pub email: String,
+    pub impersonator_id: String,
+}
+
+/// Log in as a user for support purposes ("login as user")
+/// POST /v1/admin/users/{id}/impersonate
+/// The token expires after 15 minutes and cannot be refreshed; every request made
+/// with it is audit-logged under the user with the admin's ID
+#[utoipa::path(
+    post,
+    path = "/v1/admin/users/{id}/impersonate",
+    tag = "Admin",
+    operation_id = "impersonateUser",
+    params(
+        ("id" = Uuid, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Impersonation token issued", body = ImpersonationTokenResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - admin scope required, target is an admin, or caller is already impersonating", body = ApiError),
+        (status = 404, description = "User not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn impersonate_user(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(user_id): Path<Uuid>,
+) -> impl IntoResponse {
+    if let Err(e) = auth_user.forbid_impersonation() {
+        return (*e).into_response();
+    }
+
+    let admin_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
+
+    let user = match User::find_by_id(&mut conn, user_id).await {
+        Ok(user) => user,
+        Err(UserError::NotFound) => return ApiError::not_found("User not found").into_response(),
+        Err(e) => {
+            error!("Failed to load user {}: {}", user_id, e);
+            return ApiError::internal("Failed to load user").into_response();
+        },
+    };
+
+    // Same scopes the user would get at login
+    let scope =
+        PermissionConfig::get_instance_scopes(&user.email, &state.config.security.admin_emails);
+    if scope.iter().any(|s| s == INSTANCE_ADMIN_SCOPE) {
+        return ApiError::forbidden("Admins cannot be impersonated").into_response();
+    }
+
+    let access_token = match state.jwt_service.generate_impersonation_token(
+        &user.id.to_string(),
+        &user.email,
+        user.subscription_tier_str(),
+        scope,
+        &auth_user.user_id,
+    ) {
+        Ok(token) => token,
+        Err(e) => {
+            error!("Failed to generate impersonation token: {}", e);
+            return ApiError::internal("Failed to generate token").into_response();
+        },
+    };
+
+    warn!("Admin {} started impersonating user {}", admin_id, user.id);
+    AuditLogger::log_resource_action(
+        AuditAction::UserImpersonated,
+        admin_id,
+        "user",
+        Some(user.id.to_string()),
+        Some(format!("Impersonating {}", user.email)),
+    )
+    .await;
+
+    Json(ImpersonationTokenResponse {
+        access_token,
+        token_type: "Bearer".to_string(),
+        expires_in: IMPERSONATION_TOKEN_EXPIRY_SECONDS,
+        user_id: user.id.to_string(),
+        email: user.email,
+        impersonator_id: auth_user.user_id,
+    })
+    .into_response()
+}
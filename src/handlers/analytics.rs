@@ -0,0 +1,108 @@
+// Account-level analytics endpoints
+// Per-link stats live in handlers/links.rs; these aggregate across all of a user's links
+
+use axum::{
+    extract::{Extension, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    models::analytics::UsageSummaryQuery,
+    services::{api_usage::ApiUsageService, usage_summary::UsageSummaryService},
+    utils::{
+        service_error::ServiceError,
+        timezone::{resolve_timezone, today_in},
+    },
+};
+
+/// Monthly usage summary for the authenticated user
+/// The month is counted in the `tz` parameter or the user's timezone preference
+/// GET /v1/analytics/summary
+#[utoipa::path(
+    get,
+    path = "/v1/analytics/summary",
+    tag = "Analytics",
+    operation_id = "getUsageSummary",
+    params(UsageSummaryQuery),
+    responses(
+        (status = 200, description = "Links created, clicks, top links, top referrer and top country for the month", body = UsageSummaryResponse),
+        (status = 400, description = "Malformed or future period, or unknown timezone", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_usage_summary(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<UsageSummaryQuery>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    let tz = match resolve_timezone(&state.diesel_pool, user_id, query.tz.as_deref()).await {
+        Ok(tz) => tz,
+        Err(e) => return e.into_response(),
+    };
+    let today = today_in(tz);
+    let period = match query.into_period(today) {
+        Ok(period) => period,
+        Err(msg) => return ServiceError::ValidationError(msg).into_response(),
+    };
+
+    match UsageSummaryService::new(&state)
+        .summarize(user_id, period, today, tz)
+        .await
+    {
+        Ok(summary) => Json(summary).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// API request counts for the authenticated user
+/// Requests per UTC day for the last 30 days, 429 counts and the most-used endpoints
+/// GET /v1/usage
+#[utoipa::path(
+    get,
+    path = "/v1/usage",
+    tag = "Analytics",
+    operation_id = "getApiUsage",
+    responses(
+        (status = 200, description = "Daily request and rate-limited counts and the busiest endpoints", body = ApiUsageResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_api_usage(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    match ApiUsageService::new(&state)
+        .user_usage(user_id, Utc::now().date_naive())
+        .await
+    {
+        Ok(usage) => Json(usage).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
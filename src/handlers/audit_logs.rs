@@ -0,0 +1,73 @@
+// Audit log query endpoints
+// Users see their own actions; the admin variant lives in handlers/admin.rs
+
+use axum::{
+    extract::{Extension, Query, State},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    models::audit_log::{AuditLogFilter, AuditLogListResponse, AuditLogQuery, AuditLogResponse},
+    services::audit_log::AuditLogService,
+    utils::service_error::ServiceError,
+};
+
+/// List the authenticated user's own audit trail
+/// GET /v1/audit-logs
+#[utoipa::path(
+    get,
+    path = "/v1/audit-logs",
+    tag = "Audit Logs",
+    operation_id = "listMyAuditLogs",
+    params(AuditLogQuery),
+    responses(
+        (status = 200, description = "Page of the caller's audit entries, newest first", body = AuditLogListResponse),
+        (status = 400, description = "Unknown action or 'from' not earlier than 'to'", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_my_audit_logs(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<AuditLogQuery>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    // The actor is always the caller, whatever actor_id was passed
+    match query.into_filter(Some(user_id)) {
+        Ok(filter) => query_audit_logs(&state, filter).await,
+        Err(msg) => ServiceError::ValidationError(msg).into_response(),
+    }
+}
+
+/// Run a validated filter and render the page
+pub(crate) async fn query_audit_logs(
+    state: &AppState,
+    filter: AuditLogFilter,
+) -> axum::response::Response {
+    let service = AuditLogService::new(state.diesel_pool.clone());
+
+    match service.list(&filter).await {
+        Ok((records, total)) => Json(AuditLogListResponse {
+            logs: records.into_iter().map(AuditLogResponse::from).collect(),
+            total,
+            page: filter.page,
+            per_page: filter.per_page,
+        })
+        .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -5,8 +5,8 @@
 
 use axum::{
     body::Bytes,
-    extract::{ConnectInfo, Extension, State},
-    http::{HeaderMap, StatusCode},
+    extract::{ConnectInfo, Extension, Query, State},
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Json, Response},
 };
 use axum_extra::{
@@ -14,25 +14,40 @@ use axum_extra::{
     headers::UserAgent,
     TypedHeader,
 };
+use base64::prelude::*;
+use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::net::SocketAddr;
+use subtle::ConstantTimeEq;
 use time::Duration;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
     app::AppState,
+    app_config::{CookieSameSite, RefreshCookieConfig},
+    config::PermissionConfig,
     middleware::auth::AuthenticatedUser,
     models::{
+        account_export::{AccountExportDownloadQuery, AccountExportQuery, AccountExportResponse},
+        link::TrackingMode,
+        notification_preferences::NotificationKind,
         password_reset::{
             ForgotPasswordRequest, ForgotPasswordResponse, ResetPasswordRequest,
             ResetPasswordResponse,
         },
-        user::{NewUser, OnboardingStatus, User, UserError},
+        security_incident::{SecurityIncident, SECURITY_EVENTS_LIMIT},
+        user::{NewUser, OnboardingStatus, User, UserError, UserUpdate},
+    },
+    services::{
+        account_export::AccountExportService, account_unlock::AccountUnlockService, jwt::JwtError,
+        rate_limit::RateLimitConfig,
     },
-    services::{jwt::JwtError, rate_limit::RateLimitConfig},
     utils::{
-        auth_errors::AuthError, generate_device_fingerprint, hash_password,
-        trim_and_validate_field, trim_optional_field, verify_password,
+        api_error::ApiError, auth_errors::AuthError, generate_device_fingerprint, hash_password,
+        timezone::parse_timezone, trim_and_validate_field, trim_optional_field,
+        url_validator::parse_strip_query_params, verify_password,
     },
 };
 
@@ -40,29 +55,84 @@ use crate::{
 // REQUEST/RESPONSE TYPES
 // =============================================================================
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({
+    "email": "user@example.com",
+    "password": "SecurePass123!",
+    "remember_me": false
+}))]
 pub struct LoginRequest {
     pub email: String,
+    #[schema(format = Password)]
     pub password: String,
+    /// Persist the refresh token cookie for the configured remember-me period
     #[serde(default)]
     pub remember_me: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct RefreshRequest {
     // Make refresh_token optional for web clients (use cookie instead)
     pub refresh_token: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Validate)]
+/// Query parameters for GET /v1/auth/unlock
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AccountUnlockQuery {
+    /// Token from the lockout email
+    pub token: String,
+}
+
+/// Query parameters for POST /v1/auth/sessions/revoke-all
+#[derive(Debug, Default, Deserialize, IntoParams)]
+pub struct RevokeSessionsQuery {
+    /// Keep the session making the request signed in. It is identified by its refresh
+    /// token: the refresh cookie, or `refresh_token` in the JSON body.
+    #[serde(default)]
+    pub keep_current: bool,
+}
+
+/// Result of signing out everywhere
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "revoked_sessions": 3,
+    "kept_current": true,
+    "access_token": "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9..."
+}))]
+pub struct RevokeSessionsResponse {
+    /// Refresh tokens revoked
+    pub revoked_sessions: usize,
+    pub kept_current: bool,
+    /// New access token for the kept session, since every access token issued before,
+    /// including the one that made this request, stops working; `null` without `keep_current`
+    pub access_token: Option<String>,
+}
+
+/// Security incidents on the caller's account, newest first
+#[derive(Debug, Serialize, ToSchema)]
+pub struct SecurityEventsResponse {
+    pub events: Vec<SecurityIncident>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "email": "user@example.com",
+    "password": "SecurePass123!",
+    "password_confirmation": "SecurePass123!",
+    "full_name": "Jane Doe",
+    "company_name": "Acme Inc",
+    "accept_terms": true
+}))]
 pub struct RegisterRequest {
     #[validate(email(message = "Invalid email format"))]
     #[validate(length(max = 320, message = "Email must be less than 320 characters"))]
     pub email: String,
 
-    #[validate(custom(function = "validate_password"))]
+    /// Min 8 characters with uppercase, lowercase, digit and special character
+    #[schema(format = Password)]
     pub password: String,
 
+    #[schema(format = Password)]
     pub password_confirmation: String,
 
     #[validate(length(
@@ -78,60 +148,186 @@ pub struct RegisterRequest {
     pub accept_terms: bool,
 }
 
-/// Custom password validation - min 8 chars, must have uppercase, lowercase, number, special char
-fn validate_password(password: &str) -> Result<(), validator::ValidationError> {
-    let has_uppercase = password.chars().any(|c| c.is_uppercase());
-    let has_lowercase = password.chars().any(|c| c.is_lowercase());
-    let has_digit = password.chars().any(|c| c.is_ascii_digit());
-    let has_special = password.chars().any(|c| !c.is_alphanumeric());
+/// Password policy - min 8 chars, must have uppercase, lowercase, number, special char
+/// Returns one error per unmet rule so clients can flag each requirement separately
+fn password_policy_errors(password: &str) -> Vec<validator::ValidationError> {
+    let rules = [
+        (
+            password.len() >= 8,
+            "too_short",
+            "Password must be at least 8 characters",
+        ),
+        (
+            password.chars().any(|c| c.is_uppercase()),
+            "missing_uppercase",
+            "Password must contain an uppercase letter",
+        ),
+        (
+            password.chars().any(|c| c.is_lowercase()),
+            "missing_lowercase",
+            "Password must contain a lowercase letter",
+        ),
+        (
+            password.chars().any(|c| c.is_ascii_digit()),
+            "missing_digit",
+            "Password must contain a number",
+        ),
+        (
+            password.chars().any(|c| !c.is_alphanumeric()),
+            "missing_special",
+            "Password must contain a special character",
+        ),
+    ];
+
+    rules
+        .into_iter()
+        .filter(|(met, _, _)| !met)
+        .map(|(_, code, message)| {
+            let mut error = validator::ValidationError::new(code);
+            error.message = Some(message.into());
+            error
+        })
+        .collect()
+}
 
-    if password.len() < 8 {
-        return Err(validator::ValidationError::new("password_too_short"));
+/// Run the derived validators plus the password policy for `password_field`
+fn validate_with_password_policy(
+    request: &impl Validate,
+    password_field: &'static str,
+    password: &str,
+) -> Result<(), validator::ValidationErrors> {
+    let mut errors = request.validate().err().unwrap_or_default();
+    for error in password_policy_errors(password) {
+        errors.add(password_field, error);
     }
 
-    if !has_uppercase || !has_lowercase || !has_digit || !has_special {
-        return Err(validator::ValidationError::new("password_complexity"));
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
-
-    Ok(())
 }
 
 /// Helper function to create standardized auth error responses
 /// Available to other modules in the crate for consistent error formatting
 pub(crate) fn create_auth_error_response(message: &str) -> Response {
-    let response = AuthResponse::<TokenResponse> {
-        success: false,
-        data: None,
-        message: message.to_string(),
+    ApiError::bad_request(message).into_response()
+}
+
+/// Double-submit CSRF cookie; readable by the frontend, which echoes it in `CSRF_HEADER`
+const CSRF_COOKIE_NAME: &str = "csrf_token";
+
+/// Header that must repeat the CSRF cookie on cookie-authenticated refresh and logout
+const CSRF_HEADER: &str = "x-csrf-token";
+
+/// Auth cookie with the configured domain, SameSite policy and Secure flag
+fn build_session_cookie(
+    name: String,
+    value: String,
+    http_only: bool,
+    cookie_config: &RefreshCookieConfig,
+) -> Cookie<'static> {
+    let same_site = match cookie_config.same_site {
+        CookieSameSite::Strict => SameSite::Strict,
+        CookieSameSite::Lax => SameSite::Lax,
+        CookieSameSite::None => SameSite::None,
     };
-    (StatusCode::BAD_REQUEST, Json(response)).into_response()
+
+    let mut cookie = Cookie::build((name, value))
+        .path("/")
+        .http_only(http_only)
+        .secure(cookie_config.secure)
+        .same_site(same_site)
+        .build();
+
+    // Without a domain the cookie is only sent back to the API host itself
+    if !cookie_config.domain.is_empty() {
+        cookie.set_domain(cookie_config.domain.clone());
+    }
+
+    cookie
+}
+
+/// Refresh token cookie with the configured name, domain, SameSite policy and Secure flag
+fn build_refresh_cookie(value: String, cookie_config: &RefreshCookieConfig) -> Cookie<'static> {
+    build_session_cookie(cookie_config.name.clone(), value, true, cookie_config)
+}
+
+/// CSRF cookie sharing the refresh cookie's attributes, but readable by scripts
+fn build_csrf_cookie(value: String, cookie_config: &RefreshCookieConfig) -> Cookie<'static> {
+    build_session_cookie(CSRF_COOKIE_NAME.to_string(), value, false, cookie_config)
 }
 
 /// Helper function to create a cookie that deletes the refresh token
+/// Uses the same attributes as the refresh cookie, or browsers keep the original
 fn create_delete_refresh_cookie(config: &crate::app_config::AppConfig) -> Cookie<'static> {
-    Cookie::build(("refresh_token", ""))
-        .path("/")
-        .http_only(true)
-        .secure(config.is_production())
-        .same_site(SameSite::Strict)
-        .max_age(Duration::seconds(-1)) // Negative max_age deletes the cookie
-        .build()
+    let mut cookie = build_refresh_cookie(String::new(), &config.security.refresh_cookie);
+    cookie.set_max_age(Duration::seconds(-1)); // Negative max_age deletes the cookie
+    cookie
+}
+
+/// Helper function to create a cookie that deletes the CSRF token
+fn create_delete_csrf_cookie(config: &crate::app_config::AppConfig) -> Cookie<'static> {
+    let mut cookie = build_csrf_cookie(String::new(), &config.security.refresh_cookie);
+    cookie.set_max_age(Duration::seconds(-1));
+    cookie
 }
 
 /// Helper function to create a refresh token cookie with configurable persistence
 fn create_refresh_token_cookie(token: String, remember_me: bool, config: &crate::app_config::AppConfig) -> Cookie<'static> {
-    let mut cookie_builder = Cookie::build(("refresh_token", token))
-        .path("/")
-        .http_only(true)
-        .secure(config.is_production())
-        .same_site(SameSite::Strict);
+    let mut cookie = build_refresh_cookie(token, &config.security.refresh_cookie);
 
     // Only set max_age for remember_me - without it, cookie is session-only
     if remember_me {
-        cookie_builder = cookie_builder.max_age(Duration::days(config.security.remember_me_duration_days as i64));
+        cookie.set_max_age(Duration::days(
+            config.security.remember_me_duration_days as i64,
+        ));
     }
 
-    cookie_builder.build()
+    cookie
+}
+
+/// Helper function to create a fresh CSRF cookie that lives as long as the refresh cookie
+fn create_csrf_cookie(remember_me: bool, config: &crate::app_config::AppConfig) -> Cookie<'static> {
+    let mut token = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token);
+    let mut cookie = build_csrf_cookie(
+        BASE64_URL_SAFE_NO_PAD.encode(token),
+        &config.security.refresh_cookie,
+    );
+
+    if remember_me {
+        cookie.set_max_age(Duration::days(
+            config.security.remember_me_duration_days as i64,
+        ));
+    }
+
+    cookie
+}
+
+/// Double-submit check for requests that carry the refresh cookie
+/// Other sites can make the browser send the cookies but cannot read them to set the header
+fn verify_csrf_token(jar: &CookieJar, headers: &HeaderMap) -> Result<(), Box<ApiError>> {
+    let expected = jar
+        .get(CSRF_COOKIE_NAME)
+        .map(|cookie| cookie.value())
+        .filter(|value| !value.is_empty());
+    let provided = headers
+        .get(CSRF_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    match (expected, provided) {
+        (Some(expected), Some(provided))
+            if bool::from(expected.as_bytes().ct_eq(provided.as_bytes())) =>
+        {
+            Ok(())
+        },
+        _ => Err(Box::new(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "CSRF_TOKEN_INVALID",
+            "Missing or invalid CSRF token",
+        ))),
+    }
 }
 
 /// Validate JWT token format (must have exactly 3 parts separated by dots)
@@ -141,9 +337,13 @@ pub(crate) fn is_valid_jwt_format(token: &str) -> bool {
 }
 
 /// Extract refresh token from cookie (web) or JSON body (mobile)
-fn extract_refresh_token(jar: &CookieJar, body: &Bytes) -> Result<String, Response> {
+fn extract_refresh_token(
+    jar: &CookieJar,
+    cookie_name: &str,
+    body: &Bytes,
+) -> Result<String, Response> {
     // Try cookie first (web clients)
-    if let Some(cookie) = jar.get("refresh_token") {
+    if let Some(cookie) = jar.get(cookie_name) {
         let token = cookie.value();
         // Basic JWT format validation: must have 3 parts separated by dots
         if !is_valid_jwt_format(token) {
@@ -173,7 +373,7 @@ fn extract_refresh_token(jar: &CookieJar, body: &Bytes) -> Result<String, Respon
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginResponse {
     pub access_token: String,
     pub refresh_token: String,
@@ -183,7 +383,7 @@ pub struct LoginResponse {
     pub remember_me: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct LoginUserInfo {
     pub id: String,
     pub email: String,
@@ -192,7 +392,7 @@ pub struct LoginUserInfo {
     pub onboarding_status: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct TokenResponse {
     pub access_token: String,
     pub refresh_token: String,
@@ -200,7 +400,7 @@ pub struct TokenResponse {
     pub token_type: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct RegisterResponse {
     pub user_id: String,
     pub email: String,
@@ -212,14 +412,27 @@ pub struct RegisterResponse {
     pub message: String,
 }
 
-#[derive(Debug, Serialize)]
+/// Envelope shared by the auth endpoints; `data` is null on failure
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    LoginApiResponse = AuthResponse<LoginResponse>,
+    RegisterApiResponse = AuthResponse<RegisterResponse>,
+    TokenApiResponse = AuthResponse<TokenResponse>,
+    UserInfoApiResponse = AuthResponse<UserInfo>,
+    UserPreferencesApiResponse = AuthResponse<UserPreferences>,
+    RevokeSessionsApiResponse = AuthResponse<RevokeSessionsResponse>,
+    SecurityEventsApiResponse = AuthResponse<SecurityEventsResponse>,
+    AccountExportApiResponse = AuthResponse<AccountExportResponse>,
+    TokenValidationApiResponse = AuthResponse<TokenValidation>,
+    MessageApiResponse = AuthResponse<serde_json::Value>
+)]
 pub struct AuthResponse<T> {
     pub success: bool,
     pub data: Option<T>,
     pub message: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserInfo {
     pub user_id: String,
     pub email: String,
@@ -229,6 +442,37 @@ pub struct UserInfo {
     pub permissions: Vec<String>,
 }
 
+/// Account-wide defaults applied to new links
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "default_tracking_mode": "respect_dnt",
+    "monthly_digest_enabled": true,
+    "timezone": "Asia/Tokyo",
+    "strip_query_params": ["fbclid", "gclid", "utm_*"]
+}))]
+pub struct UserPreferences {
+    /// Click tracking mode for links created without an explicit `tracking_mode`
+    pub default_tracking_mode: TrackingMode,
+    /// Receive a usage summary email on the first of each month
+    #[serde(default)]
+    pub monthly_digest_enabled: bool,
+    /// IANA timezone that stats days and months are counted in; unchanged when omitted
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// Query parameters removed from destination URLs of new and edited links. Entries are
+    /// names (`fbclid`) or prefixes ending in `*` (`utm_*`); unchanged when omitted
+    #[serde(default)]
+    pub strip_query_params: Option<Vec<String>>,
+}
+
+/// Claims echoed back by POST /auth/validate
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TokenValidation {
+    pub valid: bool,
+    pub user_id: String,
+    pub subscription_tier: String,
+}
+
 // =============================================================================
 // CONSTANTS
 // =============================================================================
@@ -243,6 +487,21 @@ pub struct UserInfo {
 /// POST /auth/login - Authenticate user and return JWT tokens
 /// DEV-102: Comprehensive login with rate limiting, account lockout, and remember_me
 /// Supports both web (cookies) and mobile (JSON tokens) authentication
+#[utoipa::path(
+    post,
+    path = "/v1/auth/login",
+    tag = "Authentication",
+    operation_id = "loginUser",
+    request_body = LoginRequest,
+    responses(
+        (status = 200, description = "Login successful; the refresh token is also set as an HttpOnly cookie, with a readable csrf_token cookie", body = LoginApiResponse),
+        (status = 401, description = "Invalid credentials", body = ApiError),
+        (status = 403, description = "Email not verified or account inactive", body = ApiError),
+        (status = 423, description = "Account locked after too many failed attempts; when enabled, the owner is emailed an unlock link", body = ApiError),
+        (status = 429, description = "Too many login attempts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    )
+)]
 pub async fn login(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
@@ -250,7 +509,10 @@ pub async fn login(
     jar: CookieJar,
     Json(login_req): Json<LoginRequest>,
 ) -> impl IntoResponse {
-    use crate::utils::{create_auth_audit_entry, log_auth_failure, AuthError, AuthEventType};
+    use crate::utils::{
+        audit_logger::AuditLogger, create_auth_audit_entry, log_auth_failure, AuthError,
+        AuthEventType,
+    };
 
     // Capture timestamp at request start for consistent timing throughout request
     let now_timestamp = chrono::Utc::now().timestamp();
@@ -451,6 +713,26 @@ pub async fn login(
 
                 // Log account lockout audit event
                 tracing::warn!("Account locked: {:?}", audit);
+                AuditLogger::log_auth_event(&audit);
+
+                // Email the owner a single-use unlock link without delaying the response
+                if config.security.account_unlock_email_enabled {
+                    let unlock_service = AccountUnlockService::new(&state);
+                    let locked_user = user.clone();
+                    let locked_email = email.clone();
+                    tokio::spawn(async move {
+                        if let Err(e) = unlock_service
+                            .notify_locked(&locked_user, &locked_email, lockout_duration as u64)
+                            .await
+                        {
+                            tracing::warn!(
+                                "Failed to send unlock email for user {}: {}",
+                                locked_user.id,
+                                e
+                            );
+                        }
+                    });
+                }
 
                 return AuthError::AccountLocked {
                     retry_after_seconds: lockout_duration as u64,
@@ -495,7 +777,8 @@ pub async fn login(
         &user.id.to_string(),
         &email,
         &user.subscription_tier,
-        vec![], // No special permissions for now
+        // No special permissions beyond instance scopes (e.g. operator admin)
+        PermissionConfig::get_instance_scopes(&email, &state.config.security.admin_emails),
     ) {
         Ok(token) => token,
         Err(e) => {
@@ -538,8 +821,13 @@ pub async fn login(
 
     // Log successful login audit event
     tracing::info!("Login successful: {:?}", audit);
+    AuditLogger::log_auth_event(&audit);
 
     // Step 12: Build and return response
+    let onboarding_status = user
+        .effective_onboarding_status(config.is_oss_deployment)
+        .as_str()
+        .to_string();
     let response = AuthResponse {
         success: true,
         data: Some(LoginResponse {
@@ -552,7 +840,7 @@ pub async fn login(
                 email: user.email,
                 full_name: user.full_name,
                 subscription_tier: user.subscription_tier,
-                onboarding_status: user.onboarding_status,
+                onboarding_status,
             },
             remember_me: login_req.remember_me,
         }),
@@ -563,9 +851,10 @@ pub async fn login(
     // When remember_me=true: persistent cookie with 30-day expiry
     // When remember_me=false: session cookie (deleted when browser closes)
     let refresh_cookie = create_refresh_token_cookie(refresh_token, login_req.remember_me, &config);
+    let csrf_cookie = create_csrf_cookie(login_req.remember_me, config);
 
-    // Add cookie to response
-    let updated_jar = jar.add(refresh_cookie);
+    // Add cookies to response
+    let updated_jar = jar.add(refresh_cookie).add(csrf_cookie);
 
     (StatusCode::OK, updated_jar, Json(response)).into_response()
 }
@@ -643,6 +932,20 @@ async fn clear_failed_login_attempts(state: &AppState, email: &str) {
 
 /// POST /auth/register - Register a new user account
 /// DEV-101: User Registration with Argon2 password hashing and email verification
+#[utoipa::path(
+    post,
+    path = "/v1/auth/register",
+    tag = "Authentication",
+    operation_id = "registerUser",
+    request_body = RegisterRequest,
+    responses(
+        (status = 201, description = "User registered", body = RegisterApiResponse),
+        (status = 400, description = "Validation failed", body = ApiError),
+        (status = 409, description = "Email already registered", body = ApiError),
+        (status = 429, description = "Too many registrations from this IP", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    )
+)]
 pub async fn register(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
@@ -650,48 +953,35 @@ pub async fn register(
     Json(register_req): Json<RegisterRequest>,
 ) -> impl IntoResponse {
     // Step 1: Validate request
-    if let Err(validation_errors) = register_req.validate() {
-        let error_messages: Vec<String> = validation_errors
-            .field_errors()
-            .into_iter()
-            .flat_map(|(field, errors)| {
-                errors.iter().map(move |e| {
-                    let message = e
-                        .message
-                        .as_ref()
-                        .map(|m| m.to_string())
-                        .unwrap_or_else(|| e.code.to_string());
-                    format!("{}: {}", field, message)
-                })
-            })
-            .collect();
-
-        let response = AuthResponse::<RegisterResponse> {
-            success: false,
-            data: None,
-            message: error_messages.join(", "),
-        };
-        return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+    if let Err(validation_errors) =
+        validate_with_password_policy(&register_req, "password", &register_req.password)
+    {
+        return ApiError::from(validation_errors)
+            .with_field_summary()
+            .with_status(StatusCode::BAD_REQUEST)
+            .into_response();
     }
 
     // Validate password confirmation matches
     if register_req.password != register_req.password_confirmation {
-        let response = AuthResponse::<RegisterResponse> {
-            success: false,
-            data: None,
-            message: "Passwords do not match".to_string(),
-        };
-        return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        return ApiError::invalid_field(
+            "password_confirmation",
+            "must_match",
+            "Passwords do not match",
+        )
+        .with_status(StatusCode::BAD_REQUEST)
+        .into_response();
     }
 
     // Step 2: Check terms acceptance
     if !register_req.accept_terms {
-        let response = AuthResponse::<RegisterResponse> {
-            success: false,
-            data: None,
-            message: "You must accept the terms and conditions".to_string(),
-        };
-        return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        return ApiError::invalid_field(
+            "accept_terms",
+            "required",
+            "You must accept the terms and conditions",
+        )
+        .with_status(StatusCode::BAD_REQUEST)
+        .into_response();
     }
 
     // Step 3: Apply rate limiting (5 requests per minute per IP) - if enabled
@@ -712,15 +1002,17 @@ pub async fn register(
             .await
         {
             Ok(status) if !status.allowed => {
-                let response = AuthResponse::<RegisterResponse> {
-                    success: false,
-                    data: None,
-                    message: format!(
+                let retry_after = status.retry_after.unwrap_or(60);
+                return ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "RATE_LIMITED",
+                    format!(
                         "Too many registration attempts. Please try again in {} seconds",
-                        status.retry_after.unwrap_or(60)
+                        retry_after
                     ),
-                };
-                return (StatusCode::TOO_MANY_REQUESTS, Json(response)).into_response();
+                )
+                .with_retry_after(retry_after as u64)
+                .into_response();
             },
             Err(e) => {
                 tracing::warn!("Rate limit check failed for registration: {}", e);
@@ -735,12 +1027,7 @@ pub async fn register(
         Ok(conn) => conn,
         Err(e) => {
             tracing::error!("Failed to get database connection: {}", e);
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "Database connection error".to_string(),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            return ApiError::internal("Database connection error").into_response();
         },
     };
 
@@ -748,24 +1035,19 @@ pub async fn register(
     match User::find_by_email(&mut conn, &register_req.email).await {
         Ok(_existing_user) => {
             // Email already exists
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "An account with this email address already exists".to_string(),
-            };
-            return (StatusCode::CONFLICT, Json(response)).into_response();
+            return ApiError::new(
+                StatusCode::CONFLICT,
+                "EMAIL_EXISTS",
+                "An account with this email address already exists",
+            )
+            .into_response();
         },
         Err(UserError::NotFound) => {
             // Good, email doesn't exist
         },
         Err(e) => {
             tracing::error!("Error checking email uniqueness: {}", e);
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "Failed to check email availability".to_string(),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            return ApiError::internal("Failed to check email availability").into_response();
         },
     }
 
@@ -774,12 +1056,7 @@ pub async fn register(
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!("Failed to hash password: {}", e);
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "Failed to process password".to_string(),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            return ApiError::internal("Failed to process password").into_response();
         },
     };
 
@@ -787,12 +1064,9 @@ pub async fn register(
     let full_name = match trim_and_validate_field(&register_req.full_name, true) {
         Ok(name) => name,
         Err(_) => {
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "Full name cannot be empty".to_string(),
-            };
-            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+            return ApiError::invalid_field("full_name", "required", "Full name cannot be empty")
+                .with_status(StatusCode::BAD_REQUEST)
+                .into_response();
         },
     };
 
@@ -823,12 +1097,7 @@ pub async fn register(
         Ok(user) => user,
         Err(e) => {
             tracing::error!("Failed to create user: {}", e);
-            let response = AuthResponse::<RegisterResponse> {
-                success: false,
-                data: None,
-                message: "Failed to create user account".to_string(),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            return ApiError::internal("Failed to create user account").into_response();
         },
     };
 
@@ -872,6 +1141,27 @@ pub async fn register(
 /// POST /auth/refresh - Refresh access token using refresh token with rotation
 /// DEV-94/DEV-107: Implements secure token refresh with rotation, device tracking, and rate limiting
 /// Supports both cookie-based (web) and JSON-based (mobile) refresh tokens
+/// Cookie-based refreshes must echo the csrf_token cookie in X-CSRF-Token
+#[utoipa::path(
+    post,
+    path = "/v1/auth/refresh",
+    tag = "Authentication",
+    operation_id = "refreshToken",
+    params(
+        ("X-CSRF-Token" = Option<String>, Header, description = "Value of the csrf_token cookie; required when the refresh token is sent as a cookie")
+    ),
+    request_body(
+        content = Option<RefreshRequest>,
+        description = "Only needed by clients that don't send the refresh_token cookie"
+    ),
+    responses(
+        (status = 200, description = "Tokens rotated; the old refresh token is revoked and a new csrf_token cookie is set", body = TokenApiResponse),
+        (status = 400, description = "Refresh token missing or malformed", body = ApiError),
+        (status = 401, description = "Refresh token invalid, expired or revoked", body = ApiError),
+        (status = 403, description = "Missing or invalid CSRF token, or token reuse or device mismatch detected. On token reuse `incident_id` names the recorded security incident", body = ApiError),
+        (status = 429, description = "Too many refresh attempts", body = ApiError)
+    )
+)]
 pub async fn refresh_token(
     State(state): State<AppState>,
     user_agent: Option<TypedHeader<UserAgent>>,
@@ -910,11 +1200,19 @@ pub async fn refresh_token(
     );
 
     // Extract refresh token from cookie (web) or JSON body (mobile)
-    let refresh_token = match extract_refresh_token(&jar, &body) {
+    let cookie_name = &crate::app_config::config().security.refresh_cookie.name;
+    let refresh_token = match extract_refresh_token(&jar, cookie_name, &body) {
         Ok(token) => token,
         Err(response) => return response,
     };
 
+    // The cookie is sent automatically, so cookie-based refreshes must prove same-origin intent
+    if jar.get(cookie_name).is_some() {
+        if let Err(error) = verify_csrf_token(&jar, &headers) {
+            return (*error).into_response();
+        }
+    }
+
     // Apply rate limiting for refresh endpoint (stricter than normal endpoints) - if enabled
     // Use centralized configuration method
     let config = &crate::app_config::CONFIG;
@@ -928,15 +1226,14 @@ pub async fn refresh_token(
             .await
         {
             Ok(status) if !status.allowed => {
-                let response = AuthResponse::<TokenResponse> {
-                    success: false,
-                    data: None,
-                    message: format!(
-                        "Rate limit exceeded. Try again in {} seconds",
-                        status.retry_after.unwrap_or(60)
-                    ),
-                };
-                return (StatusCode::TOO_MANY_REQUESTS, Json(response)).into_response();
+                let retry_after = status.retry_after.unwrap_or(60);
+                return ApiError::new(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "RATE_LIMITED",
+                    format!("Rate limit exceeded. Try again in {} seconds", retry_after),
+                )
+                .with_retry_after(retry_after as u64)
+                .into_response();
             },
             Err(_) => {
                 // Log but don't block on rate limit errors
@@ -976,47 +1273,133 @@ pub async fn refresh_token(
             let config = crate::app_config::config();
             let refresh_cookie = create_refresh_token_cookie(new_refresh_token, remember_me, &config);
 
-            // Add cookie to response
-            let updated_jar = jar.add(refresh_cookie);
+            // Rotate the CSRF token along with the refresh token
+            let csrf_cookie = create_csrf_cookie(remember_me, config);
+
+            // Add cookies to response
+            let updated_jar = jar.add(refresh_cookie).add(csrf_cookie);
 
             (StatusCode::OK, updated_jar, Json(response)).into_response()
         },
+        Err(JwtError::TokenReuseDetected { incident_id }) => {
+            // The family is already revoked; tell the user without delaying the response
+            if let Some(incident_id) = incident_id {
+                tokio::spawn(notify_token_reuse(state.clone(), incident_id));
+            }
+            ApiError::new(
+                StatusCode::FORBIDDEN,
+                "TOKEN_REUSE_DETECTED",
+                "Security breach detected - all tokens revoked",
+            )
+            .with_incident_id(incident_id)
+            .into_response()
+        },
         Err(e) => {
-            let (status_code, message) = match e {
-                JwtError::TokenExpired => (StatusCode::UNAUTHORIZED, "Refresh token expired"),
-                JwtError::TokenRevoked => (StatusCode::UNAUTHORIZED, "Refresh token revoked"),
-                JwtError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid refresh token"),
-                JwtError::TokenReuseDetected => {
-                    // Security breach - token reuse detected
-                    (
-                        StatusCode::FORBIDDEN,
-                        "Security breach detected - all tokens revoked",
-                    )
-                },
+            let (status_code, code, message) = match e {
+                JwtError::TokenExpired => (
+                    StatusCode::UNAUTHORIZED,
+                    "TOKEN_EXPIRED",
+                    "Refresh token expired",
+                ),
+                JwtError::TokenRevoked => (
+                    StatusCode::UNAUTHORIZED,
+                    "TOKEN_REVOKED",
+                    "Refresh token revoked",
+                ),
+                JwtError::InvalidToken => (
+                    StatusCode::UNAUTHORIZED,
+                    "INVALID_TOKEN",
+                    "Invalid refresh token",
+                ),
                 JwtError::SuspiciousActivity => (
                     StatusCode::FORBIDDEN,
+                    "SUSPICIOUS_ACTIVITY",
                     "Suspicious activity detected - please login again",
                 ),
-                _ => (StatusCode::INTERNAL_SERVER_ERROR, "Token refresh failed"),
+                _ => (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "INTERNAL_ERROR",
+                    "Token refresh failed",
+                ),
             };
 
-            let response = AuthResponse::<TokenResponse> {
-                success: false,
-                data: None,
-                message: message.to_string(),
-            };
-            (status_code, Json(response)).into_response()
+            ApiError::new(status_code, code, message).into_response()
         },
     }
 }
 
+/// Email the owner of a refresh token reuse incident
+async fn notify_token_reuse(state: AppState, incident_id: Uuid) {
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::warn!("Failed to get database connection: {}", e);
+            return;
+        },
+    };
+    let incident = match SecurityIncident::find_by_id(&mut conn, incident_id).await {
+        Ok(incident) => incident,
+        Err(e) => {
+            tracing::warn!("Failed to load security incident {}: {}", incident_id, e);
+            return;
+        },
+    };
+    let user = match User::find_by_id(&mut conn, incident.user_id).await {
+        Ok(user) => user,
+        Err(e) => {
+            tracing::warn!(
+                "Failed to load user for security incident {}: {}",
+                incident_id,
+                e
+            );
+            return;
+        },
+    };
+    drop(conn);
+
+    if let Err(e) = state
+        .email_service
+        .send_token_reuse_alert(&user.email, &user.full_name, &incident)
+        .await
+    {
+        tracing::warn!("Failed to send token reuse alert: {}", e);
+    }
+}
+
 /// POST /auth/logout - Invalidate tokens and logout user
-/// Clears refresh token cookie for web clients
+/// Clears the refresh token and CSRF cookies for web clients
+#[utoipa::path(
+    post,
+    path = "/v1/auth/logout",
+    tag = "Authentication",
+    operation_id = "logout",
+    params(
+        ("X-CSRF-Token" = Option<String>, Header, description = "Value of the csrf_token cookie; required when the refresh token cookie is sent")
+    ),
+    responses(
+        (status = 200, description = "Access token revoked and refresh and CSRF cookies cleared", body = MessageApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Missing or invalid CSRF token", body = ApiError),
+        (status = 500, description = "Token revocation failed", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
 pub async fn logout(
     Extension(user): Extension<AuthenticatedUser>,
     State(state): State<AppState>,
+    headers: HeaderMap,
     jar: CookieJar,
 ) -> impl IntoResponse {
+    // Browsers holding the refresh cookie must send the CSRF header too
+    let config = crate::app_config::config();
+    if jar.get(&config.security.refresh_cookie.name).is_some() {
+        if let Err(error) = verify_csrf_token(&jar, &headers) {
+            return (*error).into_response();
+        }
+    }
+
     // Calculate actual remaining TTL from token's expiration time
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -1031,6 +1414,15 @@ pub async fn logout(
         .logout_token(&user.token_id, remaining_ttl)
         .await
     {
+        Ok(_) if user.is_impersonated() => {
+            // Ending an impersonation session leaves the user's own sessions and cookie alone
+            let response = AuthResponse::<()> {
+                success: true,
+                data: None,
+                message: "Impersonation session ended".to_string(),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        },
         Ok(_) => {
             // Also revoke all user's refresh tokens (best effort)
             match state
@@ -1049,9 +1441,9 @@ pub async fn logout(
                     };
 
                     // Clear refresh token cookie for web clients
-                    let config = crate::app_config::config();
-                    let delete_cookie = create_delete_refresh_cookie(&config);
-                    let updated_jar = jar.add(delete_cookie);
+                    let updated_jar = jar
+                        .add(create_delete_refresh_cookie(config))
+                        .add(create_delete_csrf_cookie(config));
 
                     (StatusCode::OK, updated_jar, Json(response)).into_response()
                 },
@@ -1064,32 +1456,273 @@ pub async fn logout(
                     };
 
                     // Clear refresh token cookie for web clients even if revocation failed
-                    let config = crate::app_config::config();
-                    let delete_cookie = create_delete_refresh_cookie(&config);
-                    let updated_jar = jar.add(delete_cookie);
+                    let updated_jar = jar
+                        .add(create_delete_refresh_cookie(config))
+                        .add(create_delete_csrf_cookie(config));
 
                     (StatusCode::OK, updated_jar, Json(response)).into_response()
                 },
             }
         },
         Err(e) => {
-            let response = AuthResponse::<()> {
-                success: false,
-                data: None,
-                message: format!("Logout failed: {}", e),
-            };
+            tracing::error!("Logout failed: {}", e);
 
             // Still try to clear the cookie even if logout failed
-            let config = crate::app_config::config();
-            let delete_cookie = create_delete_refresh_cookie(&config);
-            let updated_jar = jar.add(delete_cookie);
+            let updated_jar = jar
+                .add(create_delete_refresh_cookie(config))
+                .add(create_delete_csrf_cookie(config));
+
+            (updated_jar, ApiError::internal("Logout failed")).into_response()
+        },
+    }
+}
+
+/// POST /auth/sessions/revoke-all - Sign out of every device
+/// Revokes every refresh token and every access token issued so far, then emails the user.
+/// With `keep_current=true` the requesting session's refresh token lineage survives and the
+/// response carries a fresh access token for it.
+#[utoipa::path(
+    post,
+    path = "/v1/auth/sessions/revoke-all",
+    tag = "Authentication",
+    operation_id = "revokeAllSessions",
+    params(
+        RevokeSessionsQuery,
+        ("X-CSRF-Token" = Option<String>, Header, description = "Value of the csrf_token cookie; required when the refresh token cookie is sent")
+    ),
+    request_body(
+        content = Option<RefreshRequest>,
+        description = "The current session's refresh token, for `keep_current` clients that don't send the refresh_token cookie"
+    ),
+    responses(
+        (status = 200, description = "Sessions revoked. Without `keep_current` the refresh and CSRF cookies are cleared", body = RevokeSessionsApiResponse),
+        (status = 400, description = "`keep_current` without the current refresh token", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token, or the current refresh token is invalid, revoked or another user's", body = ApiError),
+        (status = 403, description = "Missing or invalid CSRF token, or impersonating a user", body = ApiError),
+        (status = 500, description = "Token revocation failed", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn revoke_all_sessions(
+    Extension(user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Query(query): Query<RevokeSessionsQuery>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    body: Bytes,
+) -> impl IntoResponse {
+    use crate::utils::{audit_logger::AuditLogger, create_auth_audit_entry, AuthEventType};
+
+    if let Err(e) = user.forbid_impersonation() {
+        return (*e).into_response();
+    }
+
+    // Browsers holding the refresh cookie must send the CSRF header too
+    let config = crate::app_config::config();
+    let cookie_name = &config.security.refresh_cookie.name;
+    if jar.get(cookie_name).is_some() {
+        if let Err(error) = verify_csrf_token(&jar, &headers) {
+            return (*error).into_response();
+        }
+    }
+
+    let keep_refresh_token = if query.keep_current {
+        match extract_refresh_token(&jar, cookie_name, &body) {
+            Ok(token) => Some(token),
+            Err(_) => {
+                return ApiError::invalid_field(
+                    "refresh_token",
+                    "required",
+                    "keep_current needs the current session's refresh token, as the refresh cookie or `refresh_token` in the body",
+                )
+                .with_status(StatusCode::BAD_REQUEST)
+                .into_response()
+            },
+        }
+    } else {
+        None
+    };
+
+    let revoked_sessions = match state
+        .jwt_service
+        .revoke_all_sessions(&user.user_id, keep_refresh_token.as_deref())
+        .await
+    {
+        Ok(revoked) => revoked,
+        Err(
+            JwtError::InvalidToken
+            | JwtError::TokenExpired
+            | JwtError::TokenRevoked
+            | JwtError::EncodingError(_),
+        ) => {
+            return ApiError::new(
+                StatusCode::UNAUTHORIZED,
+                "INVALID_TOKEN",
+                "The current session's refresh token is invalid or revoked",
+            )
+            .into_response()
+        },
+        Err(e) => {
+            tracing::error!("Failed to revoke sessions for {}: {}", user.user_id, e);
+            return ApiError::internal("Failed to revoke sessions").into_response();
+        },
+    };
+
+    let ip_address = addr.ip().to_string();
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|ua| ua.to_str().ok())
+        .unwrap_or("Unknown device")
+        .to_string();
+    AuditLogger::log_auth_event(&create_auth_audit_entry(
+        AuthEventType::SessionsRevoked,
+        Some(&user.user_id),
+        &user.email,
+        &ip_address,
+        Some(&user_agent),
+        Some(serde_json::json!({
+            "revoked_sessions": revoked_sessions,
+            "kept_current": query.keep_current
+        })),
+    ));
+
+    let db_user = match state.diesel_pool.get().await {
+        Ok(mut conn) => User::find_by_email(&mut conn, &user.email).await.ok(),
+        Err(e) => {
+            tracing::warn!("Failed to get database connection: {}", e);
+            None
+        },
+    };
+
+    // The kept session needs an access token issued after the cutoff
+    let access_token = match (&db_user, query.keep_current) {
+        (Some(db_user), true) => {
+            let scope = PermissionConfig::get_permissions_for_email(&db_user.email);
+            match state.jwt_service.generate_access_token(
+                &db_user.id.to_string(),
+                &db_user.email,
+                db_user.subscription_tier_str(),
+                scope,
+            ) {
+                Ok(token) => Some(token),
+                Err(e) => {
+                    tracing::warn!("Failed to issue access token for kept session: {}", e);
+                    None
+                },
+            }
+        },
+        _ => None,
+    };
+
+    // Email the user without delaying the response
+    if let Some(db_user) = db_user {
+        let email_service = state.email_service.clone();
+        let kept_current = query.keep_current;
+        tokio::spawn(async move {
+            if let Err(e) = email_service
+                .send_sessions_revoked_notification(
+                    &db_user.email,
+                    &db_user.full_name,
+                    revoked_sessions,
+                    kept_current,
+                    &ip_address,
+                    &user_agent,
+                )
+                .await
+            {
+                tracing::warn!("Failed to send sessions revoked notification: {}", e);
+            }
+        });
+    }
+
+    let response = AuthResponse {
+        success: true,
+        data: Some(RevokeSessionsResponse {
+            revoked_sessions,
+            kept_current: query.keep_current,
+            access_token,
+        }),
+        message: format!("{} sessions revoked", revoked_sessions),
+    };
+
+    if query.keep_current {
+        (StatusCode::OK, Json(response)).into_response()
+    } else {
+        // The current session was revoked with the rest
+        let updated_jar = jar
+            .add(create_delete_refresh_cookie(config))
+            .add(create_delete_csrf_cookie(config));
+        (StatusCode::OK, updated_jar, Json(response)).into_response()
+    }
+}
+
+/// GET /auth/security-events - Security incidents on the caller's account
+/// Lists the most recent 50, e.g. refresh tokens replayed after rotation
+#[utoipa::path(
+    get,
+    path = "/v1/auth/security-events",
+    tag = "Authentication",
+    operation_id = "listSecurityEvents",
+    responses(
+        (status = 200, description = "Most recent security incidents, newest first", body = SecurityEventsApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 500, description = "Incident lookup failed", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_security_events(
+    Extension(user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&user.user_id) {
+        Ok(id) => id,
+        Err(_) => return ApiError::unauthorized("Invalid user ID").into_response(),
+    };
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
 
-            (StatusCode::INTERNAL_SERVER_ERROR, updated_jar, Json(response)).into_response()
+    match SecurityIncident::list_recent(&mut conn, Some(user_id), SECURITY_EVENTS_LIMIT).await {
+        Ok(events) => {
+            let response = AuthResponse {
+                success: true,
+                message: format!("{} security events", events.len()),
+                data: Some(SecurityEventsResponse { events }),
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        },
+        Err(e) => {
+            tracing::error!("Failed to list security incidents for {}: {}", user_id, e);
+            ApiError::internal("Failed to fetch security events").into_response()
         },
     }
 }
 
 /// GET /auth/me - Get current user information
+#[utoipa::path(
+    get,
+    path = "/v1/auth/me",
+    tag = "Authentication",
+    operation_id = "getCurrentUser",
+    responses(
+        (status = 200, description = "Current user", body = UserInfoApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 500, description = "User lookup failed", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
 pub async fn get_current_user(
     Extension(user): Extension<AuthenticatedUser>,
     State(state): State<AppState>,
@@ -1099,24 +1732,23 @@ pub async fn get_current_user(
         Ok(conn) => conn,
         Err(e) => {
             tracing::error!("Failed to get database connection: {}", e);
-            let response = AuthResponse::<UserInfo> {
-                success: false,
-                data: None,
-                message: "Database connection error".to_string(),
-            };
-            return (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response();
+            return ApiError::internal("Database connection error").into_response();
         },
     };
 
     // Get user from database to fetch full_name and onboarding_status
     match User::find_by_email(&mut conn, &user.email).await {
         Ok(db_user) => {
+            let onboarding_status = db_user
+                .effective_onboarding_status(state.config.is_oss_deployment)
+                .as_str()
+                .to_string();
             let user_info = UserInfo {
                 user_id: user.user_id,
                 email: user.email,
                 full_name: db_user.full_name,
                 subscription_tier: user.subscription_tier,
-                onboarding_status: db_user.onboarding_status,
+                onboarding_status,
                 permissions: user.permissions,
             };
 
@@ -1129,25 +1761,366 @@ pub async fn get_current_user(
         },
         Err(e) => {
             tracing::error!("Failed to fetch user from database: {}", e);
-            let response = AuthResponse::<UserInfo> {
-                success: false,
-                data: None,
-                message: "Failed to fetch user information".to_string(),
+            ApiError::internal("Failed to fetch user information").into_response()
+        },
+    }
+}
+
+/// GET /auth/me/preferences - Get account-wide link defaults
+#[utoipa::path(
+    get,
+    path = "/v1/auth/me/preferences",
+    tag = "Authentication",
+    operation_id = "getPreferences",
+    responses(
+        (status = 200, description = "Current preferences", body = UserPreferencesApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 500, description = "User lookup failed", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_preferences(
+    Extension(user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+) -> impl IntoResponse {
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
+
+    match User::find_by_email(&mut conn, &user.email).await {
+        Ok(db_user) => preferences_response(&db_user, "Preferences retrieved successfully"),
+        Err(e) => {
+            tracing::error!("Failed to fetch user from database: {}", e);
+            ApiError::internal("Failed to fetch preferences").into_response()
+        },
+    }
+}
+
+/// PUT /auth/me/preferences - Update account-wide link defaults
+/// Existing links keep their own tracking mode
+#[utoipa::path(
+    put,
+    path = "/v1/auth/me/preferences",
+    tag = "Authentication",
+    operation_id = "updatePreferences",
+    request_body = UserPreferences,
+    responses(
+        (status = 200, description = "Preferences updated", body = UserPreferencesApiResponse),
+        (status = 400, description = "Malformed request body, unknown timezone or invalid query parameter name", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 500, description = "Preferences could not be saved", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_preferences(
+    Extension(user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Json(request): Json<UserPreferences>,
+) -> impl IntoResponse {
+    if let Some(ref timezone) = request.timezone {
+        if let Err(message) = parse_timezone(timezone) {
+            return ApiError::invalid_field("timezone", "invalid_timezone", message)
+                .with_status(StatusCode::BAD_REQUEST)
+                .into_response();
+        }
+    }
+
+    let strip_query_params = match request
+        .strip_query_params
+        .as_deref()
+        .map(parse_strip_query_params)
+        .transpose()
+    {
+        Ok(params) => params,
+        Err(message) => {
+            return ApiError::invalid_field("strip_query_params", "invalid_query_param", message)
+                .with_status(StatusCode::BAD_REQUEST)
+                .into_response();
+        },
+    };
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
+
+    let db_user = match User::find_by_email(&mut conn, &user.email).await {
+        Ok(db_user) => db_user,
+        Err(e) => {
+            tracing::error!("Failed to fetch user from database: {}", e);
+            return ApiError::internal("Failed to update preferences").into_response();
+        },
+    };
+
+    // Keep the monthly_summary notification preference in step with the digest flag
+    let mut notifications = db_user.notification_preferences_typed();
+    notifications.set(NotificationKind::MonthlySummary, request.monthly_digest_enabled);
+
+    let update = UserUpdate {
+        email: None,
+        password_hash: None,
+        email_verified: None,
+        email_verified_at: None,
+        subscription_tier: None,
+        is_active: None,
+        full_name: None,
+        company_name: None,
+        onboarding_status: None,
+        default_tracking_mode: Some(request.default_tracking_mode.as_str().to_string()),
+        monthly_digest_enabled: Some(request.monthly_digest_enabled),
+        notification_preferences: Some(notifications.to_value()),
+        timezone: request.timezone,
+        strip_query_params,
+    };
+
+    match User::update(&mut conn, db_user.id, update).await {
+        Ok(updated) => preferences_response(&updated, "Preferences updated successfully"),
+        Err(e) => {
+            tracing::error!("Failed to update preferences for {}: {}", db_user.id, e);
+            ApiError::internal("Failed to update preferences").into_response()
+        },
+    }
+}
+
+fn preferences_response(user: &User, message: &str) -> Response {
+    let response = AuthResponse {
+        success: true,
+        data: Some(UserPreferences {
+            default_tracking_mode: TrackingMode::from(user.default_tracking_mode.as_str()),
+            monthly_digest_enabled: user.monthly_digest_enabled,
+            timezone: Some(user.timezone.clone()),
+            strip_query_params: Some(user.query_params_to_strip()),
+        }),
+        message: message.to_string(),
+    };
+    (StatusCode::OK, Json(response)).into_response()
+}
+
+/// GET /auth/me/export - Export the account's profile and links (data portability)
+/// The archive is built in the background and a download link is emailed when it is ready
+#[utoipa::path(
+    get,
+    path = "/v1/auth/me/export",
+    tag = "Authentication",
+    operation_id = "exportAccount",
+    params(AccountExportQuery),
+    responses(
+        (status = 202, description = "Export queued; the download link will be emailed", body = AccountExportApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Not allowed while impersonating a user", body = ApiError),
+        (status = 409, description = "An export is already being prepared", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn export_account(
+    Extension(user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Query(query): Query<AccountExportQuery>,
+) -> impl IntoResponse {
+    if let Err(e) = user.forbid_impersonation() {
+        return (*e).into_response();
+    }
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to get database connection: {}", e);
+            return ApiError::internal("Database connection error").into_response();
+        },
+    };
+
+    let db_user = match User::find_by_email(&mut conn, &user.email).await {
+        Ok(db_user) => db_user,
+        Err(e) => {
+            tracing::error!("Failed to fetch user from database: {}", e);
+            return ApiError::internal("Failed to fetch user information").into_response();
+        },
+    };
+    drop(conn);
+
+    match AccountExportService::new(&state)
+        .request_export(&db_user, query.include_stats)
+        .await
+    {
+        Ok(export) => {
+            let response = AuthResponse {
+                success: true,
+                data: Some(AccountExportResponse::from(export)),
+                message: "Export started. We'll email you a download link when it's ready"
+                    .to_string(),
             };
-            (StatusCode::INTERNAL_SERVER_ERROR, Json(response)).into_response()
+            (StatusCode::ACCEPTED, Json(response)).into_response()
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// GET /auth/export/download - Download a finished account export
+/// Authenticated by the token from the "export ready" email; valid until the export expires
+#[utoipa::path(
+    get,
+    path = "/v1/auth/export/download",
+    tag = "Authentication",
+    operation_id = "downloadAccountExport",
+    params(AccountExportDownloadQuery),
+    responses(
+        (status = 200, description = "Account archive (JSON attachment)", body = AccountArchive),
+        (status = 404, description = "Unknown or expired download token", body = ApiError)
+    )
+)]
+pub async fn download_account_export(
+    State(state): State<AppState>,
+    Query(query): Query<AccountExportDownloadQuery>,
+) -> impl IntoResponse {
+    match AccountExportService::new(&state).download(&query.token).await {
+        Ok(archive) => (
+            [
+                (
+                    header::CONTENT_DISPOSITION,
+                    "attachment; filename=\"account-export.json\"",
+                ),
+                (header::CACHE_CONTROL, "no-store"),
+            ],
+            Json(archive),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// GET /auth/unlock - Unlock an account with the link from the lockout email
+/// Tokens are single-use; attempts are rate limited per IP even when global rate limiting is off
+#[utoipa::path(
+    get,
+    path = "/v1/auth/unlock",
+    tag = "Authentication",
+    operation_id = "unlockAccount",
+    params(AccountUnlockQuery),
+    responses(
+        (status = 200, description = "Lockout and failed login count cleared", body = MessageApiResponse),
+        (status = 400, description = "Unknown, expired or already used unlock token", body = ApiError),
+        (status = 404, description = "Unlock emails are disabled on this instance", body = ApiError),
+        (status = 429, description = "Too many unlock attempts", body = ApiError)
+    )
+)]
+pub async fn unlock_account(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    user_agent: Option<TypedHeader<UserAgent>>,
+    Query(query): Query<AccountUnlockQuery>,
+) -> impl IntoResponse {
+    use crate::utils::{audit_logger::AuditLogger, create_auth_audit_entry, AuthEventType};
+
+    let config = crate::app_config::config();
+    if !config.security.account_unlock_email_enabled {
+        return ApiError::not_found("Account unlock is not enabled").into_response();
+    }
+
+    let ip_address = addr.ip().to_string();
+    let rate_key = format!("unlock:ip:{}", ip_address);
+    let rate_config = RateLimitConfig {
+        max_requests: config.security.account_unlock_rate_limit_per_ip,
+        window_seconds: 60,
+        burst_limit: Some(config.security.account_unlock_rate_limit_per_ip),
+        block_duration: 300,
+        distributed: true,
+    };
+
+    match state
+        .rate_limit_service
+        .check_rate_limit_with_config(&rate_key, &rate_config)
+        .await
+    {
+        Ok(status) if !status.allowed => {
+            let retry_after = status.retry_after.unwrap_or(300);
+            tracing::warn!("Unlock rate limit exceeded for IP: {}", ip_address);
+            return ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "RATE_LIMITED",
+                format!(
+                    "Too many unlock attempts. Try again in {} seconds",
+                    retry_after
+                ),
+            )
+            .with_retry_after(retry_after as u64)
+            .into_response();
         },
+        Err(e) => {
+            tracing::warn!("Rate limit check failed for unlock endpoint: {}", e);
+        },
+        _ => {},
     }
+
+    let grant = match AccountUnlockService::new(&state).redeem(&query.token).await {
+        Ok(Some(grant)) => grant,
+        Ok(None) => {
+            tracing::warn!("Invalid or expired unlock token from IP: {}", ip_address);
+            return ApiError::new(
+                StatusCode::BAD_REQUEST,
+                "INVALID_TOKEN",
+                "Invalid or expired unlock link",
+            )
+            .into_response();
+        },
+        Err(e) => return e.into_response(),
+    };
+
+    clear_failed_login_attempts(&state, &grant.email).await;
+
+    let user_agent = user_agent.map(|TypedHeader(ua)| ua.to_string());
+    let audit = create_auth_audit_entry(
+        AuthEventType::AccountUnlocked,
+        Some(&grant.user_id.to_string()),
+        &grant.email,
+        &ip_address,
+        user_agent.as_deref(),
+        Some(serde_json::json!({ "method": "email_link" })),
+    );
+    AuditLogger::log_auth_event(&audit);
+
+    let response = AuthResponse::<()> {
+        success: true,
+        data: None,
+        message: "Account unlocked. You can sign in again".to_string(),
+    };
+    (StatusCode::OK, Json(response)).into_response()
 }
 
 /// POST /auth/validate - Validate current access token (for client-side checks)
+#[utoipa::path(
+    post,
+    path = "/v1/auth/validate",
+    tag = "Authentication",
+    operation_id = "validateToken",
+    responses(
+        (status = 200, description = "Token is valid", body = TokenValidationApiResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
 pub async fn validate_token(Extension(user): Extension<AuthenticatedUser>) -> impl IntoResponse {
     let response = AuthResponse {
         success: true,
-        data: Some(serde_json::json!({
-            "valid": true,
-            "user_id": user.user_id,
-            "subscription_tier": user.subscription_tier
-        })),
+        data: Some(TokenValidation {
+            valid: true,
+            user_id: user.user_id,
+            subscription_tier: user.subscription_tier,
+        }),
         message: "Token is valid".to_string(),
     };
     Json(response)
@@ -1157,18 +2130,18 @@ pub async fn validate_token(Extension(user): Extension<AuthenticatedUser>) -> im
 // EMAIL VERIFICATION ENDPOINTS (DEV-103)
 // =============================================================================
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct VerifyEmailRequest {
     pub email: String,
     pub code: String,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, ToSchema)]
 pub struct ResendVerificationRequest {
     pub email: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct VerificationStatusResponse {
     pub email_verified: bool,
     pub resend_allowed: bool,
@@ -1176,8 +2149,21 @@ pub struct VerificationStatusResponse {
     pub message: String,
 }
 
-/// POST /auth/verify-email - Verify email with 6-digit code
+/// Send a password reset link
 /// POST /auth/forgot-password
+#[utoipa::path(
+    post,
+    path = "/v1/auth/forgot-password",
+    tag = "Authentication",
+    operation_id = "forgotPassword",
+    request_body = ForgotPasswordRequest,
+    responses(
+        (status = 200, description = "Reset email sent if the account exists (the response never reveals which)", body = ForgotPasswordResponse),
+        (status = 400, description = "Invalid email", body = ApiError),
+        (status = 429, description = "Too many reset requests", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    )
+)]
 pub async fn forgot_password(
     State(app_state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
@@ -1186,44 +2172,17 @@ pub async fn forgot_password(
 ) -> impl IntoResponse {
     // Validate input
     if let Err(validation_errors) = payload.validate() {
-        let error_msg = validation_errors
-            .field_errors()
-            .iter()
-            .map(|(field, errors)| {
-                format!(
-                    "{}: {}",
-                    field,
-                    errors[0]
-                        .message
-                        .as_ref()
-                        .unwrap_or(&"Invalid input".into())
-                )
-            })
-            .collect::<Vec<String>>()
-            .join(", ");
-
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ForgotPasswordResponse {
-                success: false,
-                message: format!("Validation error: {}", error_msg),
-                data: None,
-            }),
-        )
+        return ApiError::from(validation_errors)
+            .with_field_summary()
+            .with_status(StatusCode::BAD_REQUEST)
             .into_response();
     }
 
     let email = match trim_and_validate_field(&payload.email, true) {
         Ok(email) => email.to_lowercase(),
         Err(e) => {
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(ForgotPasswordResponse {
-                    success: false,
-                    message: format!("Validation error: {}", e),
-                    data: None,
-                }),
-            )
+            return ApiError::invalid_field("email", "required", e.to_string())
+                .with_status(StatusCode::BAD_REQUEST)
                 .into_response();
         },
     };
@@ -1246,29 +2205,17 @@ pub async fn forgot_password(
                         "Rate limit exceeded for forgot password from IP: {}",
                         client_ip
                     );
-                    return (
+                    return ApiError::new(
                         StatusCode::TOO_MANY_REQUESTS,
-                        Json(ForgotPasswordResponse {
-                            success: false,
-                            message: "Too many password reset attempts. Please try again later."
-                                .to_string(),
-                            data: None,
-                        }),
+                        "RATE_LIMITED",
+                        "Too many password reset attempts. Please try again later.",
                     )
-                        .into_response();
+                    .into_response();
                 }
             },
             Err(e) => {
                 tracing::error!("Rate limiting service error: {}", e);
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ForgotPasswordResponse {
-                        success: false,
-                        message: "Service temporarily unavailable".to_string(),
-                        data: None,
-                    }),
-                )
-                    .into_response();
+                return ApiError::internal("Service temporarily unavailable").into_response();
             },
         }
     }
@@ -1286,30 +2233,17 @@ pub async fn forgot_password(
                 "Too many recent password reset attempts for email: {}",
                 email
             );
-            return (
+            return ApiError::new(
                 StatusCode::TOO_MANY_REQUESTS,
-                Json(ForgotPasswordResponse {
-                    success: false,
-                    message:
-                        "Too many password reset attempts for this account. Please try again later."
-                            .to_string(),
-                    data: None,
-                }),
+                "RATE_LIMITED",
+                "Too many password reset attempts for this account. Please try again later.",
             )
-                .into_response();
+            .into_response();
         },
         Ok(_) => {}, // Continue
         Err(e) => {
             tracing::error!("Failed to check recent attempts: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ForgotPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response();
+            return ApiError::internal("Service temporarily unavailable").into_response();
         },
     }
 
@@ -1362,66 +2296,46 @@ pub async fn forgot_password(
         },
         Err(e) => {
             tracing::error!("Failed to create password reset request: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ForgotPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response()
+            ApiError::internal("Service temporarily unavailable").into_response()
         },
     }
 }
 
 /// Handle password reset with token
 /// POST /auth/reset-password
+#[utoipa::path(
+    post,
+    path = "/v1/auth/reset-password",
+    tag = "Authentication",
+    operation_id = "resetPassword",
+    request_body = ResetPasswordRequest,
+    responses(
+        (status = 200, description = "Password changed; existing sessions are revoked", body = ResetPasswordResponse),
+        (status = 400, description = "Invalid, expired or used token, or password validation failed", body = ApiError),
+        (status = 429, description = "Too many reset attempts", body = ApiError),
+        (status = 500, description = "Internal server error", body = ApiError)
+    )
+)]
 pub async fn reset_password(
     State(app_state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Json(payload): Json<ResetPasswordRequest>,
 ) -> impl IntoResponse {
-    // Validate input
-    if let Err(validation_errors) = payload.validate() {
-        let error_msg = validation_errors
-            .field_errors()
-            .iter()
-            .map(|(field, errors)| {
-                format!(
-                    "{}: {}",
-                    field,
-                    errors[0]
-                        .message
-                        .as_ref()
-                        .unwrap_or(&"Invalid input".into())
-                )
-            })
-            .collect::<Vec<String>>()
-            .join(", ");
-
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ResetPasswordResponse {
-                success: false,
-                message: format!("Validation error: {}", error_msg),
-                data: None,
-            }),
-        )
+    // Validate input, including password strength, before the token is consumed
+    if let Err(validation_errors) =
+        validate_with_password_policy(&payload, "new_password", &payload.new_password)
+    {
+        return ApiError::from(validation_errors)
+            .with_field_summary()
+            .with_status(StatusCode::BAD_REQUEST)
             .into_response();
     }
 
     // Validate that passwords match
     if let Err(e) = payload.validate_passwords_match() {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ResetPasswordResponse {
-                success: false,
-                message: format!("Validation error: {}", e),
-                data: None,
-            }),
-        )
+        return ApiError::invalid_field("confirm_password", "must_match", e)
+            .with_status(StatusCode::BAD_REQUEST)
             .into_response();
     }
 
@@ -1443,29 +2357,17 @@ pub async fn reset_password(
                         "Rate limit exceeded for password reset from IP: {}",
                         client_ip
                     );
-                    return (
+                    return ApiError::new(
                         StatusCode::TOO_MANY_REQUESTS,
-                        Json(ResetPasswordResponse {
-                            success: false,
-                            message: "Too many password reset attempts. Please try again later."
-                                .to_string(),
-                            data: None,
-                        }),
+                        "RATE_LIMITED",
+                        "Too many password reset attempts. Please try again later.",
                     )
-                        .into_response();
+                    .into_response();
                 }
             },
             Err(e) => {
                 tracing::error!("Rate limiting service error: {}", e);
-                return (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ResetPasswordResponse {
-                        success: false,
-                        message: "Service temporarily unavailable".to_string(),
-                        data: None,
-                    }),
-                )
-                    .into_response();
+                return ApiError::internal("Service temporarily unavailable").into_response();
             },
         }
     }
@@ -1484,57 +2386,25 @@ pub async fn reset_password(
                 "Invalid or expired password reset token from IP: {}",
                 client_ip
             );
-            return (
+            return ApiError::new(
                 StatusCode::BAD_REQUEST,
-                Json(ResetPasswordResponse {
-                    success: false,
-                    message: "Invalid or expired reset token".to_string(),
-                    data: None,
-                }),
+                "INVALID_TOKEN",
+                "Invalid or expired reset token",
             )
-                .into_response();
+            .into_response();
         },
         Err(e) => {
             tracing::error!("Failed to validate reset token: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ResetPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response();
+            return ApiError::internal("Service temporarily unavailable").into_response();
         },
     };
 
-    // Validate password strength (reuse existing validation)
-    if let Err(e) = validate_password(&payload.new_password) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ResetPasswordResponse {
-                success: false,
-                message: format!("Password validation failed: {}", e),
-                data: None,
-            }),
-        )
-            .into_response();
-    }
-
     // Hash the new password
     let password_hash = match hash_password(&payload.new_password) {
         Ok(hash) => hash,
         Err(e) => {
             tracing::error!("Failed to hash password: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ResetPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response();
+            return ApiError::internal("Service temporarily unavailable").into_response();
         },
     };
 
@@ -1547,15 +2417,7 @@ pub async fn reset_password(
         Ok(conn) => conn,
         Err(e) => {
             tracing::error!("Failed to get database connection: {}", e);
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ResetPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response();
+            return ApiError::internal("Service temporarily unavailable").into_response();
         },
     };
 
@@ -1635,15 +2497,7 @@ pub async fn reset_password(
         },
         Err(e) => {
             tracing::error!("Failed to update user password: {}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ResetPasswordResponse {
-                    success: false,
-                    message: "Service temporarily unavailable".to_string(),
-                    data: None,
-                }),
-            )
-                .into_response()
+            ApiError::internal("Service temporarily unavailable").into_response()
         },
     }
 }
@@ -1682,13 +2536,144 @@ mod tests {
         }
     }
 
+    fn refresh_cookie_config(same_site: CookieSameSite, domain: &str) -> RefreshCookieConfig {
+        RefreshCookieConfig {
+            name: "refresh_token".to_string(),
+            domain: domain.to_string(),
+            same_site,
+            secure: same_site == CookieSameSite::None,
+        }
+    }
+
+    #[test]
+    fn test_refresh_cookie_same_site_policies() {
+        for (configured, expected, secure) in [
+            (CookieSameSite::Strict, SameSite::Strict, false),
+            (CookieSameSite::Lax, SameSite::Lax, false),
+            (CookieSameSite::None, SameSite::None, true),
+        ] {
+            let cookie = build_refresh_cookie(
+                "header.payload.signature".to_string(),
+                &refresh_cookie_config(configured, ""),
+            );
+            assert_eq!(cookie.same_site(), Some(expected));
+            assert_eq!(cookie.secure(), Some(secure));
+            assert_eq!(cookie.http_only(), Some(true));
+            assert_eq!(cookie.path(), Some("/"));
+            assert_eq!(cookie.domain(), None);
+        }
+    }
+
+    #[test]
+    fn test_refresh_cookie_domain_and_name() {
+        let mut config = refresh_cookie_config(CookieSameSite::Lax, "example.com");
+        config.name = "qck_refresh".to_string();
+
+        let cookie = build_refresh_cookie("header.payload.signature".to_string(), &config);
+        assert_eq!(cookie.name(), "qck_refresh");
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    }
+
+    #[test]
+    fn test_csrf_cookie_is_readable_by_scripts() {
+        let config = refresh_cookie_config(CookieSameSite::Lax, "example.com");
+        let cookie = build_csrf_cookie("csrf-value".to_string(), &config);
+
+        assert_eq!(cookie.name(), CSRF_COOKIE_NAME);
+        assert_eq!(cookie.http_only(), Some(false));
+        assert_eq!(cookie.domain(), Some("example.com"));
+        assert_eq!(cookie.same_site(), Some(SameSite::Lax));
+    }
+
+    #[test]
+    fn test_verify_csrf_token() {
+        let jar = CookieJar::new().add((CSRF_COOKIE_NAME, "csrf-value"));
+        let mut headers = HeaderMap::new();
+        assert!(verify_csrf_token(&jar, &headers).is_err());
+
+        headers.insert(CSRF_HEADER, "other-value".parse().unwrap());
+        assert!(verify_csrf_token(&jar, &headers).is_err());
+
+        headers.insert(CSRF_HEADER, "csrf-value".parse().unwrap());
+        assert!(verify_csrf_token(&jar, &headers).is_ok());
+
+        // A header alone proves nothing without the cookie to compare against
+        assert!(verify_csrf_token(&CookieJar::new(), &headers).is_err());
+    }
+
+    #[test]
+    fn test_password_policy_reports_each_unmet_rule() {
+        let codes = |password: &str| -> Vec<String> {
+            password_policy_errors(password)
+                .iter()
+                .map(|e| e.code.to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            codes("weak"),
+            vec![
+                "too_short",
+                "missing_uppercase",
+                "missing_digit",
+                "missing_special"
+            ]
+        );
+        assert_eq!(codes("Longenough1"), vec!["missing_special"]);
+        assert!(codes("SecureP@ssw0rd123!").is_empty());
+    }
+
+    #[test]
+    fn test_register_validation_details() {
+        let request = RegisterRequest {
+            email: "not-an-email".to_string(),
+            password: "password1!".to_string(),
+            password_confirmation: "password1!".to_string(),
+            full_name: "Jane Doe".to_string(),
+            company_name: None,
+            accept_terms: true,
+        };
+        let error = ApiError::from(
+            validate_with_password_policy(&request, "password", &request.password).unwrap_err(),
+        )
+        .with_field_summary();
+
+        let details: Vec<(&str, &str)> = error
+            .details
+            .iter()
+            .map(|d| (d.field.as_str(), d.code.as_str()))
+            .collect();
+        assert_eq!(
+            details,
+            vec![("email", "email"), ("password", "missing_uppercase")]
+        );
+        assert_eq!(
+            error.message,
+            "email: Invalid email format, password: Password must contain an uppercase letter"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_refresh_token_from_custom_cookie_name() {
+        let jar = CookieJar::new().add(("qck_refresh", "header.payload.signature"));
+        let body = Bytes::new();
+
+        let result = extract_refresh_token(&jar, "qck_refresh", &body);
+        assert_eq!(result.unwrap(), "header.payload.signature");
+
+        // The default name is not read once a custom one is configured
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
+        assert!(result.is_err());
+    }
+
     #[tokio::test]
     async fn test_extract_refresh_token_from_cookie() {
         let jar = CookieJar::new();
         let jar_with_cookie = jar.add(("refresh_token", "header.payload.signature"));
         let body = Bytes::new();
 
-        let result = extract_refresh_token(&jar_with_cookie, &body);
+        let result = extract_refresh_token(&jar_with_cookie, "refresh_token", &body);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "header.payload.signature");
     }
@@ -1699,7 +2684,7 @@ mod tests {
         let token_json = json!({"refresh_token": "mobile.jwt.token"});
         let body = Bytes::from(token_json.to_string());
 
-        let result = extract_refresh_token(&jar, &body);
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "mobile.jwt.token");
     }
@@ -1709,7 +2694,7 @@ mod tests {
         let jar = CookieJar::new();
         let body = Bytes::new();
 
-        let result = extract_refresh_token(&jar, &body);
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1718,7 +2703,7 @@ mod tests {
         let jar = CookieJar::new();
         let body = Bytes::from("{invalid json");
 
-        let result = extract_refresh_token(&jar, &body);
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1728,7 +2713,7 @@ mod tests {
         let token_json = json!({"other_field": "value"});
         let body = Bytes::from(token_json.to_string());
 
-        let result = extract_refresh_token(&jar, &body);
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1739,7 +2724,7 @@ mod tests {
         let token_json = json!({"refresh_token": "json.jwt.token"});
         let body = Bytes::from(token_json.to_string());
 
-        let result = extract_refresh_token(&jar_with_cookie, &body);
+        let result = extract_refresh_token(&jar_with_cookie, "refresh_token", &body);
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), "cookie.jwt.token"); // Cookie should take priority
     }
@@ -1751,7 +2736,7 @@ mod tests {
         let jar_with_cookie = jar.add(("refresh_token", "invalid.token"));
         let body = Bytes::new();
 
-        let result = extract_refresh_token(&jar_with_cookie, &body);
+        let result = extract_refresh_token(&jar_with_cookie, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1762,7 +2747,7 @@ mod tests {
         let token_json = json!({"refresh_token": "invalidtoken"});
         let body = Bytes::from(token_json.to_string());
 
-        let result = extract_refresh_token(&jar, &body);
+        let result = extract_refresh_token(&jar, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1773,7 +2758,7 @@ mod tests {
         let jar_with_cookie = jar.add(("refresh_token", "too.many.parts.here"));
         let body = Bytes::new();
 
-        let result = extract_refresh_token(&jar_with_cookie, &body);
+        let result = extract_refresh_token(&jar_with_cookie, "refresh_token", &body);
         assert!(result.is_err());
     }
 
@@ -1838,6 +2823,7 @@ mod tests {
             subscription_tier: "pro".to_string(),
             permissions: vec!["pro".to_string(), "personal".to_string()],
             exp: future_exp,
+            impersonator_id: None,
         };
 
         let user_info = UserInfo::from(auth_user);
@@ -16,91 +16,19 @@ pub fn health_endpoint() -> serde_json::Value {
                     "content": {
                         "application/json": {
                             "schema": {
-                                "type": "object",
-                                "properties": {
-                                    "status": {
-                                        "type": "string",
-                                        "enum": ["healthy", "degraded"],
-                                        "description": "Overall health status"
-                                    },
-                                    "service": {
-                                        "type": "string",
-                                        "description": "Service name"
-                                    },
-                                    "timestamp": {
-                                        "type": "string",
-                                        "format": "date-time",
-                                        "description": "Health check timestamp"
-                                    },
-                                    "components": {
-                                        "type": "object",
-                                        "properties": {
-                                            "postgresql": {
-                                                "type": "object",
-                                                "properties": {
-                                                    "status": {
-                                                        "type": "string",
-                                                        "enum": ["healthy", "unhealthy"]
-                                                    },
-                                                    "max_connections": {
-                                                        "type": "integer",
-                                                        "nullable": true
-                                                    },
-                                                    "error": {
-                                                        "type": "string",
-                                                        "nullable": true
-                                                    }
-                                                }
-                                            },
-                                            "redis": {
-                                                "type": "object",
-                                                "properties": {
-                                                    "status": {
-                                                        "type": "string",
-                                                        "enum": ["healthy", "unhealthy"]
-                                                    },
-                                                    "latency_ms": {
-                                                        "type": "number",
-                                                        "nullable": true
-                                                    },
-                                                    "active_connections": {
-                                                        "type": "integer",
-                                                        "nullable": true
-                                                    },
-                                                    "total_connections": {
-                                                        "type": "integer",
-                                                        "nullable": true
-                                                    },
-                                                    "error": {
-                                                        "type": "string",
-                                                        "nullable": true
-                                                    }
-                                                }
-                                            },
-                                            "clickhouse": {
-                                                "type": "object",
-                                                "properties": {
-                                                    "status": {
-                                                        "type": "string",
-                                                        "enum": ["healthy", "unhealthy"]
-                                                    },
-                                                    "latency_ms": {
-                                                        "type": "integer",
-                                                        "nullable": true
-                                                    },
-                                                    "error": {
-                                                        "type": "string",
-                                                        "nullable": true
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
+                                "$ref": "#/components/schemas/HealthResponse"
                             }
                         }
                     }
                 },
+                "403": {
+                    "description": "Client IP is on the denylist",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/ApiError" }
+                        }
+                    }
+                },
                 "503": {
                     "description": "Service is degraded",
                     "content": {
@@ -115,3 +43,353 @@ pub fn health_endpoint() -> serde_json::Value {
         }
     })
 }
+
+/// Rate limiting metrics endpoint documentation
+pub fn rate_limit_metrics_endpoint() -> serde_json::Value {
+    json!({
+        "get": {
+            "tags": ["Health"],
+            "summary": "Rate limiting metrics",
+            "description": "Returns rate limiting analytics for the requested window, real-time monitoring stats, the most-blocked keys per endpoint and the emergency-mode status. metrics is null when analytics sampling is disabled. Windows reaching back before this instance's counters started are served from the sampled events in ClickHouse, when configured; metrics.backend tells which backend served the numbers. Requires an access token with the instance admin scope, or the static METRICS_TOKEN as the bearer token.",
+            "operationId": "rateLimitMetrics",
+            "parameters": [
+                {
+                    "name": "window_minutes",
+                    "in": "query",
+                    "required": false,
+                    "description": "Minutes up to now, 1 to 129600 (90 days); defaults to 60",
+                    "schema": { "type": "integer", "minimum": 1, "maximum": 129600, "default": 60 }
+                }
+            ],
+            "security": [{ "bearerAuth": [] }],
+            "responses": {
+                "200": {
+                    "description": "Current rate limiting metrics",
+                    "content": {
+                        "application/json": {
+                            "schema": {
+                                "$ref": "#/components/schemas/RateLimitMetricsResponse"
+                            }
+                        }
+                    }
+                },
+                "401": {
+                    "description": "Missing or invalid token",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/ApiError" }
+                        }
+                    }
+                },
+                "403": {
+                    "description": "Token lacks the instance admin scope",
+                    "content": {
+                        "application/json": {
+                            "schema": { "$ref": "#/components/schemas/ApiError" }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
+
+/// Schemas for the health and metrics endpoints
+pub fn health_schemas() -> serde_json::Value {
+    json!({
+        "HealthResponse": {
+            "type": "object",
+            "properties": {
+                "status": {
+                    "type": "string",
+                    "enum": ["healthy", "degraded"],
+                    "description": "Overall health status"
+                },
+                "service": {
+                    "type": "string",
+                    "description": "Service name"
+                },
+                "timestamp": {
+                    "type": "string",
+                    "format": "date-time",
+                    "description": "Health check timestamp"
+                },
+                "latency": {
+                    "type": "object",
+                    "description": "Request latency percentiles on this instance; see GET /v1/admin/perf for every route group",
+                    "properties": {
+                        "window_seconds": { "type": "integer" },
+                        "redirect": { "$ref": "#/components/schemas/LatencySummary" },
+                        "api": { "$ref": "#/components/schemas/LatencySummary" }
+                    }
+                },
+                "components": {
+                    "type": "object",
+                    "properties": {
+                        "postgresql": {
+                            "type": "object",
+                            "properties": {
+                                "status": {
+                                    "type": "string",
+                                    "enum": ["healthy", "unhealthy"]
+                                },
+                                "max_connections": {
+                                    "type": "integer",
+                                    "nullable": true
+                                },
+                                "error": {
+                                    "type": "string",
+                                    "nullable": true
+                                },
+                                "checked_at": {
+                                    "type": "string",
+                                    "format": "date-time"
+                                }
+                            }
+                        },
+                        "redis": {
+                            "type": "object",
+                            "properties": {
+                                "status": {
+                                    "type": "string",
+                                    "enum": ["healthy", "unhealthy"]
+                                },
+                                "latency_ms": {
+                                    "type": "number",
+                                    "nullable": true
+                                },
+                                "active_connections": {
+                                    "type": "integer",
+                                    "nullable": true
+                                },
+                                "total_connections": {
+                                    "type": "integer",
+                                    "nullable": true
+                                },
+                                "error": {
+                                    "type": "string",
+                                    "nullable": true
+                                },
+                                "checked_at": {
+                                    "type": "string",
+                                    "format": "date-time"
+                                }
+                            }
+                        },
+                        "clickhouse": {
+                            "description": "Last background probe (HEALTH_PROBE_CLICKHOUSE_*); only unhealthy fails the health check",
+                            "allOf": [{ "$ref": "#/components/schemas/ProbedComponentHealth" }]
+                        },
+                        "email": {
+                            "description": "Last background probe of the email provider (HEALTH_PROBE_EMAIL_*); does not fail the health check",
+                            "allOf": [{ "$ref": "#/components/schemas/ProbedComponentHealth" }]
+                        },
+                        "background_tasks": {
+                            "type": "object",
+                            "description": "Degraded while any task is restarting or its last run failed; does not fail the health check",
+                            "properties": {
+                                "status": {
+                                    "type": "string",
+                                    "enum": ["healthy", "degraded"]
+                                },
+                                "tasks": {
+                                    "type": "array",
+                                    "items": { "$ref": "#/components/schemas/TaskStatus" }
+                                }
+                            }
+                        },
+                        "threat_feed": {
+                            "type": "object",
+                            "description": "Age of the URLhaus threat feed; stale past URLHAUS_STALE_AFTER_HOURS, which does not fail the health check",
+                            "properties": {
+                                "status": {
+                                    "type": "string",
+                                    "enum": ["healthy", "stale", "disabled", "unknown"]
+                                },
+                                "last_refresh_at": {
+                                    "type": "string",
+                                    "format": "date-time",
+                                    "nullable": true
+                                },
+                                "age_seconds": {
+                                    "type": "integer",
+                                    "nullable": true
+                                },
+                                "stale_after_seconds": {
+                                    "type": "integer"
+                                },
+                                "error": {
+                                    "type": "string",
+                                    "nullable": true
+                                }
+                            }
+                        },
+                        "blocked_domains": {
+                            "type": "object",
+                            "description": "Where the blocked domains list used by URL validation came from; fallback when only the small built-in list is available, which does not fail the health check",
+                            "properties": {
+                                "status": {
+                                    "type": "string",
+                                    "enum": ["healthy", "fallback", "unknown"]
+                                },
+                                "source": {
+                                    "type": "string",
+                                    "enum": ["inline", "url", "file", "fallback"],
+                                    "nullable": true
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        },
+        "ProbedComponentHealth": {
+            "type": "object",
+            "description": "A dependency probed in the background rather than per request. Stale once the result is more than three probe intervals old.",
+            "properties": {
+                "status": {
+                    "type": "string",
+                    "enum": ["healthy", "unhealthy", "stale", "unknown", "disabled"]
+                },
+                "latency_ms": {
+                    "type": "integer",
+                    "nullable": true
+                },
+                "error": {
+                    "type": "string",
+                    "nullable": true
+                },
+                "checked_at": {
+                    "type": "string",
+                    "format": "date-time",
+                    "nullable": true,
+                    "description": "When the last probe ran; null before the first one"
+                },
+                "age_seconds": {
+                    "type": "integer",
+                    "nullable": true
+                },
+                "stale_after_seconds": {
+                    "type": "integer",
+                    "nullable": true
+                }
+            }
+        },
+        "RateLimitMetricsResponse": {
+            "type": "object",
+            "properties": {
+                "timestamp": {
+                    "type": "string",
+                    "format": "date-time"
+                },
+                "analytics_enabled": {
+                    "type": "boolean",
+                    "description": "Whether rate limit analytics are being collected"
+                },
+                "metrics": {
+                    "type": "object",
+                    "nullable": true,
+                    "description": "Aggregated metrics for the requested window",
+                    "properties": {
+                        "total_checks": { "type": "integer" },
+                        "total_blocked": { "type": "integer" },
+                        "avg_latency_ms": { "type": "number" },
+                        "p95_latency_ms": { "type": "integer" },
+                        "p99_latency_ms": { "type": "integer" },
+                        "endpoint_metrics": {
+                            "type": "object",
+                            "description": "Keyed by endpoint",
+                            "additionalProperties": {
+                                "type": "object",
+                                "properties": {
+                                    "endpoint": { "type": "string" },
+                                    "total_requests": { "type": "integer" },
+                                    "blocked_requests": { "type": "integer" },
+                                    "avg_latency_ms": { "type": "number" },
+                                    "block_rate": {
+                                        "type": "number",
+                                        "description": "Percentage of requests blocked"
+                                    }
+                                }
+                            }
+                        },
+                        "tier_metrics": {
+                            "type": "object",
+                            "description": "Keyed by subscription tier",
+                            "additionalProperties": {
+                                "type": "object",
+                                "properties": {
+                                    "tier": { "type": "string" },
+                                    "total_requests": { "type": "integer" },
+                                    "blocked_requests": { "type": "integer" },
+                                    "unique_users": { "type": "integer" },
+                                    "avg_latency_ms": { "type": "number" }
+                                }
+                            }
+                        },
+                        "window_start": { "type": "string", "format": "date-time" },
+                        "window_end": { "type": "string", "format": "date-time" },
+                        "backend": {
+                            "type": "string",
+                            "enum": ["memory", "clickhouse"],
+                            "description": "memory: this instance's counters since it started. clickhouse: sampled events of every instance, counts scaled by the sample rate"
+                        }
+                    }
+                },
+                "top_offenders": {
+                    "type": "object",
+                    "description": "Most-blocked rate limit keys (up to 10), keyed by endpoint",
+                    "additionalProperties": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "key": { "type": "string" },
+                                "blocked_requests": { "type": "integer" }
+                            }
+                        }
+                    }
+                },
+                "emergency_mode": {
+                    "type": "object",
+                    "properties": {
+                        "active": {
+                            "type": "boolean",
+                            "description": "Whether the global emergency limit overrides endpoint limits"
+                        },
+                        "limit": { "type": "integer", "nullable": true },
+                        "window_seconds": { "type": "integer" },
+                        "whitelisted_ips": { "type": "integer" },
+                        "blacklisted_ips": { "type": "integer" }
+                    }
+                },
+                "monitoring": {
+                    "type": "object",
+                    "nullable": true,
+                    "properties": {
+                        "current_rps": { "type": "number" },
+                        "peak_rps": {
+                            "type": "number",
+                            "description": "Peak requests per second in the last hour"
+                        },
+                        "active_keys": { "type": "integer" },
+                        "blocked_keys": { "type": "integer" },
+                        "health": {
+                            "type": "object",
+                            "properties": {
+                                "redis_healthy": { "type": "boolean" },
+                                "redis_latency_ms": { "type": "number" },
+                                "within_sla": {
+                                    "type": "boolean",
+                                    "description": "Whether checks are completing in under 5ms"
+                                },
+                                "error_rate": { "type": "number" },
+                                "memory_usage_mb": { "type": "number" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    })
+}
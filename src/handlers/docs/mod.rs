@@ -1,9 +1,9 @@
-// API Documentation handlers - modular structure
-pub mod auth;
+// API Documentation handlers
+// The spec is derived from the handlers' #[utoipa::path] annotations and the
+// ToSchema types; only routes served outside this crate's handlers keep
+// hand-written fragments
 pub mod health;
-pub mod links;
-pub mod redirect;
-pub mod schemas;
+pub mod onboarding; // Plan selection, served by managed (non-OSS) deployments only
 pub mod swagger_ui;
 
 use crate::app::AppState;
@@ -13,7 +13,379 @@ use axum::{
     http::{header, StatusCode},
     response::{IntoResponse, Response},
 };
-use serde_json::{self, json};
+use once_cell::sync::Lazy;
+use utoipa::{
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        server::ServerBuilder,
+    },
+    Modify, OpenApi,
+};
+
+use crate::handlers::admin::ImpersonationTokenResponse;
+use crate::handlers::auth::{
+    AccountExportApiResponse, LoginApiResponse, LoginRequest, LoginResponse, LoginUserInfo,
+    MessageApiResponse, RefreshRequest, RegisterApiResponse, RegisterRequest, RegisterResponse,
+    RevokeSessionsApiResponse, RevokeSessionsResponse, SecurityEventsApiResponse,
+    SecurityEventsResponse, TokenApiResponse, TokenResponse, TokenValidation,
+    TokenValidationApiResponse, UserInfo, UserInfoApiResponse, UserPreferences,
+    UserPreferencesApiResponse,
+};
+use crate::middleware::latency::{LatencyMetrics, LatencySummary};
+use crate::middleware::request_timeout::RequestTimeoutMetrics;
+use crate::models::{
+    account_export::{
+        AccountArchive, AccountExportResponse, AccountImportResponse, ArchivedClickStats,
+        ArchivedLink, ArchivedUser, ExportStatus, ImportEntityResult, ImportOutcome,
+    },
+    analytics::{
+        DailyCount, EndpointOffenders, InstanceStatsResponse, OffenderKind, OffendersFormat,
+        RateLimitOffenderEntry, RateLimitOffendersResponse, SecurityScanMetricsResponse,
+        ThreatTypeCount, TopBlockedDomain, TopLinkSummary, UsageSummaryResponse,
+    },
+    api_usage::{ApiUsageResponse, DailyUsage, EndpointUsage},
+    audit_log::{AuditLogListResponse, AuditLogResponse},
+    blocked_domain::{BlockedDomainResponse, BlockedEntryType, CreateBlockedDomainRequest},
+    denied_ip::{CreateDeniedIpRequest, DeniedIpResponse},
+    link::{
+        AppealLinkRequest, BulkCreateItemError, BulkCreateResponse, BulkStatusItem,
+        BulkStatusOutcome, BulkStatusRequest, BulkStatusResponse, CampaignClicks,
+        CheckAliasResponse, ClickBreakdown, ClickEntryPoints, CreateLinkRequest, DailyClickCount,
+        DailyConversions, ExpiryPolicy, Link, LinkCampaignStatsResponse,
+        LinkConversionStatsResponse, LinkFilter, LinkListResponse, LinkMetadata, LinkPagination,
+        LinkPreviewResponse, LinkResponse, LinkSecurityResponse, LinkSecuritySummary,
+        LinkStatsComparison, LinkStatsRange, LinkStatsResponse, LinksPauseStatus, OgImageFormat,
+        PeriodChange, QuarantinedLinkResponse, QuickLinkRequest, QuickLinkResponse,
+        ResolveAppealRequest, TrackingMode, TransferLinkRequest, UnlockLinkForm, UpdateLinkRequest,
+        ValidateLinkResponse,
+    },
+    link_revision::{FieldChange, LinkHistoryResponse, LinkRevisionResponse},
+    notification_preferences::NotificationPreferences,
+    organization::{
+        AcceptInvitationRequest, CreateOrganizationRequest, InviteMemberRequest, OrgRole,
+        OrganizationInvitationResponse, OrganizationMemberListResponse, OrganizationMemberResponse,
+        OrganizationResponse,
+    },
+    page::{CreatePageRequest, PageResponse, PageTheme, UpdatePageRequest},
+    password_reset::{
+        ForgotPasswordRequest, ForgotPasswordResponse, ResetPasswordRequest, ResetPasswordResponse,
+    },
+    security_incident::SecurityIncident,
+    trusted_domain::{CreateTrustedDomainRequest, TrustedDomainResponse},
+    user::{OnboardingStatus, OnboardingStatusResponse, OnboardingStepRequest},
+};
+use crate::services::{
+    background_tasks::{BackgroundTaskMetrics, InstanceTaskStatus, TaskLockHolder, TaskStatus},
+    click_counter::ClickCounterMetrics,
+    metadata_throttle::MetadataExtractionMetrics,
+    refresh_token_cleanup::RefreshTokenCleanupMetrics,
+    self_test::{SelfTestReport, SelfTestStatus, SelfTestStep},
+};
+use crate::utils::api_error::{ApiError, FieldError};
+use crate::utils::safe_redirect::safe_internal_redirect;
+use crate::utils::urlhaus_client::{FeedUpdateOutcome, FeedUpdateStats, ThreatFeedMetrics};
+
+/// OpenAPI document for every route served by this crate's handlers.
+/// Extended platforms can `merge` their own derived documents into this one.
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "QCK Backend API",
+        description = "URL shortener platform API with user authentication and link management",
+        version = "1.0.0",
+        contact(name = "QCK Development Team", email = "dev@qck.sh")
+    ),
+    paths(
+        crate::handlers::auth::register,
+        crate::handlers::auth::login,
+        crate::handlers::auth::refresh_token,
+        crate::handlers::auth::logout,
+        crate::handlers::auth::revoke_all_sessions,
+        crate::handlers::auth::list_security_events,
+        crate::handlers::auth::get_current_user,
+        crate::handlers::auth::get_preferences,
+        crate::handlers::auth::update_preferences,
+        crate::handlers::auth::export_account,
+        crate::handlers::auth::download_account_export,
+        crate::handlers::auth::unlock_account,
+        crate::handlers::auth::validate_token,
+        crate::handlers::auth::forgot_password,
+        crate::handlers::auth::reset_password,
+        crate::handlers::onboarding::get_onboarding_status,
+        crate::handlers::onboarding::advance_onboarding,
+        crate::handlers::onboarding::complete_onboarding,
+        crate::handlers::settings::get_notification_preferences,
+        crate::handlers::settings::update_notification_preferences,
+        crate::handlers::links::create_link,
+        crate::handlers::links::quick_create_link,
+        crate::handlers::links::validate_link,
+        crate::handlers::links::list_links,
+        crate::handlers::links::bulk_create_links,
+        crate::handlers::links::bulk_update_link_status,
+        crate::handlers::links::create_custom_link,
+        crate::handlers::links::check_alias_availability,
+        crate::handlers::links::get_link,
+        crate::handlers::links::update_link,
+        crate::handlers::links::delete_link,
+        crate::handlers::links::transfer_link,
+        crate::handlers::links::pause_all_links,
+        crate::handlers::links::resume_all_links,
+        crate::handlers::links::get_link_stats,
+        crate::handlers::links::get_link_campaign_stats,
+        crate::handlers::links::get_link_conversion_stats,
+        crate::handlers::links::appeal_link,
+        crate::handlers::links::get_link_security,
+        crate::handlers::links::get_link_history,
+        crate::handlers::links::get_link_og_image,
+        crate::handlers::links::get_conversion_pixel,
+        crate::handlers::analytics::get_usage_summary,
+        crate::handlers::analytics::get_api_usage,
+        crate::handlers::organizations::create_organization,
+        crate::handlers::organizations::list_organizations,
+        crate::handlers::organizations::invite_member,
+        crate::handlers::organizations::accept_invitation,
+        crate::handlers::organizations::list_members,
+        crate::handlers::organizations::remove_member,
+        crate::handlers::pages::create_page,
+        crate::handlers::pages::list_pages,
+        crate::handlers::pages::get_page,
+        crate::handlers::pages::update_page,
+        crate::handlers::pages::delete_page,
+        crate::handlers::audit_logs::list_my_audit_logs,
+        crate::handlers::admin::list_blocked_domains,
+        crate::handlers::admin::create_blocked_domain,
+        crate::handlers::admin::delete_blocked_domain,
+        crate::handlers::admin::list_trusted_domains,
+        crate::handlers::admin::create_trusted_domain,
+        crate::handlers::admin::delete_trusted_domain,
+        crate::handlers::admin::list_link_appeals,
+        crate::handlers::admin::resolve_link_appeal,
+        crate::handlers::admin::list_audit_logs,
+        crate::handlers::admin::list_security_events,
+        crate::handlers::admin::get_instance_stats,
+        crate::handlers::admin::get_latency_metrics,
+        crate::handlers::admin::run_self_test_handler,
+        crate::handlers::admin::get_security_metrics,
+        crate::handlers::admin::get_rate_limit_offenders,
+        crate::handlers::admin::list_denied_ips,
+        crate::handlers::admin::create_denied_ip,
+        crate::handlers::admin::delete_denied_ip,
+        crate::handlers::admin::import_user,
+        crate::handlers::admin::impersonate_user,
+        crate::handlers::admin::deactivate_user,
+        crate::handlers::redirect::root,
+        crate::handlers::redirect::favicon,
+        crate::handlers::redirect::robots_txt,
+        crate::handlers::redirect::redirect_to_url,
+        crate::handlers::redirect::redirect_with_path,
+        crate::handlers::redirect::preview_url,
+        crate::handlers::redirect::unlock_link,
+        crate::handlers::metrics::metadata_extraction_metrics,
+        crate::handlers::metrics::click_counting_metrics,
+        crate::handlers::metrics::threat_feed_metrics,
+        crate::handlers::metrics::background_task_metrics,
+        crate::handlers::metrics::request_timeouts_metrics,
+        crate::handlers::metrics::refresh_token_cleanup_metrics,
+    ),
+    components(
+        schemas(
+            // Authentication
+            LoginRequest,
+            LoginResponse,
+            LoginUserInfo,
+            RegisterRequest,
+            RegisterResponse,
+            RefreshRequest,
+            TokenResponse,
+            TokenValidation,
+            UserInfo,
+            LoginApiResponse,
+            RegisterApiResponse,
+            TokenApiResponse,
+            UserInfoApiResponse,
+            UserPreferences,
+            UserPreferencesApiResponse,
+            RevokeSessionsResponse,
+            SecurityEventsApiResponse,
+            SecurityEventsResponse,
+            SecurityIncident,
+            RevokeSessionsApiResponse,
+            TokenValidationApiResponse,
+            MessageApiResponse,
+            ForgotPasswordRequest,
+            ForgotPasswordResponse,
+            ResetPasswordRequest,
+            ResetPasswordResponse,
+            // Account export and import
+            AccountExportResponse,
+            AccountExportApiResponse,
+            ExportStatus,
+            AccountArchive,
+            ArchivedUser,
+            ArchivedLink,
+            ArchivedClickStats,
+            AccountImportResponse,
+            ImportEntityResult,
+            ImportOutcome,
+            // Onboarding
+            OnboardingStatus,
+            OnboardingStepRequest,
+            OnboardingStatusResponse,
+            // Settings
+            NotificationPreferences,
+            // Links
+            Link,
+            CreateLinkRequest,
+            UpdateLinkRequest,
+            TransferLinkRequest,
+            LinksPauseStatus,
+            AppealLinkRequest,
+            LinkResponse,
+            LinkListResponse,
+            LinkPagination,
+            LinkFilter,
+            LinkMetadata,
+            LinkSecuritySummary,
+            LinkSecurityResponse,
+            LinkStatsResponse,
+            LinkStatsRange,
+            LinkStatsComparison,
+            PeriodChange,
+            DailyClickCount,
+            ClickBreakdown,
+            ClickEntryPoints,
+            LinkCampaignStatsResponse,
+            CampaignClicks,
+            LinkConversionStatsResponse,
+            DailyConversions,
+            BulkCreateResponse,
+            BulkCreateItemError,
+            BulkStatusRequest,
+            BulkStatusResponse,
+            BulkStatusItem,
+            BulkStatusOutcome,
+            CheckAliasResponse,
+            LinkPreviewResponse,
+            UnlockLinkForm,
+            TrackingMode,
+            ExpiryPolicy,
+            OgImageFormat,
+            QuickLinkRequest,
+            QuickLinkResponse,
+            ValidateLinkResponse,
+            LinkHistoryResponse,
+            LinkRevisionResponse,
+            FieldChange,
+            // Analytics
+            UsageSummaryResponse,
+            TopLinkSummary,
+            ApiUsageResponse,
+            DailyUsage,
+            EndpointUsage,
+            // Organizations
+            OrgRole,
+            CreateOrganizationRequest,
+            OrganizationResponse,
+            InviteMemberRequest,
+            OrganizationInvitationResponse,
+            AcceptInvitationRequest,
+            OrganizationMemberResponse,
+            OrganizationMemberListResponse,
+            // Pages
+            PageTheme,
+            CreatePageRequest,
+            UpdatePageRequest,
+            PageResponse,
+            // Audit logs and admin
+            AuditLogResponse,
+            AuditLogListResponse,
+            BlockedEntryType,
+            CreateBlockedDomainRequest,
+            BlockedDomainResponse,
+            CreateTrustedDomainRequest,
+            TrustedDomainResponse,
+            ResolveAppealRequest,
+            QuarantinedLinkResponse,
+            ImpersonationTokenResponse,
+            InstanceStatsResponse,
+            SelfTestReport,
+            SelfTestStep,
+            SelfTestStatus,
+            SecurityScanMetricsResponse,
+            ThreatTypeCount,
+            TopBlockedDomain,
+            DailyCount,
+            OffendersFormat,
+            OffenderKind,
+            RateLimitOffendersResponse,
+            EndpointOffenders,
+            RateLimitOffenderEntry,
+            CreateDeniedIpRequest,
+            DeniedIpResponse,
+            // Metrics
+            MetadataExtractionMetrics,
+            ClickCounterMetrics,
+            ThreatFeedMetrics,
+            FeedUpdateStats,
+            FeedUpdateOutcome,
+            BackgroundTaskMetrics,
+            InstanceTaskStatus,
+            TaskLockHolder,
+            TaskStatus,
+            RequestTimeoutMetrics,
+            LatencyMetrics,
+            LatencySummary,
+            RefreshTokenCleanupMetrics,
+            // Errors
+            ApiError,
+            FieldError,
+        )
+    ),
+    modifiers(&BearerAuthAddon),
+    tags(
+        (name = "Authentication", description = "User authentication and registration (OSS - Auto-verification enabled)"),
+        (name = "Onboarding", description = "Onboarding progress (always completed on OSS deployments); managed deployments add plan selection"),
+        (name = "Settings", description = "Account settings such as email notification preferences"),
+        (name = "Links", description = "URL shortening and link management operations"),
+        (name = "Redirect", description = "URL redirection and preview endpoints"),
+        (name = "Analytics", description = "Usage summaries across all of the authenticated user's links, and their own API request counts"),
+        (name = "Organizations", description = "Team accounts sharing ownership of links; invitations are accepted by emailed token"),
+        (name = "Pages", description = "Link-in-bio landing pages served at their own short code"),
+        (name = "Audit Logs", description = "Audit trail of the authenticated user's actions"),
+        (name = "Admin", description = "Operator-only blocklist, trusted domains, quarantine review, audit search, instance stats, self-test, security scan metrics, account import and user impersonation (admin scope required)"),
+        (name = "Health", description = "Service health checks and rate limiting metrics")
+    )
+)]
+pub struct ApiDoc;
+
+/// Registers the `bearerAuth` scheme referenced by the protected operations
+struct BearerAuthAddon;
+
+impl Modify for BearerAuthAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearerAuth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .description(Some(
+                        "JWT access token obtained from login or refresh endpoints",
+                    ))
+                    .build(),
+            ),
+        );
+    }
+}
+
+/// Derived document; forced at startup so annotation errors surface immediately
+static API_DOC: Lazy<utoipa::openapi::OpenApi> = Lazy::new(ApiDoc::openapi);
+
+/// The derived OpenAPI document (without environment-specific servers)
+pub fn api_doc() -> &'static utoipa::openapi::OpenApi {
+    &API_DOC
+}
 
 /// Serve OpenAPI JSON specification at /v1/docs/openapi.json
 pub async fn serve_openapi_spec(State(app_state): State<AppState>) -> Response {
@@ -28,12 +400,26 @@ pub async fn serve_openapi_spec(State(app_state): State<AppState>) -> Response {
 }
 
 /// Redirect /docs to /docs/ for proper relative path resolution
-pub async fn redirect_to_docs(original_uri: OriginalUri) -> impl IntoResponse {
-    let mut path = original_uri.0.path().to_string();
+/// The Location is built from the request path behind PUBLIC_BASE_PATH, so anything that is
+/// not a plain path on this origin falls back to {base}/v1/docs/
+pub async fn redirect_to_docs(
+    State(app_state): State<AppState>,
+    original_uri: OriginalUri,
+) -> impl IntoResponse {
+    let location = docs_location(&app_state.config.public_base_path, original_uri.0.path());
+    (
+        StatusCode::MOVED_PERMANENTLY,
+        [(header::LOCATION, location)],
+    )
+        .into_response()
+}
+
+fn docs_location(base_path: &str, request_path: &str) -> String {
+    let mut path = format!("{}{}", base_path, request_path);
     if !path.ends_with('/') {
         path.push('/');
     }
-    (StatusCode::MOVED_PERMANENTLY, [(header::LOCATION, path)]).into_response()
+    safe_internal_redirect(&path, None).unwrap_or_else(|| format!("{}/v1/docs/", base_path))
 }
 
 /// Re-export swagger UI handler
@@ -42,8 +428,43 @@ pub use swagger_ui::serve_swagger_ui;
 /// Build the complete OpenAPI specification
 /// Made public to allow extended platforms to add additional endpoints
 pub fn build_openapi_spec(config: &AppConfig) -> serde_json::Value {
+    let mut doc = api_doc().clone();
+    doc.servers = Some(
+        servers(config)
+            .into_iter()
+            .map(|(url, description)| {
+                ServerBuilder::new()
+                    .url(url)
+                    .description(Some(description))
+                    .build()
+            })
+            .collect(),
+    );
+
+    let mut spec = serde_json::to_value(&doc).unwrap_or_default();
+
+    // Health lives in the binary and the metrics payload wraps non-ToSchema analytics types,
+    // so both keep hand-written fragments
+    spec["paths"]["/v1/health"] = health::health_endpoint();
+    spec["paths"]["/v1/metrics/rate-limiting"] = health::rate_limit_metrics_endpoint();
+    spec["components"]["schemas"] = merge_schemas();
+
+    // Managed deployments add plan selection to the onboarding flow
+    if !config.is_oss_deployment {
+        spec["paths"]["/v1/onboarding/select-plan"] = onboarding::select_plan_endpoint();
+    }
+
+    spec
+}
+
+/// Environment-aware server list as `(url, description)` pairs
+fn servers(config: &AppConfig) -> Vec<(String, String)> {
     // Determine the API base URL from environment
     let api_url = std::env::var("NEXT_PUBLIC_API_URL").unwrap_or_else(|_| {
+        // Behind a reverse proxy, the prefix relative to the host serving the docs
+        if !config.public_base_path.is_empty() {
+            return config.public_base_path.clone();
+        }
         // Fallback based on environment
         match config.environment {
             crate::app_config::Environment::Production => "https://qck.sh/api".to_string(),
@@ -52,118 +473,62 @@ pub fn build_openapi_spec(config: &AppConfig) -> serde_json::Value {
         }
     });
 
-    // Build server list based on environment
-    let mut servers = vec![json!({
-        "url": api_url,
-        "description": format!("Current server ({})", config.environment)
-    })];
+    let mut servers = vec![(api_url, format!("Current server ({})", config.environment))];
 
     // Add additional servers for reference in non-production environments
     if !config.is_production() {
-        servers.extend(vec![
-            json!({
-                "url": "https://s.qck.sh/api",
-                "description": "Staging server"
-            }),
-            json!({
-                "url": "https://qck.sh/api",
-                "description": "Production server"
-            }),
-        ]);
+        servers.push((
+            "https://s.qck.sh/api".to_string(),
+            "Staging server".to_string(),
+        ));
+        servers.push((
+            "https://qck.sh/api".to_string(),
+            "Production server".to_string(),
+        ));
     }
 
-    serde_json::json!({
-        "openapi": "3.0.3",
-        "info": {
-            "title": "QCK Backend API",
-            "description": "URL shortener platform API with user authentication and link management",
-            "version": "1.0.0",
-            "contact": {
-                "name": "QCK Development Team",
-                "email": "dev@qck.sh"
-            }
-        },
-        "servers": servers,
-        "tags": [
-            {
-                "name": "Authentication",
-                "description": "User authentication and registration (OSS - Auto-verification enabled)"
-            },
-            {
-                "name": "Links",
-                "description": "URL shortening and link management operations"
-            },
-            {
-                "name": "Redirect",
-                "description": "URL redirection and preview endpoints"
-            },
-            {
-                "name": "Health",
-                "description": "Service health checks"
-            }
-        ],
-        "paths": {
-            "/v1/auth/register": auth::register_endpoint(),
-            "/v1/auth/login": auth::login_endpoint(),
-            "/v1/auth/refresh": auth::refresh_endpoint(),
-            "/v1/auth/logout": auth::logout_endpoint(),
-            "/v1/auth/me": auth::get_current_user_endpoint(),
-            "/v1/auth/validate": auth::validate_token_endpoint(),
-            "/v1/auth/forgot-password": auth::forgot_password_endpoint(),
-            "/v1/auth/reset-password": auth::reset_password_endpoint(),
-            "/v1/links": json!({
-                "post": links::create_link_endpoint()["post"],
-                "get": links::list_links_endpoint()["get"]
-            }),
-            "/v1/links/bulk": json!({
-                "post": links::bulk_create_links_endpoint()["post"]
-            }),
-            "/v1/links/{id}": json!({
-                "get": links::get_link_endpoint()["get"],
-                "put": links::update_link_endpoint()["put"],
-                "delete": links::delete_link_endpoint()["delete"]
-            }),
-            "/v1/links/{id}/stats": json!({
-                "get": links::get_link_stats_endpoint()["get"]
-            }),
-            "/{short_code}": redirect::redirect_endpoint(),
-            "/{short_code}/preview": redirect::preview_endpoint(),
-            "/v1/health": health::health_endpoint(),
-        },
-        "components": {
-            "schemas": merge_schemas(),
-            "securitySchemes": {
-                "bearerAuth": {
-                    "type": "http",
-                    "scheme": "bearer",
-                    "bearerFormat": "JWT",
-                    "description": "JWT access token obtained from login or refresh endpoints"
-                }
-            }
-        }
-    })
+    servers
 }
 
 /// Merge all schemas into a single JSON object
 /// Made public to allow extended platforms to add additional schemas
 pub fn merge_schemas() -> serde_json::Value {
-    let mut all_schemas = schemas::all_schemas();
-
-    // Merge link-specific schemas
-    if let serde_json::Value::Object(ref mut map) = all_schemas {
-        if let serde_json::Value::Object(link_schemas_map) = links::link_schemas() {
-            for (key, value) in link_schemas_map {
-                map.insert(key, value);
-            }
+    let mut all_schemas = serde_json::Map::new();
+
+    if let Some(components) = &api_doc().components {
+        for (name, schema) in &components.schemas {
+            all_schemas.insert(
+                name.clone(),
+                serde_json::to_value(schema).unwrap_or_default(),
+            );
         }
+    }
 
-        // Merge redirect-specific schemas
-        if let serde_json::Value::Object(redirect_schemas_map) = redirect::preview_response_schema() {
-            for (key, value) in redirect_schemas_map {
-                map.insert(key, value);
-            }
+    // Schemas for the hand-written health and onboarding fragments
+    for extra in [health::health_schemas(), onboarding::onboarding_schemas()] {
+        if let serde_json::Value::Object(extra_map) = extra {
+            all_schemas.extend(extra_map);
         }
     }
 
-    all_schemas
+    serde_json::Value::Object(all_schemas)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_location_stays_on_origin() {
+        assert_eq!(docs_location("", "/v1/docs"), "/v1/docs/");
+        assert_eq!(docs_location("", "//evil.com/v1/docs"), "/v1/docs/");
+        assert_eq!(docs_location("", "/\\evil.com/v1/docs"), "/v1/docs/");
+        assert_eq!(docs_location("", "https:evil.com"), "/v1/docs/");
+    }
+
+    #[test]
+    fn test_docs_location_keeps_base_path() {
+        assert_eq!(docs_location("/api", "/v1/docs"), "/api/v1/docs/");
+        assert_eq!(docs_location("/api", "/v1/docs/"), "/api/v1/docs/");
+    }
 }
@@ -1,4 +1,5 @@
-// OpenAPI documentation for onboarding endpoints
+// OpenAPI documentation for plan selection, served by managed (non-OSS) deployments
+// The onboarding status endpoints themselves are derived from crate::handlers::onboarding
 
 use serde_json::json;
 
@@ -8,7 +9,7 @@ pub fn select_plan_endpoint() -> serde_json::Value {
                 "summary": "Select subscription plan",
                 "description": "Select a subscription plan during onboarding (Free, Pro, or Enterprise)",
                 "tags": ["Onboarding"],
-                "security": [{"BearerAuth": []}],
+                "security": [{"bearerAuth": []}],
                 "requestBody": {
                     "required": true,
                     "content": {
@@ -65,7 +66,7 @@ pub fn select_plan_endpoint() -> serde_json::Value {
                         "content": {
                             "application/json": {
                                 "schema": {
-                                    "$ref": "#/components/schemas/ErrorResponse"
+                                    "$ref": "#/components/schemas/ApiError"
                                 },
                                 "example": {
                                     "success": false,
@@ -80,7 +81,7 @@ pub fn select_plan_endpoint() -> serde_json::Value {
                         "content": {
                             "application/json": {
                                 "schema": {
-                                    "$ref": "#/components/schemas/ErrorResponse"
+                                    "$ref": "#/components/schemas/ApiError"
                                 }
                             }
                         }
@@ -90,7 +91,7 @@ pub fn select_plan_endpoint() -> serde_json::Value {
                         "content": {
                             "application/json": {
                                 "schema": {
-                                    "$ref": "#/components/schemas/ErrorResponse"
+                                    "$ref": "#/components/schemas/ApiError"
                                 },
                                 "example": {
                                     "success": false,
@@ -105,7 +106,7 @@ pub fn select_plan_endpoint() -> serde_json::Value {
                         "content": {
                             "application/json": {
                                 "schema": {
-                                    "$ref": "#/components/schemas/ErrorResponse"
+                                    "$ref": "#/components/schemas/ApiError"
                                 }
                             }
                         }
@@ -115,59 +116,6 @@ pub fn select_plan_endpoint() -> serde_json::Value {
     })
 }
 
-pub fn onboarding_status_endpoint() -> serde_json::Value {
-    json!({
-        "get": {
-            "summary": "Get onboarding status",
-            "description": "Get the current user's onboarding progress and next steps",
-            "tags": ["Onboarding"],
-            "security": [{"BearerAuth": []}],
-            "responses": {
-                "200": {
-                    "description": "Onboarding status retrieved successfully",
-                    "content": {
-                        "application/json": {
-                            "schema": {
-                                "$ref": "#/components/schemas/OnboardingStatusResponse"
-                            },
-                            "example": {
-                                "success": true,
-                                "data": {
-                                    "onboarding_status": "plan_selected",
-                                    "email_verified": true,
-                                    "subscription_tier": "pro",
-                                    "completed_steps": ["registered", "verified", "plan_selected"],
-                                    "next_step": "payment"
-                                }
-                            }
-                        }
-                    }
-                },
-                "401": {
-                    "description": "Unauthorized - Invalid or missing token",
-                    "content": {
-                        "application/json": {
-                            "schema": {
-                                "$ref": "#/components/schemas/ErrorResponse"
-                            }
-                        }
-                    }
-                },
-                "500": {
-                    "description": "Internal server error",
-                    "content": {
-                        "application/json": {
-                            "schema": {
-                                "$ref": "#/components/schemas/ErrorResponse"
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    })
-}
-
 pub fn onboarding_schemas() -> serde_json::Value {
     json!({
         "SelectPlanRequest": {
@@ -220,45 +168,6 @@ pub fn onboarding_schemas() -> serde_json::Value {
                     }
                 }
             }
-        },
-        "OnboardingStatusResponse": {
-            "type": "object",
-            "properties": {
-                "success": {
-                    "type": "boolean",
-                    "description": "Whether the request was successful"
-                },
-                "data": {
-                    "type": "object",
-                    "properties": {
-                        "onboarding_status": {
-                            "type": "string",
-                            "enum": ["registered", "verified", "plan_selected", "payment_pending", "completed"],
-                            "description": "Current onboarding status"
-                        },
-                        "email_verified": {
-                            "type": "boolean",
-                            "description": "Whether the user's email has been verified"
-                        },
-                        "subscription_tier": {
-                            "type": "string",
-                            "enum": ["free", "pro", "enterprise"],
-                            "description": "Current subscription tier"
-                        },
-                        "completed_steps": {
-                            "type": "array",
-                            "items": {
-                                "type": "string"
-                            },
-                            "description": "List of completed onboarding steps"
-                        },
-                        "next_step": {
-                            "type": "string",
-                            "description": "Next step the user should take"
-                        }
-                    }
-                }
-            }
         }
     })
 }
@@ -1,12 +1,33 @@
 // Swagger UI HTML serving
 
-use axum::response::{Html, IntoResponse};
+use axum::{
+    extract::State,
+    response::{Html, IntoResponse},
+};
+
+use crate::app::AppState;
 
 /// Serve Swagger UI HTML at /v1/docs
-pub async fn serve_swagger_ui() -> impl IntoResponse {
-    Html(SWAGGER_UI_HTML)
+pub async fn serve_swagger_ui(State(app_state): State<AppState>) -> impl IntoResponse {
+    Html(render_swagger_ui(&app_state.config.public_base_path))
+}
+
+/// The Swagger UI page for an API mounted under `base_path` (validated PUBLIC_BASE_PATH)
+pub fn render_swagger_ui(base_path: &str) -> String {
+    let spec_url = if base_path.is_empty() {
+        // Unconfigured: guess the /api prefix from the page location as before
+        DETECTED_SPEC_URL.to_string()
+    } else {
+        format!("const specUrl = '{}/v1/docs/openapi.json';", base_path)
+    };
+    SWAGGER_UI_HTML.replace("__SPEC_URL__", &spec_url)
 }
 
+const DETECTED_SPEC_URL: &str = r#"// Detect if we're running behind /api prefix
+            const currentPath = window.location.pathname;
+            const needsApiPrefix = currentPath.includes('/api/');
+            const specUrl = needsApiPrefix ? '/api/v1/docs/openapi.json' : '/v1/docs/openapi.json';"#;
+
 // Embedded Swagger UI HTML
 const SWAGGER_UI_HTML: &str = r#"<!DOCTYPE html>
 <html lang="en">
@@ -109,10 +130,7 @@ const SWAGGER_UI_HTML: &str = r#"<!DOCTYPE html>
         }
         
         window.onload = function() {
-            // Detect if we're running behind /api prefix
-            const currentPath = window.location.pathname;
-            const needsApiPrefix = currentPath.includes('/api/');
-            const specUrl = needsApiPrefix ? '/api/v1/docs/openapi.json' : '/v1/docs/openapi.json';
+            __SPEC_URL__
             
             const ui = SwaggerUIBundle({
                 url: specUrl,
@@ -141,3 +159,24 @@ const SWAGGER_UI_HTML: &str = r#"<!DOCTYPE html>
     </script>
 </body>
 </html>"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_swagger_ui_uses_base_path() {
+        let html = render_swagger_ui("/api");
+        assert!(html.contains("const specUrl = '/api/v1/docs/openapi.json';"));
+        assert!(!html.contains("__SPEC_URL__"));
+        assert!(!html.contains("needsApiPrefix"));
+    }
+
+    #[test]
+    fn test_swagger_ui_without_base_path_detects_prefix() {
+        let html = render_swagger_ui("");
+        assert!(html.contains("needsApiPrefix"));
+        assert!(html.contains("'/v1/docs/openapi.json'"));
+        assert!(!html.contains("__SPEC_URL__"));
+    }
+}
@@ -0,0 +1,60 @@
+// Responses for requests that match no route, or a route but not its method
+// API paths answer in the standard JSON error envelope; everything else is treated as a
+// visitor following a short link and gets an HTML page. axum sets the Allow header on
+// every 405 itself.
+
+use axum::{
+    extract::OriginalUri,
+    http::{Method, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use crate::{handlers::redirect, utils::api_error::ApiError};
+
+/// Prefix shared by every API route
+const API_PREFIX: &str = "/v1";
+
+/// Whether `path` belongs to the API rather than the short link namespace
+pub fn is_api_path(path: &str) -> bool {
+    path.strip_prefix(API_PREFIX)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// 405 for an API route requested with a method it doesn't support
+pub async fn api_method_not_allowed(method: Method, OriginalUri(uri): OriginalUri) -> ApiError {
+    ApiError::new(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "METHOD_NOT_ALLOWED",
+        format!("{} is not supported on {}", method, uri.path()),
+    )
+}
+
+/// 404 for a path that matches no route
+pub async fn not_found(OriginalUri(uri): OriginalUri) -> Response {
+    if is_api_path(uri.path()) {
+        ApiError::new(
+            StatusCode::NOT_FOUND,
+            "ROUTE_NOT_FOUND",
+            format!("No API route matches {}", uri.path()),
+        )
+        .into_response()
+    } else {
+        redirect::path_not_found(uri.path())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_api_path() {
+        assert!(is_api_path("/v1"));
+        assert!(is_api_path("/v1/"));
+        assert!(is_api_path("/v1/links/abc"));
+        // Short codes that merely start with "v1"
+        assert!(!is_api_path("/v1abc"));
+        assert!(!is_api_path("/abc123"));
+        assert!(!is_api_path("/"));
+    }
+}
@@ -4,12 +4,10 @@
 
 use axum::{
     extract::{Extension, Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
     Json,
 };
-use rand::{thread_rng, Rng};
-use serde_json::json;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 use validator::Validate;
@@ -18,10 +16,30 @@ use crate::{
     app::AppState,
     middleware::auth::AuthenticatedUser,
     models::link::{
-        CreateLinkRequest, LinkFilter, LinkPagination, ListLinksParams, UpdateLinkRequest,
+        check_url_length, normalize_referrer_domains, validate_tags, AppealLinkRequest,
+        BulkCreateItemError, BulkCreateResponse, BulkStatusRequest, BulkStatusResponse,
+        CampaignStatsQuery, CheckAliasQuery, CheckAliasResponse, CreateLinkRequest,
+        DeleteLinkQuery, LinkCampaignStatsResponse, LinkConversionStatsResponse, LinkFilter,
+        LinkPagination, LinkSecurityQuery, LinkSecurityResponse, LinkStatsQuery, LinkStatsResponse,
+        ListLinksParams, OgImageQuery, QuickLinkRequest, TransferLinkRequest, UpdateLinkRequest,
+        ValidateLinkQuery,
+    },
+    models::link_revision::LinkHistoryQuery,
+    services::{
+        conversion_tracking,
+        link::{LinkService, LinkUsage, ALIAS_RESERVATION_TTL_SECONDS},
+        link_pause::LinkPauseService,
+        og_image::OgImageService,
+        quarantine::QuarantineService,
+        rate_limit::RateLimitConfig,
+    },
+    utils::{
+        api_error::ApiError,
+        etag,
+        link_errors::LinkError,
+        service_error::ServiceError,
+        timezone::{date_in, resolve_timezone, today_in},
     },
-    services::link::LinkService,
-    utils::{link_errors::LinkError, service_error::ServiceError},
 };
 
 // =============================================================================
@@ -35,13 +53,18 @@ use crate::{
     path = "/v1/links",
     tag = "Links",
     operation_id = "createLink",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key (1-255 visible ASCII characters); retries with the same key and body replay the original response for 24h")
+    ),
     request_body = CreateLinkRequest,
     responses(
-        (status = 201, description = "Link created successfully", body = LinkResponse),
-        (status = 400, description = "Bad request - validation failed"),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 409, description = "Conflict - custom alias already exists"),
-        (status = 429, description = "Too many requests - rate limit exceeded")
+        (status = 201, description = "Link created successfully", body = LinkResponse, headers(("X-Link-Limit-Warning" = String, description = "Present once the user holds 80% of MAX_LINKS_PER_USER, e.g. `count=812, limit=1000`"))),
+        (status = 400, description = "Bad request - invalid URL or alias", body = ApiError),
+        (status = 422, description = "Validation failed - `details` lists each invalid field", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - MAX_LINKS_PER_USER reached (`LINK_LIMIT_REACHED`, `details[0].params` holds current and limit)", body = ApiError),
+        (status = 409, description = "Conflict - custom alias already exists, or Idempotency-Key reused with a different body or still in progress", body = ApiError),
+        (status = 429, description = "Too many requests - rate limit exceeded", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -54,9 +77,23 @@ pub async fn create_link(
 ) -> impl IntoResponse {
     use crate::models::user::User;
 
-    // Validate request
+    // Validate request (URL length first, before the URL is parsed)
+    if let Err(e) = request.validate_custom(state.config.max_url_length) {
+        return ApiError::from(e).into_response();
+    }
     if let Err(e) = request.validate() {
-        return LinkError::from(e).into_response();
+        return ApiError::from(e).into_response();
+    }
+    if let Err(e) = validate_tags(&request.tags) {
+        return ApiError::from(e).into_response();
+    }
+    for (list, domains) in [
+        ("allowed_referrers", &request.allowed_referrers),
+        ("blocked_referrers", &request.blocked_referrers),
+    ] {
+        if let Err(e) = normalize_referrer_domains(list, domains) {
+            return ApiError::from(e).into_response();
+        }
     }
 
     // Get database connection
@@ -88,14 +125,10 @@ pub async fn create_link(
 
     match rate_limit_check {
         Ok(result) if !result.allowed => {
-            return (
-                StatusCode::TOO_MANY_REQUESTS,
-                Json(serde_json::json!({
-                    "error": "Rate limit exceeded",
-                    "message": format!("Too many requests. Try again in {} seconds.", result.retry_after.unwrap_or(3600)),
-                    "retry_after": result.retry_after.unwrap_or(3600)
-                }))
-            ).into_response();
+            return LinkError::RateLimitExceeded {
+                retry_after: result.retry_after.unwrap_or(3600) as u64,
+            }
+            .into_response();
         },
         Ok(_) => {
             // Rate limit passed, continue
@@ -112,7 +145,183 @@ pub async fn create_link(
 
     // Create the link
     match link_service.create_link(&user, request).await {
-        Ok(link_response) => (StatusCode::CREATED, Json(link_response)).into_response(),
+        Ok(link_response) => {
+            let response = (StatusCode::CREATED, Json(link_response)).into_response();
+            with_link_limit_warning(&link_service, user.id, response).await
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Add `X-Link-Limit-Warning` once the user holds 80% of MAX_LINKS_PER_USER
+async fn with_link_limit_warning(
+    link_service: &LinkService,
+    user_id: Uuid,
+    mut response: Response,
+) -> Response {
+    match link_service.link_usage(user_id).await {
+        Ok(Some(usage)) if usage.is_near_limit() => {
+            if let Ok(value) = HeaderValue::from_str(&usage.header_value()) {
+                response
+                    .headers_mut()
+                    .insert(LinkUsage::WARNING_HEADER, value);
+            }
+        },
+        Ok(_) => {},
+        Err(e) => warn!("Failed to read link usage for {}: {}", user_id, e),
+    }
+    response
+}
+
+/// Dry-run a link creation: normalize, scan and check the alias without creating anything
+/// POST /api/v1/links/validate
+#[utoipa::path(
+    post,
+    path = "/v1/links/validate",
+    tag = "Links",
+    operation_id = "validateLink",
+    params(ValidateLinkQuery),
+    request_body = CreateLinkRequest,
+    responses(
+        (status = 200, description = "What POST /v1/links would do with this body: the normalized URL, the security scan verdict (a blocked URL has `allowed: false` and the error creation would fail with), the custom alias verdict and the metadata extraction would fill in. Nothing is written", body = ValidateLinkResponse),
+        (status = 400, description = "Bad request - the URL can't be normalized", body = ApiError),
+        (status = 422, description = "Validation failed - `details` lists each invalid field, exactly as POST /v1/links reports them", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - not allowed to create links for `organization_id`", body = ApiError),
+        (status = 429, description = "Too many validations - limited separately from link creation", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn validate_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Query(query): Query<ValidateLinkQuery>,
+    Json(request): Json<CreateLinkRequest>,
+) -> impl IntoResponse {
+    use crate::models::user::User;
+
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    // Validations scan the URL against external services and may fetch it
+    if state.config.enable_rate_limiting {
+        let rate_limit_key = format!("link_validation:{}", user_uuid);
+        match state
+            .rate_limit_service
+            .check_rate_limit_with_config(&rate_limit_key, &RateLimitConfig::link_validation())
+            .await
+        {
+            Ok(status) if !status.allowed => {
+                return LinkError::RateLimitExceeded {
+                    retry_after: u64::from(status.retry_after.unwrap_or(60)),
+                }
+                .into_response();
+            },
+            Err(e) => warn!("Rate limit check failed for link validation: {}", e),
+            _ => {},
+        }
+    }
+
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+    let user = match User::find_by_id(&mut conn, user_uuid).await {
+        Ok(user) => user,
+        Err(_) => return LinkError::NotFound.into_response(),
+    };
+    drop(conn);
+
+    match LinkService::new(&state)
+        .validate_link(&user, request, query.fetch_title)
+        .await
+    {
+        Ok(verdict) => Json(verdict).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Shorten a URL with the owner's defaults and nothing else, for browser extensions
+/// POST /api/v1/links/quick
+#[utoipa::path(
+    post,
+    path = "/v1/links/quick",
+    tag = "Links",
+    operation_id = "quickCreateLink",
+    request_body = QuickLinkRequest,
+    responses(
+        (status = 201, description = "Link created and already active, titled with the destination host. \
+            When the server sets QUICK_LINK_DEFERRED_SCAN, only local security checks (blocklists, \
+            domain reputation, URL patterns) run before this response; threat feeds and the content \
+            scan follow in the background and quarantine the link if they flag it", body = QuickLinkResponse, headers(("X-Link-Limit-Warning" = String, description = "Present once the user holds 80% of MAX_LINKS_PER_USER, e.g. `count=812, limit=1000`"))),
+        (status = 400, description = "Bad request - invalid or blocked URL", body = ApiError),
+        (status = 422, description = "Validation failed - `details` lists each invalid field", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - MAX_LINKS_PER_USER reached (`LINK_LIMIT_REACHED`)", body = ApiError),
+        (status = 429, description = "Too many requests - rate limit exceeded", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn quick_create_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<QuickLinkRequest>,
+) -> impl IntoResponse {
+    use crate::models::user::User;
+
+    if let Err(e) = request.validate() {
+        return ApiError::from(e).into_response();
+    }
+
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let user = {
+        let mut conn = match state.diesel_pool.get().await {
+            Ok(conn) => conn,
+            Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+        };
+        match User::find_by_id(&mut conn, user_uuid).await {
+            Ok(user) => user,
+            Err(_) => return LinkError::NotFound.into_response(),
+        }
+    };
+
+    // Same budget as POST /v1/links
+    let rate_limit_key = format!("user:{}:link_creation", user.id);
+    match state
+        .rate_limit_service
+        .check_rate_limit(&rate_limit_key, "/api/links")
+        .await
+    {
+        Ok(result) if !result.allowed => {
+            return LinkError::RateLimitExceeded {
+                retry_after: result.retry_after.unwrap_or(3600) as u64,
+            }
+            .into_response();
+        },
+        Ok(_) => {},
+        Err(e) => warn!("Rate limit check failed: {}", e),
+    }
+
+    let link_service = LinkService::new(&state);
+    match link_service.quick_create_link(&user, request).await {
+        Ok(response) => {
+            let response = (StatusCode::CREATED, Json(response)).into_response();
+            with_link_limit_warning(&link_service, user.id, response).await
+        },
         Err(e) => e.into_response(),
     }
 }
@@ -125,13 +334,15 @@ pub async fn create_link(
     tag = "Links",
     operation_id = "getLink",
     params(
-        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000")
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response; returns 304 if the link and its click stats are unchanged")
     ),
     responses(
-        (status = 200, description = "Link retrieved successfully", body = LinkResponse),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 403, description = "Forbidden - not the link owner"),
-        (status = 404, description = "Link not found")
+        (status = 200, description = "Link retrieved successfully, with a weak `ETag` header", body = LinkResponse),
+        (status = 304, description = "Not modified - the `If-None-Match` ETag is current"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - not the link owner", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -141,6 +352,7 @@ pub async fn get_link(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(link_id): Path<Uuid>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     // Parse user_id from string to UUID
     let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
@@ -155,7 +367,17 @@ pub async fn get_link(
 
     // Get link with ClickHouse stats included
     match link_service.get_link_with_stats(link_id, user_uuid).await {
-        Ok(response) => Json(response).into_response(),
+        Ok(response) => {
+            // Changes to the link bump updated_at; new clicks move last_accessed_at
+            let etag = etag::weak_etag(&format!(
+                "link|{}|{}|{:?}",
+                response.id, response.updated_at, response.last_accessed_at
+            ));
+            if etag::if_none_match(&headers, &etag) {
+                return etag::not_modified(&etag);
+            }
+            etag::with_etag(Json(response).into_response(), &etag)
+        },
         Err(ServiceError::NotFound) => LinkError::NotFound.into_response(),
         Err(e) => LinkError::DatabaseError(e.to_string()).into_response(),
     }
@@ -174,10 +396,11 @@ pub async fn get_link(
     request_body = UpdateLinkRequest,
     responses(
         (status = 200, description = "Link updated successfully", body = LinkResponse),
-        (status = 400, description = "Bad request - validation failed"),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 403, description = "Forbidden - not the link owner"),
-        (status = 404, description = "Link not found")
+        (status = 400, description = "Bad request - invalid URL or alias", body = ApiError),
+        (status = 422, description = "Validation failed - `details` lists each invalid field", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - not the link owner", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -191,9 +414,30 @@ pub async fn update_link(
 ) -> impl IntoResponse {
     use crate::models::user::User;
 
-    // Validate request if it has fields
+    // Validate request if it has fields (URL length first, before the URL is parsed)
+    if let Some(Err(e)) = request
+        .url
+        .as_deref()
+        .map(|url| check_url_length(url, state.config.max_url_length))
+    {
+        return ApiError::from(e).into_response();
+    }
     if let Err(e) = request.validate() {
-        return LinkError::from(e).into_response();
+        return ApiError::from(e).into_response();
+    }
+    if let Some(Err(e)) = request.tags.as_deref().map(validate_tags) {
+        return ApiError::from(e).into_response();
+    }
+    for (list, domains) in [
+        ("allowed_referrers", &request.allowed_referrers),
+        ("blocked_referrers", &request.blocked_referrers),
+    ] {
+        if let Some(Err(e)) = domains
+            .as_deref()
+            .map(|domains| normalize_referrer_domains(list, domains))
+        {
+            return ApiError::from(e).into_response();
+        }
     }
 
     // Get database connection
@@ -224,7 +468,10 @@ pub async fn update_link(
     }
 }
 
-/// Delete (deactivate) a link
+/// Header that must be `yes` on `DELETE /v1/links/{id}?permanent=true`
+const CONFIRM_PERMANENT_DELETE_HEADER: &str = "x-confirm-permanent-delete";
+
+/// Delete (deactivate) a link, or erase it with `permanent=true`
 /// DELETE /api/v1/links/:id
 #[utoipa::path(
     delete,
@@ -232,13 +479,16 @@ pub async fn update_link(
     tag = "Links",
     operation_id = "deleteLink",
     params(
-        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000")
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        DeleteLinkQuery,
+        ("X-Confirm-Permanent-Delete" = Option<String>, Header, description = "Must be `yes` when `permanent=true`")
     ),
     responses(
-        (status = 204, description = "Link deleted successfully (no content)"),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 403, description = "Forbidden - not the link owner"),
-        (status = 404, description = "Link not found")
+        (status = 204, description = "Link deleted successfully (no content); permanent deletes first record a snapshot of the link in the audit log"),
+        (status = 400, description = "Permanent delete without the confirmation header", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - organization member without owner or admin role, or a permanent delete while impersonating a user", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -248,9 +498,27 @@ pub async fn delete_link(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(link_id): Path<Uuid>,
+    Query(query): Query<DeleteLinkQuery>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     use crate::models::user::User;
 
+    // Erasing a link can't be undone, so support staff may only deactivate it
+    if query.permanent {
+        if let Err(e) = auth_user.forbid_impersonation() {
+            return (*e).into_response();
+        }
+    }
+
+    if query.permanent && !permanent_delete_confirmed(&headers) {
+        return ApiError::new(
+            StatusCode::BAD_REQUEST,
+            "CONFIRMATION_REQUIRED",
+            "Permanent deletion requires the X-Confirm-Permanent-Delete: yes header",
+        )
+        .into_response();
+    }
+
     // Get database connection
     let mut conn = match state.diesel_pool.get().await {
         Ok(conn) => conn,
@@ -273,12 +541,153 @@ pub async fn delete_link(
 
     let link_service = LinkService::new(&state);
 
-    match link_service.delete_link(&user, link_id).await {
+    let result = if query.permanent {
+        link_service.permanent_delete_link(&user, link_id).await
+    } else {
+        link_service.delete_link(&user, link_id).await
+    };
+    match result {
         Ok(()) => StatusCode::NO_CONTENT.into_response(),
         Err(e) => e.into_response(),
     }
 }
 
+fn permanent_delete_confirmed(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONFIRM_PERMANENT_DELETE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.trim().eq_ignore_ascii_case("yes"))
+}
+
+/// Move a link between personal ownership and an organization
+/// POST /api/v1/links/:id/transfer
+#[utoipa::path(
+    post,
+    path = "/v1/links/{id}/transfer",
+    tag = "Links",
+    operation_id = "transferLink",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000")
+    ),
+    request_body = TransferLinkRequest,
+    responses(
+        (status = 200, description = "Link transferred", body = LinkResponse),
+        (status = 400, description = "Link already belongs to the destination", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - caller is not an owner or admin of the source or destination organization", body = ApiError),
+        (status = 404, description = "Link or destination organization not found", body = ApiError),
+        (status = 409, description = "Custom alias is taken by another link", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn transfer_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Json(request): Json<TransferLinkRequest>,
+) -> impl IntoResponse {
+    use crate::models::user::User;
+
+    // Get database connection
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    // Parse user_id from string to UUID
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    // Fetch the actual user from database
+    let user = match User::find_by_id(&mut conn, user_uuid).await {
+        Ok(user) => user,
+        Err(_) => return LinkError::NotFound.into_response(),
+    };
+
+    let link_service = LinkService::new(&state);
+
+    match link_service
+        .transfer_link(&user, link_id, request.organization_id)
+        .await
+    {
+        Ok(link_response) => Json(link_response).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Stop every link the caller owns from redirecting, e.g. after their site was compromised
+/// POST /api/v1/links/pause-all
+#[utoipa::path(
+    post,
+    path = "/v1/links/pause-all",
+    tag = "Links",
+    operation_id = "pauseAllLinks",
+    responses(
+        (status = 200, description = "Links paused; they answer like inactive links until resumed. \
+            Takes effect within 5 seconds on every server. Pausing again keeps the original `paused_at`", body = LinksPauseStatus),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn pause_all_links(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let service = LinkPauseService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+    match service.pause_all(user_uuid).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Let the caller's links redirect again after `pause-all`
+/// POST /api/v1/links/resume-all
+#[utoipa::path(
+    post,
+    path = "/v1/links/resume-all",
+    tag = "Links",
+    operation_id = "resumeAllLinks",
+    responses(
+        (status = 200, description = "Links resumed; links deactivated one by one stay inactive", body = LinksPauseStatus),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn resume_all_links(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let service = LinkPauseService::new(state.diesel_pool.clone(), state.redis_pool.clone());
+    match service.resume_all(user_uuid).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
 /// List user's links with filtering and pagination
 /// GET /api/v1/links
 #[utoipa::path(
@@ -288,11 +697,13 @@ pub async fn delete_link(
     operation_id = "listLinks",
     params(
         LinkPagination,
-        LinkFilter
+        LinkFilter,
+        ("If-None-Match" = Option<String>, Header, description = "ETag from a previous response; returns 304 if no link in the filtered set was added, removed or changed")
     ),
     responses(
-        (status = 200, description = "Links retrieved successfully", body = LinkListResponse),
-        (status = 401, description = "Unauthorized - invalid or missing token")
+        (status = 200, description = "Links retrieved successfully, with a weak `ETag` header", body = LinkListResponse),
+        (status = 304, description = "Not modified - the `If-None-Match` ETag is current"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -303,6 +714,7 @@ pub async fn list_links(
     Extension(auth_user): Extension<AuthenticatedUser>,
     Query(filter): Query<LinkFilter>,
     Query(pagination): Query<LinkPagination>,
+    headers: HeaderMap,
 ) -> impl IntoResponse {
     use crate::models::user::User;
 
@@ -320,14 +732,29 @@ pub async fn list_links(
         },
     };
 
+    let link_service = LinkService::new(&state);
+
+    // One aggregate query decides whether the page can be served as 304
+    let etag = match link_service
+        .user_links_fingerprint(user_uuid, &filter)
+        .await
+    {
+        Ok((last_updated, count)) => etag::weak_etag(&format!(
+            "links|{}|{:?}|{}|{}|{}",
+            user_uuid, last_updated, count, pagination.page, pagination.per_page
+        )),
+        Err(e) => return e.into_response(),
+    };
+    if etag::if_none_match(&headers, &etag) {
+        return etag::not_modified(&etag);
+    }
+
     // Fetch the actual user from database
     let user = match User::find_by_id(&mut conn, user_uuid).await {
         Ok(user) => user,
         Err(_) => return LinkError::NotFound.into_response(),
     };
 
-    let link_service = LinkService::new(&state);
-
     // Create ListLinksParams from filter and pagination
     let params = ListLinksParams {
         page: pagination.page as u32,
@@ -337,7 +764,7 @@ pub async fn list_links(
     };
 
     match link_service.get_user_links(&user, params).await {
-        Ok(response) => Json(response).into_response(),
+        Ok(response) => etag::with_etag(Json(response).into_response(), &etag),
         Err(e) => e.into_response(),
     }
 }
@@ -350,13 +777,15 @@ pub async fn list_links(
     tag = "Links",
     operation_id = "getLinkStats",
     params(
-        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000")
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        LinkStatsQuery
     ),
     responses(
-        (status = 200, description = "Link statistics retrieved successfully"),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 403, description = "Forbidden - not the link owner"),
-        (status = 404, description = "Link not found")
+        (status = 200, description = "Link statistics retrieved successfully; ranges older than the raw retention window are served from daily rollups", body = LinkStatsResponse),
+        (status = 400, description = "Invalid period, date range or timezone", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - not the link owner", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -366,17 +795,8 @@ pub async fn get_link_stats(
     State(state): State<AppState>,
     Extension(auth_user): Extension<AuthenticatedUser>,
     Path(link_id): Path<Uuid>,
+    Query(range_query): Query<LinkStatsQuery>,
 ) -> impl IntoResponse {
-    use crate::schema::links::dsl;
-    use diesel::prelude::*;
-    use diesel_async::RunQueryDsl;
-    use serde_json::json;
-
-    let mut conn = match state.diesel_pool.get().await {
-        Ok(conn) => conn,
-        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
-    };
-
     // Parse user_id from string to UUID
     let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
         Ok(id) => id,
@@ -385,28 +805,105 @@ pub async fn get_link_stats(
         },
     };
 
-    // Get link and verify ownership
-    let link = match dsl::links
-        .filter(dsl::id.eq(link_id))
-        .filter(dsl::user_id.eq(user_uuid))
-        .first::<crate::models::link::Link>(&mut conn)
-        .await
-    {
+    // Verify ownership or organization membership; dashboards fan out many stats calls,
+    // so the owner is cached briefly instead of queried each time
+    let link_service = LinkService::new(&state);
+    let link = match link_service.assert_ownership_cached(link_id, user_uuid).await {
+        Ok(owner) => link_service.get_owned_link(link_id, &owner).await,
+        Err(e) => Err(e),
+    };
+    let link = match link {
         Ok(link) => link,
-        Err(diesel::result::Error::NotFound) => return LinkError::NotFound.into_response(),
+        Err(ServiceError::NotFound) => return LinkError::NotFound.into_response(),
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    // Days are counted in the requested or preferred timezone
+    let tz = match resolve_timezone(&state.diesel_pool, user_uuid, range_query.tz.as_deref()).await
+    {
+        Ok(tz) => tz,
+        Err(ServiceError::ValidationError(message)) => {
+            return LinkError::BadRequest(message).into_response()
+        },
         Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
     };
 
+    // Optional period or date range; without one the all-time totals are returned
+    let today = today_in(tz);
+    let window = match range_query.into_window(date_in(link.created_at, tz), today) {
+        Ok(window) => window,
+        Err(message) => return LinkError::BadRequest(message).into_response(),
+    };
+
     // Query ClickHouse for real-time analytics using unified service
     let mut total_clicks = 0u64;
+    let mut raw_clicks = 0u64;
     let mut unique_visitors = 0u64;
     let mut bot_clicks = 0u64;
     let mut last_accessed = link.last_accessed_at;
+    let mut range_stats = None;
+    let mut comparison = None;
+    let mut entry_points = None;
 
     // Use the unified ClickHouseAnalyticsService if available
     if let Some(ref analytics) = state.clickhouse_analytics {
-        if let Some(stats) = analytics.get_link_stats(&link_id).await {
+        let raw_retention_days = state.config.clickhouse.raw_retention_days;
+        // Only raw events record which code a click came in through
+        let mut entry_point_range = None;
+        let stats = match window {
+            // Ranges reaching past raw retention are answered from daily rollups; when
+            // comparing, the previous period decides so both periods come from one source
+            Some(window) => {
+                let earliest = if window.compare {
+                    window.previous_period().0
+                } else {
+                    window.from
+                };
+                let source = crate::services::clickhouse_analytics::stats_source_for(
+                    earliest,
+                    today,
+                    raw_retention_days,
+                );
+                if source == crate::db::StatsSource::Raw {
+                    entry_point_range = Some((window.from, window.to));
+                }
+                match analytics
+                    .get_link_stats_for_range(&link_id, &window, source, tz)
+                    .await
+                {
+                    Ok((stats, breakdown, previous)) => {
+                        range_stats = Some(breakdown);
+                        comparison = previous;
+                        Some(stats)
+                    },
+                    Err(e) => {
+                        warn!("Failed to fetch range stats for {}: {}", link_id, e);
+                        None
+                    },
+                }
+            },
+            None => {
+                let created = date_in(link.created_at, tz);
+                let from = crate::services::clickhouse_analytics::raw_retention_cutoff(
+                    today,
+                    raw_retention_days,
+                )
+                .map_or(created, |cutoff| created.max(cutoff).min(today));
+                entry_point_range = Some((from, today));
+                analytics.get_link_stats(&link_id).await
+            },
+        };
+
+        if let Some((from, to)) = entry_point_range {
+            match analytics.get_entry_points(&link_id, from, to, tz).await {
+                Ok(split) => entry_points = Some(split),
+                Err(e) => warn!("Failed to fetch entry points for {}: {}", link_id, e),
+            }
+        }
+
+        if let Some(stats) = stats {
             total_clicks = stats.total_clicks;
+            raw_clicks = stats.raw_clicks;
             unique_visitors = stats.unique_visitors;
             bot_clicks = stats.bot_clicks;
             if let Some(last_click) = stats.last_accessed_at {
@@ -416,65 +913,430 @@ pub async fn get_link_stats(
     }
 
     // Build enhanced statistics response with ClickHouse data
-    let stats = json!({
-        "short_code": link.short_code,
-        "original_url": link.original_url,
-        "total_clicks": total_clicks,
-        "unique_visitors": unique_visitors,
-        "bot_clicks": bot_clicks,
-        "human_clicks": total_clicks - bot_clicks,
-        "created_at": link.created_at,
-        "last_accessed_at": last_accessed,
-        "is_active": link.is_active,
-        "days_active": (chrono::Utc::now() - link.created_at).num_days(),
-        "average_clicks_per_day": if (chrono::Utc::now() - link.created_at).num_days() > 0 {
-            total_clicks as f64 / (chrono::Utc::now() - link.created_at).num_days() as f64
+    let days_active = (chrono::Utc::now() - link.created_at).num_days();
+    // Averages cover the requested range when one was given
+    let average_days = match window {
+        Some(window) => window.days(),
+        None => days_active,
+    };
+    let stats = LinkStatsResponse {
+        short_code: link.short_code,
+        original_url: link.original_url,
+        total_clicks,
+        raw_clicks,
+        unique_visitors,
+        bot_clicks,
+        human_clicks: total_clicks.saturating_sub(bot_clicks),
+        created_at: link.created_at,
+        last_accessed_at: last_accessed,
+        is_active: link.is_active,
+        days_active,
+        average_clicks_per_day: if average_days > 0 {
+            total_clicks as f64 / average_days as f64
         } else {
             total_clicks as f64
         },
-        "conversion_rate": if total_clicks > 0 {
+        conversion_rate: if total_clicks > 0 {
             (unique_visitors as f64 / total_clicks as f64 * 100.0).round() / 100.0
         } else {
             0.0
-        }
-    });
+        },
+        range: range_stats,
+        comparison,
+        entry_points,
+    };
 
     Json(stats).into_response()
 }
 
-/// Check custom alias availability
-/// GET /api/v1/links/check-alias/:alias
+/// Get clicks per inbound campaign
+/// GET /api/v1/links/:id/stats/campaigns
+#[utoipa::path(
+    get,
+    path = "/v1/links/{id}/stats/campaigns",
+    tag = "Links",
+    operation_id = "getLinkCampaignStats",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        CampaignStatsQuery
+    ),
+    responses(
+        (status = 200, description = "Clicks grouped by the utm_campaign, utm_source and utm_medium of the referrer URL. Only raw events carry them, so ranges are cut to the raw retention window", body = LinkCampaignStatsResponse),
+        (status = 400, description = "Invalid period or date range", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError),
+        (status = 503, description = "Analytics temporarily unavailable", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_link_campaign_stats(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Query(range_query): Query<CampaignStatsQuery>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let link_service = LinkService::new(&state);
+    let link = match link_service.assert_ownership_cached(link_id, user_uuid).await {
+        Ok(owner) => link_service.get_owned_link(link_id, &owner).await,
+        Err(e) => Err(e),
+    };
+    let link = match link {
+        Ok(link) => link,
+        Err(ServiceError::NotFound) => return LinkError::NotFound.into_response(),
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let (from, to) = match range_query.into_range(link.created_at.date_naive(), today) {
+        Ok(range) => range,
+        Err(message) => return LinkError::BadRequest(message).into_response(),
+    };
+    // Referrer UTM parameters are not kept in the daily rollups
+    let from = match crate::services::clickhouse_analytics::raw_retention_cutoff(
+        today,
+        state.config.clickhouse.raw_retention_days,
+    ) {
+        Some(cutoff) => from.max(cutoff).min(to),
+        None => from,
+    };
+
+    let campaigns = match state.clickhouse_analytics {
+        Some(ref analytics) => match analytics.get_referrer_campaigns(&link_id, from, to).await {
+            Ok(campaigns) => campaigns,
+            Err(e) => {
+                warn!("Failed to fetch campaign stats for {}: {}", link_id, e);
+                return LinkError::ServiceUnavailable.into_response();
+            },
+        },
+        // ClickHouse not configured: nothing has been recorded
+        None => Vec::new(),
+    };
+
+    Json(LinkCampaignStatsResponse {
+        from,
+        to,
+        campaigns,
+    })
+    .into_response()
+}
+
+/// Get conversions recorded by the link's conversion pixel
+/// GET /api/v1/links/:id/stats/conversions
+#[utoipa::path(
+    get,
+    path = "/v1/links/{id}/stats/conversions",
+    tag = "Links",
+    operation_id = "getLinkConversionStats",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        CampaignStatsQuery
+    ),
+    responses(
+        (status = 200, description = "Conversions recorded by GET /v1/pixel/{link_id}.gif, the clicks over the same range, conversions per 100 clicks and a daily series", body = LinkConversionStatsResponse),
+        (status = 400, description = "Invalid period or date range", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError),
+        (status = 503, description = "Analytics temporarily unavailable", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_link_conversion_stats(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Query(range_query): Query<CampaignStatsQuery>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let link_service = LinkService::new(&state);
+    let link = match link_service
+        .assert_ownership_cached(link_id, user_uuid)
+        .await
+    {
+        Ok(owner) => link_service.get_owned_link(link_id, &owner).await,
+        Err(e) => Err(e),
+    };
+    let link = match link {
+        Ok(link) => link,
+        Err(ServiceError::NotFound) => return LinkError::NotFound.into_response(),
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    let today = chrono::Utc::now().date_naive();
+    let (from, to) = match range_query.into_range(link.created_at.date_naive(), today) {
+        Ok(range) => range,
+        Err(message) => return LinkError::BadRequest(message).into_response(),
+    };
+
+    let stats = match state.clickhouse_analytics {
+        Some(ref analytics) => {
+            let source = crate::services::clickhouse_analytics::stats_source_for(
+                from,
+                today,
+                state.config.clickhouse.raw_retention_days,
+            );
+            match analytics
+                .get_link_conversions(&link_id, from, to, source)
+                .await
+            {
+                Ok(stats) => stats,
+                Err(e) => {
+                    warn!("Failed to fetch conversion stats for {}: {}", link_id, e);
+                    return LinkError::ServiceUnavailable.into_response();
+                },
+            }
+        },
+        // ClickHouse not configured: nothing has been recorded
+        None => LinkConversionStatsResponse {
+            from,
+            to,
+            clicks: 0,
+            conversions: 0,
+            unique_converters: 0,
+            conversion_rate: None,
+            daily: Vec::new(),
+        },
+    };
+
+    Json(stats).into_response()
+}
+
+/// Appeal a quarantined link
+/// POST /api/v1/links/:id/appeal
+#[utoipa::path(
+    post,
+    path = "/v1/links/{id}/appeal",
+    tag = "Links",
+    operation_id = "appealLink",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000")
+    ),
+    request_body = AppealLinkRequest,
+    responses(
+        (status = 200, description = "Appeal submitted - link flagged for admin review", body = LinkResponse),
+        (status = 400, description = "Bad request - link is not quarantined", body = ApiError),
+        (status = 422, description = "Validation failed - `details` lists each invalid field", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError),
+        (status = 409, description = "Conflict - an appeal is already pending or was rejected", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn appeal_link(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Json(request): Json<AppealLinkRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = request.validate() {
+        return ApiError::from(e).into_response();
+    }
+
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let quarantine_service = QuarantineService::new(&state);
+    let base_url = &state.config.short_link_base_url;
+
+    match quarantine_service
+        .submit_appeal(user_uuid, link_id, &request.message)
+        .await
+    {
+        Ok(link) => Json(link.to_response(base_url)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Get the stored security scan for a link, optionally re-scanning it first
+/// GET /api/v1/links/:id/security
+#[utoipa::path(
+    get,
+    path = "/v1/links/{id}/security",
+    tag = "Links",
+    operation_id = "getLinkSecurity",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        LinkSecurityQuery
+    ),
+    responses(
+        (status = 200, description = "Stored (or freshly run) security scan", body = LinkSecurityResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError),
+        (status = 429, description = "Too many requests - re-scan rate limit exceeded", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_link_security(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Query(query): Query<LinkSecurityQuery>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let link_service = LinkService::new(&state);
+
+    let link = match link_service.get_link_by_id_and_user(link_id, user_uuid).await {
+        Ok(link) => link,
+        Err(ServiceError::NotFound) => return LinkError::NotFound.into_response(),
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    if !query.rescan {
+        return Json(LinkSecurityResponse::from_link(&link, false)).into_response();
+    }
+
+    // Fresh scans hit external threat intel services, so they are rate limited per user
+    if state.config.enable_rate_limiting {
+        let rate_limit_key = format!("link_rescan:{}", user_uuid);
+        match state
+            .rate_limit_service
+            .check_rate_limit_with_config(&rate_limit_key, &RateLimitConfig::link_rescan())
+            .await
+        {
+            Ok(status) if !status.allowed => {
+                return LinkError::RateLimitExceeded {
+                    retry_after: u64::from(status.retry_after.unwrap_or(60)),
+                }
+                .into_response();
+            },
+            Err(e) => warn!("Rate limit check failed for link re-scan: {}", e),
+            _ => {},
+        }
+    }
+
+    let result = match link_service.scan_link(&link).await {
+        Ok(result) => result,
+        Err(e) => {
+            error!("On-demand scan of link {} failed: {}", link_id, e);
+            return LinkError::ServiceUnavailable.into_response();
+        },
+    };
+
+    // Same path as the background re-scan, so a bad result quarantines the link
+    if let Err(e) = QuarantineService::new(&state)
+        .record_scan(&link, &result)
+        .await
+    {
+        return LinkError::DatabaseError(e.to_string()).into_response();
+    }
+
+    match link_service.get_link_by_id_and_user(link_id, user_uuid).await {
+        Ok(link) => Json(LinkSecurityResponse::from_link(&link, true)).into_response(),
+        Err(e) => LinkError::DatabaseError(e.to_string()).into_response(),
+    }
+}
+
+/// Change history of a link: who changed which fields, and when
+/// GET /api/v1/links/:id/history
+#[utoipa::path(
+    get,
+    path = "/v1/links/{id}/history",
+    tag = "Links",
+    operation_id = "getLinkHistory",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        LinkHistoryQuery
+    ),
+    responses(
+        (status = 200, description = "Page of the link's revisions, newest first", body = LinkHistoryResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Forbidden - not a member of the link's organization", body = ApiError),
+        (status = 404, description = "Link not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_link_history(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(link_id): Path<Uuid>,
+    Query(query): Query<LinkHistoryQuery>,
+) -> impl IntoResponse {
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    let link_service = LinkService::new(&state);
+
+    match link_service
+        .get_link_history(link_id, user_uuid, &query)
+        .await
+    {
+        Ok(history) => Json(history).into_response(),
+        Err(ServiceError::NotFound) => LinkError::NotFound.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Check custom alias availability
+/// GET /api/v1/links/check-alias/:alias
 #[utoipa::path(
     get,
     path = "/v1/links/check-alias/{alias}",
     tag = "Links",
     operation_id = "checkAliasAvailability",
     params(
-        ("alias" = String, Path, description = "Custom alias to check")
+        ("alias" = String, Path, description = "Custom alias to check; matched case-insensitively"),
+        CheckAliasQuery
     ),
     responses(
-        (status = 200, description = "Alias is available", body = CheckAliasResponse),
-        (status = 409, description = "Alias is taken - suggestions provided", body = CheckAliasResponse),
-        (status = 400, description = "Invalid alias format")
+        (status = 200, description = "Alias is available; with `reserve=true` it is held for the caller until `reserved_until`", body = CheckAliasResponse),
+        (status = 409, description = "Alias is taken or reserved by another user - suggestions provided", body = CheckAliasResponse),
+        (status = 400, description = "Invalid alias format", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
     )
 )]
 pub async fn check_alias_availability(
     State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
     Path(alias): Path<String>,
+    Query(query): Query<CheckAliasQuery>,
 ) -> impl IntoResponse {
     use crate::services::short_code::ShortCodeGenerator;
-    use serde_json::json;
+    use crate::utils::custom_alias_validator::CustomAliasValidator;
+
+    // Aliases are stored trimmed and lowercase, so check the form that would be created
+    let alias = CustomAliasValidator::normalize(&alias);
 
     // Validate alias format
     if alias.is_empty() || alias.len() > 20 {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Invalid alias: must be 1-20 characters",
-                "available": false
-            })),
-        )
-            .into_response();
+        return LinkError::InvalidAlias("must be 1-20 characters".to_string()).into_response();
     }
 
     // Check for invalid characters (only allow alphanumeric, dash, underscore, dot)
@@ -482,31 +1344,48 @@ pub async fn check_alias_availability(
         .chars()
         .all(|c| c.is_alphanumeric() || c == '-' || c == '_' || c == '.');
     if !valid_chars {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Invalid alias: only letters, numbers, dash, underscore, and dot allowed",
-                "available": false
-            })),
+        return LinkError::InvalidAlias(
+            "only letters, numbers, dash, underscore, and dot allowed".to_string(),
         )
-            .into_response();
+        .into_response();
     }
 
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
     // Initialize short code generator
     let generator =
         ShortCodeGenerator::with_redis(state.diesel_pool.clone(), Some(state.redis_pool.clone()));
+    let link_service = LinkService::new(&state);
 
-    // Check if alias is available
-    match generator.is_code_unique(&alias).await {
+    // Check if alias is available, then take the reservation if asked; SET NX decides
+    // between two callers that both saw it free
+    let available: Result<bool, ServiceError> = match generator.is_code_unique(&alias).await {
+        Ok(true) if query.reserve => link_service.reserve_alias(&alias, user_id).await,
+        Ok(true) => Ok(!link_service.alias_reserved_by_other(&alias, user_id).await),
+        Ok(false) => Ok(false),
+        Err(e) => Err(e.into()),
+    };
+
+    match available {
         Ok(true) => {
             // Alias is available
+            let reserved_until = query.reserve.then(|| {
+                chrono::Utc::now() + chrono::Duration::seconds(ALIAS_RESERVATION_TTL_SECONDS as i64)
+            });
             (
                 StatusCode::OK,
-                Json(json!({
-                    "available": true,
-                    "alias": alias,
-                    "message": "This alias is available!"
-                })),
+                Json(CheckAliasResponse {
+                    available: true,
+                    message: Some("This alias is available!".to_string()),
+                    alias: Some(alias),
+                    reserved_until,
+                    ..Default::default()
+                }),
             )
                 .into_response()
         },
@@ -516,120 +1395,51 @@ pub async fn check_alias_availability(
 
             (
                 StatusCode::CONFLICT,
-                Json(json!({
-                    "available": false,
-                    "alias": alias,
-                    "message": format!("'{}' is already taken", alias),
-                    "suggestions": suggestions,
-                    "suggestion_message": "Try one of these available alternatives:"
-                })),
+                Json(CheckAliasResponse {
+                    available: false,
+                    message: Some(format!("'{}' is already taken", alias)),
+                    alias: Some(alias),
+                    suggestions: Some(suggestions),
+                    suggestion_message: Some(
+                        "Try one of these available alternatives:".to_string(),
+                    ),
+                    reserved_until: None,
+                }),
             )
                 .into_response()
         },
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(json!({
-                "error": format!("Failed to check alias: {}", e),
-                "available": false
-            })),
-        )
-            .into_response(),
+        Err(e) => {
+            error!("Failed to check alias availability: {}", e);
+            ApiError::internal("Failed to check alias").into_response()
+        },
     }
 }
 
-/// Create custom short link
+/// Retired: create the link with `POST /v1/links` and `custom_alias`
 /// POST /api/v1/links/custom
 #[utoipa::path(
     post,
     path = "/v1/links/custom",
     tag = "Links",
     operation_id = "createCustomLink",
-    request_body = CreateCustomLinkRequest,
     responses(
-        (status = 200, description = "Custom link created", body = CreateCustomLinkResponse),
-        (status = 401, description = "Unauthorized")
+        (status = 410, description = "Gone - create the link with `POST /v1/links` and `custom_alias`; check the alias first with `GET /v1/links/check-alias/{alias}`", body = ApiError),
+        (status = 401, description = "Unauthorized", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
     )
 )]
-pub async fn create_custom_link(
-    State(state): State<AppState>,
-    Extension(_auth_user): Extension<AuthenticatedUser>,
-    Json(request): Json<serde_json::Value>,
-) -> impl IntoResponse {
-    use crate::services::short_code::ShortCodeGenerator;
-    use serde_json::json;
-
-    // Extract parameters
-    let prefix = request.get("prefix").and_then(|v| v.as_str()).unwrap_or("");
-
-    let style = request
-        .get("style")
-        .and_then(|v| v.as_str())
-        .unwrap_or("discord"); // discord, readable, random
-
-    // Initialize generator
-    let generator =
-        ShortCodeGenerator::with_redis(state.diesel_pool.clone(), Some(state.redis_pool.clone()));
-
-    // Generate code based on style
-    let mut generated_code = match style {
-        "discord" => {
-            // Discord-style: 7-8 character random alphanumeric
-            let mut code = String::new();
-            let chars = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
-            let mut rng = thread_rng();
-            for _ in 0..7 {
-                let idx = rng.gen_range(0..chars.len());
-                code.push(chars.chars().nth(idx).unwrap());
-            }
-            code
-        },
-        "readable" => {
-            // Readable: word-word-number format
-            let words = vec![
-                "happy", "swift", "bright", "cool", "smart", "quick", "fresh", "bold",
-            ];
-            let mut rng = thread_rng();
-            let word1 = words[rng.gen_range(0..words.len())];
-            let word2 = words[rng.gen_range(0..words.len())];
-            let num = rng.gen_range(10..99);
-            format!("{}-{}-{}", word1, word2, num)
-        },
-        _ => {
-            // Random: Use existing generator
-            generator.generate_random_code(6)
-        },
-    };
-
-    // Add prefix if provided
-    if !prefix.is_empty() && prefix.len() <= 10 {
-        generated_code = format!("{}-{}", prefix, generated_code);
-    }
-
-    // Ensure uniqueness
-    let mut attempts = 0;
-    while attempts < 10 {
-        match generator.is_code_unique(&generated_code).await {
-            Ok(true) => break,
-            _ => {
-                // Regenerate with different random
-                let suffix = thread_rng().gen_range(100..999);
-                generated_code = format!("{}{}", generated_code, suffix);
-                attempts += 1;
-            },
-        }
-    }
-
-    Json(json!({
-        "short_code": generated_code,
-        "style": style,
-        "message": "Custom short link created successfully",
-        "available": true,
-        "short_url": format!("https://qck.sh/{}", generated_code)
-    }))
-    .into_response()
+pub async fn create_custom_link() -> impl IntoResponse {
+    // Only ever suggested a code without creating a link, which POST /v1/links with
+    // custom_alias (plus the check-alias suggestions) already covers
+    ApiError::new(
+        StatusCode::GONE,
+        "ENDPOINT_REMOVED",
+        "POST /v1/links/custom has been removed. Create the link with POST /v1/links and \
+         custom_alias; GET /v1/links/check-alias/{alias} checks availability and suggests \
+         alternatives",
+    )
 }
 
 /// Bulk create links
@@ -639,12 +1449,16 @@ pub async fn create_custom_link(
     path = "/v1/links/bulk",
     tag = "Links",
     operation_id = "bulkCreateLinks",
+    params(
+        ("Idempotency-Key" = Option<String>, Header, description = "Client-chosen key (1-255 visible ASCII characters); retries with the same key and body replay the original response for 24h")
+    ),
     request_body(content = Vec<CreateLinkRequest>, description = "Array of link creation requests (max 100)"),
     responses(
-        (status = 207, description = "Multi-status - partial success (some links created, some failed)"),
-        (status = 400, description = "Bad request - invalid request format or too many links"),
-        (status = 401, description = "Unauthorized - invalid or missing token"),
-        (status = 429, description = "Too many requests - rate limit exceeded")
+        (status = 207, description = "Multi-status - partial success (some links created, some failed)", body = BulkCreateResponse, headers(("X-Link-Limit-Warning" = String, description = "Present once the user holds 80% of MAX_LINKS_PER_USER, e.g. `count=812, limit=1000`"))),
+        (status = 400, description = "Bad request - invalid request format or too many links", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 409, description = "Conflict - Idempotency-Key reused with a different body or still in progress", body = ApiError),
+        (status = 429, description = "Too many requests - rate limit exceeded", body = ApiError)
     ),
     security(
         ("bearerAuth" = [])
@@ -705,10 +1519,10 @@ pub async fn bulk_create_links(
     for (index, request) in requests.into_iter().enumerate() {
         // Validate each request
         if let Err(e) = request.validate() {
-            errors.push(serde_json::json!({
-                "index": index,
-                "error": e.to_string()
-            }));
+            errors.push(BulkCreateItemError {
+                index,
+                error: e.to_string(),
+            });
             continue;
         }
 
@@ -735,27 +1549,217 @@ pub async fn bulk_create_links(
                     requested_count,
                     e
                 );
-                errors.push(serde_json::json!({
-                    "index": index,
-                    "error": e.to_string()
-                }));
+                errors.push(BulkCreateItemError {
+                    index,
+                    error: e.to_string(),
+                });
             },
             Err(_) => {
                 error!("Timeout creating link {} of {}", index + 1, requested_count);
-                errors.push(serde_json::json!({
-                    "index": index,
-                    "error": "Request timeout - link creation took too long"
-                }));
+                errors.push(BulkCreateItemError {
+                    index,
+                    error: "Request timeout - link creation took too long".to_string(),
+                });
             },
         }
     }
 
-    let response = serde_json::json!({
-        "success": results.len(),
-        "failed": errors.len(),
-        "links": results,
-        "errors": errors,
-    });
+    let response = BulkCreateResponse {
+        success: results.len(),
+        failed: errors.len(),
+        links: results,
+        errors,
+    };
+
+    let response = (StatusCode::MULTI_STATUS, Json(response)).into_response();
+    with_link_limit_warning(&link_service, user.id, response).await
+}
+
+/// Activate or deactivate several personal links
+/// PATCH /api/v1/links/bulk
+#[utoipa::path(
+    patch,
+    path = "/v1/links/bulk",
+    tag = "Links",
+    operation_id = "bulkUpdateLinkStatus",
+    request_body = BulkStatusRequest,
+    responses(
+        (status = 207, description = "Multi-status - an outcome for each link ID: updated, not_found, forbidden or already_in_state", body = BulkStatusResponse),
+        (status = 400, description = "Bad request - no IDs or more than 100", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn bulk_update_link_status(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<BulkStatusRequest>,
+) -> impl IntoResponse {
+    use crate::models::user::User;
+
+    if request.ids.is_empty() {
+        return LinkError::BadRequest("No links provided".to_string()).into_response();
+    }
+
+    // Get database connection
+    let mut conn = match state.diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => return LinkError::DatabaseError(e.to_string()).into_response(),
+    };
+
+    // Parse user_id from string to UUID
+    let user_uuid = match uuid::Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return LinkError::BadRequest("Invalid user ID format".to_string()).into_response()
+        },
+    };
+
+    // Fetch the actual user from database
+    let user = match User::find_by_id(&mut conn, user_uuid).await {
+        Ok(user) => user,
+        Err(_) => return LinkError::NotFound.into_response(),
+    };
+    drop(conn);
+
+    let link_service = LinkService::new(&state);
+
+    match link_service
+        .bulk_update_status(&user, request.ids, request.is_active)
+        .await
+    {
+        Ok(results) => (
+            StatusCode::MULTI_STATUS,
+            Json(BulkStatusResponse::from(results)),
+        )
+            .into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Conversion pixel: a 1x1 transparent GIF that records a conversion for the link
+/// GET /api/v1/pixel/:link_id.gif
+/// Public and embedded on the destination site. The conversion is attributed through the
+/// `qck_vid` cookie the redirect set for this pixel's path; without it nothing is recorded.
+/// Never touches Postgres: the hit is queued for ClickHouse and the GIF is served at once
+#[utoipa::path(
+    get,
+    path = "/v1/pixel/{link_id}.gif",
+    tag = "Links",
+    operation_id = "getConversionPixel",
+    params(
+        ("link_id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        ("Cookie" = Option<String>, Header, description = "`qck_vid` visitor hash set by the link's redirect, scoped to this path")
+    ),
+    responses(
+        (status = 200, description = "1x1 transparent GIF, whether or not a conversion was recorded",
+            content_type = "image/gif", body = String,
+            headers(("Cache-Control" = String, description = "`no-store`, so every page view reaches the server"))),
+        (status = 404, description = "Link ID is not a UUID", body = ApiError),
+        (status = 429, description = "Too many conversions from this visitor", body = ApiError,
+            headers(("Retry-After" = u64, description = "Seconds until the visitor is counted again")))
+    )
+)]
+pub async fn get_conversion_pixel(
+    State(state): State<AppState>,
+    Path(file): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let Some(link_id) = file
+        .strip_suffix(".gif")
+        .and_then(|id| Uuid::parse_str(id).ok())
+    else {
+        return LinkError::NotFound.into_response();
+    };
+
+    if let (Some(visitor_hash), Some(analytics)) = (
+        conversion_tracking::visitor_hash_from_cookies(&headers),
+        state.clickhouse_analytics.as_ref(),
+    ) {
+        if state.config.enable_rate_limiting {
+            let rate_limit_key = format!("conversion_pixel:{}", visitor_hash);
+            match state
+                .rate_limit_service
+                .check_rate_limit_with_config(&rate_limit_key, &RateLimitConfig::conversion_pixel())
+                .await
+            {
+                Ok(status) if !status.allowed => {
+                    return LinkError::RateLimitExceeded {
+                        retry_after: u64::from(status.retry_after.unwrap_or(60)),
+                    }
+                    .into_response();
+                },
+                Err(e) => warn!("Rate limit check failed for conversion pixel: {}", e),
+                _ => {},
+            }
+        }
+
+        conversion_tracking::record_conversion(
+            &analytics.client(),
+            conversion_tracking::ConversionEvent {
+                timestamp: chrono::Utc::now(),
+                link_id,
+                visitor_hash: visitor_hash.to_string(),
+            },
+        );
+    }
+
+    (
+        [
+            (header::CONTENT_TYPE, "image/gif"),
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        conversion_tracking::TRANSPARENT_GIF,
+    )
+        .into_response()
+}
 
-    (StatusCode::MULTI_STATUS, Json(response)).into_response()
+/// Open Graph image for a link: title, destination domain, owner branding and a QR code
+/// GET /api/v1/links/:id/og-image
+/// Public so that social network crawlers can fetch it; the link ID is the only key
+#[utoipa::path(
+    get,
+    path = "/v1/links/{id}/og-image",
+    tag = "Links",
+    operation_id = "getLinkOgImage",
+    params(
+        ("id" = Uuid, Path, description = "Link ID (UUID)", example = "123e4567-e89b-12d3-a456-426614174000"),
+        OgImageQuery
+    ),
+    responses(
+        (status = 200, description = "1200x630 card image", content(
+            ("image/png" = String),
+            ("image/svg+xml" = String)
+        ), headers(("Cache-Control" = String, description = "Public; reusable for OG_IMAGE_CACHE_TTL seconds"))),
+        (status = 404, description = "Link not found, deleted or quarantined", body = ApiError)
+    )
+)]
+pub async fn get_link_og_image(
+    State(state): State<AppState>,
+    Path(link_id): Path<Uuid>,
+    Query(query): Query<OgImageQuery>,
+) -> impl IntoResponse {
+    match OgImageService::new(&state)
+        .image(link_id, query.format)
+        .await
+    {
+        Ok(image) => (
+            [
+                (
+                    header::CONTENT_TYPE,
+                    query.format.content_type().to_string(),
+                ),
+                (
+                    header::CACHE_CONTROL,
+                    format!("public, max-age={}", state.config.og_image_cache_ttl),
+                ),
+            ],
+            image,
+        )
+            .into_response(),
+        Err(ServiceError::NotFound) => LinkError::NotFound.into_response(),
+        Err(e) => e.into_response(),
+    }
 }
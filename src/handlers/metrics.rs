@@ -0,0 +1,269 @@
+// Operational metrics endpoints
+// Mounted behind require_metrics_access: operators only, via admin JWT or METRICS_TOKEN
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app::AppState,
+    config::rate_limit::EmergencySettings,
+    middleware::request_timeout::{request_timeout_metrics, RequestTimeoutMetrics},
+    services::{
+        background_tasks::{BackgroundTaskMetrics, TASK_REGISTRY},
+        click_counter::{ClickCounterMetrics, CLICK_COUNTER},
+        metadata_throttle::{MetadataExtractionMetrics, METADATA_THROTTLE},
+        refresh_token_cleanup::{RefreshTokenCleanupMetrics, REFRESH_TOKEN_CLEANUP},
+        MonitoringStats, RateLimitMetrics, RateLimitOffender,
+    },
+    utils::urlhaus_client::{self, ThreatFeedMetrics},
+};
+
+/// Offenders reported per endpoint
+const TOP_OFFENDERS_PER_ENDPOINT: usize = 10;
+/// Metrics window when none is requested
+const DEFAULT_METRICS_WINDOW_MINUTES: u64 = 60;
+/// Longest metrics window, matching the retention of `rate_limit_events`
+const MAX_METRICS_WINDOW_MINUTES: u64 = 90 * 24 * 60;
+
+/// Query parameters for GET /v1/metrics/rate-limiting
+#[derive(Debug, Default, Deserialize)]
+pub struct RateLimitMetricsQuery {
+    /// Minutes up to now (1 to 90 days); defaults to 60
+    pub window_minutes: Option<u64>,
+}
+
+impl RateLimitMetricsQuery {
+    /// Requested window, clamped to what can be served
+    pub fn window_minutes(&self) -> u64 {
+        self.window_minutes
+            .unwrap_or(DEFAULT_METRICS_WINDOW_MINUTES)
+            .clamp(1, MAX_METRICS_WINDOW_MINUTES)
+    }
+}
+
+/// Rate limiting analytics, monitoring and emergency state
+#[derive(Debug, Serialize)]
+pub struct RateLimitMetricsResponse {
+    pub timestamp: String,
+    pub analytics_enabled: bool,
+    /// Aggregated metrics for the requested window; `None` when analytics sampling is disabled.
+    /// `metrics.backend` tells whether they come from this instance's memory or ClickHouse.
+    pub metrics: Option<RateLimitMetrics>,
+    pub monitoring: Option<MonitoringStats>,
+    /// Most-blocked rate limit keys, keyed by endpoint
+    pub top_offenders: HashMap<String, Vec<RateLimitOffender>>,
+    pub emergency_mode: EmergencyModeStatus,
+}
+
+/// Whether the global emergency limit is overriding per-endpoint limits
+#[derive(Debug, Serialize, PartialEq)]
+pub struct EmergencyModeStatus {
+    pub active: bool,
+    pub limit: Option<u32>,
+    pub window_seconds: u32,
+    pub whitelisted_ips: usize,
+    pub blacklisted_ips: usize,
+}
+
+impl From<&EmergencySettings> for EmergencyModeStatus {
+    fn from(settings: &EmergencySettings) -> Self {
+        Self {
+            active: settings.emergency_limit.is_some(),
+            limit: settings.emergency_limit,
+            window_seconds: settings.emergency_window,
+            whitelisted_ips: settings.whitelist_ips.len(),
+            blacklisted_ips: settings.blacklist_ips.len(),
+        }
+    }
+}
+
+/// Rate limiting metrics for operators
+/// GET /v1/metrics/rate-limiting
+pub async fn rate_limit_metrics(
+    State(state): State<AppState>,
+    Query(query): Query<RateLimitMetricsQuery>,
+) -> Json<RateLimitMetricsResponse> {
+    let metrics = state
+        .rate_limit_service
+        .get_analytics_metrics(
+            query.window_minutes(),
+            state.clickhouse_analytics.as_deref(),
+        )
+        .await;
+    let monitoring = state.rate_limit_service.get_monitoring_stats().await;
+    let top_offenders = state
+        .rate_limit_service
+        .get_top_offenders(TOP_OFFENDERS_PER_ENDPOINT)
+        .unwrap_or_default();
+
+    Json(RateLimitMetricsResponse {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        analytics_enabled: metrics.is_some(),
+        metrics,
+        monitoring,
+        top_offenders,
+        emergency_mode: EmergencyModeStatus::from(&state.rate_limit_config.global.emergency),
+    })
+}
+
+/// Metadata extraction outcomes for operators
+/// GET /v1/metrics/metadata-extraction
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/metadata-extraction",
+    tag = "Health",
+    operation_id = "metadataExtractionMetrics",
+    responses(
+        (status = 200, description = "Extraction outcome counters since process start and hosts whose circuit breaker is open", body = MetadataExtractionMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn metadata_extraction_metrics() -> Json<MetadataExtractionMetrics> {
+    Json(METADATA_THROTTLE.metrics())
+}
+
+/// Redirect click aggregation for operators
+/// GET /v1/metrics/click-counting
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/click-counting",
+    tag = "Health",
+    operation_id = "clickCountingMetrics",
+    responses(
+        (status = 200, description = "Clicks pending and flushed to Redis since process start, and failed flushes", body = ClickCounterMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn click_counting_metrics() -> Json<ClickCounterMetrics> {
+    Json(CLICK_COUNTER.metrics())
+}
+
+/// Refresh token pruning for operators
+/// GET /v1/metrics/refresh-token-cleanup
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/refresh-token-cleanup",
+    tag = "Health",
+    operation_id = "refreshTokenCleanupMetrics",
+    responses(
+        (status = 200, description = "Cleanup runs and refresh token rows pruned by this instance since process start", body = RefreshTokenCleanupMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn refresh_token_cleanup_metrics() -> Json<RefreshTokenCleanupMetrics> {
+    Json(REFRESH_TOKEN_CLEANUP.metrics())
+}
+
+/// Request timeouts per route group for operators
+/// GET /v1/metrics/request-timeouts
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/request-timeouts",
+    tag = "Health",
+    operation_id = "requestTimeoutMetrics",
+    responses(
+        (status = 200, description = "Requests answered with 504 per route group since process start", body = RequestTimeoutMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn request_timeouts_metrics() -> Json<RequestTimeoutMetrics> {
+    Json(request_timeout_metrics())
+}
+
+/// URLhaus threat feed state for operators
+/// GET /v1/metrics/threat-feed
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/threat-feed",
+    tag = "Health",
+    operation_id = "threatFeedMetrics",
+    responses(
+        (status = 200, description = "Feed freshness and the last update by any instance: entries, bytes downloaded, URLs added and removed, duration and when the entries last changed", body = ThreatFeedMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn threat_feed_metrics(State(state): State<AppState>) -> Json<ThreatFeedMetrics> {
+    Json(urlhaus_client::threat_feed_metrics(&state.redis_pool).await)
+}
+
+/// Background task status for operators
+/// GET /v1/metrics/background-tasks
+#[utoipa::path(
+    get,
+    path = "/v1/metrics/background-tasks",
+    tag = "Health",
+    operation_id = "backgroundTaskMetrics",
+    responses(
+        (status = 200, description = "Last start, success and error of every background task on this instance and on every instance reporting to Redis", body = BackgroundTaskMetrics),
+        (status = 401, description = "Missing or invalid token", body = ApiError),
+        (status = 403, description = "Token lacks the instance admin scope", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn background_task_metrics(State(state): State<AppState>) -> Json<BackgroundTaskMetrics> {
+    Json(TASK_REGISTRY.metrics(&state.redis_pool).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit_metrics_window() {
+        let window = |window_minutes| RateLimitMetricsQuery { window_minutes }.window_minutes();
+        assert_eq!(window(None), 60);
+        assert_eq!(window(Some(1440)), 1440);
+        assert_eq!(window(Some(0)), 1);
+        assert_eq!(window(Some(u64::MAX)), MAX_METRICS_WINDOW_MINUTES);
+    }
+
+    #[test]
+    fn test_emergency_mode_status() {
+        let mut settings = EmergencySettings {
+            emergency_limit: None,
+            emergency_window: 60,
+            whitelist_ips: vec!["10.0.0.1".to_string()],
+            blacklist_ips: vec![],
+        };
+        assert!(!EmergencyModeStatus::from(&settings).active);
+
+        settings.emergency_limit = Some(5);
+        assert_eq!(
+            EmergencyModeStatus::from(&settings),
+            EmergencyModeStatus {
+                active: true,
+                limit: Some(5),
+                window_seconds: 60,
+                whitelisted_ips: 1,
+                blacklisted_ips: 0,
+            }
+        );
+    }
+}
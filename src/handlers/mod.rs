@@ -4,14 +4,23 @@
 // DEV-68: Link Management API handlers
 // DEV-105: Link management handlers
 
+pub mod admin;
+pub mod analytics;
+pub mod audit_logs;
 pub mod auth;
 pub mod docs; // Modular documentation structure
+pub mod fallback;
 pub mod links;
+pub mod metrics;
+pub mod onboarding;
+pub mod organizations;
+pub mod pages;
 pub mod redirect;
+pub mod settings;
 
 use crate::app::AppState;
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, patch, post},
     Router,
 };
 
@@ -23,12 +32,208 @@ pub fn public_auth_routes() -> Router<AppState> {
         .route("/refresh", post(auth::refresh_token))
         .route("/forgot-password", post(auth::forgot_password))
         .route("/reset-password", post(auth::reset_password))
+        .route("/export/download", get(auth::download_account_export))
+        .route("/unlock", get(auth::unlock_account))
 }
 
 // Protected authentication routes (require JWT auth middleware)
 pub fn protected_auth_routes() -> Router<AppState> {
     Router::new()
         .route("/logout", post(auth::logout))
+        .route("/sessions/revoke-all", post(auth::revoke_all_sessions))
+        .route("/security-events", get(auth::list_security_events))
         .route("/me", get(auth::get_current_user))
+        .route(
+            "/me/preferences",
+            get(auth::get_preferences).put(auth::update_preferences),
+        )
+        .route("/me/export", get(auth::export_account))
         .route("/validate", post(auth::validate_token))
 }
+
+// Link creation routes (require JWT authentication, then idempotency_middleware)
+// They honour an Idempotency-Key header so client retries don't duplicate links. The
+// middleware takes State<AppState>, so it is layered where the router is assembled.
+pub fn link_creation_routes() -> Router<AppState> {
+    Router::new()
+        .route("/links", post(links::create_link))
+        .route("/links/bulk", post(links::bulk_create_links))
+}
+
+// Link management routes (all require JWT authentication)
+// Shares the /links and /links/bulk paths with link_creation_routes, for other methods
+pub fn link_routes() -> Router<AppState> {
+    Router::new()
+        .route("/links", get(links::list_links))
+        .route("/links/bulk", patch(links::bulk_update_link_status))
+        .route(
+            "/links/check-alias/{alias}",
+            get(links::check_alias_availability),
+        )
+        .route("/links/quick", post(links::quick_create_link))
+        .route("/links/validate", post(links::validate_link))
+        .route("/links/pause-all", post(links::pause_all_links))
+        .route("/links/resume-all", post(links::resume_all_links))
+        .route("/links/custom", post(links::create_custom_link))
+        .route(
+            "/links/{id}",
+            get(links::get_link)
+                .put(links::update_link)
+                .delete(links::delete_link),
+        )
+        .route("/links/{id}/stats", get(links::get_link_stats))
+        .route(
+            "/links/{id}/stats/campaigns",
+            get(links::get_link_campaign_stats),
+        )
+        .route(
+            "/links/{id}/stats/conversions",
+            get(links::get_link_conversion_stats),
+        )
+        .route("/links/{id}/history", get(links::get_link_history))
+        .route("/links/{id}/transfer", post(links::transfer_link))
+        .route("/links/{id}/appeal", post(links::appeal_link))
+        .route("/links/{id}/security", get(links::get_link_security))
+}
+
+// Public link assets fetched by crawlers and browsers (no auth middleware)
+pub fn public_link_routes() -> Router<AppState> {
+    Router::new()
+        .route("/links/{id}/og-image", get(links::get_link_og_image))
+        // `{file}` is `{link_id}.gif`; the router can't match a parameter with a suffix
+        .route("/pixel/{file}", get(links::get_conversion_pixel))
+}
+
+// Public short URL routes at the root (qck.sh/abc123)
+// Unsupported methods get an HTML page here, unlike the JSON 405 of the API routes
+pub fn redirect_routes() -> Router<AppState> {
+    Router::new()
+        // Fixed root paths, registered ahead of the short code routes
+        .route("/", get(redirect::root))
+        .route("/favicon.ico", get(redirect::favicon))
+        .route("/robots.txt", get(redirect::robots_txt))
+        .route("/{short_code}", get(redirect::redirect_to_url))
+        .route("/{short_code}/preview", get(redirect::preview_url))
+        .route("/{short_code}/unlock", post(redirect::unlock_link))
+        // Path-passthrough links; the static routes above take precedence
+        .route("/{short_code}/{*sub_path}", get(redirect::redirect_with_path))
+        .method_not_allowed_fallback(redirect::method_not_allowed)
+}
+
+// Per-user audit trail routes (require JWT auth middleware)
+pub fn audit_log_routes() -> Router<AppState> {
+    Router::new().route("/audit-logs", get(audit_logs::list_my_audit_logs))
+}
+
+// Account-level analytics routes (require JWT auth middleware)
+pub fn analytics_routes() -> Router<AppState> {
+    Router::new()
+        .route("/analytics/summary", get(analytics::get_usage_summary))
+        .route("/usage", get(analytics::get_api_usage))
+}
+
+// Onboarding progress routes (require JWT auth middleware)
+pub fn onboarding_routes() -> Router<AppState> {
+    Router::new()
+        .route("/onboarding/status", get(onboarding::get_onboarding_status))
+        .route("/onboarding/step", post(onboarding::advance_onboarding))
+        .route("/onboarding/complete", post(onboarding::complete_onboarding))
+}
+
+// Account settings routes (require JWT auth middleware)
+pub fn settings_routes() -> Router<AppState> {
+    Router::new().route(
+        "/settings/notifications",
+        get(settings::get_notification_preferences).put(settings::update_notification_preferences),
+    )
+}
+
+// Organization routes (require JWT auth middleware)
+pub fn organization_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/organizations",
+            get(organizations::list_organizations).post(organizations::create_organization),
+        )
+        .route(
+            "/organizations/invitations/accept",
+            post(organizations::accept_invitation),
+        )
+        .route(
+            "/organizations/{id}/invitations",
+            post(organizations::invite_member),
+        )
+        .route("/organizations/{id}/members", get(organizations::list_members))
+        .route(
+            "/organizations/{id}/members/{user_id}",
+            delete(organizations::remove_member),
+        )
+}
+
+// Landing page routes (require JWT auth middleware)
+pub fn page_routes() -> Router<AppState> {
+    Router::new()
+        .route("/pages", get(pages::list_pages).post(pages::create_page))
+        .route(
+            "/pages/{id}",
+            get(pages::get_page)
+                .put(pages::update_page)
+                .delete(pages::delete_page),
+        )
+}
+
+// Operational metrics routes (require admin JWT or METRICS_TOKEN via require_metrics_access)
+pub fn metrics_routes() -> Router<AppState> {
+    Router::new()
+        .route("/rate-limiting", get(metrics::rate_limit_metrics))
+        .route("/metadata-extraction", get(metrics::metadata_extraction_metrics))
+        .route("/click-counting", get(metrics::click_counting_metrics))
+        .route("/background-tasks", get(metrics::background_task_metrics))
+        .route("/request-timeouts", get(metrics::request_timeouts_metrics))
+        .route("/threat-feed", get(metrics::threat_feed_metrics))
+        .route(
+            "/refresh-token-cleanup",
+            get(metrics::refresh_token_cleanup_metrics),
+        )
+}
+
+// Operator-only admin routes (require JWT auth + instance admin scope)
+pub fn admin_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/blocked-domains",
+            get(admin::list_blocked_domains).post(admin::create_blocked_domain),
+        )
+        .route(
+            "/blocked-domains/{id}",
+            axum::routing::delete(admin::delete_blocked_domain),
+        )
+        .route(
+            "/trusted-domains",
+            get(admin::list_trusted_domains).post(admin::create_trusted_domain),
+        )
+        .route(
+            "/trusted-domains/{id}",
+            axum::routing::delete(admin::delete_trusted_domain),
+        )
+        .route("/link-appeals", get(admin::list_link_appeals))
+        .route("/link-appeals/{id}", post(admin::resolve_link_appeal))
+        .route("/audit-logs", get(admin::list_audit_logs))
+        .route("/security-events", get(admin::list_security_events))
+        .route("/stats", get(admin::get_instance_stats))
+        .route("/perf", get(admin::get_latency_metrics))
+        .route("/self-test", post(admin::run_self_test_handler))
+        .route("/security/metrics", get(admin::get_security_metrics))
+        .route("/rate-limits/offenders", get(admin::get_rate_limit_offenders))
+        .route(
+            "/rate-limits/denylist",
+            get(admin::list_denied_ips).post(admin::create_denied_ip),
+        )
+        .route(
+            "/rate-limits/denylist/{id}",
+            axum::routing::delete(admin::delete_denied_ip),
+        )
+        .route("/users/import", post(admin::import_user))
+        .route("/users/{id}/impersonate", post(admin::impersonate_user))
+        .route("/users/{id}/deactivate", post(admin::deactivate_user))
+}
@@ -0,0 +1,118 @@
+// Onboarding endpoints
+// OSS deployments register users as completed, so these only ever report `completed` there
+
+use axum::{
+    extract::{Extension, State},
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    models::user::OnboardingStepRequest,
+    services::onboarding::OnboardingService,
+    utils::service_error::ServiceError,
+};
+
+fn parse_user_id(auth_user: &AuthenticatedUser) -> Result<Uuid, ServiceError> {
+    Uuid::parse_str(&auth_user.user_id)
+        .map_err(|_| ServiceError::ValidationError("Invalid user ID format".to_string()))
+}
+
+/// Get the authenticated user's onboarding status
+/// GET /v1/onboarding/status
+#[utoipa::path(
+    get,
+    path = "/v1/onboarding/status",
+    tag = "Onboarding",
+    operation_id = "getOnboardingStatus",
+    responses(
+        (status = 200, description = "Current onboarding status and the next step", body = OnboardingStatusResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_onboarding_status(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match parse_user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match OnboardingService::new(&state).status(user_id).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Move onboarding to the next status
+/// POST /v1/onboarding/step
+#[utoipa::path(
+    post,
+    path = "/v1/onboarding/step",
+    tag = "Onboarding",
+    operation_id = "advanceOnboarding",
+    request_body = OnboardingStepRequest,
+    responses(
+        (status = 200, description = "Status persisted; repeating the current status is a no-op", body = OnboardingStatusResponse),
+        (status = 400, description = "Transition not allowed, e.g. from completed back to registered", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn advance_onboarding(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<OnboardingStepRequest>,
+) -> impl IntoResponse {
+    let user_id = match parse_user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match OnboardingService::new(&state)
+        .advance(user_id, request.onboarding_status)
+        .await
+    {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Finish onboarding
+/// POST /v1/onboarding/complete
+#[utoipa::path(
+    post,
+    path = "/v1/onboarding/complete",
+    tag = "Onboarding",
+    operation_id = "completeOnboarding",
+    responses(
+        (status = 200, description = "Onboarding completed; idempotent", body = OnboardingStatusResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn complete_onboarding(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match parse_user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match OnboardingService::new(&state).complete(user_id).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,281 @@
+// Organization (team account) endpoints
+// Organization links are managed through the regular /v1/links endpoints
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    models::{
+        organization::{
+            AcceptInvitationRequest, CreateOrganizationRequest, InviteMemberRequest,
+            OrganizationMemberListResponse,
+        },
+        user::User,
+    },
+    services::organization::OrganizationService,
+    utils::service_error::ServiceError,
+};
+
+/// Load the authenticated user's account
+async fn current_user(
+    state: &AppState,
+    auth_user: &AuthenticatedUser,
+) -> Result<User, ServiceError> {
+    let user_id = Uuid::parse_str(&auth_user.user_id)
+        .map_err(|_| ServiceError::ValidationError("Invalid user ID format".to_string()))?;
+
+    let mut conn = state
+        .diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+    User::find_by_id(&mut conn, user_id)
+        .await
+        .map_err(|_| ServiceError::Unauthorized)
+}
+
+/// Create an organization owned by the authenticated user
+/// POST /v1/organizations
+#[utoipa::path(
+    post,
+    path = "/v1/organizations",
+    tag = "Organizations",
+    operation_id = "createOrganization",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, description = "Organization created; the caller is its owner", body = OrganizationResponse),
+        (status = 400, description = "Invalid name", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateOrganizationRequest>,
+) -> impl IntoResponse {
+    let user = match current_user(&state, &auth_user).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response(),
+    };
+
+    match OrganizationService::new(&state)
+        .create(&user, request)
+        .await
+    {
+        Ok(organization) => (StatusCode::CREATED, Json(organization)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// List the organizations the authenticated user belongs to
+/// GET /v1/organizations
+#[utoipa::path(
+    get,
+    path = "/v1/organizations",
+    tag = "Organizations",
+    operation_id = "listOrganizations",
+    responses(
+        (status = 200, description = "Organizations with the caller's role in each", body = Vec<OrganizationResponse>),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_organizations(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    match OrganizationService::new(&state)
+        .list_for_user(user_id)
+        .await
+    {
+        Ok(organizations) => Json(organizations).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Invite someone to an organization by email
+/// POST /v1/organizations/{id}/invitations
+#[utoipa::path(
+    post,
+    path = "/v1/organizations/{id}/invitations",
+    tag = "Organizations",
+    operation_id = "inviteOrganizationMember",
+    params(
+        ("id" = Uuid, Path, description = "Organization ID")
+    ),
+    request_body = InviteMemberRequest,
+    responses(
+        (status = 201, description = "Invitation emailed; the token is only sent to the invitee", body = OrganizationInvitationResponse),
+        (status = 400, description = "Invalid email or role", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Caller is not an owner or admin", body = ApiError),
+        (status = 404, description = "Organization not found", body = ApiError),
+        (status = 409, description = "Already a member", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn invite_member(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(organization_id): Path<Uuid>,
+    Json(request): Json<InviteMemberRequest>,
+) -> impl IntoResponse {
+    let user = match current_user(&state, &auth_user).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response(),
+    };
+
+    match OrganizationService::new(&state)
+        .invite(&user, organization_id, request)
+        .await
+    {
+        Ok(invitation) => (StatusCode::CREATED, Json(invitation)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Accept an emailed invitation
+/// POST /v1/organizations/invitations/accept
+#[utoipa::path(
+    post,
+    path = "/v1/organizations/invitations/accept",
+    tag = "Organizations",
+    operation_id = "acceptOrganizationInvitation",
+    request_body = AcceptInvitationRequest,
+    responses(
+        (status = 200, description = "Joined the organization", body = OrganizationResponse),
+        (status = 400, description = "Invitation expired", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Invitation was sent to a different email address", body = ApiError),
+        (status = 404, description = "Unknown invitation token", body = ApiError),
+        (status = 409, description = "Invitation already accepted", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<AcceptInvitationRequest>,
+) -> impl IntoResponse {
+    let user = match current_user(&state, &auth_user).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response(),
+    };
+
+    match OrganizationService::new(&state)
+        .accept_invitation(&user, &request.token)
+        .await
+    {
+        Ok(organization) => Json(organization).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// List an organization's members
+/// GET /v1/organizations/{id}/members
+#[utoipa::path(
+    get,
+    path = "/v1/organizations/{id}/members",
+    tag = "Organizations",
+    operation_id = "listOrganizationMembers",
+    params(
+        ("id" = Uuid, Path, description = "Organization ID")
+    ),
+    responses(
+        (status = 200, description = "Members and their roles", body = OrganizationMemberListResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Organization not found or caller is not a member", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_members(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(organization_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    match OrganizationService::new(&state)
+        .list_members(organization_id, user_id)
+        .await
+    {
+        Ok(members) => Json(OrganizationMemberListResponse { members }).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Remove a member from an organization
+/// DELETE /v1/organizations/{id}/members/{user_id}
+#[utoipa::path(
+    delete,
+    path = "/v1/organizations/{id}/members/{user_id}",
+    tag = "Organizations",
+    operation_id = "removeOrganizationMember",
+    params(
+        ("id" = Uuid, Path, description = "Organization ID"),
+        ("user_id" = Uuid, Path, description = "Member to remove")
+    ),
+    responses(
+        (status = 204, description = "Member removed; their organization links stay with the organization"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 403, description = "Caller is not an owner or admin, or the member is the owner", body = ApiError),
+        (status = 404, description = "Organization or member not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path((organization_id, member_id)): Path<(Uuid, Uuid)>,
+) -> impl IntoResponse {
+    let user_id = match Uuid::parse_str(&auth_user.user_id) {
+        Ok(id) => id,
+        Err(_) => {
+            return ServiceError::ValidationError("Invalid user ID format".to_string())
+                .into_response()
+        },
+    };
+
+    match OrganizationService::new(&state)
+        .remove_member(user_id, organization_id, member_id)
+        .await
+    {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
@@ -0,0 +1,198 @@
+// Landing page (link-in-bio) endpoints
+// Pages are served publicly at their short code by the redirect handler
+
+use axum::{
+    extract::{Extension, Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    models::page::{CreatePageRequest, UpdatePageRequest},
+    services::page::PageService,
+    utils::service_error::ServiceError,
+};
+
+fn user_id(auth_user: &AuthenticatedUser) -> Result<Uuid, ServiceError> {
+    Uuid::parse_str(&auth_user.user_id)
+        .map_err(|_| ServiceError::ValidationError("Invalid user ID format".to_string()))
+}
+
+/// Create a landing page listing some of the authenticated user's links
+/// POST /v1/pages
+#[utoipa::path(
+    post,
+    path = "/v1/pages",
+    tag = "Pages",
+    operation_id = "createPage",
+    request_body = CreatePageRequest,
+    responses(
+        (status = 201, description = "Page created and served at its short code", body = PageResponse),
+        (status = 400, description = "Invalid title, short code or link list", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 409, description = "Short code already taken by a link or page", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn create_page(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreatePageRequest>,
+) -> impl IntoResponse {
+    let user_id = match user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match PageService::new(&state).create(user_id, request).await {
+        Ok(page) => (StatusCode::CREATED, Json(page)).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// List the authenticated user's landing pages
+/// GET /v1/pages
+#[utoipa::path(
+    get,
+    path = "/v1/pages",
+    tag = "Pages",
+    operation_id = "listPages",
+    responses(
+        (status = 200, description = "Pages, newest first", body = Vec<PageResponse>),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn list_pages(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match PageService::new(&state).list(user_id).await {
+        Ok(pages) => Json(pages).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Get one of the authenticated user's landing pages
+/// GET /v1/pages/{id}
+#[utoipa::path(
+    get,
+    path = "/v1/pages/{id}",
+    tag = "Pages",
+    operation_id = "getPage",
+    params(
+        ("id" = Uuid, Path, description = "Page ID")
+    ),
+    responses(
+        (status = 200, description = "The page", body = PageResponse),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Page not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_page(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(page_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let user_id = match user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match PageService::new(&state).get(user_id, page_id).await {
+        Ok(page) => Json(page).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Update a landing page's title, description, theme or links
+/// PUT /v1/pages/{id}
+#[utoipa::path(
+    put,
+    path = "/v1/pages/{id}",
+    tag = "Pages",
+    operation_id = "updatePage",
+    params(
+        ("id" = Uuid, Path, description = "Page ID")
+    ),
+    request_body = UpdatePageRequest,
+    responses(
+        (status = 200, description = "Page updated; the public page reflects it immediately", body = PageResponse),
+        (status = 400, description = "Invalid title or link list", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Page not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_page(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(page_id): Path<Uuid>,
+    Json(request): Json<UpdatePageRequest>,
+) -> impl IntoResponse {
+    let user_id = match user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match PageService::new(&state)
+        .update(user_id, page_id, request)
+        .await
+    {
+        Ok(page) => Json(page).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Delete a landing page; the links it listed are kept
+/// DELETE /v1/pages/{id}
+#[utoipa::path(
+    delete,
+    path = "/v1/pages/{id}",
+    tag = "Pages",
+    operation_id = "deletePage",
+    params(
+        ("id" = Uuid, Path, description = "Page ID")
+    ),
+    responses(
+        (status = 204, description = "Page deleted; its short code is free again"),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError),
+        (status = 404, description = "Page not found", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn delete_page(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Path(page_id): Path<Uuid>,
+) -> impl IntoResponse {
+    let user_id = match user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match PageService::new(&state).delete(user_id, page_id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => e.into_response(),
+    }
+}
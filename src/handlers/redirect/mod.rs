@@ -2,33 +2,227 @@
 // This is where the magic happens - turning short codes into destinations!
 
 mod pages;
-use pages::processing_page;
+use pages::{
+    escape_html, landing_page, method_not_allowed_page, preview_page, processing_page,
+    quarantined_page, referrer_blocked_page, short_code_path, too_many_attempts_page,
+};
 
 use axum::{
     extract::{ConnectInfo, Path, State},
-    http::{header, HeaderMap, StatusCode},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     response::{IntoResponse, Redirect, Response},
+    Form,
 };
-use std::net::SocketAddr;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::net::{IpAddr, SocketAddr};
 use std::time::Instant;
-use tracing::{info, warn};
+use tracing::{error, info, warn};
 
-use crate::{app::AppState, services::link::LinkService, utils::service_error::ServiceError};
+use crate::{
+    app::AppState,
+    models::link::{LinkPreviewResponse, TrackingMode, UnlockLinkForm},
+    services::{
+        click_tracking::{ClickContext, ClickedUrl},
+        conversion_tracking,
+        link::{LinkService, UnlockOutcome},
+        link_unlock::LinkUnlockService,
+        page::PageService,
+    },
+    utils::{
+        api_error::ApiError,
+        audit_logger::{AuditAction, AuditLogger},
+        custom_alias_validator::is_reserved_path_segment,
+        link_errors::LinkError,
+        safe_redirect::safe_internal_redirect,
+        service_error::ServiceError,
+    },
+};
+
+// =============================================================================
+// ROOT PATHS
+// =============================================================================
+
+/// Contents of FAVICON_PATH, read on first request; None when unset or unreadable
+static FAVICON: Lazy<Option<Vec<u8>>> = Lazy::new(|| {
+    let path = &crate::CONFIG.favicon_path;
+    if path.is_empty() {
+        return None;
+    }
+    match std::fs::read(path) {
+        Ok(icon) => Some(icon),
+        Err(e) => {
+            warn!("Failed to read FAVICON_PATH {}: {}", path, e);
+            None
+        },
+    }
+});
+
+/// Instance root: redirects to ROOT_REDIRECT_URL, or a minimal landing page when unset
+/// GET /
+#[utoipa::path(
+    get,
+    path = "/",
+    tag = "Redirect",
+    operation_id = "instanceRoot",
+    responses(
+        (status = 200, description = "Minimal landing page when ROOT_REDIRECT_URL is unset",
+            content_type = "text/html", body = String),
+        (status = 307, description = "Redirect to ROOT_REDIRECT_URL",
+            headers(("Location" = String, description = "ROOT_REDIRECT_URL"))),
+        (status = 403, description = "Client IP is on the denylist", body = ApiError)
+    )
+)]
+pub async fn root(State(state): State<AppState>) -> Response {
+    let target = &state.config.root_redirect_url;
+    if !target.is_empty() {
+        return Redirect::temporary(target).into_response();
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        landing_page(),
+    )
+        .into_response()
+}
+
+/// Instance favicon from FAVICON_PATH
+/// GET /favicon.ico
+#[utoipa::path(
+    get,
+    path = "/favicon.ico",
+    tag = "Redirect",
+    operation_id = "favicon",
+    responses(
+        (status = 200, description = "Contents of FAVICON_PATH",
+            content_type = "image/x-icon", body = Vec<u8>),
+        (status = 204, description = "No favicon configured"),
+        (status = 403, description = "Client IP is on the denylist", body = ApiError)
+    )
+)]
+pub async fn favicon() -> Response {
+    let cache = (header::CACHE_CONTROL, "public, max-age=86400");
+    match FAVICON.as_deref() {
+        Some(icon) => (
+            [(header::CONTENT_TYPE, "image/x-icon"), cache],
+            icon.to_vec(),
+        )
+            .into_response(),
+        None => (StatusCode::NO_CONTENT, [cache]).into_response(),
+    }
+}
+
+/// Crawler rules from ROBOTS_TXT; by default short codes are not crawled
+/// GET /robots.txt
+#[utoipa::path(
+    get,
+    path = "/robots.txt",
+    tag = "Redirect",
+    operation_id = "robotsTxt",
+    responses(
+        (status = 200, description = "Contents of ROBOTS_TXT",
+            content_type = "text/plain", body = String),
+        (status = 403, description = "Client IP is on the denylist", body = ApiError)
+    )
+)]
+pub async fn robots_txt(State(state): State<AppState>) -> Response {
+    (
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::CACHE_CONTROL, "public, max-age=3600"),
+        ],
+        state.config.robots_txt.clone(),
+    )
+        .into_response()
+}
 
 // =============================================================================
 // REDIRECT HANDLER
 // =============================================================================
 
-/// Handle redirect for short URLs with full click tracking
+/// Handle redirect for short URLs with click tracking
+/// How much visitor data is recorded depends on the link's `tracking_mode`
 /// GET /r/:short_code
+#[utoipa::path(
+    get,
+    path = "/{short_code}",
+    tag = "Redirect",
+    operation_id = "redirectToUrl",
+    params(
+        ("short_code" = String, Path, description = "Short code or custom alias", example = "abc123"),
+        ("Referer" = Option<String>, Header, description = "Checked against the link's `allowed_referrers` and `blocked_referrers`; may be omitted unless the link has `referrer_strict`"),
+        ("DNT" = Option<String>, Header, description = "`1` disables IP, user agent and referrer collection on `respect_dnt` links"),
+        ("Sec-GPC" = Option<String>, Header, description = "Global Privacy Control; treated like `DNT: 1`")
+    ),
+    responses(
+        (status = 200, description = "Landing page for a short code that belongs to a page",
+            content_type = "text/html", body = String),
+        (status = 301, description = "Permanent redirect to the original URL",
+            headers(("Location" = String, description = "The original URL"))),
+        (status = 401, description = "Link is password protected (HTML page)"),
+        (status = 403, description = "Link is quarantined for security review, or its referrer restrictions refuse the `Referer` (HTML page)"),
+        (status = 404, description = "Short code not found (HTML page)"),
+        (status = 410, description = "Link has expired (HTML page)"),
+        (status = 503, description = "Link is still being processed (HTML page)")
+    )
+)]
 pub async fn redirect_to_url(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Path(short_code): Path<String>,
 ) -> Response {
+    redirect(&state, addr, &headers, &short_code, None).await
+}
+
+/// Redirect a path-passthrough link, appending the rest of the path to its destination
+/// GET /r/:short_code/*sub_path
+#[utoipa::path(
+    get,
+    path = "/{short_code}/{sub_path}",
+    tag = "Redirect",
+    operation_id = "redirectWithPath",
+    params(
+        ("short_code" = String, Path, description = "Short code or custom alias of a link with `path_passthrough` enabled", example = "docs"),
+        ("sub_path" = String, Path, description = "Path appended to the destination; may span several segments. Cannot start with `preview` or `unlock`", example = "guides/getting-started"),
+        ("Referer" = Option<String>, Header, description = "Checked against the link's `allowed_referrers` and `blocked_referrers`; may be omitted unless the link has `referrer_strict`"),
+        ("DNT" = Option<String>, Header, description = "`1` disables IP, user agent and referrer collection on `respect_dnt` links"),
+        ("Sec-GPC" = Option<String>, Header, description = "Global Privacy Control; treated like `DNT: 1`")
+    ),
+    responses(
+        (status = 301, description = "Permanent redirect to the original URL with the sub-path appended",
+            headers(("Location" = String, description = "The original URL with the sub-path appended"))),
+        (status = 401, description = "Link is password protected (HTML page)"),
+        (status = 403, description = "Link is quarantined for security review, or its referrer restrictions refuse the `Referer` (HTML page)"),
+        (status = 404, description = "Short code not found, or the link doesn't pass paths through (HTML page)"),
+        (status = 410, description = "Link has expired (HTML page)"),
+        (status = 503, description = "Link is still being processed (HTML page)")
+    )
+)]
+pub async fn redirect_with_path(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path((short_code, sub_path)): Path<(String, String)>,
+) -> Response {
+    redirect(&state, addr, &headers, &short_code, Some(&sub_path)).await
+}
+
+/// Shared by both redirect routes; `sub_path` is only set on the passthrough route
+async fn redirect(
+    state: &AppState,
+    addr: SocketAddr,
+    headers: &HeaderMap,
+    short_code: &str,
+    sub_path: Option<&str>,
+) -> Response {
+    // Probes such as /favicon.ico/x never name a link; skip the lookup and its negative cache
+    if is_reserved_path_segment(short_code) {
+        return path_not_found(short_code);
+    }
+
     let start_time = Instant::now();
-    let link_service = LinkService::new(&state);
+    let link_service = LinkService::new(state);
 
     // Extract request details for click tracking
     let user_agent = headers
@@ -41,130 +235,452 @@ pub async fn redirect_to_url(
     let method = "GET";
 
     // Process the redirect
-    let (_status_code, response) = match link_service.process_redirect(&short_code).await {
-        Ok((link_id, original_url)) => {
-            info!("Redirecting {} to {}", short_code, original_url);
+    match link_service
+        .resolve_redirect_with_path(short_code, sub_path, referrer)
+        .await
+    {
+        Ok(redirect) if redirect.blocked_by_referrer => {
+            warn!("Refused referrer for {}: {:?}", short_code, referrer);
 
-            // Track click event to ClickHouse (fire-and-forget)
             let response_time = start_time.elapsed().as_millis() as u16;
             link_service.track_click_event(
-                link_id,
-                addr.ip(),
-                user_agent,
-                referrer,
-                method,
-                response_time,
-                StatusCode::MOVED_PERMANENTLY.as_u16(),
+                redirect.link_id,
+                redirect.tracking_mode,
+                ClickedUrl {
+                    short_code,
+                    via_alias: redirect.via_alias,
+                    sub_path: sub_path.unwrap_or_default(),
+                    blocked_by_referrer: true,
+                },
+                ClickContext {
+                    ip: addr.ip(),
+                    user_agent,
+                    referrer,
+                    method,
+                    response_time,
+                    status_code: StatusCode::FORBIDDEN.as_u16(),
+                    opted_out: privacy_opt_out(headers),
+                },
             );
 
-            // Use permanent redirect (301) for better SEO
             (
-                StatusCode::MOVED_PERMANENTLY,
-                Redirect::permanent(&original_url).into_response(),
+                StatusCode::FORBIDDEN,
+                [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                referrer_blocked_page(short_code),
             )
+                .into_response()
         },
-        Err(ServiceError::NotFound) => {
-            warn!("Short code not found: {}", short_code);
-            (
-                StatusCode::NOT_FOUND,
-                (
-                    StatusCode::NOT_FOUND,
+        Ok(redirect) => {
+            info!("Redirecting {} to {}", short_code, redirect.destination);
+
+            // Permanent redirect (301) by default for better SEO
+            let status = StatusCode::from_u16(redirect.redirect_type)
+                .ok()
+                .filter(StatusCode::is_redirection)
+                .unwrap_or(StatusCode::MOVED_PERMANENTLY);
+
+            // Track click event to ClickHouse (fire-and-forget)
+            let response_time = start_time.elapsed().as_millis() as u16;
+            link_service.track_click_event(
+                redirect.link_id,
+                redirect.tracking_mode,
+                ClickedUrl {
+                    short_code,
+                    via_alias: redirect.via_alias,
+                    sub_path: sub_path.unwrap_or_default(),
+                    blocked_by_referrer: false,
+                },
+                ClickContext {
+                    ip: addr.ip(),
+                    user_agent,
+                    referrer,
+                    method,
+                    response_time,
+                    status_code: status.as_u16(),
+                    opted_out: privacy_opt_out(headers),
+                },
+            );
+
+            let mut response =
+                (status, [(header::LOCATION, redirect.destination.clone())]).into_response();
+            if let Some(cookie) = conversion_cookie(
+                state,
+                &link_service,
+                redirect.link_id,
+                redirect.tracking_mode,
+                headers,
+                addr.ip(),
+                user_agent,
+            ) {
+                response.headers_mut().insert(header::SET_COOKIE, cookie);
+            }
+            response
+        },
+        Err(ServiceError::NotFound) if sub_path.is_none() => {
+            // The short code may belong to a landing page rather than a link
+            match PageService::new(state).render(short_code).await {
+                Ok(Some(html)) => (
+                    StatusCode::OK,
                     [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
-                    not_found_page(&short_code),
+                    html,
                 )
                     .into_response(),
-            )
+                Ok(None) => redirect_error_response(short_code, ServiceError::NotFound),
+                Err(e) => redirect_error_response(short_code, e),
+            }
         },
-        Err(ServiceError::Expired) => {
+        Err(e) => redirect_error_response(short_code, e),
+    }
+}
+
+/// HTML page for a short code that can't be redirected right now
+fn redirect_error_response(short_code: &str, error: ServiceError) -> Response {
+    let (status, page) = match error {
+        ServiceError::NotFound => {
+            warn!("Short code not found: {}", short_code);
+            (StatusCode::NOT_FOUND, not_found_page(short_code))
+        },
+        ServiceError::Expired => {
             warn!("Link expired: {}", short_code);
-            (
-                StatusCode::GONE,
-                (
-                    StatusCode::GONE,
-                    [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
-                    expired_page(&short_code),
-                )
-                    .into_response(),
-            )
+            (StatusCode::GONE, expired_page(short_code))
         },
-        Err(ServiceError::Inactive) => {
+        ServiceError::Inactive => {
             warn!("Link inactive or still processing: {}", short_code);
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                (
-                    StatusCode::SERVICE_UNAVAILABLE,
-                    [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
-                    processing_page(&short_code),
-                )
-                    .into_response(),
+            (StatusCode::SERVICE_UNAVAILABLE, processing_page(short_code))
+        },
+        ServiceError::Quarantined => {
+            warn!("Blocked redirect for quarantined link: {}", short_code);
+            (StatusCode::FORBIDDEN, quarantined_page(short_code))
+        },
+        ServiceError::PasswordRequired => (
+            StatusCode::UNAUTHORIZED,
+            password_required_page(short_code, None),
+        ),
+        e => {
+            warn!("Error processing redirect for {}: {:?}", short_code, e);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "An error occurred processing your request",
             )
+                .into_response();
         },
-        Err(ServiceError::PasswordRequired) => {
-            // In production, this would redirect to a password entry page
+    };
+
+    (
+        status,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        page,
+    )
+        .into_response()
+}
+
+/// Submit the password for a protected link
+/// Wrong passwords are limited per (link, IP); the link owner sees failed attempts
+/// and lockouts in their audit log
+/// POST /:short_code/unlock
+#[utoipa::path(
+    post,
+    path = "/{short_code}/unlock",
+    tag = "Redirect",
+    operation_id = "unlockLink",
+    params(
+        ("short_code" = String, Path, description = "Short code or custom alias", example = "abc123")
+    ),
+    request_body(
+        content = UnlockLinkForm,
+        content_type = "application/x-www-form-urlencoded",
+        description = "Submitted by the form on the password page"
+    ),
+    responses(
+        (status = 303, description = "Correct password; redirects to the original URL",
+            headers(("Location" = String, description = "The original URL"))),
+        (status = 401, description = "Wrong password; the password page again with the attempts left (HTML page)"),
+        (status = 403, description = "Link is quarantined for security review (HTML page)"),
+        (status = 404, description = "Short code not found (HTML page)"),
+        (status = 410, description = "Link has expired (HTML page)"),
+        (status = 429, description = "Too many wrong passwords from this IP (HTML page)",
+            headers(("Retry-After" = u64, description = "Seconds until this IP may try again"))),
+        (status = 503, description = "Link is still being processed (HTML page)")
+    )
+)]
+pub async fn unlock_link(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Path(short_code): Path<String>,
+    Form(form): Form<UnlockLinkForm>,
+) -> Response {
+    if is_reserved_path_segment(&short_code) {
+        return path_not_found(&short_code);
+    }
+
+    let start_time = Instant::now();
+    let ip = addr.ip();
+    let unlocks = LinkUnlockService::new(&state);
+    let link_service = LinkService::new(&state);
+
+    // Attempts count against the link, however its code or alias is spelled
+    let link_id = match link_service.resolve_code_meta(&short_code).await {
+        Ok(meta) => meta.link_id,
+        Err(e) => return redirect_error_response(&short_code, e),
+    };
+    if let Err(blocked) = unlocks.begin(link_id, ip).await {
+        return too_many_attempts_response(&short_code, blocked.retry_after_seconds());
+    }
+
+    let user_agent = headers
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok());
+
+    match link_service.process_unlock(&short_code, &form.password).await {
+        Ok(UnlockOutcome::Unlocked {
+            link_id,
+            original_url,
+            tracking_mode,
+            via_alias,
+        }) => {
+            unlocks.record_success(link_id, ip).await;
+            info!("Unlocked {} for {}", short_code, ip);
+
+            let response_time = start_time.elapsed().as_millis() as u16;
+            link_service.track_click_event(
+                link_id,
+                tracking_mode,
+                ClickedUrl {
+                    short_code: &short_code,
+                    via_alias,
+                    sub_path: "",
+                    blocked_by_referrer: false,
+                },
+                ClickContext {
+                    ip,
+                    user_agent: user_agent.unwrap_or("Unknown"),
+                    referrer: headers.get(header::REFERER).and_then(|v| v.to_str().ok()),
+                    method: "POST",
+                    response_time,
+                    status_code: StatusCode::SEE_OTHER.as_u16(),
+                    opted_out: privacy_opt_out(&headers),
+                },
+            );
+
+            // 303 so the browser follows with a GET, and only ever to the link's own destination
+            match safe_internal_redirect(&original_url, Some(&original_url)) {
+                Some(location) => {
+                    let mut response = Redirect::to(&location).into_response();
+                    if let Some(cookie) = conversion_cookie(
+                        &state,
+                        &link_service,
+                        link_id,
+                        tracking_mode,
+                        &headers,
+                        ip,
+                        user_agent.unwrap_or("Unknown"),
+                    ) {
+                        response.headers_mut().insert(header::SET_COOKIE, cookie);
+                    }
+                    response
+                },
+                None => {
+                    error!("Refusing to redirect {} to {}", short_code, original_url);
+                    (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "An error occurred processing your request",
+                    )
+                        .into_response()
+                },
+            }
+        },
+        Ok(UnlockOutcome::WrongPassword { link_id, owner_id }) => {
+            let (attempts, locked_for) = unlocks.record_failure(link_id, ip).await;
+            let ip_address = ip.to_string();
+
+            AuditLogger::log_visitor_action(
+                AuditAction::LinkPasswordFailed,
+                owner_id,
+                link_id,
+                &ip_address,
+                user_agent,
+                json!({
+                    "short_code": short_code,
+                    "locked_out": locked_for.is_some(),
+                }),
+            );
+
+            if let Some(lockout_seconds) = locked_for {
+                warn!(
+                    "Locked {} out of {} for {}s after repeated wrong passwords",
+                    ip, short_code, lockout_seconds
+                );
+                AuditLogger::log_visitor_action(
+                    AuditAction::LinkPasswordLockout,
+                    owner_id,
+                    link_id,
+                    &ip_address,
+                    user_agent,
+                    json!({
+                        "short_code": short_code,
+                        "lockout_seconds": lockout_seconds,
+                        "lockout_count": attempts.lockouts,
+                    }),
+                );
+                return too_many_attempts_response(&short_code, lockout_seconds);
+            }
+
+            let remaining = attempts.remaining_attempts(unlocks.policy());
+            let notice = format!(
+                "Incorrect password. {} attempt{} left.",
+                remaining,
+                if remaining == 1 { "" } else { "s" }
+            );
             (
                 StatusCode::UNAUTHORIZED,
-                (
-                    StatusCode::UNAUTHORIZED,
-                    [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
-                    password_required_page(&short_code),
-                )
-                    .into_response(),
+                [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                password_required_page(&short_code, Some(&notice)),
             )
+                .into_response()
         },
         Err(e) => {
-            warn!("Error processing redirect for {}: {:?}", short_code, e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "An error occurred processing your request",
-                )
-                    .into_response(),
-            )
+            unlocks.release(link_id, ip).await;
+            redirect_error_response(&short_code, e)
         },
-    };
+    }
+}
 
-    response
+/// HTML 405 for a short link requested with an unsupported method (axum adds `Allow`)
+pub async fn method_not_allowed(method: Method) -> Response {
+    (
+        StatusCode::METHOD_NOT_ALLOWED,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        method_not_allowed_page(method.as_str()),
+    )
+        .into_response()
+}
+
+/// HTML 404 for a non-API path that matches no route
+pub fn path_not_found(path: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        not_found_page(&escape_html(path.trim_start_matches('/'))),
+    )
+        .into_response()
+}
+
+fn too_many_attempts_response(short_code: &str, retry_after_seconds: u64) -> Response {
+    (
+        StatusCode::TOO_MANY_REQUESTS,
+        [
+            (header::CONTENT_TYPE, "text/html; charset=utf-8".to_string()),
+            (header::RETRY_AFTER, retry_after_seconds.to_string()),
+        ],
+        too_many_attempts_page(short_code, retry_after_seconds),
+    )
+        .into_response()
 }
 
 /// Preview a short URL without redirecting
+/// Returns JSON by default, or a human-readable page when the client prefers `text/html`
 /// GET /r/:short_code/preview
+#[utoipa::path(
+    get,
+    path = "/{short_code}/preview",
+    tag = "Redirect",
+    operation_id = "previewUrl",
+    params(
+        ("short_code" = String, Path, description = "Short code or custom alias", example = "abc123"),
+        ("Accept" = Option<String>, Header, description = "`text/html` returns a preview page with a continue button")
+    ),
+    responses(
+        (status = 200, description = "Destination details without redirecting", content(
+            ("application/json" = LinkPreviewResponse),
+            ("text/html" = String)
+        )),
+        (status = 404, description = "Short code not found", body = ApiError),
+        (status = 500, description = "Link lookup failed", body = ApiError)
+    )
+)]
 pub async fn preview_url(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Path(short_code): Path<String>,
 ) -> Response {
+    if is_reserved_path_segment(&short_code) {
+        return LinkError::NotFound.into_response();
+    }
+
     let link_service = LinkService::new(&state);
 
     match link_service.get_link_by_code(&short_code).await {
         Ok(Some(link)) => {
-            // Return preview information
-            let preview = serde_json::json!({
-                "short_code": link.short_code,
-                "original_url": link.original_url,
-                "created_at": link.created_at,
-                "expires_at": link.expires_at,
-                "is_active": link.is_active,
-            });
-
-            axum::Json(preview).into_response()
+            let total_clicks = link_service.get_total_clicks(link.id).await;
+            let base_url = &state.config.short_link_base_url;
+            let preview = LinkPreviewResponse::new(&link, total_clicks, base_url);
+
+            if prefers_html(&headers) {
+                (
+                    [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+                    preview_page(&preview),
+                )
+                    .into_response()
+            } else {
+                axum::Json(preview).into_response()
+            }
+        },
+        Ok(None) => LinkError::NotFound.into_response(),
+        Err(e) => {
+            error!("Failed to load link for preview {}: {}", short_code, e);
+            ApiError::internal("Failed to load link").into_response()
         },
-        Ok(None) => (
-            StatusCode::NOT_FOUND,
-            axum::Json(serde_json::json!({
-                "error": "Link not found"
-            })),
-        )
-            .into_response(),
-        Err(e) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            axum::Json(serde_json::json!({
-                "error": e.to_string()
-            })),
-        )
-            .into_response(),
     }
 }
 
+/// `Set-Cookie` for the link's conversion pixel (see services::conversion_tracking); `None`
+/// when conversion tracking is off or the click is recorded anonymously
+fn conversion_cookie(
+    state: &AppState,
+    link_service: &LinkService,
+    link_id: uuid::Uuid,
+    tracking_mode: TrackingMode,
+    headers: &HeaderMap,
+    ip: IpAddr,
+    user_agent: &str,
+) -> Option<HeaderValue> {
+    if state.config.conversion_cookie_days == 0 || state.clickhouse_analytics.is_none() {
+        return None;
+    }
+    let visitor_hash =
+        link_service.conversion_visitor(tracking_mode, privacy_opt_out(headers), ip, user_agent)?;
+    let cookie = conversion_tracking::visitor_cookie(
+        &visitor_hash,
+        &conversion_tracking::pixel_path(&state.config.public_base_path, link_id),
+        state.config.conversion_cookie_days,
+    );
+    HeaderValue::from_str(&cookie).ok()
+}
+
+/// Whether the visitor sent a Do Not Track (`DNT: 1`) or Global Privacy Control (`Sec-GPC: 1`) signal
+fn privacy_opt_out(headers: &HeaderMap) -> bool {
+    ["dnt", "sec-gpc"].iter().any(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.trim() == "1")
+    })
+}
+
+/// Whether the client asked for HTML (browsers) rather than JSON (extensions, `*/*`)
+fn prefers_html(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|accept| {
+            accept
+                .split(',')
+                .map(|range| range.split(';').next().unwrap_or("").trim())
+                .any(|media| media.eq_ignore_ascii_case("text/html"))
+        })
+}
+
 // =============================================================================
 // ERROR PAGES
 // =============================================================================
@@ -325,7 +841,25 @@ fn inactive_page(short_code: &str) -> String {
     )
 }
 
-fn password_required_page(short_code: &str) -> String {
+/// `notice` is shown above the form, e.g. after a wrong password
+fn password_required_page(short_code: &str, notice: Option<&str>) -> String {
+    let notice = notice
+        .map(|text| format!(r#"<p class="notice">{}</p>"#, text))
+        .unwrap_or_default();
+
+    let form = short_code_path(short_code, "/unlock")
+        .map(|action| {
+            format!(
+                r#"<form action="{}" method="POST">
+            <input type="password" name="password" placeholder="Enter password" required>
+            <br>
+            <button type="submit">Unlock</button>
+        </form>"#,
+                escape_html(&action)
+            )
+        })
+        .unwrap_or_default();
+
     format!(
         r#"<!DOCTYPE html>
 <html lang="en">
@@ -375,6 +909,11 @@ fn password_required_page(short_code: &str) -> String {
         button:hover {{
             transform: scale(1.05);
         }}
+        .notice {{
+            background: rgba(255, 255, 255, 0.2);
+            padding: 0.5rem 1rem;
+            border-radius: 8px;
+        }}
     </style>
 </head>
 <body>
@@ -382,14 +921,60 @@ fn password_required_page(short_code: &str) -> String {
         <div class="icon">🔒</div>
         <h1>Password Required</h1>
         <p>This link is password protected.</p>
-        <form action="/{}/unlock" method="POST">
-            <input type="password" name="password" placeholder="Enter password" required>
-            <br>
-            <button type="submit">Unlock</button>
-        </form>
+        {}
+        {}
     </div>
 </body>
 </html>"#,
-        short_code
+        notice, form
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(value).unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_privacy_opt_out() {
+        let mut headers = HeaderMap::new();
+        assert!(!privacy_opt_out(&headers));
+
+        headers.insert("dnt", HeaderValue::from_static("0"));
+        assert!(!privacy_opt_out(&headers));
+
+        headers.insert("dnt", HeaderValue::from_static("1"));
+        assert!(privacy_opt_out(&headers));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("sec-gpc", HeaderValue::from_static("1"));
+        assert!(privacy_opt_out(&headers));
+    }
+
+    #[test]
+    fn test_prefers_html() {
+        assert!(prefers_html(&accept(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        )));
+        assert!(!prefers_html(&accept("application/json")));
+        assert!(!prefers_html(&accept("*/*")));
+        assert!(!prefers_html(&HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_password_page_posts_to_same_origin_only() {
+        let page = password_required_page("abc123", None);
+        assert!(page.contains(r#"<form action="/abc123/unlock" method="POST">"#));
+
+        for short_code in ["/evil.com", "\\evil.com", "\\/evil.com"] {
+            let page = password_required_page(short_code, None);
+            assert!(!page.contains("<form"), "{:?}", short_code);
+        }
+    }
+}
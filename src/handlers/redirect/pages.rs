@@ -1,3 +1,6 @@
+use crate::models::link::LinkPreviewResponse;
+use crate::utils::safe_redirect::safe_internal_redirect;
+
 /// Generate HTML for link processing page
 pub fn processing_page(short_code: &str) -> String {
     format!(
@@ -75,3 +78,513 @@ pub fn processing_page(short_code: &str) -> String {
         short_code
     )
 }
+
+/// Generate HTML for the warning page shown instead of redirecting a quarantined link
+pub fn quarantined_page(short_code: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex, nofollow">
+    <title>Warning: Unsafe Link - QCK</title>
+    <style>
+        body {{
+            margin: 0;
+            padding: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #f5a623 0%, #d0021b 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            text-align: center;
+            padding: 2rem;
+            max-width: 560px;
+            background: rgba(255, 255, 255, 0.1);
+            border-radius: 16px;
+            backdrop-filter: blur(10px);
+        }}
+        .icon {{
+            font-size: 4rem;
+            margin: 1rem 0;
+        }}
+        .code {{
+            background: rgba(255, 255, 255, 0.2);
+            padding: 0.5rem 1rem;
+            border-radius: 8px;
+            display: inline-block;
+            margin: 1rem 0;
+            font-family: monospace;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="icon">⚠️</div>
+        <h1>This Link Has Been Disabled</h1>
+        <div class="code">qck.sh/{}</div>
+        <p>A security scan flagged the destination of this link as potentially harmful, so we stopped redirecting to it.</p>
+        <p>If you own this link, you can request a review from your dashboard.</p>
+        <p><a href="/" style="color: white;">Go to Homepage</a></p>
+    </div>
+</body>
+</html>"#,
+        escape_html(short_code)
+    )
+}
+
+/// Generate HTML for the page shown when a link's referrer restrictions refuse the visitor
+pub fn referrer_blocked_page(short_code: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex, nofollow">
+    <title>Link Restricted - QCK</title>
+    <style>
+        body {{
+            margin: 0;
+            padding: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            text-align: center;
+            padding: 2rem;
+            max-width: 520px;
+            background: rgba(255, 255, 255, 0.1);
+            border-radius: 16px;
+            backdrop-filter: blur(10px);
+        }}
+        .icon {{
+            font-size: 3rem;
+            margin: 1rem 0;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="icon">🚫</div>
+        <h1>This Link Is Restricted</h1>
+        <p>The owner of <strong>/{short_code}</strong> only lets it be opened from certain websites, and this visit didn't come from one of them.</p>
+        <p>Please open the link from the page or email where you found it. Some browsers and privacy extensions hide where a visit came from, which can also cause this.</p>
+    </div>
+</body>
+</html>"#,
+        short_code = escape_html(short_code)
+    )
+}
+
+/// Generate HTML for the page shown while an IP is locked out of a password-protected link
+pub fn too_many_attempts_page(short_code: &str, retry_after_seconds: u64) -> String {
+    let wait = if retry_after_seconds >= 120 {
+        format!("{} minutes", retry_after_seconds.div_ceil(60))
+    } else {
+        format!("{} seconds", retry_after_seconds.max(1))
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex, nofollow">
+    <title>Too Many Attempts - QCK</title>
+    <style>
+        body {{
+            margin: 0;
+            padding: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            text-align: center;
+            padding: 2rem;
+            max-width: 480px;
+            background: rgba(255, 255, 255, 0.1);
+            border-radius: 16px;
+            backdrop-filter: blur(10px);
+        }}
+        .icon {{
+            font-size: 3rem;
+            margin: 1rem 0;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <div class="icon">⏳</div>
+        <h1>Too Many Attempts</h1>
+        <p>Too many incorrect passwords were entered for <strong>/{short_code}</strong>.</p>
+        <p>Please wait {wait} before trying again.</p>
+    </div>
+</body>
+</html>"#,
+        short_code = escape_html(short_code),
+        wait = wait
+    )
+}
+
+/// Path of a short code's own page on this origin (`/{code}`, `/{code}/unlock`), or `None`
+/// if the code would make it point anywhere else
+pub fn short_code_path(short_code: &str, suffix: &str) -> Option<String> {
+    safe_internal_redirect(&format!("/{}{}", short_code, suffix), None)
+}
+
+/// Generate HTML for the human-readable preview shown at /{short_code}/preview
+pub fn preview_page(preview: &LinkPreviewResponse) -> String {
+    let title = preview
+        .title
+        .as_deref()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or(&preview.display_host);
+
+    let image = preview
+        .og_image
+        .as_deref()
+        .filter(|url| url.starts_with("https://"))
+        .map(|url| format!(r#"<img class="og" src="{}" alt="">"#, escape_html(url)))
+        .unwrap_or_default();
+
+    let description = preview
+        .description
+        .as_deref()
+        .map(|d| format!("<p>{}</p>", escape_html(d)))
+        .unwrap_or_default();
+
+    let mut notices = Vec::new();
+    if preview.display_host_warning {
+        notices
+            .push("The destination domain mixes character sets and may be imitating another site.");
+    }
+    if matches!(preview.risk_level.as_deref(), Some("high" | "critical")) {
+        notices.push("A security scan rated this destination as high risk.");
+    }
+    if preview.is_password_protected {
+        notices.push("You will be asked for a password before continuing.");
+    }
+    if preview.is_expiring_soon {
+        notices.push("This link expires within 24 hours.");
+    }
+    let notices: String = notices
+        .iter()
+        .map(|n| format!(r#"<div class="notice">{}</div>"#, n))
+        .collect();
+
+    // Quarantined links never redirect, so there is nothing to continue to
+    let action = if preview.is_quarantined {
+        r#"<div class="notice">This link has been disabled after a security review.</div>"#
+            .to_string()
+    } else {
+        short_code_path(&preview.short_code, "")
+            .map(|path| {
+                format!(
+                    r#"<a class="continue" href="{}" rel="noopener noreferrer">Continue to destination</a>"#,
+                    escape_html(&path)
+                )
+            })
+            .unwrap_or_default()
+    };
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex, nofollow">
+    <meta property="og:title" content="{title}">
+    <meta property="og:image" content="{share_image}">
+    <meta name="twitter:card" content="summary_large_image">
+    <meta name="qck:tracking-mode" content="{tracking_mode}">
+    <meta name="qck:tracking-notice" content="{tracking_notice}">
+    <title>Preview: {title} - QCK</title>
+    <style>
+        body {{
+            margin: 0;
+            padding: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            padding: 2rem;
+            max-width: 560px;
+            background: rgba(255, 255, 255, 0.1);
+            border-radius: 16px;
+            backdrop-filter: blur(10px);
+        }}
+        .og {{
+            width: 100%;
+            border-radius: 8px;
+        }}
+        .destination {{
+            background: rgba(255, 255, 255, 0.2);
+            padding: 0.5rem 1rem;
+            border-radius: 8px;
+            font-family: monospace;
+            word-break: break-all;
+        }}
+        .notice {{
+            background: rgba(208, 2, 27, 0.6);
+            padding: 0.5rem 1rem;
+            border-radius: 8px;
+            margin: 0.5rem 0;
+        }}
+        .meta {{
+            opacity: 0.8;
+            font-size: 0.9rem;
+        }}
+        .continue {{
+            display: block;
+            margin-top: 1.5rem;
+            padding: 1rem;
+            border-radius: 8px;
+            background: white;
+            color: #667eea;
+            font-weight: bold;
+            font-size: 1.1rem;
+            text-align: center;
+            text-decoration: none;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        {image}
+        <h1>{title}</h1>
+        {description}
+        <p>This link goes to <strong>{host}</strong></p>
+        <div class="destination">{url}</div>
+        <p class="meta">Clicked {clicks} times</p>
+        <p class="meta">{tracking_notice}</p>
+        {notices}
+        {action}
+    </div>
+</body>
+</html>"#,
+        title = escape_html(title),
+        share_image = escape_html(&preview.share_image),
+        image = image,
+        description = description,
+        host = escape_html(&preview.display_host),
+        url = escape_html(&preview.original_url),
+        clicks = preview.total_clicks,
+        tracking_mode = preview.tracking_mode.as_str(),
+        tracking_notice = escape_html(&preview.tracking_notice),
+        notices = notices,
+        action = action,
+    )
+}
+
+/// Generate HTML for a short link requested with a method it doesn't support
+pub fn method_not_allowed_page(method: &str) -> String {
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <meta name="robots" content="noindex, nofollow">
+    <title>Method Not Allowed - QCK</title>
+    <style>
+        body {{
+            margin: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }}
+        .container {{
+            text-align: center;
+            padding: 2rem;
+        }}
+        h1 {{
+            font-size: 4rem;
+            margin: 0;
+            opacity: 0.9;
+        }}
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>405</h1>
+        <h2>Method Not Allowed</h2>
+        <p>Short links can't be opened with a {method} request.</p>
+        <p><a href="/" style="color: white;">Go to Homepage</a></p>
+    </div>
+</body>
+</html>"#,
+        method = escape_html(method)
+    )
+}
+
+/// Generate HTML for the instance root when ROOT_REDIRECT_URL is unset
+pub fn landing_page() -> String {
+    r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>QCK</title>
+    <style>
+        body {
+            margin: 0;
+            font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif;
+            background: linear-gradient(135deg, #667eea 0%, #764ba2 100%);
+            color: white;
+            min-height: 100vh;
+            display: flex;
+            align-items: center;
+            justify-content: center;
+        }
+        .container {
+            text-align: center;
+            padding: 2rem;
+        }
+        h1 {
+            font-size: 3rem;
+            margin: 0;
+        }
+        p {
+            opacity: 0.9;
+        }
+    </style>
+</head>
+<body>
+    <div class="container">
+        <h1>QCK</h1>
+        <p>This domain serves short links.</p>
+    </div>
+</body>
+</html>"#
+        .to_string()
+}
+
+/// Escape text interpolated into HTML (titles and descriptions come from third-party pages)
+pub fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#x27;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::link::TrackingMode;
+
+    #[test]
+    fn test_escape_html() {
+        assert_eq!(
+            escape_html(r#"<script>alert("x")</script> & 'y'"#),
+            "&lt;script&gt;alert(&quot;x&quot;)&lt;/script&gt; &amp; &#x27;y&#x27;"
+        );
+    }
+    #[test]
+    fn test_landing_page() {
+        let page = landing_page();
+        assert!(page.starts_with("<!DOCTYPE html>"));
+        assert!(page.contains("short links"));
+    }
+    #[test]
+    fn test_method_not_allowed_page() {
+        let page = method_not_allowed_page("DELETE");
+        assert!(page.contains("405"));
+        assert!(page.contains("DELETE request"));
+    }
+
+    #[test]
+    fn test_too_many_attempts_page() {
+        assert!(too_many_attempts_page("abc123", 45).contains("wait 45 seconds"));
+        assert!(too_many_attempts_page("abc123", 300).contains("wait 5 minutes"));
+        assert!(too_many_attempts_page("<b>", 60).contains("/&lt;b&gt;"));
+    }
+
+    #[test]
+    fn test_quarantined_page() {
+        assert!(quarantined_page("abc123").contains("qck.sh/abc123"));
+        assert!(quarantined_page("<b>").contains("qck.sh/&lt;b&gt;"));
+    }
+
+    #[test]
+    fn test_referrer_blocked_page() {
+        let page = referrer_blocked_page("abc123");
+        assert!(page.contains("This Link Is Restricted"));
+        assert!(page.contains("/abc123"));
+        assert!(referrer_blocked_page("<b>").contains("/&lt;b&gt;"));
+    }
+
+    #[test]
+    fn test_preview_page_states_tracking_mode() {
+        let mode = TrackingMode::Anonymous;
+        let preview = LinkPreviewResponse {
+            short_code: "abc123".to_string(),
+            original_url: "https://example.com".to_string(),
+            created_at: chrono::Utc::now(),
+            expires_at: None,
+            is_active: true,
+            is_quarantined: false,
+            display_host: "example.com".to_string(),
+            display_host_warning: false,
+            title: None,
+            description: None,
+            og_image: None,
+            share_image: "https://qck.sh/og.png".to_string(),
+            total_clicks: 0,
+            risk_level: None,
+            is_password_protected: false,
+            is_expiring_soon: false,
+            tracking_mode: mode,
+            tracking_notice: mode.visitor_notice().to_string(),
+        };
+        let page = preview_page(&preview);
+
+        assert!(page.contains(r#"<meta name="qck:tracking-mode" content="anonymous">"#));
+        assert!(page.contains("turned off visitor tracking"));
+    }
+
+    #[test]
+    fn test_short_code_path_stays_on_origin() {
+        assert_eq!(
+            short_code_path("abc123", "/unlock").as_deref(),
+            Some("/abc123/unlock")
+        );
+        assert_eq!(short_code_path("/evil.com", ""), None);
+        assert_eq!(short_code_path("\\evil.com", "/unlock"), None);
+        assert_eq!(short_code_path("\t/evil.com", ""), None);
+    }
+}
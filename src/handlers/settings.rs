@@ -0,0 +1,92 @@
+// Account settings endpoints
+
+use axum::{
+    extract::{Extension, State},
+    response::IntoResponse,
+    Json,
+};
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState, middleware::auth::AuthenticatedUser,
+    services::notification_preferences::NotificationPreferencesService,
+    utils::service_error::ServiceError,
+};
+
+fn parse_user_id(auth_user: &AuthenticatedUser) -> Result<Uuid, ServiceError> {
+    Uuid::parse_str(&auth_user.user_id)
+        .map_err(|_| ServiceError::ValidationError("Invalid user ID format".to_string()))
+}
+
+/// Get the authenticated user's email notification preferences
+/// GET /v1/settings/notifications
+#[utoipa::path(
+    get,
+    path = "/v1/settings/notifications",
+    tag = "Settings",
+    operation_id = "getNotificationPreferences",
+    responses(
+        (status = 200, description = "Preferences for every notification kind", body = NotificationPreferences),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn get_notification_preferences(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+) -> impl IntoResponse {
+    let user_id = match parse_user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match NotificationPreferencesService::new(&state)
+        .get(user_id)
+        .await
+    {
+        Ok(preferences) => Json(preferences).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Update email notification preferences
+/// PUT /v1/settings/notifications
+#[utoipa::path(
+    put,
+    path = "/v1/settings/notifications",
+    tag = "Settings",
+    operation_id = "updateNotificationPreferences",
+    request_body(
+        content = NotificationPreferences,
+        description = "Any subset of the notification kinds; omitted kinds keep their current setting"
+    ),
+    responses(
+        (status = 200, description = "Updated preferences for every notification kind", body = NotificationPreferences),
+        (status = 400, description = "Unknown notification kind or non-boolean value; the message names the key", body = ApiError),
+        (status = 401, description = "Unauthorized - invalid or missing token", body = ApiError)
+    ),
+    security(
+        ("bearerAuth" = [])
+    )
+)]
+pub async fn update_notification_preferences(
+    State(state): State<AppState>,
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    Json(changes): Json<Map<String, Value>>,
+) -> impl IntoResponse {
+    let user_id = match parse_user_id(&auth_user) {
+        Ok(id) => id,
+        Err(e) => return e.into_response(),
+    };
+
+    match NotificationPreferencesService::new(&state)
+        .update(user_id, &changes)
+        .await
+    {
+        Ok(preferences) => Json(preferences).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
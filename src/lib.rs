@@ -45,6 +45,11 @@ pub type DbPool = Pool<AsyncDieselConnectionManager<AsyncPgConnection>>;
 
 // Library initialization function for external consumers
 // This allows extended platforms to initialize the core backend services
+//
+// Handlers and middleware extract the returned state as `State<AppState>`, so
+// routers only need `.with_state(state)`; no `Extension(AppState)` layer is read.
+// Middleware that needs state is attached with `from_fn_with_state`, e.g.
+// `idempotency_middleware` on `link_creation_routes()` inside `auth_middleware`.
 pub async fn initialize_app_state() -> Result<AppState, Box<dyn std::error::Error>> {
     use std::sync::Arc;
     use tracing::info;
@@ -86,7 +91,9 @@ pub async fn initialize_app_state() -> Result<AppState, Box<dyn std::error::Erro
     );
 
     let password_reset_service = Arc::new(PasswordResetService::new(diesel_pool.clone()));
-    let email_service = Arc::new(EmailService::new(config.email.clone())?);
+    // TEST_MODE swaps in in-memory email and threat lookup fakes
+    let email_service = Arc::new(EmailService::from_app_config(config)?);
+    let threat_lookup = utils::security_scanner::configured_threat_lookup(config);
 
     // Initialize ClickHouse if configured
     let clickhouse_analytics = if !config.clickhouse_url.is_empty() {
@@ -108,8 +115,10 @@ pub async fn initialize_app_state() -> Result<AppState, Box<dyn std::error::Erro
         rate_limit_config,
         password_reset_service,
         email_service,
+        threat_lookup,
         clickhouse_analytics,
         max_connections,
+        health: Default::default(),
     })
 }
 
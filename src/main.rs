@@ -15,11 +15,11 @@ pub mod utils;
 pub use app_config::CONFIG;
 
 use axum::{
-    extract::{Extension, State},
+    extract::State,
     http::StatusCode,
     middleware as axum_middleware,
     response::{IntoResponse, Json},
-    routing::get,
+    routing::{any, get},
     Router,
 };
 use std::net::SocketAddr;
@@ -37,10 +37,13 @@ use crate::{
         RedisConfig, RedisPool,
     },
     handlers::{
-        auth as auth_handlers, protected_auth_routes, public_auth_routes, docs as docs_handlers,
-        links as link_handlers, redirect as redirect_handlers,
+        admin_routes, analytics_routes, audit_log_routes, auth as auth_handlers, link_creation_routes, link_routes, metrics_routes, onboarding_routes, organization_routes, page_routes, protected_auth_routes, public_link_routes, public_auth_routes, redirect_routes, settings_routes, docs as docs_handlers,
+        fallback as fallback_handlers, links as link_handlers, redirect as redirect_handlers,
+    },
+    middleware::{
+        auth_middleware, idempotency_middleware, latency_middleware, request_timeout_middleware, require_admin,
+        require_docs_access, require_metrics_access, DocsAccess, MetricsAccess, RouteLatency, RouteTimeout,
     },
-    middleware::auth_middleware,
     services::{
         EmailService, JwtService, PasswordResetService, RateLimitService,
     },
@@ -73,6 +76,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("=== STARTING QCK BACKEND API ===");
     info!("Starting QCK Backend API on {}", bind_address);
 
+    if config.short_link_base_url_from_audience {
+        warn!(
+            "SHORT_LINK_BASE_URL is not set; short links use the JWT audience ({}). This fallback is deprecated, set SHORT_LINK_BASE_URL explicitly",
+            config.short_link_base_url
+        );
+    }
+
+    // Outbound proxy, before anything fetches from the outside world
+    if let Err(e) = crate::utils::outbound_proxy::install(&config.proxy) {
+        error!("✗ Refusing to start: {}", e);
+        return Err(format!("Invalid outbound proxy: {}", e).into());
+    }
+
+    // Blocked domains list used by URL validation
+    let blocklist_source = crate::utils::data_files::load_blocked_domains(config).await;
+    if let Err(e) = crate::utils::data_files::check_blocklist_source(
+        blocklist_source,
+        &config.environment,
+        config.allow_fallback_blocklist,
+    ) {
+        error!("✗ Refusing to start: {}", e);
+        return Err(format!("Blocked domains list unavailable: {}", e).into());
+    }
+
     // Initialize Diesel database pool
     info!("Initializing database pool...");
     let db_config = DieselDatabaseConfig::default();
@@ -163,9 +190,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let password_reset_service = Arc::new(PasswordResetService::new(diesel_pool.clone()));
     info!("✓ Password reset service initialized successfully");
 
+    if config.test_mode {
+        warn!("⚠ TEST_MODE is set: emails and external threat lookups are recorded in memory, not sent");
+    }
+
     // Initialize email service
     info!("Initializing email service...");
-    let email_service = match EmailService::new(config.email.clone()) {
+    let email_service = match EmailService::from_app_config(config) {
         Ok(service) => {
             info!("✓ Email service initialized successfully");
             Arc::new(service)
@@ -202,10 +233,20 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         rate_limit_config,
         password_reset_service,
         email_service,
+        threat_lookup: crate::utils::security_scanner::configured_threat_lookup(config),
         clickhouse_analytics,
         max_connections,
+        health: Default::default(),
     };
 
+    // `--self-test`: exercise the link lifecycle against the configured dependencies and exit
+    // non-zero on any failure, so deploy pipelines can gate on it
+    if args.iter().any(|arg| arg == "--self-test") {
+        let report = crate::services::self_test::run_self_test(&app_state).await;
+        print!("{}", report);
+        std::process::exit(if report.passed { 0 } else { 1 });
+    }
+
     // Configure CORS - Environment-aware wildcard handling
     info!(
         "CORS: Configuring origins for {} environment: {:?}",
@@ -229,20 +270,42 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         );
     }
 
+    if !config.cors.redirect.enabled {
+        info!("CORS: Public redirect routes are exempt (CORS_REDIRECT_ENABLED=false)");
+    }
+
     // Build the application router - conditionally include Swagger UI
     let mut app = Router::new()
         // Health check endpoints
-        .route("/v1/health", get(comprehensive_health_check))
-        .route("/v1/metrics/rate-limiting", get(rate_limit_metrics_handler));
+        .route("/v1/health", get(comprehensive_health_check));
 
     // Conditionally add Swagger UI routes based on configuration
     if config.enable_swagger_ui {
-        info!("🔧 Swagger UI: ENABLED at /v1/docs");
-        app = app
+        info!(
+            "🔧 Swagger UI: ENABLED at /v1/docs ({} documented paths)",
+            docs_handlers::api_doc().paths.paths.len()
+        );
+        let mut docs = Router::new()
             // Versioned API Documentation
             .route("/v1/docs", get(docs_handlers::redirect_to_docs))
             .route("/v1/docs/", get(docs_handlers::serve_swagger_ui))
             .route("/v1/docs/openapi.json", get(docs_handlers::serve_openapi_spec));
+        let docs_auth = &config.security.docs_auth;
+        if docs_auth.is_enabled() {
+            info!(
+                "🔧 Swagger UI: requires {}",
+                match (docs_auth.has_basic_auth(), docs_auth.require_admin) {
+                    (true, true) => "basic auth or an admin JWT",
+                    (true, false) => "basic auth",
+                    _ => "an admin JWT",
+                }
+            );
+            docs = docs.route_layer(axum_middleware::from_fn_with_state(
+                Arc::new(DocsAccess::new(app_state.jwt_service.clone(), docs_auth)),
+                require_docs_access,
+            ));
+        }
+        app = app.merge(docs);
     } else {
         info!("🔧 Swagger UI: DISABLED (set ENABLE_SWAGGER_UI=true to enable)");
     }
@@ -250,13 +313,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Complete router setup
     let app = app
         // Public auth routes (no auth required)
-        .nest("/v1/auth", public_auth_routes())
+        .nest("/v1/auth", public_auth_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("auth", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("auth"),
+                latency_middleware,
+            ))
+        )
         // Protected auth routes (with auth middleware)
         .nest("/v1/auth", protected_auth_routes()
             .route_layer(axum_middleware::from_fn_with_state(
                 app_state.clone(),
                 auth_middleware,
             ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("auth", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("auth"),
+                latency_middleware,
+            ))
+        )
+        // Link images fetched by social network crawlers (no auth middleware)
+        .nest("/v1", public_link_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("links", config.request_timeouts.links_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("links"),
+                latency_middleware,
+            ))
+        )
+        // Link creation routes (with auth, then idempotency middleware)
+        .nest("/v1", link_creation_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                idempotency_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("links", config.request_timeouts.links_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("links"),
+                latency_middleware,
+            ))
         )
         // Protected link routes (with auth middleware)
         .nest("/v1", link_routes()
@@ -264,27 +374,198 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
                 app_state.clone(),
                 auth_middleware,
             ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("links", config.request_timeouts.links_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("links"),
+                latency_middleware,
+            ))
+        )
+        // Per-user audit trail (with auth middleware)
+        .nest("/v1", audit_log_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("audit_logs", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("audit_logs"),
+                latency_middleware,
+            ))
+        )
+        // Account-level analytics (with auth middleware)
+        .nest("/v1", analytics_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("analytics", config.request_timeouts.analytics_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("analytics"),
+                latency_middleware,
+            ))
+        )
+        // Onboarding progress (with auth middleware)
+        .nest("/v1", onboarding_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("onboarding", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("onboarding"),
+                latency_middleware,
+            ))
+        )
+        // Account settings (with auth middleware)
+        .nest("/v1", settings_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("settings", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("settings"),
+                latency_middleware,
+            ))
         )
+        // Organizations and invitations (with auth middleware)
+        .nest("/v1", organization_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("organizations", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("organizations"),
+                latency_middleware,
+            ))
+        )
+        // Landing pages (with auth middleware); served publicly by the redirect handler
+        .nest("/v1", page_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("pages", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("pages"),
+                latency_middleware,
+            ))
+        )
+        // Operator-only admin routes (auth runs first, then the admin scope check)
+        .nest("/v1/admin", admin_routes()
+            .route_layer(axum_middleware::from_fn(require_admin))
+            .route_layer(axum_middleware::from_fn_with_state(
+                app_state.clone(),
+                auth_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("admin", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("admin"),
+                latency_middleware,
+            ))
+        )
+        // Operational metrics (admin JWT or static METRICS_TOKEN)
+        .nest("/v1/metrics", metrics_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                Arc::new(MetricsAccess::new(
+                    app_state.jwt_service.clone(),
+                    &config.security.metrics_token,
+                )),
+                require_metrics_access,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("metrics", config.request_timeouts.default_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("metrics"),
+                latency_middleware,
+            ))
+        )
+        // JSON 405 for every API route registered above (redirect routes keep their HTML one)
+        .method_not_allowed_fallback(fallback_handlers::api_method_not_allowed)
+        // "/v1" alone would otherwise be taken for a short code, and unknown API paths
+        // for a path-passthrough link
+        .route("/v1", any(fallback_handlers::not_found))
+        .route("/v1/{*path}", any(fallback_handlers::not_found))
         // Short URL redirects at root level (qck.sh/abc123)
-        .route("/{short_code}", get(handlers::redirect::redirect_to_url))
-        .route("/{short_code}/preview", get(handlers::redirect::preview_url))
+        .merge(redirect_routes()
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteTimeout::new("redirect", config.request_timeouts.redirect_ms),
+                request_timeout_middleware,
+            ))
+            .route_layer(axum_middleware::from_fn_with_state(
+                RouteLatency::new("redirect"),
+                latency_middleware,
+            ))
+        )
+        // Unmatched paths: JSON 404 under /v1, HTML everywhere else
+        .fallback(fallback_handlers::not_found)
         // Add middleware
         .layer(
             ServiceBuilder::new()
+                // Outermost so every response, including CORS rejections, carries X-Request-Id
+                .layer(axum_middleware::from_fn(crate::middleware::request_id_middleware))
                 .layer(TraceLayer::new_for_http())
-                .layer(axum_middleware::from_fn(crate::middleware::dynamic_cors_middleware))
-                .layer(Extension(app_state.clone()))
+                .layer(axum_middleware::from_fn_with_state(
+                    Arc::new(config.security_headers.clone()),
+                    crate::middleware::security_headers_middleware,
+                ))
+                .layer(axum_middleware::from_fn_with_state(
+                    Arc::new(config.cors.clone()),
+                    crate::middleware::dynamic_cors_middleware,
+                ))
+                // Denied client IPs get a 403 before any handler work
+                .layer(axum_middleware::from_fn(crate::middleware::ip_denylist_middleware))
         )
         .with_state(app_state.clone());
 
-    // Start URLhaus threat intelligence updater
-    crate::utils::urlhaus_client::spawn_urlhaus_updater();
-    info!("URLhaus threat intelligence updater started");
+    // Persist audit entries to Postgres (queued, never blocks handlers)
+    crate::utils::audit_logger::AuditLogger::init(app_state.diesel_pool.clone());
+
+    // Load the operator blocklist and keep it in sync across replicas
+    crate::services::blocklist::spawn_blocklist_refresh_task(app_state.clone());
+    info!("Operator blocklist refresh task started");
+
+    // Load the client IP denylist and keep it in sync across replicas
+    crate::services::ip_denylist::spawn_ip_denylist_refresh_task(app_state.clone());
+    info!("IP denylist refresh task started");
+
+    // Probe ClickHouse and the email provider for /v1/health
+    crate::services::health_probe::spawn_health_probe_tasks(&app_state);
+
+    // Pending redirect clicks are flushed once more after the server stops
+    let shutdown_redis_pool = app_state.redis_pool.clone();
 
     // Start background tasks for click count synchronization
     info!("Starting background tasks for click tracking synchronization...");
     crate::services::background_tasks::initialize_background_tasks(app_state).await;
-    info!("Background task manager started - syncing click counts every 5 minutes");
+    info!("Background task manager started - task status at /v1/metrics/background-tasks");
 
     // Parse and bind to address
     let addr: SocketAddr = bind_address.parse()?;
@@ -296,27 +577,59 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         listener,
         app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
     )
+    .with_graceful_shutdown(shutdown_signal())
     .await?;
 
+    crate::services::click_counter::CLICK_COUNTER
+        .flush_on_shutdown(&shutdown_redis_pool)
+        .await;
+    crate::services::api_usage::API_USAGE
+        .flush_on_shutdown(&shutdown_redis_pool)
+        .await;
+    // Hand the exclusive background jobs to another replica without waiting for lock expiry
+    crate::services::background_tasks::TASK_REGISTRY
+        .release_locks()
+        .await;
+    info!("Server stopped");
+
     Ok(())
 }
 
-// Link management routes (all require JWT authentication)
-fn link_routes() -> Router<AppState> {
-    use axum::routing::{delete, get, post, put};
-    use handlers::links;
-
-    Router::new()
-        .route("/links", post(links::create_link).get(links::list_links))
-        .route("/links/bulk", post(links::bulk_create_links))
-        .route("/links/check-alias/{alias}", get(links::check_alias_availability))
-        .route("/links/custom", post(links::create_custom_link))
-        .route("/links/{id}", get(links::get_link).put(links::update_link).delete(links::delete_link))
-        .route("/links/{id}/stats", get(links::get_link_stats))
+/// Resolves on Ctrl+C or SIGTERM so in-flight requests finish before shutdown
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            },
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            },
+        }
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+    info!("Shutdown signal received, draining connections...");
 }
 
 // Health check handler
 async fn comprehensive_health_check(State(state): State<AppState>) -> impl IntoResponse {
+    use crate::services::health_probe::{component_health, COMPONENT_CLICKHOUSE, COMPONENT_EMAIL};
     use serde_json::json;
 
     // Overall service health
@@ -329,14 +642,16 @@ async fn comprehensive_health_check(State(state): State<AppState>) -> impl IntoR
             json!({
                 "status": "healthy",
                 "max_connections": state.max_connections,
-                "error": null
+                "error": null,
+                "checked_at": timestamp
             })
         },
         Err(e) => {
             overall_healthy = false;
             json!({
                 "status": "unhealthy",
-                "error": format!("Database connection failed: {}", e)
+                "error": format!("Database connection failed: {}", e),
+                "checked_at": timestamp
             })
         },
     };
@@ -351,35 +666,89 @@ async fn comprehensive_health_check(State(state): State<AppState>) -> impl IntoR
         "latency_ms": redis_health_result.latency_ms,
         "active_connections": redis_health_result.active_connections,
         "total_connections": redis_health_result.total_connections,
-        "error": redis_health_result.error
+        "error": redis_health_result.error,
+        "checked_at": timestamp
     });
 
-    // ClickHouse health check
-    let clickhouse_health = match check_clickhouse_health().await {
-        Ok(latency) => {
-            json!({
-                "status": "healthy",
-                "latency_ms": latency,
-                "error": null
-            })
-        },
-        Err(e) => {
-            overall_healthy = false;
-            json!({
-                "status": "unhealthy",
-                "error": format!("ClickHouse connection failed: {}", e)
-            })
-        },
+    // ClickHouse and the email provider are probed in the background; only the last result is
+    // read here. A failed ClickHouse probe fails the health check, an email provider outage doesn't.
+    let probes = &state.config.health_probes;
+    let now = chrono::Utc::now();
+    let clickhouse_health = component_health(
+        state.health.get(COMPONENT_CLICKHOUSE).as_ref(),
+        probes.clickhouse_enabled && state.clickhouse_analytics.is_some(),
+        probes.clickhouse_interval_seconds,
+        now,
+    );
+    if clickhouse_health["status"] == "unhealthy" {
+        overall_healthy = false;
+    }
+    let email_health = component_health(
+        state.health.get(COMPONENT_EMAIL).as_ref(),
+        probes.email_enabled,
+        probes.email_interval_seconds,
+        now,
+    );
+
+    // Failing background tasks are reported but don't take the instance out of rotation
+    let tasks = crate::services::background_tasks::TASK_REGISTRY.snapshot();
+    let tasks_healthy = tasks.iter().all(|task| task.is_healthy());
+    let background_tasks_health = json!({
+        "status": if tasks_healthy { "healthy" } else { "degraded" },
+        "tasks": tasks
+    });
+
+    // A stale threat feed weakens link scanning but doesn't fail the health check
+    let stale_after_seconds = i64::from(state.config.security.urlhaus_stale_after_hours) * 3600;
+    let threat_feed_health = if !state.config.security.urlhaus_enabled {
+        json!({ "status": "disabled" })
+    } else {
+        match crate::utils::urlhaus_client::feed_freshness(&state.redis_pool).await {
+            Ok(feed) => json!({
+                "status": if feed.stale { "stale" } else { "healthy" },
+                "last_refresh_at": feed.last_refresh_at,
+                "age_seconds": feed.age_seconds,
+                "stale_after_seconds": stale_after_seconds
+            }),
+            Err(e) => json!({
+                "status": "unknown",
+                "error": format!("Feed refresh time unavailable: {}", e)
+            }),
+        }
     };
 
+    // Running on the built-in fallback blocklist is reported but doesn't fail the health check
+    let blocklist_source = crate::utils::data_files::blocklist_source();
+    let blocklist_health = json!({
+        "status": match blocklist_source {
+            Some(crate::utils::data_files::BlocklistSource::Fallback) => "fallback",
+            Some(_) => "healthy",
+            None => "unknown",
+        },
+        "source": blocklist_source
+    });
+
+    // Latency percentiles of this instance; informational only
+    let latency = crate::middleware::latency::latency_metrics();
+    let latency_summary = json!({
+        "window_seconds": latency.window_seconds,
+        "redirect": latency.redirect,
+        "api": latency.api
+    });
+
     let response = json!({
         "status": if overall_healthy { "healthy" } else { "degraded" },
         "service": "qck-backend",
         "timestamp": timestamp,
+        "latency": latency_summary,
         "components": {
             "postgresql": postgres_health,
             "redis": redis_health,
-            "clickhouse": clickhouse_health
+            "clickhouse": clickhouse_health,
+            "email": email_health,
+            "background_tasks": background_tasks_health,
+            "threat_feed": threat_feed_health,
+            "blocked_domains": blocklist_health
         }
     });
 
@@ -389,33 +758,3 @@ async fn comprehensive_health_check(State(state): State<AppState>) -> impl IntoR
         (StatusCode::SERVICE_UNAVAILABLE, Json(response))
     }
 }
-
-async fn check_clickhouse_health() -> Result<i64, Box<dyn std::error::Error>> {
-    use std::time::Instant;
-
-    let start = Instant::now();
-    let clickhouse_client = crate::db::create_clickhouse_client();
-
-    // Use the client's health_check method
-    clickhouse_client.health_check().await?;
-
-    let latency = start.elapsed().as_millis() as i64;
-    Ok(latency)
-}
-
-async fn rate_limit_metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
-    use serde_json::json;
-
-    // Get analytics metrics for the last hour
-    let analytics_metrics = state.rate_limit_service.get_analytics_metrics(60).await;
-    let monitoring_stats = state.rate_limit_service.get_monitoring_stats().await;
-
-    let response = json!({
-        "timestamp": chrono::Utc::now().to_rfc3339(),
-        "analytics_enabled": analytics_metrics.is_some(),
-        "metrics": analytics_metrics,
-        "monitoring": monitoring_stats
-    });
-
-    Json(response)
-}
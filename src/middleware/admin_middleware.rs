@@ -0,0 +1,30 @@
+// Admin authorization middleware for operator-only routes
+// Must run after auth_middleware so AuthenticatedUser is present in extensions
+
+use axum::{
+    body::Body,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::{
+    config::permissions::INSTANCE_ADMIN_SCOPE, middleware::auth::AuthenticatedUser,
+    utils::api_error::ApiError,
+};
+
+/// Middleware function that rejects requests without the instance admin scope
+pub async fn require_admin(request: Request<Body>, next: Next) -> Response {
+    let is_admin = match request.extensions().get::<AuthenticatedUser>() {
+        Some(user) => user.permissions.iter().any(|p| p == INSTANCE_ADMIN_SCOPE),
+        None => {
+            return ApiError::unauthorized("Authentication required").into_response();
+        },
+    };
+
+    if !is_admin {
+        return ApiError::forbidden("Admin access required").into_response();
+    }
+
+    next.run(request).await
+}
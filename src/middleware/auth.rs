@@ -1,8 +1,11 @@
 // Temporary auth middleware for compatibility
 // This will be replaced with proper Axum middleware
 
+use axum::http::StatusCode;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::api_error::ApiError;
+
 /// Authenticated user information extracted from JWT
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthenticatedUser {
@@ -12,4 +15,27 @@ pub struct AuthenticatedUser {
     pub subscription_tier: String,
     pub permissions: Vec<String>,
     pub exp: u64,
+    /// Admin acting as this user with an impersonation token
+    #[serde(default)]
+    pub impersonator_id: Option<String>,
+}
+
+impl AuthenticatedUser {
+    pub fn is_impersonated(&self) -> bool {
+        self.impersonator_id.is_some()
+    }
+
+    /// Reject operations support must not perform on a user's behalf
+    /// (account deletion, email change, ending the user's other sessions,
+    /// erasing a link)
+    pub fn forbid_impersonation(&self) -> Result<(), Box<ApiError>> {
+        if self.is_impersonated() {
+            return Err(Box::new(ApiError::new(
+                StatusCode::FORBIDDEN,
+                "IMPERSONATION_FORBIDDEN",
+                "This action is not allowed while impersonating a user",
+            )));
+        }
+        Ok(())
+    }
 }
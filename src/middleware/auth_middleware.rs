@@ -1,80 +1,152 @@
 // Authentication middleware for protected routes
 // Validates JWT tokens and injects AuthenticatedUser into request extensions
 
+use std::net::SocketAddr;
+
 use axum::{
     body::Body,
-    extract::{FromRequestParts, State},
-    http::{header, request::Parts, Request, StatusCode},
+    extract::{ConnectInfo, FromRequestParts, MatchedPath, OriginalUri, State},
+    http::{header, request::Parts, HeaderMap, Request, StatusCode},
     middleware::Next,
-    response::{IntoResponse, Json, Response},
+    response::{IntoResponse, Response},
 };
-use serde_json::json;
+use uuid::Uuid;
 
-use crate::{app::AppState, middleware::auth::AuthenticatedUser};
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    services::{api_usage::API_USAGE, JwtService},
+    utils::{api_error::ApiError, audit_logger::AuditLogger},
+};
 
 /// Middleware function that validates JWT tokens and adds AuthenticatedUser to extensions
+/// Requests made with an impersonation token are audit-logged with both identities; all
+/// others count towards the user's API usage (GET /v1/usage)
 pub async fn auth_middleware(
     State(app_state): State<AppState>,
     mut request: Request<Body>,
     next: Next,
 ) -> Response {
-    // Extract the Authorization header
-    let auth_header = request
-        .headers()
-        .get(header::AUTHORIZATION)
-        .and_then(|h| h.to_str().ok());
-
-    let token = match auth_header {
-        Some(header) if header.starts_with("Bearer ") => &header[7..],
-        _ => {
-            return (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "success": false,
-                    "message": "Missing or invalid authorization header"
-                })),
-            )
-                .into_response();
-        },
-    };
-
-    // Validate the token using JwtService from AppState
-    match app_state.jwt_service.validate_access_token(token) {
-        Ok(claims) => {
-            // Create AuthenticatedUser from claims
-            let auth_user = AuthenticatedUser {
-                user_id: claims.sub,
-                token_id: claims.jti,
-                email: claims.email,
-                subscription_tier: claims.tier,
-                permissions: claims.scope,
-                exp: claims.exp,
+    match authenticate_bearer(&app_state.jwt_service, request.headers()).await {
+        Ok(auth_user) => {
+            // Support staff acting as the user don't consume the user's quota
+            let usage = if auth_user.is_impersonated() {
+                log_impersonated_request(&auth_user, &request);
+                None
+            } else {
+                Uuid::parse_str(&auth_user.user_id)
+                    .ok()
+                    .map(|user_id| (user_id, usage_endpoint(&request)))
             };
 
             // Add AuthenticatedUser to request extensions
             request.extensions_mut().insert(auth_user);
 
             // Continue to the next handler
-            next.run(request).await
+            let response = next.run(request).await;
+
+            if let Some((user_id, endpoint)) = usage {
+                let rate_limited = response.status() == StatusCode::TOO_MANY_REQUESTS;
+                API_USAGE.record(user_id, &endpoint, rate_limited);
+            }
+            response
+        },
+        Err(e) => e.into_response(),
+    }
+}
+
+/// Validate the `Authorization: Bearer` access token and build the AuthenticatedUser
+/// Tokens issued before the user's tokens were revoked (logout, password reset,
+/// deactivation) are rejected even though they have not expired
+pub async fn authenticate_bearer(
+    jwt_service: &JwtService,
+    headers: &HeaderMap,
+) -> Result<AuthenticatedUser, ApiError> {
+    let token = bearer_token(headers)
+        .ok_or_else(|| ApiError::unauthorized("Missing or invalid authorization header"))?;
+
+    // Validate the token using JwtService from AppState
+    match jwt_service.validate_access_token(token) {
+        Ok(claims) if jwt_service.is_access_token_revoked(&claims).await => {
+            tracing::warn!("Rejected revoked access token for user {}", claims.sub);
+            Err(ApiError::unauthorized("Invalid or expired token"))
         },
+        Ok(claims) => Ok(AuthenticatedUser {
+            user_id: claims.sub,
+            token_id: claims.jti,
+            email: claims.email,
+            subscription_tier: claims.tier,
+            permissions: claims.scope,
+            exp: claims.exp,
+            impersonator_id: claims.impersonator,
+        }),
         Err(e) => {
             tracing::warn!("JWT validation failed: {}", e);
-            (
-                StatusCode::UNAUTHORIZED,
-                Json(json!({
-                    "success": false,
-                    "message": "Invalid or expired token"
-                })),
-            )
-                .into_response()
+            Err(ApiError::unauthorized("Invalid or expired token"))
         },
     }
 }
 
+fn log_impersonated_request(auth_user: &AuthenticatedUser, request: &Request<Body>) {
+    let (Some(user_id), Some(impersonator_id)) = (
+        Uuid::parse_str(&auth_user.user_id).ok(),
+        auth_user
+            .impersonator_id
+            .as_deref()
+            .and_then(|id| Uuid::parse_str(id).ok()),
+    ) else {
+        return;
+    };
+
+    // Nested routers see the path without their prefix
+    let path = request
+        .extensions()
+        .get::<OriginalUri>()
+        .map(|uri| uri.path())
+        .unwrap_or_else(|| request.uri().path());
+    let ip_address = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string());
+    let user_agent = request
+        .headers()
+        .get(header::USER_AGENT)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string);
+
+    AuditLogger::log_impersonated_request(
+        user_id,
+        impersonator_id,
+        request.method().as_str(),
+        path,
+        ip_address,
+        user_agent,
+    );
+}
+
+/// Method and route template the request matched, e.g. `GET /v1/links/{id}`; templates keep
+/// the number of distinct endpoints small
+fn usage_endpoint(request: &Request<Body>) -> String {
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(MatchedPath::as_str)
+        .unwrap_or("unmatched");
+    format!("{} {}", request.method(), path)
+}
+
+/// Raw token from an `Authorization: Bearer <token>` header
+pub fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "))
+}
+
 /// Extractor for AuthenticatedUser from request extensions
 /// This allows handlers to use Extension<AuthenticatedUser> in their parameters
 impl FromRequestParts<AppState> for AuthenticatedUser {
-    type Rejection = (StatusCode, Json<serde_json::Value>);
+    type Rejection = ApiError;
 
     async fn from_request_parts(
         parts: &mut Parts,
@@ -84,14 +156,6 @@ impl FromRequestParts<AppState> for AuthenticatedUser {
             .extensions
             .get::<AuthenticatedUser>()
             .cloned()
-            .ok_or_else(|| {
-                (
-                    StatusCode::UNAUTHORIZED,
-                    Json(json!({
-                        "success": false,
-                        "message": "Authentication required"
-                    })),
-                )
-            })
+            .ok_or_else(|| ApiError::unauthorized("Authentication required"))
     }
 }
@@ -1,20 +1,28 @@
 use axum::{
     body::Body,
+    extract::State,
     http::{
-        header::{self, HeaderValue},
+        header::{self, HeaderMap, HeaderValue},
         Method, Request, Response, StatusCode,
     },
     middleware::Next,
 };
+use std::sync::Arc;
 use tracing::debug;
 
+use crate::app_config::{CorsConfig, CorsPolicy};
+
 /// Dynamic CORS middleware that handles wildcard for staging/development
-/// while properly supporting credentials
+/// while properly supporting credentials. Policy is resolved per route group
+/// (`/v1/*` vs the public redirect routes); exempt groups pass through untouched.
 pub async fn dynamic_cors_middleware(
+    State(config): State<Arc<CorsConfig>>,
     req: Request<Body>,
     next: Next,
-) -> Result<Response<Body>, StatusCode> {
-    let config = crate::app_config::config();
+) -> Response<Body> {
+    let Some(policy) = config.policy_for(req.uri().path()) else {
+        return next.run(req).await;
+    };
 
     // Get the origin from the request
     let origin = req
@@ -23,75 +31,147 @@ pub async fn dynamic_cors_middleware(
         .and_then(|v| v.to_str().ok())
         .map(String::from);
 
-    // Check if wildcard is configured
-    let has_wildcard = config.cors_allowed_origins.iter().any(|o| o == "*");
+    let allowed_origin = origin.as_deref().and_then(|o| {
+        let allowed = config.allowed_origin(o);
+        debug!("CORS: Origin {} allowed: {}", o, allowed.is_some());
+        allowed.map(String::from)
+    });
 
-    // For staging/dev with wildcard: reflect the origin (allows any origin with credentials)
-    // For production or specific origins: check against whitelist
-    let allowed_origin = if has_wildcard && !config.is_production() {
-        // Staging/Dev mode with wildcard - reflect the origin back
-        debug!("CORS: Reflecting origin for staging/dev: {:?}", origin);
-        origin.clone()
-    } else {
-        // Production mode or specific origins - check whitelist
-        origin.as_ref().and_then(|req_origin| {
-            if config.cors_allowed_origins.contains(req_origin) {
-                debug!("CORS: Origin allowed from whitelist: {}", req_origin);
-                Some(req_origin.clone())
-            } else {
-                debug!("CORS: Origin not in whitelist: {}", req_origin);
-                None
-            }
-        })
-    };
+    // Preflight requests are answered here and never reach the handlers
+    let is_preflight = req.method() == Method::OPTIONS
+        && req
+            .headers()
+            .contains_key(header::ACCESS_CONTROL_REQUEST_METHOD);
 
-    // Handle preflight OPTIONS requests
-    if req.method() == Method::OPTIONS {
-        let mut response = Response::new(Body::empty());
+    if is_preflight {
+        return preflight_response(&config, &policy, allowed_origin.as_deref(), req.headers());
+    }
 
-        if let Some(allowed) = allowed_origin {
-            response.headers_mut().insert(
-                header::ACCESS_CONTROL_ALLOW_ORIGIN,
-                HeaderValue::from_str(&allowed).unwrap(),
-            );
-            response.headers_mut().insert(
-                header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
-                HeaderValue::from_static("true"),
-            );
-            response.headers_mut().insert(
-                header::ACCESS_CONTROL_ALLOW_METHODS,
-                HeaderValue::from_static("GET, POST, PUT, DELETE, OPTIONS"),
-            );
-            response.headers_mut().insert(
-                header::ACCESS_CONTROL_ALLOW_HEADERS,
-                HeaderValue::from_static(
-                    "content-type, authorization, accept, origin, x-requested-with",
-                ),
-            );
-            response.headers_mut().insert(
-                header::ACCESS_CONTROL_MAX_AGE,
-                HeaderValue::from_static("3600"),
+    // Process the actual request
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+
+    // The response varies by origin whether or not this one was allowed
+    headers.append(header::VARY, HeaderValue::from_static("origin"));
+
+    if let Some(allowed) = allowed_origin {
+        insert_origin_headers(headers, &config, &allowed);
+
+        if !policy.expose_headers.is_empty() {
+            insert_list(
+                headers,
+                header::ACCESS_CONTROL_EXPOSE_HEADERS,
+                policy.expose_headers,
             );
         }
+    }
+
+    response
+}
+
+fn preflight_response(
+    config: &CorsConfig,
+    policy: &CorsPolicy<'_>,
+    allowed_origin: Option<&str>,
+    request_headers: &HeaderMap,
+) -> Response<Body> {
+    let mut response = Response::new(Body::empty());
+    let headers = response.headers_mut();
 
-        *response.status_mut() = StatusCode::OK;
-        return Ok(response);
+    for vary in [
+        "origin",
+        "access-control-request-method",
+        "access-control-request-headers",
+    ] {
+        headers.append(header::VARY, HeaderValue::from_static(vary));
     }
 
-    // Process the actual request
-    let mut response = next.run(req).await;
+    let requested_method = request_headers
+        .get(header::ACCESS_CONTROL_REQUEST_METHOD)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+    let requested_headers: Vec<String> = request_headers
+        .get(header::ACCESS_CONTROL_REQUEST_HEADERS)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| {
+            v.split(',')
+                .map(|h| h.trim().to_lowercase())
+                .filter(|h| !h.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
 
-    // Add CORS headers to the response
-    if let Some(allowed) = allowed_origin {
-        response.headers_mut().insert(
-            header::ACCESS_CONTROL_ALLOW_ORIGIN,
-            HeaderValue::from_str(&allowed).unwrap(),
+    let method_allowed = policy
+        .allowed_methods
+        .iter()
+        .any(|m| m.eq_ignore_ascii_case(requested_method));
+    let any_header = policy.allowed_headers.iter().any(|h| h == "*");
+    let headers_allowed = any_header
+        || requested_headers.iter().all(|requested| {
+            policy
+                .allowed_headers
+                .iter()
+                .any(|h| h.eq_ignore_ascii_case(requested))
+        });
+
+    let Some(allowed) = allowed_origin.filter(|_| method_allowed && headers_allowed) else {
+        debug!(
+            "CORS: Rejecting preflight (origin allowed: {}, method {} allowed: {}, headers allowed: {})",
+            allowed_origin.is_some(),
+            requested_method,
+            method_allowed,
+            headers_allowed
+        );
+        *response.status_mut() = StatusCode::FORBIDDEN;
+        return response;
+    };
+
+    insert_origin_headers(headers, config, allowed);
+    insert_list(
+        headers,
+        header::ACCESS_CONTROL_ALLOW_METHODS,
+        policy.allowed_methods,
+    );
+    if any_header {
+        // Credentialed requests can't use a literal "*", so echo what was asked for
+        insert_list(
+            headers,
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            &requested_headers,
         );
-        response.headers_mut().insert(
+    } else {
+        insert_list(
+            headers,
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            policy.allowed_headers,
+        );
+    }
+    headers.insert(
+        header::ACCESS_CONTROL_MAX_AGE,
+        HeaderValue::from(policy.max_age_seconds),
+    );
+
+    *response.status_mut() = StatusCode::NO_CONTENT;
+    response
+}
+
+fn insert_origin_headers(headers: &mut HeaderMap, config: &CorsConfig, origin: &str) {
+    if let Ok(value) = HeaderValue::from_str(origin) {
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, value);
+    }
+    if config.allow_credentials {
+        headers.insert(
             header::ACCESS_CONTROL_ALLOW_CREDENTIALS,
             HeaderValue::from_static("true"),
         );
     }
+}
 
-    Ok(response)
+fn insert_list(headers: &mut HeaderMap, name: header::HeaderName, values: &[String]) {
+    if values.is_empty() {
+        return;
+    }
+    if let Ok(value) = HeaderValue::from_str(&values.join(", ")) {
+        headers.insert(name, value);
+    }
 }
@@ -0,0 +1,94 @@
+// Access control for Swagger UI and the OpenAPI spec under /v1/docs
+// Accepts the DOCS_BASIC_AUTH_* credential pair and, with DOCS_REQUIRE_ADMIN, a JWT carrying
+// the instance admin scope. Only layered when one of them is configured.
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{header, HeaderMap, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::prelude::*;
+use subtle::ConstantTimeEq;
+
+use crate::{
+    app_config::DocsAuthConfig,
+    config::permissions::INSTANCE_ADMIN_SCOPE,
+    middleware::auth_middleware::{authenticate_bearer, bearer_token},
+    services::JwtService,
+    utils::api_error::ApiError,
+};
+
+/// Realm sent in the basic auth challenge
+const DOCS_REALM: &str = "QCK API docs";
+
+/// Credentials accepted by `require_docs_access`
+pub struct DocsAccess {
+    pub jwt_service: Arc<JwtService>,
+    /// `user:password`, as encoded in a basic auth header; `None` disables basic auth
+    basic_credentials: Option<String>,
+    pub accept_admin_jwt: bool,
+}
+
+impl DocsAccess {
+    pub fn new(jwt_service: Arc<JwtService>, config: &DocsAuthConfig) -> Self {
+        Self {
+            jwt_service,
+            basic_credentials: config
+                .has_basic_auth()
+                .then(|| format!("{}:{}", config.username, config.password)),
+            accept_admin_jwt: config.require_admin,
+        }
+    }
+
+    fn is_basic_auth(&self, headers: &HeaderMap) -> bool {
+        let Some(expected) = self.basic_credentials.as_deref() else {
+            return false;
+        };
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("Basic "))
+            .and_then(|encoded| BASE64_STANDARD.decode(encoded.trim()).ok())
+            .is_some_and(|decoded| bool::from(decoded.ct_eq(expected.as_bytes())))
+    }
+
+    /// 401 telling the client which scheme to retry with; browsers prompt for basic auth
+    fn challenge(&self, error: ApiError) -> Response {
+        let scheme = if self.basic_credentials.is_some() {
+            format!("Basic realm=\"{}\", charset=\"UTF-8\"", DOCS_REALM)
+        } else {
+            format!("Bearer realm=\"{}\"", DOCS_REALM)
+        };
+        ([(header::WWW_AUTHENTICATE, scheme)], error).into_response()
+    }
+}
+
+/// Middleware function that keeps the API docs behind the configured credentials
+pub async fn require_docs_access(
+    State(access): State<Arc<DocsAccess>>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    if access.is_basic_auth(request.headers()) {
+        return next.run(request).await;
+    }
+
+    if access.accept_admin_jwt && bearer_token(request.headers()).is_some() {
+        let user = match authenticate_bearer(&access.jwt_service, request.headers()).await {
+            Ok(user) => user,
+            Err(e) => return access.challenge(e),
+        };
+        if !user.permissions.iter().any(|p| p == INSTANCE_ADMIN_SCOPE) {
+            return ApiError::forbidden("Admin access required").into_response();
+        }
+        return next.run(request).await;
+    }
+
+    access.challenge(ApiError::unauthorized(
+        "Authentication required to view the API docs",
+    ))
+}
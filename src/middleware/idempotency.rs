@@ -0,0 +1,142 @@
+// Idempotency-Key middleware for link creation routes
+// Layered with from_fn_with_state inside auth_middleware, so AuthenticatedUser is present
+// in extensions
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Extension, State},
+    http::{header, HeaderValue, Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::warn;
+
+use crate::{
+    app::AppState,
+    middleware::auth::AuthenticatedUser,
+    services::idempotency::{
+        IdempotencyCheck, IdempotencyService, StoredResponse, IDEMPOTENCY_KEY_HEADER,
+        IDEMPOTENT_REPLAYED_HEADER,
+    },
+    utils::{api_error::ApiError, link_errors::LinkError},
+};
+
+/// Same limit axum applies to JSON bodies by default
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Replays stored responses for requests carrying an `Idempotency-Key` header.
+/// Requests without the header pass straight through. Successful responses are
+/// stored for 24h; failures are not, so the client can retry with the same key.
+/// If Redis is unavailable the request is processed without idempotency.
+pub async fn idempotency_middleware(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(key) = request
+        .headers()
+        .get(IDEMPOTENCY_KEY_HEADER)
+        .map(|v| v.to_str().map(str::to_string))
+    else {
+        return next.run(request).await;
+    };
+
+    let key = match key {
+        Ok(key) => key,
+        Err(_) => {
+            return LinkError::BadRequest(
+                "Idempotency-Key may only contain visible ASCII characters".to_string(),
+            )
+            .into_response()
+        },
+    };
+    if let Err(msg) = IdempotencyService::validate_key(&key) {
+        return LinkError::BadRequest(msg).into_response();
+    }
+
+    let (parts, body) = request.into_parts();
+    let body = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => {
+            return ApiError::new(
+                StatusCode::PAYLOAD_TOO_LARGE,
+                "PAYLOAD_TOO_LARGE",
+                "Request body is too large",
+            )
+            .into_response()
+        },
+    };
+    let fingerprint = IdempotencyService::fingerprint(&body);
+    let request = Request::from_parts(parts, Body::from(body));
+
+    let service = IdempotencyService::new(state.redis_pool.clone());
+
+    match service.begin(&user.user_id, &key, &fingerprint).await {
+        Ok(IdempotencyCheck::Proceed) => {},
+        Ok(IdempotencyCheck::Replay(stored)) => return replay(stored),
+        Ok(IdempotencyCheck::Mismatch) => {
+            return LinkError::IdempotencyConflict(
+                "Idempotency-Key was already used with a different request body".to_string(),
+            )
+            .into_response()
+        },
+        Ok(IdempotencyCheck::InProgress) => {
+            return LinkError::IdempotencyConflict(
+                "A request with this Idempotency-Key is still being processed".to_string(),
+            )
+            .into_response()
+        },
+        Err(e) => {
+            warn!(
+                "Idempotency check failed, processing request without it: {}",
+                e
+            );
+            return next.run(request).await;
+        },
+    }
+
+    let response = next.run(request).await;
+
+    if !response.status().is_success() {
+        if let Err(e) = service.release(&user.user_id, &key).await {
+            warn!("Failed to release idempotency lock: {}", e);
+        }
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            warn!("Failed to buffer response for idempotency: {}", e);
+            if let Err(e) = service.release(&user.user_id, &key).await {
+                warn!("Failed to release idempotency lock: {}", e);
+            }
+            return LinkError::InternalError.into_response();
+        },
+    };
+
+    let stored = StoredResponse {
+        fingerprint,
+        status: parts.status.as_u16(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    };
+    if let Err(e) = service.complete(&user.user_id, &key, &stored).await {
+        warn!("Failed to store idempotent response: {}", e);
+    }
+
+    Response::from_parts(parts, Body::from(body))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+    headers.insert(IDEMPOTENT_REPLAYED_HEADER, HeaderValue::from_static("true"));
+    response
+}
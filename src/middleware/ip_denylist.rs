@@ -0,0 +1,30 @@
+// Rejects requests from addresses on the operator-managed IP denylist
+
+use std::net::SocketAddr;
+
+use axum::{
+    body::Body,
+    extract::ConnectInfo,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use tracing::debug;
+
+use crate::{services::ip_denylist, utils::api_error::ApiError};
+
+/// Answers 403 to denied client IPs before any routing or handler work.
+/// Requests without ConnectInfo (e.g. tests using `oneshot`) are let through.
+pub async fn ip_denylist_middleware(request: Request<Body>, next: Next) -> Response {
+    let client_ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(ip) = client_ip.filter(|ip| ip_denylist::is_denied(*ip)) {
+        debug!("Rejected request from denied IP {}", ip);
+        return ApiError::forbidden("Access from this address is denied").into_response();
+    }
+
+    next.run(request).await
+}
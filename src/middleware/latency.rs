@@ -0,0 +1,347 @@
+// Request latency middleware
+// Every route group records how long its requests take into an in-process histogram, so
+// operators get p50/p95/p99 per group from GET /v1/admin/perf without a metrics stack.
+// Buckets are log-linear (8 per power of two, about 12% relative error) over 1µs..~67s, so
+// memory is fixed per group. Counters are atomics spread over shards picked per thread; the
+// request path never takes a lock. Figures cover the current and previous WINDOW_SECONDS.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::{body::Body, extract::State, http::Request, middleware::Next, response::Response};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Length of one histogram window; reports merge the current and the previous one
+pub const WINDOW_SECONDS: u64 = 60;
+
+/// Route group of the short link redirects; every other group counts as API traffic
+pub const REDIRECT_GROUP: &str = "redirect";
+
+/// Sub-buckets per power of two
+const SUB_BUCKET_BITS: u32 = 3;
+const SUB_BUCKETS: usize = 1 << SUB_BUCKET_BITS;
+
+/// Values at or above 2^MAX_EXPONENT µs (~67s) land in the last bucket
+const MAX_EXPONENT: u32 = 26;
+const BUCKETS: usize = (MAX_EXPONENT - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKETS + 1;
+
+/// Independent counter sets per window; threads are spread over them round-robin
+const SHARDS: usize = 8;
+
+static PROCESS_START: Lazy<Instant> = Lazy::new(Instant::now);
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed) % SHARDS;
+}
+
+/// Histograms by route group, filled when the router is built
+static GROUPS: Lazy<Mutex<BTreeMap<&'static str, Arc<LatencyHistogram>>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+fn bucket_index(micros: u64) -> usize {
+    if micros < SUB_BUCKETS as u64 {
+        return micros as usize;
+    }
+    let exponent = 63 - micros.leading_zeros();
+    if exponent >= MAX_EXPONENT {
+        return BUCKETS - 1;
+    }
+    let sub = (micros >> (exponent - SUB_BUCKET_BITS)) as usize & (SUB_BUCKETS - 1);
+    (exponent - SUB_BUCKET_BITS + 1) as usize * SUB_BUCKETS + sub
+}
+
+/// Largest value (µs) that falls into the bucket; the last one reports its lower bound
+fn bucket_upper_bound(index: usize) -> u64 {
+    if index < SUB_BUCKETS {
+        return index as u64;
+    }
+    if index == BUCKETS - 1 {
+        return 1 << MAX_EXPONENT;
+    }
+    let exponent = (index / SUB_BUCKETS) as u32 + SUB_BUCKET_BITS - 1;
+    let sub = (index % SUB_BUCKETS) as u64;
+    let width = 1u64 << (exponent - SUB_BUCKET_BITS);
+    (1u64 << exponent) + (sub + 1) * width - 1
+}
+
+fn current_window() -> u64 {
+    PROCESS_START.elapsed().as_secs() / WINDOW_SECONDS
+}
+
+struct Window {
+    /// Window number the counts belong to
+    number: AtomicU64,
+    shards: Vec<[AtomicU64; BUCKETS]>,
+}
+
+impl Window {
+    fn new() -> Self {
+        Self {
+            number: AtomicU64::new(0),
+            shards: (0..SHARDS)
+                .map(|_| std::array::from_fn(|_| AtomicU64::new(0)))
+                .collect(),
+        }
+    }
+
+    fn clear(&self) {
+        for shard in &self.shards {
+            for count in shard {
+                count.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Latency histogram of one route group
+pub struct LatencyHistogram {
+    windows: [Window; 2],
+}
+
+impl LatencyHistogram {
+    fn new() -> Self {
+        Self {
+            windows: [Window::new(), Window::new()],
+        }
+    }
+
+    pub fn record(&self, micros: u64) {
+        self.record_at(current_window(), micros);
+    }
+
+    fn record_at(&self, number: u64, micros: u64) {
+        let window = &self.windows[(number % 2) as usize];
+        let seen = window.number.load(Ordering::Acquire);
+        // The first request of a new window recycles the slot of the one before last.
+        // Requests recorded while it is cleared may be lost; that is fine for percentiles.
+        if seen != number
+            && window
+                .number
+                .compare_exchange(seen, number, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        {
+            window.clear();
+        }
+        SHARD.with(|shard| {
+            window.shards[*shard][bucket_index(micros)].fetch_add(1, Ordering::Relaxed)
+        });
+    }
+
+    /// Bucket counts of the current and previous window
+    fn merged_at(&self, number: u64) -> [u64; BUCKETS] {
+        let mut merged = [0u64; BUCKETS];
+        for window in &self.windows {
+            let window_number = window.number.load(Ordering::Acquire);
+            if window_number != number && window_number + 1 != number {
+                continue;
+            }
+            for shard in &window.shards {
+                for (total, count) in merged.iter_mut().zip(shard.iter()) {
+                    *total += count.load(Ordering::Relaxed);
+                }
+            }
+        }
+        merged
+    }
+}
+
+/// Percentiles of one route group (or of all redirect / API groups together)
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct LatencySummary {
+    /// Requests in the window
+    pub count: u64,
+    pub p50_ms: f64,
+    pub p95_ms: f64,
+    pub p99_ms: f64,
+    pub max_ms: f64,
+}
+
+impl LatencySummary {
+    fn from_buckets(buckets: &[u64; BUCKETS]) -> Self {
+        let count: u64 = buckets.iter().sum();
+        if count == 0 {
+            return Self::default();
+        }
+        let percentile = |p: f64| {
+            let rank = ((count as f64 * p).ceil() as u64).max(1);
+            let mut seen = 0;
+            for (index, bucket) in buckets.iter().enumerate() {
+                seen += bucket;
+                if seen >= rank {
+                    return bucket_upper_bound(index) as f64 / 1000.0;
+                }
+            }
+            0.0
+        };
+        Self {
+            count,
+            p50_ms: percentile(0.50),
+            p95_ms: percentile(0.95),
+            p99_ms: percentile(0.99),
+            max_ms: percentile(1.0),
+        }
+    }
+}
+
+/// Request latency percentiles on this instance
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LatencyMetrics {
+    pub timestamp: String,
+    /// Requests from the last `window_seconds` to `2 * window_seconds` are included
+    pub window_seconds: u64,
+    /// Short link redirects
+    pub redirect: LatencySummary,
+    /// Every other route group together
+    pub api: LatencySummary,
+    /// Per route group
+    pub groups: BTreeMap<String, LatencySummary>,
+}
+
+pub fn latency_metrics() -> LatencyMetrics {
+    let number = current_window();
+    let groups = GROUPS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+    let mut redirect = [0u64; BUCKETS];
+    let mut api = [0u64; BUCKETS];
+    let mut summaries = BTreeMap::new();
+    for (group, histogram) in groups.iter() {
+        let buckets = histogram.merged_at(number);
+        let totals = if *group == REDIRECT_GROUP {
+            &mut redirect
+        } else {
+            &mut api
+        };
+        for (total, count) in totals.iter_mut().zip(buckets.iter()) {
+            *total += count;
+        }
+        summaries.insert(group.to_string(), LatencySummary::from_buckets(&buckets));
+    }
+
+    LatencyMetrics {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        window_seconds: WINDOW_SECONDS,
+        redirect: LatencySummary::from_buckets(&redirect),
+        api: LatencySummary::from_buckets(&api),
+        groups: summaries,
+    }
+}
+
+/// Histogram a route group records into
+#[derive(Clone)]
+pub struct RouteLatency {
+    histogram: Arc<LatencyHistogram>,
+}
+
+impl RouteLatency {
+    /// Layers created with the same group share one histogram
+    pub fn new(group: &'static str) -> Self {
+        let histogram = GROUPS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .entry(group)
+            .or_insert_with(|| Arc::new(LatencyHistogram::new()))
+            .clone();
+        Self { histogram }
+    }
+}
+
+/// Record the time until the route group's response starts
+pub async fn latency_middleware(
+    State(latency): State<RouteLatency>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let started = Instant::now();
+    let response = next.run(request).await;
+    latency
+        .histogram
+        .record(started.elapsed().as_micros().min(u64::MAX as u128) as u64);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_bounds() {
+        for micros in [0, 1, 7, 8, 9, 15, 16, 1_000, 12_345, 999_999, 60_000_000] {
+            let index = bucket_index(micros);
+            assert!(bucket_upper_bound(index) >= micros, "{}", micros);
+            if index > 0 {
+                assert!(bucket_upper_bound(index - 1) < micros, "{}", micros);
+            }
+            // Log-linear buckets stay within 1/8 of the value
+            assert!(
+                bucket_upper_bound(index) - micros <= micros / 8,
+                "{}",
+                micros
+            );
+        }
+        assert_eq!(bucket_index(u64::MAX), BUCKETS - 1);
+    }
+
+    #[test]
+    fn test_percentiles() {
+        let histogram = LatencyHistogram::new();
+        for ms in 1..=100u64 {
+            histogram.record_at(5, ms * 1000);
+        }
+        let summary = LatencySummary::from_buckets(&histogram.merged_at(5));
+
+        assert_eq!(summary.count, 100);
+        assert!(
+            (50.0..=57.0).contains(&summary.p50_ms),
+            "{}",
+            summary.p50_ms
+        );
+        assert!(
+            (95.0..=107.0).contains(&summary.p95_ms),
+            "{}",
+            summary.p95_ms
+        );
+        assert!(
+            (100.0..=113.0).contains(&summary.max_ms),
+            "{}",
+            summary.max_ms
+        );
+    }
+
+    #[test]
+    fn test_windows_roll_over() {
+        let histogram = LatencyHistogram::new();
+        histogram.record_at(5, 1_000);
+        histogram.record_at(6, 2_000);
+
+        // The previous window is still reported
+        assert_eq!(
+            LatencySummary::from_buckets(&histogram.merged_at(6)).count,
+            2
+        );
+
+        // Window 7 reuses the slot of window 5
+        histogram.record_at(7, 3_000);
+        assert_eq!(
+            LatencySummary::from_buckets(&histogram.merged_at(7)).count,
+            2
+        );
+        assert_eq!(
+            LatencySummary::from_buckets(&histogram.merged_at(9)).count,
+            0
+        );
+    }
+
+    #[test]
+    fn test_groups_share_histogram() {
+        let first = RouteLatency::new("latency_test_group");
+        let second = RouteLatency::new("latency_test_group");
+        assert!(Arc::ptr_eq(&first.histogram, &second.histogram));
+    }
+}
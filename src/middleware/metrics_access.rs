@@ -0,0 +1,65 @@
+// Access control for operational metrics endpoints
+// Accepts either the static METRICS_TOKEN (for scrapers) or a JWT carrying the instance admin scope
+
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::Request,
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use subtle::ConstantTimeEq;
+
+use crate::{
+    config::permissions::INSTANCE_ADMIN_SCOPE,
+    middleware::auth_middleware::{authenticate_bearer, bearer_token},
+    services::JwtService,
+    utils::api_error::ApiError,
+};
+
+/// Credentials accepted by `require_metrics_access`
+pub struct MetricsAccess {
+    pub jwt_service: Arc<JwtService>,
+    /// Static bearer token for scrapers; `None` allows admin JWTs only
+    pub static_token: Option<String>,
+}
+
+impl MetricsAccess {
+    pub fn new(jwt_service: Arc<JwtService>, static_token: &str) -> Self {
+        Self {
+            jwt_service,
+            static_token: Some(static_token.to_string()).filter(|t| !t.is_empty()),
+        }
+    }
+
+    fn is_static_token(&self, token: &str) -> bool {
+        self.static_token
+            .as_deref()
+            .is_some_and(|expected| bool::from(expected.as_bytes().ct_eq(token.as_bytes())))
+    }
+}
+
+/// Middleware function that rejects metrics requests from anyone but operators
+pub async fn require_metrics_access(
+    State(access): State<Arc<MetricsAccess>>,
+    mut request: Request<Body>,
+    next: Next,
+) -> Response {
+    if bearer_token(request.headers()).is_some_and(|token| access.is_static_token(token)) {
+        return next.run(request).await;
+    }
+
+    let user = match authenticate_bearer(&access.jwt_service, request.headers()).await {
+        Ok(user) => user,
+        Err(e) => return e.into_response(),
+    };
+
+    if !user.permissions.iter().any(|p| p == INSTANCE_ADMIN_SCOPE) {
+        return ApiError::forbidden("Admin access required").into_response();
+    }
+
+    request.extensions_mut().insert(user);
+    next.run(request).await
+}
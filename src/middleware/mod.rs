@@ -1,14 +1,34 @@
 // Middleware modules for QCK Backend
 // DEV-113: JWT Token Validation + DEV-115: Rate Limiting Middleware
 
+pub mod admin_middleware;
 pub mod auth;
 pub mod auth_middleware;
 pub mod cors;
+pub mod docs_access;
+pub mod idempotency;
+pub mod ip_denylist;
+pub mod latency;
+pub mod metrics_access;
+pub mod request_id;
+pub mod request_timeout;
+pub mod security_headers;
+pub mod service_auth;
 
 // Re-export auth types and middleware
+pub use admin_middleware::require_admin;
 pub use auth::AuthenticatedUser;
 pub use auth_middleware::auth_middleware;
 pub use cors::dynamic_cors_middleware;
+pub use docs_access::{require_docs_access, DocsAccess};
+pub use idempotency::idempotency_middleware;
+pub use ip_denylist::ip_denylist_middleware;
+pub use latency::{latency_middleware, RouteLatency};
+pub use metrics_access::{require_metrics_access, MetricsAccess};
+pub use request_id::request_id_middleware;
+pub use request_timeout::{request_timeout_middleware, RouteTimeout};
+pub use security_headers::security_headers_middleware;
+pub use service_auth::{require_service_auth, ServiceAuth};
 
 // TODO: Implement the following middleware modules for Actix-web:
 // - AuthMiddleware: JWT validation middleware
@@ -0,0 +1,74 @@
+// Request ID middleware
+// Tags every request with an ID that is echoed in the X-Request-Id header and in error bodies
+
+use axum::{
+    body::Body,
+    http::{HeaderName, HeaderValue, Request},
+    middleware::Next,
+    response::Response,
+};
+use uuid::Uuid;
+
+/// Header carrying the request ID in both directions
+pub static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
+const MAX_REQUEST_ID_LENGTH: usize = 128;
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// ID of the request currently being handled, if the middleware is installed
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Reuses a well-formed incoming `X-Request-Id` (e.g. from a load balancer) or
+/// generates a UUID, makes it available to `ApiError` for the duration of the
+/// request, and returns it in the response header.
+pub async fn request_id_middleware(mut request: Request<Body>, next: Next) -> Response {
+    let request_id = request
+        .headers()
+        .get(&REQUEST_ID_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+
+    let header_value = HeaderValue::from_str(&request_id).expect("request ID is visible ASCII");
+    request
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER.clone(), header_value.clone());
+
+    let mut response = REQUEST_ID.scope(request_id, next.run(request)).await;
+    response
+        .headers_mut()
+        .insert(REQUEST_ID_HEADER.clone(), header_value);
+    response
+}
+
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty() && id.len() <= MAX_REQUEST_ID_LENGTH && id.chars().all(|c| c.is_ascii_graphic())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_request_id() {
+        assert!(is_valid_request_id("lb-7f3a9c"));
+        assert!(!is_valid_request_id(""));
+        assert!(!is_valid_request_id("has space"));
+        assert!(!is_valid_request_id(&"a".repeat(MAX_REQUEST_ID_LENGTH + 1)));
+    }
+
+    #[tokio::test]
+    async fn test_current_request_id_outside_scope() {
+        assert_eq!(current_request_id(), None);
+        let id = REQUEST_ID
+            .scope("req-1".to_string(), async { current_request_id() })
+            .await;
+        assert_eq!(id.as_deref(), Some("req-1"));
+    }
+}
@@ -0,0 +1,105 @@
+// Request timeout middleware
+// Each route group gets its own budget (RequestTimeoutConfig). A handler that has not produced
+// a response in time is dropped and the client gets 504 in the standard error envelope.
+// The budget covers the time until the response starts: a streamed body (SSE, CSV export)
+// keeps flowing after its headers are sent, so streaming endpoints are never cut off mid-way.
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use axum::{
+    body::Body,
+    extract::State,
+    http::{Request, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tracing::warn;
+use utoipa::ToSchema;
+
+use crate::utils::api_error::ApiError;
+
+/// Timed-out requests per route group since process start
+static TIMEOUT_COUNTS: Lazy<Mutex<BTreeMap<&'static str, u64>>> =
+    Lazy::new(|| Mutex::new(BTreeMap::new()));
+
+/// Time limit for one route group
+#[derive(Debug, Clone, Copy)]
+pub struct RouteTimeout {
+    group: &'static str,
+    budget: Option<Duration>,
+}
+
+impl RouteTimeout {
+    /// `millis` of 0 disables the limit for the group
+    pub fn new(group: &'static str, millis: u64) -> Self {
+        Self {
+            group,
+            budget: (millis > 0).then(|| Duration::from_millis(millis)),
+        }
+    }
+}
+
+/// Request timeouts since process start
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RequestTimeoutMetrics {
+    pub timestamp: String,
+    /// Timed-out requests per route group
+    pub timeouts: BTreeMap<String, u64>,
+    pub total: u64,
+}
+
+pub fn request_timeout_metrics() -> RequestTimeoutMetrics {
+    let counts = TIMEOUT_COUNTS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    RequestTimeoutMetrics {
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        timeouts: counts
+            .iter()
+            .map(|(group, count)| (group.to_string(), *count))
+            .collect(),
+        total: counts.values().sum(),
+    }
+}
+
+/// Answer 504 when the route group's budget runs out before the handler responds
+pub async fn request_timeout_middleware(
+    State(timeout): State<RouteTimeout>,
+    request: Request<Body>,
+    next: Next,
+) -> Response {
+    let Some(budget) = timeout.budget else {
+        return next.run(request).await;
+    };
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    match tokio::time::timeout(budget, next.run(request)).await {
+        Ok(response) => response,
+        Err(_) => {
+            *TIMEOUT_COUNTS
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .entry(timeout.group)
+                .or_insert(0) += 1;
+            warn!(
+                "{} {} timed out after {}ms (route group: {})",
+                method,
+                path,
+                budget.as_millis(),
+                timeout.group
+            );
+            ApiError::new(
+                StatusCode::GATEWAY_TIMEOUT,
+                "REQUEST_TIMEOUT",
+                "The request took too long to process",
+            )
+            .into_response()
+        },
+    }
+}
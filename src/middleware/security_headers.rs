@@ -0,0 +1,80 @@
+use axum::{
+    body::Body,
+    extract::State,
+    http::{
+        header::{self, HeaderName, HeaderValue},
+        Request, Response,
+    },
+    middleware::Next,
+};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::app_config::SecurityHeadersConfig;
+
+/// Path prefix of the Swagger UI, which gets its own CSP and may be framed same-origin
+pub const DOCS_PATH_PREFIX: &str = "/v1/docs";
+
+/// Adds the configured security headers to every response (API, redirects, error pages).
+/// Headers a handler already set are left untouched.
+pub async fn security_headers_middleware(
+    State(config): State<Arc<SecurityHeadersConfig>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response<Body> {
+    let is_docs = req.uri().path().starts_with(DOCS_PATH_PREFIX);
+    let mut response = next.run(req).await;
+
+    if !config.enabled {
+        return response;
+    }
+
+    // Swagger UI may be framed by our own origin; a disabled X-Frame-Options stays disabled
+    let (frame_options, content_security_policy) = if is_docs {
+        let frame_options = if config.frame_options.is_empty() {
+            ""
+        } else {
+            "SAMEORIGIN"
+        };
+        (frame_options, config.docs_content_security_policy.as_str())
+    } else {
+        (
+            config.frame_options.as_str(),
+            config.content_security_policy.as_str(),
+        )
+    };
+
+    let headers = [
+        (
+            header::X_CONTENT_TYPE_OPTIONS,
+            config.content_type_options.as_str(),
+        ),
+        (header::X_FRAME_OPTIONS, frame_options),
+        (header::CONTENT_SECURITY_POLICY, content_security_policy),
+        (header::REFERRER_POLICY, config.referrer_policy.as_str()),
+        (
+            header::STRICT_TRANSPORT_SECURITY,
+            config.strict_transport_security.as_str(),
+        ),
+    ];
+
+    for (name, value) in headers {
+        set_if_absent(&mut response, name, value);
+    }
+
+    response
+}
+
+fn set_if_absent(response: &mut Response<Body>, name: HeaderName, value: &str) {
+    // Empty value means the header is disabled
+    if value.is_empty() || response.headers().contains_key(&name) {
+        return;
+    }
+
+    match HeaderValue::from_str(value) {
+        Ok(value) => {
+            response.headers_mut().insert(name, value);
+        },
+        Err(_) => warn!("Skipping invalid {} header value", name),
+    }
+}
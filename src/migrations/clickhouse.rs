@@ -27,8 +27,79 @@ const MIGRATION_004: (&str, &str) = (
     include_str!("../../migrations/clickhouse/004_link_totals_table.sql"),
 );
 
+const MIGRATION_005: (&str, &str) = (
+    "005_click_rollups",
+    include_str!("../../migrations/clickhouse/005_click_rollups.sql"),
+);
+
+const MIGRATION_006: (&str, &str) = (
+    "006_click_dedup",
+    include_str!("../../migrations/clickhouse/006_click_dedup.sql"),
+);
+
+const MIGRATION_007: (&str, &str) = (
+    "007_security_scan_events",
+    include_str!("../../migrations/clickhouse/007_security_scan_events.sql"),
+);
+
+const MIGRATION_008: (&str, &str) = (
+    "008_click_sub_path",
+    include_str!("../../migrations/clickhouse/008_click_sub_path.sql"),
+);
+
+const MIGRATION_009: (&str, &str) = (
+    "009_rate_limit_offenders",
+    include_str!("../../migrations/clickhouse/009_rate_limit_offenders.sql"),
+);
+
+const MIGRATION_010: (&str, &str) = (
+    "010_referrer_campaign",
+    include_str!("../../migrations/clickhouse/010_referrer_campaign.sql"),
+);
+
+const MIGRATION_011: (&str, &str) = (
+    "011_click_entry_point",
+    include_str!("../../migrations/clickhouse/011_click_entry_point.sql"),
+);
+
+const MIGRATION_012: (&str, &str) = (
+    "012_click_referrer_block",
+    include_str!("../../migrations/clickhouse/012_click_referrer_block.sql"),
+);
+
+const MIGRATION_013: (&str, &str) = (
+    "013_urlhaus_keep_listed",
+    include_str!("../../migrations/clickhouse/013_urlhaus_keep_listed.sql"),
+);
+
+const MIGRATION_014: (&str, &str) = (
+    "014_link_conversions",
+    include_str!("../../migrations/clickhouse/014_link_conversions.sql"),
+);
+
+const MIGRATION_015: (&str, &str) = (
+    "015_rate_limit_events",
+    include_str!("../../migrations/clickhouse/015_rate_limit_events.sql"),
+);
+
 /// List of all migrations in execution order (OSS - no seed migrations)
-const MIGRATIONS: &[(&str, &str)] = &[MIGRATION_001, MIGRATION_002, MIGRATION_003, MIGRATION_004];
+const MIGRATIONS: &[(&str, &str)] = &[
+    MIGRATION_001,
+    MIGRATION_002,
+    MIGRATION_003,
+    MIGRATION_004,
+    MIGRATION_005,
+    MIGRATION_006,
+    MIGRATION_007,
+    MIGRATION_008,
+    MIGRATION_009,
+    MIGRATION_010,
+    MIGRATION_011,
+    MIGRATION_012,
+    MIGRATION_013,
+    MIGRATION_014,
+    MIGRATION_015,
+];
 
 /// ClickHouse client configuration
 #[derive(Debug, Clone)]
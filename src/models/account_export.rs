@@ -0,0 +1,369 @@
+// Account export and import (data portability)
+// A user's profile and links are serialized into a versioned JSON archive that can be
+// downloaded by the user and recreated on another instance by an operator
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::models::{
+    link::{ExpiryPolicy, Link},
+    user::User,
+};
+use crate::schema::account_exports;
+
+/// Version written to new archives; imports reject any other version
+pub const ACCOUNT_ARCHIVE_VERSION: u32 = 1;
+
+/// Where an export job is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStatus {
+    Pending,
+    Ready,
+    Failed,
+}
+
+impl ExportStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExportStatus::Pending => "pending",
+            ExportStatus::Ready => "ready",
+            ExportStatus::Failed => "failed",
+        }
+    }
+}
+
+impl From<&str> for ExportStatus {
+    fn from(value: &str) -> Self {
+        match value {
+            "ready" => ExportStatus::Ready,
+            "failed" => ExportStatus::Failed,
+            _ => ExportStatus::Pending,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = account_exports)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AccountExport {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub status: String,
+    pub include_stats: bool,
+    pub archive: Option<serde_json::Value>,
+    pub token_hash: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = account_exports)]
+pub struct NewAccountExport {
+    pub user_id: Uuid,
+    pub include_stats: bool,
+    pub expires_at: DateTime<Utc>,
+}
+
+// =============================================================================
+// ARCHIVE FORMAT
+// =============================================================================
+
+/// Everything exported for one account
+/// Ids are not included: imports always create new ones
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct AccountArchive {
+    /// Archive format version (currently 1)
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub user: ArchivedUser,
+    /// Links owned by the user that have not been deleted
+    pub links: Vec<ArchivedLink>,
+    /// Lifetime click totals per short code; present when requested at export time
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub click_stats: Option<Vec<ArchivedClickStats>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedUser {
+    pub email: String,
+    pub full_name: String,
+    pub company_name: Option<String>,
+    pub subscription_tier: String,
+    pub email_verified: bool,
+    pub default_tracking_mode: String,
+    pub monthly_digest_enabled: bool,
+    #[schema(value_type = Object)]
+    pub notification_preferences: serde_json::Value,
+    /// Missing from archives exported before timezones were stored
+    #[serde(default = "default_timezone")]
+    pub timezone: String,
+    /// Missing from archives exported before strip lists were stored
+    #[serde(default)]
+    pub strip_query_params: Vec<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+fn default_timezone() -> String {
+    crate::utils::timezone::DEFAULT_TIMEZONE.to_string()
+}
+
+impl From<&User> for ArchivedUser {
+    fn from(user: &User) -> Self {
+        Self {
+            email: user.email.clone(),
+            full_name: user.full_name.clone(),
+            company_name: user.company_name.clone(),
+            subscription_tier: user.subscription_tier.clone(),
+            email_verified: user.email_verified,
+            default_tracking_mode: user.default_tracking_mode.clone(),
+            monthly_digest_enabled: user.monthly_digest_enabled,
+            notification_preferences: user.notification_preferences.clone(),
+            timezone: user.timezone.clone(),
+            strip_query_params: user.query_params_to_strip(),
+            created_at: user.created_at,
+        }
+    }
+}
+
+/// A link and its metadata
+/// Link passwords are never exported; protected links are only flagged
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedLink {
+    pub short_code: String,
+    pub original_url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub custom_alias: Option<String>,
+    pub is_active: bool,
+    pub is_password_protected: bool,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub og_image: Option<String>,
+    pub favicon_url: Option<String>,
+    pub utm_source: Option<String>,
+    pub utm_medium: Option<String>,
+    pub utm_campaign: Option<String>,
+    pub utm_term: Option<String>,
+    pub utm_content: Option<String>,
+    pub tracking_mode: String,
+    #[serde(default)]
+    pub path_passthrough: bool,
+    #[serde(default)]
+    pub allowed_referrers: Vec<String>,
+    #[serde(default)]
+    pub blocked_referrers: Vec<String>,
+    #[serde(default)]
+    pub referrer_strict: bool,
+    #[serde(default)]
+    pub expiry_policy: ExpiryPolicy,
+    #[serde(default)]
+    pub sliding_window_days: Option<i32>,
+    pub notes: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Link> for ArchivedLink {
+    fn from(link: &Link) -> Self {
+        Self {
+            short_code: link.short_code.clone(),
+            original_url: link.original_url.clone(),
+            title: link.title.clone(),
+            description: link.description.clone(),
+            tags: link.tags.iter().flatten().flatten().cloned().collect(),
+            custom_alias: link.custom_alias.clone(),
+            is_active: link.is_active,
+            is_password_protected: link.password_hash.is_some(),
+            expires_at: link.expires_at,
+            og_image: link.og_image.clone(),
+            favicon_url: link.favicon_url.clone(),
+            utm_source: link.utm_source.clone(),
+            utm_medium: link.utm_medium.clone(),
+            utm_campaign: link.utm_campaign.clone(),
+            utm_term: link.utm_term.clone(),
+            utm_content: link.utm_content.clone(),
+            tracking_mode: link.tracking_mode.clone(),
+            path_passthrough: link.path_passthrough,
+            allowed_referrers: link.allowed_referrer_domains(),
+            blocked_referrers: link.blocked_referrer_domains(),
+            referrer_strict: link.referrer_strict,
+            expiry_policy: link.expiry_policy(),
+            sliding_window_days: link.sliding_window_days,
+            notes: link.notes.clone(),
+            created_at: link.created_at,
+        }
+    }
+}
+
+/// Lifetime totals for one link
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchivedClickStats {
+    pub short_code: String,
+    pub total_clicks: u64,
+    /// Null when ClickHouse isn't enabled
+    pub unique_visitors: Option<u64>,
+}
+
+// =============================================================================
+// API MODELS
+// =============================================================================
+
+/// Query parameters for GET /v1/auth/me/export
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct AccountExportQuery {
+    /// Include lifetime click totals for each link
+    #[serde(default)]
+    pub include_stats: bool,
+}
+
+/// Query parameters for GET /v1/auth/export/download
+#[derive(Debug, Clone, Deserialize, IntoParams)]
+pub struct AccountExportDownloadQuery {
+    /// Token from the "export ready" email
+    pub token: String,
+}
+
+/// An export job; the download link is emailed once the archive is ready
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountExportResponse {
+    pub id: Uuid,
+    pub status: ExportStatus,
+    pub include_stats: bool,
+    /// When the archive and its download link are deleted
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AccountExport> for AccountExportResponse {
+    fn from(export: AccountExport) -> Self {
+        Self {
+            id: export.id,
+            status: ExportStatus::from(export.status.as_str()),
+            include_stats: export.include_stats,
+            expires_at: export.expires_at,
+            created_at: export.created_at,
+        }
+    }
+}
+
+/// Outcome for one imported entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Created,
+    /// Created under a suffixed short code because the original was taken
+    Renamed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportEntityResult {
+    /// `user` or `link`
+    pub entity: String,
+    /// Email or short code in the archive
+    pub source: String,
+    pub outcome: ImportOutcome,
+    /// Id of the created record
+    pub id: Option<Uuid>,
+    /// Short code the link was created with
+    pub short_code: Option<String>,
+    pub message: Option<String>,
+}
+
+/// Per-entity results of POST /v1/admin/users/import
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AccountImportResponse {
+    pub user_id: Uuid,
+    pub results: Vec<ImportEntityResult>,
+    pub links_created: usize,
+    pub links_renamed: usize,
+    pub links_failed: usize,
+}
+
+impl AccountImportResponse {
+    pub fn new(user_id: Uuid, results: Vec<ImportEntityResult>) -> Self {
+        let count = |outcome| {
+            results
+                .iter()
+                .filter(|r| r.entity == "link" && r.outcome == outcome)
+                .count()
+        };
+
+        Self {
+            user_id,
+            links_created: count(ImportOutcome::Created),
+            links_renamed: count(ImportOutcome::Renamed),
+            links_failed: count(ImportOutcome::Failed),
+            results,
+        }
+    }
+}
+
+/// Short codes to try, in order, when `code` may already be taken: `code`, `code-2`, `code-3`, ...
+/// The base is shortened so every candidate fits in `max_len`
+pub fn short_code_candidates(code: &str, max_len: usize, attempts: usize) -> Vec<String> {
+    let mut candidates = vec![code.to_string()];
+
+    for n in 2..=attempts {
+        let suffix = format!("-{}", n);
+        let keep = max_len.saturating_sub(suffix.len()).min(code.len());
+        let base = code[..keep].trim_end_matches(['-', '_']);
+        candidates.push(format!("{}{}", base, suffix));
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_code_candidates_use_numeric_suffixes() {
+        assert_eq!(
+            short_code_candidates("launch", 20, 3),
+            vec!["launch", "launch-2", "launch-3"]
+        );
+
+        // Long codes are shortened so the suffix still fits
+        let candidates = short_code_candidates("spring-sale-2024-web", 20, 12);
+        assert_eq!(candidates[1], "spring-sale-2024-w-2");
+        assert_eq!(candidates[11], "spring-sale-2024-12");
+        assert!(candidates.iter().all(|c| c.len() <= 20));
+    }
+
+    #[test]
+    fn test_archive_round_trips_without_stats() {
+        let json = serde_json::json!({
+            "version": 1,
+            "exported_at": "2024-06-01T00:00:00Z",
+            "user": {
+                "email": "owner@example.com",
+                "full_name": "Owner",
+                "company_name": null,
+                "subscription_tier": "pro",
+                "email_verified": true,
+                "default_tracking_mode": "full",
+                "monthly_digest_enabled": false,
+                "notification_preferences": {},
+                "created_at": "2024-01-01T00:00:00Z"
+            },
+            "links": []
+        });
+
+        let archive: AccountArchive = serde_json::from_value(json).unwrap();
+        assert_eq!(archive.version, ACCOUNT_ARCHIVE_VERSION);
+        assert_eq!(archive.user.timezone, "UTC");
+        assert!(archive.user.strip_query_params.is_empty());
+        assert!(archive.click_stats.is_none());
+        assert!(serde_json::to_value(&archive)
+            .unwrap()
+            .get("click_stats")
+            .is_none());
+    }
+}
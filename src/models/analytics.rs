@@ -0,0 +1,673 @@
+// Account-level analytics models
+// Monthly usage summaries served by GET /v1/analytics/summary and the monthly digest email,
+// plus the instance-wide totals served to operators by GET /v1/admin/stats
+
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::IpAddr;
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Links listed in a monthly summary
+pub const SUMMARY_TOP_LINKS: usize = 5;
+
+/// A calendar month (UTC), written as `YYYY-MM`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SummaryPeriod {
+    first_day: NaiveDate,
+}
+
+impl SummaryPeriod {
+    /// Parse a `YYYY-MM` period
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid period '{}', expected YYYY-MM", value);
+        let (year, month) = value.trim().split_once('-').ok_or_else(invalid)?;
+        if year.len() != 4 || month.len() != 2 {
+            return Err(invalid());
+        }
+        let year: i32 = year.parse().map_err(|_| invalid())?;
+        let month: u32 = month.parse().map_err(|_| invalid())?;
+        NaiveDate::from_ymd_opt(year, month, 1)
+            .map(|first_day| Self { first_day })
+            .ok_or_else(invalid)
+    }
+
+    /// The month containing `date`
+    pub fn containing(date: NaiveDate) -> Self {
+        Self {
+            first_day: date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// The month before the one containing `date`
+    pub fn previous(date: NaiveDate) -> Self {
+        let first_day = Self::containing(date).first_day;
+        Self::containing(first_day.pred_opt().unwrap_or(first_day))
+    }
+
+    pub fn first_day(&self) -> NaiveDate {
+        self.first_day
+    }
+
+    pub fn last_day(&self) -> NaiveDate {
+        let next_month = self
+            .first_day
+            .checked_add_months(chrono::Months::new(1))
+            .unwrap_or(self.first_day);
+        next_month.pred_opt().unwrap_or(self.first_day)
+    }
+
+    /// Human-readable month, e.g. "June 2024"
+    pub fn display_name(&self) -> String {
+        self.first_day.format("%B %Y").to_string()
+    }
+}
+
+impl fmt::Display for SummaryPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.first_day.format("%Y-%m"))
+    }
+}
+
+/// Query parameters for GET /v1/analytics/summary
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct UsageSummaryQuery {
+    /// Month to summarize as YYYY-MM; defaults to the current month
+    #[param(example = "2024-06")]
+    pub period: Option<String>,
+    /// IANA timezone the month is counted in; defaults to the user's `timezone` preference
+    #[param(example = "Asia/Tokyo")]
+    pub tz: Option<String>,
+}
+
+impl UsageSummaryQuery {
+    /// Validate the requested period; future months are rejected
+    pub fn into_period(self, today: NaiveDate) -> Result<SummaryPeriod, String> {
+        let period = match self.period {
+            Some(value) => SummaryPeriod::parse(&value)?,
+            None => SummaryPeriod::containing(today),
+        };
+        if period > SummaryPeriod::containing(today) {
+            return Err(format!("Period {} is in the future", period));
+        }
+        Ok(period)
+    }
+}
+
+/// A user's totals for one month
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "period": "2024-06",
+    "from": "2024-06-01",
+    "to": "2024-06-30",
+    "links_created": 12,
+    "total_clicks": 4821,
+    "unique_visitors": 3907,
+    "top_links": [
+        {"link_id": "123e4567-e89b-12d3-a456-426614174000", "short_code": "abc123", "clicks": 2210}
+    ],
+    "top_referrer": "twitter.com",
+    "top_country": "US",
+    "timezone": "UTC"
+}))]
+pub struct UsageSummaryResponse {
+    /// Month summarized (YYYY-MM)
+    pub period: String,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// IANA timezone the month's days are counted in; clicks older than the raw retention
+    /// window come from rollups, which are always counted in UTC
+    pub timezone: String,
+    /// Links created during the month
+    pub links_created: i64,
+    /// Clicks on any of the user's links during the month
+    pub total_clicks: u64,
+    pub unique_visitors: u64,
+    /// Most-clicked links, at most 5
+    pub top_links: Vec<TopLinkSummary>,
+    /// Referrer host sending the most clicks
+    pub top_referrer: Option<String>,
+    /// ISO country code with the most clicks
+    pub top_country: Option<String>,
+}
+
+impl UsageSummaryResponse {
+    /// Summary with no recorded clicks
+    pub fn empty(period: SummaryPeriod, links_created: i64) -> Self {
+        Self {
+            period: period.to_string(),
+            from: period.first_day(),
+            to: period.last_day(),
+            timezone: crate::utils::timezone::DEFAULT_TIMEZONE.to_string(),
+            links_created,
+            total_clicks: 0,
+            unique_visitors: 0,
+            top_links: Vec::new(),
+            top_referrer: None,
+            top_country: None,
+        }
+    }
+}
+
+/// One of a user's most-clicked links in a period
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TopLinkSummary {
+    pub link_id: Uuid,
+    pub short_code: String,
+    pub clicks: u64,
+}
+
+/// Days covered by the activity figures in instance stats
+pub const INSTANCE_STATS_DAYS: i64 = 30;
+
+/// Instance-wide usage for operators
+/// Figures whose backing store isn't enabled (or couldn't be read) are null
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "total_users": 148,
+    "active_users_30d": 61,
+    "total_links": 5230,
+    "links_created_per_day": [
+        {"date": "2024-06-14", "count": 0},
+        {"date": "2024-06-15", "count": 27}
+    ],
+    "total_redirects": 918244,
+    "deleted_links_pending_purge": 312
+}))]
+pub struct InstanceStatsResponse {
+    pub total_users: Option<i64>,
+    /// Users with a successful login in the last 30 days, from the audit log;
+    /// null when AUDIT_LOG_RETENTION_DAYS keeps less than 30 days
+    pub active_users_30d: Option<i64>,
+    /// Links that have not been deleted
+    pub total_links: Option<i64>,
+    /// Links created on each of the last 30 days (UTC), oldest first
+    pub links_created_per_day: Option<Vec<DailyCount>>,
+    /// Redirects served, from ClickHouse when enabled, otherwise the links' click counters
+    pub total_redirects: Option<i64>,
+    /// Soft-deleted links still stored
+    pub deleted_links_pending_purge: Option<i64>,
+}
+
+/// Count for a single day
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DailyCount {
+    pub date: NaiveDate,
+    pub count: i64,
+}
+
+impl DailyCount {
+    /// One entry per day from `from` to `to` inclusive, 0 for days missing from `counts`
+    pub fn fill_days(from: NaiveDate, to: NaiveDate, counts: &[(NaiveDate, i64)]) -> Vec<Self> {
+        from.iter_days()
+            .take_while(|date| *date <= to)
+            .map(|date| Self {
+                date,
+                count: counts
+                    .iter()
+                    .find(|(day, _)| *day == date)
+                    .map_or(0, |(_, count)| *count),
+            })
+            .collect()
+    }
+}
+
+/// Days covered by security scan metrics when no range is given
+pub const SECURITY_METRICS_DEFAULT_DAYS: u64 = 7;
+
+/// Longest range security scan metrics cover
+pub const SECURITY_METRICS_MAX_DAYS: i64 = 180;
+
+/// Query parameters for GET /v1/admin/security/metrics
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct SecurityMetricsQuery {
+    /// First day (inclusive, UTC); defaults to 7 days ending `to`
+    pub from: Option<NaiveDate>,
+    /// Last day (inclusive, UTC); defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+impl SecurityMetricsQuery {
+    /// Resolve the requested (from, to) range
+    pub fn into_range(self, today: NaiveDate) -> Result<(NaiveDate, NaiveDate), String> {
+        let to = self.to.unwrap_or(today);
+        let from = match self.from {
+            Some(from) => from,
+            None => to
+                .checked_sub_days(chrono::Days::new(SECURITY_METRICS_DEFAULT_DAYS - 1))
+                .ok_or("Invalid 'to' date")?,
+        };
+
+        if from > to {
+            return Err("'from' must not be after 'to'".to_string());
+        }
+        if (to - from).num_days() >= SECURITY_METRICS_MAX_DAYS {
+            return Err(format!(
+                "Range may cover at most {} days",
+                SECURITY_METRICS_MAX_DAYS
+            ));
+        }
+        Ok((from, to))
+    }
+}
+
+/// Security scan outcomes over a date range, from sampled scan telemetry
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "from": "2024-06-09",
+    "to": "2024-06-15",
+    "sample_rate": 1.0,
+    "total_scans": 1520,
+    "blocked_scans": 38,
+    "trusted_scans": 12,
+    "block_rate": 0.025,
+    "p95_scan_duration_ms": 840,
+    "threat_types": [{"threat_type": "Phishing", "scans": 21}],
+    "top_blocked_domains": [{"domain": "login-verify.example.tk", "blocked_scans": 9}]
+}))]
+pub struct SecurityScanMetricsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Fraction of scans recorded (SECURITY_SCAN_TELEMETRY_SAMPLE_RATE); counts cover
+    /// recorded scans only
+    pub sample_rate: f64,
+    pub total_scans: u64,
+    pub blocked_scans: u64,
+    /// Scans accepted only because the domain is trusted
+    pub trusted_scans: u64,
+    /// blocked_scans / total_scans; 0 when nothing was scanned
+    pub block_rate: f64,
+    /// Null when nothing was scanned
+    pub p95_scan_duration_ms: Option<u64>,
+    /// Scans each threat type fired on, most frequent first
+    pub threat_types: Vec<ThreatTypeCount>,
+    /// Most-blocked domains, at most 10
+    pub top_blocked_domains: Vec<TopBlockedDomain>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct ThreatTypeCount {
+    pub threat_type: String,
+    pub scans: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct TopBlockedDomain {
+    pub domain: String,
+    pub blocked_scans: u64,
+}
+
+/// Longest window rate limit offenders can be reported over, in hours (7 days)
+pub const OFFENDERS_MAX_WINDOW_HOURS: u32 = 168;
+/// Offenders reported per endpoint when no limit is given
+pub const OFFENDERS_DEFAULT_LIMIT: usize = 10;
+/// Most offenders reported per endpoint
+pub const OFFENDERS_MAX_LIMIT: usize = 100;
+
+/// What a rate limit key identifies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OffenderKind {
+    Ip,
+    Email,
+    User,
+    Other,
+}
+
+impl OffenderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OffenderKind::Ip => "ip",
+            OffenderKind::Email => "email",
+            OffenderKind::User => "user",
+            OffenderKind::Other => "other",
+        }
+    }
+}
+
+/// Identify the client behind a rate limit key such as `login:ip:203.0.113.7`,
+/// `login:email:a@example.com` or `user:{uuid}:link_creation`. Keys that match none of these
+/// are returned whole as `Other`.
+pub fn classify_offender_key(key: &str) -> (OffenderKind, String) {
+    let parts: Vec<&str> = key.split(':').collect();
+
+    // IPv6 addresses contain ':' themselves, so try every suffix
+    for start in 0..parts.len() {
+        let rest = parts[start..].join(":");
+        if let Ok(ip) = rest.parse::<IpAddr>() {
+            return (OffenderKind::Ip, ip.to_canonical().to_string());
+        }
+    }
+
+    if let Some(start) = parts.iter().position(|part| part.contains('@')) {
+        return (OffenderKind::Email, parts[start..].join(":"));
+    }
+
+    if let Some(user_id) = parts
+        .iter()
+        .position(|part| *part == "user")
+        .and_then(|i| parts.get(i + 1))
+        .filter(|id| Uuid::parse_str(id).is_ok())
+    {
+        return (OffenderKind::User, user_id.to_string());
+    }
+
+    (OffenderKind::Other, key.to_string())
+}
+
+/// Response format of GET /v1/admin/rate-limits/offenders
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OffendersFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+/// Query parameters for GET /v1/admin/rate-limits/offenders
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct RateLimitOffendersQuery {
+    /// Hours (`6h`) or days (`7d`) up to now, at most 7 days; defaults to `24h`
+    pub window: Option<String>,
+    /// Offenders per endpoint (1-100); defaults to 10
+    pub limit: Option<usize>,
+    /// `json` (default) or `csv`
+    #[serde(default)]
+    pub format: OffendersFormat,
+}
+
+impl RateLimitOffendersQuery {
+    /// Requested window in hours
+    pub fn window_hours(&self) -> Result<u32, String> {
+        let Some(window) = self.window.as_deref().map(str::trim) else {
+            return Ok(24);
+        };
+        let invalid = || format!("Invalid window '{}', expected e.g. 24h or 7d", window);
+
+        let (amount, hours_per_unit) = if let Some(hours) = window.strip_suffix('h') {
+            (hours, 1)
+        } else if let Some(days) = window.strip_suffix('d') {
+            (days, 24)
+        } else {
+            return Err(invalid());
+        };
+        let hours = amount
+            .parse::<u32>()
+            .map_err(|_| invalid())?
+            .checked_mul(hours_per_unit)
+            .ok_or_else(invalid)?;
+
+        if hours == 0 || hours > OFFENDERS_MAX_WINDOW_HOURS {
+            return Err(format!(
+                "Window must be between 1h and {}d",
+                OFFENDERS_MAX_WINDOW_HOURS / 24
+            ));
+        }
+        Ok(hours)
+    }
+
+    /// Requested offenders per endpoint
+    pub fn limit(&self) -> Result<usize, String> {
+        match self.limit {
+            None => Ok(OFFENDERS_DEFAULT_LIMIT),
+            Some(limit) if (1..=OFFENDERS_MAX_LIMIT).contains(&limit) => Ok(limit),
+            Some(_) => Err(format!(
+                "limit must be between 1 and {}",
+                OFFENDERS_MAX_LIMIT
+            )),
+        }
+    }
+}
+
+/// Most rate-limited clients per endpoint over a window
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "window_start": "2024-06-14T11:00:00Z",
+    "window_end": "2024-06-15T10:42:17Z",
+    "endpoints": [{
+        "endpoint": "login",
+        "offenders": [{
+            "key": "login:ip:203.0.113.7",
+            "kind": "ip",
+            "identifier": "203.0.113.7",
+            "blocked_requests": 412
+        }]
+    }]
+}))]
+pub struct RateLimitOffendersResponse {
+    /// Start of the oldest hour covered; counts are kept per hour
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    /// Endpoints with at least one blocked request, by name
+    pub endpoints: Vec<EndpointOffenders>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EndpointOffenders {
+    /// Endpoint the limit applies to (`/api/links`) or the key prefix of an ad-hoc
+    /// limit (`login`)
+    pub endpoint: String,
+    /// Most-blocked keys first
+    pub offenders: Vec<RateLimitOffenderEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct RateLimitOffenderEntry {
+    /// Rate limit key as stored in Redis
+    pub key: String,
+    pub kind: OffenderKind,
+    /// The IP, email or user id within the key; the whole key for `other`
+    pub identifier: String,
+    pub blocked_requests: u64,
+}
+
+impl RateLimitOffenderEntry {
+    pub fn new(key: String, blocked_requests: u64) -> Self {
+        let (kind, identifier) = classify_offender_key(&key);
+        Self {
+            key,
+            kind,
+            identifier,
+            blocked_requests,
+        }
+    }
+}
+
+impl RateLimitOffendersResponse {
+    /// One row per offender: `endpoint,kind,identifier,key,blocked_requests`
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("endpoint,kind,identifier,key,blocked_requests\n");
+        for endpoint in &self.endpoints {
+            for offender in &endpoint.offenders {
+                csv.push_str(&format!(
+                    "{},{},{},{},{}\n",
+                    csv_field(&endpoint.endpoint),
+                    offender.kind.as_str(),
+                    csv_field(&offender.identifier),
+                    csv_field(&offender.key),
+                    offender.blocked_requests
+                ));
+            }
+        }
+        csv
+    }
+}
+
+/// Quote a CSV field when needed, and defuse values a spreadsheet would run as a formula
+/// (keys embed client-supplied emails)
+fn csv_field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", value)
+    } else {
+        value.to_string()
+    };
+
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_summary_period_bounds() {
+        let period = SummaryPeriod::parse("2024-02").unwrap();
+        assert_eq!(period.first_day(), day("2024-02-01"));
+        assert_eq!(period.last_day(), day("2024-02-29"));
+        assert_eq!(period.to_string(), "2024-02");
+        assert_eq!(period.display_name(), "February 2024");
+
+        assert_eq!(
+            SummaryPeriod::previous(day("2024-01-01")),
+            SummaryPeriod::parse("2023-12").unwrap()
+        );
+
+        for invalid in ["2024-13", "2024-6", "24-06", "2024/06", ""] {
+            assert!(SummaryPeriod::parse(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_summary_query_rejects_future_periods() {
+        let today = day("2024-06-15");
+        let query = |period: Option<&str>| UsageSummaryQuery {
+            period: period.map(str::to_string),
+            tz: None,
+        };
+
+        assert_eq!(
+            query(None).into_period(today).unwrap().to_string(),
+            "2024-06"
+        );
+        assert!(query(Some("2024-05")).into_period(today).is_ok());
+        assert!(query(Some("2024-07")).into_period(today).is_err());
+    }
+
+    #[test]
+    fn test_daily_counts_fill_missing_days() {
+        let counts = [(day("2024-06-14"), 3), (day("2024-06-16"), 5)];
+        let filled = DailyCount::fill_days(day("2024-06-13"), day("2024-06-16"), &counts);
+
+        let values: Vec<(NaiveDate, i64)> = filled.iter().map(|d| (d.date, d.count)).collect();
+        assert_eq!(
+            values,
+            vec![
+                (day("2024-06-13"), 0),
+                (day("2024-06-14"), 3),
+                (day("2024-06-15"), 0),
+                (day("2024-06-16"), 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_security_metrics_range() {
+        let today = day("2024-06-15");
+        let query = |from: Option<&str>, to: Option<&str>| SecurityMetricsQuery {
+            from: from.map(day),
+            to: to.map(day),
+        };
+
+        assert_eq!(
+            query(None, None).into_range(today),
+            Ok((day("2024-06-09"), day("2024-06-15")))
+        );
+        assert_eq!(
+            query(None, Some("2024-05-31")).into_range(today),
+            Ok((day("2024-05-25"), day("2024-05-31")))
+        );
+        assert_eq!(
+            query(Some("2024-06-15"), None).into_range(today),
+            Ok((today, today))
+        );
+        assert!(query(Some("2024-06-16"), None).into_range(today).is_err());
+        assert!(query(Some("2023-01-01"), None).into_range(today).is_err());
+    }
+
+    #[test]
+    fn test_classify_offender_key() {
+        let user_id = Uuid::new_v4();
+        let cases = [
+            ("login:ip:203.0.113.7", OffenderKind::Ip, "203.0.113.7"),
+            ("register:2001:db8::1", OffenderKind::Ip, "2001:db8::1"),
+            (
+                "forgot_password:::ffff:10.0.0.1",
+                OffenderKind::Ip,
+                "10.0.0.1",
+            ),
+            (
+                "login:email:a@example.com",
+                OffenderKind::Email,
+                "a@example.com",
+            ),
+            (
+                &format!("user:{}:link_creation", user_id),
+                OffenderKind::User,
+                &user_id.to_string(),
+            ),
+            (
+                "user:not-a-uuid:x",
+                OffenderKind::Other,
+                "user:not-a-uuid:x",
+            ),
+        ];
+
+        for (key, kind, identifier) in cases {
+            assert_eq!(
+                classify_offender_key(key),
+                (kind, identifier.to_string()),
+                "{}",
+                key
+            );
+        }
+    }
+
+    #[test]
+    fn test_offenders_window() {
+        let query = |window: Option<&str>| RateLimitOffendersQuery {
+            window: window.map(str::to_string),
+            ..Default::default()
+        };
+
+        assert_eq!(query(None).window_hours(), Ok(24));
+        assert_eq!(query(Some("6h")).window_hours(), Ok(6));
+        assert_eq!(query(Some("7d")).window_hours(), Ok(168));
+        for invalid in ["0h", "8d", "24", "1w", "-1h", ""] {
+            assert!(query(Some(invalid)).window_hours().is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_offenders_csv_escapes_fields() {
+        let response = RateLimitOffendersResponse {
+            window_start: Utc::now(),
+            window_end: Utc::now(),
+            endpoints: vec![EndpointOffenders {
+                endpoint: "login".to_string(),
+                offenders: vec![
+                    RateLimitOffenderEntry::new("login:ip:203.0.113.7".to_string(), 12),
+                    RateLimitOffenderEntry::new(
+                        "login:email:=cmd,\"x\"@example.com".to_string(),
+                        3,
+                    ),
+                ],
+            }],
+        };
+
+        assert_eq!(
+            response.to_csv(),
+            "endpoint,kind,identifier,key,blocked_requests\n\
+             login,ip,203.0.113.7,login:ip:203.0.113.7,12\n\
+             login,email,\"'=cmd,\"\"x\"\"@example.com\",\"login:email:=cmd,\"\"x\"\"@example.com\",3\n"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+// Per-user API usage
+// Daily request counts per endpoint, served by GET /v1/usage. Counts for the current day
+// live in Redis until the rollup task moves them to `api_usage_daily`.
+
+use chrono::{Duration, NaiveDate};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::schema::api_usage_daily;
+
+/// Days covered by GET /v1/usage, today included
+pub const USAGE_WINDOW_DAYS: i64 = 30;
+
+/// Endpoints listed in a usage response
+pub const USAGE_TOP_ENDPOINTS: usize = 10;
+
+/// Requests one user made to one endpoint on one UTC day
+#[derive(Debug, Clone, PartialEq, Queryable, Selectable, Insertable)]
+#[diesel(table_name = api_usage_daily)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiUsageDay {
+    pub user_id: Uuid,
+    pub day: NaiveDate,
+    /// Method and route template, e.g. `GET /v1/links/{id}`
+    pub endpoint: String,
+    pub requests: i64,
+    /// Requests answered with 429 Too Many Requests
+    pub rate_limited: i64,
+}
+
+/// Totals for one day
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub requests: i64,
+    pub rate_limited: i64,
+}
+
+/// Totals for one endpoint over the whole window
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct EndpointUsage {
+    /// Method and route template, e.g. `GET /v1/links/{id}`
+    pub endpoint: String,
+    pub requests: i64,
+    pub rate_limited: i64,
+}
+
+/// Response of GET /v1/usage
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ApiUsageResponse {
+    /// First day of the window (UTC)
+    pub from: NaiveDate,
+    /// Last day of the window, today (UTC)
+    pub to: NaiveDate,
+    pub total_requests: i64,
+    /// Requests answered with 429 Too Many Requests
+    pub rate_limited_requests: i64,
+    /// One entry per day, oldest first; days without requests are included with zeros
+    pub daily: Vec<DailyUsage>,
+    /// Most-requested endpoints, busiest first
+    pub top_endpoints: Vec<EndpointUsage>,
+}
+
+impl ApiUsageResponse {
+    /// Aggregate per-day, per-endpoint counts into the window ending `today`.
+    /// Counts outside the window are ignored; rows for the same day and endpoint are summed.
+    pub fn from_counts(today: NaiveDate, counts: impl IntoIterator<Item = ApiUsageDay>) -> Self {
+        let from = today - Duration::days(USAGE_WINDOW_DAYS - 1);
+        let mut daily: Vec<DailyUsage> = (0..USAGE_WINDOW_DAYS)
+            .map(|offset| DailyUsage {
+                date: from + Duration::days(offset),
+                requests: 0,
+                rate_limited: 0,
+            })
+            .collect();
+        let mut endpoints: HashMap<String, EndpointUsage> = HashMap::new();
+
+        for row in counts {
+            if row.day < from || row.day > today {
+                continue;
+            }
+            let day = &mut daily[(row.day - from).num_days() as usize];
+            day.requests += row.requests;
+            day.rate_limited += row.rate_limited;
+
+            let endpoint = endpoints
+                .entry(row.endpoint.clone())
+                .or_insert_with(|| EndpointUsage {
+                    endpoint: row.endpoint,
+                    requests: 0,
+                    rate_limited: 0,
+                });
+            endpoint.requests += row.requests;
+            endpoint.rate_limited += row.rate_limited;
+        }
+
+        let mut top_endpoints: Vec<EndpointUsage> = endpoints.into_values().collect();
+        top_endpoints.sort_by(|a, b| {
+            b.requests
+                .cmp(&a.requests)
+                .then_with(|| a.endpoint.cmp(&b.endpoint))
+        });
+        top_endpoints.truncate(USAGE_TOP_ENDPOINTS);
+
+        Self {
+            from,
+            to: today,
+            total_requests: daily.iter().map(|d| d.requests).sum(),
+            rate_limited_requests: daily.iter().map(|d| d.rate_limited).sum(),
+            daily,
+            top_endpoints,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(day: NaiveDate, endpoint: &str, requests: i64, rate_limited: i64) -> ApiUsageDay {
+        ApiUsageDay {
+            user_id: Uuid::nil(),
+            day,
+            endpoint: endpoint.to_string(),
+            requests,
+            rate_limited,
+        }
+    }
+
+    #[test]
+    fn test_usage_response_from_counts() {
+        let today = NaiveDate::from_ymd_opt(2025, 9, 29).unwrap();
+        let yesterday = today - Duration::days(1);
+        let response = ApiUsageResponse::from_counts(
+            today,
+            vec![
+                row(today, "GET /v1/links", 5, 0),
+                row(today, "POST /v1/links", 3, 1),
+                // Same day and endpoint as the first row, e.g. from Redis and Postgres
+                row(today, "GET /v1/links", 2, 0),
+                row(yesterday, "POST /v1/links", 6, 2),
+                // Outside the window
+                row(
+                    today - Duration::days(USAGE_WINDOW_DAYS),
+                    "GET /v1/links",
+                    100,
+                    0,
+                ),
+            ],
+        );
+
+        assert_eq!(response.from, today - Duration::days(29));
+        assert_eq!(response.to, today);
+        assert_eq!(response.daily.len(), 30);
+        assert_eq!(response.daily[0].requests, 0);
+        assert_eq!(response.daily[28].date, yesterday);
+        assert_eq!(response.daily[28].requests, 6);
+        assert_eq!(response.daily[29].requests, 10);
+        assert_eq!(response.daily[29].rate_limited, 1);
+        assert_eq!(response.total_requests, 16);
+        assert_eq!(response.rate_limited_requests, 3);
+        assert_eq!(
+            response.top_endpoints,
+            vec![
+                EndpointUsage {
+                    endpoint: "POST /v1/links".to_string(),
+                    requests: 9,
+                    rate_limited: 3,
+                },
+                EndpointUsage {
+                    endpoint: "GET /v1/links".to_string(),
+                    requests: 7,
+                    rate_limited: 0,
+                },
+            ]
+        );
+    }
+}
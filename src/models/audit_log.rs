@@ -0,0 +1,169 @@
+// Persisted audit trail entries
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::schema::audit_logs;
+use crate::utils::audit_logger::{AuditAction, AuditLog};
+
+/// Largest page size accepted by the audit log endpoints
+pub const MAX_AUDIT_LOG_PAGE_SIZE: i64 = 200;
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = audit_logs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLogRecord {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = audit_logs)]
+pub struct NewAuditLogRecord {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLog> for NewAuditLogRecord {
+    fn from(log: AuditLog) -> Self {
+        Self {
+            id: log.id,
+            actor_id: log.user_id,
+            action: log.action.as_str().to_string(),
+            resource_type: log.resource_type,
+            resource_id: log.resource_id,
+            details: log.details,
+            ip_address: log.ip_address,
+            user_agent: log.user_agent,
+            created_at: log.timestamp,
+        }
+    }
+}
+
+// =============================================================================
+// QUERY FILTERS
+// =============================================================================
+
+/// Filters for GET /v1/audit-logs and GET /v1/admin/audit-logs.
+/// `actor_id` is only honoured on the admin endpoint.
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct AuditLogQuery {
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+    /// Action name, e.g. `LinkCreated`
+    pub action: Option<String>,
+    /// Only honoured on the admin endpoint
+    pub actor_id: Option<Uuid>,
+    pub resource_type: Option<String>,
+    /// Inclusive lower bound (RFC 3339)
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound (RFC 3339)
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Validated filter passed to the service layer
+#[derive(Debug, Clone, Default)]
+pub struct AuditLogFilter {
+    pub actor_id: Option<Uuid>,
+    pub action: Option<AuditAction>,
+    pub resource_type: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+impl AuditLogFilter {
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+}
+
+impl AuditLogQuery {
+    /// Validate the query. `actor_override` pins the actor (used for the per-user endpoint).
+    pub fn into_filter(self, actor_override: Option<Uuid>) -> Result<AuditLogFilter, String> {
+        let action = match self.action.as_deref() {
+            Some(name) => {
+                Some(AuditAction::parse(name).ok_or_else(|| format!("Unknown action: {}", name))?)
+            },
+            None => None,
+        };
+
+        if let (Some(from), Some(to)) = (self.from, self.to) {
+            if from >= to {
+                return Err("'from' must be earlier than 'to'".to_string());
+            }
+        }
+
+        Ok(AuditLogFilter {
+            actor_id: actor_override.or(self.actor_id),
+            action,
+            resource_type: self.resource_type,
+            from: self.from,
+            to: self.to,
+            page: self.page.unwrap_or(1).max(1),
+            per_page: self
+                .per_page
+                .unwrap_or(50)
+                .clamp(1, MAX_AUDIT_LOG_PAGE_SIZE),
+        })
+    }
+}
+
+// =============================================================================
+// RESPONSES
+// =============================================================================
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditLogResponse {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub details: Option<serde_json::Value>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<AuditLogRecord> for AuditLogResponse {
+    fn from(record: AuditLogRecord) -> Self {
+        Self {
+            id: record.id,
+            actor_id: record.actor_id,
+            action: record.action,
+            resource_type: record.resource_type,
+            resource_id: record.resource_id,
+            details: record.details,
+            ip_address: record.ip_address,
+            user_agent: record.user_agent,
+            created_at: record.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct AuditLogListResponse {
+    pub logs: Vec<AuditLogResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
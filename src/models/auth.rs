@@ -33,6 +33,10 @@ pub struct AccessTokenClaims {
 
     /// Expires at timestamp (Unix epoch seconds)
     pub exp: u64,
+
+    /// Admin user ID when an admin is acting as this user ("login as user")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>,
 }
 
 /// Refresh token claims structure (EXACT Linear requirements)
@@ -80,6 +84,7 @@ impl AccessTokenClaims {
             iss: issuer,
             iat: issued_at,
             exp: expires_at,
+            impersonator: None,
         }
     }
 
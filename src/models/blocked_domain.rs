@@ -0,0 +1,153 @@
+// Operator-managed domain blocklist entries
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::blocked_domains;
+
+/// Entry type: a full domain (blocks the domain and its subdomains) or a bare TLD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockedEntryType {
+    Domain,
+    Tld,
+}
+
+impl BlockedEntryType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockedEntryType::Domain => "domain",
+            BlockedEntryType::Tld => "tld",
+        }
+    }
+}
+
+impl From<&str> for BlockedEntryType {
+    fn from(s: &str) -> Self {
+        match s {
+            "tld" => BlockedEntryType::Tld,
+            _ => BlockedEntryType::Domain,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = blocked_domains)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct BlockedDomain {
+    pub id: Uuid,
+    pub pattern: String,
+    pub entry_type: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl BlockedDomain {
+    pub fn entry_type(&self) -> BlockedEntryType {
+        BlockedEntryType::from(self.entry_type.as_str())
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = blocked_domains)]
+pub struct NewBlockedDomain {
+    pub pattern: String,
+    pub entry_type: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+}
+
+// Request/Response models for API
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "pattern": "malicious.example",
+    "entry_type": "domain",
+    "reason": "Phishing campaign targeting customers"
+}))]
+pub struct CreateBlockedDomainRequest {
+    /// Domain (blocks subdomains too) or bare TLD; schemes and paths are stripped
+    #[validate(length(
+        min = 1,
+        max = 253,
+        message = "Pattern must be between 1 and 253 characters"
+    ))]
+    pub pattern: String,
+
+    pub entry_type: BlockedEntryType,
+
+    #[validate(length(
+        min = 1,
+        max = 1000,
+        message = "Reason must be between 1 and 1000 characters"
+    ))]
+    pub reason: String,
+}
+
+impl CreateBlockedDomainRequest {
+    /// Normalize the pattern: lowercase, strip scheme/path/leading dots
+    pub fn normalized_pattern(&self) -> Result<String, String> {
+        normalize_pattern(&self.pattern, self.entry_type)
+    }
+}
+
+/// Normalize a domain or TLD pattern; shared with trusted domain entries
+pub fn normalize_pattern(pattern: &str, entry_type: BlockedEntryType) -> Result<String, String> {
+    let mut pattern = pattern.trim().to_lowercase();
+
+    if let Some(idx) = pattern.find("://") {
+        pattern = pattern[idx + 3..].to_string();
+    }
+    if let Some(idx) = pattern.find('/') {
+        pattern.truncate(idx);
+    }
+    let pattern = pattern
+        .trim_start_matches("*.")
+        .trim_matches('.')
+        .to_string();
+
+    if pattern.is_empty() {
+        return Err("Pattern cannot be empty".to_string());
+    }
+    if !pattern
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+    {
+        return Err("Pattern may only contain letters, digits, hyphens and dots".to_string());
+    }
+    if entry_type == BlockedEntryType::Tld && pattern.contains('.') {
+        return Err("TLD entries must not contain dots".to_string());
+    }
+    if entry_type == BlockedEntryType::Domain && !pattern.contains('.') {
+        return Err("Domain entries must contain at least one dot".to_string());
+    }
+
+    Ok(pattern)
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct BlockedDomainResponse {
+    pub id: Uuid,
+    pub pattern: String,
+    pub entry_type: BlockedEntryType,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<BlockedDomain> for BlockedDomainResponse {
+    fn from(entry: BlockedDomain) -> Self {
+        Self {
+            entry_type: entry.entry_type(),
+            id: entry.id,
+            pattern: entry.pattern,
+            reason: entry.reason,
+            created_by: entry.created_by,
+            created_at: entry.created_at,
+        }
+    }
+}
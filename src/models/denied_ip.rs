@@ -0,0 +1,127 @@
+// Operator-managed client IP denylist entries
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::denied_ips;
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = denied_ips)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct DeniedIp {
+    pub id: Uuid,
+    pub ip: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = denied_ips)]
+pub struct NewDeniedIp {
+    pub ip: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+// Request/Response models for API
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "ip": "203.0.113.7",
+    "reason": "Credential stuffing against /v1/auth/login",
+    "expires_in_hours": 24
+}))]
+pub struct CreateDeniedIpRequest {
+    /// IPv4 or IPv6 address, e.g. the identifier of a rate limit offender
+    #[validate(length(min = 1, max = 45, message = "IP must be between 1 and 45 characters"))]
+    pub ip: String,
+
+    #[validate(length(
+        min = 1,
+        max = 1000,
+        message = "Reason must be between 1 and 1000 characters"
+    ))]
+    pub reason: String,
+
+    /// Lift the denial after this many hours; omit to deny until the entry is removed
+    #[validate(range(
+        min = 1,
+        max = 720,
+        message = "expires_in_hours must be between 1 and 720"
+    ))]
+    pub expires_in_hours: Option<u32>,
+}
+
+impl CreateDeniedIpRequest {
+    /// Parse the address into its canonical form (IPv4-mapped IPv6 becomes IPv4)
+    pub fn normalized_ip(&self) -> Result<IpAddr, String> {
+        let ip: IpAddr = self
+            .ip
+            .trim()
+            .parse()
+            .map_err(|_| format!("'{}' is not an IP address", self.ip.trim()))?;
+        Ok(ip.to_canonical())
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeniedIpResponse {
+    pub id: Uuid,
+    pub ip: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    /// Null when the address is denied until the entry is removed
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<DeniedIp> for DeniedIpResponse {
+    fn from(entry: DeniedIp) -> Self {
+        Self {
+            id: entry.id,
+            ip: entry.ip,
+            reason: entry.reason,
+            created_by: entry.created_by,
+            created_at: entry.created_at,
+            expires_at: entry.expires_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(ip: &str) -> CreateDeniedIpRequest {
+        CreateDeniedIpRequest {
+            ip: ip.to_string(),
+            reason: "abuse".to_string(),
+            expires_in_hours: None,
+        }
+    }
+
+    #[test]
+    fn test_normalized_ip() {
+        assert_eq!(
+            request(" 203.0.113.7 ").normalized_ip(),
+            Ok("203.0.113.7".parse().unwrap())
+        );
+        assert_eq!(
+            request("::ffff:203.0.113.7").normalized_ip(),
+            Ok("203.0.113.7".parse().unwrap())
+        );
+        assert_eq!(
+            request("2001:DB8::1").normalized_ip().unwrap().to_string(),
+            "2001:db8::1"
+        );
+        assert!(request("203.0.113.0/24").normalized_ip().is_err());
+        assert!(request("example.com").normalized_ip().is_err());
+    }
+}
@@ -2,7 +2,7 @@
 // DEV-124: Base62 short code support
 // DEV-105: Link creation with metadata
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use diesel::prelude::*;
 use lazy_static::lazy_static;
 use regex::Regex;
@@ -11,8 +11,153 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 use validator::Validate;
 
+use crate::models::blocked_domain::{normalize_pattern, BlockedEntryType};
 use crate::schema::links;
-use crate::services::link::LinkClickStats;
+use crate::services::{link::LinkClickStats, metadata_throttle::registrable_domain};
+use crate::utils::{
+    custom_alias_validator::CustomAliasValidator,
+    host_to_unicode,
+    security_scanner::{HomographDetector, SecurityScanResult},
+};
+
+diesel::define_sql_function! {
+    /// SQL `lower()`; alias lookups go through it so they hit the `lower(custom_alias)` and
+    /// `lower(short_code)` indexes
+    fn lower(x: diesel::sql_types::Nullable<diesel::sql_types::Text>) -> diesel::sql_types::Nullable<diesel::sql_types::Text>;
+}
+
+// =============================================================================
+// CLICK TRACKING MODE
+// =============================================================================
+
+/// How much visitor data is recorded when a link is clicked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TrackingMode {
+    /// Record IP address, user agent and referrer with every click
+    #[default]
+    Full,
+    /// Count the click without storing IP address, user agent or referrer
+    Anonymous,
+    /// Full tracking unless the visitor sends `DNT: 1` or `Sec-GPC: 1`, then anonymous
+    RespectDnt,
+}
+
+impl TrackingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackingMode::Full => "full",
+            TrackingMode::Anonymous => "anonymous",
+            TrackingMode::RespectDnt => "respect_dnt",
+        }
+    }
+
+    /// What visitors are told is recorded when they click, shown on the link preview
+    pub fn visitor_notice(&self) -> &'static str {
+        match self {
+            TrackingMode::Full => {
+                "Clicks are recorded with the visitor's IP address, approximate location, \
+                 browser and device, and referring page."
+            },
+            TrackingMode::Anonymous => {
+                "The link owner has turned off visitor tracking. Clicks are counted, but no IP \
+                 address, location, browser, device or referring page is stored."
+            },
+            TrackingMode::RespectDnt => {
+                "Clicks are recorded with the visitor's IP address, approximate location, \
+                 browser and device, and referring page, unless the browser sends Do Not Track \
+                 or Global Privacy Control. Then the click is only counted."
+            },
+        }
+    }
+
+    /// Whether a click must be recorded without identifying fields
+    /// `opted_out` is true when the visitor sent a Do Not Track or Global Privacy Control signal
+    pub fn is_anonymous(&self, opted_out: bool) -> bool {
+        match self {
+            TrackingMode::Full => false,
+            TrackingMode::Anonymous => true,
+            TrackingMode::RespectDnt => opted_out,
+        }
+    }
+}
+
+impl From<&str> for TrackingMode {
+    fn from(s: &str) -> Self {
+        match s {
+            "anonymous" => TrackingMode::Anonymous,
+            "respect_dnt" => TrackingMode::RespectDnt,
+            _ => TrackingMode::Full,
+        }
+    }
+}
+
+fn default_tracking_mode() -> String {
+    TrackingMode::Full.as_str().to_string()
+}
+
+// =============================================================================
+// EXPIRY POLICY
+// =============================================================================
+
+/// How a link's `expires_at` is set
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExpiryPolicy {
+    /// `expires_at` is the date the owner chose, or never
+    #[default]
+    Fixed,
+    /// `expires_at` is `sliding_window_days` after the last click, moved forward by the
+    /// click sync
+    Sliding,
+}
+
+impl ExpiryPolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExpiryPolicy::Fixed => "fixed",
+            ExpiryPolicy::Sliding => "sliding",
+        }
+    }
+
+    /// Check the window and an explicitly submitted `expires_at` against the policy.
+    /// Sliding links need a window and compute their own expiry; fixed links take no window.
+    pub fn check(
+        &self,
+        sliding_window_days: Option<i32>,
+        expires_at_given: bool,
+    ) -> Result<(), LinkRequestError> {
+        match self {
+            ExpiryPolicy::Fixed if sliding_window_days.is_some() => {
+                Err(LinkRequestError::WindowWithoutSliding)
+            },
+            ExpiryPolicy::Fixed => Ok(()),
+            ExpiryPolicy::Sliding if sliding_window_days.is_none() => {
+                Err(LinkRequestError::WindowRequired)
+            },
+            ExpiryPolicy::Sliding if expires_at_given => Err(LinkRequestError::ExpiryWithSliding),
+            ExpiryPolicy::Sliding => Ok(()),
+        }
+    }
+}
+
+impl From<&str> for ExpiryPolicy {
+    fn from(s: &str) -> Self {
+        match s {
+            "sliding" => ExpiryPolicy::Sliding,
+            _ => ExpiryPolicy::Fixed,
+        }
+    }
+}
+
+fn default_expiry_policy() -> String {
+    ExpiryPolicy::Fixed.as_str().to_string()
+}
+
+/// Expiry of a sliding link last clicked (or created, or reconfigured) at `from`
+pub fn sliding_expiry(from: DateTime<Utc>, window_days: i32) -> DateTime<Utc> {
+    from + chrono::Duration::days(i64::from(window_days))
+}
 
 // =============================================================================
 // DATABASE MODELS
@@ -67,8 +212,68 @@ pub struct Link {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    // Retroactive security scanning / quarantine
+    #[serde(default)]
+    pub last_scanned_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub threat_score: Option<i16>,
+    #[serde(default)]
+    pub quarantined: bool,
+    #[serde(default)]
+    pub quarantined_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub quarantine_reason: Option<String>,
+    #[serde(default)]
+    pub appeal_status: Option<String>,
+    #[serde(default)]
+    pub appeal_message: Option<String>,
+    #[serde(default)]
+    pub appealed_at: Option<DateTime<Utc>>,
+    // Latest security scan result (threat_score / last_scanned_at above)
+    #[serde(default)]
+    pub risk_level: Option<String>,
+    #[serde(default)]
+    pub threats_detected: Option<serde_json::Value>,
+    #[serde(default)]
+    pub scan_warnings: Option<serde_json::Value>,
+    /// `full`, `anonymous` or `respect_dnt` (see `TrackingMode`)
+    #[serde(default = "default_tracking_mode")]
+    pub tracking_mode: String,
+    /// Owning organization; `None` for personal links
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+    /// Clicks synced from the Redis counters; lags redirects by up to one sync interval
+    #[serde(default)]
+    pub click_count: i64,
+    /// Free-text notes from the owner, never shown to visitors
+    #[serde(default)]
+    pub notes: Option<String>,
+    /// Set when the system deactivated the link, e.g. `expired`; `None` for owner changes
+    #[serde(default)]
+    pub deactivation_reason: Option<String>,
+    /// Append whatever follows the short code in the request path to `original_url`
+    #[serde(default)]
+    pub path_passthrough: bool,
+    /// Registrable domains whose visitors may follow the link; empty allows every referrer
+    #[serde(default)]
+    pub allowed_referrers: Vec<Option<String>>,
+    /// Registrable domains whose visitors are turned away
+    #[serde(default)]
+    pub blocked_referrers: Vec<Option<String>>,
+    /// Also turn away clicks that send no `Referer`
+    #[serde(default)]
+    pub referrer_strict: bool,
+    /// `fixed` or `sliding` (see `ExpiryPolicy`)
+    #[serde(default = "default_expiry_policy")]
+    pub expiry_policy: String,
+    /// Days of inactivity after which a sliding link expires; `None` for fixed links
+    #[serde(default)]
+    pub sliding_window_days: Option<i32>,
 }
 
+/// `deactivation_reason` of links the expiry sweep switched off
+pub const DEACTIVATION_REASON_EXPIRED: &str = "expired";
+
 /// New link for insertion
 #[derive(Debug, Clone, Insertable)]
 #[diesel(table_name = links)]
@@ -97,6 +302,46 @@ pub struct NewLink {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    pub last_scanned_at: Option<DateTime<Utc>>,
+    pub threat_score: Option<i16>,
+    pub risk_level: Option<String>,
+    pub threats_detected: Option<serde_json::Value>,
+    pub scan_warnings: Option<serde_json::Value>,
+    pub tracking_mode: String,
+    pub organization_id: Option<Uuid>,
+    pub path_passthrough: bool,
+    pub allowed_referrers: Vec<Option<String>>,
+    pub blocked_referrers: Vec<Option<String>>,
+    pub referrer_strict: bool,
+    pub expiry_policy: String,
+    pub sliding_window_days: Option<i32>,
+}
+
+/// Security scan columns written after every scan
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = links)]
+pub struct LinkScanUpdate {
+    pub last_scanned_at: Option<DateTime<Utc>>,
+    pub threat_score: Option<i16>,
+    pub risk_level: Option<String>,
+    pub threats_detected: Option<serde_json::Value>,
+    pub scan_warnings: Option<serde_json::Value>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&SecurityScanResult> for LinkScanUpdate {
+    fn from(result: &SecurityScanResult) -> Self {
+        Self {
+            last_scanned_at: Some(result.scan_timestamp),
+            threat_score: Some(i16::from(result.threat_score)),
+            risk_level: serde_json::to_value(&result.risk_level)
+                .ok()
+                .and_then(|v| v.as_str().map(String::from)),
+            threats_detected: serde_json::to_value(&result.threats_detected).ok(),
+            scan_warnings: serde_json::to_value(&result.warnings).ok(),
+            updated_at: Utc::now(),
+        }
+    }
 }
 
 /// Update link fields
@@ -120,6 +365,15 @@ pub struct UpdateLink {
     pub metadata_extracted_at: Option<Option<chrono::NaiveDateTime>>,
     pub og_image: Option<Option<String>>,
     pub favicon_url: Option<Option<String>>,
+    pub tracking_mode: Option<String>,
+    pub notes: Option<Option<String>>,
+    pub deactivation_reason: Option<Option<String>>,
+    pub path_passthrough: Option<bool>,
+    pub allowed_referrers: Option<Vec<Option<String>>>,
+    pub blocked_referrers: Option<Vec<Option<String>>>,
+    pub referrer_strict: Option<bool>,
+    pub expiry_policy: Option<String>,
+    pub sliding_window_days: Option<Option<i32>>,
 }
 
 // =============================================================================
@@ -138,11 +392,17 @@ pub struct UpdateLink {
     "expires_at": "2024-12-31T23:59:59Z",
     "tags": ["work", "important"],
     "is_password_protected": false,
-    "password": null
+    "password": null,
+    "tracking_mode": "respect_dnt",
+    "path_passthrough": false,
+    "allowed_referrers": ["example.com"],
+    "blocked_referrers": [],
+    "referrer_strict": false,
+    "expiry_policy": "fixed"
 }))]
 pub struct CreateLinkRequest {
+    /// At most `MAX_URL_LENGTH` bytes (2048 by default)
     #[validate(url(message = "Invalid URL format"))]
-    #[validate(length(max = 8192, message = "URL must be less than 8192 characters"))]
     pub url: String,
 
     #[validate(length(min = 3, max = 50, message = "Custom alias must be 3-50 characters"))]
@@ -168,6 +428,7 @@ pub struct CreateLinkRequest {
 
     pub expires_at: Option<DateTime<Utc>>,
 
+    /// Up to 20 tags of at most 50 characters; stored trimmed, lowercase and deduplicated
     #[serde(default)]
     pub tags: Vec<String>,
 
@@ -175,53 +436,374 @@ pub struct CreateLinkRequest {
     pub is_password_protected: bool,
 
     pub password: Option<String>,
+
+    /// Click tracking mode; defaults to the owner's `default_tracking_mode`
+    #[serde(default)]
+    pub tracking_mode: Option<TrackingMode>,
+
+    /// Create the link on behalf of an organization the caller belongs to
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+
+    /// Redirect `/{short_code}/{rest}` to the destination with `rest` appended to its path
+    #[serde(default)]
+    pub path_passthrough: bool,
+
+    /// Only redirect visitors referred from these registrable domains (or their subdomains);
+    /// up to 50
+    #[serde(default)]
+    pub allowed_referrers: Vec<String>,
+
+    /// Never redirect visitors referred from these registrable domains; up to 50
+    #[serde(default)]
+    pub blocked_referrers: Vec<String>,
+
+    /// Turn away clicks without a `Referer`; they are allowed by default
+    #[serde(default)]
+    pub referrer_strict: bool,
+
+    /// `sliding` links expire after `sliding_window_days` without clicks instead of at
+    /// `expires_at`, which must then be omitted
+    #[serde(default)]
+    pub expiry_policy: ExpiryPolicy,
+
+    /// Required for, and only accepted with, `sliding` expiry
+    #[validate(range(
+        min = 1,
+        max = 365,
+        message = "sliding_window_days must be between 1 and 365"
+    ))]
+    pub sliding_window_days: Option<i32>,
 }
 
 lazy_static! {
     static ref CUSTOM_ALIAS_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_-]*$").unwrap();
 }
 
+/// Distinct tags allowed on one link
+pub const MAX_TAGS_PER_LINK: usize = 20;
+
+/// Characters allowed in one tag, after trimming
+pub const MAX_TAG_LENGTH: usize = 50;
+
+/// Tag rejected on create or update; `index` is its position in the submitted array
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TagError {
+    #[error("At most {} distinct tags are allowed per link", MAX_TAGS_PER_LINK)]
+    TooMany,
+
+    #[error("Tag must be at most {} characters", MAX_TAG_LENGTH)]
+    TooLong { index: usize },
+
+    #[error("Tag must not contain control characters")]
+    ControlCharacter { index: usize },
+}
+
+impl TagError {
+    /// Request field the error refers to, e.g. `tags[3]`
+    pub fn field(&self) -> String {
+        match self {
+            TagError::TooMany => "tags".to_string(),
+            TagError::TooLong { index } | TagError::ControlCharacter { index } => {
+                format!("tags[{}]", index)
+            },
+        }
+    }
+
+    /// Machine-readable code, matching `validator`'s for the same kind of check
+    pub fn code(&self) -> &'static str {
+        match self {
+            TagError::TooMany | TagError::TooLong { .. } => "length",
+            TagError::ControlCharacter { .. } => "control_character",
+        }
+    }
+}
+
+/// Check submitted tags before they are normalized, so errors point at the client's positions.
+/// The count limit applies to distinct tags, so duplicates differing only in case are fine.
+pub fn validate_tags(tags: &[String]) -> Result<(), TagError> {
+    for (index, tag) in tags.iter().enumerate() {
+        if let Some(error) = tag_error(index, tag.trim()) {
+            return Err(error);
+        }
+    }
+
+    if normalize_tags(tags).len() > MAX_TAGS_PER_LINK {
+        return Err(TagError::TooMany);
+    }
+
+    Ok(())
+}
+
+/// Trim and lowercase, dropping empty tags and repeats; the first occurrence keeps its place
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    tags.iter()
+        .map(|tag| tag.trim().to_lowercase())
+        .filter(|tag| !tag.is_empty() && seen.insert(tag.clone()))
+        .collect()
+}
+
+/// Normalized tags that pass the per-tag rules, capped at [`MAX_TAGS_PER_LINK`]; for tags
+/// that never went through the API's validation, such as imported archives
+pub fn retain_valid_tags(tags: &[String]) -> Vec<String> {
+    normalize_tags(tags)
+        .into_iter()
+        .filter(|tag| tag_error(0, tag).is_none())
+        .take(MAX_TAGS_PER_LINK)
+        .collect()
+}
+
+fn tag_error(index: usize, tag: &str) -> Option<TagError> {
+    if tag.chars().any(char::is_control) {
+        Some(TagError::ControlCharacter { index })
+    } else if tag.chars().count() > MAX_TAG_LENGTH {
+        Some(TagError::TooLong { index })
+    } else {
+        None
+    }
+}
+
+/// Domains allowed in each of a link's referrer lists
+pub const MAX_REFERRER_DOMAINS: usize = 50;
+
+/// Referrer domain rejected on create or update; `index` is its position in the submitted
+/// array and `list` the request field (`allowed_referrers` or `blocked_referrers`)
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReferrerDomainError {
+    #[error("At most {} domains are allowed per list", MAX_REFERRER_DOMAINS)]
+    TooMany { list: &'static str },
+
+    #[error("{reason}")]
+    Invalid {
+        list: &'static str,
+        index: usize,
+        reason: String,
+    },
+
+    #[error("Referrers are matched by registrable domain; use '{registrable}'")]
+    NotRegistrable {
+        list: &'static str,
+        index: usize,
+        registrable: String,
+    },
+}
+
+impl ReferrerDomainError {
+    /// Request field the error refers to, e.g. `allowed_referrers[2]`
+    pub fn field(&self) -> String {
+        match self {
+            ReferrerDomainError::TooMany { list } => list.to_string(),
+            ReferrerDomainError::Invalid { list, index, .. }
+            | ReferrerDomainError::NotRegistrable { list, index, .. } => {
+                format!("{}[{}]", list, index)
+            },
+        }
+    }
+
+    /// Machine-readable code, matching `validator`'s for the same kind of check
+    pub fn code(&self) -> &'static str {
+        match self {
+            ReferrerDomainError::TooMany { .. } => "length",
+            ReferrerDomainError::Invalid { .. } => "invalid_domain",
+            ReferrerDomainError::NotRegistrable { .. } => "not_registrable",
+        }
+    }
+}
+
+/// Normalize a referrer list the way blocklist domains are (lowercase, no scheme, path or
+/// `*.`), keeping the first occurrence of each domain. Every entry must be a registrable
+/// domain, since that is what referrers are compared by.
+pub fn normalize_referrer_domains(
+    list: &'static str,
+    domains: &[String],
+) -> Result<Vec<String>, ReferrerDomainError> {
+    let mut normalized: Vec<String> = Vec::new();
+    for (index, domain) in domains.iter().enumerate() {
+        let domain = normalize_pattern(domain, BlockedEntryType::Domain).map_err(|reason| {
+            ReferrerDomainError::Invalid {
+                list,
+                index,
+                reason,
+            }
+        })?;
+        let registrable = registrable_domain(&domain);
+        if registrable != domain {
+            return Err(ReferrerDomainError::NotRegistrable {
+                list,
+                index,
+                registrable,
+            });
+        }
+        if !normalized.contains(&domain) {
+            normalized.push(domain);
+        }
+    }
+
+    if normalized.len() > MAX_REFERRER_DOMAINS {
+        return Err(ReferrerDomainError::TooMany { list });
+    }
+    Ok(normalized)
+}
+
+/// Column value for a normalized referrer list
+pub fn referrer_column(domains: Vec<String>) -> Vec<Option<String>> {
+    domains.into_iter().map(Some).collect()
+}
+
+/// Request rejected by [`CreateLinkRequest::validate_custom`] or [`check_url_length`]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum LinkRequestError {
+    #[error("URL must be at most {max} bytes (got {current})")]
+    UrlTooLong { current: usize, max: usize },
+
+    #[error("Password is required when password protection is enabled")]
+    PasswordRequired,
+
+    #[error("Expiration date must be in the future")]
+    ExpiryInPast,
+
+    #[error("Sliding expiry requires sliding_window_days")]
+    WindowRequired,
+
+    #[error("sliding_window_days only applies to sliding expiry")]
+    WindowWithoutSliding,
+
+    #[error("Sliding links expire sliding_window_days after their last click; omit expires_at")]
+    ExpiryWithSliding,
+}
+
+impl LinkRequestError {
+    /// Request field the error refers to
+    pub fn field(&self) -> &'static str {
+        match self {
+            LinkRequestError::UrlTooLong { .. } => "url",
+            LinkRequestError::PasswordRequired => "password",
+            LinkRequestError::ExpiryInPast | LinkRequestError::ExpiryWithSliding => "expires_at",
+            LinkRequestError::WindowRequired | LinkRequestError::WindowWithoutSliding => {
+                "sliding_window_days"
+            },
+        }
+    }
+
+    /// Machine-readable code, matching `validator`'s for the same kind of check
+    pub fn code(&self) -> &'static str {
+        match self {
+            LinkRequestError::UrlTooLong { .. } => "length",
+            LinkRequestError::PasswordRequired => "required",
+            LinkRequestError::ExpiryInPast => "in_past",
+            LinkRequestError::WindowRequired => "required",
+            LinkRequestError::WindowWithoutSliding | LinkRequestError::ExpiryWithSliding => {
+                "expiry_policy"
+            },
+        }
+    }
+}
+
+/// Destination URLs are limited in bytes, the unit the scanner and the database check
+pub fn check_url_length(url: &str, max_url_length: usize) -> Result<(), LinkRequestError> {
+    if url.len() > max_url_length {
+        return Err(LinkRequestError::UrlTooLong {
+            current: url.len(),
+            max: max_url_length,
+        });
+    }
+    Ok(())
+}
+
 /// Custom validation for CreateLinkRequest
 impl CreateLinkRequest {
-    pub fn validate_custom(&self) -> Result<(), String> {
+    /// `max_url_length` is `AppConfig::max_url_length`; the URL is checked first, before any
+    /// parsing, so an oversized URL is always reported the same way
+    pub fn validate_custom(&self, max_url_length: usize) -> Result<(), LinkRequestError> {
+        check_url_length(&self.url, max_url_length)?;
+
         // Validate password if protection is enabled
         if self.is_password_protected && self.password.is_none() {
-            return Err("Password is required when password protection is enabled".to_string());
+            return Err(LinkRequestError::PasswordRequired);
         }
 
         // Validate expiration date is in the future
         if let Some(expires_at) = self.expires_at {
             if expires_at <= Utc::now() {
-                return Err("Expiration date must be in the future".to_string());
-            }
-        }
-
-        // Validate tags
-        if self.tags.len() > 10 {
-            return Err("Maximum 10 tags allowed".to_string());
-        }
-
-        for tag in &self.tags {
-            if tag.len() > 30 {
-                return Err("Each tag must be less than 30 characters".to_string());
+                return Err(LinkRequestError::ExpiryInPast);
             }
         }
 
-        Ok(())
+        self.expiry_policy
+            .check(self.sliding_window_days, self.expires_at.is_some())
     }
 
     /// Trim and sanitize input fields
     pub fn sanitize(&mut self) {
         self.url = self.url.trim().to_string();
-        self.custom_alias = self.custom_alias.as_ref().map(|s| s.trim().to_string());
+        self.custom_alias = self
+            .custom_alias
+            .as_deref()
+            .map(CustomAliasValidator::normalize);
         self.title = self.title.as_ref().map(|s| s.trim().to_string());
         self.description = self.description.as_ref().map(|s| s.trim().to_string());
         self.og_image = self.og_image.as_ref().map(|s| s.trim().to_string());
         self.favicon_url = self.favicon_url.as_ref().map(|s| s.trim().to_string());
-        self.tags = self.tags.iter().map(|s| s.trim().to_string()).collect();
+        self.tags = normalize_tags(&self.tags);
+    }
+
+    /// Fields left empty that background metadata extraction would fill in
+    pub fn metadata_to_extract(&self) -> Vec<&'static str> {
+        [
+            ("title", self.title.is_none()),
+            ("description", self.description.is_none()),
+            ("og_image", self.og_image.is_none()),
+            ("favicon_url", self.favicon_url.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(field, missing)| missing.then_some(field))
+        .collect()
     }
 }
 
+/// Minimal create request for browser extensions
+/// POST /v1/links/quick
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({ "url": "https://example.com/very/long/url/that/needs/shortening" }))]
+pub struct QuickLinkRequest {
+    /// At most `MAX_URL_LENGTH` bytes (2048 by default)
+    #[validate(url(message = "Invalid URL format"))]
+    pub url: String,
+}
+
+impl From<QuickLinkRequest> for CreateLinkRequest {
+    /// Everything else takes the owner's defaults
+    fn from(request: QuickLinkRequest) -> Self {
+        Self {
+            url: request.url,
+            custom_alias: None,
+            title: None,
+            description: None,
+            og_image: None,
+            favicon_url: None,
+            expires_at: None,
+            tags: Vec::new(),
+            is_password_protected: false,
+            password: None,
+            tracking_mode: None,
+            organization_id: None,
+            path_passthrough: false,
+            allowed_referrers: Vec::new(),
+            blocked_referrers: Vec::new(),
+            referrer_strict: false,
+            expiry_policy: ExpiryPolicy::Fixed,
+            sliding_window_days: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({ "short_url": "https://qck.sh/abc123" }))]
+pub struct QuickLinkResponse {
+    pub short_url: String,
+}
+
 /// Request to update an existing link
 #[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 #[schema(example = json!({
@@ -234,11 +816,12 @@ impl CreateLinkRequest {
     "is_active": true,
     "tags": ["updated", "modified"],
     "is_password_protected": false,
-    "password": null
+    "password": null,
+    "notes": "Printed on the summer flyer, do not deactivate"
 }))]
 pub struct UpdateLinkRequest {
+    /// At most `MAX_URL_LENGTH` bytes (2048 by default)
     #[validate(url(message = "Invalid URL format"))]
-    #[validate(length(max = 8192, message = "URL must be less than 8192 characters"))]
     pub url: Option<String>,
 
     #[validate(length(max = 200, message = "Title must be less than 200 characters"))]
@@ -259,11 +842,69 @@ pub struct UpdateLinkRequest {
 
     pub is_active: Option<bool>,
 
+    /// Replaces all tags; same limits and normalization as on create
     pub tags: Option<Vec<String>>,
 
     pub is_password_protected: Option<bool>,
 
     pub password: Option<String>,
+
+    pub tracking_mode: Option<TrackingMode>,
+
+    /// Free-text notes; an empty string clears them
+    #[validate(length(max = 2000, message = "Notes must be less than 2000 characters"))]
+    pub notes: Option<String>,
+
+    pub path_passthrough: Option<bool>,
+
+    /// Replaces the allowed referrer domains; an empty array allows every referrer
+    pub allowed_referrers: Option<Vec<String>>,
+
+    /// Replaces the blocked referrer domains
+    pub blocked_referrers: Option<Vec<String>>,
+
+    pub referrer_strict: Option<bool>,
+
+    /// Switching to `sliding`, or changing the window of a sliding link, restarts the window
+    /// from now; switching to `fixed` keeps the current `expires_at`
+    pub expiry_policy: Option<ExpiryPolicy>,
+
+    #[validate(range(
+        min = 1,
+        max = 365,
+        message = "sliding_window_days must be between 1 and 365"
+    ))]
+    pub sliding_window_days: Option<i32>,
+}
+
+/// Request to move a link to another owner
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "organization_id": "123e4567-e89b-12d3-a456-426614174000"
+}))]
+pub struct TransferLinkRequest {
+    /// Destination organization; `null` moves the link to the caller's personal links
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+}
+
+/// Whether all of a user's links are paused
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "paused": true,
+    "paused_at": "2024-01-15T10:30:00Z"
+}))]
+pub struct LinksPauseStatus {
+    pub paused: bool,
+    /// When the links were paused; `null` while they redirect
+    pub paused_at: Option<DateTime<Utc>>,
+}
+
+/// Password form posted from the password-protected link page
+#[derive(Deserialize, ToSchema)]
+pub struct UnlockLinkForm {
+    #[schema(format = Password)]
+    pub password: String,
 }
 
 /// Link response for API
@@ -283,6 +924,21 @@ pub struct UpdateLinkRequest {
     "qr_code_url": "https://qck.sh/api/v1/qr/abc123",
     "tags": ["example", "test"],
     "is_password_protected": false,
+    "is_quarantined": false,
+    "display_host": "example.com",
+    "display_host_warning": false,
+    "tracking_mode": "full",
+    "path_passthrough": false,
+    "allowed_referrers": [],
+    "blocked_referrers": [],
+    "referrer_strict": false,
+    "expiry_policy": "fixed",
+    "security": {
+        "threat_score": 0,
+        "risk_level": "Safe",
+        "threats_detected": [],
+        "scanned_at": "2024-01-01T12:00:00Z"
+    },
     "metadata": {
         "title": "Example Site",
         "description": "A great example website",
@@ -302,11 +958,45 @@ pub struct LinkResponse {
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub is_active: bool,
+    /// Why the system deactivated the link (`expired`); omitted when the owner did
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deactivation_reason: Option<String>,
     pub tags: Vec<String>,
     pub is_password_protected: bool,
+    /// Set when a security re-scan quarantined the link; redirects are disabled
+    pub is_quarantined: bool,
+    /// Destination host decoded to Unicode for display
+    pub display_host: String,
+    /// Set when the host mixes scripts (e.g. Cyrillic look-alikes); frontends should warn
+    pub display_host_warning: bool,
+    pub tracking_mode: TrackingMode,
+    /// `/{short_code}/{rest}` redirects to the destination with `rest` appended
+    pub path_passthrough: bool,
+    /// Only visitors referred from these domains are redirected; empty allows every referrer
+    pub allowed_referrers: Vec<String>,
+    /// Visitors referred from these domains get a 403 page
+    pub blocked_referrers: Vec<String>,
+    /// Clicks without a `Referer` get a 403 page too
+    pub referrer_strict: bool,
+    /// With `sliding`, `expires_at` moves to `sliding_window_days` after each click, applied
+    /// when clicks are synced (every few minutes)
+    pub expiry_policy: ExpiryPolicy,
+    /// Omitted for fixed expiry
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sliding_window_days: Option<i32>,
+    /// Owning organization; omitted for personal links
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_id: Option<Uuid>,
+    /// Free-text notes from the owner
+    pub notes: Option<String>,
+    /// Latest stored security scan
+    pub security: LinkSecuritySummary,
     pub metadata: LinkMetadata,
     // Stats from ClickHouse
+    /// Clicks excluding repeats from the same visitor within the dedup window
     pub total_clicks: u64,
+    /// Every click, repeats included
+    pub raw_clicks: u64,
     pub unique_visitors: u64,
     pub bot_clicks: u64,
     pub last_accessed_at: Option<DateTime<Utc>>,
@@ -416,74 +1106,1079 @@ pub struct ExtractedMetadata {
     pub og_image: Option<String>,
 }
 
-// =============================================================================
-// QUERY FILTERS
-// =============================================================================
-
-/// Pagination parameters
-#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+/// Owner appeal against a quarantine decision
+#[derive(Debug, Clone, Deserialize, Validate, ToSchema)]
 #[schema(example = json!({
-    "page": 1,
-    "per_page": 20
+    "message": "This is our company's download page, it was compromised last week and has been cleaned up."
 }))]
-pub struct LinkPagination {
-    #[serde(default = "default_page")]
-    pub page: i64,
+pub struct AppealLinkRequest {
+    #[validate(length(min = 1, max = 2000, message = "Message must be between 1 and 2000 characters"))]
+    pub message: String,
+}
 
-    #[serde(default = "default_per_page")]
-    pub per_page: i64,
+/// Admin decision on a pending appeal
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct ResolveAppealRequest {
+    /// `true` lifts the quarantine, `false` keeps it in place
+    pub approve: bool,
 }
 
-fn default_page() -> i64 {
-    1
+/// Stored security scan summary included in every owner-facing link response
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct LinkSecuritySummary {
+    pub threat_score: Option<i16>,
+    pub risk_level: Option<String>,
+    pub threats_detected: Vec<String>,
+    pub scanned_at: Option<DateTime<Utc>>,
 }
-fn default_per_page() -> i64 {
-    20
+
+/// Full stored security scan for a link
+/// GET /v1/links/{id}/security
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "link_id": "123e4567-e89b-12d3-a456-426614174000",
+    "url": "https://example.com/very/long/url",
+    "threat_score": 20,
+    "risk_level": "Safe",
+    "threats_detected": ["SuspiciousTld"],
+    "warnings": ["Suspicious TLD detected in domain: example.tk"],
+    "scanned_at": "2024-01-01T12:00:00Z",
+    "is_quarantined": false,
+    "quarantine_reason": null,
+    "rescanned": false
+}))]
+pub struct LinkSecurityResponse {
+    pub link_id: Uuid,
+    pub url: String,
+    pub threat_score: Option<i16>,
+    pub risk_level: Option<String>,
+    pub threats_detected: Vec<String>,
+    pub warnings: Vec<String>,
+    pub scanned_at: Option<DateTime<Utc>>,
+    pub is_quarantined: bool,
+    pub quarantine_reason: Option<String>,
+    /// Whether this response comes from a scan run for this request
+    pub rescanned: bool,
 }
 
-impl Default for LinkPagination {
-    fn default() -> Self {
+impl LinkSecurityResponse {
+    pub fn from_link(link: &Link, rescanned: bool) -> Self {
         Self {
-            page: default_page(),
-            per_page: default_per_page(),
+            link_id: link.id,
+            url: link.original_url.clone(),
+            threat_score: link.threat_score,
+            risk_level: link.risk_level.clone(),
+            threats_detected: json_string_list(link.threats_detected.as_ref()),
+            warnings: json_string_list(link.scan_warnings.as_ref()),
+            scanned_at: link.last_scanned_at,
+            is_quarantined: link.quarantined,
+            quarantine_reason: link.quarantine_reason.clone(),
+            rescanned,
         }
     }
 }
 
-impl LinkPagination {
-    pub fn offset(&self) -> i64 {
-        (self.page - 1) * self.per_page
-    }
+/// Query parameters for GET /v1/links/{id}/security
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct LinkSecurityQuery {
+    /// Run a fresh scan before responding (rate limited)
+    #[serde(default)]
+    pub rescan: bool,
+}
 
-    pub fn limit(&self) -> i64 {
-        self.per_page.min(100) // Max 100 per page
-    }
+/// Query parameters for DELETE /v1/links/{id}
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct DeleteLinkQuery {
+    /// Erase the link, its click data and cache entries instead of soft deleting it;
+    /// requires `X-Confirm-Permanent-Delete: yes`
+    #[serde(default)]
+    pub permanent: bool,
 }
 
-/// Link filter parameters
-#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
-#[schema(example = json!({
-    "search": "example",
-    "tags": ["work", "important"],
-    "is_active": true,
-    "has_password": false,
-    "domain": "example.com",
-    "created_after": "2024-01-01T00:00:00Z",
-    "created_before": "2024-12-31T23:59:59Z"
-}))]
-pub struct LinkFilter {
-    pub search: Option<String>,
-    pub tags: Option<Vec<String>>,
-    pub is_active: Option<bool>,
-    pub has_password: Option<bool>,
-    pub domain: Option<String>,
-    pub created_after: Option<DateTime<Utc>>,
-    pub created_before: Option<DateTime<Utc>>,
+/// Query parameters for GET /v1/links/check-alias/{alias}
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct CheckAliasQuery {
+    /// Hold an available alias for the caller for 60 seconds, so a following
+    /// POST /v1/links with it cannot lose to another user
+    #[serde(default)]
+    pub reserve: bool,
 }
 
-/// Link list response
-#[derive(Debug, Clone, Serialize, ToSchema)]
-#[schema(example = json!({
+/// Image format for GET /v1/links/{id}/og-image
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OgImageFormat {
+    /// 1200x630 PNG, accepted by every social network
+    #[default]
+    Png,
+    /// The same card as SVG
+    Svg,
+}
+
+impl OgImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OgImageFormat::Png => "png",
+            OgImageFormat::Svg => "svg",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            OgImageFormat::Png => "image/png",
+            OgImageFormat::Svg => "image/svg+xml",
+        }
+    }
+}
+
+/// Query parameters for GET /v1/links/{id}/og-image
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct OgImageQuery {
+    /// `png` (default) or `svg`
+    #[serde(default)]
+    pub format: OgImageFormat,
+}
+
+/// Stored JSONB arrays of strings (threat types, warnings) back to a Vec
+fn json_string_list(value: Option<&serde_json::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|item| item.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Quarantined link as seen by instance admins
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct QuarantinedLinkResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub short_code: String,
+    pub original_url: String,
+    pub threat_score: Option<i16>,
+    pub quarantined_at: Option<DateTime<Utc>>,
+    pub quarantine_reason: Option<String>,
+    pub appeal_status: Option<String>,
+    pub appeal_message: Option<String>,
+    pub appealed_at: Option<DateTime<Utc>>,
+}
+
+impl From<Link> for QuarantinedLinkResponse {
+    fn from(link: Link) -> Self {
+        Self {
+            id: link.id,
+            user_id: link.user_id,
+            short_code: link.short_code,
+            original_url: link.original_url,
+            threat_score: link.threat_score,
+            quarantined_at: link.quarantined_at,
+            quarantine_reason: link.quarantine_reason,
+            appeal_status: link.appeal_status,
+            appeal_message: link.appeal_message,
+            appealed_at: link.appealed_at,
+        }
+    }
+}
+
+/// Click statistics for a single link
+/// GET /v1/links/{id}/stats
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "short_code": "abc123",
+    "original_url": "https://example.com/very/long/url",
+    "total_clicks": 42,
+    "raw_clicks": 47,
+    "unique_visitors": 30,
+    "bot_clicks": 2,
+    "human_clicks": 40,
+    "created_at": "2024-01-01T12:00:00Z",
+    "last_accessed_at": "2024-01-15T14:30:00Z",
+    "is_active": true,
+    "days_active": 15,
+    "average_clicks_per_day": 2.8,
+    "conversion_rate": 0.71,
+    "entry_points": { "short_code": 30, "alias": 10, "unknown": 2 }
+}))]
+pub struct LinkStatsResponse {
+    pub short_code: String,
+    pub original_url: String,
+    /// Clicks excluding repeats from the same visitor within the dedup window
+    pub total_clicks: u64,
+    /// Every click, repeats included
+    pub raw_clicks: u64,
+    pub unique_visitors: u64,
+    pub bot_clicks: u64,
+    pub human_clicks: u64,
+    pub created_at: DateTime<Utc>,
+    pub last_accessed_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub days_active: i64,
+    pub average_clicks_per_day: f64,
+    /// Unique visitors per click
+    pub conversion_rate: f64,
+    /// Breakdown for the requested `from`/`to` range; totals above cover the range when set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub range: Option<LinkStatsRange>,
+    /// Clicks and visitors against the preceding period of equal length; set for ranged
+    /// requests unless `compare=false`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comparison: Option<LinkStatsComparison>,
+    /// Clicks through the short code versus the custom alias, counted from raw events over
+    /// the range (all-time: the raw retention window); absent for ranges served from rollups
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entry_points: Option<ClickEntryPoints>,
+}
+
+/// Optional date range for GET /v1/links/{id}/stats
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct LinkStatsQuery {
+    /// `7d` or `30d` (ending today), or `custom` for the `from`/`to` range.
+    /// Giving `from` or `to` without a period implies `custom`
+    #[param(example = "7d")]
+    pub period: Option<String>,
+    /// First day (inclusive); defaults to the link's creation date when only `to` is set
+    pub from: Option<NaiveDate>,
+    /// Last day (inclusive); defaults to today
+    pub to: Option<NaiveDate>,
+    /// Compare a ranged request with the preceding period of equal length (default true)
+    pub compare: Option<bool>,
+    /// IANA timezone days are counted in; defaults to the user's `timezone` preference
+    #[param(example = "Asia/Tokyo")]
+    pub tz: Option<String>,
+}
+
+impl LinkStatsQuery {
+    /// Resolve the requested range; `None` asks for all-time totals
+    pub fn into_window(
+        self,
+        created: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<Option<LinkStatsWindow>, String> {
+        let custom = self.from.is_some() || self.to.is_some();
+        let days = match self.period.as_deref() {
+            None if !custom => return Ok(None),
+            None | Some("custom") => None,
+            Some("7d") => Some(7),
+            Some("30d") => Some(30),
+            Some(other) => {
+                return Err(format!(
+                    "Unknown period `{}`; expected 7d, 30d or custom",
+                    other
+                ))
+            },
+        };
+
+        let (from, to) = match days {
+            Some(_) if custom => {
+                return Err("`from` and `to` are only allowed with period=custom".to_string())
+            },
+            Some(days) => (today - chrono::Days::new(days - 1), today),
+            None => (
+                self.from.unwrap_or(created),
+                self.to.unwrap_or(today).min(today),
+            ),
+        };
+        if from > to {
+            return Err("`from` must not be after `to`".to_string());
+        }
+
+        Ok(Some(LinkStatsWindow {
+            from,
+            to,
+            compare: self.compare.unwrap_or(true),
+        }))
+    }
+}
+
+/// Optional date range for GET /v1/links/{id}/stats/campaigns
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct CampaignStatsQuery {
+    /// `7d` or `30d` (ending today), or `custom` for the `from`/`to` range
+    #[param(example = "30d")]
+    pub period: Option<String>,
+    /// First day (inclusive, UTC); defaults to the link's creation date
+    pub from: Option<NaiveDate>,
+    /// Last day (inclusive, UTC); defaults to today
+    pub to: Option<NaiveDate>,
+}
+
+impl CampaignStatsQuery {
+    /// Resolve the requested range; without one, everything since the link was created
+    pub fn into_range(
+        self,
+        created: NaiveDate,
+        today: NaiveDate,
+    ) -> Result<(NaiveDate, NaiveDate), String> {
+        let window = LinkStatsQuery {
+            period: self.period,
+            from: self.from,
+            to: self.to,
+            compare: Some(false),
+            tz: None,
+        }
+        .into_window(created, today)?;
+
+        Ok(window.map_or((created.min(today), today), |window| {
+            (window.from, window.to)
+        }))
+    }
+}
+
+/// A resolved stats range (both days inclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkStatsWindow {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Whether to also aggregate the previous period
+    pub compare: bool,
+}
+
+impl LinkStatsWindow {
+    pub fn days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+
+    /// The period of equal length ending the day before `from`
+    pub fn previous_period(&self) -> (NaiveDate, NaiveDate) {
+        let previous_to = self.from - chrono::Days::new(1);
+        let previous_from = previous_to - chrono::Days::new(self.days() as u64 - 1);
+        (previous_from, previous_to)
+    }
+}
+
+/// A ranged stats request against the preceding period of equal length
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[schema(example = json!({
+    "previous_from": "2024-05-25",
+    "previous_to": "2024-05-31",
+    "clicks": { "current": 120, "previous": 80, "change_percent": 50.0 },
+    "unique_visitors": { "current": 90, "previous": 0, "change_percent": null }
+}))]
+pub struct LinkStatsComparison {
+    pub previous_from: NaiveDate,
+    pub previous_to: NaiveDate,
+    pub clicks: PeriodChange,
+    pub unique_visitors: PeriodChange,
+}
+
+/// One metric in the current and previous period
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct PeriodChange {
+    pub current: u64,
+    pub previous: u64,
+    /// Change from the previous period in percent, to one decimal; null when the previous
+    /// period had none
+    pub change_percent: Option<f64>,
+}
+
+impl PeriodChange {
+    pub fn new(current: u64, previous: u64) -> Self {
+        let change_percent = (previous > 0).then(|| {
+            let change = (current as f64 - previous as f64) / previous as f64 * 100.0;
+            (change * 10.0).round() / 10.0
+        });
+        Self {
+            current,
+            previous,
+            change_percent,
+        }
+    }
+}
+
+/// Click statistics for a date range
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LinkStatsRange {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// `raw` for ranges inside the raw event retention window, `rollup` for older ranges
+    pub source: String,
+    /// IANA timezone the days are counted in; always `UTC` for rollups
+    pub timezone: String,
+    pub daily: Vec<DailyClickCount>,
+    pub top_countries: Vec<ClickBreakdown>,
+    /// Referring hosts (full referrer URLs are not kept in rollups)
+    pub top_referrers: Vec<ClickBreakdown>,
+}
+
+/// Clicks on a single day
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct DailyClickCount {
+    pub date: NaiveDate,
+    pub clicks: u64,
+    pub unique_visitors: u64,
+}
+
+/// Clicks attributed to one country or referrer
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClickBreakdown {
+    pub value: String,
+    pub clicks: u64,
+}
+
+/// Clicks split by the code they came in through
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, ToSchema)]
+pub struct ClickEntryPoints {
+    /// Clicks through the link's short code
+    pub short_code: u64,
+    /// Clicks through the link's custom alias
+    pub alias: u64,
+    /// Clicks recorded before the entry point was tracked
+    pub unknown: u64,
+}
+
+/// Clicks that arrived from one inbound campaign (UTM parameters of the referrer URL)
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct CampaignClicks {
+    /// `utm_campaign` of the referrer; empty when only source or medium were set
+    pub campaign: String,
+    pub source: String,
+    pub medium: String,
+    pub clicks: u64,
+}
+
+/// Inbound campaign breakdown for GET /v1/links/{id}/stats/campaigns
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "from": "2024-06-01",
+    "to": "2024-06-30",
+    "campaigns": [
+        { "campaign": "spring_sale", "source": "newsletter", "medium": "email", "clicks": 120 },
+        { "campaign": "", "source": "twitter", "medium": "social", "clicks": 45 }
+    ]
+}))]
+pub struct LinkCampaignStatsResponse {
+    /// First day covered; later than requested when the range reaches past raw event retention
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Most clicks first; clicks from referrers without UTM parameters are not listed
+    pub campaigns: Vec<CampaignClicks>,
+}
+
+/// Clicks and conversion pixel hits on a single day
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+pub struct DailyConversions {
+    pub date: NaiveDate,
+    pub clicks: u64,
+    pub conversions: u64,
+}
+
+/// Conversions for GET /v1/links/{id}/stats/conversions
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "from": "2024-06-01",
+    "to": "2024-06-30",
+    "clicks": 480,
+    "conversions": 36,
+    "unique_converters": 30,
+    "conversion_rate": 7.5,
+    "daily": [
+        { "date": "2024-06-01", "clicks": 20, "conversions": 2 },
+        { "date": "2024-06-02", "clicks": 14, "conversions": 0 }
+    ]
+}))]
+pub struct LinkConversionStatsResponse {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    /// Clicks over the range, repeat clicks excluded
+    pub clicks: u64,
+    /// Conversion pixel hits; a visitor converting twice counts twice
+    pub conversions: u64,
+    /// Distinct visitors with at least one conversion
+    pub unique_converters: u64,
+    /// Conversions per 100 clicks; `null` without clicks
+    pub conversion_rate: Option<f64>,
+    /// Every day of the range with clicks or conversions, oldest first
+    pub daily: Vec<DailyConversions>,
+}
+
+/// Conversions per 100 clicks, to one decimal place
+pub fn conversion_rate(conversions: u64, clicks: u64) -> Option<f64> {
+    (clicks > 0).then(|| (conversions as f64 / clicks as f64 * 1000.0).round() / 10.0)
+}
+
+/// Per-item failure in a bulk create
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BulkCreateItemError {
+    /// Index of the failed link in the request array
+    pub index: usize,
+    pub error: String,
+}
+
+/// Bulk create result (returned with 207 Multi-Status)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "success": 1,
+    "failed": 1,
+    "links": [],
+    "errors": [{ "index": 1, "error": "Invalid URL format" }]
+}))]
+pub struct BulkCreateResponse {
+    /// Number of links created
+    pub success: usize,
+    /// Number of links that failed
+    pub failed: usize,
+    pub links: Vec<LinkResponse>,
+    pub errors: Vec<BulkCreateItemError>,
+}
+
+/// Request to activate or deactivate several personal links
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "ids": ["123e4567-e89b-12d3-a456-426614174000"],
+    "is_active": false
+}))]
+pub struct BulkStatusRequest {
+    /// Link IDs (max 100); repeated IDs are reported once
+    pub ids: Vec<Uuid>,
+    pub is_active: bool,
+}
+
+/// What a bulk status update did with one link ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkStatusOutcome {
+    Updated,
+    /// Missing, deleted, or belonging to someone the caller shares no organization with
+    NotFound,
+    /// An organization link the caller can see; bulk updates only change personal links
+    Forbidden,
+    AlreadyInState,
+}
+
+/// Outcome for one link ID of a bulk status update
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct BulkStatusItem {
+    pub id: Uuid,
+    pub outcome: BulkStatusOutcome,
+}
+
+/// Bulk status update result (returned with 207 Multi-Status)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "updated": 1,
+    "skipped": 1,
+    "results": [
+        { "id": "123e4567-e89b-12d3-a456-426614174000", "outcome": "updated" },
+        { "id": "123e4567-e89b-12d3-a456-426614174001", "outcome": "not_found" }
+    ]
+}))]
+pub struct BulkStatusResponse {
+    /// Number of links whose status changed
+    pub updated: usize,
+    /// Number of IDs left unchanged
+    pub skipped: usize,
+    /// One entry per distinct ID, in request order
+    pub results: Vec<BulkStatusItem>,
+}
+
+impl From<Vec<BulkStatusItem>> for BulkStatusResponse {
+    fn from(results: Vec<BulkStatusItem>) -> Self {
+        let updated = results
+            .iter()
+            .filter(|item| item.outcome == BulkStatusOutcome::Updated)
+            .count();
+        Self {
+            updated,
+            skipped: results.len() - updated,
+            results,
+        }
+    }
+}
+
+/// Custom alias availability check result
+/// GET /v1/links/check-alias/{alias}
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+#[schema(example = json!({
+    "available": false,
+    "alias": "spring-sale",
+    "message": "'spring-sale' is already taken",
+    "suggestions": ["spring-sale-7", "spring-sale-42", "spring-sale-2025"],
+    "suggestion_message": "Try one of these available alternatives:"
+}))]
+pub struct CheckAliasResponse {
+    pub available: bool,
+    /// The alias as it would be stored: trimmed and lowercase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Available alternatives, only when the alias is taken
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestions: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suggestion_message: Option<String>,
+    /// When the caller's reservation lapses, only with `reserve=true`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reserved_until: Option<DateTime<Utc>>,
+}
+
+/// Options of POST /v1/links/validate
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct ValidateLinkQuery {
+    /// Fetch the destination's title (at most 1 second) when the request has none
+    #[serde(default)]
+    pub fetch_title: bool,
+}
+
+/// What POST /v1/links would do with a request, worked out without creating anything
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "normalized_url": "https://example.com/spring-sale",
+    "allowed": true,
+    "blocked_reason": null,
+    "threat_score": 20,
+    "risk_level": "Safe",
+    "threats_detected": ["SuspiciousTld"],
+    "warnings": ["Suspicious TLD detected in domain: example.tk"],
+    "alias": {
+        "available": false,
+        "alias": "spring-sale",
+        "message": "'spring-sale' is already taken",
+        "suggestions": ["spring-sale-7", "spring-sale-42"],
+        "suggestion_message": "Try one of these available alternatives:"
+    },
+    "metadata_to_extract": ["description", "og_image", "favicon_url"],
+    "title": "Spring Sale - Example"
+}))]
+pub struct ValidateLinkResponse {
+    /// The destination as it would be stored
+    pub normalized_url: String,
+    /// Whether the security scan lets the URL be shortened
+    pub allowed: bool,
+    /// The error creating the link would fail with when the scan blocks it
+    pub blocked_reason: Option<String>,
+    pub threat_score: u8,
+    pub risk_level: String,
+    pub threats_detected: Vec<String>,
+    pub warnings: Vec<String>,
+    /// Verdict on `custom_alias`; absent when the request has none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<CheckAliasResponse>,
+    /// Fields the link would get from the destination page after creation
+    pub metadata_to_extract: Vec<String>,
+    /// Destination title, with `fetch_title=true`; `null` when it wasn't fetched in time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+/// Public preview of a short link's destination
+/// GET /{short_code}/preview
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "short_code": "abc123",
+    "original_url": "https://example.com/very/long/url",
+    "created_at": "2024-01-01T12:00:00Z",
+    "expires_at": null,
+    "is_active": true,
+    "is_quarantined": false,
+    "display_host": "example.com",
+    "display_host_warning": false,
+    "title": "Example Domain",
+    "description": "An example page",
+    "og_image": null,
+    "share_image": "https://qck.sh/v1/links/123e4567-e89b-12d3-a456-426614174000/og-image",
+    "total_clicks": 42,
+    "risk_level": "low",
+    "is_password_protected": false,
+    "is_expiring_soon": false,
+    "tracking_mode": "anonymous",
+    "tracking_notice": "The link owner has turned off visitor tracking. Clicks are counted, but no IP address, location, browser, device or referring page is stored."
+}))]
+pub struct LinkPreviewResponse {
+    pub short_code: String,
+    pub original_url: String,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub is_active: bool,
+    pub is_quarantined: bool,
+    /// Unicode form of the destination host
+    pub display_host: String,
+    /// Destination host mixes scripts (possible homograph)
+    pub display_host_warning: bool,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub og_image: Option<String>,
+    /// Image for social cards: the destination's own `og_image` when it is served over
+    /// HTTPS, otherwise the card generated at `/v1/links/{id}/og-image`
+    pub share_image: String,
+    /// Total clicks recorded in analytics (0 when analytics are unavailable)
+    pub total_clicks: u64,
+    /// Risk level from the latest security scan, if the link has been scanned
+    pub risk_level: Option<String>,
+    /// Visitors will be asked for a password before redirecting
+    pub is_password_protected: bool,
+    /// Link expires within the next 24 hours
+    pub is_expiring_soon: bool,
+    /// Visitor data recorded on click, as configured by the link owner
+    pub tracking_mode: TrackingMode,
+    /// What `tracking_mode` records and leaves out, for display to visitors
+    pub tracking_notice: String,
+}
+
+/// Window in which a preview flags a link as expiring soon
+const PREVIEW_EXPIRING_SOON_HOURS: i64 = 24;
+
+impl LinkPreviewResponse {
+    pub fn new(link: &Link, total_clicks: u64, base_url: &str) -> Self {
+        let (display_host, display_host_warning) = link.display_host();
+        let share_image = link
+            .og_image
+            .clone()
+            .filter(|url| url.starts_with("https://"))
+            .unwrap_or_else(|| format!("{}/v1/links/{}/og-image", base_url, link.id));
+        let now = Utc::now();
+        let is_expiring_soon = link.expires_at.is_some_and(|expires_at| {
+            expires_at > now
+                && expires_at - now <= chrono::Duration::hours(PREVIEW_EXPIRING_SOON_HOURS)
+        });
+
+        Self {
+            short_code: link.short_code.clone(),
+            original_url: link.original_url.clone(),
+            created_at: link.created_at,
+            expires_at: link.expires_at,
+            is_active: link.is_active,
+            is_quarantined: link.quarantined,
+            display_host,
+            display_host_warning,
+            title: link.title.clone(),
+            description: link.description.clone(),
+            og_image: link.og_image.clone(),
+            share_image,
+            total_clicks,
+            risk_level: link.risk_level.clone(),
+            is_password_protected: link.password_hash.is_some(),
+            is_expiring_soon,
+            tracking_mode: link.tracking_mode(),
+            tracking_notice: link.tracking_mode().visitor_notice().to_string(),
+        }
+    }
+}
+
+// =============================================================================
+// REDIRECT CACHE RECORD
+// =============================================================================
+
+/// HTTP status for link redirects
+pub const DEFAULT_REDIRECT_STATUS: u16 = 301;
+
+/// What the redirect path needs from a link, cached apart from the full `Link` JSON so a
+/// click decodes a handful of short keys instead of the whole row.
+/// New fields must be `#[serde(default)]` so records written by older releases still decode.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectRecord {
+    #[serde(rename = "id")]
+    pub link_id: Uuid,
+    #[serde(rename = "to")]
+    pub destination: String,
+    /// `ACTIVE`, `QUARANTINED` and `PASSWORD_PROTECTED` bits
+    #[serde(rename = "f")]
+    pub flags: u8,
+    /// Unix timestamp after which the link stops redirecting
+    #[serde(rename = "exp", default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// HTTP status of the redirect response
+    #[serde(rename = "rt", default = "default_redirect_status")]
+    pub redirect_type: u16,
+    #[serde(rename = "tm", default)]
+    pub tracking_mode: TrackingMode,
+    #[serde(rename = "pp", default)]
+    pub path_passthrough: bool,
+    /// The link's short code, to tell clicks through its custom alias apart
+    #[serde(rename = "sc", default)]
+    pub short_code: String,
+    /// Owner, for the account-wide pause; records written before it was added have none
+    /// and are reloaded from the database
+    #[serde(rename = "u", default, skip_serializing_if = "Option::is_none")]
+    pub owner_id: Option<Uuid>,
+    /// Referrer restrictions; see `referrer_allowed`
+    #[serde(rename = "ra", default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_referrers: Vec<String>,
+    #[serde(rename = "rb", default, skip_serializing_if = "Vec::is_empty")]
+    pub blocked_referrers: Vec<String>,
+    #[serde(rename = "rs", default)]
+    pub referrer_strict: bool,
+}
+
+fn default_redirect_status() -> u16 {
+    DEFAULT_REDIRECT_STATUS
+}
+
+/// First segments after a short code that are its own pages and never passed through
+pub const RESERVED_PASSTHROUGH_SEGMENTS: [&str; 2] = ["preview", "unlock"];
+
+/// `destination` with `sub_path` appended to its path, every segment percent-encoded.
+/// `None` when the sub-path starts with a reserved segment, contains `.` or `..`
+/// segments, or the destination has no path to append to.
+pub fn passthrough_destination(destination: &str, sub_path: &str) -> Option<String> {
+    let segments: Vec<&str> = sub_path.split('/').filter(|s| !s.is_empty()).collect();
+    if segments
+        .first()
+        .is_some_and(|first| RESERVED_PASSTHROUGH_SEGMENTS.contains(first))
+    {
+        return None;
+    }
+    if segments.iter().any(|s| *s == "." || *s == "..") {
+        return None;
+    }
+
+    let mut url = url::Url::parse(destination).ok()?;
+    {
+        let mut path = url.path_segments_mut().ok()?;
+        path.pop_if_empty().extend(&segments);
+        if !segments.is_empty() && sub_path.ends_with('/') {
+            path.push("");
+        }
+    }
+    Some(url.to_string())
+}
+
+impl RedirectRecord {
+    pub const ACTIVE: u8 = 1;
+    pub const QUARANTINED: u8 = 1 << 1;
+    pub const PASSWORD_PROTECTED: u8 = 1 << 2;
+
+    pub fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// `ACTIVE`, `QUARANTINED` and `PASSWORD_PROTECTED` bits for a link
+    pub fn flags_for(link: &Link) -> u8 {
+        let mut flags = 0;
+        if link.is_active {
+            flags |= Self::ACTIVE;
+        }
+        if link.quarantined {
+            flags |= Self::QUARANTINED;
+        }
+        if link.password_hash.is_some() {
+            flags |= Self::PASSWORD_PROTECTED;
+        }
+        flags
+    }
+
+    /// The stored expiry is authoritative, including for sliding links: the click sync moves
+    /// it forward and drops the cached record, so nothing is recomputed here
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires| expires < now.timestamp())
+    }
+
+    /// Whether `requested` reached the link through its custom alias. Short codes match
+    /// exactly, so any other spelling was the alias. Records cached without the short code
+    /// count as short code matches.
+    pub fn via_alias(&self, requested: &str) -> bool {
+        !self.short_code.is_empty() && requested != self.short_code
+    }
+
+    /// Whether a visitor sent with this `Referer` may follow the link. The referrer's
+    /// registrable domain must not be blocked and, when an allow list is set, must be on it.
+    /// A missing `Referer`, or one without a host, is only turned away in strict mode.
+    pub fn referrer_allowed(&self, referrer: Option<&str>) -> bool {
+        let host = referrer
+            .and_then(|referrer| url::Url::parse(referrer.trim()).ok())
+            .and_then(|url| url.host_str().map(registrable_domain));
+        let Some(domain) = host else {
+            return !self.referrer_strict;
+        };
+
+        !self.blocked_referrers.contains(&domain)
+            && (self.allowed_referrers.is_empty() || self.allowed_referrers.contains(&domain))
+    }
+}
+
+impl From<&Link> for RedirectRecord {
+    fn from(link: &Link) -> Self {
+        Self {
+            link_id: link.id,
+            destination: link.original_url.clone(),
+            flags: Self::flags_for(link),
+            expires_at: link.expires_at.map(|at| at.timestamp()),
+            redirect_type: DEFAULT_REDIRECT_STATUS,
+            tracking_mode: link.tracking_mode(),
+            path_passthrough: link.path_passthrough,
+            short_code: link.short_code.clone(),
+            owner_id: Some(link.user_id),
+            allowed_referrers: link.allowed_referrer_domains(),
+            blocked_referrers: link.blocked_referrer_domains(),
+            referrer_strict: link.referrer_strict,
+        }
+    }
+}
+
+/// Link id, owner and state flags for a short code, cached as the Redis hash `code:{code}`
+/// for callers that need neither the destination nor the rest of the row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeMeta {
+    pub link_id: Uuid,
+    pub user_id: Uuid,
+    /// Same bits as [`RedirectRecord::flags`]
+    pub flags: u8,
+}
+
+impl CodeMeta {
+    pub fn has(&self, flag: u8) -> bool {
+        self.flags & flag != 0
+    }
+
+    /// Hash fields as written to Redis
+    pub fn to_fields(&self) -> [(&'static str, String); 3] {
+        [
+            ("id", self.link_id.to_string()),
+            ("user_id", self.user_id.to_string()),
+            ("flags", self.flags.to_string()),
+        ]
+    }
+
+    /// `None` unless every field is present and parses; a missing or partial hash is a miss
+    pub fn from_fields(fields: &std::collections::HashMap<String, String>) -> Option<Self> {
+        Some(Self {
+            link_id: fields.get("id")?.parse().ok()?,
+            user_id: fields.get("user_id")?.parse().ok()?,
+            flags: fields.get("flags")?.parse().ok()?,
+        })
+    }
+}
+
+impl From<&Link> for CodeMeta {
+    fn from(link: &Link) -> Self {
+        Self {
+            link_id: link.id,
+            user_id: link.user_id,
+            flags: RedirectRecord::flags_for(link),
+        }
+    }
+}
+
+/// Who may read a link, cached briefly as the Redis hash `owner:{link_id}` for the stats
+/// routes. The short code locates the cached link itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinkOwner {
+    pub user_id: Uuid,
+    /// Organization links are readable by every member
+    pub organization_id: Option<Uuid>,
+    pub short_code: String,
+}
+
+impl LinkOwner {
+    /// Hash fields as written to Redis; a personal link has an empty `org_id`
+    pub fn to_fields(&self) -> [(&'static str, String); 3] {
+        [
+            ("user_id", self.user_id.to_string()),
+            (
+                "org_id",
+                self.organization_id
+                    .map(|id| id.to_string())
+                    .unwrap_or_default(),
+            ),
+            ("code", self.short_code.clone()),
+        ]
+    }
+
+    /// `None` unless every field is present and parses; a missing or partial hash is a miss
+    pub fn from_fields(fields: &std::collections::HashMap<String, String>) -> Option<Self> {
+        let organization_id = match fields.get("org_id")?.as_str() {
+            "" => None,
+            id => Some(id.parse().ok()?),
+        };
+        let short_code = fields.get("code")?;
+        if short_code.is_empty() {
+            return None;
+        }
+        Some(Self {
+            user_id: fields.get("user_id")?.parse().ok()?,
+            organization_id,
+            short_code: short_code.clone(),
+        })
+    }
+}
+
+impl From<&Link> for LinkOwner {
+    fn from(link: &Link) -> Self {
+        Self {
+            user_id: link.user_id,
+            organization_id: link.organization_id,
+            short_code: link.short_code.clone(),
+        }
+    }
+}
+
+// =============================================================================
+// QUERY FILTERS
+// =============================================================================
+
+/// Pagination parameters
+#[derive(Debug, Clone, Deserialize, ToSchema, IntoParams)]
+#[schema(example = json!({
+    "page": 1,
+    "per_page": 20
+}))]
+pub struct LinkPagination {
+    #[serde(default = "default_page")]
+    pub page: i64,
+
+    #[serde(default = "default_per_page")]
+    pub per_page: i64,
+}
+
+fn default_page() -> i64 {
+    1
+}
+fn default_per_page() -> i64 {
+    20
+}
+
+impl Default for LinkPagination {
+    fn default() -> Self {
+        Self {
+            page: default_page(),
+            per_page: default_per_page(),
+        }
+    }
+}
+
+impl LinkPagination {
+    pub fn offset(&self) -> i64 {
+        (self.page - 1) * self.per_page
+    }
+
+    pub fn limit(&self) -> i64 {
+        self.per_page.min(100) // Max 100 per page
+    }
+}
+
+/// Link filter parameters
+#[derive(Debug, Clone, Deserialize, Default, ToSchema, IntoParams)]
+#[schema(example = json!({
+    "search": "example",
+    "tags": ["work", "important"],
+    "is_active": true,
+    "has_password": false,
+    "domain": "example.com",
+    "created_after": "2024-01-01T00:00:00Z",
+    "created_before": "2024-12-31T23:59:59Z",
+    "expired": false
+}))]
+pub struct LinkFilter {
+    pub search: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+    pub has_password: Option<bool>,
+    pub domain: Option<String>,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    /// `true` for links past `expires_at` only; `false` for links without one or not yet expired
+    pub expired: Option<bool>,
+    /// Only links owned by this organization; personal links when unset
+    pub organization_id: Option<Uuid>,
+}
+
+/// Link list response
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
     "links": [
         {
             "id": "123e4567-e89b-12d3-a456-426614174000",
@@ -532,6 +2227,36 @@ impl Link {
         self.to_response_with_stats(base_url, default_stats)
     }
 
+    pub fn tracking_mode(&self) -> TrackingMode {
+        TrackingMode::from(self.tracking_mode.as_str())
+    }
+
+    pub fn expiry_policy(&self) -> ExpiryPolicy {
+        ExpiryPolicy::from(self.expiry_policy.as_str())
+    }
+
+    /// Domains in `allowed_referrers`
+    pub fn allowed_referrer_domains(&self) -> Vec<String> {
+        self.allowed_referrers.iter().flatten().cloned().collect()
+    }
+
+    /// Domains in `blocked_referrers`
+    pub fn blocked_referrer_domains(&self) -> Vec<String> {
+        self.blocked_referrers.iter().flatten().cloned().collect()
+    }
+
+    /// Switched off by the expiry sweep rather than by its owner
+    pub fn deactivated_by_expiry(&self) -> bool {
+        self.deactivation_reason.as_deref() == Some(DEACTIVATION_REASON_EXPIRED)
+    }
+
+    /// Unicode form of the destination host and whether it mixes scripts
+    pub fn display_host(&self) -> (String, bool) {
+        let host = extract_domain(&self.original_url).unwrap_or_default();
+        let warning = HomographDetector::has_mixed_scripts(&host);
+        (host_to_unicode(&host), warning)
+    }
+
     pub fn to_response_with_stats(&self, base_url: &str, stats: LinkClickStats) -> LinkResponse {
         // Build metadata from actual database fields
         let domain = extract_domain(&self.original_url).unwrap_or_default();
@@ -543,13 +2268,22 @@ impl Link {
             .map(|t| t.iter().filter_map(|tag| tag.clone()).collect())
             .unwrap_or_default();
 
+        let (display_host, display_host_warning) = self.display_host();
+
+        let security = LinkSecuritySummary {
+            threat_score: self.threat_score,
+            risk_level: self.risk_level.clone(),
+            threats_detected: json_string_list(self.threats_detected.as_ref()),
+            scanned_at: self.last_scanned_at,
+        };
+
         let metadata = LinkMetadata {
             title: self.title.clone(),
             description: self.description.clone(),
             favicon_url: self.favicon_url.clone(),
             og_image: self.og_image.clone(),
             domain,
-            is_safe: true, // Will be set by security scanner
+            is_safe: !self.quarantined,
             tags: tags.clone(),
             password_hash: self.password_hash.clone(),
         };
@@ -565,10 +2299,25 @@ impl Link {
             created_at: self.created_at,
             updated_at: self.updated_at,
             is_active: self.is_active,
+            deactivation_reason: self.deactivation_reason.clone(),
             tags,
             is_password_protected: self.password_hash.is_some(),
+            is_quarantined: self.quarantined,
+            display_host,
+            display_host_warning,
+            tracking_mode: self.tracking_mode(),
+            path_passthrough: self.path_passthrough,
+            allowed_referrers: self.allowed_referrer_domains(),
+            blocked_referrers: self.blocked_referrer_domains(),
+            referrer_strict: self.referrer_strict,
+            expiry_policy: self.expiry_policy(),
+            sliding_window_days: self.sliding_window_days,
+            organization_id: self.organization_id,
+            notes: self.notes.clone(),
+            security,
             metadata,
             total_clicks: stats.total_clicks,
+            raw_clicks: stats.raw_clicks,
             unique_visitors: stats.unique_visitors,
             bot_clicks: stats.bot_clicks,
             last_accessed_at: stats.last_accessed_at.or(self.last_accessed_at),
@@ -609,6 +2358,145 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_sanitize_normalizes_custom_alias() {
+        let mut request: CreateLinkRequest = serde_json::from_value(serde_json::json!({
+            "url": " https://example.com ",
+            "custom_alias": " Summer-Sale\t"
+        }))
+        .unwrap();
+        request.sanitize();
+
+        assert_eq!(request.custom_alias.as_deref(), Some("summer-sale"));
+        assert_eq!(request.url, "https://example.com");
+    }
+
+    /// `https://example.com/` padded with `fill` to exactly `bytes` bytes
+    fn url_of_len(bytes: usize, fill: &str) -> String {
+        let mut url = "https://example.com/".to_string();
+        while url.len() + fill.len() <= bytes {
+            url.push_str(fill);
+        }
+        url.push_str(&"a".repeat(bytes - url.len()));
+        url
+    }
+
+    fn request_with_url(url: String) -> CreateLinkRequest {
+        serde_json::from_value(serde_json::json!({ "url": url })).unwrap()
+    }
+
+    #[test]
+    fn test_validate_custom_url_length_boundary() {
+        let max = crate::utils::url_validator::MAX_URL_LENGTH;
+
+        let at_limit = request_with_url(url_of_len(max, "a"));
+        assert_eq!(at_limit.url.len(), max);
+        assert!(at_limit.validate_custom(max).is_ok());
+
+        let over = request_with_url(url_of_len(max + 1, "a"));
+        assert_eq!(
+            over.validate_custom(max),
+            Err(LinkRequestError::UrlTooLong {
+                current: max + 1,
+                max
+            })
+        );
+
+        // A lower configured limit applies on its own
+        assert!(at_limit.validate_custom(max - 1).is_err());
+    }
+
+    #[test]
+    fn test_validate_custom_counts_utf8_bytes() {
+        let max = crate::utils::url_validator::MAX_URL_LENGTH;
+
+        // Two-byte characters right up to the limit
+        let at_limit = request_with_url(url_of_len(max, "é"));
+        assert!(at_limit.url.chars().count() < max);
+        assert!(at_limit.validate_custom(max).is_ok());
+
+        // Under the limit in characters, one byte over it in bytes
+        let over = request_with_url(url_of_len(max - 1, "a") + "é");
+        assert_eq!(over.url.len(), max + 1);
+        assert!(over.url.chars().count() <= max);
+        assert_eq!(
+            over.validate_custom(max),
+            Err(LinkRequestError::UrlTooLong {
+                current: max + 1,
+                max
+            })
+        );
+
+        // A four-byte character ending exactly on the limit
+        let emoji = request_with_url(url_of_len(max - 4, "a") + "🦀");
+        assert_eq!(emoji.url.len(), max);
+        assert!(emoji.validate_custom(max).is_ok());
+    }
+
+    #[test]
+    fn test_sanitize_normalizes_tags() {
+        let mut request: CreateLinkRequest = serde_json::from_value(serde_json::json!({
+            "url": "https://example.com",
+            "tags": [" Work ", "work", "", "  ", "Summer Sale", "WORK", "summer sale"]
+        }))
+        .unwrap();
+        request.sanitize();
+
+        assert_eq!(request.tags, vec!["work", "summer sale"]);
+    }
+
+    #[test]
+    fn test_validate_tags_names_offending_index() {
+        let long = "a".repeat(MAX_TAG_LENGTH + 1);
+        let tags = vec!["ok".to_string(), long];
+        let error = validate_tags(&tags).unwrap_err();
+        assert_eq!(error, TagError::TooLong { index: 1 });
+        assert_eq!(error.field(), "tags[1]");
+
+        let tags = vec![
+            "ok".to_string(),
+            "ok".to_string(),
+            "bad\u{7}tag".to_string(),
+        ];
+        assert_eq!(
+            validate_tags(&tags),
+            Err(TagError::ControlCharacter { index: 2 })
+        );
+
+        // Length is counted in characters after trimming
+        let tags = vec![format!("  {}  ", "é".repeat(MAX_TAG_LENGTH))];
+        assert!(validate_tags(&tags).is_ok());
+    }
+
+    #[test]
+    fn test_validate_tags_counts_distinct_tags() {
+        let mut tags: Vec<String> = (0..MAX_TAGS_PER_LINK)
+            .map(|i| format!("tag{}", i))
+            .collect();
+        tags.push("TAG0".to_string());
+        assert!(validate_tags(&tags).is_ok());
+
+        tags.push("one-too-many".to_string());
+        let error = validate_tags(&tags).unwrap_err();
+        assert_eq!(error, TagError::TooMany);
+        assert_eq!(error.field(), "tags");
+    }
+
+    #[test]
+    fn test_retain_valid_tags_drops_invalid_and_caps() {
+        let mut tags = vec![
+            "x".repeat(MAX_TAG_LENGTH + 1),
+            "line\nbreak".to_string(),
+            " Keep ".to_string(),
+        ];
+        tags.extend((0..30).map(|i| format!("tag{}", i)));
+
+        let retained = retain_valid_tags(&tags);
+        assert_eq!(retained.len(), MAX_TAGS_PER_LINK);
+        assert_eq!(retained[0], "keep");
+        assert_eq!(retained[1], "tag0");
+    }
+
     #[test]
     fn test_pagination() {
         let pagination = LinkPagination {
@@ -625,6 +2513,89 @@ mod tests {
         assert_eq!(large_pagination.limit(), 100); // Should cap at 100
     }
 
+    #[test]
+    fn test_tracking_mode() {
+        for mode in [
+            TrackingMode::Full,
+            TrackingMode::Anonymous,
+            TrackingMode::RespectDnt,
+        ] {
+            assert_eq!(TrackingMode::from(mode.as_str()), mode);
+        }
+        assert_eq!(TrackingMode::from("unknown"), TrackingMode::Full);
+
+        assert!(!TrackingMode::Full.is_anonymous(true));
+        assert!(TrackingMode::Anonymous.is_anonymous(false));
+        assert!(TrackingMode::RespectDnt.is_anonymous(true));
+        assert!(!TrackingMode::RespectDnt.is_anonymous(false));
+    }
+
+    #[test]
+    fn test_expiry_policy_validation() {
+        let request = |body: serde_json::Value| -> CreateLinkRequest {
+            serde_json::from_value(body).unwrap()
+        };
+        let max = crate::utils::url_validator::MAX_URL_LENGTH;
+
+        let fixed = request(serde_json::json!({ "url": "https://example.com" }));
+        assert_eq!(fixed.expiry_policy, ExpiryPolicy::Fixed);
+        assert!(fixed.validate_custom(max).is_ok());
+
+        let sliding = request(serde_json::json!({
+            "url": "https://example.com",
+            "expiry_policy": "sliding",
+            "sliding_window_days": 30
+        }));
+        assert!(sliding.validate_custom(max).is_ok());
+        assert!(sliding.validate().is_ok());
+
+        let cases = [
+            (
+                serde_json::json!({ "expiry_policy": "sliding" }),
+                LinkRequestError::WindowRequired,
+            ),
+            (
+                serde_json::json!({ "sliding_window_days": 30 }),
+                LinkRequestError::WindowWithoutSliding,
+            ),
+            (
+                serde_json::json!({
+                    "expiry_policy": "sliding",
+                    "sliding_window_days": 30,
+                    "expires_at": "2999-01-01T00:00:00Z"
+                }),
+                LinkRequestError::ExpiryWithSliding,
+            ),
+        ];
+        for (mut body, expected) in cases {
+            body["url"] = "https://example.com".into();
+            assert_eq!(request(body).validate_custom(max), Err(expected));
+        }
+
+        for days in [0, 366] {
+            let out_of_range = request(serde_json::json!({
+                "url": "https://example.com",
+                "expiry_policy": "sliding",
+                "sliding_window_days": days
+            }));
+            let errors = out_of_range.validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("sliding_window_days"));
+        }
+    }
+
+    #[test]
+    fn test_sliding_expiry() {
+        let last_click = DateTime::parse_from_rfc3339("2025-10-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            sliding_expiry(last_click, 30).to_rfc3339(),
+            "2025-10-31T12:00:00+00:00"
+        );
+        assert_eq!(ExpiryPolicy::from("sliding"), ExpiryPolicy::Sliding);
+        assert_eq!(ExpiryPolicy::from("unknown"), ExpiryPolicy::Fixed);
+    }
+
     #[test]
     fn test_extract_domain() {
         assert_eq!(
@@ -637,4 +2608,354 @@ mod tests {
         );
         assert_eq!(extract_domain("invalid-url"), None);
     }
+
+    #[test]
+    fn test_stats_query_resolves_periods() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let created = day("2024-01-01");
+        let today = day("2024-06-30");
+        let query = |period: Option<&str>, from: Option<&str>, compare: Option<bool>| {
+            LinkStatsQuery {
+                period: period.map(str::to_string),
+                from: from.map(day),
+                to: None,
+                compare,
+                tz: None,
+            }
+            .into_window(created, today)
+        };
+
+        // All-time totals, no comparison possible
+        assert_eq!(query(None, None, None), Ok(None));
+
+        let week = query(Some("7d"), None, None).unwrap().unwrap();
+        assert_eq!((week.from, week.to), (day("2024-06-24"), today));
+        assert!(week.compare);
+        assert_eq!(week.days(), 7);
+        assert_eq!(
+            week.previous_period(),
+            (day("2024-06-17"), day("2024-06-23"))
+        );
+
+        let month = query(Some("30d"), None, Some(false)).unwrap().unwrap();
+        assert_eq!(month.from, day("2024-06-01"));
+        assert!(!month.compare);
+
+        // `from` alone implies a custom range ending today
+        let custom = query(None, Some("2024-06-21"), None).unwrap().unwrap();
+        assert_eq!((custom.from, custom.to), (day("2024-06-21"), today));
+        assert_eq!(
+            custom.previous_period(),
+            (day("2024-06-11"), day("2024-06-20"))
+        );
+        let since_created = query(Some("custom"), None, None).unwrap().unwrap();
+        assert_eq!(since_created.from, created);
+
+        assert!(query(Some("90d"), None, None).is_err());
+        assert!(query(Some("7d"), Some("2024-06-01"), None).is_err());
+        assert!(query(Some("custom"), Some("2024-07-01"), None).is_err());
+    }
+
+    #[test]
+    fn test_campaign_stats_query_defaults_to_link_lifetime() {
+        let day = |s: &str| s.parse::<NaiveDate>().unwrap();
+        let created = day("2024-01-01");
+        let today = day("2024-06-30");
+
+        assert_eq!(
+            CampaignStatsQuery::default().into_range(created, today),
+            Ok((created, today))
+        );
+        let week = CampaignStatsQuery {
+            period: Some("7d".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            week.into_range(created, today),
+            Ok((day("2024-06-24"), today))
+        );
+        let backwards = CampaignStatsQuery {
+            from: Some(day("2024-07-01")),
+            ..Default::default()
+        };
+        assert!(backwards.into_range(created, today).is_err());
+    }
+
+    #[test]
+    fn test_period_change_percent() {
+        assert_eq!(PeriodChange::new(120, 80).change_percent, Some(50.0));
+        assert_eq!(PeriodChange::new(2, 3).change_percent, Some(-33.3));
+        assert_eq!(PeriodChange::new(5, 5).change_percent, Some(0.0));
+        // Nothing to compare against
+        assert_eq!(PeriodChange::new(9, 0).change_percent, None);
+        assert_eq!(PeriodChange::new(0, 0).change_percent, None);
+    }
+
+    #[test]
+    fn test_metadata_to_extract() {
+        let mut request = CreateLinkRequest::from(QuickLinkRequest {
+            url: "https://example.com".to_string(),
+        });
+        assert_eq!(
+            request.metadata_to_extract(),
+            vec!["title", "description", "og_image", "favicon_url"]
+        );
+
+        request.title = Some("Spring Sale".to_string());
+        request.favicon_url = Some("https://example.com/favicon.ico".to_string());
+        assert_eq!(
+            request.metadata_to_extract(),
+            vec!["description", "og_image"]
+        );
+    }
+
+    #[test]
+    fn test_conversion_rate() {
+        assert_eq!(conversion_rate(36, 480), Some(7.5));
+        assert_eq!(conversion_rate(1, 3), Some(33.3));
+        // Pixel hits without a counted click (e.g. repeat clicks) can exceed 100%
+        assert_eq!(conversion_rate(3, 2), Some(150.0));
+        assert_eq!(conversion_rate(4, 0), None);
+    }
+
+    #[test]
+    fn test_redirect_record_encoding() {
+        let record = RedirectRecord {
+            link_id: Uuid::nil(),
+            destination: "https://example.com/a".to_string(),
+            flags: RedirectRecord::ACTIVE | RedirectRecord::PASSWORD_PROTECTED,
+            expires_at: Some(1_700_000_000),
+            redirect_type: DEFAULT_REDIRECT_STATUS,
+            tracking_mode: TrackingMode::Anonymous,
+            path_passthrough: true,
+            short_code: "abc123".to_string(),
+            owner_id: Some(Uuid::nil()),
+            allowed_referrers: vec!["example.com".to_string()],
+            blocked_referrers: vec!["spam.example".to_string()],
+            referrer_strict: true,
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(serde_json::from_str::<RedirectRecord>(&json).unwrap(), record);
+        assert!(record.has(RedirectRecord::PASSWORD_PROTECTED));
+        assert!(!record.has(RedirectRecord::QUARANTINED));
+
+        let before = DateTime::from_timestamp(1_699_999_999, 0).unwrap();
+        let after = DateTime::from_timestamp(1_700_000_001, 0).unwrap();
+        assert!(!record.is_expired(before));
+        assert!(record.is_expired(after));
+
+        // Only the required keys: optional ones take their defaults
+        let minimal: RedirectRecord = serde_json::from_str(
+            r#"{"id":"00000000-0000-0000-0000-000000000000","to":"https://example.com","f":1}"#,
+        )
+        .unwrap();
+        assert_eq!(minimal.redirect_type, DEFAULT_REDIRECT_STATUS);
+        assert_eq!(minimal.tracking_mode, TrackingMode::Full);
+        assert!(!minimal.path_passthrough);
+        assert_eq!(minimal.owner_id, None);
+        assert!(minimal.allowed_referrers.is_empty() && !minimal.referrer_strict);
+        assert!(!minimal.is_expired(after));
+        assert!(!minimal.via_alias("anything"));
+        assert!(!record.via_alias("abc123"));
+        assert!(record.via_alias("ABC123"));
+        assert!(record.via_alias("spring-sale"));
+    }
+
+    #[test]
+    fn test_referrer_allowed() {
+        let record: RedirectRecord = serde_json::from_str(
+            r#"{"id":"00000000-0000-0000-0000-000000000000","to":"https://example.com","f":1}"#,
+        )
+        .unwrap();
+        // No restrictions: anything goes, including no referrer
+        assert!(record.referrer_allowed(None));
+        assert!(record.referrer_allowed(Some("https://anywhere.example/")));
+
+        let allow_list = RedirectRecord {
+            allowed_referrers: vec!["newsletter.com".to_string()],
+            ..record.clone()
+        };
+        assert!(allow_list.referrer_allowed(Some("https://newsletter.com/issue/4")));
+        // Subdomains share the registrable domain
+        assert!(allow_list.referrer_allowed(Some("https://mail.Newsletter.COM/")));
+        assert!(!allow_list.referrer_allowed(Some("https://other.com/")));
+        assert!(!allow_list.referrer_allowed(Some("https://newsletter.com.evil.net/")));
+        // Empty referrers are allowed unless the link is strict
+        assert!(allow_list.referrer_allowed(None));
+        assert!(allow_list.referrer_allowed(Some("")));
+        let strict = RedirectRecord {
+            referrer_strict: true,
+            ..allow_list.clone()
+        };
+        assert!(!strict.referrer_allowed(None));
+        assert!(!strict.referrer_allowed(Some("not a url")));
+        assert!(strict.referrer_allowed(Some("https://newsletter.com/")));
+
+        let block_list = RedirectRecord {
+            blocked_referrers: vec!["spam.co.uk".to_string()],
+            ..record
+        };
+        assert!(!block_list.referrer_allowed(Some("http://www.spam.co.uk/page")));
+        assert!(block_list.referrer_allowed(Some("http://ham.co.uk/page")));
+        assert!(block_list.referrer_allowed(None));
+    }
+
+    #[test]
+    fn test_normalize_referrer_domains() {
+        let domains = [
+            " Newsletter.com ".to_string(),
+            "https://example.co.uk/path".to_string(),
+            "*.partner.io".to_string(),
+            "newsletter.com".to_string(),
+        ];
+        assert_eq!(
+            normalize_referrer_domains("allowed_referrers", &domains).unwrap(),
+            vec!["newsletter.com", "example.co.uk", "partner.io"]
+        );
+
+        let error =
+            normalize_referrer_domains("allowed_referrers", &["mail.example.com".to_string()])
+                .unwrap_err();
+        assert_eq!(error.field(), "allowed_referrers[0]");
+        assert_eq!(error.code(), "not_registrable");
+        assert!(error.to_string().contains("'example.com'"));
+
+        let error = normalize_referrer_domains(
+            "blocked_referrers",
+            &["ok.com".to_string(), "bad domain.com".to_string()],
+        )
+        .unwrap_err();
+        assert_eq!(error.field(), "blocked_referrers[1]");
+        assert_eq!(error.code(), "invalid_domain");
+        assert!(
+            normalize_referrer_domains("blocked_referrers", &["localhost".to_string()]).is_err()
+        );
+
+        let too_many: Vec<String> = (0..=MAX_REFERRER_DOMAINS)
+            .map(|i| format!("site{}.com", i))
+            .collect();
+        let error = normalize_referrer_domains("blocked_referrers", &too_many).unwrap_err();
+        assert_eq!(error.field(), "blocked_referrers");
+        assert_eq!(error.code(), "length");
+    }
+
+    #[test]
+    fn test_passthrough_destination() {
+        let cases = [
+            (
+                "https://docs.example.com",
+                "guides/intro",
+                "https://docs.example.com/guides/intro",
+            ),
+            (
+                "https://docs.example.com/v2/",
+                "guides/intro",
+                "https://docs.example.com/v2/guides/intro",
+            ),
+            (
+                "https://docs.example.com/v2",
+                "guides/",
+                "https://docs.example.com/v2/guides/",
+            ),
+            (
+                "https://example.com/a?ref=qck#top",
+                "b",
+                "https://example.com/a/b?ref=qck#top",
+            ),
+            ("https://example.com/", "", "https://example.com/"),
+            // Segments are re-encoded, so none can add a query, fragment or extra segment
+            (
+                "https://example.com",
+                "a b/c?d#e",
+                "https://example.com/a%20b/c%3Fd%23e",
+            ),
+            ("https://example.com", "100%", "https://example.com/100%25"),
+        ];
+        for (destination, sub_path, expected) in cases {
+            assert_eq!(
+                passthrough_destination(destination, sub_path).as_deref(),
+                Some(expected),
+                "{destination} + {sub_path}"
+            );
+        }
+
+        for sub_path in ["preview", "unlock/x", "../admin", "a/./b"] {
+            assert_eq!(
+                passthrough_destination("https://example.com", sub_path),
+                None
+            );
+        }
+        assert_eq!(passthrough_destination("mailto:a@example.com", "x"), None);
+        // Only the first segment is reserved
+        assert_eq!(
+            passthrough_destination("https://example.com", "docs/preview").as_deref(),
+            Some("https://example.com/docs/preview")
+        );
+    }
+
+    #[test]
+    fn test_code_meta_fields_round_trip() {
+        let meta = CodeMeta {
+            link_id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            flags: RedirectRecord::ACTIVE | RedirectRecord::PASSWORD_PROTECTED,
+        };
+        let fields: std::collections::HashMap<String, String> = meta
+            .to_fields()
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        assert_eq!(CodeMeta::from_fields(&fields), Some(meta));
+        assert!(meta.has(RedirectRecord::PASSWORD_PROTECTED));
+        assert!(!meta.has(RedirectRecord::QUARANTINED));
+
+        // A hash missing any field, or with a field that doesn't parse, is a miss
+        for key in ["id", "user_id", "flags"] {
+            let mut partial = fields.clone();
+            partial.remove(key);
+            assert_eq!(CodeMeta::from_fields(&partial), None, "{key}");
+        }
+        let mut corrupt = fields.clone();
+        corrupt.insert("flags".to_string(), "256".to_string());
+        assert_eq!(CodeMeta::from_fields(&corrupt), None);
+        assert_eq!(CodeMeta::from_fields(&Default::default()), None);
+    }
+
+    #[test]
+    fn test_link_owner_fields_round_trip() {
+        let fields = |owner: &LinkOwner| -> std::collections::HashMap<String, String> {
+            owner
+                .to_fields()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect()
+        };
+
+        let personal = LinkOwner {
+            user_id: Uuid::new_v4(),
+            organization_id: None,
+            short_code: "abc123".to_string(),
+        };
+        assert_eq!(fields(&personal)["org_id"], "");
+        assert_eq!(LinkOwner::from_fields(&fields(&personal)), Some(personal.clone()));
+
+        let organization = LinkOwner {
+            organization_id: Some(Uuid::new_v4()),
+            ..personal
+        };
+        let organization_fields = fields(&organization);
+        assert_eq!(
+            LinkOwner::from_fields(&organization_fields),
+            Some(organization)
+        );
+
+        for key in ["user_id", "org_id", "code"] {
+            let mut partial = organization_fields.clone();
+            partial.remove(key);
+            assert_eq!(LinkOwner::from_fields(&partial), None, "{key}");
+        }
+        let mut corrupt = organization_fields;
+        corrupt.insert("org_id".to_string(), "not-a-uuid".to_string());
+        assert_eq!(LinkOwner::from_fields(&corrupt), None);
+    }
 }
@@ -0,0 +1,345 @@
+// Link change history
+// Every change to a link's mutable fields is stored as a revision listing each changed
+// field with its value before and after
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::models::link::{ExpiryPolicy, Link, TrackingMode};
+use crate::schema::link_revisions;
+
+/// Largest page size accepted by GET /v1/links/{id}/history
+pub const MAX_LINK_HISTORY_PAGE_SIZE: i64 = 100;
+
+/// What produced a revision
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionSource {
+    /// PUT /v1/links/{id}
+    Update,
+    /// Bulk activate / deactivate
+    BulkStatus,
+}
+
+impl RevisionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RevisionSource::Update => "update",
+            RevisionSource::BulkStatus => "bulk_status",
+        }
+    }
+}
+
+/// One changed field; `before` and `after` are `null` for unset values
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldChange {
+    pub field: String,
+    #[schema(value_type = Object)]
+    pub before: serde_json::Value,
+    #[schema(value_type = Object)]
+    pub after: serde_json::Value,
+}
+
+/// Mutable fields compared between two versions of a link
+/// The password hash is never recorded, only whether the link is protected
+#[derive(Serialize)]
+struct LinkSnapshot<'a> {
+    original_url: &'a str,
+    title: Option<&'a str>,
+    description: Option<&'a str>,
+    og_image: Option<&'a str>,
+    favicon_url: Option<&'a str>,
+    tags: Vec<&'a str>,
+    expires_at: Option<DateTime<Utc>>,
+    expiry_policy: ExpiryPolicy,
+    sliding_window_days: Option<i32>,
+    is_active: bool,
+    is_password_protected: bool,
+    tracking_mode: TrackingMode,
+    path_passthrough: bool,
+    allowed_referrers: Vec<&'a str>,
+    blocked_referrers: Vec<&'a str>,
+    referrer_strict: bool,
+    notes: Option<&'a str>,
+}
+
+impl<'a> From<&'a Link> for LinkSnapshot<'a> {
+    fn from(link: &'a Link) -> Self {
+        Self {
+            original_url: &link.original_url,
+            title: link.title.as_deref(),
+            description: link.description.as_deref(),
+            og_image: link.og_image.as_deref(),
+            favicon_url: link.favicon_url.as_deref(),
+            tags: link
+                .tags
+                .iter()
+                .flatten()
+                .filter_map(|tag| tag.as_deref())
+                .collect(),
+            expires_at: link.expires_at,
+            expiry_policy: link.expiry_policy(),
+            sliding_window_days: link.sliding_window_days,
+            is_active: link.is_active,
+            is_password_protected: link.password_hash.is_some(),
+            tracking_mode: link.tracking_mode(),
+            path_passthrough: link.path_passthrough,
+            allowed_referrers: link
+                .allowed_referrers
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+            blocked_referrers: link
+                .blocked_referrers
+                .iter()
+                .flatten()
+                .map(String::as_str)
+                .collect(),
+            referrer_strict: link.referrer_strict,
+            notes: link.notes.as_deref(),
+        }
+    }
+}
+
+/// Field-level differences between two versions of a link
+/// A new password on an already protected link shows up as a `password` change with
+/// both values `null`
+pub fn diff_links(before: &Link, after: &Link) -> Vec<FieldChange> {
+    let snapshot = |link| serde_json::to_value(LinkSnapshot::from(link)).unwrap_or_default();
+    let (before_fields, after_fields) = (snapshot(before), snapshot(after));
+
+    let mut changes: Vec<FieldChange> = match (before_fields, after_fields) {
+        (serde_json::Value::Object(old), serde_json::Value::Object(mut new)) => old
+            .into_iter()
+            .filter_map(|(field, old_value)| {
+                let new_value = new.remove(&field).unwrap_or_default();
+                (old_value != new_value).then_some(FieldChange {
+                    field,
+                    before: old_value,
+                    after: new_value,
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    if before.password_hash.is_some()
+        && after.password_hash.is_some()
+        && before.password_hash != after.password_hash
+    {
+        changes.push(FieldChange {
+            field: "password".to_string(),
+            before: serde_json::Value::Null,
+            after: serde_json::Value::Null,
+        });
+    }
+
+    changes
+}
+
+// =============================================================================
+// DATABASE MODELS
+// =============================================================================
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = link_revisions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct LinkRevisionRecord {
+    pub id: Uuid,
+    pub link_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub source: String,
+    pub changes: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Insertable)]
+#[diesel(table_name = link_revisions)]
+pub struct NewLinkRevision {
+    pub link_id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub source: String,
+    pub changes: serde_json::Value,
+}
+
+impl NewLinkRevision {
+    /// Revision for the change from `before` to `after`; `None` when no tracked field changed
+    pub fn between(
+        before: &Link,
+        after: &Link,
+        actor_id: Option<Uuid>,
+        source: RevisionSource,
+    ) -> Option<Self> {
+        let changes = diff_links(before, after);
+        if changes.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            link_id: after.id,
+            actor_id,
+            source: source.as_str().to_string(),
+            changes: serde_json::to_value(changes).ok()?,
+        })
+    }
+}
+
+// =============================================================================
+// QUERY / RESPONSES
+// =============================================================================
+
+/// Pagination for GET /v1/links/{id}/history
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct LinkHistoryQuery {
+    pub page: Option<i64>,
+    /// Defaults to 20, at most 100
+    pub per_page: Option<i64>,
+}
+
+impl LinkHistoryQuery {
+    pub fn page(&self) -> i64 {
+        self.page.unwrap_or(1).max(1)
+    }
+
+    pub fn per_page(&self) -> i64 {
+        self.per_page
+            .unwrap_or(20)
+            .clamp(1, MAX_LINK_HISTORY_PAGE_SIZE)
+    }
+
+    pub fn offset(&self) -> i64 {
+        (self.page() - 1) * self.per_page()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "id": "0b6f7c1e-2a41-4c38-9d55-3f1b7c2e9a10",
+    "actor_id": "123e4567-e89b-12d3-a456-426614174000",
+    "actor_email": "marketing@example.com",
+    "source": "update",
+    "changes": [
+        {"field": "original_url", "before": "https://example.com/spring", "after": "https://example.com/summer"}
+    ],
+    "created_at": "2024-06-01T09:30:00Z"
+}))]
+pub struct LinkRevisionResponse {
+    pub id: Uuid,
+    /// `null` for changes not made by a user
+    pub actor_id: Option<Uuid>,
+    /// `null` when the account no longer exists
+    pub actor_email: Option<String>,
+    /// `update` or `bulk_status`
+    pub source: String,
+    pub changes: Vec<FieldChange>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl LinkRevisionResponse {
+    pub fn new(record: LinkRevisionRecord, actor_email: Option<String>) -> Self {
+        Self {
+            id: record.id,
+            actor_id: record.actor_id,
+            actor_email,
+            source: record.source,
+            changes: serde_json::from_value(record.changes).unwrap_or_default(),
+            created_at: record.created_at,
+        }
+    }
+}
+
+/// Page of a link's revisions, newest first
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "revisions": [{
+        "id": "0b6f7c1e-2a41-4c38-9d55-3f1b7c2e9a10",
+        "actor_id": "123e4567-e89b-12d3-a456-426614174000",
+        "actor_email": "marketing@example.com",
+        "source": "update",
+        "changes": [
+            {"field": "original_url", "before": "https://example.com/spring", "after": "https://example.com/summer"}
+        ],
+        "created_at": "2024-06-01T09:30:00Z"
+    }],
+    "total": 1,
+    "page": 1,
+    "per_page": 20
+}))]
+pub struct LinkHistoryResponse {
+    pub revisions: Vec<LinkRevisionResponse>,
+    pub total: i64,
+    pub page: i64,
+    pub per_page: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link() -> Link {
+        serde_json::from_value(serde_json::json!({
+            "id": "123e4567-e89b-12d3-a456-426614174000",
+            "user_id": "123e4567-e89b-12d3-a456-426614174001",
+            "short_code": "abc123",
+            "original_url": "https://example.com/spring",
+            "title": "Spring sale",
+            "tags": ["promo"],
+            "is_active": true,
+            "processing_status": "completed",
+            "created_at": "2024-01-01T12:00:00Z",
+            "updated_at": "2024-01-01T12:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_diff_lists_only_changed_fields() {
+        let before = link();
+        let mut after = link();
+        after.original_url = "https://example.com/summer".to_string();
+        after.tags = Some(vec![Some("promo".to_string()), Some("summer".to_string())]);
+        after.notes = Some("Printed on the flyer".to_string());
+        after.updated_at = Utc::now();
+
+        let changes = diff_links(&before, &after);
+        let fields: Vec<&str> = changes.iter().map(|c| c.field.as_str()).collect();
+        assert_eq!(fields, vec!["notes", "original_url", "tags"]);
+
+        let url = changes.iter().find(|c| c.field == "original_url").unwrap();
+        assert_eq!(url.before, "https://example.com/spring");
+        assert_eq!(url.after, "https://example.com/summer");
+        assert_eq!(changes[0].before, serde_json::Value::Null);
+    }
+
+    #[test]
+    fn test_unchanged_link_has_no_revision() {
+        let mut after = link();
+        after.updated_at = Utc::now();
+        assert!(NewLinkRevision::between(&link(), &after, None, RevisionSource::Update).is_none());
+    }
+
+    #[test]
+    fn test_password_hashes_are_never_recorded() {
+        let mut before = link();
+        before.password_hash = Some("$2b$12$old".to_string());
+        let mut after = link();
+        after.password_hash = Some("$2b$12$new".to_string());
+
+        let revision =
+            NewLinkRevision::between(&before, &after, None, RevisionSource::Update).unwrap();
+        assert_eq!(
+            revision.changes,
+            serde_json::json!([{"field": "password", "before": null, "after": null}])
+        );
+
+        // Removing protection shows up as the flag, still without the hash
+        after.password_hash = None;
+        let changes = diff_links(&before, &after);
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].field, "is_password_protected");
+        assert!(!serde_json::to_string(&changes).unwrap().contains("$2b$"));
+    }
+}
@@ -1,14 +1,28 @@
+pub mod account_export;
+pub mod analytics;
+pub mod api_usage;
+pub mod audit_log;
 pub mod auth;
+pub mod blocked_domain;
+pub mod denied_ip;
 pub mod link;
+pub mod link_revision;
+pub mod notification_preferences;
+pub mod organization;
+pub mod page;
 pub mod password_reset;
 pub mod refresh_token;
+pub mod security_incident;
+pub mod trusted_domain;
 pub mod user;
 
 // Re-export common types
 pub use auth::*;
 pub use link::{
-    CreateLinkRequest, ExtractedMetadata, Link, LinkFilter, LinkListResponse, LinkMetadata,
-    LinkPagination, LinkResponse, NewLink, UpdateLink, UpdateLinkRequest,
+    AppealLinkRequest, CreateLinkRequest, ExtractedMetadata, Link, LinkFilter, LinkListResponse,
+    LinkMetadata, LinkPagination, LinkResponse, LinkScanUpdate, LinkSecurityQuery,
+    LinkSecurityResponse, LinkSecuritySummary, NewLink, QuarantinedLinkResponse,
+    ResolveAppealRequest, TransferLinkRequest, UpdateLink, UpdateLinkRequest,
 };
 pub use password_reset::*;
 pub use refresh_token::*;
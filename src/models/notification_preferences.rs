@@ -0,0 +1,168 @@
+// Per-user email notification opt-in/out, stored as JSONB in `users.notification_preferences`
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use utoipa::ToSchema;
+
+/// Optional emails a user can opt in to or out of
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationKind {
+    /// Monthly usage summary
+    MonthlySummary,
+    /// Links about to expire
+    ExpiryDigest,
+    /// Sign-in from a device not seen before
+    NewDevice,
+    /// A link's destination stopped responding
+    BrokenLink,
+}
+
+impl NotificationKind {
+    pub const ALL: [NotificationKind; 4] = [
+        NotificationKind::MonthlySummary,
+        NotificationKind::ExpiryDigest,
+        NotificationKind::NewDevice,
+        NotificationKind::BrokenLink,
+    ];
+
+    /// JSON key used in the stored preferences and the settings API
+    pub fn key(self) -> &'static str {
+        match self {
+            NotificationKind::MonthlySummary => "monthly_summary",
+            NotificationKind::ExpiryDigest => "expiry_digest",
+            NotificationKind::NewDevice => "new_device",
+            NotificationKind::BrokenLink => "broken_link",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.key() == key)
+    }
+}
+
+/// Notification preferences; keys missing from the stored JSON take the defaults
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+#[schema(example = json!({
+    "monthly_summary": true,
+    "expiry_digest": true,
+    "new_device": true,
+    "broken_link": false
+}))]
+pub struct NotificationPreferences {
+    /// Monthly usage summary email (opt-in; mirrors `monthly_digest_enabled`)
+    pub monthly_summary: bool,
+    /// Digest of links about to expire
+    pub expiry_digest: bool,
+    /// Alert when the account signs in from a new device
+    pub new_device: bool,
+    /// Alert when a link's destination stops responding
+    pub broken_link: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            monthly_summary: false,
+            expiry_digest: true,
+            new_device: true,
+            broken_link: true,
+        }
+    }
+}
+
+impl NotificationPreferences {
+    /// Read stored preferences; a malformed value falls back to the defaults
+    pub fn from_value(value: &Value) -> Self {
+        serde_json::from_value(value.clone()).unwrap_or_else(|e| {
+            tracing::warn!("Invalid notification preferences {}: {}", value, e);
+            Self::default()
+        })
+    }
+
+    pub fn to_value(self) -> Value {
+        serde_json::to_value(self).unwrap_or_else(|_| Value::Object(Map::new()))
+    }
+
+    pub fn get(&self, kind: NotificationKind) -> bool {
+        match kind {
+            NotificationKind::MonthlySummary => self.monthly_summary,
+            NotificationKind::ExpiryDigest => self.expiry_digest,
+            NotificationKind::NewDevice => self.new_device,
+            NotificationKind::BrokenLink => self.broken_link,
+        }
+    }
+
+    pub fn set(&mut self, kind: NotificationKind, enabled: bool) {
+        match kind {
+            NotificationKind::MonthlySummary => self.monthly_summary = enabled,
+            NotificationKind::ExpiryDigest => self.expiry_digest = enabled,
+            NotificationKind::NewDevice => self.new_device = enabled,
+            NotificationKind::BrokenLink => self.broken_link = enabled,
+        }
+    }
+
+    /// Apply a partial update; kinds not mentioned keep their current setting.
+    /// Nothing is applied if any key is unknown or any value is not a boolean.
+    pub fn apply(&mut self, changes: &Map<String, Value>) -> Result<(), String> {
+        let mut updated = *self;
+        for (key, value) in changes {
+            let kind = NotificationKind::from_key(key)
+                .ok_or_else(|| format!("Unknown notification preference '{}'", key))?;
+            let enabled = value
+                .as_bool()
+                .ok_or_else(|| format!("Notification preference '{}' must be a boolean", key))?;
+            updated.set(kind, enabled);
+        }
+
+        *self = updated;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_notification_preferences_defaults_and_keys() {
+        // Missing keys take the defaults; stale keys from older releases are ignored
+        let stored = json!({"monthly_summary": true, "retired_kind": false});
+        let prefs = NotificationPreferences::from_value(&stored);
+        assert!(prefs.monthly_summary && prefs.expiry_digest && prefs.broken_link);
+        assert_eq!(
+            NotificationPreferences::from_value(&json!("garbage")),
+            NotificationPreferences::default()
+        );
+
+        let serialized = prefs.to_value();
+        for kind in NotificationKind::ALL {
+            assert_eq!(NotificationKind::from_key(kind.key()), Some(kind));
+            assert_eq!(serialized[kind.key()], json!(prefs.get(kind)));
+        }
+    }
+
+    #[test]
+    fn test_notification_preferences_apply() {
+        let mut prefs = NotificationPreferences::default();
+        let changes = json!({"broken_link": false, "monthly_summary": true});
+        prefs.apply(changes.as_object().unwrap()).unwrap();
+        assert!(!prefs.get(NotificationKind::BrokenLink));
+        assert!(prefs.get(NotificationKind::MonthlySummary));
+        assert!(prefs.get(NotificationKind::NewDevice));
+
+        let before = prefs;
+        let unknown = json!({"new_device": false, "weekly_newsletter": true});
+        let err = prefs.apply(unknown.as_object().unwrap()).unwrap_err();
+        assert!(err.contains("'weekly_newsletter'"), "{}", err);
+        assert_eq!(
+            prefs, before,
+            "a rejected update must not be partially applied"
+        );
+
+        let not_bool = json!({"expiry_digest": "yes"});
+        let err = prefs.apply(not_bool.as_object().unwrap()).unwrap_err();
+        assert!(err.contains("'expiry_digest'"), "{}", err);
+    }
+}
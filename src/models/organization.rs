@@ -0,0 +1,321 @@
+// Team accounts: organizations share ownership of links among their members
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::{organization_invitations, organization_members, organizations};
+
+/// Member role; stored lowercase in `organization_members.role`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OrgRole {
+    /// Read organization links and their stats
+    Member,
+    /// Also edit and delete any organization link, and invite members
+    Admin,
+    /// Created the organization; same rights as admin
+    Owner,
+}
+
+impl OrgRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrgRole::Member => "member",
+            OrgRole::Admin => "admin",
+            OrgRole::Owner => "owner",
+        }
+    }
+
+    /// Whether this role may perform `action`
+    pub fn permits(self, action: OrgAction) -> bool {
+        self >= action.required_role()
+    }
+}
+
+/// Operations on an organization or its links, each gated by a minimum role
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrgAction {
+    CreateLink,
+    ReadLink,
+    UpdateLink,
+    DeleteLink,
+    TransferLink,
+    ListMembers,
+    InviteMember,
+    RemoveMember,
+}
+
+impl OrgAction {
+    pub const ALL: [OrgAction; 8] = [
+        OrgAction::CreateLink,
+        OrgAction::ReadLink,
+        OrgAction::UpdateLink,
+        OrgAction::DeleteLink,
+        OrgAction::TransferLink,
+        OrgAction::ListMembers,
+        OrgAction::InviteMember,
+        OrgAction::RemoveMember,
+    ];
+
+    /// Members read and edit organization links; anything destructive or
+    /// affecting membership needs an admin or the owner
+    pub fn required_role(self) -> OrgRole {
+        match self {
+            OrgAction::CreateLink
+            | OrgAction::ReadLink
+            | OrgAction::UpdateLink
+            | OrgAction::ListMembers => OrgRole::Member,
+            OrgAction::DeleteLink
+            | OrgAction::TransferLink
+            | OrgAction::InviteMember
+            | OrgAction::RemoveMember => OrgRole::Admin,
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            OrgAction::CreateLink => "create organization links",
+            OrgAction::ReadLink => "view organization links",
+            OrgAction::UpdateLink => "edit organization links",
+            OrgAction::DeleteLink => "delete organization links",
+            OrgAction::TransferLink => "transfer organization links",
+            OrgAction::ListMembers => "list members",
+            OrgAction::InviteMember => "invite members",
+            OrgAction::RemoveMember => "remove members",
+        }
+    }
+}
+
+impl From<&str> for OrgRole {
+    fn from(s: &str) -> Self {
+        match s {
+            "owner" => OrgRole::Owner,
+            "admin" => OrgRole::Admin,
+            _ => OrgRole::Member,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = organizations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_by: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = organizations)]
+pub struct NewOrganization {
+    pub name: String,
+    pub created_by: Uuid,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = organization_members)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OrganizationMember {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OrganizationMember {
+    /// A user's role in an organization, or `None` for non-members
+    pub async fn find_role(
+        conn: &mut AsyncPgConnection,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> QueryResult<Option<OrgRole>> {
+        let role = organization_members::table
+            .find((organization_id, user_id))
+            .select(organization_members::role)
+            .first::<String>(conn)
+            .await
+            .optional()?;
+
+        Ok(role.map(|role| OrgRole::from(role.as_str())))
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = organization_members)]
+pub struct NewOrganizationMember {
+    pub organization_id: Uuid,
+    pub user_id: Uuid,
+    pub role: String,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = organization_invitations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OrganizationInvitation {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: String,
+    pub role: String,
+    pub token_hash: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub accepted_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = organization_invitations)]
+pub struct NewOrganizationInvitation {
+    pub organization_id: Uuid,
+    pub email: String,
+    pub role: String,
+    pub token_hash: String,
+    pub invited_by: Uuid,
+    pub expires_at: DateTime<Utc>,
+}
+
+// Request/Response models for API
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "name": "Acme Marketing"
+}))]
+pub struct CreateOrganizationRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Name must be between 1 and 255 characters"
+    ))]
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "email": "teammate@example.com",
+    "role": "member"
+}))]
+pub struct InviteMemberRequest {
+    #[validate(email(message = "Invalid email address"))]
+    pub email: String,
+    /// `admin` or `member`; an organization has exactly one owner
+    #[serde(default = "default_invite_role")]
+    pub role: OrgRole,
+}
+
+fn default_invite_role() -> OrgRole {
+    OrgRole::Member
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "token": "q3JvZ2FuaXphdGlvbi1pbnZpdGF0aW9uLXRva2Vu"
+}))]
+pub struct AcceptInvitationRequest {
+    /// Token from the invitation email
+    pub token: String,
+}
+
+/// An organization as seen by one of its members
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrganizationResponse {
+    pub id: Uuid,
+    pub name: String,
+    /// The caller's role
+    pub role: OrgRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl OrganizationResponse {
+    pub fn new(organization: Organization, role: OrgRole) -> Self {
+        Self {
+            id: organization.id,
+            name: organization.name,
+            role,
+            created_at: organization.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrganizationMemberResponse {
+    pub user_id: Uuid,
+    pub email: String,
+    pub full_name: String,
+    pub role: OrgRole,
+    pub joined_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrganizationMemberListResponse {
+    pub members: Vec<OrganizationMemberResponse>,
+}
+
+/// A pending invitation; the token itself is only ever sent by email
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct OrganizationInvitationResponse {
+    pub id: Uuid,
+    pub organization_id: Uuid,
+    pub email: String,
+    pub role: OrgRole,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl From<OrganizationInvitation> for OrganizationInvitationResponse {
+    fn from(invitation: OrganizationInvitation) -> Self {
+        Self {
+            id: invitation.id,
+            organization_id: invitation.organization_id,
+            role: OrgRole::from(invitation.role.as_str()),
+            email: invitation.email,
+            expires_at: invitation.expires_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_org_role_ordering() {
+        assert!(OrgRole::Owner > OrgRole::Admin && OrgRole::Admin > OrgRole::Member);
+        for role in [OrgRole::Member, OrgRole::Admin, OrgRole::Owner] {
+            assert_eq!(OrgRole::from(role.as_str()), role);
+        }
+    }
+
+    #[test]
+    fn test_org_permission_matrix() {
+        use OrgAction::*;
+
+        // (action, member, admin, owner)
+        let matrix = [
+            (CreateLink, true, true, true),
+            (ReadLink, true, true, true),
+            (UpdateLink, true, true, true),
+            (ListMembers, true, true, true),
+            (DeleteLink, false, true, true),
+            (TransferLink, false, true, true),
+            (InviteMember, false, true, true),
+            (RemoveMember, false, true, true),
+        ];
+        assert_eq!(matrix.len(), OrgAction::ALL.len());
+
+        for (action, member, admin, owner) in matrix {
+            assert_eq!(
+                OrgRole::Member.permits(action),
+                member,
+                "member {:?}",
+                action
+            );
+            assert_eq!(OrgRole::Admin.permits(action), admin, "admin {:?}", action);
+            assert_eq!(OrgRole::Owner.permits(action), owner, "owner {:?}", action);
+        }
+    }
+}
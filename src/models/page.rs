@@ -0,0 +1,200 @@
+// Landing pages (link-in-bio)
+// A page is served at its own short code and lists buttons for several of the owner's
+// links; every button goes through that link's normal redirect
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::schema::{page_links, pages};
+
+/// Most links a page can list
+pub const MAX_PAGE_LINKS: usize = 50;
+
+/// Color scheme of a rendered page
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PageTheme {
+    #[default]
+    Light,
+    Dark,
+}
+
+impl PageTheme {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PageTheme::Light => "light",
+            PageTheme::Dark => "dark",
+        }
+    }
+}
+
+impl From<&str> for PageTheme {
+    fn from(s: &str) -> Self {
+        match s {
+            "dark" => PageTheme::Dark,
+            _ => PageTheme::Light,
+        }
+    }
+}
+
+// =============================================================================
+// DATABASE MODELS
+// =============================================================================
+
+#[derive(Debug, Clone, Queryable, Selectable, Identifiable)]
+#[diesel(table_name = pages)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Page {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub short_code: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub theme: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl Page {
+    pub fn theme(&self) -> PageTheme {
+        PageTheme::from(self.theme.as_str())
+    }
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = pages)]
+pub struct NewPage {
+    pub user_id: Uuid,
+    pub short_code: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub theme: String,
+}
+
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = pages)]
+pub struct UpdatePage {
+    pub title: Option<String>,
+    pub description: Option<Option<String>>,
+    pub theme: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = page_links)]
+pub struct NewPageLink {
+    pub page_id: Uuid,
+    pub link_id: Uuid,
+    pub position: i32,
+}
+
+// =============================================================================
+// API MODELS
+// =============================================================================
+
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "short_code": "jane",
+    "title": "Jane Doe",
+    "description": "Designer. Links to my portfolio and shop.",
+    "theme": "dark",
+    "link_ids": [
+        "123e4567-e89b-12d3-a456-426614174000",
+        "123e4567-e89b-12d3-a456-426614174001"
+    ]
+}))]
+pub struct CreatePageRequest {
+    /// Short code the page is served at; generated when omitted. Shares the namespace of
+    /// link short codes and aliases
+    pub short_code: Option<String>,
+
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Title must be between 1 and 255 characters"
+    ))]
+    pub title: String,
+
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub theme: PageTheme,
+
+    /// Links shown as buttons, in this order (at most 50); each must be one the caller
+    /// can manage
+    #[serde(default)]
+    pub link_ids: Vec<Uuid>,
+}
+
+/// Omitted fields are left unchanged; `link_ids` replaces the whole list
+#[derive(Debug, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "title": "Jane Doe - Studio",
+    "link_ids": ["123e4567-e89b-12d3-a456-426614174001"]
+}))]
+pub struct UpdatePageRequest {
+    #[validate(length(
+        min = 1,
+        max = 255,
+        message = "Title must be between 1 and 255 characters"
+    ))]
+    pub title: Option<String>,
+
+    /// An empty description removes it
+    #[validate(length(max = 1000, message = "Description must be at most 1000 characters"))]
+    pub description: Option<String>,
+
+    pub theme: Option<PageTheme>,
+
+    pub link_ids: Option<Vec<Uuid>>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[schema(example = json!({
+    "id": "9b2f0c1e-4d3a-4c52-9a57-2f1c3e5d7a90",
+    "short_code": "jane",
+    "url": "https://qck.sh/jane",
+    "title": "Jane Doe",
+    "description": "Designer. Links to my portfolio and shop.",
+    "theme": "dark",
+    "link_ids": [
+        "123e4567-e89b-12d3-a456-426614174000",
+        "123e4567-e89b-12d3-a456-426614174001"
+    ],
+    "created_at": "2025-09-18T09:00:00Z",
+    "updated_at": "2025-09-18T09:00:00Z"
+}))]
+pub struct PageResponse {
+    pub id: Uuid,
+    pub short_code: String,
+    /// Public URL of the page
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    pub theme: PageTheme,
+    /// Links shown on the page, in order
+    pub link_ids: Vec<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PageResponse {
+    pub fn new(page: Page, link_ids: Vec<Uuid>, base_url: &str) -> Self {
+        Self {
+            id: page.id,
+            url: format!("{}/{}", base_url, page.short_code),
+            theme: page.theme(),
+            short_code: page.short_code,
+            title: page.title,
+            description: page.description,
+            link_ids,
+            created_at: page.created_at,
+            updated_at: page.updated_at,
+        }
+    }
+}
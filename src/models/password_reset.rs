@@ -1,6 +1,7 @@
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 use crate::schema::password_reset_tokens;
@@ -48,24 +49,25 @@ impl NewPasswordResetToken {
 }
 
 // Request/Response models for API
-#[derive(Debug, Serialize, Deserialize, validator::Validate)]
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
+#[schema(example = json!({ "email": "user@example.com" }))]
 pub struct ForgotPasswordRequest {
     #[validate(email(message = "Please provide a valid email address"))]
     pub email: String,
 }
 
-#[derive(Debug, Serialize, Deserialize, validator::Validate)]
+#[derive(Debug, Serialize, Deserialize, validator::Validate, ToSchema)]
 pub struct ResetPasswordRequest {
     #[validate(length(min = 32, max = 64, message = "Invalid reset token format"))]
     pub token: String,
 
-    #[validate(length(
-        min = 8,
-        max = 128,
-        message = "Password must be between 8 and 128 characters"
-    ))]
+    /// Min 8 characters with uppercase, lowercase, digit and special character;
+    /// the policy is checked by the handler
+    #[validate(length(max = 128, message = "Password must be at most 128 characters"))]
+    #[schema(format = Password)]
     pub new_password: String,
 
+    #[schema(format = Password)]
     pub confirm_password: String,
 }
 
@@ -79,14 +81,14 @@ impl ResetPasswordRequest {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ForgotPasswordResponse {
     pub success: bool,
     pub message: String,
     pub data: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ResetPasswordResponse {
     pub success: bool,
     pub message: String,
@@ -358,6 +358,36 @@ impl RefreshToken {
         Ok(updated)
     }
 
+    /// Revoke every live refresh token of a user except those in `keep_family`, the
+    /// rotation lineage of the session that asked. Returns the number revoked.
+    pub async fn revoke_all_for_user_except_family(
+        conn: &mut AsyncPgConnection,
+        user_id_val: Uuid,
+        keep_family: &str,
+        reason: &str,
+    ) -> Result<usize, RefreshTokenError> {
+        use crate::schema::refresh_tokens::dsl::*;
+
+        let now = Utc::now();
+
+        let updated = diesel::update(
+            refresh_tokens
+                .filter(user_id.eq(user_id_val))
+                .filter(token_family.ne(keep_family))
+                .filter(revoked_at.is_null())
+                .filter(expires_at.gt(now)),
+        )
+        .set((
+            revoked_at.eq(Some(now)),
+            revoked_reason.eq(Some(reason)),
+            updated_at.eq(now),
+        ))
+        .execute(conn)
+        .await?;
+
+        Ok(updated)
+    }
+
     /// Clean up expired tokens (should be run periodically)
     pub async fn cleanup_expired(conn: &mut AsyncPgConnection) -> Result<usize, RefreshTokenError> {
         use crate::schema::refresh_tokens::dsl::*;
@@ -375,6 +405,32 @@ impl RefreshToken {
         Ok(deleted)
     }
 
+    /// Delete up to `batch_size` tokens that expired or were revoked before `cutoff`.
+    /// Returns the number of rows deleted; fewer than `batch_size` means nothing is left.
+    pub async fn prune_batch(
+        conn: &mut AsyncPgConnection,
+        cutoff: DateTime<Utc>,
+        batch_size: i64,
+    ) -> Result<usize, RefreshTokenError> {
+        use crate::schema::refresh_tokens::dsl::*;
+
+        let batch: Vec<Uuid> = refresh_tokens
+            .filter(expires_at.lt(cutoff).or(revoked_at.lt(cutoff)))
+            .select(id)
+            .limit(batch_size)
+            .load(conn)
+            .await?;
+        if batch.is_empty() {
+            return Ok(0);
+        }
+
+        let deleted = diesel::delete(refresh_tokens.filter(id.eq_any(&batch)))
+            .execute(conn)
+            .await?;
+
+        Ok(deleted)
+    }
+
     /// Get active token count for user (for rate limiting)
     pub async fn count_active_for_user(
         conn: &mut AsyncPgConnection,
@@ -0,0 +1,109 @@
+// Security incidents recorded against a user's account
+// Listed to the user at GET /v1/auth/security-events and to operators at
+// GET /v1/admin/security-events
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::schema::security_incidents;
+
+/// A rotated refresh token was presented again; its whole family was revoked
+pub const INCIDENT_REFRESH_TOKEN_REUSE: &str = "refresh_token_reuse";
+
+/// Incidents returned by one listing, newest first
+pub const SECURITY_EVENTS_LIMIT: i64 = 50;
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, ToSchema)]
+#[diesel(table_name = security_incidents)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+#[schema(example = json!({
+    "id": "7d9f3c2a-5b1e-4f6a-9c8d-2e4b6a8c0f13",
+    "user_id": "0b7e4a52-3c9d-4e1f-8a6b-5d2c7f9e1a34",
+    "kind": "refresh_token_reuse",
+    "detected_at": "2025-09-30T08:15:42Z",
+    "ip_address": "203.0.113.7",
+    "user_agent": "Mozilla/5.0 (Windows NT 10.0; Win64; x64)",
+    "device_fingerprint": "3f1a9c...",
+    "token_family": "c2b8e0d4-1f7a-4e3b-9d6c-8a5e2f4b7c19",
+    "revoked_tokens": 2
+}))]
+pub struct SecurityIncident {
+    /// Quoted in the `incident_id` of the error that reported the incident
+    pub id: Uuid,
+    pub user_id: Uuid,
+    /// What was detected, e.g. `refresh_token_reuse`
+    pub kind: String,
+    pub detected_at: DateTime<Utc>,
+    /// Client that triggered the incident, e.g. the one replaying a refresh token
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_fingerprint: Option<String>,
+    /// Refresh token family revoked in response
+    pub token_family: Option<String>,
+    /// Refresh tokens revoked in response
+    pub revoked_tokens: i32,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = security_incidents)]
+pub struct NewSecurityIncident {
+    pub user_id: Uuid,
+    pub kind: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub device_fingerprint: Option<String>,
+    pub token_family: Option<String>,
+    pub revoked_tokens: i32,
+}
+
+/// Query parameters of GET /v1/admin/security-events
+#[derive(Debug, Clone, Default, Deserialize, IntoParams)]
+pub struct SecurityEventsQuery {
+    /// Only incidents on this user's account
+    pub user_id: Option<Uuid>,
+}
+
+impl SecurityIncident {
+    pub async fn create(
+        conn: &mut AsyncPgConnection,
+        incident: NewSecurityIncident,
+    ) -> Result<Self, diesel::result::Error> {
+        diesel::insert_into(security_incidents::table)
+            .values(&incident)
+            .returning(SecurityIncident::as_returning())
+            .get_result(conn)
+            .await
+    }
+
+    pub async fn find_by_id(
+        conn: &mut AsyncPgConnection,
+        incident_id: Uuid,
+    ) -> Result<Self, diesel::result::Error> {
+        security_incidents::table
+            .find(incident_id)
+            .select(SecurityIncident::as_select())
+            .first(conn)
+            .await
+    }
+
+    /// Most recent incidents, newest first, optionally for one user
+    pub async fn list_recent(
+        conn: &mut AsyncPgConnection,
+        user_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<Self>, diesel::result::Error> {
+        let mut query = security_incidents::table
+            .select(SecurityIncident::as_select())
+            .order(security_incidents::detected_at.desc())
+            .limit(limit)
+            .into_boxed();
+        if let Some(user_id) = user_id {
+            query = query.filter(security_incidents::user_id.eq(user_id));
+        }
+        query.load(conn).await
+    }
+}
@@ -0,0 +1,81 @@
+// Operator-managed trusted domain entries
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::models::blocked_domain::{normalize_pattern, BlockedEntryType};
+use crate::schema::trusted_domains;
+
+#[derive(Debug, Clone, Queryable, Selectable, Serialize, Deserialize)]
+#[diesel(table_name = trusted_domains)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct TrustedDomain {
+    pub id: Uuid,
+    pub pattern: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = trusted_domains)]
+pub struct NewTrustedDomain {
+    pub pattern: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+}
+
+// Request/Response models for API
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+#[schema(example = json!({
+    "pattern": "corp.example.com",
+    "reason": "Customer's own domain; account and verify pages were being blocked"
+}))]
+pub struct CreateTrustedDomainRequest {
+    /// Domain (trusts subdomains too); schemes and paths are stripped
+    #[validate(length(
+        min = 1,
+        max = 253,
+        message = "Pattern must be between 1 and 253 characters"
+    ))]
+    pub pattern: String,
+
+    #[validate(length(
+        min = 1,
+        max = 1000,
+        message = "Reason must be between 1 and 1000 characters"
+    ))]
+    pub reason: String,
+}
+
+impl CreateTrustedDomainRequest {
+    /// Normalize the pattern the same way as blocklist domain entries
+    pub fn normalized_pattern(&self) -> Result<String, String> {
+        normalize_pattern(&self.pattern, BlockedEntryType::Domain)
+    }
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrustedDomainResponse {
+    pub id: Uuid,
+    pub pattern: String,
+    pub reason: String,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<TrustedDomain> for TrustedDomainResponse {
+    fn from(entry: TrustedDomain) -> Self {
+        Self {
+            id: entry.id,
+            pattern: entry.pattern,
+            reason: entry.reason,
+            created_by: entry.created_by,
+            created_at: entry.created_at,
+        }
+    }
+}
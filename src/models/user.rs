@@ -6,12 +6,15 @@ use diesel::prelude::*;
 use diesel_async::{AsyncPgConnection, RunQueryDsl};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+use crate::models::notification_preferences::{NotificationKind, NotificationPreferences};
 use crate::schema::users;
 
 /// Onboarding status enumeration for tracking user progress (OSS simplified)
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
+#[serde(rename_all = "lowercase")]
 pub enum OnboardingStatus {
     Registered, // Just registered (auto-verified in OSS)
     Completed,  // Onboarding completed, full access
@@ -34,6 +37,46 @@ impl OnboardingStatus {
             _ => Err(format!("Invalid onboarding status: {}", s)),
         }
     }
+
+    /// The status that follows this one, or `None` once onboarding is completed
+    pub fn next(&self) -> Option<Self> {
+        match self {
+            OnboardingStatus::Registered => Some(OnboardingStatus::Completed),
+            OnboardingStatus::Completed => None,
+        }
+    }
+
+    /// Onboarding only moves forward one step at a time; repeating the current status is a no-op
+    pub fn can_transition_to(&self, next: OnboardingStatus) -> bool {
+        *self == next || self.next() == Some(next)
+    }
+}
+
+/// Request body for `POST /v1/onboarding/step`
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "onboarding_status": "completed"
+}))]
+pub struct OnboardingStepRequest {
+    /// Status to move to; must be the current status or the one after it
+    pub onboarding_status: OnboardingStatus,
+}
+
+/// The authenticated user's onboarding progress
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OnboardingStatusResponse {
+    pub onboarding_status: OnboardingStatus,
+    /// Status the next `POST /v1/onboarding/step` should request; null once completed
+    pub next_step: Option<OnboardingStatus>,
+}
+
+impl From<OnboardingStatus> for OnboardingStatusResponse {
+    fn from(status: OnboardingStatus) -> Self {
+        Self {
+            onboarding_status: status,
+            next_step: status.next(),
+        }
+    }
 }
 
 /// Subscription tier enumeration matching your pricing structure
@@ -115,6 +158,20 @@ pub struct User {
     pub full_name: String,
     pub company_name: Option<String>,
     pub onboarding_status: String,
+    /// Tracking mode applied to new links that don't set one
+    pub default_tracking_mode: String,
+    /// Opted in to the monthly usage digest email
+    pub monthly_digest_enabled: bool,
+    /// Last month (YYYY-MM) a digest was sent for
+    pub last_digest_period: Option<String>,
+    /// Email notification opt-in/out; see `NotificationPreferences`
+    pub notification_preferences: serde_json::Value,
+    /// IANA timezone analytics are bucketed in
+    pub timezone: String,
+    /// Set while all of the user's links are paused (`POST /v1/links/pause-all`)
+    pub links_paused_at: Option<DateTime<Utc>>,
+    /// Query parameters removed from new destination URLs; see `query_params_to_strip`
+    pub strip_query_params: Vec<Option<String>>,
 }
 
 /// New user for insertion
@@ -143,6 +200,11 @@ pub struct UserUpdate {
     pub full_name: Option<String>,
     pub company_name: Option<Option<String>>,
     pub onboarding_status: Option<String>,
+    pub default_tracking_mode: Option<String>,
+    pub monthly_digest_enabled: Option<bool>,
+    pub notification_preferences: Option<serde_json::Value>,
+    pub timezone: Option<String>,
+    pub strip_query_params: Option<Vec<String>>,
 }
 
 /// Errors for user operations
@@ -230,6 +292,11 @@ impl User {
             })
     }
 
+    /// Query parameter names and `prefix*` patterns removed from the user's destination URLs
+    pub fn query_params_to_strip(&self) -> Vec<String> {
+        self.strip_query_params.iter().flatten().cloned().collect()
+    }
+
     /// Get user's subscription tier as enum
     pub fn subscription_tier_enum(&self) -> SubscriptionTier {
         SubscriptionTier::from_str(&self.subscription_tier).unwrap_or_else(|e| {
@@ -268,6 +335,16 @@ impl User {
         })
     }
 
+    /// Onboarding status as reported to clients
+    /// OSS deployments skip onboarding, so their users are always Completed as at registration
+    pub fn effective_onboarding_status(&self, is_oss_deployment: bool) -> OnboardingStatus {
+        if is_oss_deployment {
+            OnboardingStatus::Completed
+        } else {
+            self.onboarding_status_enum_with_fallback()
+        }
+    }
+
     /// Check if user has completed onboarding
     pub fn is_onboarding_complete(&self) -> bool {
         match self.onboarding_status_enum() {
@@ -285,6 +362,16 @@ impl User {
         }
     }
 
+    /// Parsed notification preferences, with defaults for kinds not stored
+    pub fn notification_preferences_typed(&self) -> NotificationPreferences {
+        NotificationPreferences::from_value(&self.notification_preferences)
+    }
+
+    /// Whether optional emails of `kind` should be sent to this user
+    pub fn wants_notification(&self, kind: NotificationKind) -> bool {
+        self.notification_preferences_typed().get(kind)
+    }
+
     /// Check if user has completed onboarding (OSS simplified - no payments)
     pub fn needs_payment(&self) -> bool {
         // OSS version has no payments
@@ -361,6 +448,13 @@ mod tests {
             full_name: "Test User".to_string(),
             company_name: None,
             onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+            default_tracking_mode: "full".to_string(),
+            monthly_digest_enabled: false,
+            last_digest_period: None,
+            notification_preferences: serde_json::json!({}),
+            timezone: "UTC".to_string(),
+            links_paused_at: None,
+            strip_query_params: Vec::new(),
         };
 
         assert_eq!(
@@ -384,6 +478,13 @@ mod tests {
             full_name: "Test User".to_string(),
             company_name: None,
             onboarding_status: OnboardingStatus::Registered.as_str().to_string(),
+            default_tracking_mode: "full".to_string(),
+            monthly_digest_enabled: false,
+            last_digest_period: None,
+            notification_preferences: serde_json::json!({}),
+            timezone: "UTC".to_string(),
+            links_paused_at: None,
+            strip_query_params: Vec::new(),
         };
 
         assert_eq!(
@@ -407,6 +508,13 @@ mod tests {
             full_name: "Test User".to_string(),
             company_name: None,
             onboarding_status: OnboardingStatus::Registered.as_str().to_string(),
+            default_tracking_mode: "full".to_string(),
+            monthly_digest_enabled: false,
+            last_digest_period: None,
+            notification_preferences: serde_json::json!({}),
+            timezone: "UTC".to_string(),
+            links_paused_at: None,
+            strip_query_params: Vec::new(),
         };
 
         assert!(!plan_selected_free_user.needs_payment()); // OSS has no payments
@@ -425,6 +533,13 @@ mod tests {
             full_name: "Test User".to_string(),
             company_name: None,
             onboarding_status: OnboardingStatus::Registered.as_str().to_string(),
+            default_tracking_mode: "full".to_string(),
+            monthly_digest_enabled: false,
+            last_digest_period: None,
+            notification_preferences: serde_json::json!({}),
+            timezone: "UTC".to_string(),
+            links_paused_at: None,
+            strip_query_params: Vec::new(),
         };
 
         assert_eq!(
@@ -453,6 +568,13 @@ mod tests {
             full_name: "Test User".to_string(),
             company_name: None,
             onboarding_status: "invalid_status".to_string(),
+            default_tracking_mode: "full".to_string(),
+            monthly_digest_enabled: false,
+            last_digest_period: None,
+            notification_preferences: serde_json::json!({}),
+            timezone: "UTC".to_string(),
+            links_paused_at: None,
+            strip_query_params: Vec::new(),
         };
 
         // onboarding_status_enum() should return an error
@@ -467,5 +589,32 @@ mod tests {
         // Methods should handle invalid status gracefully
         assert!(!invalid_status_user.is_onboarding_complete());
         assert!(!invalid_status_user.needs_payment());
+
+        // OSS deployments report every user as completed, as registration does
+        assert_eq!(
+            invalid_status_user.effective_onboarding_status(true),
+            OnboardingStatus::Completed
+        );
+        assert_eq!(
+            invalid_status_user.effective_onboarding_status(false),
+            OnboardingStatus::Registered
+        );
+    }
+
+    #[test]
+    fn test_onboarding_transitions() {
+        use OnboardingStatus::*;
+
+        assert!(Registered.can_transition_to(Completed));
+        assert!(Registered.can_transition_to(Registered));
+        assert!(Completed.can_transition_to(Completed));
+        assert!(!Completed.can_transition_to(Registered));
+
+        assert_eq!(Registered.next(), Some(Completed));
+        assert_eq!(Completed.next(), None);
+        assert_eq!(
+            serde_json::to_value(OnboardingStatusResponse::from(Registered)).unwrap(),
+            serde_json::json!({"onboarding_status": "registered", "next_step": "completed"})
+        );
     }
 }
@@ -1,5 +1,103 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    account_exports (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 20]
+        status -> Varchar,
+        include_stats -> Bool,
+        archive -> Nullable<Jsonb>,
+        #[max_length = 64]
+        token_hash -> Nullable<Varchar>,
+        expires_at -> Timestamptz,
+        completed_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    api_usage_daily (user_id, day, endpoint) {
+        user_id -> Uuid,
+        day -> Date,
+        #[max_length = 255]
+        endpoint -> Varchar,
+        requests -> Int8,
+        rate_limited -> Int8,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    audit_logs (id) {
+        id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 50]
+        action -> Varchar,
+        #[max_length = 50]
+        resource_type -> Varchar,
+        resource_id -> Nullable<Text>,
+        details -> Nullable<Jsonb>,
+        ip_address -> Nullable<Text>,
+        user_agent -> Nullable<Text>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    blocked_domains (id) {
+        id -> Uuid,
+        #[max_length = 253]
+        pattern -> Varchar,
+        #[max_length = 10]
+        entry_type -> Varchar,
+        reason -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    denied_ips (id) {
+        id -> Uuid,
+        #[max_length = 45]
+        ip -> Varchar,
+        reason -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+        expires_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    link_revisions (id) {
+        id -> Uuid,
+        link_id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 20]
+        source -> Varchar,
+        changes -> Jsonb,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::pg::sql_types::*;
@@ -38,6 +136,110 @@ diesel::table! {
         deleted_at -> Nullable<Timestamptz>,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        last_scanned_at -> Nullable<Timestamptz>,
+        threat_score -> Nullable<Int2>,
+        quarantined -> Bool,
+        quarantined_at -> Nullable<Timestamptz>,
+        quarantine_reason -> Nullable<Text>,
+        #[max_length = 20]
+        appeal_status -> Nullable<Varchar>,
+        appeal_message -> Nullable<Text>,
+        appealed_at -> Nullable<Timestamptz>,
+        #[max_length = 20]
+        risk_level -> Nullable<Varchar>,
+        threats_detected -> Nullable<Jsonb>,
+        scan_warnings -> Nullable<Jsonb>,
+        #[max_length = 20]
+        tracking_mode -> Varchar,
+        organization_id -> Nullable<Uuid>,
+        click_count -> Int8,
+        notes -> Nullable<Text>,
+        #[max_length = 20]
+        deactivation_reason -> Nullable<Varchar>,
+        path_passthrough -> Bool,
+        allowed_referrers -> Array<Nullable<Text>>,
+        blocked_referrers -> Array<Nullable<Text>>,
+        referrer_strict -> Bool,
+        #[max_length = 10]
+        expiry_policy -> Varchar,
+        sliding_window_days -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    organization_invitations (id) {
+        id -> Uuid,
+        organization_id -> Uuid,
+        #[max_length = 320]
+        email -> Varchar,
+        #[max_length = 20]
+        role -> Varchar,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        invited_by -> Uuid,
+        expires_at -> Timestamptz,
+        accepted_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    organization_members (organization_id, user_id) {
+        organization_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 20]
+        role -> Varchar,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    organizations (id) {
+        id -> Uuid,
+        #[max_length = 255]
+        name -> Varchar,
+        created_by -> Uuid,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    page_links (page_id, link_id) {
+        page_id -> Uuid,
+        link_id -> Uuid,
+        position -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    pages (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 20]
+        short_code -> Varchar,
+        #[max_length = 255]
+        title -> Varchar,
+        description -> Nullable<Text>,
+        #[max_length = 20]
+        theme -> Varchar,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
@@ -84,6 +286,52 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    reserved_short_codes (code) {
+        #[max_length = 20]
+        code -> Varchar,
+        reserved_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    security_incidents (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 50]
+        kind -> Varchar,
+        detected_at -> Timestamptz,
+        #[max_length = 45]
+        ip_address -> Nullable<Varchar>,
+        user_agent -> Nullable<Text>,
+        #[max_length = 255]
+        device_fingerprint -> Nullable<Varchar>,
+        #[max_length = 64]
+        token_family -> Nullable<Varchar>,
+        revoked_tokens -> Int4,
+    }
+}
+
+diesel::table! {
+    use diesel::sql_types::*;
+    use diesel::pg::sql_types::*;
+
+    trusted_domains (id) {
+        id -> Uuid,
+        #[max_length = 253]
+        pattern -> Varchar,
+        reason -> Text,
+        created_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     use diesel::sql_types::*;
     use diesel::pg::sql_types::*;
@@ -106,16 +354,56 @@ diesel::table! {
         company_name -> Nullable<Varchar>,
         #[max_length = 50]
         onboarding_status -> Varchar,
+        #[max_length = 20]
+        default_tracking_mode -> Varchar,
+        monthly_digest_enabled -> Bool,
+        #[max_length = 7]
+        last_digest_period -> Nullable<Varchar>,
+        notification_preferences -> Jsonb,
+        #[max_length = 64]
+        timezone -> Varchar,
+        links_paused_at -> Nullable<Timestamptz>,
+        strip_query_params -> Array<Nullable<Text>>,
     }
 }
 
+diesel::joinable!(account_exports -> users (user_id));
+diesel::joinable!(api_usage_daily -> users (user_id));
+diesel::joinable!(blocked_domains -> users (created_by));
+diesel::joinable!(denied_ips -> users (created_by));
+diesel::joinable!(link_revisions -> links (link_id));
+diesel::joinable!(links -> organizations (organization_id));
 diesel::joinable!(links -> users (user_id));
+diesel::joinable!(organization_invitations -> organizations (organization_id));
+diesel::joinable!(organization_invitations -> users (invited_by));
+diesel::joinable!(organization_members -> organizations (organization_id));
+diesel::joinable!(organization_members -> users (user_id));
+diesel::joinable!(organizations -> users (created_by));
+diesel::joinable!(page_links -> links (link_id));
+diesel::joinable!(page_links -> pages (page_id));
+diesel::joinable!(pages -> users (user_id));
 diesel::joinable!(password_reset_tokens -> users (user_id));
 diesel::joinable!(refresh_tokens -> users (user_id));
+diesel::joinable!(security_incidents -> users (user_id));
+diesel::joinable!(trusted_domains -> users (created_by));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    account_exports,
+    api_usage_daily,
+    audit_logs,
+    blocked_domains,
+    denied_ips,
+    link_revisions,
     links,
+    organization_invitations,
+    organization_members,
+    organizations,
+    page_links,
+    pages,
     password_reset_tokens,
     refresh_tokens,
+    reserved_short_codes,
+    security_incidents,
+    trusted_domains,
     users,
 );
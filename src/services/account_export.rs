@@ -0,0 +1,607 @@
+// Account export and import (data portability)
+// Exports are built in the background and the download link is emailed; archives are
+// deleted when the link expires. Operators recreate accounts from archives on import.
+
+use base64::prelude::*;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::{
+        account_export::{
+            short_code_candidates, AccountArchive, AccountExport, AccountImportResponse,
+            ArchivedClickStats, ArchivedLink, ArchivedUser, ExportStatus, ImportEntityResult,
+            ImportOutcome, NewAccountExport, ACCOUNT_ARCHIVE_VERSION,
+        },
+        link::{
+            normalize_referrer_domains, referrer_column, retain_valid_tags, ExpiryPolicy, Link,
+            NewLink,
+        },
+        user::{NewUser, OnboardingStatus, SubscriptionTier, User},
+    },
+    schema::{account_exports, links, reserved_short_codes, users},
+    services::{
+        background_tasks::TASK_REGISTRY, clickhouse_analytics::ClickHouseAnalyticsService,
+        email::EmailService,
+    },
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        custom_alias_validator::CustomAliasValidator,
+        hash_password,
+        service_error::ServiceError,
+        timezone::{parse_timezone, DEFAULT_TIMEZONE},
+        url_validator::parse_strip_query_params,
+    },
+};
+
+/// Days an export (and its emailed download link) is kept
+const EXPORT_EXPIRY_DAYS: i64 = 7;
+
+/// How often expired exports are deleted
+const EXPORT_CLEANUP_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Longest short code the links table accepts
+const MAX_SHORT_CODE_LENGTH: usize = 20;
+
+/// Suffixed short codes tried for an imported link before giving up
+const IMPORT_SHORT_CODE_ATTEMPTS: usize = 20;
+
+#[derive(Clone)]
+pub struct AccountExportService {
+    diesel_pool: DieselPool,
+    email_service: Arc<EmailService>,
+    analytics: Option<Arc<ClickHouseAnalyticsService>>,
+}
+
+impl AccountExportService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            email_service: state.email_service.clone(),
+            analytics: state.clickhouse_analytics.clone(),
+        }
+    }
+
+    /// Queue an export of the user's account; the download link is emailed when it is ready
+    /// Only one export per user is prepared at a time
+    pub async fn request_export(
+        &self,
+        user: &User,
+        include_stats: bool,
+    ) -> Result<AccountExport, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let in_progress = diesel::select(diesel::dsl::exists(
+            account_exports::table
+                .filter(account_exports::user_id.eq(user.id))
+                .filter(account_exports::status.eq(ExportStatus::Pending.as_str())),
+        ))
+        .get_result::<bool>(&mut conn)
+        .await?;
+        if in_progress {
+            return Err(ServiceError::Conflict(
+                "An export is already being prepared".to_string(),
+            ));
+        }
+
+        let export = diesel::insert_into(account_exports::table)
+            .values(&NewAccountExport {
+                user_id: user.id,
+                include_stats,
+                expires_at: Utc::now() + Duration::days(EXPORT_EXPIRY_DAYS),
+            })
+            .returning(AccountExport::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::AccountExported,
+            user.id,
+            "user",
+            Some(user.id.to_string()),
+            Some(format!("Requested account export {}", export.id)),
+        )
+        .await;
+
+        let service = self.clone();
+        let job = export.clone();
+        let user = user.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.complete_export(&job, &user).await {
+                error!("Account export {} failed: {}", job.id, e);
+                if let Err(e) = service.mark_failed(job.id).await {
+                    error!("Failed to mark account export {} as failed: {}", job.id, e);
+                }
+            }
+        });
+
+        Ok(export)
+    }
+
+    /// Build the archive, store it and email the download link
+    async fn complete_export(
+        &self,
+        export: &AccountExport,
+        user: &User,
+    ) -> Result<(), ServiceError> {
+        let archive = self.build_archive(user, export.include_stats).await?;
+        let archive = serde_json::to_value(&archive).map_err(|e| {
+            error!("Failed to serialize account archive: {}", e);
+            ServiceError::InternalError
+        })?;
+
+        let (token, token_hash) = generate_download_token();
+        let mut conn = self.get_conn().await?;
+        diesel::update(account_exports::table.find(export.id))
+            .set((
+                account_exports::status.eq(ExportStatus::Ready.as_str()),
+                account_exports::archive.eq(Some(archive)),
+                account_exports::token_hash.eq(Some(token_hash)),
+                account_exports::completed_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut conn)
+            .await?;
+
+        // The token only exists in the email, so an unsent export can never be downloaded
+        self.email_service
+            .send_account_export_ready(&user.email, &user.full_name, &token, EXPORT_EXPIRY_DAYS)
+            .await
+            .map_err(|e| {
+                error!("Failed to send account export {}: {}", export.id, e);
+                ServiceError::InternalError
+            })?;
+
+        info!("Account export {} ready for user {}", export.id, user.id);
+        Ok(())
+    }
+
+    async fn mark_failed(&self, export_id: Uuid) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+        diesel::update(account_exports::table.find(export_id))
+            .set((
+                account_exports::status.eq(ExportStatus::Failed.as_str()),
+                account_exports::archive.eq(None::<serde_json::Value>),
+                account_exports::token_hash.eq(None::<String>),
+                account_exports::completed_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Profile and non-deleted links of `user`, plus lifetime click totals when requested
+    pub async fn build_archive(
+        &self,
+        user: &User,
+        include_stats: bool,
+    ) -> Result<AccountArchive, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let owned: Vec<Link> = links::table
+            .filter(links::user_id.eq(user.id))
+            .filter(links::deleted_at.is_null())
+            .order(links::created_at.asc())
+            .load(&mut conn)
+            .await?;
+
+        let click_stats = if include_stats {
+            Some(self.click_stats(&owned).await)
+        } else {
+            None
+        };
+
+        Ok(AccountArchive {
+            version: ACCOUNT_ARCHIVE_VERSION,
+            exported_at: Utc::now(),
+            user: ArchivedUser::from(user),
+            links: owned.iter().map(ArchivedLink::from).collect(),
+            click_stats,
+        })
+    }
+
+    /// ClickHouse totals when enabled, otherwise the links' click counters
+    async fn click_stats(&self, owned: &[Link]) -> Vec<ArchivedClickStats> {
+        let stats = match self.analytics {
+            Some(ref analytics) => {
+                let ids: Vec<Uuid> = owned.iter().map(|link| link.id).collect();
+                Some(analytics.get_bulk_link_stats(&ids).await)
+            },
+            None => None,
+        };
+
+        owned
+            .iter()
+            .map(|link| match stats.as_ref() {
+                Some(stats) => {
+                    let link_stats = stats.get(&link.id);
+                    ArchivedClickStats {
+                        short_code: link.short_code.clone(),
+                        total_clicks: link_stats.map_or(0, |s| s.total_clicks),
+                        unique_visitors: Some(link_stats.map_or(0, |s| s.unique_visitors)),
+                    }
+                },
+                None => ArchivedClickStats {
+                    short_code: link.short_code.clone(),
+                    total_clicks: u64::try_from(link.click_count).unwrap_or(0),
+                    unique_visitors: None,
+                },
+            })
+            .collect()
+    }
+
+    /// Archive for an emailed download token that hasn't expired
+    pub async fn download(&self, token: &str) -> Result<serde_json::Value, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        account_exports::table
+            .filter(account_exports::token_hash.eq(hash_download_token(token)))
+            .filter(account_exports::status.eq(ExportStatus::Ready.as_str()))
+            .filter(account_exports::expires_at.gt(Utc::now()))
+            .select(account_exports::archive)
+            .first::<Option<serde_json::Value>>(&mut conn)
+            .await
+            .optional()?
+            .flatten()
+            .ok_or(ServiceError::NotFound)
+    }
+
+    /// Recreate an account from an archive with new ids
+    /// Taken short codes get a numeric suffix; password-protected links are imported
+    /// inactive because their passwords aren't exported. The user sets a password
+    /// with the forgot-password flow.
+    pub async fn import(
+        &self,
+        archive: AccountArchive,
+        admin_id: Uuid,
+    ) -> Result<AccountImportResponse, ServiceError> {
+        if archive.version != ACCOUNT_ARCHIVE_VERSION {
+            return Err(ServiceError::ValidationError(format!(
+                "Unsupported archive version {} (expected {})",
+                archive.version, ACCOUNT_ARCHIVE_VERSION
+            )));
+        }
+
+        let user = self.import_user(&archive.user).await?;
+        let mut results = vec![ImportEntityResult {
+            entity: "user".to_string(),
+            source: archive.user.email.clone(),
+            outcome: ImportOutcome::Created,
+            id: Some(user.id),
+            short_code: None,
+            message: None,
+        }];
+
+        for link in &archive.links {
+            results.push(self.import_link(&user, link).await);
+        }
+
+        let response = AccountImportResponse::new(user.id, results);
+
+        AuditLogger::log_resource_action(
+            AuditAction::AccountImported,
+            admin_id,
+            "user",
+            Some(user.id.to_string()),
+            Some(format!(
+                "Imported {} with {} links ({} renamed, {} failed)",
+                user.email,
+                response.links_created + response.links_renamed,
+                response.links_renamed,
+                response.links_failed
+            )),
+        )
+        .await;
+
+        Ok(response)
+    }
+
+    async fn import_user(&self, archived: &ArchivedUser) -> Result<User, ServiceError> {
+        let email = archived.email.trim().to_lowercase();
+        let mut conn = self.get_conn().await?;
+
+        let exists = diesel::select(diesel::dsl::exists(
+            users::table.filter(users::email.eq(&email)),
+        ))
+        .get_result::<bool>(&mut conn)
+        .await?;
+        if exists {
+            return Err(ServiceError::Conflict(format!(
+                "A user with email {} already exists",
+                email
+            )));
+        }
+
+        // Nobody knows this password; the user picks one via forgot-password
+        let mut password = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut password);
+        let password_hash =
+            hash_password(&BASE64_URL_SAFE_NO_PAD.encode(password)).map_err(|e| {
+                error!("Failed to hash imported user password: {}", e);
+                ServiceError::InternalError
+            })?;
+
+        let subscription_tier = archived
+            .subscription_tier
+            .parse::<SubscriptionTier>()
+            .unwrap_or(SubscriptionTier::Free);
+
+        let user = diesel::insert_into(users::table)
+            .values(&NewUser {
+                email,
+                password_hash,
+                email_verified: archived.email_verified,
+                subscription_tier: subscription_tier.as_str().to_string(),
+                full_name: archived.full_name.clone(),
+                company_name: archived.company_name.clone(),
+                onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+            })
+            .get_result::<User>(&mut conn)
+            .await?;
+
+        let user = diesel::update(users::table.find(user.id))
+            .set((
+                users::default_tracking_mode.eq(&archived.default_tracking_mode),
+                users::monthly_digest_enabled.eq(archived.monthly_digest_enabled),
+                users::notification_preferences.eq(&archived.notification_preferences),
+                // An archive is user-supplied; a zone we don't know falls back to the default
+                users::timezone.eq(parse_timezone(&archived.timezone)
+                    .map_or(DEFAULT_TIMEZONE, |_| archived.timezone.as_str())),
+                // Same for a strip list that doesn't validate
+                users::strip_query_params
+                    .eq(parse_strip_query_params(&archived.strip_query_params).unwrap_or_default()),
+            ))
+            .get_result::<User>(&mut conn)
+            .await?;
+
+        Ok(user)
+    }
+
+    async fn import_link(&self, user: &User, archived: &ArchivedLink) -> ImportEntityResult {
+        let mut result = ImportEntityResult {
+            entity: "link".to_string(),
+            source: archived.short_code.clone(),
+            outcome: ImportOutcome::Failed,
+            id: None,
+            short_code: None,
+            message: None,
+        };
+
+        match self.insert_imported_link(user, archived).await {
+            Ok(link) => {
+                result.outcome = if link.short_code == archived.short_code {
+                    ImportOutcome::Created
+                } else {
+                    ImportOutcome::Renamed
+                };
+                result.id = Some(link.id);
+                result.short_code = Some(link.short_code);
+                if archived.is_password_protected {
+                    result.message =
+                        Some("Imported inactive: set a new password before activating".to_string());
+                }
+            },
+            Err(e) => {
+                warn!("Failed to import link {}: {}", archived.short_code, e);
+                result.message = Some(e.to_string());
+            },
+        }
+
+        result
+    }
+
+    /// Insert under the first free candidate short code
+    async fn insert_imported_link(
+        &self,
+        user: &User,
+        archived: &ArchivedLink,
+    ) -> Result<Link, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let now = Utc::now();
+
+        // Aliases from older archives may be mixed-case; they are stored lowercase now
+        let code = match archived.custom_alias {
+            Some(_) => CustomAliasValidator::normalize(&archived.short_code),
+            None => archived.short_code.clone(),
+        };
+
+        // Archives from before tags were bounded may hold some the API now rejects; drop those
+        let tags = retain_valid_tags(&archived.tags);
+
+        for short_code in
+            short_code_candidates(&code, MAX_SHORT_CODE_LENGTH, IMPORT_SHORT_CODE_ATTEMPTS)
+        {
+            let reserved = diesel::select(diesel::dsl::exists(
+                reserved_short_codes::table.filter(reserved_short_codes::code.eq(&short_code)),
+            ))
+            .get_result::<bool>(&mut conn)
+            .await?;
+            if reserved {
+                continue;
+            }
+
+            // A sliding window out of bounds leaves the link with a fixed expiry
+            let sliding_window_days = archived
+                .sliding_window_days
+                .filter(|days| (1..=365).contains(days))
+                .filter(|_| archived.expiry_policy == ExpiryPolicy::Sliding);
+            let new_link = NewLink {
+                id: Uuid::new_v4(),
+                user_id: user.id,
+                short_code: short_code.clone(),
+                original_url: archived.original_url.clone(),
+                title: archived.title.clone(),
+                description: archived.description.clone(),
+                tags: (!tags.is_empty()).then(|| tags.iter().cloned().map(Some).collect()),
+                custom_alias: archived.custom_alias.as_ref().map(|_| short_code.clone()),
+                is_active: archived.is_active && !archived.is_password_protected,
+                expires_at: archived.expires_at,
+                password_hash: None,
+                last_accessed_at: None,
+                og_image: archived.og_image.clone(),
+                favicon_url: archived.favicon_url.clone(),
+                processing_status: "completed".to_string(),
+                metadata_extracted_at: None,
+                utm_source: archived.utm_source.clone(),
+                utm_medium: archived.utm_medium.clone(),
+                utm_campaign: archived.utm_campaign.clone(),
+                utm_term: archived.utm_term.clone(),
+                utm_content: archived.utm_content.clone(),
+                deleted_at: None,
+                created_at: archived.created_at,
+                updated_at: now,
+                // The rolling re-scan checks imported links like any other
+                last_scanned_at: None,
+                threat_score: None,
+                risk_level: None,
+                threats_detected: None,
+                scan_warnings: None,
+                tracking_mode: archived.tracking_mode.clone(),
+                organization_id: None,
+                path_passthrough: archived.path_passthrough,
+                // Lists that don't validate are dropped rather than failing the import
+                allowed_referrers: referrer_column(
+                    normalize_referrer_domains("allowed_referrers", &archived.allowed_referrers)
+                        .unwrap_or_default(),
+                ),
+                blocked_referrers: referrer_column(
+                    normalize_referrer_domains("blocked_referrers", &archived.blocked_referrers)
+                        .unwrap_or_default(),
+                ),
+                referrer_strict: archived.referrer_strict,
+                expiry_policy: if sliding_window_days.is_some() {
+                    ExpiryPolicy::Sliding
+                } else {
+                    ExpiryPolicy::Fixed
+                }
+                .as_str()
+                .to_string(),
+                sliding_window_days,
+            };
+
+            let inserted = diesel::insert_into(links::table)
+                .values(&new_link)
+                .get_result::<Link>(&mut conn)
+                .await;
+
+            match inserted {
+                Ok(link) => {
+                    if archived.notes.is_none() {
+                        return Ok(link);
+                    }
+                    return Ok(diesel::update(links::table.find(link.id))
+                        .set(links::notes.eq(&archived.notes))
+                        .get_result::<Link>(&mut conn)
+                        .await?);
+                },
+                Err(diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                )) => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+
+        Err(ServiceError::Conflict(format!(
+            "No free short code found for {}",
+            archived.short_code
+        )))
+    }
+
+    /// Delete exports whose download link has expired; returns how many were removed
+    pub async fn prune_expired(&self) -> Result<usize, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let deleted = diesel::delete(
+            account_exports::table.filter(account_exports::expires_at.lt(Utc::now())),
+        )
+        .execute(&mut conn)
+        .await?;
+
+        Ok(deleted)
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Periodically delete expired exports (archives are only kept for EXPORT_EXPIRY_DAYS)
+pub fn spawn_account_export_cleanup_task(state: AppState) {
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("account_export_cleanup", redis_pool, move |reporter| {
+        let service = AccountExportService::new(&state);
+        async move {
+            let mut interval = tokio::time::interval(EXPORT_CLEANUP_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                match service.prune_expired().await {
+                    Ok(deleted) => {
+                        if deleted > 0 {
+                            info!("Deleted {} expired account exports", deleted);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Account export cleanup failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+/// Random download token (sent by email) and its SHA-256 hash (stored)
+fn generate_download_token() -> (String, String) {
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    let token = BASE64_URL_SAFE_NO_PAD.encode(token_bytes);
+    let token_hash = hash_download_token(&token);
+    (token, token_hash)
+}
+
+fn hash_download_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_token_hash() {
+        let (token, token_hash) = generate_download_token();
+        assert_eq!(token.len(), 43);
+        assert_eq!(token_hash.len(), 64);
+        assert_eq!(hash_download_token(&token), token_hash);
+        assert_ne!(generate_download_token().0, token);
+    }
+}
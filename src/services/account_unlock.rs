@@ -0,0 +1,161 @@
+// Self-service unlock for accounts locked after repeated failed logins
+// The lockout email carries a random token; Redis keeps only its hash, and redeeming it
+// deletes it, so each link works once and expires with the configured TTL
+
+use base64::prelude::*;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState, db::RedisPool, models::user::User, services::email::EmailService,
+    utils::service_error::ServiceError,
+};
+
+/// Who an unlock token was issued for, stored as JSON under the token hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnlockGrant {
+    pub user_id: Uuid,
+    /// Normalized login email the lockout and failure counters are keyed by
+    pub email: String,
+}
+
+pub struct AccountUnlockService {
+    redis_pool: RedisPool,
+    email_service: Arc<EmailService>,
+    token_ttl_seconds: u64,
+}
+
+impl AccountUnlockService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            redis_pool: state.redis_pool.clone(),
+            email_service: state.email_service.clone(),
+            token_ttl_seconds: state.config.security.account_unlock_token_ttl_seconds,
+        }
+    }
+
+    /// Email `user` an unlock link for the lockout on `email`
+    /// Only the first caller per lockout sends, so concurrent failures don't duplicate the email
+    pub async fn notify_locked(
+        &self,
+        user: &User,
+        email: &str,
+        lockout_seconds: u64,
+    ) -> Result<(), ServiceError> {
+        let first = self
+            .redis_pool
+            .set_nx_with_expiry(
+                &notified_key(email),
+                user.id.to_string(),
+                lockout_seconds.max(1) as usize,
+            )
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+        if !first {
+            return Ok(());
+        }
+
+        let (token, token_hash) = generate_unlock_token();
+        let grant = UnlockGrant {
+            user_id: user.id,
+            email: email.to_string(),
+        };
+        let value = serde_json::to_string(&grant).map_err(|e| {
+            error!("Failed to serialize unlock grant: {}", e);
+            ServiceError::InternalError
+        })?;
+        self.redis_pool
+            .set_with_expiry(
+                &token_key(&token_hash),
+                value,
+                self.token_ttl_seconds as usize,
+            )
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+        self.email_service
+            .send_account_locked(
+                &user.email,
+                &user.full_name,
+                &token,
+                lockout_seconds,
+                self.token_ttl_seconds,
+            )
+            .await
+            .map_err(|e| {
+                error!("Failed to send unlock email to user {}: {}", user.id, e);
+                ServiceError::InternalError
+            })
+    }
+
+    /// Consume an unlock token; `None` when it is unknown, expired or already used
+    pub async fn redeem(&self, token: &str) -> Result<Option<UnlockGrant>, ServiceError> {
+        let value = self
+            .redis_pool
+            .get_del(&token_key(&hash_unlock_token(token)))
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+        let Some(value) = value else {
+            return Ok(None);
+        };
+
+        match serde_json::from_str::<UnlockGrant>(&value) {
+            Ok(grant) => {
+                // A later lockout may send a fresh link
+                if let Err(e) = self.redis_pool.del(&notified_key(&grant.email)).await {
+                    warn!(
+                        "Failed to reset unlock notification for {}: {}",
+                        grant.email, e
+                    );
+                }
+                Ok(Some(grant))
+            },
+            Err(e) => {
+                warn!("Discarding malformed unlock grant: {}", e);
+                Ok(None)
+            },
+        }
+    }
+}
+
+fn token_key(token_hash: &str) -> String {
+    format!("account_unlock:token:{}", token_hash)
+}
+
+fn notified_key(email: &str) -> String {
+    format!("account_unlock:notified:{}", email)
+}
+
+/// Random URL-safe token for the email and the hex SHA-256 that is stored
+fn generate_unlock_token() -> (String, String) {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let token = BASE64_URL_SAFE_NO_PAD.encode(bytes);
+    let hash = hash_unlock_token(&token);
+    (token, hash)
+}
+
+fn hash_unlock_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_token_hash() {
+        let (token, hash) = generate_unlock_token();
+        assert_eq!(token.len(), 43);
+        assert_eq!(hash, hash_unlock_token(&token));
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash_unlock_token("other-token"), hash);
+    }
+}
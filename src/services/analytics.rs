@@ -1,19 +1,41 @@
 // Analytics and Monitoring Service for Rate Limiting
 // DEV-115: Monitoring and metrics collection for rate limiting middleware
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, DurationRound, Utc};
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
     sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc, RwLock,
+        atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
     },
 };
 use thiserror::Error;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
-use crate::db::RedisPool;
+use crate::{
+    app::AppState,
+    db::RedisPool,
+    models::analytics::{classify_offender_key, OffenderKind, OFFENDERS_MAX_WINDOW_HOURS},
+    services::background_tasks::TASK_REGISTRY,
+};
+
+/// Hourly Redis hashes of blocked counts, field `{endpoint}\x1f{key}`
+const OFFENDER_BUCKET_PREFIX: &str = "analytics:rate_limit:offenders:";
+/// Separates endpoint and key in offender bucket fields
+const OFFENDER_FIELD_SEPARATOR: char = '\u{1f}';
+/// Buckets outlive the longest window by a day so exports can catch up
+const OFFENDER_BUCKET_TTL_SECONDS: i64 = (OFFENDERS_MAX_WINDOW_HOURS as i64 + 24) * 3600;
+/// Offenders exported to ClickHouse per endpoint and hour
+const OFFENDERS_EXPORTED_PER_ENDPOINT: usize = 100;
+/// Completed hours re-exported on every run; rows replace earlier exports of the same hour
+const OFFENDER_EXPORT_LOOKBACK_HOURS: i64 = 3;
+/// Table hourly offender counts are exported to
+pub const RATE_LIMIT_OFFENDERS_TABLE: &str = "rate_limit_offenders";
+/// Table sampled events are flushed to
+pub const RATE_LIMIT_EVENTS_TABLE: &str = "rate_limit_events";
+/// Sampled events waiting for the next flush; further samples are dropped
+const MAX_PENDING_SAMPLES: usize = 10_000;
 
 // =============================================================================
 // ERROR TYPES
@@ -99,6 +121,19 @@ pub struct RateLimitMetrics {
     /// Time window for these metrics
     pub window_start: DateTime<Utc>,
     pub window_end: DateTime<Utc>,
+
+    /// Where the numbers came from
+    pub backend: MetricsBackend,
+}
+
+/// Source of aggregated rate limiting metrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MetricsBackend {
+    /// This instance's counters since it started or was reset
+    Memory,
+    /// Sampled events of every instance, scaled up by their sample weight
+    Clickhouse,
 }
 
 /// Metrics for a specific endpoint
@@ -111,6 +146,23 @@ pub struct EndpointMetrics {
     pub block_rate: f64, // Percentage of requests blocked
 }
 
+/// A rate limit key that was blocked on an endpoint
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitOffender {
+    pub key: String,
+    pub blocked_requests: u64,
+}
+
+/// Blocked counts of one hour, as exported to ClickHouse
+#[derive(Debug, Clone, PartialEq)]
+pub struct HourlyOffenderRow {
+    pub hour: DateTime<Utc>,
+    pub endpoint: String,
+    pub key: String,
+    pub kind: OffenderKind,
+    pub blocked_requests: u64,
+}
+
 /// Metrics for a specific subscription tier
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TierMetrics {
@@ -178,12 +230,23 @@ pub struct RateLimitAnalytics {
     endpoint_counters: Arc<RwLock<HashMap<String, (u64, u64)>>>, // (total, blocked)
     tier_counters: Arc<RwLock<HashMap<String, (u64, u64)>>>,     // (total, blocked)
 
+    // Blocked counts per rate limit key, grouped by endpoint
+    offender_counters: Arc<RwLock<HashMap<String, HashMap<String, u64>>>>,
+
     // Configuration
     max_samples: usize,
+    max_offenders_per_endpoint: usize,
 
     // Sampling optimization - counter-based sampling
     sample_counter: AtomicU64,
     sample_interval: u64, // Sample every N events instead of RNG on each
+
+    // Sampled events waiting to be flushed to ClickHouse, once an export task drains them
+    pending_samples: Mutex<Vec<RateLimitEvent>>,
+    sample_export_enabled: AtomicBool,
+
+    // When the in-memory counters started (Unix millis)
+    counters_since_ms: AtomicI64,
 }
 
 impl RateLimitAnalytics {
@@ -204,9 +267,14 @@ impl RateLimitAnalytics {
             latency_samples: Arc::new(RwLock::new(Vec::new())),
             endpoint_counters: Arc::new(RwLock::new(HashMap::new())),
             tier_counters: Arc::new(RwLock::new(HashMap::new())),
+            offender_counters: Arc::new(RwLock::new(HashMap::new())),
             max_samples: 10000, // Keep last 10k latency samples
+            max_offenders_per_endpoint: 1000, // Bounds memory under key-spraying attacks
             sample_counter: AtomicU64::new(0),
             sample_interval,
+            pending_samples: Mutex::new(Vec::new()),
+            sample_export_enabled: AtomicBool::new(false),
+            counters_since_ms: AtomicI64::new(Utc::now().timestamp_millis()),
         }
     }
 
@@ -254,6 +322,24 @@ impl RateLimitAnalytics {
             }
         }
 
+        // Update offender counters
+        if event.blocked {
+            match self.offender_counters.write() {
+                Ok(mut counters) => {
+                    let keys = counters.entry(event.endpoint.clone()).or_default();
+                    record_offender(keys, &event.key, self.max_offenders_per_endpoint);
+                },
+                Err(e) => {
+                    error!("Failed to acquire write lock for offender counters: {}", e);
+                },
+            }
+        }
+
+        // Count the block in this hour's shared bucket for windowed offender reports
+        if event.blocked {
+            self.store_offender_hit(&event).await?;
+        }
+
         // Update tier counters
         if let Some(ref tier) = event.user_tier {
             match self.tier_counters.write() {
@@ -273,6 +359,7 @@ impl RateLimitAnalytics {
 
         // Sample events for detailed storage (respects sample rate)
         if self.should_sample() {
+            self.queue_sample_for_export(&event);
             self.store_event_sample(&event).await?;
         }
 
@@ -368,9 +455,110 @@ impl RateLimitAnalytics {
             tier_metrics,
             window_start,
             window_end,
+            backend: MetricsBackend::Memory,
         })
     }
 
+    /// Start of the in-memory counters; older windows need the ClickHouse export
+    pub fn counters_since(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.counters_since_ms.load(Ordering::Acquire))
+            .unwrap_or_else(Utc::now)
+    }
+
+    /// Checks each sampled event stands for
+    pub fn sample_weight(&self) -> u32 {
+        u32::try_from(self.sample_interval).unwrap_or(u32::MAX)
+    }
+
+    /// Keep sampled events for `drain_samples`; until called, samples only go to Redis
+    pub fn enable_sample_export(&self) {
+        self.sample_export_enabled.store(true, Ordering::Release);
+    }
+
+    /// Whether sampled events are being exported to ClickHouse
+    pub fn sample_export_enabled(&self) -> bool {
+        self.sample_export_enabled.load(Ordering::Acquire)
+    }
+
+    /// Take the sampled events queued since the last call
+    pub fn drain_samples(&self) -> Vec<RateLimitEvent> {
+        match self.pending_samples.lock() {
+            Ok(mut pending) => std::mem::take(&mut *pending),
+            Err(e) => {
+                error!("Failed to acquire lock for pending samples: {}", e);
+                Vec::new()
+            },
+        }
+    }
+
+    /// Most-blocked rate limit keys per endpoint, highest first
+    pub fn top_offenders(&self, limit: usize) -> HashMap<String, Vec<RateLimitOffender>> {
+        match self.offender_counters.read() {
+            Ok(counters) => counters
+                .iter()
+                .map(|(endpoint, keys)| (endpoint.clone(), rank_offenders(keys, limit)))
+                .collect(),
+            Err(e) => {
+                error!("Failed to acquire read lock for offender counters: {}", e);
+                HashMap::new()
+            },
+        }
+    }
+
+    /// Most-blocked rate limit keys per endpoint over the last `window_hours` hours (including
+    /// the current one), highest first. Counts come from the hourly buckets shared by every
+    /// replica, so they cover the whole deployment.
+    pub async fn top_offenders_in_window(
+        &self,
+        window_hours: u32,
+        limit: usize,
+    ) -> Result<HashMap<String, Vec<RateLimitOffender>>, AnalyticsError> {
+        let window_start = offender_window_start(window_hours);
+        let hours: Vec<DateTime<Utc>> = (0..window_hours.clamp(1, OFFENDERS_MAX_WINDOW_HOURS))
+            .map(|i| window_start + chrono::Duration::hours(i64::from(i)))
+            .collect();
+
+        let mut merged: HashMap<String, HashMap<String, u64>> = HashMap::new();
+        for bucket in self.read_offender_buckets(&hours).await? {
+            for (endpoint, keys) in bucket {
+                let totals = merged.entry(endpoint).or_default();
+                for (key, count) in keys {
+                    *totals.entry(key).or_default() += count;
+                }
+            }
+        }
+
+        Ok(merged
+            .iter()
+            .map(|(endpoint, keys)| (endpoint.clone(), rank_offenders(keys, limit)))
+            .collect())
+    }
+
+    /// Top offenders per endpoint for each completed hour in the export lookback
+    pub async fn hourly_offender_rows(&self) -> Result<Vec<HourlyOffenderRow>, AnalyticsError> {
+        let current_hour = current_hour();
+        let hours: Vec<DateTime<Utc>> = (1..=OFFENDER_EXPORT_LOOKBACK_HOURS)
+            .map(|i| current_hour - chrono::Duration::hours(i))
+            .collect();
+
+        let buckets = self.read_offender_buckets(&hours).await?;
+        let mut rows = Vec::new();
+        for (hour, bucket) in hours.iter().zip(buckets) {
+            for (endpoint, keys) in bucket {
+                for offender in rank_offenders(&keys, OFFENDERS_EXPORTED_PER_ENDPOINT) {
+                    rows.push(HourlyOffenderRow {
+                        hour: *hour,
+                        endpoint: endpoint.clone(),
+                        kind: classify_offender_key(&offender.key).0,
+                        key: offender.key,
+                        blocked_requests: offender.blocked_requests,
+                    });
+                }
+            }
+        }
+        Ok(rows)
+    }
+
     /// Get real-time monitoring statistics
     pub async fn get_monitoring_stats(&self) -> Result<MonitoringStats, AnalyticsError> {
         // Calculate current RPS (requests in last minute)
@@ -413,6 +601,18 @@ impl RateLimitAnalytics {
         })
     }
 
+    /// Queue a sampled event for the ClickHouse export, dropping it when the queue is full
+    fn queue_sample_for_export(&self, event: &RateLimitEvent) {
+        if !self.sample_export_enabled() {
+            return;
+        }
+        match self.pending_samples.lock() {
+            Ok(mut pending) if pending.len() < MAX_PENDING_SAMPLES => pending.push(event.clone()),
+            Ok(_) => debug!("Dropping rate limit sample, export queue full"),
+            Err(e) => error!("Failed to acquire lock for pending samples: {}", e),
+        }
+    }
+
     /// Store event sample in Redis for detailed analysis
     async fn store_event_sample(&self, event: &RateLimitEvent) -> Result<(), AnalyticsError> {
         let mut conn = self.redis_pool.get_connection().await?;
@@ -439,6 +639,52 @@ impl RateLimitAnalytics {
         Ok(())
     }
 
+    /// Count a blocked request in the current hour's offender bucket
+    async fn store_offender_hit(&self, event: &RateLimitEvent) -> Result<(), AnalyticsError> {
+        let mut conn = self.redis_pool.get_connection().await?;
+
+        let bucket = offender_bucket_key(event.timestamp);
+        redis::pipe()
+            .hincr(&bucket, offender_field(&event.endpoint, &event.key), 1)
+            .ignore()
+            .expire(&bucket, OFFENDER_BUCKET_TTL_SECONDS)
+            .ignore()
+            .query_async::<()>(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Blocked counts per endpoint and key for each of `hours`, in the same order
+    async fn read_offender_buckets(
+        &self,
+        hours: &[DateTime<Utc>],
+    ) -> Result<Vec<HashMap<String, HashMap<String, u64>>>, AnalyticsError> {
+        let mut conn = self.redis_pool.get_connection().await?;
+
+        let mut pipe = redis::pipe();
+        for hour in hours {
+            pipe.hgetall(offender_bucket_key(*hour));
+        }
+        let buckets: Vec<HashMap<String, u64>> = pipe.query_async(&mut conn).await?;
+
+        Ok(buckets
+            .into_iter()
+            .map(|fields| {
+                let mut by_endpoint: HashMap<String, HashMap<String, u64>> = HashMap::new();
+                for (field, count) in fields {
+                    if let Some((endpoint, key)) = field.split_once(OFFENDER_FIELD_SEPARATOR) {
+                        by_endpoint
+                            .entry(endpoint.to_string())
+                            .or_default()
+                            .insert(key.to_string(), count);
+                    }
+                }
+                by_endpoint
+            })
+            .collect())
+    }
+
     /// Check if we should sample this event
     fn should_sample(&self) -> bool {
         // Use counter-based sampling for better performance at high load
@@ -564,6 +810,8 @@ impl RateLimitAnalytics {
         self.total_checks.store(0, Ordering::Release);
         self.total_blocked.store(0, Ordering::Release);
         self.sample_counter.store(0, Ordering::Release);
+        self.counters_since_ms
+            .store(Utc::now().timestamp_millis(), Ordering::Release);
 
         match self.latency_samples.write() {
             Ok(mut samples) => samples.clear(),
@@ -589,10 +837,191 @@ impl RateLimitAnalytics {
             ),
         }
 
+        match self.offender_counters.write() {
+            Ok(mut counters) => counters.clear(),
+            Err(e) => error!(
+                "Failed to acquire write lock for offender counters during reset: {}",
+                e
+            ),
+        }
+
         info!("Rate limiting analytics counters reset");
     }
 }
 
+/// Start of the current UTC hour
+fn current_hour() -> DateTime<Utc> {
+    let now = Utc::now();
+    now.duration_trunc(chrono::Duration::hours(1))
+        .unwrap_or(now)
+}
+
+/// Start of the oldest hourly bucket in a window of `window_hours` ending now
+pub fn offender_window_start(window_hours: u32) -> DateTime<Utc> {
+    let hours = window_hours.clamp(1, OFFENDERS_MAX_WINDOW_HOURS);
+    current_hour() - chrono::Duration::hours(i64::from(hours - 1))
+}
+
+fn offender_bucket_key(timestamp: DateTime<Utc>) -> String {
+    format!("{}{}", OFFENDER_BUCKET_PREFIX, timestamp.format("%Y%m%d%H"))
+}
+
+fn offender_field(endpoint: &str, key: &str) -> String {
+    format!("{}{}{}", endpoint, OFFENDER_FIELD_SEPARATOR, key)
+}
+
+/// Export the last completed hours of offender counts to ClickHouse for long-term trends
+pub fn spawn_offender_export_task(state: AppState) {
+    let Some(clickhouse) = state.clickhouse_analytics.clone() else {
+        info!("ClickHouse not configured, rate limit offender export disabled");
+        return;
+    };
+    if state.rate_limit_service.analytics().is_none() {
+        info!("Rate limit analytics disabled, offender export disabled");
+        return;
+    }
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("rate_limit_offender_export", redis_pool, move |reporter| {
+        let clickhouse = Arc::clone(&clickhouse);
+        let rate_limit_service = Arc::clone(&state.rate_limit_service);
+        async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(15 * 60));
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                let Some(analytics) = rate_limit_service.analytics() else {
+                    continue;
+                };
+
+                let rows = match analytics.hourly_offender_rows().await {
+                    Ok(rows) => rows,
+                    Err(e) => {
+                        error!("Failed to read rate limit offenders: {}", e);
+                        reporter.failure(e);
+                        continue;
+                    },
+                };
+
+                match crate::db::clickhouse_insert_builder::insert_rate_limit_offenders(
+                    clickhouse.client().client(),
+                    RATE_LIMIT_OFFENDERS_TABLE,
+                    &rows,
+                )
+                .await
+                {
+                    Ok(()) => reporter.success(),
+                    Err(e) => {
+                        error!(
+                            "Failed to export {} rate limit offender rows: {}",
+                            rows.len(),
+                            e
+                        );
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+/// Write this instance's sampled events to ClickHouse every `flush_interval`, so metrics outlive
+/// restarts and cover every replica. Not exclusive: each instance flushes its own samples.
+pub fn spawn_event_export_task(state: AppState) {
+    let Some(clickhouse) = state.clickhouse_analytics.clone() else {
+        info!("ClickHouse not configured, rate limit event export disabled");
+        return;
+    };
+    let flush_interval = state.config.rate_limit_analytics_flush_interval_secs;
+    if flush_interval == 0 || state.config.rate_limit_analytics_sample_rate <= 0.0 {
+        info!("Rate limit sampling or flushing disabled, rate limit event export disabled");
+        return;
+    }
+    let Some(analytics) = state.rate_limit_service.analytics() else {
+        info!("Rate limit analytics disabled, rate limit event export disabled");
+        return;
+    };
+    analytics.enable_sample_export();
+
+    TASK_REGISTRY.spawn("rate_limit_event_export", move |reporter| {
+        let clickhouse = Arc::clone(&clickhouse);
+        let rate_limit_service = Arc::clone(&state.rate_limit_service);
+        async move {
+            let mut interval =
+                tokio::time::interval(std::time::Duration::from_secs(flush_interval));
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+                let Some(analytics) = rate_limit_service.analytics() else {
+                    continue;
+                };
+
+                let events = analytics.drain_samples();
+                // Dropped on failure: the in-memory counters still have them
+                match crate::db::clickhouse_insert_builder::insert_rate_limit_events(
+                    clickhouse.client().client(),
+                    RATE_LIMIT_EVENTS_TABLE,
+                    &events,
+                    analytics.sample_weight(),
+                )
+                .await
+                {
+                    Ok(()) => reporter.success(),
+                    Err(e) => {
+                        error!("Failed to export {} rate limit events: {}", events.len(), e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+    info!(
+        "Rate limit event export started (every {}s)",
+        flush_interval
+    );
+}
+
+/// Count a blocked request for `key`, evicting the least-blocked key once `max_keys` are tracked
+fn record_offender(keys: &mut HashMap<String, u64>, key: &str, max_keys: usize) {
+    if let Some(count) = keys.get_mut(key) {
+        *count += 1;
+        return;
+    }
+
+    if keys.len() >= max_keys {
+        let least_blocked = keys
+            .iter()
+            .min_by_key(|(_, count)| **count)
+            .map(|(key, _)| key.clone());
+        if let Some(evicted) = least_blocked {
+            keys.remove(&evicted);
+        }
+    }
+    keys.insert(key.to_string(), 1);
+}
+
+/// Keys sorted by blocked count (ties broken by key for stable output), truncated to `limit`
+fn rank_offenders(keys: &HashMap<String, u64>, limit: usize) -> Vec<RateLimitOffender> {
+    let mut ranked: Vec<RateLimitOffender> = keys
+        .iter()
+        .map(|(key, count)| RateLimitOffender {
+            key: key.clone(),
+            blocked_requests: *count,
+        })
+        .collect();
+    ranked.sort_by(|a, b| {
+        b.blocked_requests
+            .cmp(&a.blocked_requests)
+            .then_with(|| a.key.cmp(&b.key))
+    });
+    ranked.truncate(limit);
+    ranked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -654,6 +1083,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_offenders_are_ranked_and_bounded() {
+        let mut keys = HashMap::new();
+        for key in ["ip:1", "ip:2", "ip:2", "ip:3", "ip:3", "ip:3"] {
+            record_offender(&mut keys, key, 3);
+        }
+
+        // A new key at capacity evicts the least-blocked one
+        record_offender(&mut keys, "ip:4", 3);
+        assert_eq!(keys.len(), 3);
+        assert!(!keys.contains_key("ip:1"));
+
+        assert_eq!(
+            rank_offenders(&keys, 2),
+            vec![
+                RateLimitOffender {
+                    key: "ip:3".to_string(),
+                    blocked_requests: 3,
+                },
+                RateLimitOffender {
+                    key: "ip:2".to_string(),
+                    blocked_requests: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_offender_field_round_trips_keys_with_colons() {
+        let field = offender_field("/api/links", "login:ip:2001:db8::1");
+        assert_eq!(
+            field.split_once(OFFENDER_FIELD_SEPARATOR),
+            Some(("/api/links", "login:ip:2001:db8::1"))
+        );
+    }
+
     #[test]
     fn test_event_creation() {
         let event = create_test_event(true, 3);
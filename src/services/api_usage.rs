@@ -0,0 +1,452 @@
+// Per-user API usage counting
+// auth_middleware records every authenticated request in memory; a background task flushes
+// the totals once a second to `api_usage:{user_id}:{YYYY-MM-DD}` hashes in Redis with a
+// single pipeline (HINCRBY + EXPIRE), and the rollup task moves completed days into
+// `api_usage_daily`. GET /v1/usage reads both, so today's requests show up right away.
+
+use chrono::{Duration as ChronoDuration, NaiveDate, Utc};
+use diesel::{prelude::*, upsert::excluded};
+use diesel_async::RunQueryDsl;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::api_usage::{ApiUsageDay, ApiUsageResponse, USAGE_WINDOW_DAYS},
+    schema::api_usage_daily,
+    services::{
+        background_tasks::TASK_REGISTRY,
+        pending_counter::{scan_keys, CounterSpec, PendingCounter},
+    },
+    utils::service_error::ServiceError,
+};
+
+/// How often pending counts are written to Redis
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the rollup task looks for completed days; hourly, so a missed night is
+/// caught up soon after
+const ROLLUP_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Daily usage hashes outlive their day by a week, covering a rollup outage
+const USAGE_KEY_TTL_SECONDS: i64 = 8 * 86400;
+
+const USAGE_KEY_PREFIX: &str = "api_usage:";
+
+/// Hash field prefixes for request and 429 counts of an endpoint
+const REQUESTS_FIELD: &str = "r:";
+const RATE_LIMITED_FIELD: &str = "l:";
+
+/// Keys requested per SCAN call and drained per pipeline
+const ROLLUP_BATCH_SIZE: usize = 500;
+
+/// Process-wide counter fed by auth_middleware
+pub static API_USAGE: Lazy<ApiUsageCounter> = Lazy::new(ApiUsageCounter::new);
+
+/// Redis hash holding a user's not-yet-rolled-up counts for one day
+pub fn usage_key(user_id: Uuid, day: NaiveDate) -> String {
+    format!("{}{}:{}", USAGE_KEY_PREFIX, user_id, day.format("%Y-%m-%d"))
+}
+
+/// User and day a usage key belongs to
+fn parse_usage_key(key: &str) -> Option<(Uuid, NaiveDate)> {
+    let (user_id, day) = key.strip_prefix(USAGE_KEY_PREFIX)?.split_once(':')?;
+    Some((
+        Uuid::parse_str(user_id).ok()?,
+        NaiveDate::parse_from_str(day, "%Y-%m-%d").ok()?,
+    ))
+}
+
+/// Per-endpoint rows from a usage hash; unknown fields are skipped
+fn rows_from_hash(user_id: Uuid, day: NaiveDate, fields: HashMap<String, i64>) -> Vec<ApiUsageDay> {
+    let mut rows: HashMap<String, ApiUsageDay> = HashMap::new();
+    for (field, count) in fields {
+        let (endpoint, rate_limited) = if let Some(endpoint) = field.strip_prefix(REQUESTS_FIELD) {
+            (endpoint, false)
+        } else if let Some(endpoint) = field.strip_prefix(RATE_LIMITED_FIELD) {
+            (endpoint, true)
+        } else {
+            continue;
+        };
+        let row = rows
+            .entry(endpoint.to_string())
+            .or_insert_with(|| ApiUsageDay {
+                user_id,
+                day,
+                endpoint: endpoint.to_string(),
+                requests: 0,
+                rate_limited: 0,
+            });
+        if rate_limited {
+            row.rate_limited += count;
+        } else {
+            row.requests += count;
+        }
+    }
+    rows.into_values().collect()
+}
+
+/// Requests and 429 responses per user, day and endpoint
+pub struct UsageCounts;
+
+impl CounterSpec for UsageCounts {
+    type Key = (Uuid, NaiveDate, String);
+    type Count = (i64, i64);
+
+    const NAME: &'static str = "API usage";
+
+    fn merge(count: &mut (i64, i64), other: (i64, i64)) {
+        count.0 += other.0;
+        count.1 += other.1;
+    }
+
+    fn queue(
+        pipe: &mut redis::Pipeline,
+        (user_id, day, endpoint): &Self::Key,
+        (requests, rate_limited): &Self::Count,
+    ) {
+        queue_usage(pipe, *user_id, *day, endpoint, *requests, *rate_limited);
+    }
+}
+
+/// In-memory request counts waiting for the next flush
+pub struct ApiUsageCounter {
+    pending: PendingCounter<UsageCounts>,
+}
+
+impl Default for ApiUsageCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApiUsageCounter {
+    pub fn new() -> Self {
+        Self {
+            pending: PendingCounter::new(),
+        }
+    }
+
+    /// Count one request under today's UTC date; never blocks on I/O
+    pub fn record(&self, user_id: Uuid, endpoint: &str, rate_limited: bool) {
+        self.pending.add_owned(
+            (user_id, Utc::now().date_naive(), endpoint.to_string()),
+            (1, i64::from(rate_limited)),
+        );
+    }
+
+    /// Write pending counts to Redis in one pipeline; on failure they are kept for the
+    /// next flush
+    pub async fn flush(&self, redis_pool: &RedisPool) -> Result<usize, redis::RedisError> {
+        self.pending
+            .flush(redis_pool, 0)
+            .await
+            .map(|written| written.len())
+    }
+
+    /// Final flush when the server stops
+    pub async fn flush_on_shutdown(&self, redis_pool: &RedisPool) {
+        if let Err(e) = self.flush(redis_pool).await {
+            error!(
+                "Shutting down with API usage counts that could not be written: {}",
+                e
+            );
+        }
+    }
+}
+
+/// Queue the commands adding counts to a user's daily usage hash
+fn queue_usage(
+    pipe: &mut redis::Pipeline,
+    user_id: Uuid,
+    day: NaiveDate,
+    endpoint: &str,
+    requests: i64,
+    rate_limited: i64,
+) {
+    let key = usage_key(user_id, day);
+    pipe.hincr(&key, format!("{}{}", REQUESTS_FIELD, endpoint), requests)
+        .ignore();
+    if rate_limited > 0 {
+        pipe.hincr(
+            &key,
+            format!("{}{}", RATE_LIMITED_FIELD, endpoint),
+            rate_limited,
+        )
+        .ignore();
+    }
+    pipe.expire(&key, USAGE_KEY_TTL_SECONDS).ignore();
+}
+
+/// Add rows to the daily usage hashes
+async fn write_rows(redis_pool: &RedisPool, rows: &[ApiUsageDay]) -> Result<(), redis::RedisError> {
+    let mut conn = redis_pool.get_connection().await?;
+
+    let mut pipe = redis::pipe();
+    for row in rows {
+        queue_usage(
+            &mut pipe,
+            row.user_id,
+            row.day,
+            &row.endpoint,
+            row.requests,
+            row.rate_limited,
+        );
+    }
+    pipe.query_async::<()>(&mut conn).await
+}
+
+pub struct ApiUsageService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+}
+
+impl ApiUsageService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            redis_pool: state.redis_pool.clone(),
+        }
+    }
+
+    /// A user's usage for the `USAGE_WINDOW_DAYS` days ending `today`: rolled-up days from
+    /// Postgres plus whatever is still in Redis
+    pub async fn user_usage(
+        &self,
+        user_id: Uuid,
+        today: NaiveDate,
+    ) -> Result<ApiUsageResponse, ServiceError> {
+        let from = today - ChronoDuration::days(USAGE_WINDOW_DAYS - 1);
+        let days: Vec<NaiveDate> = (0..USAGE_WINDOW_DAYS)
+            .map(|offset| from + ChronoDuration::days(offset))
+            .collect();
+
+        let mut redis_conn = self.redis_pool.get_connection().await?;
+        let mut pipe = redis::pipe();
+        for day in &days {
+            pipe.hgetall(usage_key(user_id, *day));
+        }
+        let hashes: Vec<HashMap<String, i64>> = pipe.query_async(&mut redis_conn).await?;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+        let mut rows = api_usage_daily::table
+            .filter(api_usage_daily::user_id.eq(user_id))
+            .filter(api_usage_daily::day.between(from, today))
+            .select(ApiUsageDay::as_select())
+            .load(&mut conn)
+            .await?;
+
+        for (day, fields) in days.into_iter().zip(hashes) {
+            rows.extend(rows_from_hash(user_id, day, fields));
+        }
+
+        Ok(ApiUsageResponse::from_counts(today, rows))
+    }
+
+    /// Move the usage hashes of days before `today` into `api_usage_daily`; returns how
+    /// many rows were written. Each hash is read and deleted in one MULTI, and rows the
+    /// database rejected are added back to Redis for the next run.
+    pub async fn rollup_completed_days(&self, today: NaiveDate) -> Result<usize, ServiceError> {
+        let keys = self.completed_day_keys(today).await?;
+        let mut written = 0;
+
+        for batch in keys.chunks(ROLLUP_BATCH_SIZE) {
+            let mut redis_conn = self.redis_pool.get_connection().await?;
+            let mut pipe = redis::pipe();
+            pipe.atomic();
+            for (key, _, _) in batch {
+                pipe.hgetall(key).del(key).ignore();
+            }
+            let hashes: Vec<HashMap<String, i64>> = pipe.query_async(&mut redis_conn).await?;
+
+            let rows: Vec<ApiUsageDay> = batch
+                .iter()
+                .zip(hashes)
+                .flat_map(|((_, user_id, day), fields)| rows_from_hash(*user_id, *day, fields))
+                .collect();
+            if rows.is_empty() {
+                continue;
+            }
+
+            match self.upsert(&rows).await {
+                Ok(count) => written += count,
+                Err(e) => {
+                    if let Err(requeue_error) = write_rows(&self.redis_pool, &rows).await {
+                        error!(
+                            "Lost {} API usage rows that could not be stored or put back: {}",
+                            rows.len(),
+                            requeue_error
+                        );
+                    }
+                    return Err(e);
+                },
+            }
+        }
+
+        Ok(written)
+    }
+
+    /// Usage keys of days before `today`
+    async fn completed_day_keys(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<(String, Uuid, NaiveDate)>, ServiceError> {
+        let keys = scan_keys(&self.redis_pool, USAGE_KEY_PREFIX, ROLLUP_BATCH_SIZE).await?;
+
+        Ok(keys
+            .into_iter()
+            .filter_map(|key| match parse_usage_key(&key) {
+                Some((user_id, day)) if day < today => Some((key, user_id, day)),
+                Some(_) => None,
+                None => {
+                    warn!("Skipping malformed API usage key {}", key);
+                    None
+                },
+            })
+            .collect())
+    }
+
+    /// Add rows to the stored daily counts
+    async fn upsert(&self, rows: &[ApiUsageDay]) -> Result<usize, ServiceError> {
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let written = diesel::insert_into(api_usage_daily::table)
+            .values(rows)
+            .on_conflict((
+                api_usage_daily::user_id,
+                api_usage_daily::day,
+                api_usage_daily::endpoint,
+            ))
+            .do_update()
+            .set((
+                api_usage_daily::requests
+                    .eq(api_usage_daily::requests + excluded(api_usage_daily::requests)),
+                api_usage_daily::rate_limited
+                    .eq(api_usage_daily::rate_limited + excluded(api_usage_daily::rate_limited)),
+            ))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(written)
+    }
+}
+
+/// Flush pending usage counts to Redis every `FLUSH_INTERVAL`
+pub fn spawn_api_usage_flush_task(redis_pool: RedisPool) {
+    TASK_REGISTRY.spawn("api_usage_flush", move |reporter| {
+        let redis_pool = redis_pool.clone();
+        async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                // Failures are logged and retried on the next tick
+                match API_USAGE.flush(&redis_pool).await {
+                    Ok(_) => reporter.success(),
+                    Err(e) => reporter.failure(e),
+                }
+            }
+        }
+    });
+}
+
+/// Roll completed days of usage from Redis into Postgres
+pub fn spawn_api_usage_rollup_task(state: AppState) {
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("api_usage_rollup", redis_pool, move |reporter| {
+        let service = ApiUsageService::new(&state);
+        async move {
+            let mut interval = tokio::time::interval(ROLLUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                match service.rollup_completed_days(Utc::now().date_naive()).await {
+                    Ok(written) => {
+                        if written > 0 {
+                            info!("Rolled up {} daily API usage rows", written);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("API usage rollup failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_key_round_trip() {
+        let user_id = Uuid::new_v4();
+        let day = NaiveDate::from_ymd_opt(2025, 9, 29).unwrap();
+        let key = usage_key(user_id, day);
+        assert_eq!(key, format!("api_usage:{}:2025-09-29", user_id));
+        assert_eq!(parse_usage_key(&key), Some((user_id, day)));
+
+        assert_eq!(parse_usage_key("api_usage:not-a-uuid:2025-09-29"), None);
+        assert_eq!(parse_usage_key(&format!("api_usage:{}", user_id)), None);
+        assert_eq!(parse_usage_key("clicks:abc123"), None);
+    }
+
+    #[test]
+    fn test_rows_from_hash() {
+        let user_id = Uuid::new_v4();
+        let day = NaiveDate::from_ymd_opt(2025, 9, 29).unwrap();
+        let fields = HashMap::from([
+            ("r:GET /v1/links".to_string(), 7),
+            ("r:POST /v1/links".to_string(), 3),
+            ("l:POST /v1/links".to_string(), 2),
+            ("unknown".to_string(), 99),
+        ]);
+
+        let mut rows = rows_from_hash(user_id, day, fields);
+        rows.sort_by(|a, b| a.endpoint.cmp(&b.endpoint));
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].endpoint, "GET /v1/links");
+        assert_eq!((rows[0].requests, rows[0].rate_limited), (7, 0));
+        assert_eq!(rows[1].endpoint, "POST /v1/links");
+        assert_eq!((rows[1].requests, rows[1].rate_limited), (3, 2));
+    }
+
+    #[test]
+    fn test_usage_counter_aggregates_requests() {
+        let counter = ApiUsageCounter::new();
+        let user_id = Uuid::new_v4();
+        let today = Utc::now().date_naive();
+        counter.record(user_id, "GET /v1/links", false);
+        counter.record(user_id, "GET /v1/links", true);
+        counter.record(user_id, "POST /v1/links", false);
+
+        let pending = counter.pending.drain();
+        assert_eq!(pending.len(), 2);
+        assert_eq!(
+            pending[&(user_id, today, "GET /v1/links".to_string())],
+            (2, 1)
+        );
+        assert_eq!(
+            pending[&(user_id, today, "POST /v1/links".to_string())],
+            (1, 0)
+        );
+        assert!(counter.pending.drain().is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+// Durable audit log storage
+// Writes come from the AuditLogger queue; reads back the per-user and admin endpoints
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::time::Duration;
+use tracing::{error, info};
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::audit_log::{AuditLogFilter, AuditLogRecord, NewAuditLogRecord},
+    schema::audit_logs,
+    services::background_tasks::TASK_REGISTRY,
+    utils::service_error::ServiceError,
+};
+
+/// How often expired audit entries are pruned
+const AUDIT_RETENTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub struct AuditLogService {
+    diesel_pool: DieselPool,
+}
+
+impl AuditLogService {
+    pub fn new(diesel_pool: DieselPool) -> Self {
+        Self { diesel_pool }
+    }
+
+    /// Insert a batch of entries in one statement
+    pub async fn insert_batch(
+        &self,
+        records: Vec<NewAuditLogRecord>,
+    ) -> Result<usize, ServiceError> {
+        if records.is_empty() {
+            return Ok(0);
+        }
+
+        let mut conn = self.get_conn().await?;
+
+        let inserted = diesel::insert_into(audit_logs::table)
+            .values(&records)
+            .execute(&mut conn)
+            .await?;
+
+        Ok(inserted)
+    }
+
+    /// Filtered page of entries (newest first) and the total matching count
+    pub async fn list(
+        &self,
+        filter: &AuditLogFilter,
+    ) -> Result<(Vec<AuditLogRecord>, i64), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let total = Self::filtered(filter)
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await?;
+
+        let records = Self::filtered(filter)
+            .order(audit_logs::created_at.desc())
+            .offset(filter.offset())
+            .limit(filter.per_page)
+            .select(AuditLogRecord::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok((records, total))
+    }
+
+    /// Delete entries older than the retention window; returns how many were removed
+    pub async fn prune_older_than(
+        &self,
+        retention: chrono::Duration,
+    ) -> Result<usize, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let cutoff = Utc::now() - retention;
+
+        let deleted = diesel::delete(audit_logs::table.filter(audit_logs::created_at.lt(cutoff)))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(deleted)
+    }
+
+    fn filtered(filter: &AuditLogFilter) -> audit_logs::BoxedQuery<'static, diesel::pg::Pg> {
+        let mut query = audit_logs::table.into_boxed();
+
+        if let Some(actor_id) = filter.actor_id {
+            query = query.filter(audit_logs::actor_id.eq(actor_id));
+        }
+        if let Some(action) = filter.action {
+            query = query.filter(audit_logs::action.eq(action.as_str()));
+        }
+        if let Some(ref resource_type) = filter.resource_type {
+            query = query.filter(audit_logs::resource_type.eq(resource_type.clone()));
+        }
+        if let Some(from) = filter.from {
+            query = query.filter(audit_logs::created_at.ge(from));
+        }
+        if let Some(to) = filter.to {
+            query = query.filter(audit_logs::created_at.lt(to));
+        }
+
+        query
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Periodically delete audit entries older than AUDIT_LOG_RETENTION_DAYS
+pub fn spawn_audit_log_retention_task(state: AppState) {
+    let retention_days = state.config.security.audit_log_retention_days;
+    if retention_days == 0 {
+        info!("Audit log retention pruning disabled (AUDIT_LOG_RETENTION_DAYS=0)");
+        return;
+    }
+
+    let retention = chrono::Duration::days(i64::from(retention_days));
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("audit_log_retention", redis_pool, move |reporter| {
+        let service = AuditLogService::new(state.diesel_pool.clone());
+        async move {
+            let mut interval = tokio::time::interval(AUDIT_RETENTION_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                match service.prune_older_than(retention).await {
+                    Ok(deleted) => {
+                        if deleted > 0 {
+                            info!(
+                                "Pruned {} audit log entries older than {} days",
+                                deleted, retention_days
+                            );
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Audit log retention pruning failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
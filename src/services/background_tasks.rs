@@ -1,9 +1,552 @@
 // Background task scheduler for DEV-124 & DEV-105
 // Handles periodic maintenance tasks for link management
+// Every task runs under `TASK_REGISTRY`, which records its last start, success and error,
+// restarts it with backoff when it panics or exits, and mirrors the status to Redis
+// (`background_tasks:{instance_id}`) so any replica can report on the whole fleet.
+// Jobs that touch shared state run through `spawn_exclusive`: every replica keeps the task
+// alive, but only the holder of its Redis lock executes an iteration. Tasks that work on
+// process-local state (click flush, blocklist reload, status mirror) run on every replica.
 
-use tracing::info;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tracing::{error, info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
 
 use crate::app::AppState;
+use crate::db::RedisPool;
+use crate::utils::distributed_lock::DistributedLock;
+
+/// Delay before the first restart of a failed task; doubles on every consecutive failure
+const RESTART_BACKOFF_INITIAL: Duration = Duration::from_secs(1);
+
+/// Upper bound for the restart delay
+const RESTART_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// How often this instance's task status is written to Redis
+const STATUS_MIRROR_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Mirrored status expires when an instance stops reporting
+const STATUS_MIRROR_TTL_SECONDS: usize = 90;
+
+/// Prefix of the mirrored status keys, one per instance
+const STATUS_KEY_PREFIX: &str = "background_tasks:";
+
+/// Lock expiry for exclusive tasks; a crashed holder is replaced within this window
+const TASK_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// How often a held task lock is renewed
+const TASK_LOCK_RENEW_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often Redis click counters are added to `links.click_count`
+const CLICK_SYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often links past their expiry are marked inactive
+const EXPIRY_SWEEP_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Process-wide registry every background task is spawned through
+pub static TASK_REGISTRY: Lazy<Arc<BackgroundTaskRegistry>> =
+    Lazy::new(|| Arc::new(BackgroundTaskRegistry::new(instance_id())));
+
+/// Name this instance reports under: the pod hostname, or a random id outside containers
+fn instance_id() -> String {
+    std::env::var("HOSTNAME")
+        .ok()
+        .filter(|host| !host.is_empty())
+        .unwrap_or_else(|| Uuid::new_v4().to_string())
+}
+
+/// Last known state of one background task
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TaskStatus {
+    pub name: String,
+    /// False while the task waits to be restarted
+    pub running: bool,
+    pub last_started_at: Option<DateTime<Utc>>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<DateTime<Utc>>,
+    /// Failed runs and crashes since the last success
+    pub consecutive_failures: u32,
+    /// Times the task was restarted after a panic or unexpected exit
+    pub restarts: u32,
+    /// Runs on one replica at a time, under a Redis lock
+    pub exclusive: bool,
+    /// This instance holds the task's lock (always true for non-exclusive tasks)
+    pub holds_lock: bool,
+}
+
+impl TaskStatus {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            running: false,
+            last_started_at: None,
+            last_success_at: None,
+            last_error: None,
+            last_error_at: None,
+            consecutive_failures: 0,
+            restarts: 0,
+            exclusive: false,
+            holds_lock: true,
+        }
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.running && self.consecutive_failures == 0
+    }
+}
+
+/// Task status reported by one instance
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct InstanceTaskStatus {
+    pub instance_id: String,
+    pub reported_at: DateTime<Utc>,
+    pub tasks: Vec<TaskStatus>,
+}
+
+/// Instance currently running an exclusive task
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct TaskLockHolder {
+    pub task: String,
+    /// None while the lock is free
+    pub instance_id: Option<String>,
+}
+
+/// Background task status for this instance and every instance mirrored to Redis
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct BackgroundTaskMetrics {
+    pub timestamp: String,
+    pub instance_id: String,
+    pub tasks: Vec<TaskStatus>,
+    /// Empty when Redis is unreachable
+    pub instances: Vec<InstanceTaskStatus>,
+    /// Empty when Redis is unreachable
+    pub lock_holders: Vec<TaskLockHolder>,
+}
+
+/// Handle a running task uses to report the outcome of each run
+#[derive(Clone)]
+pub struct TaskReporter {
+    registry: Arc<BackgroundTaskRegistry>,
+    name: &'static str,
+    lock: Option<Arc<DistributedLock>>,
+}
+
+impl TaskReporter {
+    /// Whether this instance should run the next iteration: always for tasks spawned with
+    /// `spawn`, only while holding the Redis lock for tasks spawned with `spawn_exclusive`
+    pub async fn is_leader(&self) -> bool {
+        let Some(lock) = &self.lock else {
+            return true;
+        };
+
+        let leader = match lock.try_acquire().await {
+            Ok(acquired) => acquired,
+            Err(e) => {
+                warn!("Failed to acquire lock for task '{}': {}", self.name, e);
+                false
+            },
+        };
+        self.registry
+            .update(self.name, |status| status.holds_lock = leader);
+        leader
+    }
+
+    /// Record a successful run; clears the failure streak and the restart backoff
+    pub fn success(&self) {
+        self.registry.update(self.name, |status| {
+            status.last_success_at = Some(Utc::now());
+            status.consecutive_failures = 0;
+        });
+    }
+
+    /// Record a failed run; the task keeps running
+    pub fn failure(&self, error: impl std::fmt::Display) {
+        let message = error.to_string();
+        self.registry.update(self.name, |status| {
+            status.last_error = Some(message);
+            status.last_error_at = Some(Utc::now());
+            status.consecutive_failures += 1;
+        });
+    }
+}
+
+/// Shared status of every background task, with a supervisor per task
+pub struct BackgroundTaskRegistry {
+    instance_id: String,
+    tasks: Mutex<BTreeMap<&'static str, TaskStatus>>,
+    task_locks: Mutex<Vec<Arc<DistributedLock>>>,
+}
+
+impl BackgroundTaskRegistry {
+    pub fn new(instance_id: String) -> Self {
+        Self {
+            instance_id,
+            tasks: Mutex::new(BTreeMap::new()),
+            task_locks: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn instance_id(&self) -> &str {
+        &self.instance_id
+    }
+
+    /// Run `task` under supervision. `task` builds a fresh future for every (re)start; the
+    /// future is expected to loop forever, so returning counts as a failure like a panic does.
+    pub fn spawn<F, Fut>(self: &Arc<Self>, name: &'static str, task: F)
+    where
+        F: Fn(TaskReporter) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.supervise(name, None, task);
+    }
+
+    /// Like `spawn`, for jobs that must run once across all replicas. Each iteration starts
+    /// with `reporter.is_leader()`; the lock is renewed in the background while held, so the
+    /// same instance keeps running the task until it stops or loses Redis.
+    pub fn spawn_exclusive<F, Fut>(
+        self: &Arc<Self>,
+        name: &'static str,
+        redis_pool: RedisPool,
+        task: F,
+    ) where
+        F: Fn(TaskReporter) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let lock = Arc::new(DistributedLock::new(
+            redis_pool,
+            &format!("task:{}", name),
+            &self.instance_id,
+            TASK_LOCK_TTL,
+        ));
+        self.task_locks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(Arc::clone(&lock));
+        self.update(name, |status| {
+            status.exclusive = true;
+            status.holds_lock = false;
+        });
+
+        let registry = Arc::clone(self);
+        let renewed = Arc::clone(&lock);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TASK_LOCK_RENEW_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !renewed.is_held() {
+                    continue;
+                }
+                let held = match renewed.renew().await {
+                    Ok(held) => held,
+                    Err(e) => {
+                        warn!("Failed to renew lock for task '{}': {}", name, e);
+                        // Retried on the next tick, while the lock has not expired yet
+                        continue;
+                    },
+                };
+                if !held {
+                    warn!("Lost lock for task '{}' to another instance", name);
+                }
+                registry.update(name, |status| status.holds_lock = held);
+            }
+        });
+
+        self.supervise(name, Some(lock), task);
+    }
+
+    /// Give up every task lock this instance holds, so another replica takes over at once
+    pub async fn release_locks(&self) {
+        let locks: Vec<Arc<DistributedLock>> = self
+            .task_locks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        for lock in locks.iter().filter(|lock| lock.is_held()) {
+            if let Err(e) = lock.release().await {
+                warn!("Failed to release lock '{}': {}", lock.name(), e);
+            }
+        }
+    }
+
+    fn supervise<F, Fut>(
+        self: &Arc<Self>,
+        name: &'static str,
+        lock: Option<Arc<DistributedLock>>,
+        task: F,
+    ) where
+        F: Fn(TaskReporter) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.update(name, |_| {});
+        let registry = Arc::clone(self);
+
+        tokio::spawn(async move {
+            let mut backoff = RESTART_BACKOFF_INITIAL;
+            loop {
+                let started_at = Utc::now();
+                registry.update(name, |status| {
+                    status.running = true;
+                    status.last_started_at = Some(started_at);
+                });
+
+                let reporter = TaskReporter {
+                    registry: Arc::clone(&registry),
+                    name,
+                    lock: lock.clone(),
+                };
+                let reason = match tokio::spawn(task(reporter)).await {
+                    Ok(()) => "task exited unexpectedly".to_string(),
+                    Err(e) if e.is_panic() => {
+                        format!("task panicked: {}", panic_message(e.into_panic()))
+                    },
+                    Err(e) => format!("task aborted: {}", e),
+                };
+
+                let succeeded_since_start = registry.update(name, |status| {
+                    let succeeded = status.last_success_at.is_some_and(|at| at >= started_at);
+                    status.running = false;
+                    status.last_error = Some(reason.clone());
+                    status.last_error_at = Some(Utc::now());
+                    status.consecutive_failures += 1;
+                    status.restarts += 1;
+                    succeeded
+                });
+
+                // A task that did useful work before crashing restarts quickly again
+                if succeeded_since_start {
+                    backoff = RESTART_BACKOFF_INITIAL;
+                }
+                error!(
+                    "Background task '{}' stopped ({}), restarting in {}s",
+                    name,
+                    reason,
+                    backoff.as_secs()
+                );
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(RESTART_BACKOFF_MAX);
+            }
+        });
+    }
+
+    /// Status of every registered task, ordered by name
+    pub fn snapshot(&self) -> Vec<TaskStatus> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Status of one task
+    pub fn status(&self, name: &str) -> Option<TaskStatus> {
+        self.lock().get(name).cloned()
+    }
+
+    /// Write this instance's status to Redis for the other replicas
+    pub async fn mirror_to_redis(&self, redis_pool: &RedisPool) -> Result<(), redis::RedisError> {
+        let report = InstanceTaskStatus {
+            instance_id: self.instance_id.clone(),
+            reported_at: Utc::now(),
+            tasks: self.snapshot(),
+        };
+        let payload = serde_json::to_string(&report).unwrap_or_default();
+        redis_pool
+            .set_with_expiry(
+                &format!("{}{}", STATUS_KEY_PREFIX, self.instance_id),
+                payload,
+                STATUS_MIRROR_TTL_SECONDS,
+            )
+            .await
+    }
+
+    /// Local status plus the status every live instance mirrored to Redis
+    pub async fn metrics(&self, redis_pool: &RedisPool) -> BackgroundTaskMetrics {
+        let instances = match read_mirrored_status(redis_pool).await {
+            Ok(instances) => instances,
+            Err(e) => {
+                warn!("Failed to read mirrored background task status: {}", e);
+                Vec::new()
+            },
+        };
+
+        let locks: Vec<Arc<DistributedLock>> = self
+            .task_locks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        let mut lock_holders = Vec::with_capacity(locks.len());
+        for lock in &locks {
+            match DistributedLock::holder(redis_pool, lock.name()).await {
+                Ok(instance_id) => lock_holders.push(TaskLockHolder {
+                    task: lock.name().trim_start_matches("task:").to_string(),
+                    instance_id,
+                }),
+                Err(e) => {
+                    warn!("Failed to read task lock holders: {}", e);
+                    lock_holders.clear();
+                    break;
+                },
+            }
+        }
+
+        BackgroundTaskMetrics {
+            timestamp: Utc::now().to_rfc3339(),
+            instance_id: self.instance_id.clone(),
+            tasks: self.snapshot(),
+            instances,
+            lock_holders,
+        }
+    }
+
+    fn update<R>(&self, name: &'static str, f: impl FnOnce(&mut TaskStatus) -> R) -> R {
+        let mut tasks = self.lock();
+        f(tasks.entry(name).or_insert_with(|| TaskStatus::new(name)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<&'static str, TaskStatus>> {
+        // A panic while holding the lock cannot leave a status half-written
+        self.tasks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Every instance's mirrored task status, ordered by instance id
+async fn read_mirrored_status(
+    redis_pool: &RedisPool,
+) -> Result<Vec<InstanceTaskStatus>, redis::RedisError> {
+    let mut conn = redis_pool.get_connection().await?;
+    let mut keys = HashSet::new();
+    let mut cursor: u64 = 0;
+
+    loop {
+        let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(format!("{}*", STATUS_KEY_PREFIX))
+            .arg("COUNT")
+            .arg(100)
+            .query_async(&mut conn)
+            .await?;
+        keys.extend(batch);
+        cursor = next;
+        if cursor == 0 {
+            break;
+        }
+    }
+
+    if keys.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let keys: Vec<String> = keys.into_iter().collect();
+    let values: Vec<Option<String>> = redis::cmd("MGET").arg(&keys).query_async(&mut conn).await?;
+
+    let mut instances: Vec<InstanceTaskStatus> = values
+        .into_iter()
+        .flatten()
+        .filter_map(|payload| serde_json::from_str(&payload).ok())
+        .collect();
+    instances.sort_by(|a, b| a.instance_id.cmp(&b.instance_id));
+    Ok(instances)
+}
+
+/// Message of a panic payload (`panic!` with a literal or a formatted string)
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Periodically mirror the registry to Redis
+fn spawn_status_mirror_task(redis_pool: RedisPool) {
+    TASK_REGISTRY.spawn("task_status_mirror", move |reporter| {
+        let redis_pool = redis_pool.clone();
+        async move {
+            let mut interval = tokio::time::interval(STATUS_MIRROR_INTERVAL);
+            loop {
+                interval.tick().await;
+                match TASK_REGISTRY.mirror_to_redis(&redis_pool).await {
+                    Ok(()) => reporter.success(),
+                    Err(e) => {
+                        warn!("Failed to mirror background task status: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+/// Add drained Redis click counters to `links.click_count`
+fn spawn_click_sync_task(state: AppState) {
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("click_count_sync", redis_pool, move |reporter| {
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(CLICK_SYNC_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                match crate::services::link::sync_click_counts_to_database(
+                    &state.redis_pool,
+                    &state.diesel_pool,
+                )
+                .await
+                {
+                    Ok(_) => reporter.success(),
+                    Err(e) => {
+                        error!("Click count sync failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+/// Mark links past `expires_at` inactive and invalidate their cached redirects
+fn spawn_expiry_sweep_task(state: AppState) {
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("link_expiry_sweep", redis_pool, move |reporter| {
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(EXPIRY_SWEEP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                match crate::services::link::deactivate_expired_links(
+                    &state.diesel_pool,
+                    &state.redis_pool,
+                )
+                .await
+                {
+                    Ok(deactivated) => {
+                        if deactivated > 0 {
+                            info!("Deactivated {} expired links", deactivated);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Link expiry sweep failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
 
 /// Background task manager for link services
 pub struct BackgroundTaskManager {
@@ -19,11 +562,64 @@ impl BackgroundTaskManager {
     pub async fn start_all_tasks(&self) {
         info!("Starting background tasks for link management");
 
-        // Click count sync is no longer needed since we fetch from ClickHouse directly
-        // Add other background tasks here as needed
+        // Share task status with the other replicas
+        spawn_status_mirror_task(self.state.redis_pool.clone());
+
+        // Write redirect clicks aggregated in memory to Redis in batches
+        crate::services::click_counter::spawn_click_flush_task(self.state.redis_pool.clone());
+
+        // Write per-user API request counts aggregated in memory to Redis
+        crate::services::api_usage::spawn_api_usage_flush_task(self.state.redis_pool.clone());
+
+        // Move completed days of API usage from Redis to api_usage_daily
+        crate::services::api_usage::spawn_api_usage_rollup_task(self.state.clone());
 
-        // Example: Could add a task to periodically refresh ClickHouse materialized views
-        // or cleanup expired links
+        // Persist the Redis click counters to the links table
+        spawn_click_sync_task(self.state.clone());
+
+        // Mark expired links inactive so they drop out of active counts
+        spawn_expiry_sweep_task(self.state.clone());
+
+        // Pre-load the hottest links into the redirect cache
+        crate::services::cache_warmer::spawn_link_cache_warm_task(self.state.clone());
+
+        // Restore pause keys of users who paused all links, should Redis have lost them
+        crate::services::link_pause::spawn_pause_resync_task(self.state.clone());
+
+        // Refresh the URLhaus threat feed (URLHAUS_ENABLED, URLHAUS_UPDATE_INTERVAL_HOURS)
+        crate::utils::urlhaus_client::spawn_urlhaus_updater(self.state.redis_pool.clone());
+
+        // Rolling security re-scan of existing links (quarantines links that turned malicious)
+        crate::services::quarantine::spawn_link_rescan_task(self.state.clone());
+
+        // Keep the pre-generated short code pool topped up
+        crate::services::short_code::spawn_short_code_pool_task(self.state.clone());
+
+        // Prune audit log entries past the retention window
+        crate::services::audit_log::spawn_audit_log_retention_task(self.state.clone());
+
+        // Delete refresh tokens expired or revoked longer than the grace period
+        crate::services::refresh_token_cleanup::spawn_refresh_token_cleanup_task(
+            self.state.clone(),
+        );
+
+        // Delete account exports whose download link has expired
+        crate::services::account_export::spawn_account_export_cleanup_task(self.state.clone());
+
+        // Prune link change history past LINK_REVISION_RETENTION_DAYS
+        crate::services::link_revision::spawn_link_revision_retention_task(self.state.clone());
+
+        // Roll raw click events into daily tables and prune them past raw retention
+        crate::services::clickhouse_analytics::spawn_click_rollup_task(self.state.clone());
+
+        // Mail last month's usage summary to users who opted in
+        crate::services::usage_summary::spawn_monthly_digest_task(self.state.clone());
+
+        // Keep hourly rate limit offender counts in ClickHouse for long-term trends
+        crate::services::analytics::spawn_offender_export_task(self.state.clone());
+
+        // Write sampled rate limit checks to ClickHouse so metrics outlive restarts
+        crate::services::analytics::spawn_event_export_task(self.state.clone());
     }
 }
 
@@ -32,3 +628,89 @@ pub async fn initialize_background_tasks(state: AppState) {
     let task_manager = BackgroundTaskManager::new(state);
     task_manager.start_all_tasks().await;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Wait (in paused test time) until the task's status satisfies `done`
+    async fn wait_for(
+        registry: &BackgroundTaskRegistry,
+        name: &str,
+        done: impl Fn(&TaskStatus) -> bool,
+    ) -> TaskStatus {
+        for _ in 0..1000 {
+            if let Some(status) = registry.status(name).filter(|status| done(status)) {
+                return status;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        panic!("task '{}' never reached the expected state", name);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_panicked_task_is_restarted_and_reports_failure() {
+        let registry = Arc::new(BackgroundTaskRegistry::new("test".to_string()));
+        let runs = Arc::new(AtomicU32::new(0));
+
+        let task_runs = Arc::clone(&runs);
+        registry.spawn("flaky", move |reporter| {
+            let run = task_runs.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if run == 0 {
+                    panic!("boom");
+                }
+                reporter.success();
+                std::future::pending::<()>().await;
+            }
+        });
+
+        let status = wait_for(&registry, "flaky", |status| {
+            status.last_success_at.is_some()
+        })
+        .await;
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(status.restarts, 1);
+        assert!(status.running);
+        assert_eq!(status.consecutive_failures, 0);
+        assert!(status.last_error.unwrap().contains("boom"));
+        assert!(status.last_error_at.is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_restart_backoff_grows_with_consecutive_crashes() {
+        let registry = Arc::new(BackgroundTaskRegistry::new("test".to_string()));
+        let started = tokio::time::Instant::now();
+
+        registry.spawn("exits", |_reporter| async {});
+
+        let status = wait_for(&registry, "exits", |status| status.restarts >= 4).await;
+        assert_eq!(status.consecutive_failures, status.restarts);
+        assert_eq!(
+            status.last_error.as_deref(),
+            Some("task exited unexpectedly")
+        );
+        // 1s + 2s + 4s of backoff before the fourth restart
+        assert!(started.elapsed() >= Duration::from_secs(7));
+    }
+
+    #[tokio::test]
+    async fn test_reported_failures_keep_task_running() {
+        let registry = Arc::new(BackgroundTaskRegistry::new("test".to_string()));
+        registry.spawn("reporting", |reporter| async move {
+            reporter.failure("database unavailable");
+            reporter.failure("database unavailable");
+            std::future::pending::<()>().await;
+        });
+
+        let status = wait_for(&registry, "reporting", |status| {
+            status.consecutive_failures == 2
+        })
+        .await;
+        assert!(status.running);
+        assert!(!status.is_healthy());
+        assert_eq!(status.restarts, 0);
+        assert_eq!(status.last_error.as_deref(), Some("database unavailable"));
+    }
+}
@@ -0,0 +1,306 @@
+// Operator-managed domain blocklist and trusted domains
+// Persists entries in Postgres and keeps every replica's in-memory copy fresh via a Redis version key
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::{
+        blocked_domain::{
+            BlockedDomain, BlockedEntryType, CreateBlockedDomainRequest, NewBlockedDomain,
+        },
+        link::Link,
+        trusted_domain::{CreateTrustedDomainRequest, NewTrustedDomain, TrustedDomain},
+    },
+    schema::{blocked_domains, links, trusted_domains},
+    services::list_sync::{spawn_list_refresh_task, SyncedList},
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        security_scanner::{
+            replace_operator_blocklist, replace_operator_trusted_domains, OperatorBlocklist,
+            TrustedDomains,
+        },
+        service_error::ServiceError,
+    },
+};
+
+/// Redis key bumped on every blocklist change so other replicas know to reload
+const BLOCKLIST_VERSION_KEY: &str = "blocklist:version";
+
+pub struct BlocklistService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+}
+
+impl BlocklistService {
+    pub fn new(diesel_pool: DieselPool, redis_pool: RedisPool) -> Self {
+        Self {
+            diesel_pool,
+            redis_pool,
+        }
+    }
+
+    /// List all operator-managed entries, newest first
+    pub async fn list(&self) -> Result<Vec<BlockedDomain>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entries = blocked_domains::table
+            .order(blocked_domains::created_at.desc())
+            .select(BlockedDomain::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Add an entry, apply it locally and notify other replicas
+    pub async fn add(
+        &self,
+        request: &CreateBlockedDomainRequest,
+        created_by: Uuid,
+    ) -> Result<BlockedDomain, ServiceError> {
+        let pattern = request
+            .normalized_pattern()
+            .map_err(ServiceError::ValidationError)?;
+
+        let mut conn = self.get_conn().await?;
+
+        let new_entry = NewBlockedDomain {
+            pattern: pattern.clone(),
+            entry_type: request.entry_type.as_str().to_string(),
+            reason: request.reason.trim().to_string(),
+            created_by: Some(created_by),
+        };
+
+        let entry = diesel::insert_into(blocked_domains::table)
+            .values(&new_entry)
+            .returning(BlockedDomain::as_returning())
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => ServiceError::Conflict(format!("{} is already blocked", pattern)),
+                other => ServiceError::from(other),
+            })?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::DomainBlocked,
+            created_by,
+            "blocked_domain",
+            Some(entry.id.to_string()),
+            Some(format!(
+                "Blocked {} {}: {}",
+                entry.entry_type, entry.pattern, entry.reason
+            )),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(entry)
+    }
+
+    /// Remove an entry, apply it locally and notify other replicas
+    pub async fn remove(&self, id: Uuid, removed_by: Uuid) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entry = diesel::delete(blocked_domains::table.find(id))
+            .returning(BlockedDomain::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::DomainUnblocked,
+            removed_by,
+            "blocked_domain",
+            Some(entry.id.to_string()),
+            Some(format!("Unblocked {} {}", entry.entry_type, entry.pattern)),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(())
+    }
+
+    /// List all trusted domains, newest first
+    pub async fn list_trusted(&self) -> Result<Vec<TrustedDomain>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entries = trusted_domains::table
+            .order(trusted_domains::created_at.desc())
+            .select(TrustedDomain::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Trust a domain, apply it locally and notify other replicas
+    pub async fn add_trusted(
+        &self,
+        request: &CreateTrustedDomainRequest,
+        created_by: Uuid,
+    ) -> Result<TrustedDomain, ServiceError> {
+        let pattern = request
+            .normalized_pattern()
+            .map_err(ServiceError::ValidationError)?;
+
+        let mut conn = self.get_conn().await?;
+
+        let new_entry = NewTrustedDomain {
+            pattern: pattern.clone(),
+            reason: request.reason.trim().to_string(),
+            created_by: Some(created_by),
+        };
+
+        let entry = diesel::insert_into(trusted_domains::table)
+            .values(&new_entry)
+            .returning(TrustedDomain::as_returning())
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => ServiceError::Conflict(format!("{} is already trusted", pattern)),
+                other => ServiceError::from(other),
+            })?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::DomainTrusted,
+            created_by,
+            "trusted_domain",
+            Some(entry.id.to_string()),
+            Some(format!("Trusted {}: {}", entry.pattern, entry.reason)),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(entry)
+    }
+
+    /// Remove a trusted domain, apply it locally and notify other replicas
+    pub async fn remove_trusted(&self, id: Uuid, removed_by: Uuid) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entry = diesel::delete(trusted_domains::table.find(id))
+            .returning(TrustedDomain::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::DomainUntrusted,
+            removed_by,
+            "trusted_domain",
+            Some(entry.id.to_string()),
+            Some(format!("Untrusted {}", entry.pattern)),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(())
+    }
+
+    /// Active, non-deleted links whose destination host matches the given entry
+    pub async fn find_active_links_for_entry(
+        &self,
+        entry: &BlockedDomain,
+    ) -> Result<Vec<Link>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        // Cheap substring prefilter in SQL, exact host matching in Rust
+        let candidates: Vec<Link> = links::table
+            .filter(links::is_active.eq(true))
+            .filter(links::deleted_at.is_null())
+            .filter(links::original_url.ilike(format!("%{}%", entry.pattern)))
+            .select(Link::as_select())
+            .load(&mut conn)
+            .await?;
+
+        let mut matcher = OperatorBlocklist::new();
+        match entry.entry_type() {
+            BlockedEntryType::Domain => matcher.insert_domain(&entry.pattern, &entry.reason),
+            BlockedEntryType::Tld => matcher.insert_tld(&entry.pattern, &entry.reason),
+        }
+
+        Ok(candidates
+            .into_iter()
+            .filter(|link| {
+                url::Url::parse(&link.original_url)
+                    .ok()
+                    .and_then(|url| url.host_str().map(|host| matcher.matches(host).is_some()))
+                    .unwrap_or(false)
+            })
+            .collect())
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl SyncedList for BlocklistService {
+    const NAME: &'static str = "operator blocklist";
+    const VERSION_KEY: &'static str = BLOCKLIST_VERSION_KEY;
+
+    fn redis_pool(&self) -> &RedisPool {
+        &self.redis_pool
+    }
+
+    /// Load all blocklist entries and trusted domains from Postgres into the shared in-memory
+    /// lists. Returns the number of entries loaded.
+    async fn reload(&self) -> Result<usize, ServiceError> {
+        let entries = self.list().await?;
+        let trusted_entries = self.list_trusted().await?;
+
+        let mut blocklist = OperatorBlocklist::new();
+        for entry in &entries {
+            match entry.entry_type() {
+                BlockedEntryType::Domain => blocklist.insert_domain(&entry.pattern, &entry.reason),
+                BlockedEntryType::Tld => blocklist.insert_tld(&entry.pattern, &entry.reason),
+            }
+        }
+
+        let mut trusted = TrustedDomains::new();
+        for entry in &trusted_entries {
+            trusted.insert(&entry.pattern, &entry.reason);
+        }
+
+        let count = blocklist.len() + trusted.len();
+        replace_operator_blocklist(blocklist).await;
+        replace_operator_trusted_domains(trusted).await;
+        Ok(count)
+    }
+}
+
+/// Load the blocklist once and then reload it whenever another replica changes it
+pub fn spawn_blocklist_refresh_task(state: AppState) {
+    spawn_list_refresh_task(
+        "blocklist_refresh",
+        state.config.security.blocklist_refresh_interval_seconds,
+        BlocklistService::new(state.diesel_pool.clone(), state.redis_pool.clone()),
+    );
+}
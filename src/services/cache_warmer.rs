@@ -0,0 +1,262 @@
+// Redirect cache warming
+// After a deploy or a Redis flush every first redirect would fall through to Postgres at
+// once. At startup and every LINK_CACHE_WARM_INTERVAL seconds, the hottest links (most
+// clicks, most recently accessed) are written to the link cache ahead of traffic, within
+// LINK_CACHE_WARM_BUDGET_MS. A run that finds the hottest links already cached does nothing.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::collections::HashSet;
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+use tracing::{error, info};
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::link::Link,
+    services::{
+        background_tasks::TASK_REGISTRY,
+        link::{cache_keys_for_code, cache_link},
+    },
+    utils::service_error::ServiceError,
+};
+
+/// Links checked (and cached) per Redis round trip
+const WARM_BATCH_SIZE: usize = 100;
+
+/// Share of the hottest batch that must already be cached to skip a run
+const HEALTHY_CACHED_PERCENT: usize = 90;
+
+/// Outcome of one warming run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct CacheWarmReport {
+    /// Hot links selected from the database
+    pub candidates: usize,
+    pub already_cached: usize,
+    pub warmed: usize,
+    /// The cache was already populated, nothing was written
+    pub skipped: bool,
+    /// The time budget ran out before every candidate was checked
+    pub budget_exhausted: bool,
+}
+
+/// Load up to `limit` of the hottest links into the redirect cache within `budget`
+pub async fn warm_link_cache(
+    diesel_pool: &DieselPool,
+    redis_pool: &RedisPool,
+    limit: usize,
+    budget: Duration,
+) -> Result<CacheWarmReport, ServiceError> {
+    let deadline = Instant::now() + budget;
+    let links = load_hot_links(diesel_pool, limit).await?;
+    let mut report = CacheWarmReport {
+        candidates: links.len(),
+        ..CacheWarmReport::default()
+    };
+
+    for (batch_index, batch) in links.chunks(WARM_BATCH_SIZE).enumerate() {
+        if Instant::now() >= deadline {
+            report.budget_exhausted = true;
+            break;
+        }
+
+        let cached = cached_flags(redis_pool, batch).await?;
+        let cached_count = cached.iter().filter(|cached| **cached).count();
+        report.already_cached += cached_count;
+
+        // The hottest links are the first to be cached by traffic
+        if batch_index == 0 && is_healthy_population(cached_count, batch.len()) {
+            report.skipped = true;
+            break;
+        }
+
+        for (link, _) in batch.iter().zip(&cached).filter(|(_, cached)| !**cached) {
+            if Instant::now() >= deadline {
+                report.budget_exhausted = true;
+                break;
+            }
+            cache_link(redis_pool, link).await?;
+            report.warmed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Active, unexpired links ordered by clicks and by last access, interleaved
+async fn load_hot_links(diesel_pool: &DieselPool, limit: usize) -> Result<Vec<Link>, ServiceError> {
+    use crate::schema::links::dsl;
+
+    if limit == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+    let now = Utc::now();
+
+    let by_clicks: Vec<Link> = dsl::links
+        .filter(dsl::deleted_at.is_null())
+        .filter(dsl::is_active.eq(true))
+        .filter(dsl::expires_at.is_null().or(dsl::expires_at.gt(now)))
+        .filter(dsl::click_count.gt(0))
+        .order(dsl::click_count.desc())
+        .limit(limit as i64)
+        .load(&mut conn)
+        .await?;
+
+    let by_recency: Vec<Link> = dsl::links
+        .filter(dsl::deleted_at.is_null())
+        .filter(dsl::is_active.eq(true))
+        .filter(dsl::expires_at.is_null().or(dsl::expires_at.gt(now)))
+        .filter(dsl::last_accessed_at.is_not_null())
+        .order(dsl::last_accessed_at.desc())
+        .limit(limit as i64)
+        .load(&mut conn)
+        .await?;
+
+    Ok(interleave_rankings(by_clicks, by_recency, limit, |link| {
+        link.id
+    }))
+}
+
+/// Alternate between two rankings, dropping entries already taken, up to `limit`
+fn interleave_rankings<T, K: Hash + Eq>(
+    first: Vec<T>,
+    second: Vec<T>,
+    limit: usize,
+    key: impl Fn(&T) -> K,
+) -> Vec<T> {
+    let mut seen = HashSet::new();
+    let mut merged = Vec::with_capacity(limit.min(first.len() + second.len()));
+    let mut first = first.into_iter();
+    let mut second = second.into_iter();
+
+    while merged.len() < limit {
+        let next = [first.next(), second.next()];
+        if next.iter().all(Option::is_none) {
+            break;
+        }
+        for item in next.into_iter().flatten() {
+            if merged.len() < limit && seen.insert(key(&item)) {
+                merged.push(item);
+            }
+        }
+    }
+
+    merged
+}
+
+/// Whether each link's redirect record is already cached
+async fn cached_flags(redis_pool: &RedisPool, links: &[Link]) -> Result<Vec<bool>, ServiceError> {
+    let mut conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    let mut pipe = redis::pipe();
+    for link in links {
+        let [_, redirect_key, _] = cache_keys_for_code(&link.short_code);
+        pipe.exists(redirect_key);
+    }
+    pipe.query_async(&mut conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))
+}
+
+fn is_healthy_population(cached: usize, sampled: usize) -> bool {
+    sampled > 0 && cached * 100 >= sampled * HEALTHY_CACHED_PERCENT
+}
+
+/// Warm the cache at startup and every LINK_CACHE_WARM_INTERVAL seconds
+pub fn spawn_link_cache_warm_task(state: AppState) {
+    let limit = state.config.link_cache_warm_count;
+    if limit == 0 {
+        info!("Link cache warming disabled (LINK_CACHE_WARM_COUNT=0)");
+        return;
+    }
+    let budget = Duration::from_millis(state.config.link_cache_warm_budget_ms);
+    let interval_secs = state.config.link_cache_warm_interval;
+
+    // The cache is shared, so one replica warms it for all
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("link_cache_warm", redis_pool, move |reporter| {
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                let started = Instant::now();
+                match warm_link_cache(&state.diesel_pool, &state.redis_pool, limit, budget).await
+                {
+                    Ok(report) => {
+                        if report.skipped {
+                            info!(
+                                "Link cache already populated ({} of the hottest links cached), skipped warming",
+                                report.already_cached
+                            );
+                        } else {
+                            info!(
+                                "Warmed {} links into the redirect cache in {}ms ({} candidates, {} already cached{})",
+                                report.warmed,
+                                started.elapsed().as_millis(),
+                                report.candidates,
+                                report.already_cached,
+                                if report.budget_exhausted {
+                                    ", time budget exhausted"
+                                } else {
+                                    ""
+                                }
+                            );
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Link cache warming failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interleave_rankings() {
+        // 2 is both heavily clicked and recently accessed
+        let by_clicks = vec![1, 2, 3];
+        let by_recency = vec![4, 2, 5];
+
+        let merged = interleave_rankings(by_clicks.clone(), by_recency.clone(), 10, |id| *id);
+        assert_eq!(merged, vec![1, 4, 2, 3, 5]);
+
+        assert_eq!(
+            interleave_rankings(by_clicks, by_recency, 3, |id| *id),
+            vec![1, 4, 2]
+        );
+        assert_eq!(
+            interleave_rankings(vec![1, 2], Vec::new(), 5, |id| *id),
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn test_is_healthy_population() {
+        assert!(is_healthy_population(90, 100));
+        assert!(!is_healthy_population(89, 100));
+        assert!(is_healthy_population(5, 5));
+        assert!(!is_healthy_population(0, 0));
+    }
+}
@@ -0,0 +1,284 @@
+// Per-process click aggregation for the redirect path
+// Redirects only bump an in-memory counter; one background task flushes the totals to
+// `clicks:{short_code}` in Redis with a single pipeline (INCRBY + EXPIRE per code), where
+// `sync_click_counts_to_database` drains them. A failed flush puts the clicks back, so
+// they go out with the next one, and the server flushes once more on graceful shutdown.
+// Counts the database sync could not store go to `clicks:fallback:{short_code}`.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::db::RedisPool;
+use crate::services::background_tasks::TASK_REGISTRY;
+use crate::services::pending_counter::{scan_keys, CounterSpec, PendingCounter};
+
+/// How often pending clicks are written to Redis
+const FLUSH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Retries for one flush before its clicks are put back for the next tick
+const FLUSH_MAX_RETRIES: u32 = 3;
+
+/// Click counter TTL (24 hours)
+const CLICK_COUNTER_TTL_SECONDS: i64 = 86400;
+
+/// Prefix of every click counter key; `clicks:*` also matches the fallback keys
+const CLICK_COUNTER_PREFIX: &str = "clicks:";
+
+/// Prefix of counts put back after a failed database sync
+const CLICK_FALLBACK_PREFIX: &str = "clicks:fallback:";
+
+/// Keys requested per SCAN call and drained per pipeline
+const DRAIN_BATCH_SIZE: usize = 500;
+
+/// Process-wide aggregator used by every redirect
+pub static CLICK_COUNTER: Lazy<ClickCounter> = Lazy::new(ClickCounter::new);
+
+/// Redis key holding the not-yet-synced click count for a short code
+pub fn click_counter_key(short_code: &str) -> String {
+    format!("{}{}", CLICK_COUNTER_PREFIX, short_code)
+}
+
+/// Short code a click counter or fallback key belongs to
+fn short_code_from_key(key: &str) -> Option<&str> {
+    key.strip_prefix(CLICK_FALLBACK_PREFIX)
+        .or_else(|| key.strip_prefix(CLICK_COUNTER_PREFIX))
+        .filter(|code| !code.is_empty())
+}
+
+/// Click aggregation counters since process start
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ClickCounterMetrics {
+    pub timestamp: String,
+    /// Clicks waiting for the next flush
+    pub pending_clicks: u64,
+    /// Clicks written to Redis
+    pub flushed_clicks: u64,
+    /// Flushes that failed after retries; their clicks were kept for the next flush
+    pub flush_failures: u64,
+    /// Clicks still in memory when a shutdown flush failed
+    pub lost_clicks: u64,
+}
+
+/// Pending clicks per short code
+pub struct Clicks;
+
+impl CounterSpec for Clicks {
+    type Key = String;
+    type Count = u64;
+
+    const NAME: &'static str = "Click";
+
+    fn merge(count: &mut u64, other: u64) {
+        *count += other;
+    }
+
+    fn queue(pipe: &mut redis::Pipeline, short_code: &String, clicks: &u64) {
+        let key = click_counter_key(short_code);
+        pipe.incr(&key, *clicks)
+            .ignore()
+            .expire(&key, CLICK_COUNTER_TTL_SECONDS)
+            .ignore();
+    }
+}
+
+pub struct ClickCounter {
+    pending: PendingCounter<Clicks>,
+    flushed: AtomicU64,
+    flush_failures: AtomicU64,
+    lost: AtomicU64,
+}
+
+impl Default for ClickCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ClickCounter {
+    pub fn new() -> Self {
+        Self {
+            pending: PendingCounter::new(),
+            flushed: AtomicU64::new(0),
+            flush_failures: AtomicU64::new(0),
+            lost: AtomicU64::new(0),
+        }
+    }
+
+    /// Count one click; never blocks on I/O
+    pub fn record(&self, short_code: &str) {
+        self.pending.add(short_code, 1);
+    }
+
+    fn pending_clicks(&self) -> u64 {
+        self.pending.total(|clicks| *clicks)
+    }
+
+    /// Write pending clicks to Redis in one pipeline. On failure after retries the
+    /// clicks are put back and the error is returned.
+    pub async fn flush(&self, redis_pool: &RedisPool) -> Result<u64, redis::RedisError> {
+        match self.pending.flush(redis_pool, FLUSH_MAX_RETRIES).await {
+            Ok(written) => {
+                let clicks: u64 = written.values().sum();
+                self.flushed.fetch_add(clicks, Ordering::Relaxed);
+                Ok(clicks)
+            },
+            Err(e) => {
+                self.flush_failures.fetch_add(1, Ordering::Relaxed);
+                Err(e)
+            },
+        }
+    }
+
+    /// Final flush when the server stops; anything that can't be written is logged
+    /// and counted as lost
+    pub async fn flush_on_shutdown(&self, redis_pool: &RedisPool) {
+        match self.flush(redis_pool).await {
+            Ok(0) => {},
+            Ok(clicks) => info!("Flushed {} pending clicks on shutdown", clicks),
+            Err(_) => {
+                let lost = self.pending_clicks();
+                self.lost.fetch_add(lost, Ordering::Relaxed);
+                error!(
+                    "Shutting down with {} clicks that could not be written to Redis",
+                    lost
+                );
+            },
+        }
+    }
+
+    pub fn metrics(&self) -> ClickCounterMetrics {
+        ClickCounterMetrics {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            pending_clicks: self.pending_clicks(),
+            flushed_clicks: self.flushed.load(Ordering::Relaxed),
+            flush_failures: self.flush_failures.load(Ordering::Relaxed),
+            lost_clicks: self.lost.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Take every click count out of Redis, summed per short code.
+/// Each key is drained once with GETDEL, so an INCR racing the drain creates a fresh key for
+/// the next sync instead of being deleted unseen. Requires Redis 6.2+ for GETDEL.
+pub async fn drain_click_counters(
+    redis_pool: &RedisPool,
+) -> Result<HashMap<String, i64>, redis::RedisError> {
+    let keys = scan_keys(redis_pool, CLICK_COUNTER_PREFIX, DRAIN_BATCH_SIZE).await?;
+    let mut conn = redis_pool.get_connection().await?;
+    let mut counts: HashMap<String, i64> = HashMap::new();
+
+    for batch in keys.chunks(DRAIN_BATCH_SIZE) {
+        let mut pipe = redis::pipe();
+        for key in batch {
+            pipe.cmd("GETDEL").arg(key);
+        }
+        let values: Vec<Option<i64>> = pipe.query_async(&mut conn).await?;
+
+        for (key, value) in batch.iter().zip(values) {
+            match (short_code_from_key(key), value) {
+                (Some(short_code), Some(clicks)) if clicks > 0 => {
+                    *counts.entry(short_code.to_string()).or_insert(0) += clicks;
+                },
+                _ => {},
+            }
+        }
+    }
+
+    Ok(counts)
+}
+
+/// Put drained counts back as fallback keys so the next sync retries them
+pub async fn requeue_click_counts(
+    redis_pool: &RedisPool,
+    counts: &[(String, i64)],
+) -> Result<(), redis::RedisError> {
+    if counts.is_empty() {
+        return Ok(());
+    }
+
+    let mut conn = redis_pool.get_connection().await?;
+    let mut pipe = redis::pipe();
+    for (short_code, clicks) in counts {
+        let key = format!("{}{}", CLICK_FALLBACK_PREFIX, short_code);
+        pipe.incr(&key, *clicks)
+            .ignore()
+            .expire(&key, CLICK_COUNTER_TTL_SECONDS)
+            .ignore();
+    }
+    pipe.query_async::<()>(&mut conn).await
+}
+
+/// Flush pending clicks to Redis every `FLUSH_INTERVAL`
+pub fn spawn_click_flush_task(redis_pool: RedisPool) {
+    TASK_REGISTRY.spawn("click_flush", move |reporter| {
+        let redis_pool = redis_pool.clone();
+        async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                // Failures are logged, counted and retried on the next tick
+                match CLICK_COUNTER.flush(&redis_pool).await {
+                    Ok(_) => reporter.success(),
+                    Err(e) => reporter.failure(e),
+                }
+            }
+        }
+    });
+    info!(
+        "Click counter flush task started (every {}ms)",
+        FLUSH_INTERVAL.as_millis()
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_click_counter_aggregates_across_shards() {
+        let counter = ClickCounter::new();
+        for _ in 0..3 {
+            counter.record("abc123");
+        }
+        for i in 0..40 {
+            counter.record(&format!("code{}", i));
+        }
+        assert_eq!(counter.pending_clicks(), 43);
+
+        let pending = counter.pending.drain();
+        assert_eq!(pending.len(), 41);
+        assert_eq!(pending["abc123"], 3);
+        assert_eq!(counter.pending_clicks(), 0);
+        assert!(counter.pending.drain().is_empty());
+    }
+
+    #[test]
+    fn test_short_code_from_key() {
+        assert_eq!(short_code_from_key("clicks:abc123"), Some("abc123"));
+        assert_eq!(
+            short_code_from_key("clicks:fallback:abc123"),
+            Some("abc123")
+        );
+        assert_eq!(short_code_from_key("clicks:"), None);
+        assert_eq!(short_code_from_key("link:abc123"), None);
+    }
+
+    #[test]
+    fn test_click_counter_requeue_keeps_new_clicks() {
+        let counter = ClickCounter::new();
+        counter.record("abc123");
+        counter.record("abc123");
+        let failed = counter.pending.drain();
+
+        // Clicks recorded while the failed flush was in flight are merged, not replaced
+        counter.record("abc123");
+        counter.pending.requeue(failed);
+        assert_eq!(counter.pending.drain()["abc123"], 3);
+    }
+}
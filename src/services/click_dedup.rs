@@ -0,0 +1,113 @@
+// Click-fraud protection
+// A visitor clicking the same link again within CLICK_DEDUP_WINDOW_SECS is still redirected,
+// but the click is recorded with `is_repeat` set and left out of default click counts.
+// Visitors are identified by an HMAC of their IP address and user agent; only the HMAC is
+// stored, in Redis, for the length of the window.
+
+use ring::hmac;
+use std::net::IpAddr;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{app::AppState, db::RedisPool};
+
+#[derive(Clone)]
+pub struct ClickDeduplicator {
+    redis_pool: RedisPool,
+    window_secs: u64,
+    key: hmac::Key,
+}
+
+impl ClickDeduplicator {
+    /// `window_secs` of 0 disables deduplication: every click counts
+    pub fn new(redis_pool: RedisPool, window_secs: u64, secret: &str) -> Self {
+        Self {
+            redis_pool,
+            window_secs,
+            key: hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes()),
+        }
+    }
+
+    pub fn from_state(state: &AppState) -> Self {
+        Self::new(
+            state.redis_pool.clone(),
+            state.config.click_dedup_window_secs,
+            &state.config.jwt.access_secret,
+        )
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.window_secs > 0
+    }
+
+    /// Hex HMAC-SHA256 identifying a visitor without storing their IP address or user agent
+    pub fn visitor_hash(&self, ip: IpAddr, user_agent: &str) -> String {
+        visitor_hash(&self.key, ip, user_agent)
+    }
+
+    /// Whether the visitor already clicked this link within the window; the first click
+    /// opens the window (SET NX EX), later clicks don't extend it.
+    /// Redis outages fail open so no click is wrongly discounted.
+    pub async fn is_repeat(&self, link_id: Uuid, visitor_hash: &str) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        match self
+            .redis_pool
+            .set_nx_with_expiry(
+                &dedup_key(link_id, visitor_hash),
+                "1".to_string(),
+                self.window_secs as usize,
+            )
+            .await
+        {
+            Ok(first_click) => !first_click,
+            Err(e) => {
+                warn!("Click dedup unavailable for link {}: {}", link_id, e);
+                false
+            },
+        }
+    }
+}
+
+fn visitor_hash(key: &hmac::Key, ip: IpAddr, user_agent: &str) -> String {
+    hmac::sign(key, format!("{}|{}", ip, user_agent).as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+pub fn dedup_key(link_id: Uuid, visitor_hash: &str) -> String {
+    format!("click_dedup:{}:{}", link_id, visitor_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENT: &str = "Mozilla/5.0 (X11; Linux x86_64) Firefox/128.0";
+
+    #[test]
+    fn test_visitor_hash_is_stable_and_opaque() {
+        let key = hmac::Key::new(hmac::HMAC_SHA256, b"test-secret-at-least-32-bytes-long!!");
+        let ip: IpAddr = "203.0.113.7".parse().unwrap();
+
+        let hash = visitor_hash(&key, ip, USER_AGENT);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, visitor_hash(&key, ip, USER_AGENT));
+        assert!(!hash.contains("203.0.113.7"));
+
+        // Another device on the same network is another visitor
+        assert_ne!(hash, visitor_hash(&key, ip, "curl/8.5.0"));
+        assert_ne!(
+            hash,
+            visitor_hash(&key, "203.0.113.8".parse().unwrap(), USER_AGENT)
+        );
+
+        // Without the server secret the hash can't be recomputed from a guessed IP
+        let other_key = hmac::Key::new(hmac::HMAC_SHA256, b"another-secret-at-least-32-bytes!!");
+        assert_ne!(hash, visitor_hash(&other_key, ip, USER_AGENT));
+    }
+}
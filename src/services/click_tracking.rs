@@ -47,6 +47,20 @@ pub struct ClickEvent {
     pub is_bot: bool,
     pub bot_name: String,
 
+    // Repeat click from the same visitor within CLICK_DEDUP_WINDOW_SECS
+    pub is_repeat: bool,
+
+    // Path after the short code on path-passthrough links; empty otherwise
+    pub sub_path: String,
+
+    // Code the click came in through, as requested, and whether it matched the link's
+    // custom alias rather than its short code
+    pub short_code: String,
+    pub via_alias: bool,
+
+    // The link's referrer restrictions turned the visitor away (403, no redirect)
+    pub blocked_by_referrer: bool,
+
     // Performance metrics
     pub http_method: String, // LowCardinality(String) in CH
     pub response_time: u16,
@@ -56,8 +70,43 @@ pub struct ClickEvent {
     pub utm_source: String,   // LowCardinality(String) in CH
     pub utm_medium: String,   // LowCardinality(String) in CH
     pub utm_campaign: String, // LowCardinality(String) in CH
+
+    // Inbound campaign: UTM parameters of the referrer URL, not of the destination
+    pub referrer_utm_source: String,   // LowCardinality(String) in CH
+    pub referrer_utm_medium: String,   // LowCardinality(String) in CH
+    pub referrer_utm_campaign: String, // LowCardinality(String) in CH
+}
+
+/// What the visitor clicked: the code as requested, whether it was the link's custom alias,
+/// and the path after it on path-passthrough links (empty otherwise). `blocked_by_referrer`
+/// is set when the link's referrer restrictions refused the click.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClickedUrl<'a> {
+    pub short_code: &'a str,
+    pub via_alias: bool,
+    pub sub_path: &'a str,
+    pub blocked_by_referrer: bool,
+}
+
+/// The request behind a click and how it was answered. `opted_out` is set when the visitor
+/// sent `DNT: 1` or `Sec-GPC: 1`.
+#[derive(Debug, Clone, Copy)]
+pub struct ClickContext<'a> {
+    pub ip: IpAddr,
+    pub user_agent: &'a str,
+    pub referrer: Option<&'a str>,
+    pub method: &'a str,
+    pub response_time: u16,
+    pub status_code: u16,
+    pub opted_out: bool,
 }
 
+/// Referrers longer than this are stored but not parsed for UTM parameters
+const MAX_REFERRER_PARSE_LENGTH: usize = 2048;
+
+/// Longest UTM value kept from a referrer, in characters
+const MAX_REFERRER_UTM_LENGTH: usize = 100;
+
 // Note: ClickEventInsert struct removed - we use the SQL builder pattern instead
 // The Row derive approach has fundamental limitations with Option<T> fields
 // and byte count mismatches, so we use ClickHouseInsertBuilder for clean SQL generation
@@ -122,8 +171,9 @@ impl ClickEvent {
             String::new()
         };
 
-        // Extract UTM parameters from referrer if present
-        let (utm_source, utm_medium, utm_campaign) = Self::extract_utm_params(referrer);
+        // The campaign that brought the visitor to the short link
+        let (referrer_utm_source, referrer_utm_medium, referrer_utm_campaign) =
+            Self::referrer_utm_params(referrer);
 
         Self {
             event_id: Uuid::new_v4(),
@@ -147,37 +197,103 @@ impl ClickEvent {
             os_version,
             is_bot,
             bot_name,
+            is_repeat: false,
+            sub_path: String::new(),
+            short_code: String::new(),
+            via_alias: false,
+            blocked_by_referrer: false,
             http_method: method.to_string(),
             response_time: response_time_ms,
             status_code,
-            utm_source,
-            utm_medium,
-            utm_campaign,
+            utm_source: String::new(),
+            utm_medium: String::new(),
+            utm_campaign: String::new(),
+            referrer_utm_source,
+            referrer_utm_medium,
+            referrer_utm_campaign,
         }
     }
 
-    /// Extract UTM parameters from URL
-    fn extract_utm_params(referrer: Option<&str>) -> (String, String, String) {
-        if let Some(url_str) = referrer {
-            if let Ok(url) = url::Url::parse(url_str) {
-                let params: std::collections::HashMap<_, _> = url.query_pairs().collect();
-                return (
-                    params
-                        .get("utm_source")
-                        .map(|s| s.to_string())
-                        .unwrap_or_default(),
-                    params
-                        .get("utm_medium")
-                        .map(|s| s.to_string())
-                        .unwrap_or_default(),
-                    params
-                        .get("utm_campaign")
-                        .map(|s| s.to_string())
-                        .unwrap_or_default(),
-                );
-            }
+    /// Create a click event without identifying fields (anonymous and DNT-respecting links)
+    /// The click is still counted, but no IP address, user agent or referrer is stored
+    pub fn anonymous(link_id: Uuid, method: &str, response_time_ms: u16, status_code: u16) -> Self {
+        let timestamp = Utc::now();
+
+        Self {
+            event_id: Uuid::new_v4(),
+            link_id,
+            user_id: None,
+            timestamp,
+            date: timestamp.date_naive(),
+            ip_address: IpAddr::from(std::net::Ipv6Addr::UNSPECIFIED).to_string(),
+            user_agent: String::new(),
+            referrer: String::new(),
+            country: String::new(),
+            country_code: String::new(),
+            city: String::new(),
+            region: String::new(),
+            device_type: 0, // unknown
+            device_brand: String::new(),
+            device_model: String::new(),
+            browser: String::new(),
+            browser_version: String::new(),
+            os: String::new(),
+            os_version: String::new(),
+            is_bot: false,
+            bot_name: String::new(),
+            is_repeat: false,
+            sub_path: String::new(),
+            short_code: String::new(),
+            via_alias: false,
+            blocked_by_referrer: false,
+            http_method: method.to_string(),
+            response_time: response_time_ms,
+            status_code,
+            utm_source: String::new(),
+            utm_medium: String::new(),
+            utm_campaign: String::new(),
+            referrer_utm_source: String::new(),
+            referrer_utm_medium: String::new(),
+            referrer_utm_campaign: String::new(),
         }
-        (String::new(), String::new(), String::new())
+    }
+
+    /// (utm_source, utm_medium, utm_campaign) from the referrer URL's query string
+    /// Referrers come straight from the client: anything that isn't a reasonably sized
+    /// http(s) URL yields empty values, and each value is trimmed and capped in length.
+    fn referrer_utm_params(referrer: Option<&str>) -> (String, String, String) {
+        let empty = (String::new(), String::new(), String::new());
+        let Some(referrer) = referrer.filter(|r| r.len() <= MAX_REFERRER_PARSE_LENGTH) else {
+            return empty;
+        };
+        let Ok(url) = url::Url::parse(referrer) else {
+            return empty;
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return empty;
+        }
+
+        // The first occurrence of each parameter wins
+        let param = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| {
+                    value
+                        .chars()
+                        .filter(|c| !c.is_control())
+                        .collect::<String>()
+                        .trim()
+                        .chars()
+                        .take(MAX_REFERRER_UTM_LENGTH)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        (
+            param("utm_source"),
+            param("utm_medium"),
+            param("utm_campaign"),
+        )
     }
 }
 
@@ -383,3 +499,89 @@ pub async fn enrich_with_geoip(event: &mut ClickEvent) {
         event.city = "Unknown".to_string();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const USER_AGENT: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 Chrome/120.0 Safari/537.36";
+
+    #[test]
+    fn test_full_event_records_visitor() {
+        let event = ClickEvent::new(
+            Uuid::new_v4(),
+            "203.0.113.7".parse().unwrap(),
+            USER_AGENT,
+            Some("https://news.example.com/?utm_source=newsletter"),
+            "GET",
+            12,
+            301,
+        );
+
+        assert_eq!(event.ip_address, "203.0.113.7");
+        assert_eq!(event.user_agent, USER_AGENT);
+        assert_eq!(event.referrer_utm_source, "newsletter");
+        // utm_* describe the destination, never the referrer
+        assert!(event.utm_source.is_empty());
+    }
+
+    #[test]
+    fn test_referrer_utm_params() {
+        assert_eq!(
+            ClickEvent::referrer_utm_params(Some(
+                "https://blog.example.com/post?utm_source=twitter&utm_medium=social&utm_campaign=Spring%20Sale&utm_source=other"
+            )),
+            (
+                "twitter".to_string(),
+                "social".to_string(),
+                "Spring Sale".to_string()
+            )
+        );
+        let (_, _, campaign) =
+            ClickEvent::referrer_utm_params(Some("https://example.com/?utm_campaign=%20launch%0A"));
+        assert_eq!(campaign, "launch");
+
+        let long_value = "x".repeat(500);
+        let (source, _, _) = ClickEvent::referrer_utm_params(Some(&format!(
+            "https://example.com/?utm_source={}",
+            long_value
+        )));
+        assert_eq!(source.chars().count(), MAX_REFERRER_UTM_LENGTH);
+
+        let empty = (String::new(), String::new(), String::new());
+        for referrer in [
+            None,
+            Some(""),
+            Some("not a url"),
+            Some("android-app://com.example/?utm_source=app"),
+            Some("https://example.com/?utm_source"),
+            Some("https://example.com/%%%?utm_=x&=y"),
+        ] {
+            assert_eq!(
+                ClickEvent::referrer_utm_params(referrer),
+                empty,
+                "{:?}",
+                referrer
+            );
+        }
+        let oversized = format!(
+            "https://example.com/?utm_source=ok&pad={}",
+            "a".repeat(MAX_REFERRER_PARSE_LENGTH)
+        );
+        assert_eq!(ClickEvent::referrer_utm_params(Some(&oversized)), empty);
+    }
+
+    #[test]
+    fn test_anonymous_event_omits_identifying_fields() {
+        let link_id = Uuid::new_v4();
+        let event = ClickEvent::anonymous(link_id, "GET", 12, 301);
+
+        assert_eq!(event.link_id, link_id);
+        assert_eq!(event.ip_address, "::");
+        assert!(event.user_agent.is_empty());
+        assert!(event.referrer.is_empty());
+        assert!(event.browser.is_empty() && event.os.is_empty());
+        assert_eq!(event.status_code, 301);
+    }
+}
@@ -2,15 +2,93 @@
 // Unified service for ALL ClickHouse operations - analytics and event tracking
 // Built on top of the ClickHouse Query Builder for clean abstraction
 
-use crate::db::{ClickHouseClient, ClickHouseQueryBuilder, SingleLinkStats};
+use crate::app::AppState;
+use crate::db::{
+    BreakdownRow, CampaignRow, ClickHouseClient, ClickHouseQueryBuilder, ConversionTotalsRow,
+    EntryPointRow, RangeComparisonRow, RangeStatsRow, RateLimitEndpointRow, RateLimitSummaryRow,
+    RateLimitTierRow, SecurityScanSummaryRow, SingleLinkStats, StatsSource, TimeSeriesRow,
+    UsageSummaryRow,
+};
+use crate::models::analytics::{SecurityScanMetricsResponse, ThreatTypeCount, TopBlockedDomain};
+use crate::models::link::{
+    conversion_rate, CampaignClicks, ClickBreakdown, ClickEntryPoints, DailyClickCount,
+    DailyConversions, LinkConversionStatsResponse, LinkStatsComparison, LinkStatsRange,
+    LinkStatsWindow, PeriodChange,
+};
+use crate::services::analytics::{EndpointMetrics, MetricsBackend, RateLimitMetrics, TierMetrics};
+use crate::services::background_tasks::TASK_REGISTRY;
 use crate::services::click_tracking::ClickEvent;
 use crate::services::link::LinkClickStats;
-use std::collections::HashMap;
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// Countries and referrers returned with range stats
+const RANGE_BREAKDOWN_LIMIT: u32 = 10;
+
+/// Inbound campaigns returned by the campaign breakdown
+const CAMPAIGN_BREAKDOWN_LIMIT: u32 = 50;
+
+/// Domains returned with security scan metrics
+const TOP_BLOCKED_DOMAINS_LIMIT: u32 = 10;
+
+/// Clicks behind an account's monthly usage summary
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UsageClicks {
+    pub total_clicks: u64,
+    pub unique_visitors: u64,
+    /// (link_id, clicks), most-clicked first
+    pub links: Vec<(Uuid, u64)>,
+    pub top_referrer: Option<String>,
+    pub top_country: Option<String>,
+}
+
+impl UsageClicks {
+    /// Fold (dimension, key, clicks, unique_visitors) rows; breakdowns are ranked by clicks
+    pub fn from_rows(rows: Vec<UsageSummaryRow>) -> Self {
+        let mut usage = UsageClicks::default();
+        let mut referrers = Vec::new();
+        let mut countries = Vec::new();
+
+        for (dimension, key, clicks, unique_visitors) in rows {
+            match dimension.as_str() {
+                "total" => {
+                    usage.total_clicks = clicks;
+                    usage.unique_visitors = unique_visitors;
+                },
+                "link" => {
+                    if let Ok(link_id) = key.parse() {
+                        usage.links.push((link_id, clicks));
+                    }
+                },
+                "referrer" if !key.is_empty() => referrers.push((key, clicks)),
+                "country" if !key.is_empty() => countries.push((key, clicks)),
+                _ => {},
+            }
+        }
+
+        usage
+            .links
+            .sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        usage.top_referrer = top_key(referrers);
+        usage.top_country = top_key(countries);
+        usage
+    }
+}
+
+/// Most-clicked key; ties go to the alphabetically first key so results are stable
+fn top_key(entries: Vec<(String, u64)>) -> Option<String> {
+    entries
+        .into_iter()
+        .filter(|(_, clicks)| *clicks > 0)
+        .min_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)))
+        .map(|(key, _)| key)
+}
+
 /// Unified ClickHouse service for analytics and event tracking
 pub struct ClickHouseAnalyticsService {
     client: Arc<ClickHouseClient>,
@@ -77,10 +155,11 @@ impl ClickHouseAnalyticsService {
             .await;
 
         match stats_result {
-            Ok((total_clicks, unique_visitors, bot_clicks)) => {
-                if total_clicks > 0 || unique_visitors > 0 || bot_clicks > 0 {
+            Ok((total_clicks, raw_clicks, unique_visitors, bot_clicks)) => {
+                if raw_clicks > 0 || unique_visitors > 0 || bot_clicks > 0 {
                     Some(LinkClickStats {
                         total_clicks,
+                        raw_clicks,
                         unique_visitors,
                         bot_clicks,
                         last_accessed_at: Some(chrono::Utc::now()),
@@ -143,6 +222,18 @@ impl ClickHouseAnalyticsService {
         }
     }
 
+    /// Redirects served across all links since tracking began
+    pub async fn get_total_redirects(&self) -> Result<u64, String> {
+        let query = self.query_builder.build_total_redirects_query();
+
+        self.client
+            .client()
+            .query(&query)
+            .fetch_one::<u64>()
+            .await
+            .map_err(|e| format!("Total redirects query failed: {:?}", e))
+    }
+
     /// Get top performing links for a user
     pub async fn get_top_links(&self, user_id: Option<&Uuid>, limit: u32) -> Vec<(Uuid, u64)> {
         let _query = self.query_builder.build_top_links_query(user_id, limit);
@@ -153,6 +244,512 @@ impl ClickHouseAnalyticsService {
         vec![]
     }
 
+    /// Statistics for a date range, read from raw events or daily rollups
+    /// With `window.compare` the totals query also covers the previous period.
+    /// Raw events are counted into days in `tz`; rollups only hold UTC days.
+    pub async fn get_link_stats_for_range(
+        &self,
+        link_id: &Uuid,
+        window: &LinkStatsWindow,
+        source: StatsSource,
+        tz: Tz,
+    ) -> Result<(LinkClickStats, LinkStatsRange, Option<LinkStatsComparison>), String> {
+        let client = self.client.client();
+        let LinkStatsWindow { from, to, .. } = *window;
+        let tz = match source {
+            StatsSource::Raw => tz,
+            StatsSource::Rollup => Tz::UTC,
+        };
+        let query_builder = self.query_builder.in_timezone(tz);
+
+        let (totals, comparison) = self
+            .fetch_range_totals(&query_builder, link_id, window, source)
+            .await?;
+
+        let daily = client
+            .query(&query_builder.build_range_daily_clicks(link_id, from, to, source))
+            .fetch_all::<(String, u64, u64)>()
+            .await
+            .map_err(|e| format!("Daily clicks query failed: {:?}", e))?
+            .into_iter()
+            .filter_map(|(date, clicks, unique_visitors)| {
+                Some(DailyClickCount {
+                    date: date.parse().ok()?,
+                    clicks,
+                    unique_visitors,
+                })
+            })
+            .collect();
+
+        let top_countries = self
+            .fetch_breakdown(&query_builder.build_range_top_countries(
+                link_id,
+                from,
+                to,
+                source,
+                RANGE_BREAKDOWN_LIMIT,
+            ))
+            .await?;
+        let top_referrers = self
+            .fetch_breakdown(&query_builder.build_range_top_referrers(
+                link_id,
+                from,
+                to,
+                source,
+                RANGE_BREAKDOWN_LIMIT,
+            ))
+            .await?;
+
+        let range = LinkStatsRange {
+            from,
+            to,
+            source: source.as_str().to_string(),
+            timezone: tz.name().to_string(),
+            daily,
+            top_countries,
+            top_referrers,
+        };
+        Ok((totals, range, comparison))
+    }
+
+    /// Range totals, plus the previous period from the same scan when comparing
+    async fn fetch_range_totals(
+        &self,
+        query_builder: &ClickHouseQueryBuilder,
+        link_id: &Uuid,
+        window: &LinkStatsWindow,
+        source: StatsSource,
+    ) -> Result<(LinkClickStats, Option<LinkStatsComparison>), String> {
+        let client = self.client.client();
+
+        if !window.compare {
+            let (total_clicks, raw_clicks, unique_visitors, bot_clicks) = client
+                .query(&query_builder.build_range_stats(link_id, window.from, window.to, source))
+                .fetch_one::<RangeStatsRow>()
+                .await
+                .map_err(|e| format!("Range stats query failed: {:?}", e))?;
+            let totals = LinkClickStats {
+                total_clicks,
+                raw_clicks,
+                unique_visitors,
+                bot_clicks,
+                last_accessed_at: None,
+            };
+            return Ok((totals, None));
+        }
+
+        let (previous_from, previous_to) = window.previous_period();
+        let (total_clicks, raw_clicks, unique_visitors, bot_clicks, prev_clicks, prev_visitors) =
+            client
+                .query(&query_builder.build_range_stats_with_comparison(
+                    link_id,
+                    previous_from,
+                    window.from,
+                    window.to,
+                    source,
+                ))
+                .fetch_one::<RangeComparisonRow>()
+                .await
+                .map_err(|e| format!("Range comparison query failed: {:?}", e))?;
+
+        let totals = LinkClickStats {
+            total_clicks,
+            raw_clicks,
+            unique_visitors,
+            bot_clicks,
+            last_accessed_at: None,
+        };
+        let comparison = LinkStatsComparison {
+            previous_from,
+            previous_to,
+            clicks: PeriodChange::new(total_clicks, prev_clicks),
+            unique_visitors: PeriodChange::new(unique_visitors, prev_visitors),
+        };
+        Ok((totals, Some(comparison)))
+    }
+
+    async fn fetch_breakdown(&self, query: &str) -> Result<Vec<ClickBreakdown>, String> {
+        let rows = self
+            .client
+            .client()
+            .query(query)
+            .fetch_all::<BreakdownRow>()
+            .await
+            .map_err(|e| format!("Breakdown query failed: {:?}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(value, clicks)| ClickBreakdown { value, clicks })
+            .collect())
+    }
+
+    /// Clicks per inbound campaign (referrer UTM parameters) over a date range of raw events
+    pub async fn get_referrer_campaigns(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Vec<CampaignClicks>, String> {
+        let rows = self
+            .client
+            .client()
+            .query(&self.query_builder.build_referrer_campaigns(
+                link_id,
+                from,
+                to,
+                CAMPAIGN_BREAKDOWN_LIMIT,
+            ))
+            .fetch_all::<CampaignRow>()
+            .await
+            .map_err(|e| format!("Campaign breakdown query failed: {:?}", e))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(campaign, source, medium, clicks)| CampaignClicks {
+                campaign,
+                source,
+                medium,
+                clicks,
+            })
+            .collect())
+    }
+
+    /// Clicks through the short code versus the custom alias over a date range (days in `tz`)
+    /// of raw events
+    pub async fn get_entry_points(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        tz: Tz,
+    ) -> Result<ClickEntryPoints, String> {
+        let query = self
+            .query_builder
+            .in_timezone(tz)
+            .build_entry_points(link_id, from, to);
+        let (short_code, alias, unknown) = self
+            .client
+            .client()
+            .query(&query)
+            .fetch_one::<EntryPointRow>()
+            .await
+            .map_err(|e| format!("Entry point query failed: {:?}", e))?;
+
+        Ok(ClickEntryPoints {
+            short_code,
+            alias,
+            unknown,
+        })
+    }
+
+    /// Conversion pixel hits against clicks over a UTC date range
+    /// Clicks come from `source`; conversions are never rolled up or pruned
+    pub async fn get_link_conversions(
+        &self,
+        link_id: &Uuid,
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+    ) -> Result<LinkConversionStatsResponse, String> {
+        let client = self.client.client();
+
+        let daily_clicks = client
+            .query(
+                &self
+                    .query_builder
+                    .build_range_daily_clicks(link_id, from, to, source),
+            )
+            .fetch_all::<TimeSeriesRow>()
+            .await
+            .map_err(|e| format!("Daily clicks query failed: {:?}", e))?;
+        let daily_conversions = client
+            .query(
+                &self
+                    .query_builder
+                    .build_daily_conversions(link_id, from, to),
+            )
+            .fetch_all::<TimeSeriesRow>()
+            .await
+            .map_err(|e| format!("Daily conversions query failed: {:?}", e))?;
+        let (conversions, unique_converters) = client
+            .query(
+                &self
+                    .query_builder
+                    .build_conversion_totals(link_id, from, to),
+            )
+            .fetch_one::<ConversionTotalsRow>()
+            .await
+            .map_err(|e| format!("Conversion totals query failed: {:?}", e))?;
+
+        let daily = merge_daily_conversions(daily_clicks, daily_conversions);
+        let clicks = daily.iter().map(|day| day.clicks).sum();
+        Ok(LinkConversionStatsResponse {
+            from,
+            to,
+            clicks,
+            conversions,
+            unique_converters,
+            conversion_rate: conversion_rate(conversions, clicks),
+            daily,
+        })
+    }
+
+    /// Clicks across a set of links for a date range, in one aggregate query
+    /// Raw events are counted into days in `tz`; rollups only hold UTC days.
+    pub async fn get_usage_clicks(
+        &self,
+        link_ids: &[Uuid],
+        from: NaiveDate,
+        to: NaiveDate,
+        source: StatsSource,
+        tz: Tz,
+    ) -> Result<UsageClicks, String> {
+        if link_ids.is_empty() {
+            return Ok(UsageClicks::default());
+        }
+
+        let query = self
+            .query_builder
+            .in_timezone(tz)
+            .build_usage_summary(link_ids, from, to, source);
+        let rows = self
+            .client
+            .client()
+            .query(&query)
+            .fetch_all::<UsageSummaryRow>()
+            .await
+            .map_err(|e| format!("Usage summary query failed: {:?}", e))?;
+
+        Ok(UsageClicks::from_rows(rows))
+    }
+
+    /// Block rate, threat types, top blocked domains and p95 latency of recorded security scans
+    pub async fn get_security_scan_metrics(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        sample_rate: f64,
+    ) -> Result<SecurityScanMetricsResponse, String> {
+        let client = self.client.client();
+
+        let (total_scans, blocked_scans, trusted_scans, p95_duration_ms) = client
+            .query(&self.query_builder.build_security_scan_summary(from, to))
+            .fetch_one::<SecurityScanSummaryRow>()
+            .await
+            .map_err(|e| format!("Security scan summary query failed: {:?}", e))?;
+
+        let threat_types = client
+            .query(&self.query_builder.build_security_scan_threats(from, to))
+            .fetch_all::<BreakdownRow>()
+            .await
+            .map_err(|e| format!("Security scan threats query failed: {:?}", e))?
+            .into_iter()
+            .map(|(threat_type, scans)| ThreatTypeCount { threat_type, scans })
+            .collect();
+
+        let top_blocked_domains = client
+            .query(&self.query_builder.build_top_blocked_domains(
+                from,
+                to,
+                TOP_BLOCKED_DOMAINS_LIMIT,
+            ))
+            .fetch_all::<BreakdownRow>()
+            .await
+            .map_err(|e| format!("Top blocked domains query failed: {:?}", e))?
+            .into_iter()
+            .map(|(domain, blocked_scans)| TopBlockedDomain {
+                domain,
+                blocked_scans,
+            })
+            .collect();
+
+        Ok(SecurityScanMetricsResponse {
+            from,
+            to,
+            sample_rate,
+            total_scans,
+            blocked_scans,
+            trusted_scans,
+            block_rate: if total_scans == 0 {
+                0.0
+            } else {
+                blocked_scans as f64 / total_scans as f64
+            },
+            p95_scan_duration_ms: (total_scans > 0).then_some(p95_duration_ms),
+            threat_types,
+            top_blocked_domains,
+        })
+    }
+
+    /// Rate limiting metrics of every instance over [from, to), from the sampled events
+    pub async fn get_rate_limit_metrics(
+        &self,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+    ) -> Result<RateLimitMetrics, String> {
+        let client = self.client.client();
+
+        let (total_checks, total_blocked, avg_latency_ms, p95_latency_ms, p99_latency_ms) = client
+            .query(&self.query_builder.build_rate_limit_summary(from, to))
+            .fetch_one::<RateLimitSummaryRow>()
+            .await
+            .map_err(|e| format!("Rate limit summary query failed: {:?}", e))?;
+
+        let endpoint_metrics = client
+            .query(&self.query_builder.build_rate_limit_endpoints(from, to))
+            .fetch_all::<RateLimitEndpointRow>()
+            .await
+            .map_err(|e| format!("Rate limit endpoint query failed: {:?}", e))?
+            .into_iter()
+            .map(
+                |(endpoint, total_requests, blocked_requests, avg_latency_ms)| {
+                    let block_rate = if total_requests > 0 {
+                        (blocked_requests as f64 / total_requests as f64) * 100.0
+                    } else {
+                        0.0
+                    };
+                    (
+                        endpoint.clone(),
+                        EndpointMetrics {
+                            endpoint,
+                            total_requests,
+                            blocked_requests,
+                            avg_latency_ms,
+                            block_rate,
+                        },
+                    )
+                },
+            )
+            .collect();
+
+        let tier_metrics = client
+            .query(&self.query_builder.build_rate_limit_tiers(from, to))
+            .fetch_all::<RateLimitTierRow>()
+            .await
+            .map_err(|e| format!("Rate limit tier query failed: {:?}", e))?
+            .into_iter()
+            .map(
+                |(tier, total_requests, blocked_requests, unique_users, avg_latency_ms)| {
+                    (
+                        tier.clone(),
+                        TierMetrics {
+                            tier,
+                            total_requests,
+                            blocked_requests,
+                            unique_users,
+                            avg_latency_ms,
+                        },
+                    )
+                },
+            )
+            .collect();
+
+        Ok(RateLimitMetrics {
+            total_checks,
+            total_blocked,
+            avg_latency_ms,
+            p95_latency_ms,
+            p99_latency_ms,
+            endpoint_metrics,
+            tier_metrics,
+            window_start: from,
+            window_end: to,
+            backend: MetricsBackend::Clickhouse,
+        })
+    }
+
+    // =============================================================================
+    // ROLLUPS AND RAW EVENT RETENTION
+    // =============================================================================
+
+    /// Re-roll recent days into the daily rollup tables
+    /// Starts the day before the latest rollup (late events), or at the oldest raw event on first run.
+    /// Returns the first day that was rolled up.
+    pub async fn refresh_daily_rollups(&self, today: NaiveDate) -> Result<NaiveDate, String> {
+        let from = match self
+            .fetch_date(&self.query_builder.build_latest_rollup_date())
+            .await?
+        {
+            Some(latest) => latest.pred_opt().unwrap_or(latest).min(today),
+            None => self
+                .fetch_date(&self.query_builder.build_earliest_event_date())
+                .await?
+                .unwrap_or(today),
+        };
+
+        for statement in self.query_builder.build_daily_rollups(from, today) {
+            self.client
+                .client()
+                .query(&statement)
+                .execute()
+                .await
+                .map_err(|e| format!("Daily rollup failed: {:?}", e))?;
+        }
+
+        Ok(from)
+    }
+
+    /// Most recent day present in the rollup tables
+    pub async fn latest_rollup_date(&self) -> Result<Option<NaiveDate>, String> {
+        self.fetch_date(&self.query_builder.build_latest_rollup_date())
+            .await
+    }
+
+    /// Drop raw event partitions that end before `before`; returns the number dropped
+    /// Callers must make sure those days are already rolled up
+    pub async fn prune_raw_events(&self, before: NaiveDate) -> Result<usize, String> {
+        let partitions = self
+            .client
+            .client()
+            .query(&self.query_builder.build_prunable_partitions(before))
+            .fetch_all::<String>()
+            .await
+            .map_err(|e| format!("Partition lookup failed: {:?}", e))?;
+
+        for partition in &partitions {
+            self.client
+                .client()
+                .query(&self.query_builder.build_drop_partition(partition))
+                .execute()
+                .await
+                .map_err(|e| format!("Dropping partition {} failed: {:?}", partition, e))?;
+        }
+
+        Ok(partitions.len())
+    }
+
+    /// Erase every stored click for a link, raw events and aggregates
+    /// Mutations run synchronously, so the events are gone once this returns
+    pub async fn delete_link_events(&self, link_id: &Uuid) -> Result<(), String> {
+        for statement in self.query_builder.build_delete_link_events(link_id) {
+            self.client
+                .client()
+                .query(&statement)
+                .with_option("mutations_sync", "2")
+                .execute()
+                .await
+                .map_err(|e| format!("Deleting click data for {} failed: {:?}", link_id, e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Run a `toString(<date>)` query; ClickHouse returns 1970-01-01 for empty aggregates
+    async fn fetch_date(&self, query: &str) -> Result<Option<NaiveDate>, String> {
+        let value = self
+            .client
+            .client()
+            .query(query)
+            .fetch_one::<String>()
+            .await
+            .map_err(|e| format!("Date query failed: {:?}", e))?;
+
+        Ok(value
+            .parse::<NaiveDate>()
+            .ok()
+            .filter(|date| *date != NaiveDate::default()))
+    }
+
     // =============================================================================
     // EVENT TRACKING METHODS (unified from ClickTrackingService)
     // =============================================================================
@@ -252,8 +849,223 @@ impl ClickHouseAnalyticsService {
     }
 }
 
+/// Join per-day (date, clicks, _) and (date, conversions, _) rows into one series, oldest first
+fn merge_daily_conversions(
+    clicks: Vec<TimeSeriesRow>,
+    conversions: Vec<TimeSeriesRow>,
+) -> Vec<DailyConversions> {
+    let mut days: BTreeMap<NaiveDate, DailyConversions> = BTreeMap::new();
+    let parse = |rows: Vec<TimeSeriesRow>| {
+        rows.into_iter()
+            .filter_map(|(date, count, _)| Some((date.parse::<NaiveDate>().ok()?, count)))
+    };
+
+    for (date, clicks) in parse(clicks) {
+        days.entry(date)
+            .or_insert(DailyConversions {
+                date,
+                clicks: 0,
+                conversions: 0,
+            })
+            .clicks = clicks;
+    }
+    for (date, conversions) in parse(conversions) {
+        days.entry(date)
+            .or_insert(DailyConversions {
+                date,
+                clicks: 0,
+                conversions: 0,
+            })
+            .conversions = conversions;
+    }
+
+    days.into_values().collect()
+}
+
+/// First day still guaranteed to be in raw events; `None` when raw events are kept forever
+pub fn raw_retention_cutoff(today: NaiveDate, retention_days: u32) -> Option<NaiveDate> {
+    if retention_days == 0 {
+        return None;
+    }
+    today.checked_sub_days(chrono::Days::new(u64::from(retention_days)))
+}
+
+/// Ranges starting before the raw retention cutoff are served from rollups
+pub fn stats_source_for(from: NaiveDate, today: NaiveDate, retention_days: u32) -> StatsSource {
+    match raw_retention_cutoff(today, retention_days) {
+        Some(cutoff) if from < cutoff => StatsSource::Rollup,
+        _ => StatsSource::Raw,
+    }
+}
+
+/// Periodically refresh daily rollups and drop raw events past CLICKHOUSE_RAW_RETENTION_DAYS
+pub fn spawn_click_rollup_task(state: AppState) {
+    let Some(analytics) = state.clickhouse_analytics.clone() else {
+        info!("ClickHouse not configured, click rollups disabled");
+        return;
+    };
+    let retention_days = state.config.clickhouse.raw_retention_days;
+    let interval_secs = state.config.clickhouse.rollup_interval_secs;
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("click_rollup", redis_pool, move |reporter| {
+        let analytics = Arc::clone(&analytics);
+        async move {
+            let mut interval =
+                tokio::time::interval(tokio::time::Duration::from_secs(interval_secs));
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                let today = chrono::Utc::now().date_naive();
+
+                if let Err(e) = analytics.refresh_daily_rollups(today).await {
+                    error!("Click rollup failed: {}", e);
+                    reporter.failure(e);
+                    // Never prune raw events that might not be rolled up yet
+                    continue;
+                }
+
+                let Some(cutoff) = raw_retention_cutoff(today, retention_days) else {
+                    reporter.success();
+                    continue;
+                };
+
+                // The day before the latest rollup may still be re-rolled, keep it
+                let rolled_through = match analytics.latest_rollup_date().await {
+                    Ok(Some(latest)) => latest.pred_opt().unwrap_or(latest),
+                    Ok(None) => {
+                        reporter.success();
+                        continue;
+                    },
+                    Err(e) => {
+                        error!("Click rollup lookup failed: {}", e);
+                        reporter.failure(e);
+                        continue;
+                    },
+                };
+
+                match analytics.prune_raw_events(cutoff.min(rolled_through)).await {
+                    Ok(dropped) => {
+                        if dropped > 0 {
+                            info!(
+                                "Dropped {} raw click event partitions older than {} days",
+                                dropped, retention_days
+                            );
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Raw click event pruning failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
 /// Factory function to create ClickHouse Analytics Service
 pub fn create_clickhouse_analytics_service() -> ClickHouseAnalyticsService {
     let client = crate::db::clickhouse_client::create_clickhouse_client();
     ClickHouseAnalyticsService::new(client)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_usage_clicks_from_rows() {
+        let popular = Uuid::new_v4();
+        let quiet = Uuid::new_v4();
+        let row = |dimension: &str, key: &str, clicks: u64| {
+            (dimension.to_string(), key.to_string(), clicks, 0)
+        };
+
+        let usage = UsageClicks::from_rows(vec![
+            ("total".to_string(), String::new(), 12, 9),
+            row("link", &quiet.to_string(), 3),
+            row("link", &popular.to_string(), 9),
+            row("referrer", "", 7),
+            row("referrer", "b.com", 3),
+            row("referrer", "a.com", 3),
+            row("country", "", 2),
+            row("country", "DE", 10),
+        ]);
+
+        assert_eq!(usage.total_clicks, 12);
+        assert_eq!(usage.unique_visitors, 9);
+        assert_eq!(usage.links, vec![(popular, 9), (quiet, 3)]);
+        assert_eq!(usage.top_referrer.as_deref(), Some("a.com"));
+        assert_eq!(usage.top_country.as_deref(), Some("DE"));
+    }
+
+    #[test]
+    fn test_merge_daily_conversions() {
+        let row = |date: &str, count: u64| (date.to_string(), count, 0);
+
+        let daily = merge_daily_conversions(
+            vec![row("2024-06-01", 20), row("2024-06-03", 5)],
+            vec![row("2024-06-02", 1), row("2024-06-01", 2)],
+        );
+        assert_eq!(
+            daily,
+            vec![
+                DailyConversions {
+                    date: day("2024-06-01"),
+                    clicks: 20,
+                    conversions: 2
+                },
+                DailyConversions {
+                    date: day("2024-06-02"),
+                    clicks: 0,
+                    conversions: 1
+                },
+                DailyConversions {
+                    date: day("2024-06-03"),
+                    clicks: 5,
+                    conversions: 0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_raw_retention_cutoff() {
+        assert_eq!(
+            raw_retention_cutoff(day("2024-04-30"), 90),
+            Some(day("2024-01-31"))
+        );
+        assert_eq!(raw_retention_cutoff(day("2024-04-30"), 0), None);
+    }
+
+    #[test]
+    fn test_stats_source_switches_to_rollups_beyond_retention() {
+        let today = day("2024-04-30");
+
+        assert_eq!(
+            stats_source_for(day("2024-04-01"), today, 90),
+            StatsSource::Raw
+        );
+        assert_eq!(
+            stats_source_for(day("2024-01-31"), today, 90),
+            StatsSource::Raw
+        );
+        assert_eq!(
+            stats_source_for(day("2024-01-30"), today, 90),
+            StatsSource::Rollup
+        );
+        // Raw events kept forever
+        assert_eq!(
+            stats_source_for(day("2020-01-01"), today, 0),
+            StatsSource::Raw
+        );
+    }
+}
@@ -0,0 +1,183 @@
+// Conversion pixels
+// A tracked redirect sets the `qck_vid` cookie, scoped to the link's pixel path, holding the
+// visitor hash. When the destination site loads GET /v1/pixel/{link_id}.gif the browser sends
+// it back and the hit is recorded in ClickHouse `link_conversions`. Recording never waits on
+// ClickHouse and never touches Postgres: hits go through a bounded queue and are dropped when
+// it is full.
+
+use axum::http::{header, HeaderMap};
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, warn};
+use uuid::Uuid;
+
+use crate::db::ClickHouseClient;
+
+/// Table the recorder writes to
+pub const LINK_CONVERSIONS_TABLE: &str = "link_conversions";
+
+/// Cookie carrying the visitor hash from the redirect to the pixel
+pub const VISITOR_COOKIE: &str = "qck_vid";
+
+/// 1×1 transparent GIF served by the pixel
+pub const TRANSPARENT_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+/// Conversions waiting to be written; further hits are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+/// Conversions written per INSERT
+const BATCH_SIZE: usize = 500;
+/// Longest a conversion waits before its batch is written
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+static RECORDER: OnceCell<mpsc::Sender<ConversionEvent>> = OnceCell::new();
+
+/// One row of `link_conversions`
+#[derive(Debug, Clone)]
+pub struct ConversionEvent {
+    pub timestamp: DateTime<Utc>,
+    pub link_id: Uuid,
+    /// Hex HMAC-SHA256 visitor hash from the `qck_vid` cookie
+    pub visitor_hash: String,
+}
+
+/// Path of a link's pixel, which is also the only path its cookie is sent to
+pub fn pixel_path(base_path: &str, link_id: Uuid) -> String {
+    format!("{}/v1/pixel/{}.gif", base_path, link_id)
+}
+
+/// `Set-Cookie` value for a tracked redirect
+/// The pixel is loaded from the destination site, so the cookie has to be `SameSite=None`
+/// (and therefore `Secure`) to be sent back at all
+pub fn visitor_cookie(visitor_hash: &str, pixel_path: &str, max_age_days: u32) -> String {
+    format!(
+        "{}={}; Path={}; Max-Age={}; HttpOnly; Secure; SameSite=None",
+        VISITOR_COOKIE,
+        visitor_hash,
+        pixel_path,
+        u64::from(max_age_days) * 86_400
+    )
+}
+
+/// Visitor hash from the request's `qck_vid` cookie; `None` when absent or malformed
+pub fn visitor_hash_from_cookies(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == VISITOR_COOKIE)
+        .map(|(_, value)| value)
+        .filter(|value| {
+            value.len() == 64
+                && value
+                    .bytes()
+                    .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        })
+}
+
+/// Queue a conversion; never blocks
+pub fn record_conversion(client: &Arc<ClickHouseClient>, event: ConversionEvent) {
+    let sender = RECORDER.get_or_init(|| spawn_writer(client.clone()));
+    if let Err(e) = sender.try_send(event) {
+        debug!("Dropping link conversion: {}", e);
+    }
+}
+
+/// Batch queued conversions into ClickHouse until the process exits
+fn spawn_writer(client: Arc<ClickHouseClient>) -> mpsc::Sender<ConversionEvent> {
+    let (tx, mut rx) = mpsc::channel::<ConversionEvent>(QUEUE_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    let Some(event) = event else { break };
+                    batch.push(event);
+                    if batch.len() >= BATCH_SIZE {
+                        write_batch(&client, &mut batch).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    if !batch.is_empty() {
+                        write_batch(&client, &mut batch).await;
+                    }
+                }
+            }
+        }
+
+        warn!("Link conversion queue closed");
+    });
+
+    tx
+}
+
+async fn write_batch(client: &ClickHouseClient, batch: &mut Vec<ConversionEvent>) {
+    let events = std::mem::take(batch);
+    if let Err(e) = crate::db::clickhouse_insert_builder::insert_link_conversions(
+        client.client(),
+        LINK_CONVERSIONS_TABLE,
+        &events,
+    )
+    .await
+    {
+        error!("Failed to write {} link conversions: {}", events.len(), e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "3f1a9c0b7e4a52c3d9e1f8a6b5d2c7f9e1a34c2b8e0d4f1a7e3b9d6c8a5e2f4b";
+
+    fn cookies(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::COOKIE, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn test_visitor_cookie_is_scoped_to_the_pixel() {
+        let link_id = Uuid::parse_str("123e4567-e89b-12d3-a456-426614174000").unwrap();
+        let path = pixel_path("/api", link_id);
+        assert_eq!(
+            path,
+            "/api/v1/pixel/123e4567-e89b-12d3-a456-426614174000.gif"
+        );
+
+        let cookie = visitor_cookie(HASH, &path, 30);
+        assert!(cookie.starts_with(&format!("qck_vid={}; ", HASH)));
+        assert!(cookie.contains(&format!("Path={};", path)));
+        assert!(cookie.contains("Max-Age=2592000;"));
+        assert!(cookie.ends_with("HttpOnly; Secure; SameSite=None"));
+    }
+
+    #[test]
+    fn test_visitor_hash_from_cookies() {
+        assert_eq!(
+            visitor_hash_from_cookies(&cookies(&format!("theme=dark; qck_vid={}", HASH))),
+            Some(HASH)
+        );
+        assert_eq!(visitor_hash_from_cookies(&HeaderMap::new()), None);
+        // Anything but a visitor hash is ignored rather than recorded
+        for bad in [
+            "qck_vid=abc".to_string(),
+            format!("qck_vid={}", HASH.to_uppercase()),
+            format!("qck_vid={}'", &HASH[..63]),
+            format!("other_vid={}", HASH),
+        ] {
+            assert_eq!(visitor_hash_from_cookies(&cookies(&bad)), None, "{}", bad);
+        }
+    }
+}
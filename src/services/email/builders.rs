@@ -2,9 +2,14 @@
 // Each builder knows how to construct its specific email type
 
 use super::types::{
-    EmailBuilder, EmailError, EmailMessage, PasswordChangedEmailData, PasswordResetEmailData,
+    AccountExportReadyEmailData, AccountLockedEmailData, DigestTopLink, EmailBuilder, EmailError,
+    EmailMessage, LinkQuarantinedEmailData, MonthlyDigestEmailData,
+    OrganizationInvitationEmailData, PasswordChangedEmailData, PasswordResetEmailData,
+    SessionsRevokedEmailData, TokenReuseEmailData,
 };
 use crate::app_config::EmailConfig;
+use crate::models::analytics::UsageSummaryResponse;
+use crate::models::security_incident::SecurityIncident;
 use handlebars::Handlebars;
 use tracing::instrument;
 
@@ -189,6 +194,8 @@ mod tests {
             support_email: "support@example.com".to_string(),
             frontend_url: "https://app.example.com".to_string(),
             dashboard_url: "https://dashboard.example.com".to_string(),
+            short_link_base_url: "https://sho.rt".to_string(),
+            monthly_digest_enabled: true,
             verification_code_ttl: 900,
             verification_max_attempts: 5,
             resend_limit: 10,
@@ -213,6 +220,30 @@ mod tests {
             .register_template_string("password_changed", "Password changed from {{ip_address}}")
             .unwrap();
         templates
+            .register_template_string(
+                "monthly_digest",
+                "{{total_clicks}} clicks{{#each top_links}} {{this.short_url}}{{/each}}",
+            )
+            .unwrap();
+        templates
+            .register_template_string("organization_invitation", "Join {{organization_name}}")
+            .unwrap();
+        templates
+            .register_template_string("account_export_ready", "Export ready for {{user_name}}")
+            .unwrap();
+        templates
+            .register_template_string("account_locked", "Locked for {{lockout_minutes}} minutes")
+            .unwrap();
+        templates
+            .register_template_string(
+                "sessions_revoked",
+                "{{revoked_sessions}} revoked{{#if kept_current}}, current kept{{/if}}",
+            )
+            .unwrap();
+        templates
+            .register_template_string("token_reuse_detected", "Incident {{incident_id}}")
+            .unwrap();
+        templates
     }
 
     #[test]
@@ -254,4 +285,855 @@ mod tests {
         );
         assert_eq!(message.reply_to, Some("support@example.com".to_string()));
     }
+
+    #[test]
+    fn test_monthly_digest_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let mut summary = UsageSummaryResponse::empty(
+            crate::models::analytics::SummaryPeriod::parse("2024-06").unwrap(),
+            3,
+        );
+        summary.total_clicks = 42;
+        summary.top_links.push(crate::models::analytics::TopLinkSummary {
+            link_id: uuid::Uuid::new_v4(),
+            short_code: "abc123".to_string(),
+            clicks: 40,
+        });
+        summary.top_referrer = Some("twitter.com".to_string());
+
+        let builder = MonthlyDigestEmailBuilder::new(
+            "user@example.com",
+            "John Doe",
+            &summary,
+            &config,
+            &templates,
+        );
+
+        let message = builder.build().unwrap();
+        assert_eq!(message.subject, "Your June 2024 summary - Test App");
+        assert_eq!(message.html, "42 clicks https://sho.rt/abc123");
+        let text = message.text.unwrap();
+        assert!(text.contains("1. https://sho.rt/abc123 - 40 clicks"));
+        assert!(text.contains("Top referrer: twitter.com"));
+        assert!(!text.contains("Top country"));
+        assert!(text.contains("https://dashboard.example.com/settings"));
+    }
+
+    #[test]
+    fn test_organization_invitation_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let builder = OrganizationInvitationEmailBuilder::new(
+            "teammate@example.com",
+            "John Doe",
+            "Acme Marketing",
+            "admin",
+            "invite_token_123",
+            7,
+            &config,
+            &templates,
+        );
+
+        let message = builder.build().unwrap();
+        assert_eq!(message.to, vec!["teammate@example.com"]);
+        assert_eq!(
+            message.subject,
+            "John Doe invited you to Acme Marketing on Test App"
+        );
+        assert_eq!(message.html, "Join Acme Marketing");
+        assert!(message
+            .text
+            .unwrap()
+            .contains("https://app.example.com/invitations/accept?token=invite_token_123"));
+    }
+
+    #[test]
+    fn test_account_export_ready_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let builder = AccountExportReadyEmailBuilder::new(
+            "user@example.com",
+            "John Doe",
+            "export_token_123",
+            7,
+            &config,
+            &templates,
+        );
+
+        let message = builder.build().unwrap();
+        assert_eq!(message.to, vec!["user@example.com"]);
+        assert_eq!(message.subject, "Your Test App data export is ready");
+        assert_eq!(message.html, "Export ready for John Doe");
+        let text = message.text.unwrap();
+        assert!(text.contains("https://app.example.com/account/export?token=export_token_123"));
+        assert!(text.contains("expires in 7 days"));
+    }
+
+    #[test]
+    fn test_account_locked_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let builder = AccountLockedEmailBuilder::new(
+            "user@example.com",
+            "John Doe",
+            "unlock_token_123",
+            1800,
+            90,
+            &config,
+            &templates,
+        );
+
+        let message = builder.build().unwrap();
+        assert_eq!(message.to, vec!["user@example.com"]);
+        assert_eq!(message.subject, "Your Test App account has been locked");
+        assert_eq!(message.html, "Locked for 30 minutes");
+        let text = message.text.unwrap();
+        assert!(text.contains("https://app.example.com/account/unlock?token=unlock_token_123"));
+        assert!(text.contains("expires in 2 minutes"));
+    }
+
+    #[test]
+    fn test_sessions_revoked_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let build = |kept_current| {
+            SessionsRevokedEmailBuilder::new(
+                "user@example.com",
+                "John Doe",
+                3,
+                kept_current,
+                "192.168.1.1",
+                "Mozilla/5.0",
+                &config,
+                &templates,
+            )
+            .build()
+            .unwrap()
+        };
+
+        let message = build(true);
+        assert_eq!(
+            message.subject,
+            "Test App Security Alert: You were signed out everywhere"
+        );
+        assert_eq!(message.html, "3 revoked, current kept");
+        let text = message.text.unwrap();
+        assert!(text.contains("Every other device"));
+        assert!(text.contains("(3 sessions)"));
+        assert!(text.contains("192.168.1.1"));
+
+        let message = build(false);
+        assert_eq!(message.html, "3 revoked");
+        assert!(message.text.unwrap().contains("Every device"));
+    }
+
+    #[test]
+    fn test_token_reuse_email_builder() {
+        let config = setup_test_config();
+        let templates = setup_test_templates();
+        let incident = SecurityIncident {
+            id: uuid::Uuid::nil(),
+            user_id: uuid::Uuid::nil(),
+            kind: "refresh_token_reuse".to_string(),
+            detected_at: chrono::Utc::now(),
+            ip_address: Some("203.0.113.7".to_string()),
+            user_agent: None,
+            device_fingerprint: None,
+            token_family: Some("family".to_string()),
+            revoked_tokens: 2,
+        };
+        let builder = TokenReuseEmailBuilder::new(
+            "user@example.com",
+            "John Doe",
+            &incident,
+            &config,
+            &templates,
+        );
+
+        let message = builder.build().unwrap();
+        assert_eq!(
+            message.subject,
+            "Test App Security Alert: Suspicious sign-in activity"
+        );
+        assert_eq!(message.html, format!("Incident {}", uuid::Uuid::nil()));
+        assert!(message.high_priority);
+        let text = message.text.unwrap();
+        assert!(text.contains("(2 sessions)"));
+        assert!(text.contains("203.0.113.7"));
+        assert!(text.contains("Device: Unknown device"));
+    }
+}
+
+/// Builder for link quarantine notifications sent to the link owner
+pub struct LinkQuarantinedEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    short_code: &'a str,
+    original_url: &'a str,
+    reason: &'a str,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> LinkQuarantinedEmailBuilder<'a> {
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        short_code: &'a str,
+        original_url: &'a str,
+        reason: &'a str,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            short_code,
+            original_url,
+            reason,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for LinkQuarantinedEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        let short_url = format!("{}/{}", self.config.short_link_base_url, self.short_code);
+
+        // Prepare template data
+        let data = LinkQuarantinedEmailData {
+            user_name: self.user_name.to_string(),
+            short_code: self.short_code.to_string(),
+            short_url: short_url.clone(),
+            original_url: self.original_url.to_string(),
+            reason: self.reason.to_string(),
+            timestamp: chrono::Utc::now()
+                .format("%B %d, %Y at %H:%M UTC")
+                .to_string(),
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.dashboard_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("link_quarantined", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        // Create plain text version
+        let text = format!(
+            "Hi {},\n\n\
+            A routine security re-scan flagged the destination of your short link {}.\n\
+            To protect visitors, the link now shows a warning page instead of redirecting.\n\n\
+            Destination: {}\n\
+            Reason: {}\n\n\
+            If you believe this is a mistake, you can request a review from your dashboard at {}.\n\n\
+            Best regards,\n\
+            The {} Security Team",
+            self.user_name,
+            short_url,
+            self.original_url,
+            self.reason,
+            self.config.dashboard_url,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!("{}: Your link {} was quarantined", self.config.from_name, self.short_code),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone()))
+    }
+}
+
+/// Builder for the opt-in monthly usage digest
+pub struct MonthlyDigestEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    summary: &'a UsageSummaryResponse,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> MonthlyDigestEmailBuilder<'a> {
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        summary: &'a UsageSummaryResponse,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            summary,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for MonthlyDigestEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        let summary = self.summary;
+        let period_name = summary.from.format("%B %Y").to_string();
+        let preferences_url = format!("{}/settings", self.config.dashboard_url);
+
+        // Prepare template data
+        let data = MonthlyDigestEmailData {
+            user_name: self.user_name.to_string(),
+            period_name: period_name.clone(),
+            links_created: summary.links_created,
+            total_clicks: summary.total_clicks,
+            unique_visitors: summary.unique_visitors,
+            top_links: summary
+                .top_links
+                .iter()
+                .map(|link| DigestTopLink {
+                    short_code: link.short_code.clone(),
+                    short_url: format!("{}/{}", self.config.short_link_base_url, link.short_code),
+                    clicks: link.clicks,
+                })
+                .collect(),
+            top_referrer: summary.top_referrer.clone(),
+            top_country: summary.top_country.clone(),
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.dashboard_url.clone(),
+            preferences_url: preferences_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("monthly_digest", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        // Create plain text version
+        let mut highlights = Vec::new();
+        if let Some(ref referrer) = summary.top_referrer {
+            highlights.push(format!("Top referrer: {}", referrer));
+        }
+        if let Some(ref country) = summary.top_country {
+            highlights.push(format!("Top country: {}", country));
+        }
+        for (rank, link) in summary.top_links.iter().enumerate() {
+            highlights.push(format!(
+                "{}. {}/{} - {} clicks",
+                rank + 1,
+                self.config.short_link_base_url,
+                link.short_code,
+                link.clicks
+            ));
+        }
+
+        let text = format!(
+            "Hi {},\n\n\
+            Here is how your short links performed in {}:\n\n\
+            - Total clicks: {}\n\
+            - Unique visitors: {}\n\
+            - Links created: {}\n\n\
+            {}\n\n\
+            See the full breakdown on your dashboard at {}.\n\n\
+            You receive this summary because you turned on monthly digests. \
+            You can turn them off at {}.\n\n\
+            Best regards,\n\
+            The {} Team",
+            self.user_name,
+            period_name,
+            summary.total_clicks,
+            summary.unique_visitors,
+            summary.links_created,
+            highlights.join("\n"),
+            self.config.dashboard_url,
+            preferences_url,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!("Your {} summary - {}", period_name, self.config.from_name),
+            html,
+        )
+        .with_text(text))
+    }
+}
+
+/// Builder for organization invitations with a single-use accept token
+pub struct OrganizationInvitationEmailBuilder<'a> {
+    to_email: &'a str,
+    inviter_name: &'a str,
+    organization_name: &'a str,
+    role: &'a str,
+    token: &'a str,
+    expiry_days: i64,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> OrganizationInvitationEmailBuilder<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        to_email: &'a str,
+        inviter_name: &'a str,
+        organization_name: &'a str,
+        role: &'a str,
+        token: &'a str,
+        expiry_days: i64,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            inviter_name,
+            organization_name,
+            role,
+            token,
+            expiry_days,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for OrganizationInvitationEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        // Construct accept URL
+        let accept_url = format!(
+            "{}/invitations/accept?token={}",
+            self.config.frontend_url, self.token
+        );
+
+        // Prepare template data
+        let data = OrganizationInvitationEmailData {
+            inviter_name: self.inviter_name.to_string(),
+            organization_name: self.organization_name.to_string(),
+            role: self.role.to_string(),
+            accept_url: accept_url.clone(),
+            expiry_days: self.expiry_days,
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.frontend_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("organization_invitation", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        // Create plain text version
+        let text = format!(
+            "Hi,\n\n\
+            {} invited you to join {} on {} as {}.\n\n\
+            Accept the invitation here: {}\n\n\
+            The invitation expires in {} days. \
+            Sign in or create an account with this email address to accept it.\n\n\
+            If you were not expecting this invitation, you can ignore this email.\n\n\
+            Best regards,\n\
+            The {} Team",
+            self.inviter_name,
+            self.organization_name,
+            self.config.from_name,
+            self.role,
+            accept_url,
+            self.expiry_days,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!(
+                "{} invited you to {} on {}",
+                self.inviter_name, self.organization_name, self.config.from_name
+            ),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone()))
+    }
+}
+
+/// Builder for the "account export ready" email carrying a single-use download token
+pub struct AccountExportReadyEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    token: &'a str,
+    expiry_days: i64,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> AccountExportReadyEmailBuilder<'a> {
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        token: &'a str,
+        expiry_days: i64,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            token,
+            expiry_days,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for AccountExportReadyEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        // Construct download URL
+        let download_url = format!(
+            "{}/account/export?token={}",
+            self.config.frontend_url, self.token
+        );
+
+        // Prepare template data
+        let data = AccountExportReadyEmailData {
+            user_name: self.user_name.to_string(),
+            download_url: download_url.clone(),
+            expiry_days: self.expiry_days,
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.frontend_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("account_export_ready", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        // Create plain text version
+        let text = format!(
+            "Hi {},\n\n\
+            The export of your {} account is ready.\n\n\
+            Download it here: {}\n\n\
+            The link expires in {} days, after which the export is deleted.\n\n\
+            If you did not request this export, contact {} right away.\n\n\
+            Best regards,\n\
+            The {} Team",
+            self.user_name,
+            self.config.from_name,
+            download_url,
+            self.expiry_days,
+            self.config.support_email,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!("Your {} data export is ready", self.config.from_name),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone()))
+    }
+}
+
+/// Builder for the lockout notification with a single-use unlock link
+pub struct AccountLockedEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    token: &'a str,
+    lockout_seconds: u64,
+    token_ttl_seconds: u64,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> AccountLockedEmailBuilder<'a> {
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        token: &'a str,
+        lockout_seconds: u64,
+        token_ttl_seconds: u64,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            token,
+            lockout_seconds,
+            token_ttl_seconds,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for AccountLockedEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        // Construct unlock URL
+        let unlock_url = format!(
+            "{}/account/unlock?token={}",
+            self.config.frontend_url, self.token
+        );
+
+        // Round up so a 90 second lockout doesn't read as "1 minute"
+        let lockout_minutes = self.lockout_seconds.div_ceil(60);
+        let link_expiry_minutes = self.token_ttl_seconds.div_ceil(60);
+
+        // Prepare template data
+        let data = AccountLockedEmailData {
+            user_name: self.user_name.to_string(),
+            unlock_url: unlock_url.clone(),
+            lockout_minutes,
+            link_expiry_minutes,
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.frontend_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("account_locked", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        // Create plain text version
+        let text = format!(
+            "Hi {},\n\n\
+            We locked your {} account after several sign-in attempts with a wrong password.\n\n\
+            It unlocks on its own in {} minutes, or you can unlock it now: {}\n\n\
+            The link works once and expires in {} minutes.\n\n\
+            If these attempts weren't yours, someone may be guessing your password; \
+            consider changing it after you sign in.\n\n\
+            Best regards,\n\
+            The {} Team",
+            self.user_name,
+            self.config.from_name,
+            lockout_minutes,
+            unlock_url,
+            link_expiry_minutes,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!("Your {} account has been locked", self.config.from_name),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone()))
+    }
+}
+
+/// Builder for the security notification sent after signing out of all devices
+pub struct SessionsRevokedEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    revoked_sessions: usize,
+    kept_current: bool,
+    ip_address: &'a str,
+    user_agent: &'a str,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> SessionsRevokedEmailBuilder<'a> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        revoked_sessions: usize,
+        kept_current: bool,
+        ip_address: &'a str,
+        user_agent: &'a str,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            revoked_sessions,
+            kept_current,
+            ip_address,
+            user_agent,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for SessionsRevokedEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        let data = SessionsRevokedEmailData {
+            user_name: self.user_name.to_string(),
+            revoked_sessions: self.revoked_sessions,
+            kept_current: self.kept_current,
+            ip_address: self.ip_address.to_string(),
+            user_agent: self.user_agent.to_string(),
+            timestamp: chrono::Utc::now()
+                .format("%B %d, %Y at %H:%M UTC")
+                .to_string(),
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.frontend_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("sessions_revoked", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        let scope = if self.kept_current {
+            "Every other device signed in to your account was signed out; the device that \
+            made the request stays signed in"
+        } else {
+            "Every device signed in to your account was signed out"
+        };
+        let text = format!(
+            "Hi {},\n\n\
+            {} ({} sessions) on {}.\n\n\
+            Details:\n\
+            - IP Address: {}\n\
+            - Device: {}\n\n\
+            If this wasn't you, someone else has access to your account. \
+            Reset your password at {}/forgot-password\n\n\
+            Best regards,\n\
+            The {} Security Team",
+            self.user_name,
+            scope,
+            self.revoked_sessions,
+            data.timestamp,
+            self.ip_address,
+            self.user_agent,
+            self.config.frontend_url,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!(
+                "{} Security Alert: You were signed out everywhere",
+                self.config.from_name
+            ),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone()))
+    }
+}
+
+/// Builder for the alert sent when a replayed refresh token revokes a session
+pub struct TokenReuseEmailBuilder<'a> {
+    to_email: &'a str,
+    user_name: &'a str,
+    incident: &'a SecurityIncident,
+    config: &'a EmailConfig,
+    templates: &'a Handlebars<'a>,
+}
+
+impl<'a> TokenReuseEmailBuilder<'a> {
+    pub fn new(
+        to_email: &'a str,
+        user_name: &'a str,
+        incident: &'a SecurityIncident,
+        config: &'a EmailConfig,
+        templates: &'a Handlebars<'a>,
+    ) -> Self {
+        Self {
+            to_email,
+            user_name,
+            incident,
+            config,
+            templates,
+        }
+    }
+}
+
+impl<'a> EmailBuilder for TokenReuseEmailBuilder<'a> {
+    #[instrument(skip(self))]
+    fn build(&self) -> Result<EmailMessage, EmailError> {
+        let data = TokenReuseEmailData {
+            user_name: self.user_name.to_string(),
+            incident_id: self.incident.id.to_string(),
+            revoked_sessions: self.incident.revoked_tokens,
+            ip_address: self
+                .incident
+                .ip_address
+                .clone()
+                .unwrap_or_else(|| "Unknown".to_string()),
+            user_agent: self
+                .incident
+                .user_agent
+                .clone()
+                .unwrap_or_else(|| "Unknown device".to_string()),
+            timestamp: self
+                .incident
+                .detected_at
+                .format("%B %d, %Y at %H:%M UTC")
+                .to_string(),
+            app_name: self.config.from_name.clone(),
+            app_url: self.config.frontend_url.clone(),
+            support_email: self.config.support_email.clone(),
+        };
+
+        // Render HTML content
+        let html = self
+            .templates
+            .render("token_reuse_detected", &data)
+            .map_err(|e| EmailError::TemplateError(e.to_string()))?;
+
+        let text = format!(
+            "Hi {},\n\n\
+            A sign-in token that had already been replaced was used again on {}. This usually \
+            means a copy of it was taken from one of your devices. The session it belonged to was \
+            signed out ({} sessions).\n\n\
+            Details:\n\
+            - Incident: {}\n\
+            - IP Address: {}\n\
+            - Device: {}\n\n\
+            If you don't recognise this device, reset your password at {}/forgot-password \
+            and quote the incident ID when contacting support.\n\n\
+            Best regards,\n\
+            The {} Security Team",
+            self.user_name,
+            data.timestamp,
+            data.revoked_sessions,
+            data.incident_id,
+            data.ip_address,
+            data.user_agent,
+            self.config.frontend_url,
+            self.config.from_name
+        );
+
+        Ok(EmailMessage::new(
+            format!("{} <{}>", self.config.from_name, self.config.from_email),
+            vec![self.to_email.to_string()],
+            format!(
+                "{} Security Alert: Suspicious sign-in activity",
+                self.config.from_name
+            ),
+            html,
+        )
+        .with_text(text)
+        .with_reply_to(self.config.support_email.clone())
+        .with_high_priority())
+    }
 }
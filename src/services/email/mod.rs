@@ -3,13 +3,18 @@
 
 pub mod builders;
 pub mod sender;
+pub mod transport;
 pub mod types;
 
 use self::types::EmailBuilder;
-use crate::app_config::EmailConfig;
+use crate::app_config::{AppConfig, EmailConfig};
 use anyhow::Result;
+use crate::models::analytics::UsageSummaryResponse;
+use crate::models::security_incident::SecurityIncident;
 use builders::{
-    PasswordChangedEmailBuilder, PasswordResetEmailBuilder,
+    AccountExportReadyEmailBuilder, AccountLockedEmailBuilder, LinkQuarantinedEmailBuilder,
+    MonthlyDigestEmailBuilder, OrganizationInvitationEmailBuilder, PasswordChangedEmailBuilder,
+    PasswordResetEmailBuilder, SessionsRevokedEmailBuilder, TokenReuseEmailBuilder,
 };
 use handlebars::Handlebars;
 use sender::EmailSender;
@@ -19,7 +24,7 @@ use tracing::{info, instrument};
 /// Email service for sending various types of emails
 #[derive(Clone)]
 pub struct EmailService {
-    sender: EmailSender,
+    transport: Arc<dyn EmailTransport>,
     config: EmailConfig,
     templates: Arc<Handlebars<'static>>,
 }
@@ -27,20 +32,37 @@ pub struct EmailService {
 impl EmailService {
     /// Create a new email service instance
     pub fn new(config: EmailConfig) -> Result<Self> {
-        // Initialize templates
-        let mut templates = Handlebars::new();
-
-        // Register all email templates
-        Self::register_templates(&mut templates)?;
-
         // Create the email sender with API URL from config
         let sender =
             EmailSender::new_resend(config.resend_api_key.clone(), config.resend_api_url.clone())
                 .with_max_retries(3)
                 .with_retry_delay(std::time::Duration::from_secs(1));
 
+        Self::with_transport(config, Arc::new(sender))
+    }
+
+    /// Resend, or a [`RecordingEmailTransport`] when TEST_MODE is set
+    pub fn from_app_config(config: &AppConfig) -> Result<Self> {
+        if config.test_mode {
+            Self::with_transport(
+                config.email.clone(),
+                Arc::new(RecordingEmailTransport::new()),
+            )
+        } else {
+            Self::new(config.email.clone())
+        }
+    }
+
+    /// Create an email service delivering through `transport`
+    pub fn with_transport(config: EmailConfig, transport: Arc<dyn EmailTransport>) -> Result<Self> {
+        // Initialize templates
+        let mut templates = Handlebars::new();
+
+        // Register all email templates
+        Self::register_templates(&mut templates)?;
+
         Ok(Self {
-            sender,
+            transport,
             config,
             templates: Arc::new(templates),
         })
@@ -60,6 +82,50 @@ impl EmailService {
             .register_template_string("password_changed", password_changed_template)
             .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
 
+        // Register link quarantine notification template
+        let link_quarantined_template = include_str!("../../templates/email/link_quarantined.html");
+        templates
+            .register_template_string("link_quarantined", link_quarantined_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register monthly usage digest template
+        let monthly_digest_template = include_str!("../../templates/email/monthly_digest.html");
+        templates
+            .register_template_string("monthly_digest", monthly_digest_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register organization invitation template
+        let organization_invitation_template =
+            include_str!("../../templates/email/organization_invitation.html");
+        templates
+            .register_template_string("organization_invitation", organization_invitation_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register account export ready template
+        let account_export_ready_template =
+            include_str!("../../templates/email/account_export_ready.html");
+        templates
+            .register_template_string("account_export_ready", account_export_ready_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register account lockout template
+        let account_locked_template = include_str!("../../templates/email/account_locked.html");
+        templates
+            .register_template_string("account_locked", account_locked_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register "signed out everywhere" template
+        let sessions_revoked_template = include_str!("../../templates/email/sessions_revoked.html");
+        templates
+            .register_template_string("sessions_revoked", sessions_revoked_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
+        // Register refresh token reuse alert template
+        let token_reuse_template = include_str!("../../templates/email/token_reuse_detected.html");
+        templates
+            .register_template_string("token_reuse_detected", token_reuse_template)
+            .map_err(|e| types::EmailError::TemplateError(e.to_string()))?;
+
         Ok(())
     }
 
@@ -82,7 +148,7 @@ impl EmailService {
         );
 
         let message = builder.build()?;
-        self.sender.send_with_retry(message).await
+        self.transport.send_with_retry(message).await
     }
 
     /// Send password change security notification
@@ -107,16 +173,195 @@ impl EmailService {
 
         let message = builder.build()?;
         // Security notifications should be sent immediately without retry
-        self.sender.send(message).await
+        self.transport.send(message).await
+    }
+
+    /// Notify a link owner that their link was quarantined by a security re-scan
+    #[instrument(skip(self))]
+    pub async fn send_link_quarantined_notification(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        short_code: &str,
+        original_url: &str,
+        reason: &str,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending link quarantine notification to {}", to_email);
+
+        let builder = LinkQuarantinedEmailBuilder::new(
+            to_email,
+            user_name,
+            short_code,
+            original_url,
+            reason,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        self.transport.send_with_retry(message).await
+    }
+
+    /// Send the opt-in monthly usage digest
+    #[instrument(skip(self, summary))]
+    pub async fn send_monthly_digest(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        summary: &UsageSummaryResponse,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending {} usage digest to {}", summary.period, to_email);
+
+        let builder = MonthlyDigestEmailBuilder::new(
+            to_email,
+            user_name,
+            summary,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        self.transport.send_with_retry(message).await
+    }
+
+    /// Invite someone to join an organization
+    #[instrument(skip(self, token))]
+    pub async fn send_organization_invitation(
+        &self,
+        to_email: &str,
+        inviter_name: &str,
+        organization_name: &str,
+        role: &str,
+        token: &str,
+        expiry_days: i64,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending {} invitation to {}", organization_name, to_email);
+
+        let builder = OrganizationInvitationEmailBuilder::new(
+            to_email,
+            inviter_name,
+            organization_name,
+            role,
+            token,
+            expiry_days,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        self.transport.send_with_retry(message).await
+    }
+
+    /// Send the download link for a finished account export
+    #[instrument(skip(self, token))]
+    pub async fn send_account_export_ready(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        token: &str,
+        expiry_days: i64,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending account export link to {}", to_email);
+
+        let builder = AccountExportReadyEmailBuilder::new(
+            to_email,
+            user_name,
+            token,
+            expiry_days,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        self.transport.send_with_retry(message).await
+    }
+
+    /// Tell a locked-out user about the lockout, with a link that unlocks the account
+    #[instrument(skip(self, token))]
+    pub async fn send_account_locked(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        token: &str,
+        lockout_seconds: u64,
+        token_ttl_seconds: u64,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending account lockout notification to {}", to_email);
+
+        let builder = AccountLockedEmailBuilder::new(
+            to_email,
+            user_name,
+            token,
+            lockout_seconds,
+            token_ttl_seconds,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        self.transport.send_with_retry(message).await
+    }
+
+    /// Tell the user their sessions were revoked from `POST /v1/auth/sessions/revoke-all`
+    #[instrument(skip(self))]
+    pub async fn send_sessions_revoked_notification(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        revoked_sessions: usize,
+        kept_current: bool,
+        ip_address: &str,
+        user_agent: &str,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending sessions revoked notification to {}", to_email);
+
+        let builder = SessionsRevokedEmailBuilder::new(
+            to_email,
+            user_name,
+            revoked_sessions,
+            kept_current,
+            ip_address,
+            user_agent,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        // Security notifications should be sent immediately without retry
+        self.transport.send(message).await
+    }
+
+    /// Alert the user that a replayed refresh token revoked their session
+    #[instrument(skip(self, incident), fields(incident_id = %incident.id))]
+    pub async fn send_token_reuse_alert(
+        &self,
+        to_email: &str,
+        user_name: &str,
+        incident: &SecurityIncident,
+    ) -> Result<(), types::EmailError> {
+        info!("Sending token reuse alert to {}", to_email);
+
+        let builder = TokenReuseEmailBuilder::new(
+            to_email,
+            user_name,
+            incident,
+            &self.config,
+            &self.templates,
+        );
+
+        let message = builder.build()?;
+        // Security notifications should be sent immediately without retry
+        self.transport.send(message).await
     }
 
     /// Perform a health check on the email service
     pub async fn health_check(&self) -> Result<(), EmailError> {
-        self.sender.health_check().await
+        self.transport.health_check().await
     }
 }
 
 // Re-export commonly used types for convenience
+pub use transport::{EmailTransport, RecordingEmailTransport};
 pub use types::{EmailError, EmailMessage};
 
 #[cfg(test)]
@@ -134,6 +379,8 @@ mod tests {
             support_email: "support@test.com".to_string(),
             frontend_url: "https://app.test.com".to_string(),
             dashboard_url: "https://dashboard.test.com".to_string(),
+            short_link_base_url: "https://qck.test".to_string(),
+            monthly_digest_enabled: true,
             verification_code_ttl: 900,
             verification_max_attempts: 5,
             resend_limit: 10,
@@ -148,4 +395,20 @@ mod tests {
         let service = EmailService::new(config);
         assert!(service.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_custom_transport_receives_emails() {
+        let transport = Arc::new(RecordingEmailTransport::new());
+        let service =
+            EmailService::with_transport(create_test_config(), transport.clone()).unwrap();
+
+        service
+            .send_password_reset_email("user@example.com", "User", "reset_token_123")
+            .await
+            .unwrap();
+
+        let sent = transport.sent_to("user@example.com");
+        assert_eq!(sent.len(), 1);
+        assert!(sent[0].html.contains("reset_token_123"));
+    }
 }
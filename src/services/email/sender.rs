@@ -2,11 +2,19 @@
 // This module handles the actual sending of emails through email providers
 
 use super::types::{EmailError, EmailMessage, ResendEmailPayload};
+use crate::utils::outbound_proxy;
 use reqwest::Client;
 use std::sync::Arc;
 use std::time::Duration;
 use tracing::{error, info, instrument, warn};
 
+/// HTTP client for the provider API, routed through the outbound proxy when one is set
+fn http_client() -> Client {
+    outbound_proxy::apply(Client::builder())
+        .build()
+        .unwrap_or_default()
+}
+
 /// Generic email sender that handles delivery to email providers
 #[derive(Clone)]
 pub struct EmailSender {
@@ -21,7 +29,7 @@ impl EmailSender {
     /// Create a new email sender for Resend API
     pub fn new_resend(api_key: String, api_url: String) -> Self {
         Self {
-            client: Arc::new(Client::new()),
+            client: Arc::new(http_client()),
             api_key,
             api_url,
             max_retries: 3,
@@ -32,7 +40,7 @@ impl EmailSender {
     /// Create a custom email sender with specific configuration
     pub fn new(api_url: String, api_key: String) -> Self {
         Self {
-            client: Arc::new(Client::new()),
+            client: Arc::new(http_client()),
             api_key,
             api_url,
             max_retries: 3,
@@ -0,0 +1,104 @@
+// Email Transport - Where built email messages are delivered
+// Resend in normal operation; an in-memory recorder when TEST_MODE is set
+
+use super::sender::EmailSender;
+use super::types::{EmailError, EmailMessage};
+use async_trait::async_trait;
+use std::sync::Mutex;
+
+/// Delivers built email messages to a provider
+#[async_trait]
+pub trait EmailTransport: Send + Sync {
+    /// Send once, without retrying
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError>;
+
+    /// Send, retrying transient failures
+    async fn send_with_retry(&self, message: EmailMessage) -> Result<(), EmailError> {
+        self.send(message).await
+    }
+
+    /// Check that the provider is reachable and accepts the credentials
+    async fn health_check(&self) -> Result<(), EmailError> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EmailTransport for EmailSender {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        EmailSender::send(self, message).await
+    }
+
+    async fn send_with_retry(&self, message: EmailMessage) -> Result<(), EmailError> {
+        EmailSender::send_with_retry(self, message).await
+    }
+
+    async fn health_check(&self) -> Result<(), EmailError> {
+        EmailSender::health_check(self).await
+    }
+}
+
+/// Keeps every message instead of sending it, for tests to assert on (TEST_MODE)
+#[derive(Debug, Default)]
+pub struct RecordingEmailTransport {
+    sent: Mutex<Vec<EmailMessage>>,
+}
+
+impl RecordingEmailTransport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every message sent so far, oldest first
+    pub fn sent(&self) -> Vec<EmailMessage> {
+        self.sent.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Messages addressed to `address`, oldest first
+    pub fn sent_to(&self, address: &str) -> Vec<EmailMessage> {
+        self.sent()
+            .into_iter()
+            .filter(|message| message.to.iter().any(|to| to.eq_ignore_ascii_case(address)))
+            .collect()
+    }
+}
+
+#[async_trait]
+impl EmailTransport for RecordingEmailTransport {
+    async fn send(&self, message: EmailMessage) -> Result<(), EmailError> {
+        self.sent
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(message);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(to: &str) -> EmailMessage {
+        EmailMessage::new(
+            "sender@example.com".to_string(),
+            vec![to.to_string()],
+            "Subject".to_string(),
+            "<p>Body</p>".to_string(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_recording_transport_keeps_messages() {
+        let transport = RecordingEmailTransport::new();
+        transport.send(message("a@example.com")).await.unwrap();
+        transport
+            .send_with_retry(message("b@example.com"))
+            .await
+            .unwrap();
+
+        assert_eq!(transport.sent().len(), 2);
+        assert_eq!(transport.sent_to("B@example.com").len(), 1);
+        assert!(transport.sent_to("c@example.com").is_empty());
+        assert!(transport.health_check().await.is_ok());
+    }
+}
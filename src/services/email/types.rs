@@ -2,6 +2,7 @@
 // This module contains all shared types used across the email service
 
 use serde::Serialize;
+use std::collections::HashMap;
 use thiserror::Error;
 
 /// Errors that can occur during email operations
@@ -35,6 +36,8 @@ pub struct EmailMessage {
     pub html: String,
     pub text: Option<String>,
     pub reply_to: Option<String>,
+    /// Ask mail clients to flag the message as important
+    pub high_priority: bool,
 }
 
 impl EmailMessage {
@@ -46,6 +49,7 @@ impl EmailMessage {
             html,
             text: None,
             reply_to: None,
+            high_priority: false,
         }
     }
 
@@ -58,6 +62,11 @@ impl EmailMessage {
         self.reply_to = Some(reply_to);
         self
     }
+
+    pub fn with_high_priority(mut self) -> Self {
+        self.high_priority = true;
+        self
+    }
 }
 
 /// Trait that all email builders must implement
@@ -89,10 +98,113 @@ pub struct PasswordChangedEmailData {
     pub support_email: String,
 }
 
+/// Data structure for link quarantine notification template
+#[derive(Serialize)]
+pub struct LinkQuarantinedEmailData {
+    pub user_name: String,
+    pub short_code: String,
+    pub short_url: String,
+    pub original_url: String,
+    pub reason: String,
+    pub timestamp: String,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
+/// Data structure for the monthly usage digest template
+#[derive(Serialize)]
+pub struct MonthlyDigestEmailData {
+    pub user_name: String,
+    pub period_name: String,
+    pub links_created: i64,
+    pub total_clicks: u64,
+    pub unique_visitors: u64,
+    pub top_links: Vec<DigestTopLink>,
+    pub top_referrer: Option<String>,
+    pub top_country: Option<String>,
+    pub app_name: String,
+    pub app_url: String,
+    pub preferences_url: String,
+    pub support_email: String,
+}
+
+/// One row of the digest's top links table
+#[derive(Serialize)]
+pub struct DigestTopLink {
+    pub short_code: String,
+    pub short_url: String,
+    pub clicks: u64,
+}
+
+/// Data structure for the organization invitation template
+#[derive(Serialize)]
+pub struct OrganizationInvitationEmailData {
+    pub inviter_name: String,
+    pub organization_name: String,
+    pub role: String,
+    pub accept_url: String,
+    pub expiry_days: i64,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
+/// Data structure for the "account export ready" template
+#[derive(Serialize)]
+pub struct AccountExportReadyEmailData {
+    pub user_name: String,
+    pub download_url: String,
+    pub expiry_days: i64,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
+/// Data structure for the account lockout notification template
+#[derive(Serialize)]
+pub struct AccountLockedEmailData {
+    pub user_name: String,
+    pub unlock_url: String,
+    pub lockout_minutes: u64,
+    pub link_expiry_minutes: u64,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
+/// Data structure for the "signed out everywhere" security notification template
+#[derive(Serialize)]
+pub struct SessionsRevokedEmailData {
+    pub user_name: String,
+    pub revoked_sessions: usize,
+    pub kept_current: bool,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub timestamp: String,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
+/// Data structure for the refresh token reuse security alert template
+#[derive(Serialize)]
+pub struct TokenReuseEmailData {
+    pub user_name: String,
+    pub incident_id: String,
+    pub revoked_sessions: i32,
+    pub ip_address: String,
+    pub user_agent: String,
+    pub timestamp: String,
+    pub app_name: String,
+    pub app_url: String,
+    pub support_email: String,
+}
+
 /// Resend API specific email format
 ///
 /// This struct represents the email payload sent to the Resend API.
-/// Optional fields (`text` and `reply_to`) are omitted from the JSON payload
+/// Optional fields (`text`, `reply_to` and `headers`) are omitted from the JSON payload
 /// when they are `None`, reducing payload size and avoiding sending null values.
 ///
 /// # Fields
@@ -102,6 +214,7 @@ pub struct PasswordChangedEmailData {
 /// - `html`: HTML content of the email (required)
 /// - `text`: Optional plain text version of the email. Omitted from API payload when None.
 /// - `reply_to`: Optional reply-to email address. Omitted from API payload when None.
+/// - `headers`: Extra message headers, set for high-priority messages. Omitted when None.
 #[derive(Debug, Serialize)]
 pub struct ResendEmailPayload {
     pub from: String,
@@ -112,6 +225,8 @@ pub struct ResendEmailPayload {
     pub text: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reply_to: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
 }
 
 impl From<EmailMessage> for ResendEmailPayload {
@@ -123,6 +238,12 @@ impl From<EmailMessage> for ResendEmailPayload {
             html: message.html,
             text: message.text,
             reply_to: message.reply_to,
+            headers: message.high_priority.then(|| {
+                HashMap::from([
+                    ("X-Priority".to_string(), "1".to_string()),
+                    ("Importance".to_string(), "high".to_string()),
+                ])
+            }),
         }
     }
 }
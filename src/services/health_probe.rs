@@ -0,0 +1,243 @@
+// Cached health of dependencies that are too slow or costly to check on every /v1/health call
+// ClickHouse and the email provider are probed by background tasks on every replica, each on its
+// own interval (HEALTH_PROBE_*). The health handler only reads the last result, so a monitor
+// polling /v1/health never reaches them. Results older than STALE_AFTER_INTERVALS intervals are
+// reported as stale instead of being trusted.
+
+use chrono::{DateTime, Utc};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::future::Future;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+use crate::{app::AppState, services::background_tasks::TASK_REGISTRY};
+
+pub const COMPONENT_CLICKHOUSE: &str = "clickhouse";
+pub const COMPONENT_EMAIL: &str = "email";
+
+/// A result is stale once this many probe intervals have passed without a new one
+const STALE_AFTER_INTERVALS: u64 = 3;
+
+/// Longest a single probe may take before it counts as failed
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Outcome of one probe
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProbeResult {
+    pub healthy: bool,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub checked_at: DateTime<Utc>,
+}
+
+/// Last probe result per component, shared through `AppState`
+#[derive(Debug, Default)]
+pub struct HealthCache {
+    results: RwLock<HashMap<&'static str, ProbeResult>>,
+}
+
+impl HealthCache {
+    pub fn record(&self, component: &'static str, result: ProbeResult) {
+        self.results
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(component, result);
+    }
+
+    pub fn get(&self, component: &str) -> Option<ProbeResult> {
+        self.results
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(component)
+            .cloned()
+    }
+}
+
+/// Run one probe with a timeout and time it
+pub async fn probe<F, E>(check: F) -> ProbeResult
+where
+    F: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    let start = Instant::now();
+    let outcome = tokio::time::timeout(PROBE_TIMEOUT, check).await;
+    let latency_ms = start.elapsed().as_millis() as u64;
+
+    let error = match outcome {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e.to_string()),
+        Err(_) => Some(format!("No response within {}s", PROBE_TIMEOUT.as_secs())),
+    };
+    ProbeResult {
+        healthy: error.is_none(),
+        latency_ms,
+        error,
+        checked_at: Utc::now(),
+    }
+}
+
+/// Health component JSON for a cached result: `disabled` when the probe is off, `unknown`
+/// before the first probe, `stale` once the result is older than `STALE_AFTER_INTERVALS`
+/// intervals, otherwise `healthy` or `unhealthy`
+pub fn component_health(
+    result: Option<&ProbeResult>,
+    enabled: bool,
+    interval_seconds: u64,
+    now: DateTime<Utc>,
+) -> serde_json::Value {
+    if !enabled {
+        return json!({ "status": "disabled" });
+    }
+    let Some(result) = result else {
+        return json!({
+            "status": "unknown",
+            "checked_at": null,
+            "error": "Not probed yet"
+        });
+    };
+
+    let age_seconds = (now - result.checked_at).num_seconds().max(0);
+    let stale_after_seconds = interval_seconds.saturating_mul(STALE_AFTER_INTERVALS);
+    let status = if age_seconds as u64 > stale_after_seconds {
+        "stale"
+    } else if result.healthy {
+        "healthy"
+    } else {
+        "unhealthy"
+    };
+
+    json!({
+        "status": status,
+        "latency_ms": result.latency_ms,
+        "error": result.error,
+        "checked_at": result.checked_at.to_rfc3339(),
+        "age_seconds": age_seconds,
+        "stale_after_seconds": stale_after_seconds
+    })
+}
+
+/// Start the enabled probes; ClickHouse is only probed when it is configured
+pub fn spawn_health_probe_tasks(state: &AppState) {
+    let config = state.config.health_probes.clone();
+
+    if config.clickhouse_enabled && state.clickhouse_analytics.is_some() {
+        let health = state.health.clone();
+        let interval = Duration::from_secs(config.clickhouse_interval_seconds);
+        TASK_REGISTRY.spawn("clickhouse_health_probe", move |reporter| {
+            let health = health.clone();
+            async move {
+                // One client for the task's lifetime, not one per probe
+                let client = crate::db::create_clickhouse_client();
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    let result = probe(client.health_check()).await;
+                    if let Some(error) = &result.error {
+                        warn!("ClickHouse health probe failed: {}", error);
+                    }
+                    health.record(COMPONENT_CLICKHOUSE, result);
+                    reporter.success();
+                }
+            }
+        });
+        info!(
+            "ClickHouse health probe started (every {}s)",
+            config.clickhouse_interval_seconds
+        );
+    }
+
+    if config.email_enabled {
+        let health = state.health.clone();
+        let email_service = state.email_service.clone();
+        let interval = Duration::from_secs(config.email_interval_seconds);
+        TASK_REGISTRY.spawn("email_health_probe", move |reporter| {
+            let health = health.clone();
+            let email_service = email_service.clone();
+            async move {
+                let mut ticker = tokio::time::interval(interval);
+                loop {
+                    ticker.tick().await;
+                    let result = probe(email_service.health_check()).await;
+                    if let Some(error) = &result.error {
+                        warn!("Email provider health probe failed: {}", error);
+                    }
+                    health.record(COMPONENT_EMAIL, result);
+                    reporter.success();
+                }
+            }
+        });
+        info!(
+            "Email provider health probe started (every {}s)",
+            config.email_interval_seconds
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(healthy: bool, checked_at: DateTime<Utc>) -> ProbeResult {
+        ProbeResult {
+            healthy,
+            latency_ms: 12,
+            error: (!healthy).then(|| "connection refused".to_string()),
+            checked_at,
+        }
+    }
+
+    #[test]
+    fn test_component_health_status() {
+        let now = Utc::now();
+        let status = |value: serde_json::Value| value["status"].as_str().unwrap().to_string();
+
+        assert_eq!(status(component_health(None, false, 15, now)), "disabled");
+        assert_eq!(status(component_health(None, true, 15, now)), "unknown");
+
+        let fresh = result(true, now - chrono::Duration::seconds(10));
+        let value = component_health(Some(&fresh), true, 15, now);
+        assert_eq!(status(value.clone()), "healthy");
+        assert_eq!(value["age_seconds"], 10);
+        assert_eq!(value["stale_after_seconds"], 45);
+        assert_eq!(value["checked_at"], fresh.checked_at.to_rfc3339());
+
+        let failed = result(false, now);
+        let value = component_health(Some(&failed), true, 15, now);
+        assert_eq!(status(value.clone()), "unhealthy");
+        assert_eq!(value["error"], "connection refused");
+
+        // A result past three intervals is stale whatever it said
+        let old = result(true, now - chrono::Duration::seconds(46));
+        assert_eq!(status(component_health(Some(&old), true, 15, now)), "stale");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_probe_records_errors_and_timeouts() {
+        let ok = probe(async { Ok::<(), String>(()) }).await;
+        assert!(ok.healthy);
+        assert_eq!(ok.error, None);
+
+        let failed = probe(async { Err::<(), _>("invalid API key") }).await;
+        assert!(!failed.healthy);
+        assert_eq!(failed.error.as_deref(), Some("invalid API key"));
+
+        let hung = probe(std::future::pending::<Result<(), String>>()).await;
+        assert!(!hung.healthy);
+        assert_eq!(hung.error.as_deref(), Some("No response within 5s"));
+    }
+
+    #[test]
+    fn test_health_cache_keeps_latest_result() {
+        let cache = HealthCache::default();
+        assert_eq!(cache.get(COMPONENT_EMAIL), None);
+
+        let now = Utc::now();
+        cache.record(COMPONENT_EMAIL, result(false, now));
+        cache.record(COMPONENT_EMAIL, result(true, now));
+        assert!(cache.get(COMPONENT_EMAIL).unwrap().healthy);
+        assert_eq!(cache.get(COMPONENT_CLICKHOUSE), None);
+    }
+}
@@ -0,0 +1,198 @@
+// Idempotency-Key support for link creation
+// Retried POSTs replay the stored response instead of creating duplicate links
+
+use redis::RedisError;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::db::RedisPool;
+
+/// Request header carrying the client-chosen idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Response header set when a stored response is replayed
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// How long a completed response can be replayed
+const RECORD_TTL_SECONDS: usize = 24 * 60 * 60;
+
+/// Lock held while the first request with a key is processed. Long enough for a
+/// full bulk create; released early once the response is stored.
+const LOCK_TTL_SECONDS: usize = 120;
+
+const MAX_KEY_LENGTH: usize = 255;
+
+/// Response stored against an idempotency key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StoredResponse {
+    /// SHA-256 of the request body the response was produced for
+    pub fingerprint: String,
+    pub status: u16,
+    pub body: String,
+}
+
+/// Outcome of claiming an idempotency key
+#[derive(Debug, PartialEq)]
+pub enum IdempotencyCheck {
+    /// First request with this key; the caller holds the lock and must `complete` or `release`
+    Proceed,
+    /// Same key and body as an earlier request
+    Replay(StoredResponse),
+    /// Key was already used with a different body
+    Mismatch,
+    /// Another request with this key is still being processed
+    InProgress,
+}
+
+pub struct IdempotencyService {
+    redis_pool: RedisPool,
+}
+
+impl IdempotencyService {
+    pub fn new(redis_pool: RedisPool) -> Self {
+        Self { redis_pool }
+    }
+
+    /// Keys must be 1-255 visible ASCII characters
+    pub fn validate_key(key: &str) -> Result<(), String> {
+        if key.is_empty() || key.len() > MAX_KEY_LENGTH {
+            return Err(format!(
+                "Idempotency-Key must be between 1 and {} characters",
+                MAX_KEY_LENGTH
+            ));
+        }
+        if !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err("Idempotency-Key may only contain visible ASCII characters".to_string());
+        }
+        Ok(())
+    }
+
+    /// SHA-256 of the raw request body
+    pub fn fingerprint(body: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(body))
+    }
+
+    /// Keys are scoped per user and hashed so client input never lands in Redis key names
+    fn record_key(user_id: &str, key: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(user_id.as_bytes());
+        hasher.update([0]);
+        hasher.update(key.as_bytes());
+        format!("idempotency:{:x}", hasher.finalize())
+    }
+
+    fn lock_key(user_id: &str, key: &str) -> String {
+        format!("{}:lock", Self::record_key(user_id, key))
+    }
+
+    /// Check for a stored response, or take the processing lock for a new key
+    pub async fn begin(
+        &self,
+        user_id: &str,
+        key: &str,
+        fingerprint: &str,
+    ) -> Result<IdempotencyCheck, RedisError> {
+        if let Some(stored) = self.stored(user_id, key).await? {
+            return Ok(Self::check_stored(stored, fingerprint));
+        }
+
+        let acquired = self
+            .redis_pool
+            .set_nx_with_expiry(
+                &Self::lock_key(user_id, key),
+                fingerprint.to_string(),
+                LOCK_TTL_SECONDS,
+            )
+            .await?;
+        if !acquired {
+            return Ok(IdempotencyCheck::InProgress);
+        }
+
+        // A concurrent request may have finished between the lookup and the lock
+        if let Some(stored) = self.stored(user_id, key).await? {
+            self.release(user_id, key).await?;
+            return Ok(Self::check_stored(stored, fingerprint));
+        }
+
+        Ok(IdempotencyCheck::Proceed)
+    }
+
+    /// Store the response for replays and drop the lock
+    pub async fn complete(
+        &self,
+        user_id: &str,
+        key: &str,
+        response: &StoredResponse,
+    ) -> Result<(), RedisError> {
+        let value = serde_json::to_string(response).unwrap_or_default();
+        self.redis_pool
+            .set_with_expiry(&Self::record_key(user_id, key), value, RECORD_TTL_SECONDS)
+            .await?;
+        self.release(user_id, key).await
+    }
+
+    /// Drop the lock without storing anything so the client can retry
+    pub async fn release(&self, user_id: &str, key: &str) -> Result<(), RedisError> {
+        self.redis_pool.del(&Self::lock_key(user_id, key)).await
+    }
+
+    async fn stored(&self, user_id: &str, key: &str) -> Result<Option<StoredResponse>, RedisError> {
+        let raw: Option<String> = self.redis_pool.get(&Self::record_key(user_id, key)).await?;
+        Ok(raw.and_then(|value| serde_json::from_str(&value).ok()))
+    }
+
+    fn check_stored(stored: StoredResponse, fingerprint: &str) -> IdempotencyCheck {
+        if stored.fingerprint == fingerprint {
+            IdempotencyCheck::Replay(stored)
+        } else {
+            IdempotencyCheck::Mismatch
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_key() {
+        assert!(IdempotencyService::validate_key("3f2b9c1e-retry-key").is_ok());
+        assert!(IdempotencyService::validate_key("").is_err());
+        assert!(IdempotencyService::validate_key("has space").is_err());
+        assert!(IdempotencyService::validate_key(&"a".repeat(MAX_KEY_LENGTH + 1)).is_err());
+    }
+
+    #[test]
+    fn test_record_key_is_scoped_per_user() {
+        let a = IdempotencyService::record_key("user-a", "key-1");
+        let b = IdempotencyService::record_key("user-b", "key-1");
+        assert_ne!(a, b);
+        assert!(a.starts_with("idempotency:"));
+        assert!(!a.contains("key-1"));
+    }
+
+    #[test]
+    fn test_check_stored_compares_fingerprints() {
+        let body = br#"{"url":"https://example.com"}"#;
+        let stored = StoredResponse {
+            fingerprint: IdempotencyService::fingerprint(body),
+            status: 201,
+            body: "{}".to_string(),
+        };
+
+        assert_eq!(
+            IdempotencyService::check_stored(
+                stored.clone(),
+                &IdempotencyService::fingerprint(body)
+            ),
+            IdempotencyCheck::Replay(stored.clone())
+        );
+        assert_eq!(
+            IdempotencyService::check_stored(
+                stored,
+                &IdempotencyService::fingerprint(br#"{"url":"https://other.example"}"#)
+            ),
+            IdempotencyCheck::Mismatch
+        );
+    }
+}
@@ -0,0 +1,180 @@
+// Instance-wide usage for self-hosted operators
+// Backs GET /v1/admin/stats; each figure is read independently so one failing or
+// disabled store only nulls the figures it backs
+
+use chrono::{Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::analytics::{DailyCount, InstanceStatsResponse, INSTANCE_STATS_DAYS},
+    schema::{audit_logs, links, users},
+    services::clickhouse_analytics::ClickHouseAnalyticsService,
+    utils::{audit_logger::AuditAction, service_error::ServiceError},
+};
+
+#[derive(diesel::QueryableByName)]
+struct DayCountRow {
+    #[diesel(sql_type = diesel::sql_types::Date)]
+    day: NaiveDate,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    count: i64,
+}
+
+#[derive(diesel::QueryableByName)]
+struct TotalRow {
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    total: i64,
+}
+
+pub struct InstanceStatsService {
+    diesel_pool: DieselPool,
+    analytics: Option<Arc<ClickHouseAnalyticsService>>,
+    audit_log_retention_days: u32,
+}
+
+impl InstanceStatsService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            analytics: state.clickhouse_analytics.clone(),
+            audit_log_retention_days: state.config.security.audit_log_retention_days,
+        }
+    }
+
+    pub async fn collect(&self) -> InstanceStatsResponse {
+        let today = Utc::now().date_naive();
+        let active_users_30d = if self.audit_log_covers_window() {
+            log_unavailable("active_users_30d", self.active_users().await)
+        } else {
+            None
+        };
+
+        InstanceStatsResponse {
+            total_users: log_unavailable("total_users", self.total_users().await),
+            active_users_30d,
+            total_links: log_unavailable("total_links", self.total_links().await),
+            links_created_per_day: log_unavailable(
+                "links_created_per_day",
+                self.links_created_per_day(today).await,
+            ),
+            total_redirects: self.total_redirects().await,
+            deleted_links_pending_purge: log_unavailable(
+                "deleted_links_pending_purge",
+                self.deleted_links().await,
+            ),
+        }
+    }
+
+    /// Logins older than the audit retention window have been pruned (0 keeps forever)
+    fn audit_log_covers_window(&self) -> bool {
+        self.audit_log_retention_days == 0
+            || i64::from(self.audit_log_retention_days) >= INSTANCE_STATS_DAYS
+    }
+
+    async fn total_users(&self) -> Result<i64, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        Ok(users::table.count().get_result(&mut conn).await?)
+    }
+
+    async fn active_users(&self) -> Result<i64, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let since = Utc::now() - Duration::days(INSTANCE_STATS_DAYS);
+
+        Ok(audit_logs::table
+            .filter(audit_logs::action.eq(AuditAction::LoginSuccess.as_str()))
+            .filter(audit_logs::created_at.ge(since))
+            .select(diesel::dsl::count_distinct(audit_logs::actor_id))
+            .get_result(&mut conn)
+            .await?)
+    }
+
+    async fn total_links(&self) -> Result<i64, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        Ok(links::table
+            .filter(links::deleted_at.is_null())
+            .count()
+            .get_result(&mut conn)
+            .await?)
+    }
+
+    /// Deleted links count too: they were still created that day
+    async fn links_created_per_day(
+        &self,
+        today: NaiveDate,
+    ) -> Result<Vec<DailyCount>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let from = today - Duration::days(INSTANCE_STATS_DAYS - 1);
+
+        let rows: Vec<DayCountRow> = diesel::sql_query(
+            "SELECT (created_at AT TIME ZONE 'UTC')::date AS day, COUNT(*) AS count
+             FROM links
+             WHERE created_at >= $1::timestamp AT TIME ZONE 'UTC'
+             GROUP BY day",
+        )
+        .bind::<diesel::sql_types::Date, _>(from)
+        .load(&mut conn)
+        .await?;
+
+        let counts: Vec<(NaiveDate, i64)> = rows.into_iter().map(|r| (r.day, r.count)).collect();
+        Ok(DailyCount::fill_days(from, today, &counts))
+    }
+
+    /// ClickHouse keeps every redirect; the click_count column only has clicks
+    /// counted since it was added, so it is the fallback
+    async fn total_redirects(&self) -> Option<i64> {
+        if let Some(ref analytics) = self.analytics {
+            match analytics.get_total_redirects().await {
+                Ok(total) => return Some(i64::try_from(total).unwrap_or(i64::MAX)),
+                Err(e) => warn!("Instance stats: falling back to click counters: {}", e),
+            }
+        }
+
+        log_unavailable("total_redirects", self.counted_redirects().await)
+    }
+
+    async fn counted_redirects(&self) -> Result<i64, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let row: TotalRow =
+            diesel::sql_query("SELECT COALESCE(SUM(click_count), 0)::BIGINT AS total FROM links")
+                .get_result(&mut conn)
+                .await?;
+        Ok(row.total)
+    }
+
+    async fn deleted_links(&self) -> Result<i64, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        Ok(links::table
+            .filter(links::deleted_at.is_not_null())
+            .count()
+            .get_result(&mut conn)
+            .await?)
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+fn log_unavailable<T>(figure: &str, result: Result<T, ServiceError>) -> Option<T> {
+    result
+        .map_err(|e| warn!("Instance stats: {} unavailable: {}", figure, e))
+        .ok()
+}
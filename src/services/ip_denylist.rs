@@ -0,0 +1,250 @@
+// Operator-managed client IP denylist
+// Persists entries in Postgres and keeps every replica's in-memory copy fresh via a Redis version
+// key (see list_sync). Checked on every request by ip_denylist_middleware.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::RwLock;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::denied_ip::{CreateDeniedIpRequest, DeniedIp, NewDeniedIp},
+    schema::denied_ips,
+    services::list_sync::{spawn_list_refresh_task, SyncedList},
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        service_error::ServiceError,
+    },
+};
+
+/// Redis key bumped on every denylist change so other replicas know to reload
+const DENYLIST_VERSION_KEY: &str = "ip_denylist:version";
+
+/// Denied addresses and when their denial ends (`None`: until removed)
+static DENIED_IPS: Lazy<RwLock<HashMap<IpAddr, Option<DateTime<Utc>>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Whether requests from `ip` are currently denied
+pub fn is_denied(ip: IpAddr) -> bool {
+    let denied = DENIED_IPS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match denied.get(&ip.to_canonical()) {
+        Some(Some(expires_at)) => *expires_at > Utc::now(),
+        Some(None) => true,
+        None => false,
+    }
+}
+
+fn replace_denied_ips(entries: HashMap<IpAddr, Option<DateTime<Utc>>>) {
+    *DENIED_IPS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = entries;
+}
+
+pub struct IpDenylistService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+}
+
+impl IpDenylistService {
+    pub fn new(diesel_pool: DieselPool, redis_pool: RedisPool) -> Self {
+        Self {
+            diesel_pool,
+            redis_pool,
+        }
+    }
+
+    /// List entries that have not expired, newest first
+    pub async fn list(&self) -> Result<Vec<DeniedIp>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entries = denied_ips::table
+            .filter(
+                denied_ips::expires_at
+                    .is_null()
+                    .or(denied_ips::expires_at.gt(Utc::now())),
+            )
+            .order(denied_ips::created_at.desc())
+            .select(DeniedIp::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(entries)
+    }
+
+    /// Deny an address, apply it locally and notify other replicas
+    pub async fn add(
+        &self,
+        request: &CreateDeniedIpRequest,
+        created_by: Uuid,
+    ) -> Result<DeniedIp, ServiceError> {
+        let ip = request
+            .normalized_ip()
+            .map_err(ServiceError::ValidationError)?
+            .to_string();
+
+        let mut conn = self.get_conn().await?;
+
+        // An expired entry for the same address no longer counts as a duplicate
+        diesel::delete(
+            denied_ips::table
+                .filter(denied_ips::ip.eq(&ip))
+                .filter(denied_ips::expires_at.le(Utc::now())),
+        )
+        .execute(&mut conn)
+        .await?;
+
+        let new_entry = NewDeniedIp {
+            ip: ip.clone(),
+            reason: request.reason.trim().to_string(),
+            created_by: Some(created_by),
+            expires_at: request
+                .expires_in_hours
+                .map(|hours| Utc::now() + chrono::Duration::hours(i64::from(hours))),
+        };
+
+        let entry = diesel::insert_into(denied_ips::table)
+            .values(&new_entry)
+            .returning(DeniedIp::as_returning())
+            .get_result(&mut conn)
+            .await
+            .map_err(|e| match e {
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => ServiceError::Conflict(format!("{} is already denied", ip)),
+                other => ServiceError::from(other),
+            })?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::IpDenied,
+            created_by,
+            "denied_ip",
+            Some(entry.id.to_string()),
+            Some(format!("Denied {}: {}", entry.ip, entry.reason)),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(entry)
+    }
+
+    /// Remove an entry, apply it locally and notify other replicas
+    pub async fn remove(&self, id: Uuid, removed_by: Uuid) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let entry = diesel::delete(denied_ips::table.find(id))
+            .returning(DeniedIp::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::IpUndenied,
+            removed_by,
+            "denied_ip",
+            Some(entry.id.to_string()),
+            Some(format!("Undenied {}", entry.ip)),
+        )
+        .await;
+
+        self.publish_change().await;
+
+        Ok(())
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl SyncedList for IpDenylistService {
+    const NAME: &'static str = "IP denylist";
+    const VERSION_KEY: &'static str = DENYLIST_VERSION_KEY;
+
+    fn redis_pool(&self) -> &RedisPool {
+        &self.redis_pool
+    }
+
+    /// Load all unexpired entries from Postgres into the shared in-memory denylist. Returns
+    /// the number of entries loaded.
+    async fn reload(&self) -> Result<usize, ServiceError> {
+        let entries = self.list().await?;
+
+        let denied: HashMap<IpAddr, Option<DateTime<Utc>>> = entries
+            .iter()
+            .filter_map(|entry| match entry.ip.parse::<IpAddr>() {
+                Ok(ip) => Some((ip.to_canonical(), entry.expires_at)),
+                Err(_) => {
+                    warn!("Skipping invalid denylist entry {}: {}", entry.id, entry.ip);
+                    None
+                },
+            })
+            .collect();
+
+        let count = denied.len();
+        replace_denied_ips(denied);
+        Ok(count)
+    }
+}
+
+/// Load the denylist once and then reload it whenever another replica changes it
+pub fn spawn_ip_denylist_refresh_task(state: AppState) {
+    spawn_list_refresh_task(
+        "ip_denylist_refresh",
+        state.config.security.ip_denylist_refresh_interval_seconds,
+        IpDenylistService::new(state.diesel_pool.clone(), state.redis_pool.clone()),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_denied_honours_expiry_and_canonical_form() {
+        let now = Utc::now();
+        replace_denied_ips(HashMap::from([
+            ("203.0.113.7".parse().unwrap(), None),
+            (
+                "203.0.113.8".parse().unwrap(),
+                Some(now + chrono::Duration::hours(1)),
+            ),
+            (
+                "203.0.113.9".parse().unwrap(),
+                Some(now - chrono::Duration::seconds(1)),
+            ),
+        ]));
+
+        assert!(is_denied("203.0.113.7".parse().unwrap()));
+        assert!(is_denied("::ffff:203.0.113.7".parse().unwrap()));
+        assert!(is_denied("203.0.113.8".parse().unwrap()));
+        assert!(!is_denied("203.0.113.9".parse().unwrap()));
+        assert!(!is_denied("198.51.100.1".parse().unwrap()));
+
+        replace_denied_ips(HashMap::new());
+    }
+}
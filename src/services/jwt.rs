@@ -8,8 +8,9 @@ use jsonwebtoken::{
     decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation,
 };
 use redis::AsyncCommands;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use thiserror::Error;
+use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::app_config::SECONDS_PER_DAY;
@@ -17,7 +18,29 @@ use crate::config::PermissionConfig;
 use crate::db::{DieselPool, RedisPool};
 use crate::models::auth::{AccessTokenClaims, RefreshTokenClaims};
 use crate::models::refresh_token::{DeviceInfo, RefreshToken, RefreshTokenError};
+use crate::models::security_incident::{
+    NewSecurityIncident, SecurityIncident, INCIDENT_REFRESH_TOKEN_REUSE,
+};
 use crate::models::user::{User, UserError};
+use crate::utils::ttl_cache::TtlCache;
+
+/// Lifetime of impersonation tokens; they are never paired with a refresh token
+pub const IMPERSONATION_TOKEN_EXPIRY_SECONDS: u64 = 15 * 60;
+
+/// How long a replica trusts its cached copy of a user's access token cutoff
+const TOKEN_CUTOFF_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Stale cutoffs are dropped once the cache holds more users than this
+const TOKEN_CUTOFF_CACHE_MAX_USERS: usize = 50_000;
+
+fn token_cutoff_cache() -> TtlCache<String, Option<u64>> {
+    TtlCache::new(TOKEN_CUTOFF_CACHE_TTL, TOKEN_CUTOFF_CACHE_MAX_USERS)
+}
+
+/// Redis key holding the Unix time before which a user's access tokens are invalid
+fn tokens_revoked_before_key(user_id: &str) -> String {
+    format!("auth:tokens_revoked_before:{}", user_id)
+}
 
 // Error types for JWT operations
 #[derive(Error, Debug)]
@@ -52,8 +75,10 @@ pub enum JwtError {
     #[error("Pool error: {0}")]
     PoolError(String),
 
+    /// A rotated refresh token was presented again; `incident_id` is the recorded
+    /// security incident, None if it couldn't be stored
     #[error("Token reuse detected - possible security breach")]
-    TokenReuseDetected,
+    TokenReuseDetected { incident_id: Option<Uuid> },
 
     #[error("Suspicious activity detected")]
     SuspiciousActivity,
@@ -192,6 +217,8 @@ pub struct JwtService {
     config: JwtConfig,
     db_pool: Option<DieselPool>,
     redis_pool: Option<RedisPool>,
+    // Users' access token cutoffs as last seen in Redis
+    token_cutoffs: TtlCache<String, Option<u64>>,
 }
 
 impl JwtService {
@@ -201,6 +228,7 @@ impl JwtService {
             config,
             db_pool: None,
             redis_pool: None,
+            token_cutoffs: token_cutoff_cache(),
         }
     }
 
@@ -210,6 +238,7 @@ impl JwtService {
             config,
             db_pool: Some(db_pool),
             redis_pool: None,
+            token_cutoffs: token_cutoff_cache(),
         }
     }
 
@@ -223,6 +252,7 @@ impl JwtService {
             config,
             db_pool: Some(db_pool),
             redis_pool: Some(redis_pool),
+            token_cutoffs: token_cutoff_cache(),
         }
     }
 
@@ -274,6 +304,46 @@ impl JwtService {
         email: &str,
         subscription_tier: &str,
         scope: Vec<String>,
+    ) -> Result<String, JwtError> {
+        self.encode_access_token(
+            user_id,
+            email,
+            subscription_tier,
+            scope,
+            self.config.access_token_expiry,
+            None,
+        )
+    }
+
+    /// Generate an access token for an admin acting as `user_id` ("login as user")
+    /// Valid for IMPERSONATION_TOKEN_EXPIRY_SECONDS and carries an `impersonator` claim;
+    /// no refresh token is issued, so the session cannot be extended
+    pub fn generate_impersonation_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        subscription_tier: &str,
+        scope: Vec<String>,
+        impersonator_id: &str,
+    ) -> Result<String, JwtError> {
+        self.encode_access_token(
+            user_id,
+            email,
+            subscription_tier,
+            scope,
+            IMPERSONATION_TOKEN_EXPIRY_SECONDS,
+            Some(impersonator_id.to_string()),
+        )
+    }
+
+    fn encode_access_token(
+        &self,
+        user_id: &str,
+        email: &str,
+        subscription_tier: &str,
+        scope: Vec<String>,
+        expiry_seconds: u64,
+        impersonator: Option<String>,
     ) -> Result<String, JwtError> {
         let now = SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -289,7 +359,8 @@ impl JwtService {
             aud: self.config.audience.clone(),
             iss: self.config.issuer.clone(),
             iat: now,
-            exp: now + self.config.access_token_expiry,
+            exp: now + expiry_seconds,
+            impersonator,
         };
 
         let mut header = Header::new(self.config.algorithm);
@@ -465,7 +536,7 @@ impl JwtService {
         }
 
         // Generate new tokens
-        let scope = crate::config::PermissionConfig::get_default_permissions();
+        let scope = crate::config::PermissionConfig::get_permissions_for_email(&user.email);
         let access_token = self.generate_access_token(
             &user.id.to_string(),
             &user.email,
@@ -513,19 +584,130 @@ impl JwtService {
         }
     }
 
-    /// Revoke all user tokens
+    /// Revoke all user tokens: every refresh token, and every access token issued so far
     pub async fn revoke_all_user_tokens(&self, user_id: &str) -> Result<usize, JwtError> {
-        if let Some(pool) = &self.db_pool {
+        let access_revoked = self.revoke_access_tokens(user_id).await;
+
+        let revoked = if let Some(pool) = &self.db_pool {
             let mut conn = pool
                 .get()
                 .await
                 .map_err(|e| JwtError::PoolError(e.to_string()))?;
 
             let user_uuid = Uuid::parse_str(user_id).map_err(|_| JwtError::InvalidToken)?;
-            Ok(RefreshToken::revoke_all_for_user(&mut conn, user_uuid).await?)
+            RefreshToken::revoke_all_for_user(&mut conn, user_uuid).await?
         } else {
-            Ok(0)
+            0
+        };
+
+        access_revoked?;
+        Ok(revoked)
+    }
+
+    /// Sign the user out everywhere ("revoke all sessions"). With `keep_refresh_token`,
+    /// refresh tokens of that token's rotation lineage survive, so its session can refresh;
+    /// every access token issued so far is invalidated either way. The kept token must be
+    /// live and belong to the user, otherwise nothing is revoked. Returns the number of
+    /// refresh tokens revoked.
+    pub async fn revoke_all_sessions(
+        &self,
+        user_id: &str,
+        keep_refresh_token: Option<&str>,
+    ) -> Result<usize, JwtError> {
+        let Some(keep_refresh_token) = keep_refresh_token else {
+            return self.revoke_all_user_tokens(user_id).await;
+        };
+
+        let user_uuid = Uuid::parse_str(user_id).map_err(|_| JwtError::InvalidToken)?;
+        let claims = self.validate_refresh_token(keep_refresh_token).await?;
+        if claims.sub != user_id {
+            return Err(JwtError::InvalidToken);
         }
+
+        let mut conn = self.get_db_connection().await?;
+        let kept = RefreshToken::validate(&mut conn, &claims.jti).await?;
+
+        self.revoke_access_tokens(user_id).await?;
+        let revoked = RefreshToken::revoke_all_for_user_except_family(
+            &mut conn,
+            user_uuid,
+            &kept.token_family,
+            "sessions_revoked",
+        )
+        .await?;
+        Ok(revoked)
+    }
+
+    /// Invalidate every access token issued to the user before now. Takes effect on this
+    /// replica at once and on the others within TOKEN_CUTOFF_CACHE_TTL.
+    pub async fn revoke_access_tokens(&self, user_id: &str) -> Result<(), JwtError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| JwtError::KeyGenerationError(e.to_string()))?
+            .as_secs();
+
+        self.cache_token_cutoff(user_id, Some(now));
+
+        if let Some(redis_pool) = &self.redis_pool {
+            let mut conn = redis_pool
+                .get_connection()
+                .await
+                .map_err(|e| JwtError::PoolError(e.to_string()))?;
+
+            // Tokens issued before the cutoff have all expired by the time the key does
+            let ttl_seconds = self
+                .config
+                .access_token_expiry
+                .max(IMPERSONATION_TOKEN_EXPIRY_SECONDS);
+            conn.set_ex::<_, _, ()>(tokens_revoked_before_key(user_id), now, ttl_seconds)
+                .await
+                .map_err(|e| JwtError::PoolError(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// Whether the access token was issued before its user's tokens were revoked.
+    /// Costs at most one Redis read per user per TOKEN_CUTOFF_CACHE_TTL; if Redis is
+    /// unavailable the last known cutoff is used.
+    pub async fn is_access_token_revoked(&self, claims: &AccessTokenClaims) -> bool {
+        // Tokens issued in the same second as the cutoff stay valid, so logging in again
+        // right after a forced logout works
+        self.tokens_revoked_before(&claims.sub)
+            .await
+            .is_some_and(|cutoff| claims.iat < cutoff)
+    }
+
+    async fn tokens_revoked_before(&self, user_id: &str) -> Option<u64> {
+        let cached = self.token_cutoffs.get(user_id);
+
+        // Without Redis the cutoffs set on this replica are all there is
+        let Some(redis_pool) = &self.redis_pool else {
+            return cached.and_then(|(revoked_before, _)| revoked_before);
+        };
+        if let Some((revoked_before, true)) = cached {
+            return revoked_before;
+        }
+
+        let fetched = match redis_pool
+            .get::<u64>(&tokens_revoked_before_key(user_id))
+            .await
+        {
+            Ok(revoked_before) => revoked_before,
+            Err(e) => {
+                warn!("Failed to read access token cutoff for {}: {}", user_id, e);
+                None
+            },
+        };
+        self.cache_token_cutoff(user_id, fetched)
+    }
+
+    /// Record a cutoff, keeping the later one if a newer cutoff was cached meanwhile.
+    /// Returns the cutoff now in effect.
+    fn cache_token_cutoff(&self, user_id: &str, revoked_before: Option<u64>) -> Option<u64> {
+        self.token_cutoffs
+            .insert_with(user_id.to_string(), |previous| {
+                previous.copied().flatten().max(revoked_before)
+            })
     }
 
     /// Generate refresh token with device information
@@ -650,36 +832,15 @@ impl JwtService {
                         .await
                         .map_err(|e| JwtError::PoolError(e.to_string()))?;
 
-                    // Check the token in database to see if it should trigger family revocation
-                    use crate::schema::refresh_tokens::dsl::*;
-                    use diesel::prelude::*;
-                    use diesel_async::RunQueryDsl;
-
-                    let jti_hash_val = RefreshToken::hash_jti(&token_data.claims.jti);
-                    let token_info = refresh_tokens
-                        .filter(jti_hash.eq(&jti_hash_val))
-                        .first::<RefreshToken>(&mut conn)
-                        .await
-                        .optional()
-                        .map_err(|e| JwtError::DatabaseError(RefreshTokenError::Database(e)))?;
-
-                    if let Some(token) = token_info {
-                        // If this token was revoked due to rotation, it's a security breach
-                        if token.revoked_reason.as_deref() == Some("rotation") {
-                            // Revoke entire family
-                            eprintln!(
-                                "DEV-105: Reuse of rotated token detected! Revoking family {}",
-                                &token.token_family
-                            );
-                            RefreshToken::revoke_token_family(
-                                &mut conn,
-                                &token.token_family,
-                                "token_reuse_detected",
-                            )
-                            .await?;
-                            return Err(JwtError::TokenReuseDetected);
-                        }
-                    }
+                    return Err(self
+                        .handle_revoked_refresh_token(
+                            &mut conn,
+                            &token_data.claims.jti,
+                            device_fingerprint,
+                            ip_address,
+                            user_agent,
+                        )
+                        .await);
                 }
                 return Err(JwtError::TokenRevoked);
             },
@@ -696,6 +857,14 @@ impl JwtService {
             .await
             .map_err(|e| JwtError::PoolError(e.to_string()))?;
 
+        // Kept for reuse handling, which runs after the transaction has rolled back
+        let old_jti = old_claims.jti.clone();
+        let reuse_context = (
+            device_fingerprint.clone(),
+            ip_address.clone(),
+            user_agent.clone(),
+        );
+
         // Start transaction for atomic rotation
         use diesel_async::AsyncConnection;
         let result: Result<(String, String, bool), JwtError> = conn
@@ -713,39 +882,9 @@ impl JwtService {
                     let existing_token = match validation_result {
                         Ok(token) => token,
                         Err(RefreshTokenError::Revoked) => {
-                            // Token is revoked - this could be a reuse attack
-                            // Get the token info to check its family
-                            use crate::schema::refresh_tokens::dsl::*;
-                            use diesel::prelude::*;
-                            use diesel_async::RunQueryDsl;
-
-                            let jti_hash_val = RefreshToken::hash_jti(&old_claims.jti);
-                            let token_info = refresh_tokens
-                                .filter(jti_hash.eq(&jti_hash_val))
-                                .first::<RefreshToken>(tx)
-                                .await
-                                .optional()
-                                .map_err(|e| {
-                                    JwtError::DatabaseError(RefreshTokenError::Database(e))
-                                })?;
-
-                            if let Some(token) = token_info {
-                                // Check if this was revoked as part of normal rotation
-                                // For security, any attempt to use a rotated token should revoke the family
-                                // This prevents token theft where an attacker gets an old token
-                                if token.revoked_reason.as_deref() == Some("rotation") {
-                                    // This is a reuse attack - someone is trying to use an already-rotated token!
-                                    // Revoke entire family for security
-                                    let _revoked_count = RefreshToken::revoke_token_family(
-                                        tx,
-                                        &token.token_family,
-                                        "token_reuse_detected",
-                                    )
-                                    .await?;
-                                    return Err(JwtError::TokenReuseDetected);
-                                }
-                            }
-                            // Token was already revoked, return the error
+                            // Revoked since it was validated above, e.g. by a concurrent
+                            // refresh. Family revocation would roll back with this
+                            // transaction, so it happens after it.
                             return Err(JwtError::TokenRevoked);
                         },
                         Err(e) => return Err(e.into()),
@@ -780,7 +919,7 @@ impl JwtService {
                     let user = User::find_by_id(tx, existing_token.user_id).await?;
 
                     // Get user's permissions (OSS: everyone gets full permissions)
-                    let user_scopes = PermissionConfig::get_default_permissions();
+                    let user_scopes = PermissionConfig::get_permissions_for_email(&user.email);
 
                     // Generate new token pair with actual user data
                     let new_access_token = self.generate_access_token(
@@ -848,7 +987,94 @@ impl JwtService {
             })
             .await;
 
-        result
+        match result {
+            Err(JwtError::TokenRevoked) => {
+                let (device_fingerprint, ip_address, user_agent) = reuse_context;
+                Err(self
+                    .handle_revoked_refresh_token(
+                        &mut conn,
+                        &old_jti,
+                        device_fingerprint,
+                        ip_address,
+                        user_agent,
+                    )
+                    .await)
+            },
+            result => result,
+        }
+    }
+
+    /// Decide how to answer a revoked refresh token. One revoked by rotation was copied
+    /// before it was rotated: its whole family is revoked and a security incident recorded.
+    async fn handle_revoked_refresh_token(
+        &self,
+        conn: &mut AsyncPgConnection,
+        jti: &str,
+        device_fingerprint: Option<String>,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> JwtError {
+        use crate::schema::refresh_tokens;
+        use diesel::prelude::*;
+        use diesel_async::RunQueryDsl;
+
+        let jti_hash_val = RefreshToken::hash_jti(jti);
+        let token = match refresh_tokens::table
+            .filter(refresh_tokens::jti_hash.eq(&jti_hash_val))
+            .first::<RefreshToken>(conn)
+            .await
+            .optional()
+        {
+            Ok(Some(token)) => token,
+            Ok(None) => return JwtError::TokenRevoked,
+            Err(e) => return JwtError::DatabaseError(RefreshTokenError::Database(e)),
+        };
+
+        // Any attempt to use a rotated token means it was stolen, or the client is replaying it
+        if token.revoked_reason.as_deref() != Some("rotation") {
+            return JwtError::TokenRevoked;
+        }
+
+        warn!(
+            "Reuse of rotated refresh token detected; revoking family {} of user {}",
+            token.token_family, token.user_id
+        );
+        let revoked = match RefreshToken::revoke_token_family(
+            conn,
+            &token.token_family,
+            "token_reuse_detected",
+        )
+        .await
+        {
+            Ok(revoked) => revoked,
+            Err(e) => return e.into(),
+        };
+
+        let incident = SecurityIncident::create(
+            conn,
+            NewSecurityIncident {
+                user_id: token.user_id,
+                kind: INCIDENT_REFRESH_TOKEN_REUSE.to_string(),
+                ip_address,
+                user_agent,
+                device_fingerprint,
+                token_family: Some(token.token_family),
+                revoked_tokens: revoked as i32,
+            },
+        )
+        .await;
+
+        let incident_id = match incident {
+            Ok(incident) => Some(incident.id),
+            Err(e) => {
+                error!(
+                    "Failed to record token reuse incident for user {}: {}",
+                    token.user_id, e
+                );
+                None
+            },
+        };
+        JwtError::TokenReuseDetected { incident_id }
     }
 }
 
@@ -892,5 +1118,54 @@ mod tests {
         let claims = service.validate_access_token(&token).unwrap();
         assert_eq!(claims.sub, "test-user-id");
         assert_eq!(claims.email, "test@example.com");
+        assert_eq!(claims.impersonator, None);
+    }
+
+    #[tokio::test]
+    async fn test_impersonation_token_cannot_be_refreshed() {
+        let service = JwtService::new(JwtConfig::for_test());
+
+        let token = service
+            .generate_impersonation_token(
+                "target-user-id",
+                "user@example.com",
+                "free",
+                vec!["read".to_string()],
+                "admin-user-id",
+            )
+            .unwrap();
+
+        let claims = service.validate_access_token(&token).unwrap();
+        assert_eq!(claims.sub, "target-user-id");
+        assert_eq!(claims.impersonator.as_deref(), Some("admin-user-id"));
+        assert_eq!(claims.exp - claims.iat, IMPERSONATION_TOKEN_EXPIRY_SECONDS);
+
+        // Access tokens are signed with a different key than refresh tokens
+        assert!(service.validate_refresh_token(&token).await.is_err());
+        assert!(matches!(
+            service.refresh_tokens(&token).await,
+            Err(JwtError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_revoke_access_tokens_rejects_earlier_tokens() {
+        let service = JwtService::new(JwtConfig::for_test());
+        let token = service
+            .generate_access_token("user-a", "a@example.com", "free", vec![])
+            .unwrap();
+        let mut claims = service.validate_access_token(&token).unwrap();
+        assert!(!service.is_access_token_revoked(&claims).await);
+
+        service.revoke_access_tokens("user-a").await.unwrap();
+
+        // Issued in the same second as the cutoff: still valid
+        assert!(!service.is_access_token_revoked(&claims).await);
+
+        claims.iat -= 1;
+        assert!(service.is_access_token_revoked(&claims).await);
+
+        claims.sub = "user-b".to_string();
+        assert!(!service.is_access_token_revoked(&claims).await);
     }
 }
@@ -4,11 +4,10 @@
 
 use chrono::Utc;
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel_async::{AsyncConnection, RunQueryDsl};
 use once_cell::sync::Lazy;
-use redis::AsyncCommands;
-use reqwest::Client;
 use scraper::Html;
+use std::collections::HashSet;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -20,22 +19,43 @@ use crate::{
     app::AppState,
     db::{DieselPool, RedisPool},
     models::{
+        account_export::ArchivedLink,
         link::{
-            CreateLinkRequest, ExtractedMetadata, Link, LinkMetadata, LinkResponse,
-            ListLinksParams, NewLink, UpdateLink, UpdateLinkRequest,
+            check_url_length, lower, normalize_referrer_domains, normalize_tags,
+            passthrough_destination, referrer_column, sliding_expiry, validate_tags,
+            BulkStatusItem, BulkStatusOutcome, CheckAliasResponse, CodeMeta, CreateLinkRequest,
+            ExpiryPolicy, ExtractedMetadata, Link, LinkFilter, LinkMetadata, LinkOwner,
+            LinkRequestError, LinkResponse, LinkScanUpdate, ListLinksParams, NewLink,
+            QuickLinkRequest, QuickLinkResponse, RedirectRecord, TrackingMode, UpdateLink,
+            UpdateLinkRequest, ValidateLinkResponse, DEACTIVATION_REASON_EXPIRED,
         },
+        link_revision::{LinkHistoryQuery, LinkHistoryResponse, NewLinkRevision, RevisionSource},
+        organization::{OrgAction, OrgRole, OrganizationMember},
         user::User,
     },
-    services::{clickhouse_analytics::ClickHouseAnalyticsService, short_code::ShortCodeGenerator},
+    services::{
+        click_counter::{self, CLICK_COUNTER},
+        click_dedup::ClickDeduplicator,
+        click_tracking::{ClickContext, ClickedUrl},
+        clickhouse_analytics::ClickHouseAnalyticsService,
+        link_pause::is_owner_paused,
+        link_revision::LinkRevisionService,
+        metadata_throttle::{self, ExtractionOutcome, METADATA_THROTTLE},
+        organization::authorize,
+        quarantine::QuarantineService,
+        robots,
+        security_alerts::SecurityAlertService,
+        short_code::ShortCodeGenerator,
+    },
     utils::{
         audit_logger::{AuditAction, AuditLogger},
-        security_scanner::SecurityService,
+        safe_http::SafeHttpClient,
+        security_scanner::{SecurityScanResult, SecurityService},
         service_error::ServiceError,
-        url_validator::{UrlMetadata, UrlValidator},
+        url_validator::{host_to_unicode, UrlMetadata, UrlValidator},
     },
     CONFIG,
 };
-use crate::app_config::SECONDS_PER_DAY;
 
 // =============================================================================
 // TYPES
@@ -44,12 +64,40 @@ use crate::app_config::SECONDS_PER_DAY;
 /// ClickHouse link statistics
 #[derive(Debug, Clone, Default)]
 pub struct LinkClickStats {
+    /// Clicks excluding repeats from the same visitor within the dedup window
     pub total_clicks: u64,
+    /// Every click, repeats included
+    pub raw_clicks: u64,
     pub unique_visitors: u64,
     pub bot_clicks: u64,
     pub last_accessed_at: Option<chrono::DateTime<Utc>>,
 }
 
+/// Live links a user owns against MAX_LINKS_PER_USER
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LinkUsage {
+    pub current: u64,
+    pub limit: u64,
+}
+
+impl LinkUsage {
+    /// Response header on link creation once the user holds 80% of the cap
+    pub const WARNING_HEADER: &'static str = "x-link-limit-warning";
+
+    pub fn is_reached(&self) -> bool {
+        self.current >= self.limit
+    }
+
+    pub fn is_near_limit(&self) -> bool {
+        self.current.saturating_mul(5) >= self.limit.saturating_mul(4)
+    }
+
+    /// `WARNING_HEADER` value, e.g. `count=812, limit=1000`
+    pub fn header_value(&self) -> String {
+        format!("count={}, limit={}", self.current, self.limit)
+    }
+}
+
 // =============================================================================
 // CONSTANTS
 // =============================================================================
@@ -57,21 +105,27 @@ pub struct LinkClickStats {
 /// Cache TTL for link data (1 hour)
 const LINK_CACHE_TTL_SECONDS: usize = 3600;
 
-/// Click counter TTL (24 hours)
-const CLICK_COUNTER_TTL_SECONDS: usize = 86400;
+/// Cache TTL for link ownership; the longest a missed invalidation can let a previous owner
+/// keep reading a link's stats
+pub const OWNER_CACHE_TTL_SECONDS: u64 = 30;
+
+/// Cache TTL for per-user link counts behind MAX_LINKS_PER_USER
+const LINK_COUNT_CACHE_TTL_SECONDS: usize = 300;
+
+/// How long `check-alias?reserve=true` holds an alias for the caller
+pub const ALIAS_RESERVATION_TTL_SECONDS: usize = 60;
 
 /// Batch size for processing click counters
 const CLICK_SYNC_BATCH_SIZE: usize = 100;
 
-// Shared HTTP client for metadata extraction with connection pooling
-static METADATA_HTTP_CLIENT: Lazy<Client> = Lazy::new(|| {
-    Client::builder()
-        .pool_max_idle_per_host(10)
-        .pool_idle_timeout(Duration::from_secs(30))
-        .timeout(Duration::from_secs(3))
-        .user_agent("Mozilla/5.0 (compatible; QCK-Bot/1.0)")
-        .build()
-        .expect("Failed to create HTTP client for metadata extraction")
+/// Links deactivated per statement by the expiry sweep
+const EXPIRY_SWEEP_BATCH_SIZE: i64 = 500;
+
+// Shared SSRF-safe HTTP client for metadata extraction
+// (resolves and pins public addresses for every hop instead of pooling connections)
+static METADATA_HTTP_CLIENT: Lazy<SafeHttpClient> = Lazy::new(|| {
+    SafeHttpClient::new("Mozilla/5.0 (compatible; QCK-Bot/1.0)")
+        .with_timeout(Duration::from_secs(3))
 });
 
 // =============================================================================
@@ -94,13 +148,50 @@ pub struct LinkService {
     diesel_pool: DieselPool,
     redis_pool: RedisPool,
     short_code_generator: ShortCodeGenerator,
-    security_service: SecurityService,
+    security_service: Arc<SecurityService>,
+    security_alerts: SecurityAlertService,
     base_url: String,
     // Cache monitoring
     cache_hits: Arc<AtomicU64>,
     cache_misses: Arc<AtomicU64>,
     // Unified ClickHouse service for analytics and event tracking
     clickhouse_analytics: Option<Arc<ClickHouseAnalyticsService>>,
+    click_dedup: ClickDeduplicator,
+    // Set when quick links defer their external security checks (QUICK_LINK_DEFERRED_SCAN)
+    deferred_scans: Option<Arc<QuarantineService>>,
+    // Longest destination URL accepted, in bytes (MAX_URL_LENGTH)
+    max_url_length: usize,
+    // Live links per user; 0 = unlimited (MAX_LINKS_PER_USER)
+    max_links_per_user: u64,
+    // Only the local security checks on create, never the external lookups (self-test)
+    local_scans_only: bool,
+}
+
+/// Where a short code redirects to, resolved from the compact redirect record
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedRedirect {
+    pub link_id: Uuid,
+    pub destination: String,
+    /// HTTP status of the redirect response
+    pub redirect_type: u16,
+    pub tracking_mode: TrackingMode,
+    /// The requested code matched the link's custom alias rather than its short code
+    pub via_alias: bool,
+    /// The link's referrer restrictions refused the visitor; answer with a 403, not a redirect
+    pub blocked_by_referrer: bool,
+}
+
+/// Result of submitting a password for a protected link
+#[derive(Debug)]
+pub enum UnlockOutcome {
+    Unlocked {
+        link_id: Uuid,
+        original_url: String,
+        tracking_mode: TrackingMode,
+        via_alias: bool,
+    },
+    /// Identifies the link so the failed attempt can be reported to its owner
+    WrongPassword { link_id: Uuid, owner_id: Uuid },
 }
 
 impl LinkService {
@@ -116,15 +207,40 @@ impl LinkService {
         Self {
             diesel_pool: state.diesel_pool.clone(),
             redis_pool: state.redis_pool.clone(),
-            short_code_generator: ShortCodeGenerator::new(state.diesel_pool.clone()),
-            security_service: SecurityService::new(clickhouse_client),
-            base_url: format!("https://{}", CONFIG.jwt.audience.clone()), // Using JWT audience as base domain
+            short_code_generator: ShortCodeGenerator::with_redis(
+                state.diesel_pool.clone(),
+                Some(state.redis_pool.clone()),
+            ),
+            security_service: Arc::new(
+                SecurityService::new(clickhouse_client)
+                    .with_redis_cache(state.redis_pool.clone())
+                    .with_threat_lookup(state.threat_lookup.clone()),
+            ),
+            security_alerts: SecurityAlertService::from_config(&state.config.security),
+            base_url: state.config.short_link_base_url.clone(),
             cache_hits: Arc::new(AtomicU64::new(0)),
             cache_misses: Arc::new(AtomicU64::new(0)),
             clickhouse_analytics: state.clickhouse_analytics.clone(),
+            click_dedup: ClickDeduplicator::from_state(state),
+            deferred_scans: state
+                .config
+                .security
+                .quick_link_deferred_scan
+                .then(|| Arc::new(QuarantineService::new(state))),
+            max_url_length: state.config.max_url_length,
+            max_links_per_user: state.config.max_links_per_user,
+            local_scans_only: false,
         }
     }
 
+    /// Create links after only the local security checks (blocklist, URL patterns, domain
+    /// heuristics), with no deferred external scan either. For the self-test, which must not
+    /// depend on third-party services.
+    pub fn with_local_scans_only(mut self) -> Self {
+        self.local_scans_only = true;
+        self
+    }
+
     /// Get cache statistics
     pub fn get_cache_stats(&self) -> CacheStats {
         let hits = AtomicU64::load(&self.cache_hits, Ordering::Relaxed);
@@ -153,35 +269,61 @@ impl LinkService {
     }
 
     /// Create a new short link
-    #[instrument(skip(self, user, request))]
     pub async fn create_link(
+        &self,
+        user: &User,
+        request: CreateLinkRequest,
+    ) -> Result<LinkResponse, ServiceError> {
+        self.create_link_with(user, request, false).await
+    }
+
+    /// Create a link from just a URL, for browser extensions. The link is active right away
+    /// with the destination host as its title, and no metadata is fetched. With
+    /// QUICK_LINK_DEFERRED_SCAN only the local security checks run before the response; the
+    /// external lookups follow in the background and quarantine the link if they flag it.
+    pub async fn quick_create_link(
+        &self,
+        user: &User,
+        request: QuickLinkRequest,
+    ) -> Result<QuickLinkResponse, ServiceError> {
+        let link = self.create_link_with(user, request.into(), true).await?;
+        Ok(QuickLinkResponse {
+            short_url: link.short_url,
+        })
+    }
+
+    #[instrument(skip(self, user, request))]
+    async fn create_link_with(
         &self,
         user: &User,
         mut request: CreateLinkRequest,
+        quick: bool,
     ) -> Result<LinkResponse, ServiceError> {
         info!("Creating new link for user: {}", user.id);
 
         // 1. Sanitize and validate request
-        request.sanitize();
-        request.validate()?;
-        request
-            .validate_custom()
-            .map_err(|e| ServiceError::ValidationError(e))?;
+        let (allowed_referrers, blocked_referrers) = self.check_create_request(&mut request)?;
 
         // 2. Validate subscription limits
         self.validate_subscription_limits(user).await?;
 
-        // 3. Normalize URL FIRST (use async version for proper validation)
-        let normalized_url = crate::utils::normalize_url_async(&request.url).await?;
+        // Organization links may be created by any member
+        if let Some(organization_id) = request.organization_id {
+            self.authorize_org(organization_id, user.id, OrgAction::CreateLink)
+                .await?;
+        }
 
-        // 4. Security scan the NORMALIZED URL with comprehensive threat detection
-        let security_result = self
-            .security_service
-            .comprehensive_security_scan(&normalized_url)
-            .await
-            .map_err(|e| ServiceError::SecurityBlocked(format!("Security scan failed: {}", e)))?;
+        // 3-4. Normalize the URL, then security scan the NORMALIZED URL
+        // (local checks only for quick links with deferred scanning; the rest runs after creation)
+        let defer_scan = quick && self.deferred_scans.is_some() && !self.local_scans_only;
+        let (normalized_url, security_result) = self
+            .scan_destination(user, &request.url, defer_scan || self.local_scans_only)
+            .await?;
+
+        // Tell operators about blocked or high-risk URLs (fire-and-forget)
+        self.security_alerts.notify(user.id, &security_result);
 
-        if !security_result.is_safe {
+        if let Some(security_message) = blocked_reason(&security_result) {
             warn!(
                 "URL blocked for security: {} - Threat score: {}, Risk: {:?}, Threats: {:?}",
                 normalized_url,
@@ -190,15 +332,6 @@ impl LinkService {
                 security_result.threats_detected
             );
 
-            let security_message = if !security_result.warnings.is_empty() {
-                security_result.warnings.join("; ")
-            } else {
-                format!(
-                    "URL blocked due to security threats detected (Risk: {:?}, Score: {})",
-                    security_result.risk_level, security_result.threat_score
-                )
-            };
-
             return Err(ServiceError::SecurityBlocked(security_message));
         }
 
@@ -210,10 +343,12 @@ impl LinkService {
             );
         }
 
+        let scan = LinkScanUpdate::from(&security_result);
+
         // 5. Generate or validate short code
         let short_code = if let Some(ref custom_alias) = request.custom_alias {
             // Validate custom alias using the specification method
-            self.validate_custom_alias(custom_alias).await?;
+            self.validate_custom_alias(custom_alias, user.id).await?;
             custom_alias.clone()
         } else {
             // Generate random short code
@@ -256,18 +391,35 @@ impl LinkService {
             Some(request.tags.iter().map(|t| Some(t.clone())).collect())
         };
 
+        // Quick links skip metadata extraction and are titled with the destination host
+        let fallback_title = if quick {
+            robots_fallback_title(&normalized_url)
+        } else {
+            None
+        };
+
         // 10. Create link record with all fields
         let new_link = NewLink {
             id: Uuid::new_v4(),
             user_id: user.id,
             short_code: short_code.clone(),
             original_url: normalized_url,
-            title: request.title.clone().or(metadata.title.clone()),
+            title: request
+                .title
+                .clone()
+                .or(metadata.title.clone())
+                .or(fallback_title),
             description: request.description.clone().or(metadata.description.clone()),
             tags,
             custom_alias: request.custom_alias.clone(),
-            is_active: false, // Start inactive until metadata extraction completes
-            expires_at: request.expires_at,
+            is_active: quick, // Others start inactive until metadata extraction completes
+            // Sliding links start their first window now
+            expires_at: match request.expiry_policy {
+                ExpiryPolicy::Fixed => request.expires_at,
+                ExpiryPolicy::Sliding => request
+                    .sliding_window_days
+                    .map(|days| sliding_expiry(Utc::now(), days)),
+            },
             password_hash,
             last_accessed_at: None,
             utm_source: None, // Could be extracted from URL
@@ -278,16 +430,37 @@ impl LinkService {
             created_at: Utc::now(),
             updated_at: Utc::now(),
             deleted_at: None, // New links are not deleted
-            processing_status: "extracting".to_string(),
+            processing_status: if quick { "skipped" } else { "extracting" }.to_string(),
             metadata_extracted_at: None,
             og_image: request.og_image.clone(),
             favicon_url: request.favicon_url.clone(),
+            // Keep the creation-time scan so owners can see why a URL drew warnings
+            last_scanned_at: scan.last_scanned_at,
+            threat_score: scan.threat_score,
+            risk_level: scan.risk_level,
+            threats_detected: scan.threats_detected,
+            scan_warnings: scan.scan_warnings,
+            tracking_mode: request
+                .tracking_mode
+                .map(|mode| mode.as_str().to_string())
+                .unwrap_or_else(|| user.default_tracking_mode.clone()),
+            organization_id: request.organization_id,
+            path_passthrough: request.path_passthrough,
+            allowed_referrers: referrer_column(allowed_referrers),
+            blocked_referrers: referrer_column(blocked_referrers),
+            referrer_strict: request.referrer_strict,
+            expiry_policy: request.expiry_policy.as_str().to_string(),
+            sliding_window_days: request.sliding_window_days,
         };
 
         // 9. Insert into database with transaction
         let link = self
             .insert_link(new_link, user.id, &user.subscription_tier)
             .await?;
+        self.invalidate_link_count(user.id).await;
+        if let Some(ref alias) = link.custom_alias {
+            self.release_alias(alias).await;
+        }
 
         // 10. Cache link for fast redirects
         self.cache_link(&link).await?;
@@ -301,12 +474,17 @@ impl LinkService {
         )
         .await;
 
+        // Quick links are already active; only the deferred scan is left
+        if quick {
+            if defer_scan {
+                self.spawn_deferred_scan(link.clone());
+            }
+            return Ok(link.to_response_with_stats(&self.base_url, LinkClickStats::default()));
+        }
+
         // 12. Spawn background task for metadata extraction (with rate limiting)
         // Skip metadata extraction if user provided all metadata fields
-        let needs_metadata_extraction = request.title.is_none()
-            || request.description.is_none()
-            || request.og_image.is_none()
-            || request.favicon_url.is_none();
+        let needs_metadata_extraction = !request.metadata_to_extract().is_empty();
 
         let link_id = link.id;
         let original_url = link.original_url.clone();
@@ -314,6 +492,12 @@ impl LinkService {
         let redis_pool = Arc::new(self.redis_pool.clone());
 
         if needs_metadata_extraction {
+            let respect_robots = CONFIG.metadata_respect_robots;
+            let fallback_title = request
+                .title
+                .clone()
+                .or_else(|| robots_fallback_title(&original_url));
+
             // Use a static semaphore to limit concurrent metadata extractions
             static METADATA_SEMAPHORE: Lazy<Arc<tokio::sync::Semaphore>> = Lazy::new(|| {
                 // Allow max 5 concurrent metadata extractions to avoid overwhelming external servers
@@ -322,6 +506,33 @@ impl LinkService {
 
             let semaphore = METADATA_SEMAPHORE.clone();
             tokio::spawn(async move {
+                // Take the per-host slot first so links queued behind a slow host don't
+                // hold global permits that extractions for other hosts could use
+                let host = metadata_throttle::throttle_key(&original_url);
+                let _host_permit = match &host {
+                    Some(host) => match METADATA_THROTTLE.acquire(host).await {
+                        Ok(permit) => Some(permit),
+                        Err(open) => {
+                            info!(
+                                "Skipping metadata extraction for {}: circuit open for {} ({}s left)",
+                                link_id,
+                                host,
+                                open.retry_after.as_secs()
+                            );
+                            if let Err(e) =
+                                activate_link_after_failure(diesel_pool, link_id, redis_pool).await
+                            {
+                                warn!(
+                                    "Failed to activate link after skipped extraction {}: {}",
+                                    link_id, e
+                                );
+                            }
+                            return;
+                        },
+                    },
+                    None => None,
+                };
+
                 // Acquire permit before extraction (will wait if too many concurrent)
                 let _permit = match semaphore.acquire().await {
                     Ok(permit) => permit,
@@ -331,14 +542,60 @@ impl LinkService {
                     },
                 };
 
-                // Add small delay for bulk operations to avoid thundering herd
-                if semaphore.available_permits() < 3 {
-                    tokio::time::sleep(Duration::from_millis(100)).await;
+                // robots.txt is fetched under the host and global permits held above
+                if respect_robots {
+                    let outcome = match robots::is_allowed(&original_url, Some(&redis_pool)).await {
+                        Ok(true) => None,
+                        Ok(false) => Some(ExtractionOutcome::Disallowed),
+                        Err(e) => {
+                            // An unreachable robots.txt is treated as disallowing everything
+                            warn!("robots.txt check failed for {}: {}", link_id, e);
+                            Some(if e.is_timeout() {
+                                ExtractionOutcome::Timeout
+                            } else {
+                                ExtractionOutcome::Failed
+                            })
+                        },
+                    };
+
+                    if let Some(outcome) = outcome {
+                        info!(
+                            "Skipping metadata extraction for {}: not allowed by robots.txt",
+                            link_id
+                        );
+                        if let Some(host) = &host {
+                            METADATA_THROTTLE.record(host, outcome);
+                        }
+                        if let Err(e) = activate_link_with_fallback_title(
+                            diesel_pool,
+                            link_id,
+                            fallback_title,
+                            redis_pool,
+                        )
+                        .await
+                        {
+                            warn!(
+                                "Failed to activate link after robots.txt skip {}: {}",
+                                link_id, e
+                            );
+                        }
+                        return;
+                    }
                 }
 
                 // Try to extract metadata
                 let validator = UrlValidator::new();
-                match validator.extract_metadata(&original_url).await {
+                let result = validator.extract_metadata(&original_url).await;
+                if let Some(host) = &host {
+                    let outcome = match &result {
+                        Ok(_) => ExtractionOutcome::Success,
+                        Err(e) if e.is_timeout() => ExtractionOutcome::Timeout,
+                        Err(_) => ExtractionOutcome::Failed,
+                    };
+                    METADATA_THROTTLE.record(host, outcome);
+                }
+
+                match result {
                     Ok(metadata) => {
                         // Update link with extracted metadata and activate it
                         if let Err(e) = update_link_metadata_and_activate(
@@ -365,7 +622,7 @@ impl LinkService {
                         }
                     },
                 }
-                // Permit is automatically released when _permit goes out of scope
+                // Permits are automatically released when they go out of scope
             });
         } else {
             // User provided all metadata, just activate the link immediately
@@ -391,8 +648,168 @@ impl LinkService {
         Ok(response)
     }
 
+    /// Run the external security checks skipped when a quick link was created, quarantining
+    /// the link if they would have rejected its URL
+    fn spawn_deferred_scan(&self, link: Link) {
+        let Some(quarantine) = self.deferred_scans.clone() else {
+            return;
+        };
+        let security = self.security_service.clone();
+
+        tokio::spawn(async move {
+            match security
+                .comprehensive_security_scan(&link.original_url)
+                .await
+            {
+                Ok(result) => match quarantine.record_deferred_scan(&link, &result).await {
+                    Ok(true) => info!("Deferred scan quarantined quick link {}", link.id),
+                    Ok(false) => {},
+                    Err(e) => error!("Failed to record deferred scan of {}: {}", link.id, e),
+                },
+                Err(e) => warn!("Deferred scan of quick link {} failed: {}", link.id, e),
+            }
+        });
+    }
+
+    /// Work out what creating a link from `request` would do, without writing anything
+    /// Runs the same checks, URL normalization, security scan and alias validation as
+    /// `create_link`; a blocked URL or taken alias is reported in the response rather than
+    /// failing it. With `fetch_title` the destination's title is fetched within 1 second.
+    pub async fn validate_link(
+        &self,
+        user: &User,
+        mut request: CreateLinkRequest,
+        fetch_title: bool,
+    ) -> Result<ValidateLinkResponse, ServiceError> {
+        self.check_create_request(&mut request)?;
+        if let Some(organization_id) = request.organization_id {
+            self.authorize_org(organization_id, user.id, OrgAction::CreateLink)
+                .await?;
+        }
+
+        let (normalized_url, security_result) = self
+            .scan_destination(user, &request.url, self.local_scans_only)
+            .await?;
+        let blocked_reason = blocked_reason(&security_result);
+
+        let alias = match request.custom_alias.as_deref() {
+            Some(alias) => Some(self.alias_verdict(alias, user.id).await?),
+            None => None,
+        };
+
+        // Blocked destinations are never fetched
+        let title = if fetch_title && request.title.is_none() && blocked_reason.is_none() {
+            tokio::time::timeout(
+                Duration::from_secs(1),
+                self.try_extract_metadata(&normalized_url),
+            )
+            .await
+            .ok()
+            .flatten()
+            .and_then(|metadata| metadata.title)
+        } else {
+            None
+        };
+
+        Ok(ValidateLinkResponse {
+            normalized_url,
+            allowed: blocked_reason.is_none(),
+            blocked_reason,
+            threat_score: security_result.threat_score,
+            risk_level: format!("{:?}", security_result.risk_level),
+            threats_detected: security_result
+                .threats_detected
+                .iter()
+                .map(|threat| format!("{:?}", threat))
+                .collect(),
+            warnings: security_result.warnings,
+            alias,
+            metadata_to_extract: request
+                .metadata_to_extract()
+                .into_iter()
+                .map(String::from)
+                .collect(),
+            title,
+        })
+    }
+
+    /// Request checks shared by create and validate (tags first, so errors name the submitted
+    /// index); returns the normalized allowed and blocked referrer domains
+    fn check_create_request(
+        &self,
+        request: &mut CreateLinkRequest,
+    ) -> Result<(Vec<String>, Vec<String>), ServiceError> {
+        validate_tags(&request.tags)?;
+        let allowed_referrers =
+            normalize_referrer_domains("allowed_referrers", &request.allowed_referrers)?;
+        let blocked_referrers =
+            normalize_referrer_domains("blocked_referrers", &request.blocked_referrers)?;
+        request.sanitize();
+        request.validate_custom(self.max_url_length)?;
+        request.validate()?;
+        Ok((allowed_referrers, blocked_referrers))
+    }
+
+    /// Normalize a destination URL, then scan the normalized URL; `local_only` skips the
+    /// external lookups
+    async fn scan_destination(
+        &self,
+        user: &User,
+        url: &str,
+        local_only: bool,
+    ) -> Result<(String, SecurityScanResult), ServiceError> {
+        // Use the async version for proper validation
+        let normalized_url =
+            crate::utils::normalize_url_async(url, &user.query_params_to_strip()).await?;
+
+        let security_result = if local_only {
+            self.security_service
+                .quick_security_scan(&normalized_url)
+                .await
+        } else {
+            self.security_service
+                .comprehensive_security_scan(&normalized_url)
+                .await
+        }
+        .map_err(|e| ServiceError::SecurityBlocked(format!("Security scan failed: {}", e)))?;
+
+        Ok((normalized_url, security_result))
+    }
+
+    /// Availability of a custom alias as `validate_custom_alias` decides it, with suggestions
+    /// when it is taken
+    async fn alias_verdict(
+        &self,
+        alias: &str,
+        user_id: Uuid,
+    ) -> Result<CheckAliasResponse, ServiceError> {
+        let verdict = CheckAliasResponse {
+            alias: Some(alias.to_string()),
+            ..Default::default()
+        };
+        match self.validate_custom_alias(alias, user_id).await {
+            Ok(()) => Ok(CheckAliasResponse {
+                available: true,
+                message: Some("This alias is available!".to_string()),
+                ..verdict
+            }),
+            Err(ServiceError::AliasAlreadyExists) => Ok(CheckAliasResponse {
+                message: Some(format!("'{}' is already taken", alias)),
+                suggestions: Some(self.short_code_generator.generate_suggestions(alias).await),
+                suggestion_message: Some("Try one of these available alternatives:".to_string()),
+                ..verdict
+            }),
+            Err(ServiceError::ValidationError(reason)) => Ok(CheckAliasResponse {
+                message: Some(reason),
+                ..verdict
+            }),
+            Err(e) => Err(e),
+        }
+    }
+
     /// Validate that a custom alias is available and valid
-    async fn validate_custom_alias(&self, alias: &str) -> Result<(), ServiceError> {
+    /// An alias reserved by another user counts as taken until the reservation lapses.
+    async fn validate_custom_alias(&self, alias: &str, user_id: Uuid) -> Result<(), ServiceError> {
         use crate::schema::links::dsl;
 
         let mut conn = self
@@ -401,9 +818,14 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        // Check if alias is available
+        // Check if alias is available; aliases differing only in case are the same alias,
+        // and an alias may not be a generated code in another case either
         let exists = dsl::links
-            .filter(dsl::short_code.eq(alias))
+            .filter(
+                lower(dsl::short_code.nullable())
+                    .eq(alias.to_lowercase())
+                    .or(lower(dsl::custom_alias).eq(alias.to_lowercase())),
+            )
             .filter(dsl::deleted_at.is_null())
             .select(dsl::id)
             .first::<Uuid>(&mut conn)
@@ -411,7 +833,20 @@ impl LinkService {
             .optional()?
             .is_some();
 
-        if exists {
+        // Landing pages share the short code namespace
+        let page_exists = crate::schema::pages::table
+            .filter(
+                crate::schema::pages::short_code
+                    .eq(alias)
+                    .or(crate::schema::pages::short_code.eq(alias.to_lowercase())),
+            )
+            .select(crate::schema::pages::id)
+            .first::<Uuid>(&mut conn)
+            .await
+            .optional()?
+            .is_some();
+
+        if exists || page_exists || self.alias_reserved_by_other(alias, user_id).await {
             return Err(ServiceError::AliasAlreadyExists);
         }
 
@@ -424,7 +859,62 @@ impl LinkService {
         Ok(())
     }
 
-    /// Get a link by ID and verify ownership
+    /// Hold an available alias for `user_id` for [`ALIAS_RESERVATION_TTL_SECONDS`]
+    /// Returns false when another user holds it. Reserving again refreshes the caller's own hold.
+    pub async fn reserve_alias(&self, alias: &str, user_id: Uuid) -> Result<bool, ServiceError> {
+        let key = alias_reservation_key(alias);
+        let holder = user_id.to_string();
+
+        let reserved = self
+            .redis_pool
+            .set_nx_with_expiry(&key, holder.clone(), ALIAS_RESERVATION_TTL_SECONDS)
+            .await?;
+        if reserved {
+            return Ok(true);
+        }
+
+        match self.redis_pool.get::<String>(&key).await {
+            Ok(Some(current)) if current == holder => {
+                self.redis_pool
+                    .set_with_expiry(&key, holder, ALIAS_RESERVATION_TTL_SECONDS)
+                    .await?;
+                Ok(true)
+            },
+            Ok(Some(_)) => Ok(false),
+            // Lapsed between the two calls
+            Ok(None) => Ok(self
+                .redis_pool
+                .set_nx_with_expiry(&key, holder, ALIAS_RESERVATION_TTL_SECONDS)
+                .await?),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Whether someone other than `user_id` holds a reservation on the alias
+    /// Fails open on Redis errors; the unique index on insert still settles any race.
+    pub async fn alias_reserved_by_other(&self, alias: &str, user_id: Uuid) -> bool {
+        match self
+            .redis_pool
+            .get::<String>(&alias_reservation_key(alias))
+            .await
+        {
+            Ok(Some(holder)) => holder != user_id.to_string(),
+            Ok(None) => false,
+            Err(e) => {
+                warn!("Redis error reading alias reservation for {}: {}", alias, e);
+                false
+            },
+        }
+    }
+
+    /// Drop an alias reservation once the link using it exists
+    async fn release_alias(&self, alias: &str) {
+        if let Err(e) = self.redis_pool.del(&alias_reservation_key(alias)).await {
+            warn!("Failed to release alias reservation for {}: {}", alias, e);
+        }
+    }
+
+    /// Get a link by ID and verify the user owns it or belongs to its organization
     #[instrument(skip(self))]
     pub async fn get_link_by_id_and_user(
         &self,
@@ -439,9 +929,8 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        let link = dsl::links
+        let link = Self::accessible_links(user_id)
             .filter(dsl::id.eq(link_id))
-            .filter(dsl::user_id.eq(user_id))
             .filter(dsl::deleted_at.is_null())
             .first::<Link>(&mut conn)
             .await?;
@@ -458,41 +947,37 @@ impl LinkService {
         Ok(link)
     }
 
-    /// Get a link by ID with ClickHouse stats
-    #[instrument(skip(self))]
-    pub async fn get_link_with_stats(
+    /// Page of a link's change history, for its owner or organization members
+    #[instrument(skip(self, query))]
+    pub async fn get_link_history(
         &self,
         link_id: Uuid,
         user_id: Uuid,
-    ) -> Result<LinkResponse, ServiceError> {
-        // Get the link from PostgreSQL
-        let link = self.get_link_by_id_and_user(link_id, user_id).await?;
-
-        // Get stats from ClickHouse for this single link
-        let link_ids = vec![link_id];
-        let stats_map = self.get_clickhouse_stats(&link_ids).await;
-
-        // Get the stats for this link, or use default values
-        let stats = stats_map.get(&link_id).cloned().unwrap_or_default();
+        query: &LinkHistoryQuery,
+    ) -> Result<LinkHistoryResponse, ServiceError> {
+        self.get_link_for(link_id, user_id, OrgAction::ReadLink)
+            .await?;
 
-        // Get base URL from config
-        let base_url = format!("https://{}", crate::app_config::CONFIG.jwt_audience.clone());
+        let (revisions, total) = LinkRevisionService::new(self.diesel_pool.clone())
+            .list(link_id, query)
+            .await?;
 
-        // Convert to response with stats
-        Ok(link.to_response_with_stats(&base_url, stats))
+        Ok(LinkHistoryResponse {
+            revisions,
+            total,
+            page: query.page(),
+            per_page: query.per_page(),
+        })
     }
 
-    /// Get a link by short code (for internal use)
-    #[instrument(skip(self))]
-    pub async fn get_link(&self, short_code: &str) -> Result<Link, ServiceError> {
-        // Try cache first
-        if let Ok(Some(link)) = self.get_cached_link(short_code).await {
-            AtomicU64::fetch_add(&self.cache_hits, 1, Ordering::Relaxed);
-            return Ok(link);
-        }
-
-        // Cache miss - fall back to database
-        AtomicU64::fetch_add(&self.cache_misses, 1, Ordering::Relaxed);
+    /// Get a link and check the user may perform `action` on it. Personal links
+    /// are open to their owner; organization links go through the role guard.
+    async fn get_link_for(
+        &self,
+        link_id: Uuid,
+        user_id: Uuid,
+        action: OrgAction,
+    ) -> Result<Link, ServiceError> {
         use crate::schema::links::dsl;
 
         let mut conn = self
@@ -501,42 +986,138 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        let link = dsl::links
-            .filter(dsl::short_code.eq(short_code))
-            .or_filter(dsl::custom_alias.eq(short_code))
+        let link = Self::accessible_links(user_id)
+            .filter(dsl::id.eq(link_id))
             .filter(dsl::deleted_at.is_null())
-            .filter(dsl::is_active.eq(true))
             .first::<Link>(&mut conn)
             .await?;
 
-        // Cache for next time
-        let _ = self.cache_link(&link).await;
+        if let Some(organization_id) = link.organization_id {
+            let role = OrganizationMember::find_role(&mut conn, organization_id, user_id).await?;
+            authorize(role, action)?;
+        }
 
         Ok(link)
     }
 
-    /// Get a link by short code (specification method name)
+    /// Personal links (no organization) owned by the user, plus links of every
+    /// organization the user belongs to
+    pub fn accessible_links(
+        user_id: Uuid,
+    ) -> crate::schema::links::BoxedQuery<'static, diesel::pg::Pg> {
+        use crate::schema::{links::dsl, organization_members};
+
+        let organizations = organization_members::table
+            .filter(organization_members::user_id.eq(user_id))
+            .select(organization_members::organization_id.nullable());
+
+        dsl::links
+            .filter(
+                dsl::user_id
+                    .eq(user_id)
+                    .and(dsl::organization_id.is_null())
+                    .or(dsl::organization_id.eq_any(organizations)),
+            )
+            .into_boxed()
+    }
+
+    /// A user's role in an organization, checked against `action`
+    async fn authorize_org(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+        action: OrgAction,
+    ) -> Result<OrgRole, ServiceError> {
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let role = OrganizationMember::find_role(&mut conn, organization_id, user_id).await?;
+        authorize(role, action)
+    }
+
+    /// Check the user may read a non-deleted link's stats, using the `owner:{link_id}` cache.
+    /// A miss (or a Redis error) loads the owner from the database and caches it for
+    /// `OWNER_CACHE_TTL_SECONDS`. Personal links need no query on a hit; organization
+    /// membership is always checked against the database, so a removed member loses access
+    /// at once. Same result as `accessible_links`: `NotFound` for anyone else.
     #[instrument(skip(self))]
-    pub async fn get_link_by_code(&self, short_code: &str) -> Result<Option<Link>, ServiceError> {
-        match self.get_link(short_code).await {
-            Ok(link) => Ok(Some(link)),
-            Err(ServiceError::NotFound) => Ok(None),
-            Err(e) => Err(e),
+    pub async fn assert_ownership_cached(
+        &self,
+        link_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<LinkOwner, ServiceError> {
+        let cached = cached_link_owner(&self.redis_pool, link_id)
+            .await
+            .unwrap_or_else(|e| {
+                warn!("Redis error reading owner of link {}: {}", link_id, e);
+                None
+            });
+
+        let owner = match cached {
+            Some(owner) => owner,
+            None => {
+                let owner = self.find_link_owner(link_id).await?;
+                if let Err(e) = cache_link_owner(&self.redis_pool, link_id, &owner).await {
+                    warn!("Failed to cache owner of link {}: {}", link_id, e);
+                }
+                owner
+            },
+        };
+
+        match owner.organization_id {
+            Some(organization_id) => {
+                self.authorize_org(organization_id, user_id, OrgAction::ReadLink)
+                    .await?;
+            },
+            None if owner.user_id != user_id => return Err(ServiceError::NotFound),
+            None => {},
         }
+
+        Ok(owner)
     }
 
-    /// Update an existing link
-    #[instrument(skip(self, user, request))]
-    pub async fn update_link(
+    async fn find_link_owner(&self, link_id: Uuid) -> Result<LinkOwner, ServiceError> {
+        use crate::schema::links::dsl;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let (user_id, organization_id, short_code) = dsl::links
+            .find(link_id)
+            .filter(dsl::deleted_at.is_null())
+            .select((dsl::user_id, dsl::organization_id, dsl::short_code))
+            .first::<(Uuid, Option<Uuid>, String)>(&mut conn)
+            .await?;
+
+        Ok(LinkOwner {
+            user_id,
+            organization_id,
+            short_code,
+        })
+    }
+
+    /// The link behind an `assert_ownership_cached` result: the cached link for its short
+    /// code when that is still the same link, otherwise the database row
+    pub async fn get_owned_link(
         &self,
-        user: &User,
         link_id: Uuid,
-        request: UpdateLinkRequest,
-    ) -> Result<LinkResponse, ServiceError> {
+        owner: &LinkOwner,
+    ) -> Result<Link, ServiceError> {
         use crate::schema::links::dsl;
 
-        // Check ownership using the helper method
-        let existing_link = self.get_link_by_id_and_user(link_id, user.id).await?;
+        if let Ok(Some(link)) = self.get_cached_link(&owner.short_code).await {
+            if link.id == link_id {
+                AtomicU64::fetch_add(&self.cache_hits, 1, Ordering::Relaxed);
+                return Ok(link);
+            }
+        }
+        AtomicU64::fetch_add(&self.cache_misses, 1, Ordering::Relaxed);
 
         let mut conn = self
             .diesel_pool
@@ -544,13 +1125,167 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
+        let link = dsl::links
+            .find(link_id)
+            .filter(dsl::deleted_at.is_null())
+            .first::<Link>(&mut conn)
+            .await?;
+
+        Ok(link)
+    }
+
+    /// Get a link by ID with ClickHouse stats
+    #[instrument(skip(self))]
+    pub async fn get_link_with_stats(
+        &self,
+        link_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<LinkResponse, ServiceError> {
+        // Get the link from PostgreSQL
+        let link = self.get_link_by_id_and_user(link_id, user_id).await?;
+
+        // Get stats from ClickHouse for this single link
+        let link_ids = vec![link_id];
+        let stats_map = self.get_clickhouse_stats(&link_ids).await;
+
+        // Get the stats for this link, or use default values
+        let stats = stats_map.get(&link_id).cloned().unwrap_or_default();
+
+        // Convert to response with stats
+        Ok(link.to_response_with_stats(&self.base_url, stats))
+    }
+
+    /// Get a link by short code (for internal use)
+    #[instrument(skip(self))]
+    pub async fn get_link(&self, short_code: &str) -> Result<Link, ServiceError> {
+        // Try cache first
+        if let Ok(Some(link)) = self.get_cached_link(short_code).await {
+            AtomicU64::fetch_add(&self.cache_hits, 1, Ordering::Relaxed);
+            return Ok(link);
+        }
+
+        // Cache miss - fall back to database
+        AtomicU64::fetch_add(&self.cache_misses, 1, Ordering::Relaxed);
+        let link = self.find_active_link(short_code).await?;
+
+        // Cache for next time
+        let _ = self.cache_link(&link).await;
+
+        Ok(link)
+    }
+
+    /// Look up an active, non-deleted link by short code or custom alias in the database
+    /// Generated short codes are case-sensitive; aliases match in any case, and an exact
+    /// short code wins over an alias that only matches case-insensitively
+    async fn find_active_link(&self, short_code: &str) -> Result<Link, ServiceError> {
+        use crate::schema::links::dsl;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let link = dsl::links
+            .filter(
+                dsl::short_code
+                    .eq(short_code)
+                    .or(lower(dsl::custom_alias).eq(short_code.to_lowercase())),
+            )
+            .filter(dsl::deleted_at.is_null())
+            // Swept links still resolve so visitors get the expired page rather than a 404
+            .filter(
+                dsl::is_active
+                    .eq(true)
+                    .or(dsl::deactivation_reason.eq(DEACTIVATION_REASON_EXPIRED)),
+            )
+            .order(dsl::short_code.eq(short_code).desc())
+            .first::<Link>(&mut conn)
+            .await?;
+
+        Ok(link)
+    }
+
+    /// Get a link by short code (specification method name)
+    #[instrument(skip(self))]
+    pub async fn get_link_by_code(&self, short_code: &str) -> Result<Option<Link>, ServiceError> {
+        match self.get_link(short_code).await {
+            Ok(link) => Ok(Some(link)),
+            Err(ServiceError::NotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Link id, owner and flags for a short code or alias, without decoding the cached link.
+    /// Reads the `code:` hash; on a miss (or a Redis error) the link is loaded from the
+    /// database and all of its cache entries are written again.
+    #[instrument(skip(self))]
+    pub async fn resolve_code_meta(&self, short_code: &str) -> Result<CodeMeta, ServiceError> {
+        match cached_code_meta(&self.redis_pool, short_code).await {
+            Ok(Some(meta)) => {
+                AtomicU64::fetch_add(&self.cache_hits, 1, Ordering::Relaxed);
+                return Ok(meta);
+            },
+            Ok(None) => {},
+            Err(e) => warn!("Redis error getting code meta for {}: {}", short_code, e),
+        }
+
+        AtomicU64::fetch_add(&self.cache_misses, 1, Ordering::Relaxed);
+        let link = self.find_active_link(short_code).await?;
+        let _ = self.cache_link(&link).await;
+
+        Ok(CodeMeta::from(&link))
+    }
+
+    /// Run a fresh security scan against a link's destination
+    pub async fn scan_link(&self, link: &Link) -> Result<SecurityScanResult, ServiceError> {
+        self.security_service
+            .comprehensive_security_scan(&link.original_url)
+            .await
+            .map_err(|e| ServiceError::SecurityBlocked(format!("Security scan failed: {}", e)))
+    }
+
+    /// Update an existing link
+    #[instrument(skip(self, user, request))]
+    pub async fn update_link(
+        &self,
+        user: &User,
+        link_id: Uuid,
+        request: UpdateLinkRequest,
+    ) -> Result<LinkResponse, ServiceError> {
+        use crate::schema::{link_revisions, links::dsl};
+
+        // Personal owner, or any organization member
+        let existing_link = self
+            .get_link_for(link_id, user.id, OrgAction::UpdateLink)
+            .await?;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        // Store destination URLs in the same canonical (punycode) form as on create
+        let original_url = match request.url.as_deref() {
+            Some(url) => {
+                check_url_length(url, self.max_url_length)?;
+                Some(crate::utils::normalize_url_async(url, &user.query_params_to_strip()).await?)
+            },
+            None => None,
+        };
+
         // Build update struct with proper field handling
         let password_hash = if let Some(is_protected) = request.is_password_protected {
             if is_protected {
-                request.password.as_ref().map(|p| {
-                    // TODO: Use proper bcrypt hashing in production
-                    Some(format!("hashed_{}", p))
-                })
+                match request.password.as_ref() {
+                    // Same bcrypt hashing as on create, so the unlock form can verify it
+                    Some(p) => Some(Some(bcrypt::hash(p, bcrypt::DEFAULT_COST).map_err(|e| {
+                        error!("Failed to hash password: {}", e);
+                        ServiceError::DatabaseError(format!("Password hashing failed: {}", e))
+                    })?)),
+                    None => None,
+                }
             } else {
                 Some(None) // Clear password
             }
@@ -558,40 +1293,142 @@ impl LinkService {
             None // Don't change password status
         };
 
-        // Convert tags properly for database
-        let tags = request.tags.as_ref().map(|tag_list| {
-            Some(
-                tag_list
-                    .iter()
-                    .map(|t| Some(t.clone()))
-                    .collect::<Vec<Option<String>>>(),
-            )
-        });
+        // Same tag rules as on create; stored normalized
+        let tags = match request.tags.as_deref() {
+            Some(tag_list) => {
+                validate_tags(tag_list)?;
+                Some(Some(
+                    normalize_tags(tag_list)
+                        .into_iter()
+                        .map(Some)
+                        .collect::<Vec<Option<String>>>(),
+                ))
+            },
+            None => None,
+        };
+        let allowed_referrers = request
+            .allowed_referrers
+            .as_deref()
+            .map(|domains| normalize_referrer_domains("allowed_referrers", domains))
+            .transpose()?
+            .map(referrer_column);
+        let blocked_referrers = request
+            .blocked_referrers
+            .as_deref()
+            .map(|domains| normalize_referrer_domains("blocked_referrers", domains))
+            .transpose()?
+            .map(referrer_column);
+
+        // The window applies to the policy the link ends up with; a new policy or window for
+        // a sliding link restarts it from now, and switching to fixed drops the window
+        let now = Utc::now();
+        let expiry_policy = request
+            .expiry_policy
+            .unwrap_or_else(|| existing_link.expiry_policy());
+        let sliding_window_days = match expiry_policy {
+            ExpiryPolicy::Fixed if request.expiry_policy.is_some() => None,
+            _ => request
+                .sliding_window_days
+                .or(existing_link.sliding_window_days),
+        };
+        let reconfigured = request.expiry_policy.is_some() || request.sliding_window_days.is_some();
+        if reconfigured {
+            expiry_policy.check(
+                request.sliding_window_days.or(sliding_window_days),
+                request.expires_at.is_some(),
+            )?;
+        }
+        let expires_at = match (expiry_policy, sliding_window_days) {
+            (ExpiryPolicy::Sliding, Some(days)) if reconfigured => {
+                Some(Some(sliding_expiry(now, days)))
+            },
+            (ExpiryPolicy::Sliding, _) if request.expires_at.is_some() => {
+                return Err(LinkRequestError::ExpiryWithSliding.into());
+            },
+            _ => request.expires_at,
+        };
+
+        // Moving or clearing the expiry of a link the sweep switched off brings it back;
+        // an explicit is_active from the owner always wins and clears the reason
+        let expiry_extended = matches!(
+            expires_at,
+            Some(expiry) if expiry.is_none_or(|at| at > now)
+        );
+        let (is_active, deactivation_reason) = match request.is_active {
+            Some(active) => (Some(active), Some(None)),
+            None if expiry_extended && existing_link.deactivated_by_expiry() => {
+                (Some(true), Some(None))
+            },
+            None => (None, None),
+        };
 
         let update = UpdateLink {
-            original_url: request.url,
+            original_url,
             title: request.title.map(Some),
             description: request.description.map(Some),
             og_image: request.og_image.map(Some),
             favicon_url: request.favicon_url.map(Some),
             tags,
-            expires_at: request.expires_at,
-            is_active: request.is_active,
+            expires_at,
+            is_active,
             password_hash,
             utm_source: None,
             utm_medium: None,
             utm_campaign: None,
             utm_term: None,
             utm_content: None,
-            updated_at: Utc::now(),
+            updated_at: now,
             processing_status: None, // Don't change processing status on regular updates
             metadata_extracted_at: None, // Don't change metadata timestamp on regular updates
+            tracking_mode: request.tracking_mode.map(|mode| mode.as_str().to_string()),
+            path_passthrough: request.path_passthrough,
+            allowed_referrers,
+            blocked_referrers,
+            referrer_strict: request.referrer_strict,
+            expiry_policy: request
+                .expiry_policy
+                .map(|policy| policy.as_str().to_string()),
+            sliding_window_days: reconfigured.then_some(sliding_window_days),
+            // Blank notes clear them
+            notes: request.notes.map(|notes| {
+                let notes = notes.trim();
+                (!notes.is_empty()).then(|| notes.to_string())
+            }),
+            deactivation_reason,
         };
 
-        // Apply update
-        let updated_link = diesel::update(dsl::links.find(link_id))
-            .set(&update)
-            .get_result::<Link>(&mut conn)
+        // Apply the update and record its revision together
+        let actor_id = user.id;
+        let updated_link = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                Box::pin(async move {
+                    // Locked, so the recorded "before" values are the ones this update replaces
+                    let previous = dsl::links
+                        .find(link_id)
+                        .for_update()
+                        .first::<Link>(tx)
+                        .await?;
+
+                    let updated = diesel::update(dsl::links.find(link_id))
+                        .set(&update)
+                        .get_result::<Link>(tx)
+                        .await?;
+
+                    if let Some(revision) = NewLinkRevision::between(
+                        &previous,
+                        &updated,
+                        Some(actor_id),
+                        RevisionSource::Update,
+                    ) {
+                        diesel::insert_into(link_revisions::table)
+                            .values(&revision)
+                            .execute(tx)
+                            .await?;
+                    }
+
+                    Ok(updated)
+                })
+            })
             .await?;
 
         // Invalidate cache
@@ -628,11 +1465,13 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        // Verify ownership and soft delete
+        // Personal owner, or organization owner/admin
+        self.get_link_for(link_id, user.id, OrgAction::DeleteLink)
+            .await?;
+
         let rows_affected = diesel::update(
             dsl::links
                 .filter(dsl::id.eq(link_id))
-                .filter(dsl::user_id.eq(user.id))
                 .filter(dsl::deleted_at.is_null()),
         )
         .set((
@@ -654,6 +1493,8 @@ impl LinkService {
         if let Some(ref alias) = link.custom_alias {
             self.invalidate_cache(alias).await?;
         }
+        self.invalidate_owner(link_id).await?;
+        self.invalidate_link_count(link.user_id).await;
 
         // Audit log the deletion
         AuditLogger::log_link_action(
@@ -671,56 +1512,191 @@ impl LinkService {
         Ok(())
     }
 
-    /// Permanently delete a link (admin-only operation)
-    #[instrument(skip(self))]
-    pub async fn permanent_delete_link(
+    /// Move a link between personal ownership and an organization, or between
+    /// organizations. The caller must be able to transfer the link where it is
+    /// (owner, or org admin) and administer the destination organization.
+    #[instrument(skip(self, user))]
+    pub async fn transfer_link(
         &self,
+        user: &User,
         link_id: Uuid,
-        user_id: Uuid,
-    ) -> Result<(), ServiceError> {
+        destination: Option<Uuid>,
+    ) -> Result<LinkResponse, ServiceError> {
         use crate::schema::links::dsl;
 
+        let link = self
+            .get_link_for(link_id, user.id, OrgAction::TransferLink)
+            .await?;
+
+        if link.organization_id == destination {
+            return Err(ServiceError::ValidationError(
+                "Link already belongs to the destination".to_string(),
+            ));
+        }
+        if let Some(organization_id) = destination {
+            self.authorize_org(organization_id, user.id, OrgAction::TransferLink)
+                .await?;
+        }
+
+        // Short URLs share one namespace, so the alias must still be free of
+        // every other link wherever this one ends up
+        if let Some(ref alias) = link.custom_alias {
+            self.validate_alias_for_transfer(alias, link.id).await?;
+        }
+
         let mut conn = self
             .diesel_pool
             .get()
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        // Get the link first to invalidate cache
-        let link = dsl::links.find(link_id).first::<Link>(&mut conn).await?;
-
-        // Permanently delete from database
-        let rows_affected = diesel::delete(dsl::links.find(link_id))
-            .execute(&mut conn)
-            .await?;
+        // Links moved to personal ownership belong to the caller
+        let owner_id = if destination.is_none() {
+            user.id
+        } else {
+            link.user_id
+        };
 
-        if rows_affected == 0 {
-            return Err(ServiceError::NotFound);
-        }
+        let transferred = diesel::update(
+            dsl::links
+                .filter(dsl::id.eq(link_id))
+                .filter(dsl::deleted_at.is_null()),
+        )
+        .set((
+            dsl::organization_id.eq(destination),
+            dsl::user_id.eq(owner_id),
+            dsl::updated_at.eq(Utc::now()),
+        ))
+        .get_result::<Link>(&mut conn)
+        .await?;
 
-        // Invalidate cache
         self.invalidate_cache(&link.short_code).await?;
         if let Some(ref alias) = link.custom_alias {
             self.invalidate_cache(alias).await?;
         }
+        self.invalidate_owner(link_id).await?;
+        self.invalidate_link_count(link.user_id).await;
+        if owner_id != link.user_id {
+            self.invalidate_link_count(owner_id).await;
+        }
 
-        // Audit log the permanent deletion
+        let scope = |organization_id: Option<Uuid>| match organization_id {
+            Some(id) => format!("organization {}", id),
+            None => "personal".to_string(),
+        };
         AuditLogger::log_link_action(
-            AuditAction::LinkPermanentlyDeleted,
-            user_id,
+            AuditAction::LinkTransferred,
+            user.id,
             Some(link_id.to_string()),
             Some(format!(
-                "Permanently deleted link with short code: {}",
-                link.short_code
+                "Transferred from {} to {}",
+                scope(link.organization_id),
+                scope(destination)
             )),
         )
         .await;
 
-        warn!("Link {} permanently deleted by user {}", link_id, user_id);
+        let stats_map = self.get_clickhouse_stats(&[link_id]).await;
+        let stats = stats_map.get(&link_id).cloned().unwrap_or_default();
+
+        Ok(transferred.to_response_with_stats(&self.base_url, stats))
+    }
+
+    /// Alias checks for a link changing owner: still valid and not taken by another link
+    async fn validate_alias_for_transfer(
+        &self,
+        alias: &str,
+        link_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        use crate::schema::links::dsl;
+        use crate::utils::custom_alias_validator::CustomAliasValidator;
+
+        CustomAliasValidator::validate(alias).map_err(ServiceError::ValidationError)?;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let taken = diesel::select(diesel::dsl::exists(
+            dsl::links
+                .filter(dsl::id.ne(link_id))
+                .filter(dsl::deleted_at.is_null())
+                .filter(
+                    dsl::short_code
+                        .eq(alias)
+                        .or(lower(dsl::custom_alias).eq(alias.to_lowercase())),
+                ),
+        ))
+        .get_result::<bool>(&mut conn)
+        .await?;
+
+        if taken {
+            return Err(ServiceError::AliasAlreadyExists);
+        }
+
         Ok(())
     }
 
-    /// Bulk delete (deactivate) multiple links
+    /// Permanently delete a link, its click data and cache entries
+    /// Same permissions as `delete_link`. A snapshot of the link is written to the audit log
+    /// first, so nothing is erased unless the record of it was stored.
+    #[instrument(skip(self, user))]
+    pub async fn permanent_delete_link(
+        &self,
+        user: &User,
+        link_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        use crate::schema::links::dsl;
+
+        // Personal owner, or organization owner/admin
+        let link = self
+            .get_link_for(link_id, user.id, OrgAction::DeleteLink)
+            .await?;
+
+        AuditLogger::log_link_action_now(
+            &self.diesel_pool,
+            AuditAction::LinkPermanentlyDeleted,
+            user.id,
+            link_id,
+            permanent_delete_snapshot(&link),
+        )
+        .await?;
+
+        if let Some(ref analytics) = self.clickhouse_analytics {
+            analytics.delete_link_events(&link_id).await.map_err(|e| {
+                error!("{}", e);
+                ServiceError::InternalError
+            })?;
+        }
+
+        self.invalidate_cache(&link.short_code).await?;
+        if let Some(ref alias) = link.custom_alias {
+            self.invalidate_cache(alias).await?;
+        }
+        self.invalidate_owner(link_id).await?;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let rows_affected = diesel::delete(dsl::links.find(link_id))
+            .execute(&mut conn)
+            .await?;
+
+        if rows_affected == 0 {
+            return Err(ServiceError::NotFound);
+        }
+        self.invalidate_link_count(link.user_id).await;
+
+        warn!("Link {} permanently deleted by user {}", link_id, user.id);
+        Ok(())
+    }
+
+    /// Bulk delete (deactivate) multiple personal links
     #[instrument(skip(self, user))]
     pub async fn bulk_delete_links(
         &self,
@@ -751,6 +1727,7 @@ impl LinkService {
             dsl::links
                 .filter(dsl::id.eq_any(&link_ids))
                 .filter(dsl::user_id.eq(user.id))
+                .filter(dsl::organization_id.is_null())
                 .filter(dsl::deleted_at.is_null()),
         )
         .set((
@@ -766,20 +1743,15 @@ impl LinkService {
 
         // Invalidate cache for all deleted links using pipeline for efficiency
         let deleted_ids: Vec<String> = links_to_delete.iter().map(|l| l.id.to_string()).collect();
-        let cache_keys_to_delete: Vec<String> = links_to_delete
-            .iter()
-            .flat_map(|link| {
-                let mut keys = vec![format!("link:{}", link.short_code)];
-                if let Some(ref alias) = link.custom_alias {
-                    keys.push(format!("link:{}", alias));
-                }
-                keys
-            })
-            .collect();
+        let cache_keys_to_delete: Vec<String> =
+            links_to_delete.iter().flat_map(link_cache_keys).collect();
 
         if !cache_keys_to_delete.is_empty() {
             let _ = self.invalidate_cache_batch(cache_keys_to_delete).await;
         }
+        if rows_affected > 0 {
+            self.invalidate_link_count(user.id).await;
+        }
 
         // Audit log the bulk deletion
         if rows_affected > 0 {
@@ -796,19 +1768,21 @@ impl LinkService {
         Ok(rows_affected as u64)
     }
 
-    /// Bulk update status (active/inactive) for multiple links
+    /// Bulk update status (active/inactive) for multiple personal links, with one outcome
+    /// per distinct ID in request order. IDs the caller cannot see are `NotFound`, whether
+    /// or not they exist; organization links the caller can see are `Forbidden`.
     #[instrument(skip(self, user))]
     pub async fn bulk_update_status(
         &self,
         user: &User,
         link_ids: Vec<Uuid>,
         is_active: bool,
-    ) -> Result<u64, ServiceError> {
-        use crate::schema::links::dsl;
+    ) -> Result<Vec<BulkStatusItem>, ServiceError> {
+        use crate::schema::{link_revisions, links::dsl};
 
         // Validate bulk operation size
         if link_ids.is_empty() {
-            return Ok(0);
+            return Ok(Vec::new());
         }
 
         if link_ids.len() > 100 {
@@ -817,32 +1791,114 @@ impl LinkService {
             ));
         }
 
+        let mut seen = HashSet::new();
+        let link_ids: Vec<Uuid> = link_ids.into_iter().filter(|id| seen.insert(*id)).collect();
+
         let mut conn = self
             .diesel_pool
             .get()
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        // Get links that will be updated (for cache invalidation)
-        // Only update non-deleted links
-        let links_to_update = dsl::links
-            .filter(dsl::id.eq_any(&link_ids))
-            .filter(dsl::user_id.eq(user.id))
-            .filter(dsl::deleted_at.is_null())
-            .load::<Link>(&mut conn)
+        // Update non-deleted links and record a revision for each one whose status changed
+        let user_id = user.id;
+        let requested_ids = link_ids.clone();
+        let (links_to_update, unchanged_ids, forbidden_ids) = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                Box::pin(async move {
+                    // Locked, so the recorded "before" values are the ones this update replaces
+                    let previous = dsl::links
+                        .filter(dsl::id.eq_any(&requested_ids))
+                        .filter(dsl::user_id.eq(user_id))
+                        .filter(dsl::organization_id.is_null())
+                        .filter(dsl::deleted_at.is_null())
+                        .for_update()
+                        .load::<Link>(tx)
+                        .await?;
+
+                    let personal: HashSet<Uuid> = previous.iter().map(|link| link.id).collect();
+                    let (unchanged, previous): (Vec<Link>, Vec<Link>) = previous
+                        .into_iter()
+                        .partition(|link| link.is_active == is_active);
+                    let unchanged_ids: HashSet<Uuid> =
+                        unchanged.iter().map(|link| link.id).collect();
+
+                    // Whatever else the caller can see belongs to an organization
+                    let others: Vec<Uuid> = requested_ids
+                        .iter()
+                        .filter(|id| !personal.contains(id))
+                        .copied()
+                        .collect();
+                    let forbidden_ids: HashSet<Uuid> = if others.is_empty() {
+                        HashSet::new()
+                    } else {
+                        Self::accessible_links(user_id)
+                            .filter(dsl::id.eq_any(others))
+                            .filter(dsl::deleted_at.is_null())
+                            .select(dsl::id)
+                            .load::<Uuid>(tx)
+                            .await?
+                            .into_iter()
+                            .collect()
+                    };
+
+                    let locked_ids: Vec<Uuid> = previous.iter().map(|link| link.id).collect();
+                    let updated: Vec<Link> = if locked_ids.is_empty() {
+                        Vec::new()
+                    } else {
+                        diesel::update(dsl::links.filter(dsl::id.eq_any(&locked_ids)))
+                            .set((
+                                dsl::is_active.eq(is_active),
+                                // The owner decided; the sweep's reason no longer applies
+                                dsl::deactivation_reason.eq(None::<String>),
+                                dsl::updated_at.eq(Utc::now()),
+                            ))
+                            .returning(dsl::links::all_columns())
+                            .get_results(tx)
+                            .await?
+                    };
+
+                    let revisions: Vec<NewLinkRevision> = previous
+                        .iter()
+                        .filter_map(|before| {
+                            let after = updated.iter().find(|link| link.id == before.id)?;
+                            NewLinkRevision::between(
+                                before,
+                                after,
+                                Some(user_id),
+                                RevisionSource::BulkStatus,
+                            )
+                        })
+                        .collect();
+                    if !revisions.is_empty() {
+                        diesel::insert_into(link_revisions::table)
+                            .values(&revisions)
+                            .execute(tx)
+                            .await?;
+                    }
+
+                    Ok((previous, unchanged_ids, forbidden_ids))
+                })
+            })
             .await?;
+        let rows_affected = links_to_update.len();
 
-        // Perform bulk status update
-        // Only update non-deleted links
-        let rows_affected = diesel::update(
-            dsl::links
-                .filter(dsl::id.eq_any(&link_ids))
-                .filter(dsl::user_id.eq(user.id))
-                .filter(dsl::deleted_at.is_null()),
-        )
-        .set((dsl::is_active.eq(is_active), dsl::updated_at.eq(Utc::now())))
-        .execute(&mut conn)
-        .await?;
+        let updated_set: HashSet<Uuid> = links_to_update.iter().map(|link| link.id).collect();
+        let results = link_ids
+            .iter()
+            .map(|&id| BulkStatusItem {
+                id,
+                outcome: if updated_set.contains(&id) {
+                    BulkStatusOutcome::Updated
+                } else if unchanged_ids.contains(&id) {
+                    BulkStatusOutcome::AlreadyInState
+                } else if forbidden_ids.contains(&id) {
+                    BulkStatusOutcome::Forbidden
+                } else {
+                    BulkStatusOutcome::NotFound
+                },
+            })
+            .collect();
 
         // Invalidate cache for all updated links
         let updated_ids: Vec<String> = links_to_update.iter().map(|l| l.id.to_string()).collect();
@@ -878,7 +1934,7 @@ impl LinkService {
             "Bulk {} {} links for user {}",
             action, rows_affected, user.id
         );
-        Ok(rows_affected as u64)
+        Ok(results)
     }
 
     /// Get user's links with filtering and pagination (specification method name)
@@ -896,71 +1952,14 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-        // Build query with filters - exclude soft deleted links
-        let mut query = dsl::links
-            .filter(dsl::user_id.eq(user.id))
-            .filter(dsl::deleted_at.is_null())
-            .into_boxed();
-
-        if let Some(ref search) = params.filter.search {
-            let pattern = format!("%{}%", search);
-            query = query.filter(
-                dsl::short_code
-                    .ilike(pattern.clone())
-                    .or(dsl::original_url.ilike(pattern.clone()))
-                    .or(dsl::custom_alias.ilike(pattern)),
-            );
-        }
-
-        if let Some(is_active) = params.filter.is_active {
-            query = query.filter(dsl::is_active.eq(is_active));
-        }
-
-        if let Some(ref domain) = params.filter.domain {
-            let pattern = format!("%{}%", domain);
-            query = query.filter(dsl::original_url.ilike(pattern));
-        }
-
-        if let Some(created_after) = params.filter.created_after {
-            query = query.filter(dsl::created_at.ge(created_after));
-        }
-
-        if let Some(created_before) = params.filter.created_before {
-            query = query.filter(dsl::created_at.le(created_before));
-        }
-
-        // Count total results (rebuild query for count)
-        // CRITICAL: Must filter by deleted_at to match main query
-        let mut count_query = dsl::links
-            .filter(dsl::user_id.eq(user.id))
-            .filter(dsl::deleted_at.is_null())
-            .into_boxed();
-
-        // Apply same filters for count
-        if let Some(ref search) = params.filter.search {
-            count_query = count_query.filter(
-                dsl::original_url
-                    .ilike(format!("%{}%", search))
-                    .or(dsl::short_code.ilike(format!("%{}%", search)))
-                    .or(dsl::custom_alias.ilike(format!("%{}%", search))),
-            );
-        }
-
-        if let Some(is_active) = params.filter.is_active {
-            count_query = count_query.filter(dsl::is_active.eq(is_active));
-        }
-
-        if let Some(created_after) = params.filter.created_after {
-            count_query = count_query.filter(dsl::created_at.ge(created_after));
-        }
-
-        if let Some(created_before) = params.filter.created_before {
-            count_query = count_query.filter(dsl::created_at.le(created_before));
-        }
-
-        let total = count_query.count().get_result::<i64>(&mut conn).await?;
+        // Count total results with the same filters as the page query
+        let total = Self::filtered_user_links(user.id, &params.filter)
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await?;
 
         // Get paginated results - order by sort_by parameter or default to created_at
+        let query = Self::filtered_user_links(user.id, &params.filter);
         let links = match params.sort_by.as_deref() {
             Some("title") => query.order(dsl::title.asc()),
             _ => query.order(dsl::created_at.desc()),
@@ -999,6 +1998,92 @@ impl LinkService {
         })
     }
 
+    /// Latest `updated_at` and row count of the user's filtered link set, for list ETags
+    pub async fn user_links_fingerprint(
+        &self,
+        user_id: Uuid,
+        filter: &LinkFilter,
+    ) -> Result<(Option<chrono::DateTime<Utc>>, i64), ServiceError> {
+        use crate::schema::links::dsl;
+
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let fingerprint = Self::filtered_user_links(user_id, filter)
+            .select((diesel::dsl::max(dsl::updated_at), diesel::dsl::count_star()))
+            .get_result::<(Option<chrono::DateTime<Utc>>, i64)>(&mut conn)
+            .await?;
+
+        Ok(fingerprint)
+    }
+
+    /// A user's non-deleted links narrowed by the list filter; with an
+    /// `organization_id` filter, that organization's links instead (members only)
+    fn filtered_user_links(
+        user_id: Uuid,
+        filter: &LinkFilter,
+    ) -> crate::schema::links::BoxedQuery<'static, diesel::pg::Pg> {
+        use crate::schema::links::dsl;
+
+        let mut query = match filter.organization_id {
+            Some(organization_id) => Self::accessible_links(user_id)
+                .filter(dsl::organization_id.eq(organization_id)),
+            None => dsl::links
+                .filter(dsl::user_id.eq(user_id))
+                .filter(dsl::organization_id.is_null())
+                .into_boxed(),
+        }
+        .filter(dsl::deleted_at.is_null());
+
+        if let Some(ref search) = filter.search {
+            let pattern = format!("%{}%", search);
+            query = query.filter(
+                dsl::short_code
+                    .ilike(pattern.clone())
+                    .or(dsl::original_url.ilike(pattern.clone()))
+                    .or(dsl::custom_alias.ilike(pattern)),
+            );
+        }
+
+        if let Some(is_active) = filter.is_active {
+            query = query.filter(dsl::is_active.eq(is_active));
+        }
+
+        match filter.expired {
+            Some(true) => query = query.filter(dsl::expires_at.lt(Utc::now())),
+            Some(false) => {
+                query = query.filter(dsl::expires_at.is_null().or(dsl::expires_at.ge(Utc::now())))
+            },
+            None => {},
+        }
+
+        if let Some(ref domain) = filter.domain {
+            let pattern = format!("%{}%", domain);
+            query = query.filter(dsl::original_url.ilike(pattern));
+        }
+
+        if let Some(created_after) = filter.created_after {
+            query = query.filter(dsl::created_at.ge(created_after));
+        }
+
+        if let Some(created_before) = filter.created_before {
+            query = query.filter(dsl::created_at.le(created_before));
+        }
+
+        query
+    }
+
+    /// Total clicks for a single link from ClickHouse (0 when analytics are unavailable)
+    pub async fn get_total_clicks(&self, link_id: Uuid) -> u64 {
+        self.get_clickhouse_stats(&[link_id])
+            .await
+            .get(&link_id)
+            .map_or(0, |stats| stats.total_clicks)
+    }
+
     /// Fetch link statistics from ClickHouse for a list of link IDs
     /// Uses the ClickHouseAnalyticsService for clean separation of concerns
     #[instrument(skip(self), fields(link_count = link_ids.len()))]
@@ -1029,90 +2114,322 @@ impl LinkService {
         }
     }
 
-    /// Process a redirect and increment click count
-    #[instrument(skip(self))]
-    pub async fn process_redirect(&self, short_code: &str) -> Result<(Uuid, String), ServiceError> {
-        // Get link
-        let link = self.get_link(short_code).await?;
+    /// Resolve a short code for the redirect path and count the click.
+    /// Reads only the compact redirect record; the full link is loaded from the database
+    /// (and both cache entries written) on a miss.
+    pub async fn resolve_redirect(
+        &self,
+        short_code: &str,
+    ) -> Result<ResolvedRedirect, ServiceError> {
+        self.resolve_redirect_with_path(short_code, None, None).await
+    }
+
+    /// Like `resolve_redirect`, with `sub_path` (the request path after the short code)
+    /// appended to the destination. Only links with path passthrough accept a sub-path;
+    /// for other links, and for sub-paths under the short code's own pages, it is
+    /// `NotFound`.
+    /// `referrer` is the visitor's `Referer`; when the link's referrer restrictions refuse
+    /// it, the result has `blocked_by_referrer` set and the click isn't counted.
+    #[instrument(skip(self))]
+    pub async fn resolve_redirect_with_path(
+        &self,
+        short_code: &str,
+        sub_path: Option<&str>,
+        referrer: Option<&str>,
+    ) -> Result<ResolvedRedirect, ServiceError> {
+        let record = match self.get_cached_redirect(short_code).await {
+            Some(record) => {
+                AtomicU64::fetch_add(&self.cache_hits, 1, Ordering::Relaxed);
+                record
+            },
+            None => {
+                AtomicU64::fetch_add(&self.cache_misses, 1, Ordering::Relaxed);
+                let link = self.find_active_link(short_code).await?;
+                let _ = self.cache_link(&link).await;
+                RedirectRecord::from(&link)
+            },
+        };
+
+        let passthrough = match sub_path {
+            None => None,
+            Some(_) if !record.path_passthrough => return Err(ServiceError::NotFound),
+            Some(sub_path) => Some(
+                passthrough_destination(&record.destination, sub_path)
+                    .ok_or(ServiceError::NotFound)?,
+            ),
+        };
+        Self::ensure_redirectable(&record)?;
+        self.ensure_owner_not_paused(record.owner_id).await?;
+
+        // Refused referrers are turned away before the password form
+        let blocked_by_referrer = !record.referrer_allowed(referrer);
+
+        // Password-protected links only redirect through the unlock form
+        if !blocked_by_referrer && record.has(RedirectRecord::PASSWORD_PROTECTED) {
+            return Err(ServiceError::PasswordRequired);
+        }
+
+        if !blocked_by_referrer {
+            self.count_redirect(short_code);
+        }
+        Ok(ResolvedRedirect {
+            link_id: record.link_id,
+            via_alias: record.via_alias(short_code),
+            destination: passthrough.unwrap_or(record.destination),
+            redirect_type: record.redirect_type,
+            tracking_mode: record.tracking_mode,
+            blocked_by_referrer,
+        })
+    }
+
+    /// Check the password of a protected link and, if it matches, redirect like
+    /// `resolve_redirect`
+    #[instrument(skip(self, password))]
+    pub async fn process_unlock(
+        &self,
+        short_code: &str,
+        password: &str,
+    ) -> Result<UnlockOutcome, ServiceError> {
+        let link = self.get_link(short_code).await?;
+        let record = RedirectRecord::from(&link);
+        Self::ensure_redirectable(&record)?;
+        self.ensure_owner_not_paused(record.owner_id).await?;
+
+        if let Some(hash) = link.password_hash.as_deref() {
+            // Hashes that aren't bcrypt never match; those links need their password set again
+            if !bcrypt::verify(password, hash).unwrap_or(false) {
+                return Ok(UnlockOutcome::WrongPassword {
+                    link_id: link.id,
+                    owner_id: link.user_id,
+                });
+            }
+        }
+
+        self.count_redirect(short_code);
+        Ok(UnlockOutcome::Unlocked {
+            link_id: link.id,
+            tracking_mode: link.tracking_mode(),
+            via_alias: record.via_alias(short_code),
+            original_url: link.original_url,
+        })
+    }
+
+    /// Quarantine, expiry and active checks shared by every redirect path
+    fn ensure_redirectable(record: &RedirectRecord) -> Result<(), ServiceError> {
+        // Quarantined links never redirect, regardless of other state
+        if record.has(RedirectRecord::QUARANTINED) {
+            return Err(ServiceError::Quarantined);
+        }
 
         // Check if expired
-        if let Some(expires_at) = link.expires_at {
-            if expires_at < Utc::now() {
-                return Err(ServiceError::Expired);
-            }
+        if record.is_expired(Utc::now()) {
+            return Err(ServiceError::Expired);
         }
 
         // Check if active
-        if !link.is_active {
+        if !record.has(RedirectRecord::ACTIVE) {
             return Err(ServiceError::Inactive);
         }
 
-        // Async increment click count in Redis (fast)
-        // This is fire-and-forget but with error logging and fallback queue
-        let short_code_clone = short_code.to_string();
-        let redis_pool = self.redis_pool.clone();
-        tokio::spawn(async move {
-            if let Err(e) = increment_click_count_redis(&redis_pool, &short_code_clone).await {
-                error!(
-                    "Failed to track click for {}: {}. Will be retried in background sync.",
-                    short_code_clone, e
-                );
-                // Error is logged and fallback queue is already handled in increment_click_count_redis
-            }
-        });
+        Ok(())
+    }
+
+    /// Links of an owner who paused all their links redirect as if switched off
+    async fn ensure_owner_not_paused(&self, owner_id: Option<Uuid>) -> Result<(), ServiceError> {
+        match owner_id {
+            Some(owner_id) if is_owner_paused(&self.redis_pool, owner_id).await => {
+                Err(ServiceError::Inactive)
+            },
+            _ => Ok(()),
+        }
+    }
 
-        Ok((link.id, link.original_url.clone()))
+    /// Count a redirect; the click counter flushes it to Redis in the background
+    fn count_redirect(&self, short_code: &str) {
+        CLICK_COUNTER.record(short_code);
     }
 
     /// Track a click event to ClickHouse for analytics
+    /// `context.opted_out` together with `tracking_mode` decides whether IP, user agent and
+    /// referrer are recorded.
+    /// Repeat clicks from the same visitor within the dedup window are flagged `is_repeat`.
+    /// `clicked` is the code the visitor requested, whether it was the custom alias, and
+    /// the path after it on path-passthrough links.
     pub fn track_click_event(
         &self,
         link_id: Uuid,
-        ip: std::net::IpAddr,
-        user_agent: &str,
-        referrer: Option<&str>,
-        method: &str,
-        response_time: u16,
-        status_code: u16,
+        tracking_mode: TrackingMode,
+        clicked: ClickedUrl<'_>,
+        context: ClickContext<'_>,
     ) {
         // Use the unified ClickHouse analytics service for event tracking
         if let Some(ref analytics) = self.clickhouse_analytics {
-            let event = crate::services::click_tracking::ClickEvent::new(
-                link_id,
-                ip,
-                user_agent,
-                referrer,
-                method,
-                response_time,
-                status_code,
-            );
+            let event = if tracking_mode.is_anonymous(context.opted_out) {
+                crate::services::click_tracking::ClickEvent::anonymous(
+                    link_id,
+                    context.method,
+                    context.response_time,
+                    context.status_code,
+                )
+            } else {
+                crate::services::click_tracking::ClickEvent::new(
+                    link_id,
+                    context.ip,
+                    context.user_agent,
+                    context.referrer,
+                    context.method,
+                    context.response_time,
+                    context.status_code,
+                )
+            };
+            let event = crate::services::click_tracking::ClickEvent {
+                sub_path: clicked.sub_path.to_string(),
+                short_code: clicked.short_code.to_string(),
+                via_alias: clicked.via_alias,
+                blocked_by_referrer: clicked.blocked_by_referrer,
+                ..event
+            };
+
+            if !self.click_dedup.is_enabled() {
+                // Track the click through unified service (async, fire-and-forget)
+                analytics.track_click(event);
+                return;
+            }
 
-            // Track the click through unified service (async, fire-and-forget)
-            analytics.track_click(event);
+            // The dedup check runs after the redirect has been answered
+            let visitor_hash = self
+                .click_dedup
+                .visitor_hash(context.ip, context.user_agent);
+            let click_dedup = self.click_dedup.clone();
+            let analytics = analytics.clone();
+            tokio::spawn(async move {
+                let is_repeat = click_dedup.is_repeat(link_id, &visitor_hash).await;
+                analytics.track_click(crate::services::click_tracking::ClickEvent {
+                    is_repeat,
+                    ..event
+                });
+            });
         } else {
             // ClickHouse not configured - silently skip (this is normal in dev/test)
             info!("ClickHouse analytics not configured, skipping event tracking");
         }
     }
 
+    /// Visitor hash for the conversion pixel cookie of a tracked redirect; `None` when the
+    /// click is recorded anonymously, so anonymous visitors can't be followed to a conversion
+    pub fn conversion_visitor(
+        &self,
+        tracking_mode: TrackingMode,
+        opted_out: bool,
+        ip: std::net::IpAddr,
+        user_agent: &str,
+    ) -> Option<String> {
+        (!tracking_mode.is_anonymous(opted_out))
+            .then(|| self.click_dedup.visitor_hash(ip, user_agent))
+    }
+
     // =============================================================================
     // HELPER METHODS
     // =============================================================================
 
     /// Validate user subscription limits
-    /// Self-hosted deployments have no link creation limits
-    async fn validate_subscription_limits(&self, _user: &User) -> Result<(), ServiceError> {
-        // No subscription limits enforced for self-hosted installations
-        Ok(())
+    /// No subscription tiers for self-hosted installations, only the optional
+    /// MAX_LINKS_PER_USER safety cap. Concurrent creates can overshoot it by a few links.
+    async fn validate_subscription_limits(&self, user: &User) -> Result<(), ServiceError> {
+        match self.link_usage(user.id).await? {
+            Some(usage) if usage.is_reached() => Err(ServiceError::LinkLimitReached {
+                current: usage.current,
+                limit: usage.limit,
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// The user's live links against MAX_LINKS_PER_USER; `None` when there is no cap
+    /// The count is cached briefly and dropped whenever the user's links are created or deleted
+    pub async fn link_usage(&self, user_id: Uuid) -> Result<Option<LinkUsage>, ServiceError> {
+        use crate::schema::links::dsl;
+
+        if self.max_links_per_user == 0 {
+            return Ok(None);
+        }
+
+        let key = link_count_cache_key(user_id);
+        let cached = match self.redis_pool.get::<u64>(&key).await {
+            Ok(cached) => cached,
+            Err(e) => {
+                warn!("Redis error reading link count for {}: {}", user_id, e);
+                None
+            },
+        };
+
+        let current = match cached {
+            Some(count) => count,
+            None => {
+                let mut conn = self
+                    .diesel_pool
+                    .get()
+                    .await
+                    .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+                let count: i64 = dsl::links
+                    .filter(dsl::user_id.eq(user_id))
+                    .filter(dsl::deleted_at.is_null())
+                    .count()
+                    .get_result(&mut conn)
+                    .await?;
+                let count = count as u64;
+
+                if let Err(e) = self
+                    .redis_pool
+                    .set_with_expiry(&key, count.to_string(), LINK_COUNT_CACHE_TTL_SECONDS)
+                    .await
+                {
+                    warn!("Failed to cache link count for {}: {}", user_id, e);
+                }
+                count
+            },
+        };
+
+        Ok(Some(LinkUsage {
+            current,
+            limit: self.max_links_per_user,
+        }))
+    }
+
+    /// Drop a user's cached link count after their links were created or deleted
+    async fn invalidate_link_count(&self, user_id: Uuid) {
+        if self.max_links_per_user == 0 {
+            return;
+        }
+        if let Err(e) = self.redis_pool.del(&link_count_cache_key(user_id)).await {
+            warn!("Failed to invalidate link count for {}: {}", user_id, e);
+        }
     }
 
     /// Try to extract metadata from URL using shared HTTP client with connection pooling
     async fn try_extract_metadata(&self, url: &str) -> Option<ExtractedMetadata> {
         use tokio::time::timeout;
 
-        // Use shared HTTP client with connection pooling and timeout for additional safety
+        if CONFIG.metadata_respect_robots {
+            match robots::is_allowed(url, Some(&self.redis_pool)).await {
+                Ok(true) => {},
+                Ok(false) => {
+                    info!(
+                        "Not fetching {} for metadata: disallowed by robots.txt",
+                        url
+                    );
+                    return None;
+                },
+                Err(e) => {
+                    warn!("robots.txt check failed for {}: {}", url, e);
+                    return None;
+                },
+            }
+        }
+
+        // Use shared SSRF-safe HTTP client with an overall timeout for additional safety
         let response =
-            match timeout(Duration::from_secs(3), METADATA_HTTP_CLIENT.get(url).send()).await {
+            match timeout(Duration::from_secs(3), METADATA_HTTP_CLIENT.get(url)).await {
                 Ok(Ok(resp)) => {
                     // Check content type early to avoid processing non-HTML content
                     if let Some(content_type) = resp.headers().get("content-type") {
@@ -1294,6 +2611,8 @@ impl LinkService {
             .await
             .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
+        let has_alias = new_link.custom_alias.is_some();
+
         // Insert the new link (OSS: no quota limits)
         conn.build_transaction()
             .run::<_, diesel::result::Error, _>(|conn| {
@@ -1305,47 +2624,49 @@ impl LinkService {
                 })
             })
             .await
-            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+            .map_err(|e| match e {
+                // Another request took the alias (in some case) since it was checked
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) if has_alias => ServiceError::AliasAlreadyExists,
+                e => ServiceError::DatabaseError(e.to_string()),
+            })
     }
 
-    /// Cache link in Redis
+    /// Cache link in Redis (see [`cache_link`])
     async fn cache_link(&self, link: &Link) -> Result<(), ServiceError> {
-        let cache_key = format!("link:{}", link.short_code);
-
-        // Serialize the entire Link object
-        let serialized = serde_json::to_string(link)
-            .map_err(|e| ServiceError::CacheError(format!("Failed to serialize link: {}", e)))?;
-
-        let mut redis_conn = self
-            .redis_pool
-            .get_connection()
-            .await
-            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+        cache_link(&self.redis_pool, link).await
+    }
 
-        let _: () = redis_conn
-            .set_ex(
-                &cache_key,
-                serialized.clone(),
-                LINK_CACHE_TTL_SECONDS as u64,
-            )
-            .await
-            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+    /// Get the cached redirect record; `None` on a miss, a Redis error or a record that
+    /// no longer decodes, so the caller falls back to the database
+    async fn get_cached_redirect(&self, short_code: &str) -> Option<RedirectRecord> {
+        let [_, cache_key, _] = cache_keys_for_code(short_code);
 
-        // Also cache by custom alias if present
-        if let Some(ref alias) = link.custom_alias {
-            let alias_key = format!("link:{}", alias);
-            let _: () = redis_conn
-                .set_ex(&alias_key, serialized, LINK_CACHE_TTL_SECONDS as u64)
-                .await
-                .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+        match self.redis_pool.get::<String>(&cache_key).await {
+            Ok(Some(data)) => match serde_json::from_str::<RedirectRecord>(&data) {
+                // Records from before owners were cached can't honour a paused account
+                Ok(record) => record.owner_id.is_some().then_some(record),
+                Err(e) => {
+                    warn!(
+                        "Failed to deserialize redirect record for {}: {}",
+                        short_code, e
+                    );
+                    None
+                },
+            },
+            Ok(None) => None,
+            Err(e) => {
+                warn!("Redis error getting redirect record: {}", e);
+                None
+            },
         }
-
-        Ok(())
     }
 
     /// Get cached link
     async fn get_cached_link(&self, short_code: &str) -> Result<Option<Link>, ServiceError> {
-        let cache_key = format!("link:{}", short_code);
+        let [cache_key, _, _] = cache_keys_for_code(short_code);
 
         match self.redis_pool.get::<String>(&cache_key).await {
             Ok(Some(data)) => {
@@ -1369,16 +2690,17 @@ impl LinkService {
         }
     }
 
-    /// Invalidate cache entry
+    /// Invalidate every cache entry for a short code or alias (see [`invalidate_code_cache`])
     async fn invalidate_cache(&self, short_code: &str) -> Result<(), ServiceError> {
-        let cache_key = format!("link:{}", short_code);
+        invalidate_code_cache(&self.redis_pool, short_code).await
+    }
 
+    /// Drop a link's cached owner once it is deleted or changes hands
+    async fn invalidate_owner(&self, link_id: Uuid) -> Result<(), ServiceError> {
         self.redis_pool
-            .del(&cache_key)
+            .del(&owner_cache_key(link_id))
             .await
-            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
-
-        Ok(())
+            .map_err(|e| ServiceError::CacheError(e.to_string()))
     }
 
     /// Invalidate multiple cache entries using Redis pipeline for efficiency
@@ -1410,178 +2732,337 @@ impl LinkService {
     }
 }
 
-/// Fast Redis click count increment with retry mechanism (used for real-time tracking)
-async fn increment_click_count_redis(
+/// Redis keys caching one short code or alias: the full Link JSON, the redirect record and
+/// the `CodeMeta` hash.
+/// Consistency rules: all three are written together by [`cache_link`] in one MULTI/EXEC
+/// with the same TTL, and deleted together by [`invalidate_code_cache`] or by deleting every
+/// key from [`link_cache_keys`]. Readers use only their own key and treat a missing or
+/// undecodable entry as a miss, reloading from the database and rewriting all three, so an
+/// entry left behind by a partial invalidation is never combined with a newer one.
+pub fn cache_keys_for_code(code: &str) -> [String; 3] {
+    [
+        format!("link:{}", code),
+        format!("redirect:{}", code),
+        format!("code:{}", code),
+    ]
+}
+
+/// Cache link in Redis: the full Link JSON for the management API, the compact
+/// redirect record for the redirect path and the `CodeMeta` hash for id lookups, under the
+/// short code and any custom alias
+pub async fn cache_link(redis_pool: &RedisPool, link: &Link) -> Result<(), ServiceError> {
+    // Serialize the entire Link object
+    let serialized = serde_json::to_string(link)
+        .map_err(|e| ServiceError::CacheError(format!("Failed to serialize link: {}", e)))?;
+    let record = serde_json::to_string(&RedirectRecord::from(link)).map_err(|e| {
+        ServiceError::CacheError(format!("Failed to serialize redirect record: {}", e))
+    })?;
+
+    let mut redis_conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    let meta = CodeMeta::from(link).to_fields();
+
+    // Also cache by custom alias if present
+    let mut pipe = redis::pipe();
+    pipe.atomic();
+    for code in std::iter::once(&link.short_code).chain(link.custom_alias.as_ref()) {
+        let [link_key, redirect_key, code_key] = cache_keys_for_code(code);
+        pipe.set_ex(link_key, &serialized, LINK_CACHE_TTL_SECONDS as u64)
+            .ignore()
+            .set_ex(redirect_key, &record, LINK_CACHE_TTL_SECONDS as u64)
+            .ignore()
+            // Replace, not merge, so no field from an older write survives
+            .del(&code_key)
+            .ignore()
+            .hset_multiple(&code_key, &meta)
+            .ignore()
+            .expire(&code_key, LINK_CACHE_TTL_SECONDS as i64)
+            .ignore();
+    }
+
+    pipe.query_async::<()>(&mut redis_conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    Ok(())
+}
+
+/// The cached `CodeMeta` for a short code or alias; `None` on a miss or a partial hash
+pub async fn cached_code_meta(
     redis_pool: &RedisPool,
-    short_code: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let counter_key = format!("clicks:{}", short_code);
+    code: &str,
+) -> Result<Option<CodeMeta>, ServiceError> {
+    let [_, _, code_key] = cache_keys_for_code(code);
+    let mut conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    let fields: std::collections::HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(&code_key)
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    Ok(CodeMeta::from_fields(&fields))
+}
 
-    increment_click_count_with_retry(redis_pool, &counter_key, short_code, 3).await
+/// Redis key holding the user id that reserved an alias; aliases are matched case-insensitively
+pub fn alias_reservation_key(alias: &str) -> String {
+    format!("alias_reserve:{}", alias.to_lowercase())
 }
 
-/// Increment click count with exponential backoff retry
-async fn increment_click_count_with_retry(
+/// Redis key caching a user's live link count ([`LinkUsage`])
+pub fn link_count_cache_key(user_id: Uuid) -> String {
+    format!("link_count:{}", user_id)
+}
+
+/// Redis key caching who may read a link ([`LinkOwner`]); keyed by id, not short code
+pub fn owner_cache_key(link_id: Uuid) -> String {
+    format!("owner:{}", link_id)
+}
+
+/// The cached `LinkOwner` for a link; `None` on a miss or a partial hash
+pub async fn cached_link_owner(
     redis_pool: &RedisPool,
-    counter_key: &str,
-    short_code: &str,
-    max_retries: u32,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    use std::time::Duration;
-    use tokio::time::sleep;
+    link_id: Uuid,
+) -> Result<Option<LinkOwner>, ServiceError> {
+    let mut conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
 
-    let mut retries = 0;
-    let mut delay = Duration::from_millis(100);
+    let fields: std::collections::HashMap<String, String> = redis::cmd("HGETALL")
+        .arg(owner_cache_key(link_id))
+        .query_async(&mut conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
 
-    loop {
-        match try_increment_click(redis_pool, counter_key).await {
-            Ok(_) => return Ok(()),
-            Err(e) if retries < max_retries => {
-                warn!(
-                    "Click tracking failed for {}, retry {}/{}: {}",
-                    short_code,
-                    retries + 1,
-                    max_retries,
-                    e
-                );
-                retries += 1;
-                sleep(delay).await;
-                delay *= 2; // Exponential backoff
-            },
-            Err(e) => {
-                // After max retries, log to a fallback queue for batch processing
-                error!(
-                    "Click tracking failed after {} retries for {}: {}. Adding to fallback queue.",
-                    max_retries, short_code, e
-                );
+    Ok(LinkOwner::from_fields(&fields))
+}
 
-                // Try to add to fallback queue (best effort)
-                if let Ok(mut conn) = redis_pool.get_connection().await {
-                    let fallback_key = format!("clicks:fallback:{}", short_code);
-                    let _ = conn.incr::<_, _, ()>(&fallback_key, 1).await;
-                    let _ = conn.expire::<_, ()>(&fallback_key, SECONDS_PER_DAY as i64).await; // 24 hour TTL
-                }
+/// Cache a link's owner for `OWNER_CACHE_TTL_SECONDS`, replacing any earlier entry
+pub async fn cache_link_owner(
+    redis_pool: &RedisPool,
+    link_id: Uuid,
+    owner: &LinkOwner,
+) -> Result<(), ServiceError> {
+    let key = owner_cache_key(link_id);
+    let mut conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    redis::pipe()
+        .atomic()
+        .del(&key)
+        .ignore()
+        .hset_multiple(&key, &owner.to_fields())
+        .ignore()
+        .expire(&key, OWNER_CACHE_TTL_SECONDS as i64)
+        .ignore()
+        .query_async::<()>(&mut conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))
+}
 
-                return Err(e);
-            },
-        }
-    }
+/// Delete every cache entry for a short code or alias with a single DEL
+pub async fn invalidate_code_cache(redis_pool: &RedisPool, code: &str) -> Result<(), ServiceError> {
+    let mut conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    redis::cmd("DEL")
+        .arg(&cache_keys_for_code(code)[..])
+        .query_async::<()>(&mut conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))
+}
+
+/// Audit log details for a permanent delete: the link in account-export form plus the
+/// ids needed to trace it afterwards
+fn permanent_delete_snapshot(link: &Link) -> serde_json::Value {
+    serde_json::json!({
+        "link_id": link.id,
+        "owner_id": link.user_id,
+        "organization_id": link.organization_id,
+        "link": ArchivedLink::from(link),
+    })
 }
 
-/// Actual Redis increment operation
-async fn try_increment_click(
+/// Switch off active links past `expires_at` and drop their cached redirects
+/// Redirects already refuse expired links; this keeps `is_active` honest for counts and
+/// list filters. Returns the number of links deactivated.
+pub async fn deactivate_expired_links(
+    diesel_pool: &DieselPool,
     redis_pool: &RedisPool,
-    counter_key: &str,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let mut redis_conn = redis_pool.get_connection().await?;
+) -> Result<usize, ServiceError> {
+    use crate::schema::links::dsl;
+
+    let mut conn = diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
 
-    // Increment counter
-    let _: () = redis_conn.incr(counter_key, 1).await?;
+    let mut total = 0;
+    loop {
+        let now = Utc::now();
+        let batch: Vec<Uuid> = dsl::links
+            .select(dsl::id)
+            .filter(dsl::is_active.eq(true))
+            .filter(dsl::expires_at.lt(now))
+            .filter(dsl::deleted_at.is_null())
+            .limit(EXPIRY_SWEEP_BATCH_SIZE)
+            .load(&mut conn)
+            .await?;
+        if batch.is_empty() {
+            break;
+        }
 
-    // Set TTL for cleanup
-    redis_conn
-        .expire::<_, ()>(counter_key, CLICK_COUNTER_TTL_SECONDS as i64)
+        // Re-checked so a link reactivated in between is left alone
+        let expired: Vec<Link> = diesel::update(
+            dsl::links
+                .filter(dsl::id.eq_any(&batch))
+                .filter(dsl::is_active.eq(true))
+                .filter(dsl::expires_at.lt(now)),
+        )
+        .set((
+            dsl::is_active.eq(false),
+            dsl::deactivation_reason.eq(DEACTIVATION_REASON_EXPIRED),
+            dsl::updated_at.eq(now),
+        ))
+        .returning(dsl::links::all_columns())
+        .get_results(&mut conn)
         .await?;
 
-    Ok(())
+        total += expired.len();
+
+        let mut pipe = redis::pipe();
+        for key in expired.iter().flat_map(link_cache_keys) {
+            pipe.del(key).ignore();
+        }
+        match redis_pool.get_connection().await {
+            Ok(mut redis_conn) => {
+                if let Err(e) = pipe.query_async::<()>(&mut redis_conn).await {
+                    warn!("Failed to invalidate cache for expired links: {}", e);
+                }
+            },
+            Err(e) => warn!("Failed to invalidate cache for expired links: {}", e),
+        }
+
+        if (batch.len() as i64) < EXPIRY_SWEEP_BATCH_SIZE {
+            break;
+        }
+    }
+
+    Ok(total)
+}
+
+/// Every cache key for a link: those under its short code and any custom alias, and its owner
+pub fn link_cache_keys(link: &Link) -> Vec<String> {
+    let mut keys = cache_keys_for_code(&link.short_code).to_vec();
+    if let Some(ref alias) = link.custom_alias {
+        keys.extend(cache_keys_for_code(alias));
+    }
+    keys.push(owner_cache_key(link.id));
+    keys
 }
 
 /// Background job to sync Redis click counts to database (call every 5 minutes)
+/// Adds the drained counts to `links.click_count`; a batch the database rejects goes back
+/// to Redis as fallback keys for the next run. Returns the number of short codes synced.
+/// Active sliding links get `expires_at` moved to `sliding_window_days` from now, and their
+/// cached redirect records are dropped so redirects see the new expiry.
 pub async fn sync_click_counts_to_database(
     redis_pool: &RedisPool,
     diesel_pool: &DieselPool,
 ) -> Result<u32, Box<dyn std::error::Error + Send + Sync>> {
     use crate::schema::links::dsl;
-
-    // Get all click counter keys (including fallback queue)
-    let mut redis_conn = redis_pool.get_connection().await?;
-    let pattern = "clicks:*";
-    let mut keys: Vec<String> = redis_conn.keys(pattern).await.unwrap_or_default();
-
-    // Also get fallback queue keys
-    let fallback_pattern = "clicks:fallback:*";
-    let fallback_keys: Vec<String> = redis_conn.keys(fallback_pattern).await.unwrap_or_default();
-    keys.extend(fallback_keys);
-
-    if keys.is_empty() {
+    use diesel::dsl::sql;
+    use diesel::sql_types::{Nullable, Timestamptz};
+
+    let counts: Vec<(String, i64)> = click_counter::drain_click_counters(redis_pool)
+        .await?
+        .into_iter()
+        .collect();
+    if counts.is_empty() {
         return Ok(0);
     }
 
     let mut updated_count = 0;
-    let mut conn = diesel_pool.get().await?;
+    let mut conn = match diesel_pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            requeue_unsynced_clicks(redis_pool, &counts).await;
+            return Err(e.into());
+        },
+    };
 
     // Process in batches
-    for chunk in keys.chunks(CLICK_SYNC_BATCH_SIZE) {
-        // Use Redis pipeline to fetch all click counts efficiently
-        let mut updates = Vec::new();
-        if !chunk.is_empty() {
-            // Create pipeline to fetch all click counts at once
-            let mut pipe = redis::pipe();
-            for key in chunk {
-                pipe.get(key);
-            }
-
-            // Execute pipeline and get all values
-            if let Ok(values) = pipe
-                .query_async::<Vec<Option<String>>>(&mut redis_conn)
-                .await
-            {
-                for (key, value_opt) in chunk.iter().zip(values.iter()) {
-                    if let Some(count_str) = value_opt {
-                        // Extract short_code from key (handle both regular and fallback keys)
-                        let short_code = if let Some(code) = key.strip_prefix("clicks:fallback:") {
-                            code.to_string()
-                        } else if let Some(code) = key.strip_prefix("clicks:") {
-                            code.to_string()
-                        } else {
-                            continue; // Skip invalid keys
-                        };
-
-                        if let Ok(count) = count_str.parse::<i32>() {
-                            if count > 0 {
-                                updates.push((short_code, count));
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        let update_count = updates.len();
-
-        // Start transaction for database updates
+    for chunk in counts.chunks(CLICK_SYNC_BATCH_SIZE) {
         let result = conn
             .build_transaction()
             .run::<_, diesel::result::Error, _>(|conn| {
                 Box::pin(async move {
-                    for (short_code, _count) in &updates {
-                        // Update database
-                        let _rows_affected = diesel::update(
-                            dsl::links
-                                .filter(dsl::short_code.eq(short_code))
-                                .or_filter(dsl::custom_alias.eq(short_code)),
-                        )
-                        .set(dsl::last_accessed_at.eq(Utc::now()))
-                        .execute(conn)
-                        .await?;
-
-                        // Count successful updates (we'll move increment outside)
+                    let now = Utc::now();
+                    let mut extended = Vec::new();
+                    for (short_code, clicks) in chunk {
+                        // Redirects count under the short code or the custom alias, which may
+                        // have been requested in any case; an exact short code wins, as on lookup
+                        let mut ids: Vec<Uuid> = dsl::links
+                            .filter(dsl::short_code.eq(short_code))
+                            .select(dsl::id)
+                            .load(conn)
+                            .await?;
+                        if ids.is_empty() {
+                            ids = dsl::links
+                                .filter(lower(dsl::custom_alias).eq(short_code.to_lowercase()))
+                                .select(dsl::id)
+                                .load(conn)
+                                .await?;
+                        }
+                        let by_code = dsl::id.eq_any(ids);
+                        diesel::update(dsl::links.filter(by_code.clone()))
+                            .set((
+                                dsl::click_count.eq(dsl::click_count + clicks),
+                                dsl::last_accessed_at.eq(now),
+                            ))
+                            .execute(conn)
+                            .await?;
+
+                        let sliding_expiry = sql::<Nullable<Timestamptz>>("")
+                            .bind::<Timestamptz, _>(now)
+                            .sql(" + sliding_window_days * INTERVAL '1 day'");
+                        extended.extend(
+                            diesel::update(
+                                dsl::links
+                                    .filter(by_code)
+                                    .filter(dsl::expiry_policy.eq(ExpiryPolicy::Sliding.as_str()))
+                                    .filter(dsl::is_active.eq(true)),
+                            )
+                            .set(dsl::expires_at.eq(sliding_expiry))
+                            .get_results::<Link>(conn)
+                            .await?,
+                        );
                     }
-                    Ok(())
+                    Ok(extended)
                 })
             })
             .await;
 
-        if result.is_ok() {
-            updated_count += update_count as u32;
-            // Delete processed keys from Redis using pipeline for efficiency
-            if !chunk.is_empty() {
-                let mut pipe = redis::pipe();
-                for key in chunk {
-                    pipe.del(key);
-                }
-                let _ = pipe.query_async::<Vec<i32>>(&mut redis_conn).await;
-            }
+        match result {
+            Ok(extended) => {
+                updated_count += chunk.len() as u32;
+                invalidate_extended_links(redis_pool, &extended).await;
+            },
+            Err(e) => {
+                error!("Failed to sync {} click counts: {}", chunk.len(), e);
+                requeue_unsynced_clicks(redis_pool, chunk).await;
+            },
         }
     }
 
@@ -1592,6 +3073,39 @@ pub async fn sync_click_counts_to_database(
     Ok(updated_count)
 }
 
+/// Drop cached redirect records still carrying a sliding link's previous expiry
+async fn invalidate_extended_links(redis_pool: &RedisPool, links: &[Link]) {
+    if links.is_empty() {
+        return;
+    }
+
+    let mut pipe = redis::pipe();
+    for key in links.iter().flat_map(link_cache_keys) {
+        pipe.del(key).ignore();
+    }
+    match redis_pool.get_connection().await {
+        Ok(mut redis_conn) => {
+            if let Err(e) = pipe.query_async::<()>(&mut redis_conn).await {
+                warn!("Failed to invalidate cache for sliding links: {}", e);
+            }
+        },
+        Err(e) => warn!("Failed to invalidate cache for sliding links: {}", e),
+    }
+}
+
+/// Return drained counts to Redis; if that fails too they can only be logged
+async fn requeue_unsynced_clicks(redis_pool: &RedisPool, counts: &[(String, i64)]) {
+    if let Err(e) = click_counter::requeue_click_counts(redis_pool, counts).await {
+        let clicks: i64 = counts.iter().map(|(_, clicks)| clicks).sum();
+        error!(
+            "Lost {} clicks for {} links: sync failed and requeue failed: {}",
+            clicks,
+            counts.len(),
+            e
+        );
+    }
+}
+
 /// Helper function to update link metadata and activate it
 async fn update_link_metadata_and_activate(
     diesel_pool: Arc<DieselPool>,
@@ -1627,17 +3141,10 @@ async fn update_link_metadata_and_activate(
     // Get the link to invalidate its cache
     let link = dsl::links.find(link_id).first::<Link>(&mut conn).await?;
 
-    // Invalidate cache for this link
-    let cache_key = format!("link:{}", link.short_code);
-    if let Err(e) = redis_pool.del(&cache_key).await {
-        warn!("Failed to invalidate cache for {}: {}", cache_key, e);
-    }
-
-    // Also invalidate custom alias cache if exists
-    if let Some(ref alias) = link.custom_alias {
-        let alias_key = format!("link:{}", alias);
-        if let Err(e) = redis_pool.del(&alias_key).await {
-            warn!("Failed to invalidate cache for {}: {}", alias_key, e);
+    // Invalidate cache for this link, including any custom alias
+    for cache_key in link_cache_keys(&link) {
+        if let Err(e) = redis_pool.del(&cache_key).await {
+            warn!("Failed to invalidate cache for {}: {}", cache_key, e);
         }
     }
 
@@ -1679,17 +3186,10 @@ async fn activate_link_immediately(
     // Get the link to invalidate its cache
     let link = dsl::links.find(link_id).first::<Link>(&mut conn).await?;
 
-    // Invalidate the short_code cache
-    let cache_key = format!("link:{}", link.short_code);
-    if let Err(e) = redis_pool.del(&cache_key).await {
-        warn!("Failed to invalidate cache for {}: {}", cache_key, e);
-    }
-
-    // Also invalidate custom alias cache if exists
-    if let Some(ref alias) = link.custom_alias {
-        let alias_key = format!("link:{}", alias);
-        if let Err(e) = redis_pool.del(&alias_key).await {
-            warn!("Failed to invalidate cache for {}: {}", alias_key, e);
+    // Invalidate cache for this link, including any custom alias
+    for cache_key in link_cache_keys(&link) {
+        if let Err(e) = redis_pool.del(&cache_key).await {
+            warn!("Failed to invalidate cache for {}: {}", cache_key, e);
         }
     }
 
@@ -1730,22 +3230,82 @@ async fn activate_link_after_failure(
     // Get the link to invalidate its cache
     let link = dsl::links.find(link_id).first::<Link>(&mut conn).await?;
 
-    // Invalidate cache for this link
-    let cache_key = format!("link:{}", link.short_code);
-    if let Err(e) = redis_pool.del(&cache_key).await {
-        warn!("Failed to invalidate cache for {}: {}", cache_key, e);
+    // Invalidate cache for this link, including any custom alias
+    for cache_key in link_cache_keys(&link) {
+        if let Err(e) = redis_pool.del(&cache_key).await {
+            warn!("Failed to invalidate cache for {}: {}", cache_key, e);
+        }
     }
 
-    // Also invalidate custom alias cache if exists
-    if let Some(ref alias) = link.custom_alias {
-        let alias_key = format!("link:{}", alias);
-        if let Err(e) = redis_pool.del(&alias_key).await {
-            warn!("Failed to invalidate cache for {}: {}", alias_key, e);
+    info!(
+        "Activated link {} after metadata extraction failure and invalidated cache",
+        link_id
+    );
+    Ok(())
+}
+
+/// Message `create_link` refuses a URL with when its security scan isn't safe
+fn blocked_reason(result: &SecurityScanResult) -> Option<String> {
+    if result.is_safe {
+        return None;
+    }
+    Some(if !result.warnings.is_empty() {
+        result.warnings.join("; ")
+    } else {
+        format!(
+            "URL blocked due to security threats detected (Risk: {:?}, Score: {})",
+            result.risk_level, result.threat_score
+        )
+    })
+}
+
+/// Title for a link whose destination can't be fetched: the bare hostname
+fn robots_fallback_title(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    parsed.host_str().map(host_to_unicode)
+}
+
+/// Helper function to activate a link without fetching its destination.
+/// Keeps user-provided metadata and sets the fallback title.
+async fn activate_link_with_fallback_title(
+    diesel_pool: Arc<DieselPool>,
+    link_id: Uuid,
+    title: Option<String>,
+    redis_pool: Arc<RedisPool>,
+) -> Result<(), ServiceError> {
+    use crate::schema::links::dsl;
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+
+    let mut conn = diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+    diesel::update(dsl::links.filter(dsl::id.eq(link_id)))
+        .set((
+            dsl::title.eq(title),
+            dsl::is_active.eq(true),
+            dsl::processing_status.eq("skipped"),
+            dsl::metadata_extracted_at.eq(Utc::now().naive_utc()),
+            dsl::updated_at.eq(Utc::now()),
+        ))
+        .execute(&mut conn)
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+    // Get the link to invalidate its cache
+    let link = dsl::links.find(link_id).first::<Link>(&mut conn).await?;
+
+    // Invalidate cache for this link, including any custom alias
+    for cache_key in link_cache_keys(&link) {
+        if let Err(e) = redis_pool.del(&cache_key).await {
+            warn!("Failed to invalidate cache for {}: {}", cache_key, e);
         }
     }
 
     info!(
-        "Activated link {} after metadata extraction failure and invalidated cache",
+        "Activated link {} without metadata extraction and invalidated cache",
         link_id
     );
     Ok(())
@@ -0,0 +1,279 @@
+// Account-wide link pause ("pause all links")
+// A user whose site was compromised can stop every short link they own at once without
+// touching the links themselves. `users.links_paused_at` is the record; the redirect path
+// reads the Redis key `links_paused:{user_id}` through a per-process cache instead, so a
+// redirect costs at most one Redis read per owner per PAUSE_CACHE_TTL. Pausing takes effect
+// at once on the replica that handled it and within PAUSE_CACHE_TTL elsewhere.
+// A leader task rewrites the keys of paused users from the database in case Redis lost them.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use once_cell::sync::Lazy;
+use redis::AsyncCommands;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::link::LinksPauseStatus,
+    schema::users,
+    services::background_tasks::TASK_REGISTRY,
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        service_error::ServiceError,
+        ttl_cache::TtlCache,
+    },
+};
+
+/// How long a replica trusts its cached copy of an owner's pause flag
+const PAUSE_CACHE_TTL: Duration = Duration::from_secs(5);
+
+/// Stale flags are dropped once the cache holds more owners than this
+const PAUSE_CACHE_MAX_OWNERS: usize = 50_000;
+
+/// Lifetime of the `0` written on resume; it keeps a resync that read the user as paused
+/// just before from pausing them again
+const RESUME_TOMBSTONE_SECONDS: u64 = 3600;
+
+/// How often the keys of paused users are rewritten from the database
+const PAUSE_RESYNC_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Redis key holding the Unix time a user paused their links; `0` or missing: not paused
+pub fn links_paused_key(user_id: Uuid) -> String {
+    format!("links_paused:{}", user_id)
+}
+
+/// Owners' pause flags as last seen in Redis
+static PAUSED_OWNERS: Lazy<TtlCache<Uuid, bool>> =
+    Lazy::new(|| TtlCache::new(PAUSE_CACHE_TTL, PAUSE_CACHE_MAX_OWNERS));
+
+/// Whether the owner has paused all of their links. If Redis is unavailable the last known
+/// flag is used, and links of owners never seen keep redirecting.
+pub async fn is_owner_paused(redis_pool: &RedisPool, owner_id: Uuid) -> bool {
+    let cached = PAUSED_OWNERS.get(&owner_id);
+    if let Some((paused, true)) = cached {
+        return paused;
+    }
+
+    match redis_pool.get::<i64>(&links_paused_key(owner_id)).await {
+        Ok(paused_at) => {
+            let paused = paused_at.is_some_and(|at| at > 0);
+            PAUSED_OWNERS.insert(owner_id, paused);
+            paused
+        },
+        Err(e) => {
+            warn!("Failed to read link pause flag for {}: {}", owner_id, e);
+            cached.is_some_and(|(paused, _)| paused)
+        },
+    }
+}
+
+pub struct LinkPauseService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+}
+
+impl LinkPauseService {
+    pub fn new(diesel_pool: DieselPool, redis_pool: RedisPool) -> Self {
+        Self {
+            diesel_pool,
+            redis_pool,
+        }
+    }
+
+    /// Stop every link the user owns from redirecting. Pausing again keeps the original
+    /// `paused_at`.
+    pub async fn pause_all(&self, user_id: Uuid) -> Result<LinksPauseStatus, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        diesel::update(
+            users::table
+                .find(user_id)
+                .filter(users::links_paused_at.is_null()),
+        )
+        .set(users::links_paused_at.eq(Utc::now()))
+        .execute(&mut conn)
+        .await?;
+
+        let paused_at: DateTime<Utc> = users::table
+            .find(user_id)
+            .select(users::links_paused_at)
+            .first::<Option<DateTime<Utc>>>(&mut conn)
+            .await
+            .optional()?
+            .flatten()
+            .ok_or(ServiceError::NotFound)?;
+
+        PAUSED_OWNERS.insert(user_id, true);
+        let mut redis_conn = self
+            .redis_pool
+            .get_connection()
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+        redis_conn
+            .set::<_, _, ()>(links_paused_key(user_id), paused_at.timestamp())
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::LinksPaused,
+            user_id,
+            "user",
+            Some(user_id.to_string()),
+            Some("Paused all links".to_string()),
+        )
+        .await;
+        info!("User {} paused all links", user_id);
+
+        Ok(LinksPauseStatus {
+            paused: true,
+            paused_at: Some(paused_at),
+        })
+    }
+
+    /// Let the user's links redirect again; links switched off one by one stay off
+    pub async fn resume_all(&self, user_id: Uuid) -> Result<LinksPauseStatus, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let updated = diesel::update(users::table.find(user_id))
+            .set(users::links_paused_at.eq(None::<DateTime<Utc>>))
+            .execute(&mut conn)
+            .await?;
+        if updated == 0 {
+            return Err(ServiceError::NotFound);
+        }
+
+        PAUSED_OWNERS.insert(user_id, false);
+        let mut redis_conn = self
+            .redis_pool
+            .get_connection()
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+        redis_conn
+            .set_ex::<_, _, ()>(links_paused_key(user_id), 0, RESUME_TOMBSTONE_SECONDS)
+            .await
+            .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::LinksResumed,
+            user_id,
+            "user",
+            Some(user_id.to_string()),
+            Some("Resumed all links".to_string()),
+        )
+        .await;
+        info!("User {} resumed all links", user_id);
+
+        Ok(LinksPauseStatus {
+            paused: false,
+            paused_at: None,
+        })
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Write the pause key of every paused user that has none. Keys are only ever added here,
+/// never overwritten, so a resume (which leaves a `0` behind) is not undone by a run that
+/// loaded the user before it. Returns the number of keys written.
+pub async fn resync_paused_owners(
+    diesel_pool: &DieselPool,
+    redis_pool: &RedisPool,
+) -> Result<usize, ServiceError> {
+    let mut conn = diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+    let paused: Vec<(Uuid, Option<DateTime<Utc>>)> = users::table
+        .filter(users::links_paused_at.is_not_null())
+        .select((users::id, users::links_paused_at))
+        .load(&mut conn)
+        .await?;
+    drop(conn);
+
+    if paused.is_empty() {
+        return Ok(0);
+    }
+
+    let mut redis_conn = redis_pool
+        .get_connection()
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+    let mut pipe = redis::pipe();
+    for (user_id, paused_at) in &paused {
+        pipe.cmd("SET")
+            .arg(links_paused_key(*user_id))
+            .arg(paused_at.map_or(1, |at| at.timestamp()))
+            .arg("NX");
+    }
+    let written: Vec<Option<String>> = pipe
+        .query_async(&mut redis_conn)
+        .await
+        .map_err(|e| ServiceError::CacheError(e.to_string()))?;
+
+    Ok(written.iter().filter(|reply| reply.is_some()).count())
+}
+
+/// Rewrite missing pause keys at startup and every PAUSE_RESYNC_INTERVAL
+pub fn spawn_pause_resync_task(state: AppState) {
+    // The keys are shared, so one replica writes them for all
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("link_pause_resync", redis_pool, move |reporter| {
+        let state = state.clone();
+        async move {
+            let mut interval = tokio::time::interval(PAUSE_RESYNC_INTERVAL);
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                match resync_paused_owners(&state.diesel_pool, &state.redis_pool).await {
+                    Ok(written) => {
+                        if written > 0 {
+                            warn!("Restored {} missing link pause keys", written);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Link pause resync failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pause_cache() {
+        let owner = Uuid::new_v4();
+        assert_eq!(links_paused_key(owner), format!("links_paused:{}", owner));
+
+        PAUSED_OWNERS.insert(owner, true);
+        assert_eq!(PAUSED_OWNERS.get(&owner), Some((true, true)));
+
+        // Resuming replaces the entry rather than adding a second one
+        PAUSED_OWNERS.insert(owner, false);
+        assert_eq!(PAUSED_OWNERS.get(&owner), Some((false, true)));
+    }
+}
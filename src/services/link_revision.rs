@@ -0,0 +1,158 @@
+// Link change history storage
+// Revisions are written by LinkService inside the transaction that changes the link;
+// this service reads them back and prunes them after LINK_REVISION_RETENTION_DAYS
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::{error, info};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::link_revision::{LinkHistoryQuery, LinkRevisionRecord, LinkRevisionResponse},
+    schema::{link_revisions, users},
+    services::background_tasks::TASK_REGISTRY,
+    utils::service_error::ServiceError,
+};
+
+/// How often expired revisions are pruned
+const REVISION_RETENTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+pub struct LinkRevisionService {
+    diesel_pool: DieselPool,
+}
+
+impl LinkRevisionService {
+    pub fn new(diesel_pool: DieselPool) -> Self {
+        Self { diesel_pool }
+    }
+
+    /// Page of a link's revisions (newest first) with each actor's email, and the total count
+    pub async fn list(
+        &self,
+        link_id: Uuid,
+        query: &LinkHistoryQuery,
+    ) -> Result<(Vec<LinkRevisionResponse>, i64), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let total = link_revisions::table
+            .filter(link_revisions::link_id.eq(link_id))
+            .count()
+            .get_result::<i64>(&mut conn)
+            .await?;
+
+        let records = link_revisions::table
+            .filter(link_revisions::link_id.eq(link_id))
+            .order((link_revisions::created_at.desc(), link_revisions::id.desc()))
+            .offset(query.offset())
+            .limit(query.per_page())
+            .select(LinkRevisionRecord::as_select())
+            .load(&mut conn)
+            .await?;
+
+        let mut actor_ids: Vec<Uuid> = records.iter().filter_map(|r| r.actor_id).collect();
+        actor_ids.sort_unstable();
+        actor_ids.dedup();
+
+        let emails: HashMap<Uuid, String> = if actor_ids.is_empty() {
+            HashMap::new()
+        } else {
+            users::table
+                .filter(users::id.eq_any(&actor_ids))
+                .select((users::id, users::email))
+                .load::<(Uuid, String)>(&mut conn)
+                .await?
+                .into_iter()
+                .collect()
+        };
+
+        let revisions = records
+            .into_iter()
+            .map(|record| {
+                let email = record.actor_id.and_then(|id| emails.get(&id).cloned());
+                LinkRevisionResponse::new(record, email)
+            })
+            .collect();
+
+        Ok((revisions, total))
+    }
+
+    /// Delete revisions older than the retention window; returns how many were removed
+    pub async fn prune_older_than(
+        &self,
+        retention: chrono::Duration,
+    ) -> Result<usize, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let cutoff = Utc::now() - retention;
+
+        let deleted =
+            diesel::delete(link_revisions::table.filter(link_revisions::created_at.lt(cutoff)))
+                .execute(&mut conn)
+                .await?;
+
+        Ok(deleted)
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Periodically delete link revisions older than LINK_REVISION_RETENTION_DAYS
+pub fn spawn_link_revision_retention_task(state: AppState) {
+    let retention_days = state.config.link_revision_retention_days;
+    if retention_days == 0 {
+        info!("Link revision pruning disabled (LINK_REVISION_RETENTION_DAYS=0)");
+        return;
+    }
+
+    let retention = chrono::Duration::days(i64::from(retention_days));
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("link_revision_retention", redis_pool, move |reporter| {
+        let service = LinkRevisionService::new(state.diesel_pool.clone());
+        async move {
+            let mut interval = tokio::time::interval(REVISION_RETENTION_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                match service.prune_older_than(retention).await {
+                    Ok(deleted) => {
+                        if deleted > 0 {
+                            info!(
+                                "Pruned {} link revisions older than {} days",
+                                deleted, retention_days
+                            );
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Link revision pruning failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,303 @@
+// Brute-force protection for password-protected links
+// Wrong passwords are counted per (link, IP) in Redis; after `link_unlock_max_attempts`
+// failures that IP is locked out of the link, for twice as long on each repeat lockout.
+// Keys use the link id, so a link's short code, its alias and the alias in another case
+// share one count.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::{app::AppState, app_config::SecurityConfig, db::RedisPool};
+
+/// How long an attempt holds the per-(link, IP) guard; concurrent guesses are
+/// refused rather than queued, so parallel requests can't bypass the failure count
+const ATTEMPT_GUARD_SECONDS: usize = 10;
+
+/// Unlock limits from `AppConfig.security`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnlockLockoutPolicy {
+    pub max_attempts: u32,
+    pub lockout_seconds: u64,
+    pub max_lockout_seconds: u64,
+    pub attempt_window_seconds: u64,
+}
+
+impl UnlockLockoutPolicy {
+    pub fn from_config(security: &SecurityConfig) -> Self {
+        Self {
+            max_attempts: security.link_unlock_max_attempts.max(1),
+            lockout_seconds: security.link_unlock_lockout_seconds.max(1),
+            max_lockout_seconds: security
+                .link_unlock_max_lockout_seconds
+                .max(security.link_unlock_lockout_seconds.max(1)),
+            attempt_window_seconds: security.link_unlock_attempt_window_seconds.max(1),
+        }
+    }
+
+    /// Length of the `n`th lockout (1-based): the base duration doubled each time, capped
+    pub fn lockout_duration(&self, n: u32) -> u64 {
+        let doublings = n.saturating_sub(1).min(32);
+        self.lockout_seconds
+            .saturating_mul(1u64 << doublings)
+            .min(self.max_lockout_seconds)
+    }
+}
+
+/// Unlock history for one (link, IP), stored as JSON in Redis
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnlockAttempts {
+    /// Wrong passwords since the last lockout or successful unlock
+    pub failures: u32,
+    /// Lockouts so far; each one lasts twice as long as the previous
+    pub lockouts: u32,
+    /// Unix timestamp the current lockout ends at
+    pub locked_until: Option<i64>,
+}
+
+impl UnlockAttempts {
+    /// Seconds left on the lockout, or `None` when attempts are allowed
+    pub fn lockout_remaining(&self, now: i64) -> Option<u64> {
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| (until - now) as u64)
+    }
+
+    /// Count a wrong password; returns the lockout length when this failure triggers one
+    pub fn record_failure(&mut self, policy: &UnlockLockoutPolicy, now: i64) -> Option<u64> {
+        if self.lockout_remaining(now).is_none() {
+            self.locked_until = None;
+        }
+
+        self.failures += 1;
+        if self.failures < policy.max_attempts {
+            return None;
+        }
+
+        self.failures = 0;
+        self.lockouts += 1;
+        let duration = policy.lockout_duration(self.lockouts);
+        self.locked_until = Some(now + duration as i64);
+        Some(duration)
+    }
+
+    /// Forget all failures and lockouts, as a successful unlock does
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Attempts remaining before the next lockout
+    pub fn remaining_attempts(&self, policy: &UnlockLockoutPolicy) -> u32 {
+        policy.max_attempts.saturating_sub(self.failures)
+    }
+
+    /// How long Redis keeps the record: through any lockout, then the idle window
+    fn ttl_seconds(&self, policy: &UnlockLockoutPolicy, now: i64) -> usize {
+        (self.lockout_remaining(now).unwrap_or(0) + policy.attempt_window_seconds) as usize
+    }
+}
+
+/// Why an unlock attempt may not proceed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockBlocked {
+    /// Too many wrong passwords; retry after this many seconds
+    LockedOut(u64),
+    /// Another attempt from the same IP is still being checked
+    AttemptInProgress,
+}
+
+impl UnlockBlocked {
+    pub fn retry_after_seconds(&self) -> u64 {
+        match self {
+            UnlockBlocked::LockedOut(seconds) => *seconds,
+            UnlockBlocked::AttemptInProgress => 1,
+        }
+    }
+}
+
+pub struct LinkUnlockService {
+    redis_pool: RedisPool,
+    policy: UnlockLockoutPolicy,
+}
+
+impl LinkUnlockService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            redis_pool: state.redis_pool.clone(),
+            policy: UnlockLockoutPolicy::from_config(&state.config.security),
+        }
+    }
+
+    pub fn policy(&self) -> &UnlockLockoutPolicy {
+        &self.policy
+    }
+
+    /// Claim the right to check one password; follow with `record_failure`, `record_success`
+    /// or `release`.
+    /// Redis outages fail open, like the login lockout, so protected links stay reachable.
+    pub async fn begin(&self, link_id: Uuid, ip: IpAddr) -> Result<(), UnlockBlocked> {
+        let now = Utc::now().timestamp();
+        if let Some(remaining) = self.load(link_id, ip).await.lockout_remaining(now) {
+            return Err(UnlockBlocked::LockedOut(remaining));
+        }
+
+        match self
+            .redis_pool
+            .set_nx_with_expiry(
+                &guard_key(link_id, ip),
+                now.to_string(),
+                ATTEMPT_GUARD_SECONDS,
+            )
+            .await
+        {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(UnlockBlocked::AttemptInProgress),
+            Err(e) => {
+                warn!("Unlock guard unavailable for {}: {}", link_id, e);
+                Ok(())
+            },
+        }
+    }
+
+    /// Record a wrong password and release the guard. Returns the updated history
+    /// and the lockout length if this failure triggered one.
+    pub async fn record_failure(&self, link_id: Uuid, ip: IpAddr) -> (UnlockAttempts, Option<u64>) {
+        let now = Utc::now().timestamp();
+        let mut attempts = self.load(link_id, ip).await;
+        let locked_for = attempts.record_failure(&self.policy, now);
+
+        let key = attempts_key(link_id, ip);
+        let value = serde_json::to_string(&attempts).unwrap_or_default();
+        if let Err(e) = self
+            .redis_pool
+            .set_with_expiry(&key, value, attempts.ttl_seconds(&self.policy, now))
+            .await
+        {
+            warn!("Failed to record unlock failure for {}: {}", link_id, e);
+        }
+
+        self.release(link_id, ip).await;
+        (attempts, locked_for)
+    }
+
+    /// A correct password clears the failure count and lockout history for this IP;
+    /// deleting the record is the stored form of `UnlockAttempts::reset`
+    pub async fn record_success(&self, link_id: Uuid, ip: IpAddr) {
+        if let Err(e) = self.redis_pool.del(&attempts_key(link_id, ip)).await {
+            warn!("Failed to reset unlock attempts for {}: {}", link_id, e);
+        }
+        self.release(link_id, ip).await;
+    }
+
+    /// Release the guard without recording anything (e.g. the link no longer exists)
+    pub async fn release(&self, link_id: Uuid, ip: IpAddr) {
+        let _ = self.redis_pool.del(&guard_key(link_id, ip)).await;
+    }
+
+    async fn load(&self, link_id: Uuid, ip: IpAddr) -> UnlockAttempts {
+        match self
+            .redis_pool
+            .get::<String>(&attempts_key(link_id, ip))
+            .await
+        {
+            Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_default(),
+            Ok(None) => UnlockAttempts::default(),
+            Err(e) => {
+                warn!("Failed to load unlock attempts for {}: {}", link_id, e);
+                UnlockAttempts::default()
+            },
+        }
+    }
+}
+
+fn attempts_key(link_id: Uuid, ip: IpAddr) -> String {
+    format!("link_unlock:attempts:{}:{}", link_id, ip)
+}
+
+fn guard_key(link_id: Uuid, ip: IpAddr) -> String {
+    format!("link_unlock:guard:{}:{}", link_id, ip)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> UnlockLockoutPolicy {
+        UnlockLockoutPolicy {
+            max_attempts: 3,
+            lockout_seconds: 60,
+            max_lockout_seconds: 300,
+            attempt_window_seconds: 900,
+        }
+    }
+
+    #[test]
+    fn test_unlock_lockout_after_max_failures() {
+        let policy = policy();
+        let mut attempts = UnlockAttempts::default();
+        let now = 1_000;
+
+        assert_eq!(attempts.record_failure(&policy, now), None);
+        assert_eq!(attempts.record_failure(&policy, now), None);
+        assert_eq!(attempts.remaining_attempts(&policy), 1);
+        assert_eq!(attempts.record_failure(&policy, now), Some(60));
+        assert_eq!(attempts.lockout_remaining(now), Some(60));
+        assert_eq!(attempts.lockout_remaining(now + 45), Some(15));
+        assert_eq!(attempts.remaining_attempts(&policy), 3);
+    }
+
+    #[test]
+    fn test_unlock_lockout_expires_and_escalates() {
+        let policy = policy();
+        let mut attempts = UnlockAttempts::default();
+        let mut now = 1_000;
+
+        for _ in 0..3 {
+            attempts.record_failure(&policy, now);
+        }
+        now += 60;
+        assert_eq!(attempts.lockout_remaining(now), None, "lockout has expired");
+
+        // Repeat lockouts double, up to the cap
+        for expected in [120, 240, 300, 300] {
+            assert_eq!(attempts.record_failure(&policy, now), None);
+            assert_eq!(attempts.record_failure(&policy, now), None);
+            assert_eq!(attempts.record_failure(&policy, now), Some(expected));
+            now += expected as i64;
+            assert_eq!(attempts.lockout_remaining(now), None);
+        }
+
+        // Redis keeps the record through the lockout plus the idle window
+        attempts.record_failure(&policy, now);
+        attempts.record_failure(&policy, now);
+        attempts.record_failure(&policy, now);
+        assert_eq!(attempts.ttl_seconds(&policy, now), 300 + 900);
+    }
+
+    #[test]
+    fn test_unlock_success_resets_attempts() {
+        let policy = policy();
+        let now = 1_000;
+        let mut attempts = UnlockAttempts::default();
+        for _ in 0..3 {
+            attempts.record_failure(&policy, now);
+        }
+        attempts.record_failure(&policy, now + 60);
+
+        // Stored as JSON between requests
+        let json = serde_json::to_string(&attempts).unwrap();
+        assert_eq!(
+            serde_json::from_str::<UnlockAttempts>(&json).unwrap(),
+            attempts
+        );
+
+        // A successful unlock deletes the record: the count and the lockout escalation restart
+        attempts.reset();
+        assert_eq!(attempts, UnlockAttempts::default());
+        assert_eq!(attempts.record_failure(&policy, now + 60), None);
+        assert_eq!(attempts.record_failure(&policy, now + 60), None);
+        assert_eq!(attempts.record_failure(&policy, now + 60), Some(60));
+    }
+}
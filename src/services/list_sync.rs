@@ -0,0 +1,103 @@
+// Operator-managed lists kept in memory on every replica
+// Postgres holds the entries. A change reloads the local copy and bumps a Redis version key;
+// every replica polls the key and reloads from Postgres when it moves. The domain blocklist
+// and the IP denylist both work this way.
+
+use async_trait::async_trait;
+use redis::AsyncCommands;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+use crate::{
+    db::RedisPool, services::background_tasks::TASK_REGISTRY, utils::service_error::ServiceError,
+};
+
+#[async_trait]
+pub trait SyncedList: Send + Sync + 'static {
+    /// What the entries are, for log lines ("operator blocklist", "IP denylist")
+    const NAME: &'static str;
+
+    /// Redis key bumped on every change so other replicas know to reload
+    const VERSION_KEY: &'static str;
+
+    fn redis_pool(&self) -> &RedisPool;
+
+    /// Replace the in-memory copy with the entries in Postgres. Returns the number of
+    /// entries loaded.
+    async fn reload(&self) -> Result<usize, ServiceError>;
+
+    /// Current list version as seen in Redis
+    async fn current_version(&self) -> Option<i64> {
+        self.redis_pool()
+            .get::<i64>(Self::VERSION_KEY)
+            .await
+            .ok()
+            .flatten()
+    }
+
+    /// Apply a change on this replica and tell the others to reload
+    async fn publish_change(&self) {
+        if let Err(e) = self.reload().await {
+            error!("Failed to reload {} after change: {}", Self::NAME, e);
+        }
+
+        match self.redis_pool().get_connection().await {
+            Ok(mut conn) => {
+                if let Err(e) = conn.incr::<_, _, i64>(Self::VERSION_KEY, 1).await {
+                    warn!("Failed to bump {} version: {}", Self::NAME, e);
+                }
+            },
+            Err(e) => warn!("Failed to bump {} version: {}", Self::NAME, e),
+        }
+    }
+}
+
+/// Load the list once and then poll its version key every `interval_secs`, reloading on change
+pub fn spawn_list_refresh_task<L: SyncedList>(
+    task_name: &'static str,
+    interval_secs: u64,
+    list: L,
+) {
+    let list = Arc::new(list);
+    let interval_secs = interval_secs.max(1);
+
+    TASK_REGISTRY.spawn(task_name, move |reporter| {
+        let list = Arc::clone(&list);
+        async move {
+            let mut last_version = list.current_version().await;
+            match list.reload().await {
+                Ok(count) => {
+                    info!("Loaded {} {} entries", count, L::NAME);
+                    reporter.success();
+                },
+                Err(e) => {
+                    error!("Initial {} load failed: {}", L::NAME, e);
+                    reporter.failure(e);
+                },
+            }
+
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+
+                let version = list.current_version().await;
+                if version == last_version {
+                    continue;
+                }
+
+                match list.reload().await {
+                    Ok(count) => {
+                        info!("Reloaded {} ({} entries)", L::NAME, count);
+                        last_version = version;
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Failed to reload {}: {}", L::NAME, e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,359 @@
+// Per-host throttling for background metadata extraction
+// Bulk imports often point many links at one site; without this a slow host holds every
+// global extraction slot and links for other hosts wait behind it. Each registrable domain
+// gets its own small semaphore and a minimum gap between requests, and a circuit breaker
+// stops fetching from a host that keeps timing out until a cooldown has passed.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::warn;
+use utoipa::ToSchema;
+
+/// Concurrent fetches allowed per registrable domain
+const PER_HOST_CONCURRENCY: usize = 2;
+
+/// Minimum gap between the start of two fetches to the same registrable domain
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Consecutive timeouts that open a host's circuit breaker
+const BREAKER_TIMEOUT_THRESHOLD: u32 = 3;
+
+/// How long extraction is skipped for a host once its breaker opens
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// Idle hosts are dropped from the map once it grows past this many entries
+const MAX_TRACKED_HOSTS: usize = 1024;
+
+/// Second-level labels that are registered under like a TLD (`example.co.uk`)
+const SECOND_LEVEL_SUFFIXES: &[&str] = &["ac", "co", "com", "edu", "gov", "net", "org"];
+
+/// Process-wide throttle shared by every extraction task
+pub static METADATA_THROTTLE: Lazy<MetadataThrottle> = Lazy::new(MetadataThrottle::new);
+
+/// How an extraction attempt ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtractionOutcome {
+    Success,
+    /// The host did not answer in time; counts towards its circuit breaker
+    Timeout,
+    /// Any other error (bad status, unparseable page, blocked address)
+    Failed,
+    /// robots.txt disallows fetching the page
+    Disallowed,
+}
+
+/// Extraction was not attempted because the host's circuit breaker is open
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BreakerOpen {
+    pub retry_after: Duration,
+}
+
+/// Extraction outcome counters since process start
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct MetadataExtractionMetrics {
+    pub timestamp: String,
+    pub success: u64,
+    pub timeout: u64,
+    pub failed: u64,
+    /// Extractions skipped because the destination's robots.txt disallows them
+    pub disallowed_by_robots: u64,
+    /// Extractions skipped because the destination host's circuit breaker was open
+    pub skipped_by_breaker: u64,
+    /// Hosts currently being skipped
+    pub open_breakers: usize,
+    /// Hosts with throttle state in memory
+    pub tracked_hosts: usize,
+}
+
+/// Throttle state for one registrable domain
+struct HostState {
+    semaphore: Arc<Semaphore>,
+    /// Earliest time the next fetch may start
+    next_slot: Instant,
+    consecutive_timeouts: u32,
+    open_until: Option<Instant>,
+}
+
+impl HostState {
+    fn new(now: Instant) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(PER_HOST_CONCURRENCY)),
+            next_slot: now,
+            consecutive_timeouts: 0,
+            open_until: None,
+        }
+    }
+
+    fn breaker_remaining(&self, now: Instant) -> Option<Duration> {
+        self.open_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Reserve the next request slot; returns how long to wait before fetching
+    fn reserve_slot(&mut self, now: Instant) -> Duration {
+        let start = self.next_slot.max(now);
+        self.next_slot = start + MIN_REQUEST_INTERVAL;
+        start - now
+    }
+
+    /// Returns true when this outcome opened the breaker
+    fn record(&mut self, outcome: ExtractionOutcome, now: Instant) -> bool {
+        if outcome != ExtractionOutcome::Timeout {
+            // The host answered, even if with an error
+            self.consecutive_timeouts = 0;
+            return false;
+        }
+
+        self.consecutive_timeouts += 1;
+        if self.consecutive_timeouts < BREAKER_TIMEOUT_THRESHOLD {
+            return false;
+        }
+
+        self.consecutive_timeouts = 0;
+        self.open_until = Some(now + BREAKER_COOLDOWN);
+        true
+    }
+
+    fn is_idle(&self, now: Instant) -> bool {
+        self.semaphore.available_permits() == PER_HOST_CONCURRENCY
+            && self.next_slot <= now
+            && self.consecutive_timeouts == 0
+            && self.breaker_remaining(now).is_none()
+    }
+}
+
+#[derive(Default)]
+struct OutcomeCounters {
+    success: AtomicU64,
+    timeout: AtomicU64,
+    failed: AtomicU64,
+    disallowed_by_robots: AtomicU64,
+    skipped_by_breaker: AtomicU64,
+}
+
+pub struct MetadataThrottle {
+    hosts: Mutex<HashMap<String, HostState>>,
+    counters: OutcomeCounters,
+}
+
+impl Default for MetadataThrottle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetadataThrottle {
+    pub fn new() -> Self {
+        Self {
+            hosts: Mutex::new(HashMap::new()),
+            counters: OutcomeCounters::default(),
+        }
+    }
+
+    /// Wait for a fetch slot for `host`. The returned permit must be held for the whole
+    /// fetch; an open breaker is reported (and counted) instead of waiting.
+    pub async fn acquire(&self, host: &str) -> Result<OwnedSemaphorePermit, BreakerOpen> {
+        let semaphore = {
+            let mut hosts = self.lock_hosts();
+            let now = Instant::now();
+            let state = hosts
+                .entry(host.to_string())
+                .or_insert_with(|| HostState::new(now));
+            if let Some(retry_after) = state.breaker_remaining(now) {
+                self.counters
+                    .skipped_by_breaker
+                    .fetch_add(1, Ordering::Relaxed);
+                return Err(BreakerOpen { retry_after });
+            }
+            state.semaphore.clone()
+        };
+
+        // The semaphores are never closed, so acquiring cannot fail
+        let permit = semaphore
+            .acquire_owned()
+            .await
+            .expect("metadata host semaphore closed");
+
+        let wait = {
+            let mut hosts = self.lock_hosts();
+            let now = Instant::now();
+            hosts
+                .entry(host.to_string())
+                .or_insert_with(|| HostState::new(now))
+                .reserve_slot(now)
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+
+        Ok(permit)
+    }
+
+    /// Record how an extraction for `host` ended
+    pub fn record(&self, host: &str, outcome: ExtractionOutcome) {
+        let counter = match outcome {
+            ExtractionOutcome::Success => &self.counters.success,
+            ExtractionOutcome::Timeout => &self.counters.timeout,
+            ExtractionOutcome::Failed => &self.counters.failed,
+            ExtractionOutcome::Disallowed => &self.counters.disallowed_by_robots,
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        let mut hosts = self.lock_hosts();
+        let now = Instant::now();
+        if let Some(state) = hosts.get_mut(host) {
+            if state.record(outcome, now) {
+                warn!(
+                    "Metadata extraction for {} timed out {} times in a row; skipping it for {}s",
+                    host,
+                    BREAKER_TIMEOUT_THRESHOLD,
+                    BREAKER_COOLDOWN.as_secs()
+                );
+            }
+        }
+
+        if hosts.len() > MAX_TRACKED_HOSTS {
+            hosts.retain(|_, state| !state.is_idle(now));
+        }
+    }
+
+    pub fn metrics(&self) -> MetadataExtractionMetrics {
+        let hosts = self.lock_hosts();
+        let now = Instant::now();
+        MetadataExtractionMetrics {
+            timestamp: chrono::Utc::now().to_rfc3339(),
+            success: self.counters.success.load(Ordering::Relaxed),
+            timeout: self.counters.timeout.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            disallowed_by_robots: self.counters.disallowed_by_robots.load(Ordering::Relaxed),
+            skipped_by_breaker: self.counters.skipped_by_breaker.load(Ordering::Relaxed),
+            open_breakers: hosts
+                .values()
+                .filter(|state| state.breaker_remaining(now).is_some())
+                .count(),
+            tracked_hosts: hosts.len(),
+        }
+    }
+
+    fn lock_hosts(&self) -> std::sync::MutexGuard<'_, HashMap<String, HostState>> {
+        // State is only ever updated in place, so a poisoned map is still consistent
+        self.hosts.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Throttle key for a destination URL: its registrable domain, so `a.example.com` and
+/// `b.example.com` share one budget. `None` when the URL has no host.
+pub fn throttle_key(url: &str) -> Option<String> {
+    let parsed = url::Url::parse(url).ok()?;
+    parsed.host_str().map(registrable_domain)
+}
+
+/// Approximate registrable domain (eTLD+1) without a public suffix list: the last two
+/// labels, or three under common second-level suffixes such as `co.uk` and `com.au`
+pub fn registrable_domain(host: &str) -> String {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok() {
+        return host;
+    }
+
+    let labels: Vec<&str> = host.split('.').collect();
+    if labels.len() <= 2 {
+        return host;
+    }
+
+    let tld = labels[labels.len() - 1];
+    let second = labels[labels.len() - 2];
+    let keep = if tld.len() == 2 && SECOND_LEVEL_SUFFIXES.contains(&second) {
+        3
+    } else {
+        2
+    };
+    labels[labels.len() - keep..].join(".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registrable_domain() {
+        assert_eq!(registrable_domain("example.com"), "example.com");
+        assert_eq!(registrable_domain("a.b.Example.COM."), "example.com");
+        assert_eq!(registrable_domain("shop.example.co.uk"), "example.co.uk");
+        assert_eq!(registrable_domain("news.example.com.au"), "example.com.au");
+        // A two-letter TLD alone doesn't imply a second-level suffix
+        assert_eq!(registrable_domain("blog.example.io"), "example.io");
+        assert_eq!(registrable_domain("localhost"), "localhost");
+        assert_eq!(registrable_domain("203.0.113.7"), "203.0.113.7");
+
+        assert_eq!(
+            throttle_key("https://cdn.images.example.org/a.png").as_deref(),
+            Some("example.org")
+        );
+        assert_eq!(
+            throttle_key("http://[2001:db8::1]:8080/").as_deref(),
+            Some("[2001:db8::1]")
+        );
+        assert_eq!(throttle_key("not a url"), None);
+    }
+
+    #[test]
+    fn test_host_requests_are_spaced() {
+        let now = Instant::now();
+        let mut state = HostState::new(now);
+        assert_eq!(state.reserve_slot(now), Duration::ZERO);
+        assert_eq!(state.reserve_slot(now), MIN_REQUEST_INTERVAL);
+        assert_eq!(state.reserve_slot(now), MIN_REQUEST_INTERVAL * 2);
+
+        // Slots that have already passed are not waited for
+        let later = now + MIN_REQUEST_INTERVAL * 10;
+        assert_eq!(state.reserve_slot(later), Duration::ZERO);
+    }
+
+    #[test]
+    fn test_breaker_opens_after_consecutive_timeouts() {
+        let now = Instant::now();
+        let mut state = HostState::new(now);
+
+        // Any answer from the host resets the streak
+        assert!(!state.record(ExtractionOutcome::Timeout, now));
+        assert!(!state.record(ExtractionOutcome::Timeout, now));
+        assert!(!state.record(ExtractionOutcome::Failed, now));
+        assert!(!state.record(ExtractionOutcome::Timeout, now));
+        assert!(!state.record(ExtractionOutcome::Timeout, now));
+        assert_eq!(state.breaker_remaining(now), None);
+
+        assert!(state.record(ExtractionOutcome::Timeout, now));
+        assert_eq!(state.breaker_remaining(now), Some(BREAKER_COOLDOWN));
+        assert!(!state.is_idle(now));
+
+        let after_cooldown = now + BREAKER_COOLDOWN;
+        assert_eq!(state.breaker_remaining(after_cooldown), None);
+        assert!(state.is_idle(after_cooldown));
+    }
+
+    // Paused clock: the spacing sleeps between acquires return immediately
+    #[tokio::test(start_paused = true)]
+    async fn test_open_breaker_skips_and_counts() {
+        let throttle = MetadataThrottle::new();
+        for _ in 0..BREAKER_TIMEOUT_THRESHOLD {
+            drop(throttle.acquire("slow.example").await.unwrap());
+            throttle.record("slow.example", ExtractionOutcome::Timeout);
+        }
+
+        let skipped = throttle.acquire("slow.example").await.unwrap_err();
+        assert!(skipped.retry_after <= BREAKER_COOLDOWN);
+
+        let metrics = throttle.metrics();
+        assert_eq!(metrics.timeout, BREAKER_TIMEOUT_THRESHOLD as u64);
+        assert_eq!(metrics.skipped_by_breaker, 1);
+        assert_eq!(metrics.open_breakers, 1);
+    }
+}
@@ -1,28 +1,79 @@
 // Services module for QCK Core Backend
 // Business logic layer for the application
 
+pub mod account_export;
+pub mod account_unlock;
 pub mod analytics;
+pub mod api_usage;
+pub mod audit_log;
 pub mod background_tasks;
+pub mod blocklist;
+pub mod cache_warmer;
+pub mod click_dedup;
+pub mod click_counter;
 pub mod click_tracking;
 pub mod clickhouse_analytics;
+pub mod conversion_tracking;
 pub mod email; // Needed for password reset
+pub mod health_probe;
+pub mod idempotency;
+pub mod instance_stats;
+pub mod ip_denylist;
 pub mod jwt;
 pub mod link;
+pub mod link_pause;
+pub mod link_revision;
+pub mod link_unlock;
+pub mod list_sync;
+pub mod metadata_throttle;
+pub mod notification_preferences;
+pub mod onboarding;
+pub mod og_image;
+pub mod organization;
+pub mod page;
 pub mod password_reset;
+pub mod pending_counter;
+pub mod quarantine;
 pub mod rate_limit;
+pub mod refresh_token_cleanup;
+pub mod robots;
+pub mod security_alerts;
+pub mod security_telemetry;
+pub mod self_test;
+pub mod service_auth;
 pub mod short_code;
+pub mod usage_summary;
+pub mod webhook;
 
 // Re-export commonly used services
 pub use analytics::{
-    AnalyticsError, MonitoringStats, RateLimitAnalytics, RateLimitEvent, RateLimitMetrics,
+    AnalyticsError, MetricsBackend, MonitoringStats, RateLimitAnalytics, RateLimitEvent,
+    RateLimitMetrics, RateLimitOffender,
 };
+pub use api_usage::ApiUsageService;
+pub use audit_log::AuditLogService;
 pub use background_tasks::initialize_background_tasks;
+pub use blocklist::BlocklistService;
 pub use clickhouse_analytics::{create_clickhouse_analytics_service, ClickHouseAnalyticsService};
 pub use email::{EmailError, EmailService}; // For password reset emails
+pub use idempotency::IdempotencyService;
+pub use ip_denylist::IpDenylistService;
 pub use jwt::{JwtConfig, JwtError, JwtService};
 pub use link::LinkService;
+pub use link_pause::LinkPauseService;
+pub use link_revision::LinkRevisionService;
+pub use link_unlock::LinkUnlockService;
+pub use notification_preferences::NotificationPreferencesService;
+pub use onboarding::OnboardingService;
+pub use og_image::OgImageService;
+pub use organization::OrganizationService;
+pub use page::PageService;
 pub use password_reset::{PasswordResetService, PasswordResetTokenInfo};
+pub use quarantine::QuarantineService;
 pub use rate_limit::{
     RateLimitConfig, RateLimitError, RateLimitResult, RateLimitService,
 };
+pub use security_alerts::SecurityAlertService;
 pub use short_code::{GenerationStats, ShortCodeError, ShortCodeGenerator};
+pub use usage_summary::UsageSummaryService;
+pub use webhook::{WebhookError, WebhookSender};
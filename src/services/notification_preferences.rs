@@ -0,0 +1,100 @@
+// Email notification preferences
+// Background tasks check User::wants_notification before sending optional emails
+
+use serde_json::{Map, Value};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::{
+        notification_preferences::NotificationPreferences,
+        user::{User, UserError, UserUpdate},
+    },
+    utils::service_error::ServiceError,
+};
+
+pub struct NotificationPreferencesService {
+    diesel_pool: DieselPool,
+}
+
+impl NotificationPreferencesService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+        }
+    }
+
+    pub async fn get(&self, user_id: Uuid) -> Result<NotificationPreferences, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let user = User::find_by_id(&mut conn, user_id)
+            .await
+            .map_err(map_user_error)?;
+
+        Ok(user.notification_preferences_typed())
+    }
+
+    /// Apply a partial update; unknown keys are rejected with the key named
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        changes: &Map<String, Value>,
+    ) -> Result<NotificationPreferences, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let user = User::find_by_id(&mut conn, user_id)
+            .await
+            .map_err(map_user_error)?;
+
+        let mut preferences = user.notification_preferences_typed();
+        preferences
+            .apply(changes)
+            .map_err(ServiceError::ValidationError)?;
+
+        let update = UserUpdate {
+            email: None,
+            password_hash: None,
+            email_verified: None,
+            email_verified_at: None,
+            subscription_tier: None,
+            is_active: None,
+            full_name: None,
+            company_name: None,
+            onboarding_status: None,
+            default_tracking_mode: None,
+            // The digest task scans this indexed column, so it mirrors monthly_summary
+            monthly_digest_enabled: Some(preferences.monthly_summary),
+            notification_preferences: Some(preferences.to_value()),
+            timezone: None,
+            strip_query_params: None,
+        };
+        let updated = User::update(&mut conn, user.id, update)
+            .await
+            .map_err(map_user_error)?;
+
+        Ok(updated.notification_preferences_typed())
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+fn map_user_error(e: UserError) -> ServiceError {
+    match e {
+        UserError::NotFound => ServiceError::Unauthorized,
+        e => ServiceError::DatabaseError(e.to_string()),
+    }
+}
@@ -0,0 +1,402 @@
+// Open Graph images for links
+// A 1200x630 card with the link's title, destination domain, owner branding and a QR code for
+// the short URL. The card is composed as SVG and rasterized with resvg for PNG. Rendered images
+// are cached in Redis, or written to OG_IMAGE_DIR when it is set; either way a link edit makes
+// the next request render a fresh card.
+
+use base64::prelude::*;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use handlebars::Handlebars;
+use once_cell::sync::Lazy;
+use qrcode::{Color, QrCode};
+use resvg::{tiny_skia, usvg};
+use serde_json::json;
+use std::{path::PathBuf, sync::Arc, time::SystemTime};
+use tracing::{error, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::link::{Link, OgImageFormat},
+    schema::{links, organizations, users},
+    utils::service_error::ServiceError,
+};
+
+pub const OG_IMAGE_WIDTH: u32 = 1200;
+pub const OG_IMAGE_HEIGHT: u32 = 630;
+
+/// Redis key prefix, followed by the link ID, its last update and the file extension
+const CACHE_KEY_PREFIX: &str = "og:image:";
+
+/// Title wrapping at the card's 56px title size, leaving room for the QR code
+const TITLE_LINE_CHARS: usize = 22;
+const TITLE_MAX_LINES: usize = 3;
+const TITLE_FIRST_LINE_Y: usize = 230;
+const TITLE_LINE_HEIGHT: usize = 70;
+
+/// Longest branding and short URL shown before they are cut off
+const BRANDING_MAX_CHARS: usize = 40;
+const SHORT_URL_MAX_CHARS: usize = 22;
+
+/// Side of the QR code inside its white box, in pixels
+const QR_SIZE: f64 = 250.0;
+
+static CARD_TEMPLATES: Lazy<Handlebars<'static>> = Lazy::new(|| {
+    let mut templates = Handlebars::new();
+    templates
+        .register_template_string("card", include_str!("../templates/og/card.svg"))
+        .expect("OG card template is valid");
+    templates
+});
+
+/// Fonts for the PNG text; the container image must ship at least one (e.g. DejaVu)
+static FONT_DB: Lazy<Arc<usvg::fontdb::Database>> = Lazy::new(|| {
+    let mut fonts = usvg::fontdb::Database::new();
+    fonts.load_system_fonts();
+    if fonts.is_empty() {
+        warn!("No system fonts found; OG image PNGs will be rendered without text");
+    }
+    Arc::new(fonts)
+});
+
+/// What goes on a card
+#[derive(Debug, Clone)]
+pub struct OgCard {
+    pub title: String,
+    pub domain: String,
+    /// Full short URL as encoded in the QR code; the card shows it without its scheme
+    pub short_url: String,
+    /// Organization name, or the owner's company name for personal links
+    pub branding: Option<String>,
+}
+
+pub struct OgImageService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+    base_url: String,
+    image_dir: String,
+    cache_ttl: u64,
+}
+
+impl OgImageService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            redis_pool: state.redis_pool.clone(),
+            base_url: state.config.short_link_base_url.clone(),
+            image_dir: state.config.og_image_dir.clone(),
+            cache_ttl: state.config.og_image_cache_ttl,
+        }
+    }
+
+    /// Card for a link, from cache when the link hasn't changed since it was rendered.
+    /// Deleted and quarantined links have no card.
+    pub async fn image(
+        &self,
+        link_id: Uuid,
+        format: OgImageFormat,
+    ) -> Result<Vec<u8>, ServiceError> {
+        let mut conn = self
+            .diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+
+        let link: Link = links::table
+            .filter(links::id.eq(link_id))
+            .filter(links::deleted_at.is_null())
+            .select(Link::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?
+            .ok_or(ServiceError::NotFound)?;
+        if link.quarantined {
+            return Err(ServiceError::NotFound);
+        }
+
+        if let Some(image) = self.cached(&link, format).await {
+            return Ok(image);
+        }
+
+        let branding: Option<String> = match link.organization_id {
+            Some(organization_id) => organizations::table
+                .filter(organizations::id.eq(organization_id))
+                .select(organizations::name)
+                .first(&mut conn)
+                .await
+                .optional()?,
+            None => users::table
+                .filter(users::id.eq(link.user_id))
+                .select(users::company_name)
+                .first::<Option<String>>(&mut conn)
+                .await
+                .optional()?
+                .flatten(),
+        };
+        drop(conn);
+
+        let card = OgCard::new(&link, &self.base_url, branding);
+        let svg = render_svg(&card).map_err(|e| {
+            error!("Failed to compose OG image for link {}: {}", link.id, e);
+            ServiceError::InternalError
+        })?;
+        let image = match format {
+            OgImageFormat::Svg => svg.into_bytes(),
+            OgImageFormat::Png => tokio::task::spawn_blocking(move || render_png(&svg))
+                .await
+                .map_err(|_| ServiceError::InternalError)?
+                .map_err(|e| {
+                    error!("Failed to rasterize OG image for link {}: {}", link.id, e);
+                    ServiceError::InternalError
+                })?,
+        };
+
+        self.store(&link, format, &image).await;
+        Ok(image)
+    }
+
+    // =============================================================================
+    // CACHE
+    // =============================================================================
+
+    async fn cached(&self, link: &Link, format: OgImageFormat) -> Option<Vec<u8>> {
+        if !self.image_dir.is_empty() {
+            let path = self.file_path(link, format);
+            let modified = tokio::fs::metadata(&path).await.ok()?.modified().ok()?;
+            let fresh = SystemTime::from(link.updated_at) <= modified
+                && modified
+                    .elapsed()
+                    .is_ok_and(|age| age.as_secs() < self.cache_ttl);
+            if !fresh {
+                return None;
+            }
+            return tokio::fs::read(&path).await.ok();
+        }
+
+        match self
+            .redis_pool
+            .get::<String>(&cache_key(link, format))
+            .await
+        {
+            Ok(Some(encoded)) => BASE64_STANDARD.decode(encoded).ok(),
+            Ok(None) => None,
+            Err(e) => {
+                warn!("OG image cache read failed for link {}: {}", link.id, e);
+                None
+            },
+        }
+    }
+
+    async fn store(&self, link: &Link, format: OgImageFormat, image: &[u8]) {
+        if !self.image_dir.is_empty() {
+            let path = self.file_path(link, format);
+            let written = match tokio::fs::create_dir_all(&self.image_dir).await {
+                Ok(()) => tokio::fs::write(&path, image).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = written {
+                warn!("Failed to write OG image {}: {}", path.display(), e);
+            }
+            return;
+        }
+
+        if let Err(e) = self
+            .redis_pool
+            .set_with_expiry(
+                &cache_key(link, format),
+                BASE64_STANDARD.encode(image),
+                self.cache_ttl as usize,
+            )
+            .await
+        {
+            warn!("OG image cache write failed for link {}: {}", link.id, e);
+        }
+    }
+
+    /// One file per link and format, overwritten when the link changes
+    fn file_path(&self, link: &Link, format: OgImageFormat) -> PathBuf {
+        PathBuf::from(&self.image_dir).join(format!("{}.{}", link.id, format.extension()))
+    }
+}
+
+fn cache_key(link: &Link, format: OgImageFormat) -> String {
+    format!(
+        "{}{}:{}.{}",
+        CACHE_KEY_PREFIX,
+        link.id,
+        link.updated_at.timestamp(),
+        format.extension()
+    )
+}
+
+impl OgCard {
+    pub fn new(link: &Link, base_url: &str, branding: Option<String>) -> Self {
+        let domain = link.display_host().0;
+        let title = link
+            .title
+            .clone()
+            .filter(|title| !title.trim().is_empty())
+            .unwrap_or_else(|| domain.clone());
+        Self {
+            title,
+            domain,
+            short_url: format!("{}/{}", base_url, link.short_code),
+            branding: branding.filter(|b| !b.trim().is_empty()),
+        }
+    }
+}
+
+/// Compose the card as an SVG document
+pub fn render_svg(card: &OgCard) -> Result<String, handlebars::RenderError> {
+    let title_lines: Vec<serde_json::Value> = wrap_title(&card.title)
+        .into_iter()
+        .enumerate()
+        .map(|(i, text)| json!({ "y": TITLE_FIRST_LINE_Y + i * TITLE_LINE_HEIGHT, "text": text }))
+        .collect();
+
+    // The short URL always encodes; QR codes hold far more than a URL's worth of bytes
+    let qr = QrCode::new(card.short_url.as_bytes()).expect("short URL fits in a QR code");
+    let width = qr.width();
+    let qr_path: String = qr
+        .to_colors()
+        .iter()
+        .enumerate()
+        .filter(|(_, color)| **color == Color::Dark)
+        .map(|(i, _)| format!("M{} {}h1v1h-1z", i % width, i / width))
+        .collect();
+
+    CARD_TEMPLATES.render(
+        "card",
+        &json!({
+            "branding": card.branding.as_deref().map(|b| truncate(b, BRANDING_MAX_CHARS)),
+            "title_lines": title_lines,
+            "domain": truncate(&card.domain, TITLE_LINE_CHARS * 2),
+            "short_url": truncate(display_url(&card.short_url), SHORT_URL_MAX_CHARS),
+            "qr_path": qr_path,
+            "qr_scale": QR_SIZE / width as f64,
+        }),
+    )
+}
+
+/// A short URL as printed on the card, without its scheme
+fn display_url(short_url: &str) -> &str {
+    short_url
+        .strip_prefix("https://")
+        .or_else(|| short_url.strip_prefix("http://"))
+        .unwrap_or(short_url)
+}
+
+/// Rasterize a card to PNG
+pub fn render_png(svg: &str) -> Result<Vec<u8>, String> {
+    let options = usvg::Options {
+        fontdb: FONT_DB.clone(),
+        ..Default::default()
+    };
+    let tree = usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+    let mut pixmap =
+        tiny_skia::Pixmap::new(OG_IMAGE_WIDTH, OG_IMAGE_HEIGHT).ok_or("invalid image size")?;
+    resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+/// Word-wrap the title onto at most three lines, ending with an ellipsis when it doesn't fit
+fn wrap_title(title: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let words = title.split_whitespace().flat_map(|word| {
+        // Words wider than a line are broken up
+        let chars: Vec<char> = word.chars().collect();
+        chars
+            .chunks(TITLE_LINE_CHARS)
+            .map(|chunk| chunk.iter().collect::<String>())
+            .collect::<Vec<_>>()
+    });
+
+    let mut overflow = false;
+    for word in words {
+        let len = current.chars().count();
+        if len == 0 {
+            current = word;
+        } else if len + 1 + word.chars().count() <= TITLE_LINE_CHARS {
+            current.push(' ');
+            current.push_str(&word);
+        } else {
+            lines.push(std::mem::replace(&mut current, word));
+            if lines.len() == TITLE_MAX_LINES {
+                overflow = true;
+                break;
+            }
+        }
+    }
+    if !overflow && !current.is_empty() {
+        lines.push(current);
+    }
+
+    if overflow {
+        if let Some(last) = lines.last_mut() {
+            *last = format!("{}…", truncate(last, TITLE_LINE_CHARS - 1).trim_end());
+        }
+    }
+    lines
+}
+
+/// Cut text to `max` characters, marking the cut with an ellipsis
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max.saturating_sub(1)).collect();
+    format!("{}…", kept)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(title: &str) -> OgCard {
+        OgCard {
+            title: title.to_string(),
+            domain: "example.com".to_string(),
+            short_url: "https://qck.sh/abc123".to_string(),
+            branding: Some("Acme & Co".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_wrap_title() {
+        assert_eq!(wrap_title("Short title"), ["Short title"]);
+        assert_eq!(
+            wrap_title("The quick brown fox jumps over the lazy dog"),
+            ["The quick brown fox", "jumps over the lazy", "dog"]
+        );
+
+        let long = wrap_title(&"word ".repeat(30));
+        assert_eq!(long.len(), TITLE_MAX_LINES);
+        assert!(long[2].ends_with('…'));
+        assert!(long
+            .iter()
+            .all(|line| line.chars().count() <= TITLE_LINE_CHARS));
+
+        let unbroken = wrap_title(&"x".repeat(30));
+        assert_eq!(unbroken, ["x".repeat(22), "x".repeat(8)]);
+    }
+
+    #[test]
+    fn test_render_svg_escapes_text() {
+        let svg = render_svg(&card("<script> & friends")).unwrap();
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains("&lt;script&gt; &amp; friends"));
+        assert!(svg.contains("Acme &amp; Co"));
+        assert!(svg.contains(">qck.sh/abc123<"));
+        assert!(!svg.contains("<script>"));
+    }
+
+    #[test]
+    fn test_render_png() {
+        let svg = render_svg(&card("Example")).unwrap();
+        let png = render_png(&svg).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
@@ -0,0 +1,121 @@
+// Onboarding progress (Registered -> Completed)
+// OSS deployments skip onboarding entirely, so their users are always reported as Completed
+
+use tracing::info;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::user::{OnboardingStatus, OnboardingStatusResponse, User, UserError, UserUpdate},
+    utils::service_error::ServiceError,
+};
+
+pub struct OnboardingService {
+    diesel_pool: DieselPool,
+    is_oss_deployment: bool,
+}
+
+impl OnboardingService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            is_oss_deployment: state.config.is_oss_deployment,
+        }
+    }
+
+    /// Current onboarding status of a user
+    pub async fn status(&self, user_id: Uuid) -> Result<OnboardingStatusResponse, ServiceError> {
+        let user = self.load_user(user_id).await?;
+        Ok(user
+            .effective_onboarding_status(self.is_oss_deployment)
+            .into())
+    }
+
+    /// Move a user to `next`, which must be their current status or the one after it
+    pub async fn advance(
+        &self,
+        user_id: Uuid,
+        next: OnboardingStatus,
+    ) -> Result<OnboardingStatusResponse, ServiceError> {
+        let user = self.load_user(user_id).await?;
+        let current = user.effective_onboarding_status(self.is_oss_deployment);
+
+        if !current.can_transition_to(next) {
+            return Err(ServiceError::ValidationError(format!(
+                "Cannot move onboarding from '{}' to '{}'",
+                current.as_str(),
+                next.as_str()
+            )));
+        }
+
+        // Also rewrites legacy or invalid stored values to the canonical one
+        if user.onboarding_status != next.as_str() {
+            let mut conn = self.get_conn().await?;
+            let update = UserUpdate {
+                email: None,
+                password_hash: None,
+                email_verified: None,
+                email_verified_at: None,
+                subscription_tier: None,
+                is_active: None,
+                full_name: None,
+                company_name: None,
+                onboarding_status: Some(next.as_str().to_string()),
+                default_tracking_mode: None,
+                monthly_digest_enabled: None,
+                notification_preferences: None,
+                timezone: None,
+                strip_query_params: None,
+            };
+            User::update(&mut conn, user.id, update)
+                .await
+                .map_err(map_user_error)?;
+
+            info!(
+                "User {} onboarding moved from '{}' to '{}'",
+                user.id,
+                user.onboarding_status,
+                next.as_str()
+            );
+        }
+
+        Ok(next.into())
+    }
+
+    /// Finish onboarding; a no-op for users who already completed it
+    pub async fn complete(&self, user_id: Uuid) -> Result<OnboardingStatusResponse, ServiceError> {
+        self.advance(user_id, OnboardingStatus::Completed).await
+    }
+
+    async fn load_user(&self, user_id: Uuid) -> Result<User, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        User::find_by_id(&mut conn, user_id)
+            .await
+            .map_err(map_user_error)
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+fn map_user_error(e: UserError) -> ServiceError {
+    match e {
+        UserError::NotFound => ServiceError::Unauthorized,
+        e => ServiceError::DatabaseError(e.to_string()),
+    }
+}
@@ -0,0 +1,501 @@
+// Organizations (team accounts)
+// Members share ownership of organization links; owners and admins invite new members by email
+
+use base64::prelude::*;
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use tracing::{error, info};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    db::DieselPool,
+    models::{
+        organization::{
+            CreateOrganizationRequest, InviteMemberRequest, NewOrganization,
+            NewOrganizationInvitation, NewOrganizationMember, OrgAction, OrgRole, Organization,
+            OrganizationInvitation, OrganizationInvitationResponse, OrganizationMember,
+            OrganizationMemberResponse, OrganizationResponse,
+        },
+        user::User,
+    },
+    schema::{organization_invitations, organization_members, organizations, users},
+    services::email::EmailService,
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        service_error::ServiceError,
+    },
+};
+
+/// Days an invitation stays valid
+const INVITATION_EXPIRY_DAYS: i64 = 7;
+
+pub struct OrganizationService {
+    diesel_pool: DieselPool,
+    email_service: Arc<EmailService>,
+}
+
+impl OrganizationService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            email_service: state.email_service.clone(),
+        }
+    }
+
+    /// Create an organization; the creator becomes its owner
+    pub async fn create(
+        &self,
+        user: &User,
+        request: CreateOrganizationRequest,
+    ) -> Result<OrganizationResponse, ServiceError> {
+        request.validate()?;
+        let mut conn = self.get_conn().await?;
+
+        let new_organization = NewOrganization {
+            name: request.name.trim().to_string(),
+            created_by: user.id,
+        };
+        let user_id = user.id;
+
+        let organization = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                Box::pin(async move {
+                    let organization = diesel::insert_into(organizations::table)
+                        .values(&new_organization)
+                        .returning(Organization::as_returning())
+                        .get_result(tx)
+                        .await?;
+
+                    diesel::insert_into(organization_members::table)
+                        .values(&NewOrganizationMember {
+                            organization_id: organization.id,
+                            user_id,
+                            role: OrgRole::Owner.as_str().to_string(),
+                        })
+                        .execute(tx)
+                        .await?;
+
+                    Ok(organization)
+                })
+            })
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::OrganizationCreated,
+            user.id,
+            "organization",
+            Some(organization.id.to_string()),
+            Some(format!("Created organization {}", organization.name)),
+        )
+        .await;
+
+        info!(
+            "Organization {} created by user {}",
+            organization.id, user.id
+        );
+        Ok(OrganizationResponse::new(organization, OrgRole::Owner))
+    }
+
+    /// Organizations the user belongs to, with the user's role in each
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<OrganizationResponse>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let rows: Vec<(Organization, String)> = organizations::table
+            .inner_join(organization_members::table)
+            .filter(organization_members::user_id.eq(user_id))
+            .order(organizations::created_at.asc())
+            .select((Organization::as_select(), organization_members::role))
+            .load(&mut conn)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(organization, role)| {
+                OrganizationResponse::new(organization, OrgRole::from(role.as_str()))
+            })
+            .collect())
+    }
+
+    /// The user's role in an organization, if a member
+    pub async fn member_role(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Option<OrgRole>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        Ok(OrganizationMember::find_role(&mut conn, organization_id, user_id).await?)
+    }
+
+    /// Members of an organization; only visible to its members
+    pub async fn list_members(
+        &self,
+        organization_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Vec<OrganizationMemberResponse>, ServiceError> {
+        authorize(
+            self.member_role(organization_id, user_id).await?,
+            OrgAction::ListMembers,
+        )?;
+
+        let mut conn = self.get_conn().await?;
+
+        let rows: Vec<(Uuid, String, String, String, chrono::DateTime<Utc>)> =
+            organization_members::table
+                .inner_join(users::table)
+                .filter(organization_members::organization_id.eq(organization_id))
+                .order(organization_members::created_at.asc())
+                .select((
+                    users::id,
+                    users::email,
+                    users::full_name,
+                    organization_members::role,
+                    organization_members::created_at,
+                ))
+                .load(&mut conn)
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(user_id, email, full_name, role, joined_at)| OrganizationMemberResponse {
+                    user_id,
+                    email,
+                    full_name,
+                    role: OrgRole::from(role.as_str()),
+                    joined_at,
+                },
+            )
+            .collect())
+    }
+
+    /// Invite someone by email. Requires owner or admin; replaces any pending
+    /// invitation for the same address.
+    pub async fn invite(
+        &self,
+        inviter: &User,
+        organization_id: Uuid,
+        request: InviteMemberRequest,
+    ) -> Result<OrganizationInvitationResponse, ServiceError> {
+        request.validate()?;
+        if request.role == OrgRole::Owner {
+            return Err(ServiceError::ValidationError(
+                "Invitations can only grant the admin or member role".to_string(),
+            ));
+        }
+
+        authorize(
+            self.member_role(organization_id, inviter.id).await?,
+            OrgAction::InviteMember,
+        )?;
+
+        let email = request.email.trim().to_lowercase();
+        let mut conn = self.get_conn().await?;
+
+        let organization = organizations::table
+            .find(organization_id)
+            .select(Organization::as_select())
+            .first(&mut conn)
+            .await?;
+
+        let already_member = diesel::select(diesel::dsl::exists(
+            organization_members::table
+                .inner_join(users::table)
+                .filter(organization_members::organization_id.eq(organization_id))
+                .filter(users::email.eq(&email)),
+        ))
+        .get_result::<bool>(&mut conn)
+        .await?;
+        if already_member {
+            return Err(ServiceError::Conflict(format!(
+                "{} is already a member of this organization",
+                email
+            )));
+        }
+
+        // Re-inviting supersedes the previous token
+        diesel::delete(
+            organization_invitations::table
+                .filter(organization_invitations::organization_id.eq(organization_id))
+                .filter(organization_invitations::email.eq(&email))
+                .filter(organization_invitations::accepted_at.is_null()),
+        )
+        .execute(&mut conn)
+        .await?;
+
+        let (token, token_hash) = generate_invitation_token();
+        let invitation = diesel::insert_into(organization_invitations::table)
+            .values(&NewOrganizationInvitation {
+                organization_id,
+                email: email.clone(),
+                role: request.role.as_str().to_string(),
+                token_hash,
+                invited_by: inviter.id,
+                expires_at: Utc::now() + Duration::days(INVITATION_EXPIRY_DAYS),
+            })
+            .returning(OrganizationInvitation::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        if let Err(e) = self
+            .email_service
+            .send_organization_invitation(
+                &email,
+                &inviter.full_name,
+                &organization.name,
+                request.role.as_str(),
+                &token,
+                INVITATION_EXPIRY_DAYS,
+            )
+            .await
+        {
+            // The token only exists in the email, so an unsent invitation is useless
+            error!("Failed to send invitation {}: {}", invitation.id, e);
+            diesel::delete(organization_invitations::table.find(invitation.id))
+                .execute(&mut conn)
+                .await?;
+            return Err(ServiceError::InternalError);
+        }
+
+        AuditLogger::log_resource_action(
+            AuditAction::OrganizationMemberInvited,
+            inviter.id,
+            "organization",
+            Some(organization_id.to_string()),
+            Some(format!("Invited {} as {}", email, request.role.as_str())),
+        )
+        .await;
+
+        Ok(OrganizationInvitationResponse::from(invitation))
+    }
+
+    /// Join an organization with an emailed token. The invitation must be
+    /// addressed to the user's email, unexpired and not yet used.
+    pub async fn accept_invitation(
+        &self,
+        user: &User,
+        token: &str,
+    ) -> Result<OrganizationResponse, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let invitation = organization_invitations::table
+            .filter(organization_invitations::token_hash.eq(hash_invitation_token(token)))
+            .select(OrganizationInvitation::as_select())
+            .first(&mut conn)
+            .await?;
+
+        if invitation.accepted_at.is_some() {
+            return Err(ServiceError::Conflict(
+                "Invitation has already been accepted".to_string(),
+            ));
+        }
+        if invitation.expires_at <= Utc::now() {
+            return Err(ServiceError::ValidationError(
+                "Invitation has expired".to_string(),
+            ));
+        }
+        if !invitation.email.eq_ignore_ascii_case(user.email.trim()) {
+            return Err(ServiceError::Forbidden(
+                "Invitation was sent to a different email address".to_string(),
+            ));
+        }
+
+        let invitation_id = invitation.id;
+        let new_member = NewOrganizationMember {
+            organization_id: invitation.organization_id,
+            user_id: user.id,
+            role: invitation.role.clone(),
+        };
+
+        let claimed = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                Box::pin(async move {
+                    // Conditional update so a token can only be redeemed once
+                    let claimed = diesel::update(
+                        organization_invitations::table
+                            .find(invitation_id)
+                            .filter(organization_invitations::accepted_at.is_null()),
+                    )
+                    .set(organization_invitations::accepted_at.eq(Some(Utc::now())))
+                    .execute(tx)
+                    .await?;
+
+                    if claimed == 1 {
+                        // Existing members keep their current role
+                        diesel::insert_into(organization_members::table)
+                            .values(&new_member)
+                            .on_conflict_do_nothing()
+                            .execute(tx)
+                            .await?;
+                    }
+
+                    Ok(claimed == 1)
+                })
+            })
+            .await?;
+
+        if !claimed {
+            return Err(ServiceError::Conflict(
+                "Invitation has already been accepted".to_string(),
+            ));
+        }
+
+        let organization = organizations::table
+            .find(invitation.organization_id)
+            .select(Organization::as_select())
+            .first(&mut conn)
+            .await?;
+        let role = self
+            .member_role(organization.id, user.id)
+            .await?
+            .unwrap_or_else(|| OrgRole::from(invitation.role.as_str()));
+
+        AuditLogger::log_resource_action(
+            AuditAction::OrganizationMemberJoined,
+            user.id,
+            "organization",
+            Some(organization.id.to_string()),
+            Some(format!("Joined as {}", role.as_str())),
+        )
+        .await;
+
+        Ok(OrganizationResponse::new(organization, role))
+    }
+
+    /// Remove a member. Requires owner or admin; the owner cannot be removed.
+    /// Links the member created stay with the organization.
+    pub async fn remove_member(
+        &self,
+        actor_id: Uuid,
+        organization_id: Uuid,
+        member_id: Uuid,
+    ) -> Result<(), ServiceError> {
+        authorize(
+            self.member_role(organization_id, actor_id).await?,
+            OrgAction::RemoveMember,
+        )?;
+
+        let member_role = self
+            .member_role(organization_id, member_id)
+            .await?
+            .ok_or(ServiceError::NotFound)?;
+        if member_role == OrgRole::Owner {
+            return Err(ServiceError::Forbidden(
+                "The organization owner cannot be removed".to_string(),
+            ));
+        }
+
+        let mut conn = self.get_conn().await?;
+        diesel::delete(organization_members::table.find((organization_id, member_id)))
+            .execute(&mut conn)
+            .await?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::OrganizationMemberRemoved,
+            actor_id,
+            "organization",
+            Some(organization_id.to_string()),
+            Some(format!("Removed {} ({})", member_id, member_role.as_str())),
+        )
+        .await;
+
+        Ok(())
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Permissions guard for organization actions. Non-members get `NotFound` so
+/// organization IDs are not confirmed to outsiders; members lacking the role get `Forbidden`.
+pub fn authorize(role: Option<OrgRole>, action: OrgAction) -> Result<OrgRole, ServiceError> {
+    match role {
+        None => Err(ServiceError::NotFound),
+        Some(role) if role.permits(action) => Ok(role),
+        Some(_) => Err(ServiceError::Forbidden(format!(
+            "Only organization owners and admins can {}",
+            action.description()
+        ))),
+    }
+}
+
+/// Random invitation token (sent by email) and its SHA-256 hash (stored)
+fn generate_invitation_token() -> (String, String) {
+    let mut token_bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut token_bytes);
+    let token = BASE64_URL_SAFE_NO_PAD.encode(token_bytes);
+    let token_hash = hash_invitation_token(&token);
+    (token, token_hash)
+}
+
+fn hash_invitation_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.trim().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_authorize_status_codes() {
+        use axum::http::StatusCode;
+
+        for action in OrgAction::ALL {
+            let status = |role| authorize(role, action).map_err(|e| e.status_code());
+
+            assert_eq!(status(None), Err(StatusCode::NOT_FOUND), "{:?}", action);
+            assert_eq!(
+                status(Some(OrgRole::Owner)),
+                Ok(OrgRole::Owner),
+                "{:?}",
+                action
+            );
+            assert_eq!(
+                status(Some(OrgRole::Admin)),
+                Ok(OrgRole::Admin),
+                "{:?}",
+                action
+            );
+
+            let expected = if action.required_role() == OrgRole::Member {
+                Ok(OrgRole::Member)
+            } else {
+                Err(StatusCode::FORBIDDEN)
+            };
+            assert_eq!(status(Some(OrgRole::Member)), expected, "{:?}", action);
+        }
+    }
+
+    #[test]
+    fn test_invitation_token_hash() {
+        let (token, token_hash) = generate_invitation_token();
+        assert_eq!(token.len(), 43);
+        assert_eq!(token_hash.len(), 64);
+        assert_eq!(hash_invitation_token(&token), token_hash);
+        assert_ne!(generate_invitation_token().0, token);
+    }
+}
@@ -0,0 +1,540 @@
+// Landing pages (link-in-bio)
+// A page lives at its own short code and renders an HTML list of the owner's links. The
+// rendered page is cached in Redis; page edits drop the cache right away, while edits to
+// the listed links show up once it expires.
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{AsyncConnection, RunQueryDsl};
+use handlebars::Handlebars;
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::collections::HashSet;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::{
+        link::{Link, RedirectRecord},
+        page::{
+            CreatePageRequest, NewPage, NewPageLink, Page, PageResponse, UpdatePage,
+            UpdatePageRequest, MAX_PAGE_LINKS,
+        },
+    },
+    schema::{links, page_links, pages},
+    services::{
+        link::LinkService,
+        short_code::{ShortCodeError, ShortCodeGenerator},
+    },
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        custom_alias_validator::CustomAliasValidator,
+        service_error::ServiceError,
+    },
+};
+
+/// Redis key prefix for rendered pages, followed by the short code
+const CACHE_KEY_PREFIX: &str = "page:html:";
+
+/// How long a rendered page is served from cache; bounds how stale link titles can get
+const CACHE_TTL_SECONDS: usize = 300;
+
+static PAGE_TEMPLATES: Lazy<Handlebars<'static>> = Lazy::new(|| {
+    let mut templates = Handlebars::new();
+    templates
+        .register_template_string("landing", include_str!("../templates/pages/landing.html"))
+        .expect("landing page template is valid");
+    templates
+});
+
+pub struct PageService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+    short_code_generator: ShortCodeGenerator,
+    base_url: String,
+}
+
+impl PageService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            redis_pool: state.redis_pool.clone(),
+            short_code_generator: ShortCodeGenerator::with_redis(
+                state.diesel_pool.clone(),
+                Some(state.redis_pool.clone()),
+            ),
+            base_url: state.config.short_link_base_url.clone(),
+        }
+    }
+
+    /// Create a page; without a short code one is generated like for links
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        request: CreatePageRequest,
+    ) -> Result<PageResponse, ServiceError> {
+        request.validate()?;
+        let mut conn = self.get_conn().await?;
+        Self::check_links(&mut conn, user_id, &request.link_ids).await?;
+
+        let short_code = match request.short_code.as_deref() {
+            Some(code) => {
+                let code = CustomAliasValidator::normalize(code);
+                self.short_code_generator
+                    .validate_custom_alias(&code)
+                    .await
+                    .map_err(alias_error)?;
+                code
+            },
+            None => self.short_code_generator.generate_unique_code().await?,
+        };
+
+        let new_page = NewPage {
+            user_id,
+            short_code,
+            title: request.title.trim().to_string(),
+            description: non_blank(request.description),
+            theme: request.theme.as_str().to_string(),
+        };
+        let link_ids = request.link_ids;
+
+        let page = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                let link_ids = link_ids.clone();
+                Box::pin(async move {
+                    let page = diesel::insert_into(pages::table)
+                        .values(&new_page)
+                        .returning(Page::as_returning())
+                        .get_result(tx)
+                        .await?;
+                    Self::replace_links(tx, page.id, &link_ids).await?;
+                    Ok(page)
+                })
+            })
+            .await
+            .map_err(|e| match e {
+                // Another page took the short code since it was checked
+                diesel::result::Error::DatabaseError(
+                    diesel::result::DatabaseErrorKind::UniqueViolation,
+                    _,
+                ) => ServiceError::AliasAlreadyExists,
+                other => ServiceError::from(other),
+            })?;
+
+        AuditLogger::log_resource_action(
+            AuditAction::PageCreated,
+            user_id,
+            "page",
+            Some(page.id.to_string()),
+            Some(format!("Created page {}", page.short_code)),
+        )
+        .await;
+
+        info!("Page {} created by user {}", page.short_code, user_id);
+        Ok(PageResponse::new(page, link_ids, &self.base_url))
+    }
+
+    /// The user's pages, newest first
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<PageResponse>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let user_pages: Vec<Page> = pages::table
+            .filter(pages::user_id.eq(user_id))
+            .order(pages::created_at.desc())
+            .select(Page::as_select())
+            .load(&mut conn)
+            .await?;
+
+        let page_ids: Vec<Uuid> = user_pages.iter().map(|page| page.id).collect();
+        let rows: Vec<(Uuid, Uuid)> = page_links::table
+            .filter(page_links::page_id.eq_any(&page_ids))
+            .order((page_links::page_id, page_links::position.asc()))
+            .select((page_links::page_id, page_links::link_id))
+            .load(&mut conn)
+            .await?;
+
+        Ok(user_pages
+            .into_iter()
+            .map(|page| {
+                let link_ids = rows
+                    .iter()
+                    .filter(|(page_id, _)| *page_id == page.id)
+                    .map(|(_, link_id)| *link_id)
+                    .collect();
+                PageResponse::new(page, link_ids, &self.base_url)
+            })
+            .collect())
+    }
+
+    /// One of the user's pages
+    pub async fn get(&self, user_id: Uuid, page_id: Uuid) -> Result<PageResponse, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let page = Self::find_owned(&mut conn, user_id, page_id).await?;
+        let link_ids = Self::link_ids(&mut conn, page.id).await?;
+        Ok(PageResponse::new(page, link_ids, &self.base_url))
+    }
+
+    /// Change a page; its short code is fixed once created
+    pub async fn update(
+        &self,
+        user_id: Uuid,
+        page_id: Uuid,
+        request: UpdatePageRequest,
+    ) -> Result<PageResponse, ServiceError> {
+        request.validate()?;
+        let mut conn = self.get_conn().await?;
+        Self::find_owned(&mut conn, user_id, page_id).await?;
+        if let Some(link_ids) = &request.link_ids {
+            Self::check_links(&mut conn, user_id, link_ids).await?;
+        }
+
+        let update = UpdatePage {
+            title: request.title.map(|title| title.trim().to_string()),
+            // An empty description clears it
+            description: request.description.map(|d| non_blank(Some(d))),
+            theme: request.theme.map(|theme| theme.as_str().to_string()),
+            updated_at: Some(Utc::now()),
+        };
+        let link_ids = request.link_ids;
+
+        let page = conn
+            .transaction::<_, diesel::result::Error, _>(|tx| {
+                Box::pin(async move {
+                    let page = diesel::update(pages::table.find(page_id))
+                        .set(&update)
+                        .returning(Page::as_returning())
+                        .get_result(tx)
+                        .await?;
+                    if let Some(link_ids) = link_ids {
+                        Self::replace_links(tx, page.id, &link_ids).await?;
+                    }
+                    Ok(page)
+                })
+            })
+            .await?;
+
+        self.invalidate(&page.short_code).await;
+        AuditLogger::log_resource_action(
+            AuditAction::PageUpdated,
+            user_id,
+            "page",
+            Some(page.id.to_string()),
+            None,
+        )
+        .await;
+
+        let link_ids = Self::link_ids(&mut conn, page.id).await?;
+        Ok(PageResponse::new(page, link_ids, &self.base_url))
+    }
+
+    /// Delete a page; its short code becomes available again and the links are untouched
+    pub async fn delete(&self, user_id: Uuid, page_id: Uuid) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let page = Self::find_owned(&mut conn, user_id, page_id).await?;
+
+        diesel::delete(pages::table.find(page.id))
+            .execute(&mut conn)
+            .await?;
+
+        self.invalidate(&page.short_code).await;
+        AuditLogger::log_resource_action(
+            AuditAction::PageDeleted,
+            user_id,
+            "page",
+            Some(page.id.to_string()),
+            Some(format!("Deleted page {}", page.short_code)),
+        )
+        .await;
+
+        info!("Page {} deleted by user {}", page.short_code, user_id);
+        Ok(())
+    }
+
+    /// HTML for the page at `short_code`, or `None` when no page has it.
+    /// Custom page codes are stored lowercase and match in any case.
+    pub async fn render(&self, short_code: &str) -> Result<Option<String>, ServiceError> {
+        match self.redis_pool.get::<String>(&cache_key(short_code)).await {
+            Ok(Some(html)) => return Ok(Some(html)),
+            Ok(None) => {},
+            Err(e) => warn!("Page cache read failed for {}: {}", short_code, e),
+        }
+
+        let mut conn = self.get_conn().await?;
+        let page: Option<Page> = pages::table
+            .filter(
+                pages::short_code
+                    .eq(short_code)
+                    .or(pages::short_code.eq(short_code.to_lowercase())),
+            )
+            .select(Page::as_select())
+            .first(&mut conn)
+            .await
+            .optional()?;
+        let Some(page) = page else {
+            return Ok(None);
+        };
+
+        let page_links: Vec<Link> = page_links::table
+            .inner_join(links::table)
+            .filter(page_links::page_id.eq(page.id))
+            .filter(links::deleted_at.is_null())
+            .order(page_links::position.asc())
+            .select(Link::as_select())
+            .load(&mut conn)
+            .await?;
+
+        let html = render_page(&page, &page_links).map_err(|e| {
+            error!("Failed to render page {}: {}", page.short_code, e);
+            ServiceError::InternalError
+        })?;
+
+        // Other spellings of a custom code are rendered uncached so invalidation stays exact
+        if page.short_code == short_code {
+            if let Err(e) = self
+                .redis_pool
+                .set_with_expiry(&cache_key(short_code), html.clone(), CACHE_TTL_SECONDS)
+                .await
+            {
+                warn!("Page cache write failed for {}: {}", short_code, e);
+            }
+        }
+
+        Ok(Some(html))
+    }
+
+    // =============================================================================
+    // HELPER METHODS
+    // =============================================================================
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_owned(
+        conn: &mut diesel_async::AsyncPgConnection,
+        user_id: Uuid,
+        page_id: Uuid,
+    ) -> Result<Page, ServiceError> {
+        Ok(pages::table
+            .filter(pages::id.eq(page_id))
+            .filter(pages::user_id.eq(user_id))
+            .select(Page::as_select())
+            .first(conn)
+            .await?)
+    }
+
+    async fn link_ids(
+        conn: &mut diesel_async::AsyncPgConnection,
+        page_id: Uuid,
+    ) -> Result<Vec<Uuid>, ServiceError> {
+        Ok(page_links::table
+            .filter(page_links::page_id.eq(page_id))
+            .order(page_links::position.asc())
+            .select(page_links::link_id)
+            .load(conn)
+            .await?)
+    }
+
+    /// Pages may only list distinct links the user can manage
+    async fn check_links(
+        conn: &mut diesel_async::AsyncPgConnection,
+        user_id: Uuid,
+        link_ids: &[Uuid],
+    ) -> Result<(), ServiceError> {
+        if link_ids.len() > MAX_PAGE_LINKS {
+            return Err(ServiceError::ValidationError(format!(
+                "A page can list at most {} links",
+                MAX_PAGE_LINKS
+            )));
+        }
+        let distinct: HashSet<&Uuid> = link_ids.iter().collect();
+        if distinct.len() != link_ids.len() {
+            return Err(ServiceError::ValidationError(
+                "A link can only appear once on a page".to_string(),
+            ));
+        }
+        if link_ids.is_empty() {
+            return Ok(());
+        }
+
+        let found: Vec<Uuid> = LinkService::accessible_links(user_id)
+            .filter(links::id.eq_any(link_ids))
+            .filter(links::deleted_at.is_null())
+            .select(links::id)
+            .load(conn)
+            .await?;
+        if let Some(missing) = link_ids.iter().find(|id| !found.contains(id)) {
+            return Err(ServiceError::ValidationError(format!(
+                "Link {} does not exist or is not yours",
+                missing
+            )));
+        }
+        Ok(())
+    }
+
+    async fn replace_links(
+        conn: &mut diesel_async::AsyncPgConnection,
+        page_id: Uuid,
+        link_ids: &[Uuid],
+    ) -> Result<(), diesel::result::Error> {
+        diesel::delete(page_links::table.filter(page_links::page_id.eq(page_id)))
+            .execute(conn)
+            .await?;
+
+        let rows: Vec<NewPageLink> = link_ids
+            .iter()
+            .enumerate()
+            .map(|(position, link_id)| NewPageLink {
+                page_id,
+                link_id: *link_id,
+                position: position as i32,
+            })
+            .collect();
+        diesel::insert_into(page_links::table)
+            .values(&rows)
+            .execute(conn)
+            .await?;
+        Ok(())
+    }
+
+    async fn invalidate(&self, short_code: &str) {
+        if let Err(e) = self.redis_pool.del(&cache_key(short_code)).await {
+            warn!("Failed to drop cached page {}: {}", short_code, e);
+        }
+    }
+}
+
+fn cache_key(short_code: &str) -> String {
+    format!("{}{}", CACHE_KEY_PREFIX, short_code)
+}
+
+fn non_blank(text: Option<String>) -> Option<String> {
+    text.map(|t| t.trim().to_string()).filter(|t| !t.is_empty())
+}
+
+/// Reserved and taken codes are client errors, like for link aliases
+fn alias_error(error: ShortCodeError) -> ServiceError {
+    match error {
+        ShortCodeError::ReservedAlias(code) => {
+            ServiceError::ValidationError(format!("'{}' is reserved and cannot be used", code))
+        },
+        other => ServiceError::from(other),
+    }
+}
+
+/// Render the landing page. Links that can't be followed right now (inactive, expired or
+/// quarantined) are left out; password-protected ones lead to their unlock form.
+pub fn render_page(page: &Page, page_links: &[Link]) -> Result<String, handlebars::RenderError> {
+    let now = Utc::now();
+    let buttons: Vec<serde_json::Value> = page_links
+        .iter()
+        .filter(|link| {
+            let record = RedirectRecord::from(*link);
+            record.has(RedirectRecord::ACTIVE)
+                && !record.has(RedirectRecord::QUARANTINED)
+                && !record.is_expired(now)
+        })
+        .map(|link| {
+            let label = link
+                .title
+                .clone()
+                .filter(|title| !title.trim().is_empty())
+                .unwrap_or_else(|| link.display_host().0);
+            json!({ "short_code": link.short_code, "label": label })
+        })
+        .collect();
+
+    PAGE_TEMPLATES.render(
+        "landing",
+        &json!({
+            "title": page.title,
+            "description": page.description,
+            "theme": page.theme().as_str(),
+            "links": buttons,
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page() -> Page {
+        Page {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            short_code: "jane".to_string(),
+            title: "Jane <Doe>".to_string(),
+            description: Some("Designer".to_string()),
+            theme: "dark".to_string(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn link(short_code: &str, title: Option<&str>, is_active: bool) -> Link {
+        serde_json::from_value(json!({
+            "id": Uuid::new_v4(),
+            "user_id": Uuid::new_v4(),
+            "short_code": short_code,
+            "original_url": "https://shop.example.com/spring",
+            "title": title,
+            "is_active": is_active,
+            "processing_status": "completed",
+            "created_at": "2024-01-01T12:00:00Z",
+            "updated_at": "2024-01-01T12:00:00Z"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_page_lists_followable_links_in_order() {
+        let links = [
+            link("shop", Some("My shop"), true),
+            link("old", Some("Retired"), false),
+            link("blog", None, true),
+        ];
+        let html = render_page(&page(), &links).unwrap();
+
+        assert!(html.contains(r#"<body class="theme-dark">"#));
+        // Page text is escaped
+        assert!(html.contains("Jane &lt;Doe&gt;"));
+        assert!(!html.contains("<Doe>"));
+
+        let shop = html.find(r#"href="/shop""#).expect("shop button");
+        let blog = html.find(r#"href="/blog""#).expect("blog button");
+        assert!(shop < blog);
+        assert!(html.contains(">My shop<"));
+        // Untitled links fall back to their host
+        assert!(html.contains(">shop.example.com<"));
+        assert!(!html.contains("/old"));
+    }
+
+    #[test]
+    fn test_render_empty_page() {
+        let mut page = page();
+        page.description = None;
+        page.theme = "light".to_string();
+
+        let html = render_page(&page, &[]).unwrap();
+        assert!(html.contains(r#"<body class="theme-light">"#));
+        assert!(html.contains("No links here yet."));
+        assert!(!html.contains("<p>"));
+    }
+}
@@ -0,0 +1,210 @@
+// Sharded in-memory counters flushed to Redis in the background
+// Hot paths (redirects, authenticated requests) only bump a count under one of SHARD_COUNT
+// locks. A flush drains every shard and writes the totals with a single pipeline; if that
+// fails the counts are put back, so they go out with the next flush. Used by click_counter and
+// api_usage, which also find the keys their flushes wrote with `scan_keys`.
+
+use std::borrow::Borrow;
+use std::collections::hash_map::RandomState;
+use std::collections::{HashMap, HashSet};
+use std::hash::{BuildHasher, Hash};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tracing::{error, warn};
+
+use crate::db::RedisPool;
+
+/// Independent counter maps, so concurrent callers rarely contend on one lock
+const SHARD_COUNT: usize = 16;
+
+/// What a counter counts and how the counts reach Redis
+pub trait CounterSpec: 'static {
+    type Key: Eq + Hash;
+    type Count: Default;
+
+    /// Names the counter in log lines ("Click", "API usage")
+    const NAME: &'static str;
+
+    /// Fold `other` into `count`
+    fn merge(count: &mut Self::Count, other: Self::Count);
+
+    /// Queue the commands adding `count` to Redis
+    fn queue(pipe: &mut redis::Pipeline, key: &Self::Key, count: &Self::Count);
+}
+
+pub struct PendingCounter<S: CounterSpec> {
+    shards: Vec<Mutex<HashMap<S::Key, S::Count>>>,
+    hasher: RandomState,
+    /// Held for a whole flush, so the shutdown flush waits for one in progress
+    flush_lock: tokio::sync::Mutex<()>,
+}
+
+impl<S: CounterSpec> Default for PendingCounter<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: CounterSpec> PendingCounter<S> {
+    pub fn new() -> Self {
+        Self {
+            shards: (0..SHARD_COUNT)
+                .map(|_| Mutex::new(HashMap::new()))
+                .collect(),
+            hasher: RandomState::new(),
+            flush_lock: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Add to a key's pending count; never blocks on I/O. The key is only copied the first
+    /// time it is seen between flushes.
+    pub fn add<Q>(&self, key: &Q, count: S::Count)
+    where
+        S::Key: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = S::Key> + ?Sized,
+    {
+        let mut shard = self.shard(key);
+        match shard.get_mut(key) {
+            Some(pending) => S::merge(pending, count),
+            None => {
+                shard.insert(key.to_owned(), count);
+            },
+        }
+    }
+
+    /// Like `add`, for callers that already own the key
+    pub fn add_owned(&self, key: S::Key, count: S::Count) {
+        let mut shard = self.shard(&key);
+        S::merge(shard.entry(key).or_default(), count);
+    }
+
+    /// Take every pending count, leaving the shards empty
+    pub fn drain(&self) -> HashMap<S::Key, S::Count> {
+        let mut pending: HashMap<S::Key, S::Count> = HashMap::new();
+        for shard in &self.shards {
+            let taken = std::mem::take(&mut *lock(shard));
+            for (key, count) in taken {
+                S::merge(pending.entry(key).or_default(), count);
+            }
+        }
+        pending
+    }
+
+    /// Put back counts from a failed flush so they go out with the next one
+    pub fn requeue(&self, pending: HashMap<S::Key, S::Count>) {
+        for (key, count) in pending {
+            self.add_owned(key, count);
+        }
+    }
+
+    /// Sum of `value` over every pending count
+    pub fn total(&self, value: impl Fn(&S::Count) -> u64) -> u64 {
+        self.shards
+            .iter()
+            .map(|shard| lock(shard).values().map(&value).sum::<u64>())
+            .sum()
+    }
+
+    /// Write pending counts to Redis in one pipeline, retrying up to `max_retries` times
+    /// with exponential backoff. Returns the counts written; on failure they are put back
+    /// and the error is returned.
+    pub async fn flush(
+        &self,
+        redis_pool: &RedisPool,
+        max_retries: u32,
+    ) -> Result<HashMap<S::Key, S::Count>, redis::RedisError> {
+        let _flushing = self.flush_lock.lock().await;
+        let pending = self.drain();
+        if pending.is_empty() {
+            return Ok(pending);
+        }
+
+        let mut pipe = redis::pipe();
+        for (key, count) in &pending {
+            S::queue(&mut pipe, key, count);
+        }
+
+        let mut retries = 0;
+        let mut delay = Duration::from_millis(100);
+        loop {
+            match write(redis_pool, &pipe).await {
+                Ok(()) => return Ok(pending),
+                Err(e) if retries < max_retries => {
+                    warn!(
+                        "{} flush failed, retry {}/{}: {}",
+                        S::NAME,
+                        retries + 1,
+                        max_retries,
+                        e
+                    );
+                    retries += 1;
+                    tokio::time::sleep(delay).await;
+                    delay *= 2; // Exponential backoff
+                },
+                Err(e) => {
+                    error!(
+                        "{} flush failed after {} retries: {}. Keeping {} counts for the next flush.",
+                        S::NAME,
+                        retries,
+                        e,
+                        pending.len()
+                    );
+                    self.requeue(pending);
+                    return Err(e);
+                },
+            }
+        }
+    }
+
+    fn shard<Q>(&self, key: &Q) -> MutexGuard<'_, HashMap<S::Key, S::Count>>
+    where
+        Q: Hash + ?Sized,
+    {
+        let index = self.hasher.hash_one(key) as usize % self.shards.len();
+        lock(&self.shards[index])
+    }
+}
+
+fn lock<K, C>(shard: &Mutex<HashMap<K, C>>) -> MutexGuard<'_, HashMap<K, C>> {
+    // Counts are only ever updated in place, so a poisoned shard is still consistent
+    shard.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+async fn write(redis_pool: &RedisPool, pipe: &redis::Pipeline) -> Result<(), redis::RedisError> {
+    let mut conn = redis_pool.get_connection().await?;
+    pipe.query_async::<()>(&mut conn).await
+}
+
+/// Every key starting with `prefix`, found with SCAN (never KEYS, which blocks Redis on large
+/// keyspaces) asking for `batch_size` keys per call
+pub async fn scan_keys(
+    redis_pool: &RedisPool,
+    prefix: &str,
+    batch_size: usize,
+) -> Result<Vec<String>, redis::RedisError> {
+    let mut conn = redis_pool.get_connection().await?;
+    // SCAN may return a key more than once
+    let mut seen = HashSet::new();
+    let mut keys = Vec::new();
+    let mut cursor: u64 = 0;
+
+    loop {
+        let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN")
+            .arg(cursor)
+            .arg("MATCH")
+            .arg(format!("{}*", prefix))
+            .arg("COUNT")
+            .arg(batch_size)
+            .query_async(&mut conn)
+            .await?;
+
+        keys.extend(batch.into_iter().filter(|key| seen.insert(key.clone())));
+
+        cursor = next;
+        if cursor == 0 {
+            break;
+        }
+    }
+
+    Ok(keys)
+}
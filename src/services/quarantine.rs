@@ -0,0 +1,500 @@
+// Retroactive link re-scanning and quarantine
+// A URL that was clean at creation can later turn malicious, so active links are
+// re-scanned on a rolling schedule and quarantined when they cross the threshold
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, RedisPool},
+    models::{
+        link::{Link, LinkScanUpdate},
+        user::User,
+    },
+    schema::links,
+    services::{background_tasks::TASK_REGISTRY, email::EmailService, link::link_cache_keys},
+    utils::{
+        audit_logger::{AuditAction, AuditLogger},
+        security_scanner::{SecurityScanResult, SecurityService},
+        service_error::ServiceError,
+    },
+};
+
+/// Appeal lifecycle values stored in `links.appeal_status`
+pub const APPEAL_PENDING: &str = "pending";
+pub const APPEAL_APPROVED: &str = "approved";
+pub const APPEAL_REJECTED: &str = "rejected";
+
+/// Actor recorded in audit logs for automated quarantine decisions
+const SYSTEM_ACTOR: Uuid = Uuid::nil();
+
+pub struct QuarantineService {
+    diesel_pool: DieselPool,
+    redis_pool: RedisPool,
+    email_service: Arc<EmailService>,
+    threshold: u8,
+}
+
+impl QuarantineService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            redis_pool: state.redis_pool.clone(),
+            email_service: state.email_service.clone(),
+            threshold: state.config.security.quarantine_threshold,
+        }
+    }
+
+    /// Next batch of active links to re-scan, oldest-scanned (or never scanned) first
+    pub async fn next_rescan_batch(
+        &self,
+        limit: i64,
+        min_age: chrono::Duration,
+    ) -> Result<Vec<Link>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let cutoff = Utc::now() - min_age;
+
+        let batch = links::table
+            .filter(links::is_active.eq(true))
+            .filter(links::deleted_at.is_null())
+            .filter(links::quarantined.eq(false))
+            .filter(
+                links::last_scanned_at
+                    .is_null()
+                    .or(links::last_scanned_at.lt(cutoff)),
+            )
+            .order(links::last_scanned_at.asc().nulls_first())
+            .limit(limit)
+            .select(Link::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(batch)
+    }
+
+    /// Store a scan result and quarantine the link if it crosses the threshold.
+    /// Returns `true` when the link was quarantined.
+    pub async fn record_scan(
+        &self,
+        link: &Link,
+        result: &SecurityScanResult,
+    ) -> Result<bool, ServiceError> {
+        // An admin already reviewed and released this link; keep scoring it but don't re-quarantine
+        let admin_released = link.appeal_status.as_deref() == Some(APPEAL_APPROVED);
+
+        self.store_scan_result(link.id, result).await?;
+
+        // Links the scanner accepts (e.g. on a trusted domain) are never quarantined
+        if result.threat_score >= self.threshold && !result.is_safe && !admin_released {
+            self.quarantine(link, result.threat_score, quarantine_reason(result))
+                .await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Store the scan deferred when a quick link was created on local checks alone, and
+    /// quarantine the link when the URL would have been rejected at creation.
+    /// Returns `true` when the link was quarantined.
+    pub async fn record_deferred_scan(
+        &self,
+        link: &Link,
+        result: &SecurityScanResult,
+    ) -> Result<bool, ServiceError> {
+        self.store_scan_result(link.id, result).await?;
+
+        if !result.is_safe {
+            self.quarantine(link, result.threat_score, quarantine_reason(result))
+                .await?;
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    /// Persist the full scan result (score, risk level, threats, warnings) on the link
+    pub async fn store_scan_result(
+        &self,
+        link_id: Uuid,
+        result: &SecurityScanResult,
+    ) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        diesel::update(links::table.find(link_id))
+            .set(LinkScanUpdate::from(result))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Update the scan timestamp without a score (e.g. the scanner rejected the URL outright)
+    pub async fn mark_scanned(
+        &self,
+        link_id: Uuid,
+        threat_score: Option<u8>,
+    ) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        diesel::update(links::table.find(link_id))
+            .set((
+                links::last_scanned_at.eq(Some(Utc::now())),
+                links::threat_score.eq(threat_score.map(i16::from)),
+                links::updated_at.eq(Utc::now()),
+            ))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Quarantine a link: stop redirects, drop cached copies and notify the owner
+    pub async fn quarantine(
+        &self,
+        link: &Link,
+        threat_score: u8,
+        reason: String,
+    ) -> Result<(), ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let now = Utc::now();
+
+        // Only the first quarantine of a link counts; repeat calls are no-ops
+        let updated = diesel::update(
+            links::table
+                .find(link.id)
+                .filter(links::quarantined.eq(false)),
+        )
+        .set((
+            links::quarantined.eq(true),
+            links::quarantined_at.eq(Some(now)),
+            links::quarantine_reason.eq(Some(reason.clone())),
+            links::threat_score.eq(Some(i16::from(threat_score))),
+            links::last_scanned_at.eq(Some(now)),
+            links::appeal_status.eq(None::<String>),
+            links::appeal_message.eq(None::<String>),
+            links::appealed_at.eq(None::<chrono::DateTime<Utc>>),
+            links::updated_at.eq(now),
+        ))
+        .execute(&mut conn)
+        .await?;
+
+        if updated == 0 {
+            return Ok(());
+        }
+
+        warn!(
+            "Quarantined link {} ({}) with threat score {}: {}",
+            link.id, link.short_code, threat_score, reason
+        );
+
+        self.invalidate_link_cache(link).await;
+
+        AuditLogger::log_link_action(
+            AuditAction::LinkQuarantined,
+            SYSTEM_ACTOR,
+            Some(link.id.to_string()),
+            Some(format!("Threat score {}: {}", threat_score, reason)),
+        )
+        .await;
+
+        match User::find_by_id(&mut conn, link.user_id).await {
+            Ok(owner) => {
+                if let Err(e) = self
+                    .email_service
+                    .send_link_quarantined_notification(
+                        &owner.email,
+                        &owner.full_name,
+                        &link.short_code,
+                        &link.original_url,
+                        &reason,
+                    )
+                    .await
+                {
+                    warn!("Failed to send quarantine notice for {}: {}", link.id, e);
+                }
+            },
+            Err(e) => warn!(
+                "Failed to load owner of quarantined link {}: {}",
+                link.id, e
+            ),
+        }
+
+        Ok(())
+    }
+
+    /// Owner appeal: flag a quarantined link for admin review
+    pub async fn submit_appeal(
+        &self,
+        user_id: Uuid,
+        link_id: Uuid,
+        message: &str,
+    ) -> Result<Link, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let link = links::table
+            .filter(links::id.eq(link_id))
+            .filter(links::user_id.eq(user_id))
+            .filter(links::deleted_at.is_null())
+            .select(Link::as_select())
+            .first(&mut conn)
+            .await?;
+
+        if !link.quarantined {
+            return Err(ServiceError::ValidationError(
+                "Link is not quarantined".to_string(),
+            ));
+        }
+        match link.appeal_status.as_deref() {
+            Some(APPEAL_PENDING) => {
+                return Err(ServiceError::Conflict(
+                    "An appeal for this link is already pending".to_string(),
+                ))
+            },
+            Some(APPEAL_REJECTED) => {
+                return Err(ServiceError::Conflict(
+                    "The appeal for this link was already rejected".to_string(),
+                ))
+            },
+            _ => {},
+        }
+
+        let updated = diesel::update(links::table.find(link_id))
+            .set((
+                links::appeal_status.eq(Some(APPEAL_PENDING)),
+                links::appeal_message.eq(Some(message.trim())),
+                links::appealed_at.eq(Some(Utc::now())),
+                links::updated_at.eq(Utc::now()),
+            ))
+            .returning(Link::as_returning())
+            .get_result(&mut conn)
+            .await?;
+
+        AuditLogger::log_link_action(
+            AuditAction::LinkAppealSubmitted,
+            user_id,
+            Some(link_id.to_string()),
+            None,
+        )
+        .await;
+
+        info!("Appeal submitted for quarantined link {}", link_id);
+        Ok(updated)
+    }
+
+    /// Quarantined links with an appeal awaiting review, oldest first
+    pub async fn list_pending_appeals(&self) -> Result<Vec<Link>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let pending = links::table
+            .filter(links::quarantined.eq(true))
+            .filter(links::appeal_status.eq(APPEAL_PENDING))
+            .order(links::appealed_at.asc())
+            .select(Link::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(pending)
+    }
+
+    /// Admin decision on a pending appeal. Approval lifts the quarantine.
+    pub async fn resolve_appeal(
+        &self,
+        link_id: Uuid,
+        admin_id: Uuid,
+        approve: bool,
+    ) -> Result<Link, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let target = links::table
+            .find(link_id)
+            .filter(links::appeal_status.eq(APPEAL_PENDING));
+
+        let link = if approve {
+            diesel::update(target)
+                .set((
+                    links::quarantined.eq(false),
+                    links::quarantined_at.eq(None::<chrono::DateTime<Utc>>),
+                    links::quarantine_reason.eq(None::<String>),
+                    links::appeal_status.eq(Some(APPEAL_APPROVED)),
+                    links::updated_at.eq(Utc::now()),
+                ))
+                .returning(Link::as_returning())
+                .get_result(&mut conn)
+                .await?
+        } else {
+            diesel::update(target)
+                .set((
+                    links::appeal_status.eq(Some(APPEAL_REJECTED)),
+                    links::updated_at.eq(Utc::now()),
+                ))
+                .returning(Link::as_returning())
+                .get_result(&mut conn)
+                .await?
+        };
+
+        self.invalidate_link_cache(&link).await;
+
+        AuditLogger::log_link_action(
+            AuditAction::LinkAppealResolved,
+            admin_id,
+            Some(link_id.to_string()),
+            Some(
+                if approve {
+                    APPEAL_APPROVED
+                } else {
+                    APPEAL_REJECTED
+                }
+                .to_string(),
+            ),
+        )
+        .await;
+
+        Ok(link)
+    }
+
+    /// Re-scan one batch of links with bounded concurrency.
+    /// Returns (scanned, quarantined).
+    pub async fn rescan_batch(
+        &self,
+        security: &SecurityService,
+        batch_size: i64,
+        concurrency: usize,
+        min_age: chrono::Duration,
+    ) -> Result<(usize, usize), ServiceError> {
+        let batch = self.next_rescan_batch(batch_size, min_age).await?;
+        let scanned = batch.len();
+
+        let quarantined = stream::iter(batch)
+            .map(|link| async move {
+                match security
+                    .comprehensive_security_scan(&link.original_url)
+                    .await
+                {
+                    Ok(result) => self.record_scan(&link, &result).await,
+                    Err(e) => {
+                        // Still advance the timestamp so a bad URL can't stall the queue
+                        warn!("Re-scan of link {} failed: {}", link.id, e);
+                        self.mark_scanned(link.id, None).await.map(|_| false)
+                    },
+                }
+            })
+            .buffer_unordered(concurrency.max(1))
+            .fold(0usize, |count, outcome| async move {
+                match outcome {
+                    Ok(true) => count + 1,
+                    Ok(false) => count,
+                    Err(e) => {
+                        error!("Failed to record re-scan result: {}", e);
+                        count
+                    },
+                }
+            })
+            .await;
+
+        Ok((scanned, quarantined))
+    }
+
+    async fn invalidate_link_cache(&self, link: &Link) {
+        for key in link_cache_keys(link) {
+            if let Err(e) = self.redis_pool.del(&key).await {
+                warn!("Failed to invalidate cache key {}: {}", key, e);
+            }
+        }
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Human-readable quarantine reason shown to the owner and admins
+fn quarantine_reason(result: &SecurityScanResult) -> String {
+    if !result.warnings.is_empty() {
+        return result.warnings.join("; ");
+    }
+    if !result.threats_detected.is_empty() {
+        let threats: Vec<String> = result
+            .threats_detected
+            .iter()
+            .map(|t| format!("{:?}", t))
+            .collect();
+        return format!("Threats detected: {}", threats.join(", "));
+    }
+    format!(
+        "Threat score {} exceeded the safety threshold",
+        result.threat_score
+    )
+}
+
+/// Periodically re-scan existing active links and quarantine the ones that turned malicious
+pub fn spawn_link_rescan_task(state: AppState) {
+    let security_config = &state.config.security;
+    if !security_config.link_rescan_enabled {
+        info!("Link re-scanning disabled (LINK_RESCAN_ENABLED=false)");
+        return;
+    }
+
+    let Some(analytics) = state.clickhouse_analytics.clone() else {
+        warn!("Link re-scanning disabled: ClickHouse analytics is not configured");
+        return;
+    };
+
+    let interval_secs = security_config.link_rescan_interval_seconds.max(1);
+    let batch_size = i64::from(security_config.link_rescan_batch_size.max(1));
+    let concurrency = security_config.link_rescan_concurrency;
+    let min_age = chrono::Duration::hours(i64::from(security_config.link_rescan_min_age_hours));
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("link_rescan", redis_pool, move |reporter| {
+        let security = SecurityService::new(analytics.client())
+            .with_redis_cache(state.redis_pool.clone())
+            .with_threat_lookup(state.threat_lookup.clone());
+        let service = QuarantineService::new(&state);
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                match service
+                    .rescan_batch(&security, batch_size, concurrency, min_age)
+                    .await
+                {
+                    Ok((scanned, quarantined)) => {
+                        if scanned > 0 {
+                            info!("Re-scanned {} links, quarantined {}", scanned, quarantined);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Link re-scan batch failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
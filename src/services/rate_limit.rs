@@ -89,6 +89,39 @@ impl RateLimitConfig {
         }
     }
 
+    /// Create on-demand link re-scan configuration (each scan hits external services)
+    pub fn link_rescan() -> Self {
+        Self {
+            max_requests: 10,
+            window_seconds: 3600, // 1 hour
+            burst_limit: None,
+            block_duration: 300,
+            distributed: true,
+        }
+    }
+
+    /// Create link dry-run configuration (each validation scans and may fetch the URL)
+    pub fn link_validation() -> Self {
+        Self {
+            max_requests: 60,
+            window_seconds: 3600, // 1 hour
+            burst_limit: Some(10),
+            block_duration: 300,
+            distributed: true,
+        }
+    }
+
+    /// Create conversion pixel configuration, keyed by visitor hash (the pixel is public)
+    pub fn conversion_pixel() -> Self {
+        Self {
+            max_requests: 30,
+            window_seconds: 3600, // 1 hour
+            burst_limit: Some(10),
+            block_duration: 600,
+            distributed: true,
+        }
+    }
+
     /// Create default API endpoint configuration
     pub fn default_api() -> Self {
         Self {
@@ -191,13 +224,21 @@ impl RateLimitService {
         service
     }
 
-    /// Check rate limit with custom configuration
+    /// Check rate limit with custom configuration. Analytics record the check under the key's
+    /// first segment as endpoint (`login:ip:203.0.113.7` counts towards `login`).
     pub async fn check_rate_limit_with_config(
         &self,
         key: &str,
         config: &RateLimitConfig,
     ) -> Result<RateLimitResult, RateLimitError> {
-        self.sliding_window_check(key, config).await
+        let start_time = std::time::Instant::now();
+        let result = self.sliding_window_check(key, config).await?;
+
+        let endpoint = key.split(':').next().unwrap_or(key);
+        self.record_analytics(key, endpoint, &result, config, start_time.elapsed())
+            .await;
+
+        Ok(result)
     }
 
     /// Check rate limit using atomic Redis Lua script
@@ -212,8 +253,22 @@ impl RateLimitService {
 
         let result = self.sliding_window_check(key, config).await?;
 
-        // Record analytics event
-        let latency_ms = start_time.elapsed().as_millis() as u64;
+        self.record_analytics(key, endpoint, &result, config, start_time.elapsed())
+            .await;
+
+        Ok(result)
+    }
+
+    /// Send a check to the analytics pipeline if enabled and warn about slow checks
+    async fn record_analytics(
+        &self,
+        key: &str,
+        endpoint: &str,
+        result: &RateLimitResult,
+        config: &RateLimitConfig,
+        elapsed: std::time::Duration,
+    ) {
+        let latency_ms = elapsed.as_millis() as u64;
 
         // Send event to analytics pipeline if enabled
         if let Some(ref analytics) = self.analytics {
@@ -244,8 +299,6 @@ impl RateLimitService {
                 latency_ms, key
             );
         }
-
-        Ok(result)
     }
 
     /// Atomic sliding window rate limiting with burst support using Lua script
@@ -444,16 +497,34 @@ impl RateLimitService {
         Ok(())
     }
 
-    /// Get analytics metrics if analytics are enabled
+    /// Get analytics metrics if analytics are enabled. Windows reaching back before the
+    /// in-memory counters started are read from the ClickHouse export when it is running,
+    /// falling back to the in-memory counters if ClickHouse fails.
     pub async fn get_analytics_metrics(
         &self,
         window_minutes: u64,
+        clickhouse: Option<&crate::services::ClickHouseAnalyticsService>,
     ) -> Option<crate::services::analytics::RateLimitMetrics> {
-        if let Some(ref analytics) = self.analytics {
-            analytics.get_metrics(window_minutes).await.ok()
-        } else {
-            None
+        let analytics = self.analytics.as_ref()?;
+
+        let window_end = chrono::Utc::now();
+        let window_start = window_end - chrono::Duration::minutes(window_minutes as i64);
+        if let Some(clickhouse) = clickhouse {
+            if analytics.sample_export_enabled() && window_start < analytics.counters_since() {
+                match clickhouse
+                    .get_rate_limit_metrics(window_start, window_end)
+                    .await
+                {
+                    Ok(metrics) => return Some(metrics),
+                    Err(e) => warn!(
+                        "Rate limit metrics from ClickHouse failed, using in-memory counters: {}",
+                        e
+                    ),
+                }
+            }
         }
+
+        analytics.get_metrics(window_minutes).await.ok()
     }
 
     /// Get monitoring statistics if analytics are enabled
@@ -466,6 +537,21 @@ impl RateLimitService {
             None
         }
     }
+
+    /// Analytics pipeline, if enabled
+    pub fn analytics(&self) -> Option<&RateLimitAnalytics> {
+        self.analytics.as_ref()
+    }
+
+    /// Most-blocked keys per endpoint if analytics are enabled
+    pub fn get_top_offenders(
+        &self,
+        limit: usize,
+    ) -> Option<HashMap<String, Vec<crate::services::analytics::RateLimitOffender>>> {
+        self.analytics
+            .as_ref()
+            .map(|analytics| analytics.top_offenders(limit))
+    }
 }
 
 #[cfg(test)]
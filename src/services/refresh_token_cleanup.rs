@@ -0,0 +1,219 @@
+// Pruning of expired and revoked refresh tokens
+// Rows are kept for REFRESH_TOKEN_CLEANUP_GRACE_HOURS after they expire or are revoked, so
+// reuse of a recently rotated token is still detected, and then deleted in small batches.
+// Each run stops after REFRESH_TOKEN_CLEANUP_MAX_ROWS; a backlog is worked off over several
+// runs instead of one long burst of deletes. Runs on one replica at a time.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{error, info};
+use utoipa::ToSchema;
+
+use crate::{
+    app::AppState, db::DieselPool, models::refresh_token::RefreshToken,
+    services::background_tasks::TASK_REGISTRY, utils::service_error::ServiceError,
+};
+
+/// Process-wide counters reported by /v1/metrics/refresh-token-cleanup
+pub static REFRESH_TOKEN_CLEANUP: Lazy<RefreshTokenCleanupStats> =
+    Lazy::new(RefreshTokenCleanupStats::default);
+
+/// Refresh token cleanup counters since process start
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct RefreshTokenCleanupMetrics {
+    pub timestamp: String,
+    /// Completed cleanup runs on this instance
+    pub runs: u64,
+    pub failed_runs: u64,
+    pub rows_pruned: u64,
+    /// Runs that stopped at the per-run row cap with rows left over
+    pub capped_runs: u64,
+    pub last_run_at: Option<String>,
+    pub last_run_rows_pruned: u64,
+}
+
+/// Result of one cleanup run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PruneOutcome {
+    pub deleted: u64,
+    /// The run hit the row cap; more rows may be eligible
+    pub capped: bool,
+}
+
+#[derive(Default)]
+pub struct RefreshTokenCleanupStats {
+    runs: AtomicU64,
+    failed_runs: AtomicU64,
+    rows_pruned: AtomicU64,
+    capped_runs: AtomicU64,
+    last_run_rows_pruned: AtomicU64,
+    last_run_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl RefreshTokenCleanupStats {
+    fn record_run(&self, outcome: PruneOutcome) {
+        self.runs.fetch_add(1, Ordering::Relaxed);
+        self.rows_pruned
+            .fetch_add(outcome.deleted, Ordering::Relaxed);
+        if outcome.capped {
+            self.capped_runs.fetch_add(1, Ordering::Relaxed);
+        }
+        self.last_run_rows_pruned
+            .store(outcome.deleted, Ordering::Relaxed);
+        *self.lock_last_run() = Some(Utc::now());
+    }
+
+    fn record_failure(&self, deleted: u64) {
+        self.failed_runs.fetch_add(1, Ordering::Relaxed);
+        self.rows_pruned.fetch_add(deleted, Ordering::Relaxed);
+    }
+
+    pub fn metrics(&self) -> RefreshTokenCleanupMetrics {
+        RefreshTokenCleanupMetrics {
+            timestamp: Utc::now().to_rfc3339(),
+            runs: self.runs.load(Ordering::Relaxed),
+            failed_runs: self.failed_runs.load(Ordering::Relaxed),
+            rows_pruned: self.rows_pruned.load(Ordering::Relaxed),
+            capped_runs: self.capped_runs.load(Ordering::Relaxed),
+            last_run_at: self.lock_last_run().map(|at| at.to_rfc3339()),
+            last_run_rows_pruned: self.last_run_rows_pruned.load(Ordering::Relaxed),
+        }
+    }
+
+    fn lock_last_run(&self) -> std::sync::MutexGuard<'_, Option<DateTime<Utc>>> {
+        self.last_run_at.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Size of the next batch, or `None` once `max_rows` have been deleted
+fn next_batch_size(deleted: u64, batch_size: u32, max_rows: u32) -> Option<i64> {
+    let remaining = u64::from(max_rows).saturating_sub(deleted);
+    (remaining > 0).then(|| remaining.min(u64::from(batch_size)) as i64)
+}
+
+/// Delete tokens expired or revoked before `cutoff`, `batch_size` rows per statement and at
+/// most `max_rows` in total. A connection is only held for one batch at a time.
+pub async fn prune_refresh_tokens(
+    diesel_pool: &DieselPool,
+    cutoff: DateTime<Utc>,
+    batch_size: u32,
+    max_rows: u32,
+) -> Result<PruneOutcome, (ServiceError, u64)> {
+    let mut deleted = 0u64;
+
+    while let Some(limit) = next_batch_size(deleted, batch_size, max_rows) {
+        let mut conn = diesel_pool
+            .get()
+            .await
+            .map_err(|e| (ServiceError::DatabaseError(e.to_string()), deleted))?;
+
+        let batch = RefreshToken::prune_batch(&mut conn, cutoff, limit)
+            .await
+            .map_err(|e| (ServiceError::DatabaseError(e.to_string()), deleted))?;
+        deleted += batch as u64;
+
+        if (batch as i64) < limit {
+            return Ok(PruneOutcome {
+                deleted,
+                capped: false,
+            });
+        }
+    }
+
+    Ok(PruneOutcome {
+        deleted,
+        capped: true,
+    })
+}
+
+pub fn spawn_refresh_token_cleanup_task(state: AppState) {
+    let security = &state.config.security;
+    let grace_hours = security.refresh_token_cleanup_grace_hours;
+    if grace_hours == 0 {
+        info!("Refresh token cleanup disabled (REFRESH_TOKEN_CLEANUP_GRACE_HOURS=0)");
+        return;
+    }
+
+    let grace = chrono::Duration::hours(i64::from(grace_hours));
+    let interval_secs = security.refresh_token_cleanup_interval_seconds;
+    let batch_size = security.refresh_token_cleanup_batch_size;
+    let max_rows = security.refresh_token_cleanup_max_rows;
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("refresh_token_cleanup", redis_pool, move |reporter| {
+        let diesel_pool = state.diesel_pool.clone();
+        async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(interval_secs));
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                let cutoff = Utc::now() - grace;
+                match prune_refresh_tokens(&diesel_pool, cutoff, batch_size, max_rows).await {
+                    Ok(outcome) => {
+                        REFRESH_TOKEN_CLEANUP.record_run(outcome);
+                        if outcome.capped {
+                            info!(
+                                "Pruned {} refresh tokens (per-run cap reached, rest deferred)",
+                                outcome.deleted
+                            );
+                        } else if outcome.deleted > 0 {
+                            info!(
+                                "Pruned {} refresh tokens expired or revoked over {}h ago",
+                                outcome.deleted, grace_hours
+                            );
+                        }
+                        reporter.success();
+                    },
+                    Err((e, deleted)) => {
+                        REFRESH_TOKEN_CLEANUP.record_failure(deleted);
+                        error!("Refresh token cleanup failed after {} rows: {}", deleted, e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_batch_size_respects_run_cap() {
+        assert_eq!(next_batch_size(0, 1000, 50_000), Some(1000));
+        assert_eq!(next_batch_size(49_500, 1000, 50_000), Some(500));
+        assert_eq!(next_batch_size(50_000, 1000, 50_000), None);
+        assert_eq!(next_batch_size(0, 1000, 0), None);
+    }
+
+    #[test]
+    fn test_record_run_counts_capped_runs() {
+        let stats = RefreshTokenCleanupStats::default();
+        stats.record_run(PruneOutcome {
+            deleted: 10,
+            capped: false,
+        });
+        stats.record_run(PruneOutcome {
+            deleted: 500,
+            capped: true,
+        });
+        stats.record_failure(3);
+
+        let metrics = stats.metrics();
+        assert_eq!(metrics.runs, 2);
+        assert_eq!(metrics.failed_runs, 1);
+        assert_eq!(metrics.rows_pruned, 513);
+        assert_eq!(metrics.capped_runs, 1);
+        assert_eq!(metrics.last_run_rows_pruned, 500);
+        assert!(metrics.last_run_at.is_some());
+    }
+}
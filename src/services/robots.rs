@@ -0,0 +1,318 @@
+// robots.txt support for metadata extraction
+// Destinations whose robots.txt disallows our crawler are not fetched. The rules for our
+// user agent are cached per origin in Redis for a day, and robots.txt itself is fetched
+// through the SSRF-safe client like every other destination request.
+
+use crate::db::RedisPool;
+use crate::utils::safe_http::{SafeHttpClient, SafeHttpError};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+/// Product token matched against `User-agent` lines
+pub const ROBOTS_USER_AGENT: &str = "QCK-Bot";
+
+/// Redis key prefix for cached rules, followed by the origin
+const CACHE_KEY_PREFIX: &str = "robots:";
+
+/// How long fetched rules are reused (RFC 9309 allows up to 24 hours)
+const CACHE_TTL_SECONDS: usize = 24 * 60 * 60;
+
+/// Bytes of robots.txt parsed; the rest is ignored (the RFC 9309 minimum)
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+static ROBOTS_HTTP_CLIENT: Lazy<SafeHttpClient> = Lazy::new(|| {
+    SafeHttpClient::new("Mozilla/5.0 (compatible; QCK-Bot/1.0)")
+        .with_timeout(Duration::from_secs(3))
+        .with_max_redirects(5)
+});
+
+/// One `Allow` or `Disallow` line
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct RobotsRule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The rules in a robots.txt that apply to one user agent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RobotsRules {
+    rules: Vec<RobotsRule>,
+}
+
+impl RobotsRules {
+    pub fn allow_all() -> Self {
+        Self::default()
+    }
+
+    pub fn disallow_all() -> Self {
+        Self {
+            rules: vec![RobotsRule {
+                allow: false,
+                pattern: "/".to_string(),
+            }],
+        }
+    }
+
+    /// Keep the groups naming `user_agent`, or the `*` groups when none does
+    pub fn parse(body: &str, user_agent: &str) -> Self {
+        let agent = user_agent.to_ascii_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut names_agent = false;
+
+        // A group is a run of User-agent lines followed by its rules
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let key = key.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    let name = value.to_ascii_lowercase();
+                    names_agent |= name == agent;
+                    group_agents.push(name);
+                },
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // An empty Disallow allows everything, so it adds no rule
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = RobotsRule {
+                        allow: key == "allow",
+                        pattern: value.to_string(),
+                    };
+                    if group_agents.contains(&agent) {
+                        specific.push(rule.clone());
+                    }
+                    if group_agents.iter().any(|name| name == "*") {
+                        wildcard.push(rule);
+                    }
+                },
+                // Sitemap, Crawl-delay and unknown lines don't affect access
+                _ => {},
+            }
+        }
+
+        Self {
+            rules: if names_agent { specific } else { wildcard },
+        }
+    }
+
+    /// The longest matching pattern decides; on a tie `Allow` wins
+    pub fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Match a path against a pattern where `*` is any run of characters and a trailing `$`
+/// anchors the end
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    if !path.starts_with(first) {
+        return false;
+    }
+
+    let rest: Vec<&str> = parts.collect();
+    let mut pos = first.len();
+    if rest.is_empty() {
+        return !anchored || pos == path.len();
+    }
+
+    for (i, part) in rest.iter().enumerate() {
+        if anchored && i == rest.len() - 1 {
+            return path.len() >= pos + part.len() && path.ends_with(part);
+        }
+        match path[pos..].find(part) {
+            Some(offset) => pos += offset + part.len(),
+            None => return false,
+        }
+    }
+    true
+}
+
+/// Whether robots.txt on the URL's origin lets us fetch it.
+/// Errors mean robots.txt could not be fetched at all.
+pub async fn is_allowed(url: &str, cache: Option<&RedisPool>) -> Result<bool, SafeHttpError> {
+    let url = Url::parse(url).map_err(|e| SafeHttpError::InvalidUrl(e.to_string()))?;
+    let origin = url.origin().ascii_serialization();
+
+    let rules = match get_cached(&origin, cache).await {
+        Some(rules) => rules,
+        None => {
+            let (rules, cacheable) = fetch(&origin).await?;
+            if cacheable {
+                set_cached(&origin, &rules, cache).await;
+            }
+            rules
+        },
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    Ok(rules.is_allowed(&path))
+}
+
+/// Fetch and parse robots.txt; the flag says whether the result may be cached
+async fn fetch(origin: &str) -> Result<(RobotsRules, bool), SafeHttpError> {
+    let mut response = ROBOTS_HTTP_CLIENT
+        .get(&format!("{}/robots.txt", origin))
+        .await?;
+    let status = response.status();
+
+    // RFC 9309: a missing robots.txt allows everything, a failing server
+    // disallows everything until it can be fetched again
+    if status.is_client_error() {
+        return Ok((RobotsRules::allow_all(), true));
+    }
+    if !status.is_success() {
+        debug!("robots.txt for {} returned {}", origin, status);
+        return Ok((RobotsRules::disallow_all(), false));
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        body.extend_from_slice(&chunk);
+        if body.len() >= MAX_ROBOTS_BYTES {
+            body.truncate(MAX_ROBOTS_BYTES);
+            break;
+        }
+    }
+
+    let rules = RobotsRules::parse(&String::from_utf8_lossy(&body), ROBOTS_USER_AGENT);
+    Ok((rules, true))
+}
+
+fn cache_key(origin: &str) -> String {
+    format!("{}{}", CACHE_KEY_PREFIX, origin)
+}
+
+async fn get_cached(origin: &str, cache: Option<&RedisPool>) -> Option<RobotsRules> {
+    let cache = cache?;
+    match cache.get::<String>(&cache_key(origin)).await {
+        Ok(Some(value)) => serde_json::from_str(&value).ok(),
+        Ok(None) => None,
+        Err(e) => {
+            debug!("robots.txt cache read failed: {}", e);
+            None
+        },
+    }
+}
+
+async fn set_cached(origin: &str, rules: &RobotsRules, cache: Option<&RedisPool>) {
+    let Some(cache) = cache else {
+        return;
+    };
+    let Ok(value) = serde_json::to_string(rules) else {
+        return;
+    };
+    if let Err(e) = cache
+        .set_with_expiry(&cache_key(origin), value, CACHE_TTL_SECONDS)
+        .await
+    {
+        debug!("robots.txt cache write failed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROBOTS: &str = "\
+# Example robots.txt
+User-agent: Googlebot
+Disallow: /
+
+User-agent: *
+Disallow: /private/
+Allow: /private/press/
+Disallow: /*.pdf$
+
+Sitemap: https://example.com/sitemap.xml
+";
+
+    #[test]
+    fn test_wildcard_group_applies_without_specific_group() {
+        let rules = RobotsRules::parse(ROBOTS, ROBOTS_USER_AGENT);
+        assert!(rules.is_allowed("/"));
+        assert!(rules.is_allowed("/blog/post"));
+        assert!(!rules.is_allowed("/private/report"));
+        // The longer Allow overrides the shorter Disallow
+        assert!(rules.is_allowed("/private/press/release"));
+        assert!(!rules.is_allowed("/files/report.pdf"));
+        assert!(rules.is_allowed("/files/report.pdf?download=1"));
+    }
+
+    #[test]
+    fn test_specific_group_replaces_wildcard() {
+        let body = "\
+User-agent: *
+Disallow: /
+
+User-agent: Other-Bot
+User-agent: qck-bot
+Disallow: /admin
+";
+        let rules = RobotsRules::parse(body, ROBOTS_USER_AGENT);
+        assert!(rules.is_allowed("/"));
+        assert!(!rules.is_allowed("/admin/users"));
+
+        let rules = RobotsRules::parse(body, "SomeoneElse");
+        assert!(!rules.is_allowed("/"));
+    }
+
+    #[test]
+    fn test_empty_and_missing_rules_allow_everything() {
+        assert!(RobotsRules::parse("", ROBOTS_USER_AGENT).is_allowed("/anything"));
+        assert!(
+            RobotsRules::parse("User-agent: *\nDisallow:\n", ROBOTS_USER_AGENT)
+                .is_allowed("/anything")
+        );
+        assert!(RobotsRules::allow_all().is_allowed("/"));
+        assert!(!RobotsRules::disallow_all().is_allowed("/"));
+    }
+
+    #[test]
+    fn test_pattern_matching() {
+        assert!(pattern_matches("/", "/index.html"));
+        assert!(pattern_matches("/shop/*/cart", "/shop/books/cart/add"));
+        assert!(!pattern_matches("/shop/*/cart", "/shop/books"));
+        assert!(pattern_matches("/*.php$", "/index.php"));
+        assert!(!pattern_matches("/*.php$", "/index.php5"));
+        assert!(pattern_matches("/exact$", "/exact"));
+        assert!(!pattern_matches("/exact$", "/exactly"));
+        assert!(!pattern_matches("/a*b$", "/ab/"));
+    }
+
+    #[test]
+    fn test_rules_roundtrip_through_cache_format() {
+        let rules = RobotsRules::parse(ROBOTS, ROBOTS_USER_AGENT);
+        let cached = serde_json::to_string(&rules).unwrap();
+        assert_eq!(serde_json::from_str::<RobotsRules>(&cached).unwrap(), rules);
+    }
+}
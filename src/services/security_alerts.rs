@@ -0,0 +1,163 @@
+// Operator alerting for blocked and high-risk URLs
+// Abuse teams get a signed webhook whenever someone tries to shorten a malicious
+// URL, or a risky one slips under the blocking threshold
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::Duration;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    app_config::SecurityConfig,
+    services::webhook::{WebhookError, WebhookSender},
+    utils::security_scanner::{SecurityRiskLevel, SecurityScanResult, ThreatType},
+};
+
+/// Shared sender so concurrent alerts reuse one connection pool
+static ALERT_WEBHOOK_SENDER: Lazy<WebhookSender> = Lazy::new(WebhookSender::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecurityAlertEvent {
+    /// Link creation was rejected by the security scan
+    UrlBlocked,
+    /// Link was created but scored at or above the alert threshold
+    HighRiskUrlAccepted,
+}
+
+impl SecurityAlertEvent {
+    /// Value sent in the event header
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecurityAlertEvent::UrlBlocked => "security.url_blocked",
+            SecurityAlertEvent::HighRiskUrlAccepted => "security.high_risk_url_accepted",
+        }
+    }
+}
+
+/// Webhook payload. The user id is hashed so alerts can be correlated without exposing it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityAlert {
+    pub event: SecurityAlertEvent,
+    pub user_id_hash: String,
+    pub url: String,
+    pub threat_score: u8,
+    pub risk_level: SecurityRiskLevel,
+    pub threats_detected: Vec<ThreatType>,
+    pub warnings: Vec<String>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// SHA-256 of the user id, hex encoded
+pub fn hash_user_id(user_id: Uuid) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(user_id.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+#[derive(Clone)]
+pub struct SecurityAlertService {
+    webhook_url: String,
+    webhook_secret: String,
+    threat_score_threshold: u8,
+    max_retries: u32,
+}
+
+impl SecurityAlertService {
+    pub fn new(webhook_url: impl Into<String>, webhook_secret: impl Into<String>) -> Self {
+        Self {
+            webhook_url: webhook_url.into(),
+            webhook_secret: webhook_secret.into(),
+            threat_score_threshold: 50,
+            max_retries: 3,
+        }
+    }
+
+    pub fn from_config(config: &SecurityConfig) -> Self {
+        Self::new(&config.alert_webhook_url, &config.alert_webhook_secret)
+            .with_threat_score_threshold(config.alert_webhook_threat_score)
+            .with_max_retries(config.alert_webhook_max_retries)
+    }
+
+    pub fn with_threat_score_threshold(mut self, threshold: u8) -> Self {
+        self.threat_score_threshold = threshold;
+        self
+    }
+
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Alerts are only sent when a webhook URL is configured
+    pub fn is_enabled(&self) -> bool {
+        !self.webhook_url.is_empty()
+    }
+
+    /// Decide whether a scan result warrants an alert
+    pub fn alert_for_scan(
+        &self,
+        user_id: Uuid,
+        result: &SecurityScanResult,
+    ) -> Option<SecurityAlert> {
+        let event = if !result.is_safe {
+            SecurityAlertEvent::UrlBlocked
+        } else if result.threat_score >= self.threat_score_threshold {
+            SecurityAlertEvent::HighRiskUrlAccepted
+        } else {
+            return None;
+        };
+
+        Some(SecurityAlert {
+            event,
+            user_id_hash: hash_user_id(user_id),
+            url: result.url.clone(),
+            threat_score: result.threat_score,
+            risk_level: result.risk_level.clone(),
+            threats_detected: result.threats_detected.clone(),
+            warnings: result.warnings.clone(),
+            occurred_at: Utc::now(),
+        })
+    }
+
+    /// Fire an alert for a scan result in the background.
+    /// Never waits on the webhook and never returns an error to the caller.
+    pub fn notify(&self, user_id: Uuid, result: &SecurityScanResult) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let Some(alert) = self.alert_for_scan(user_id, result) else {
+            return;
+        };
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.deliver(&alert).await {
+                error!(
+                    "Failed to deliver {} security alert: {}",
+                    alert.event.as_str(),
+                    e
+                );
+            }
+        });
+    }
+
+    /// Deliver an alert now, with retries
+    pub async fn deliver(&self, alert: &SecurityAlert) -> Result<(), WebhookError> {
+        ALERT_WEBHOOK_SENDER
+            .clone()
+            .with_max_retries(self.max_retries)
+            .with_retry_delay(Duration::from_secs(1))
+            .deliver(
+                &self.webhook_url,
+                &self.webhook_secret,
+                alert.event.as_str(),
+                alert,
+            )
+            .await
+    }
+}
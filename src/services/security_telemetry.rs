@@ -0,0 +1,199 @@
+// Security scan telemetry
+// Sampled scan results are batched into ClickHouse `security_scan_events`. Recording never waits
+// on ClickHouse: events go through a bounded queue and are dropped when it is full.
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, warn};
+
+use crate::db::ClickHouseClient;
+use crate::utils::security_scanner::SecurityScanResult;
+
+/// Table the recorder writes to
+pub const SECURITY_SCAN_EVENTS_TABLE: &str = "security_scan_events";
+
+/// Events waiting to be written; further events are dropped
+const QUEUE_CAPACITY: usize = 10_000;
+/// Events written per INSERT
+const BATCH_SIZE: usize = 500;
+/// Longest an event waits before its batch is written
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+static RECORDER: OnceCell<mpsc::Sender<SecurityScanEvent>> = OnceCell::new();
+
+/// How a scan ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanOutcome {
+    Allowed,
+    Blocked,
+    /// Accepted because the domain is trusted, whatever it scored
+    Trusted,
+}
+
+impl ScanOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanOutcome::Allowed => "allowed",
+            ScanOutcome::Blocked => "blocked",
+            ScanOutcome::Trusted => "trusted",
+        }
+    }
+}
+
+/// One row of `security_scan_events`
+#[derive(Debug, Clone)]
+pub struct SecurityScanEvent {
+    pub timestamp: DateTime<Utc>,
+    /// Hex SHA-256 of the scanned URL; the URL itself is never stored
+    pub url_hash: String,
+    pub domain: String,
+    pub threat_score: u8,
+    pub threats: Vec<String>,
+    pub duration_ms: u32,
+    pub outcome: ScanOutcome,
+}
+
+impl SecurityScanEvent {
+    pub fn from_result(result: &SecurityScanResult, domain: &str, outcome: ScanOutcome) -> Self {
+        Self {
+            timestamp: result.scan_timestamp,
+            url_hash: hash_url(&result.url),
+            domain: domain.to_lowercase(),
+            threat_score: result.threat_score,
+            threats: result
+                .threats_detected
+                .iter()
+                .map(|threat| format!("{:?}", threat))
+                .collect(),
+            duration_ms: u32::try_from(result.scan_duration_ms).unwrap_or(u32::MAX),
+            outcome,
+        }
+    }
+}
+
+/// Hex SHA-256 of a URL
+pub fn hash_url(url: &str) -> String {
+    format!("{:x}", Sha256::digest(url.as_bytes()))
+}
+
+/// Records sampled scan events; cheap to create per SecurityService
+#[derive(Clone)]
+pub struct ScanTelemetry {
+    client: Arc<ClickHouseClient>,
+    sample_rate: f64,
+}
+
+impl ScanTelemetry {
+    /// `sample_rate` is the fraction of scans recorded (clamped to 0.0..=1.0; 0 disables)
+    pub fn new(client: Arc<ClickHouseClient>, sample_rate: f64) -> Self {
+        Self {
+            client,
+            sample_rate: sample_rate.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn sample_rate(&self) -> f64 {
+        self.sample_rate
+    }
+
+    pub fn should_sample(&self) -> bool {
+        if self.sample_rate >= 1.0 {
+            return true;
+        }
+        self.sample_rate > 0.0 && rand::random::<f64>() < self.sample_rate
+    }
+
+    /// Queue an event if it is sampled; never blocks
+    pub fn record(&self, event: SecurityScanEvent) {
+        if !self.should_sample() {
+            return;
+        }
+
+        let sender = RECORDER.get_or_init(|| spawn_writer(self.client.clone()));
+        if let Err(e) = sender.try_send(event) {
+            debug!("Dropping security scan event: {}", e);
+        }
+    }
+}
+
+/// Batch queued events into ClickHouse until the process exits
+fn spawn_writer(client: Arc<ClickHouseClient>) -> mpsc::Sender<SecurityScanEvent> {
+    let (tx, mut rx) = mpsc::channel::<SecurityScanEvent>(QUEUE_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut batch = Vec::with_capacity(BATCH_SIZE);
+        let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+
+        loop {
+            tokio::select! {
+                event = rx.recv() => {
+                    let Some(event) = event else { break };
+                    batch.push(event);
+                    if batch.len() >= BATCH_SIZE {
+                        write_batch(&client, &mut batch).await;
+                    }
+                }
+                _ = interval.tick() => {
+                    if !batch.is_empty() {
+                        write_batch(&client, &mut batch).await;
+                    }
+                }
+            }
+        }
+
+        warn!("Security scan telemetry queue closed");
+    });
+
+    tx
+}
+
+async fn write_batch(client: &ClickHouseClient, batch: &mut Vec<SecurityScanEvent>) {
+    let events = std::mem::take(batch);
+    if let Err(e) = crate::db::clickhouse_insert_builder::insert_security_scan_events(
+        client.client(),
+        SECURITY_SCAN_EVENTS_TABLE,
+        &events,
+    )
+    .await
+    {
+        error!(
+            "Failed to write {} security scan events: {}",
+            events.len(),
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::security_scanner::{SecurityRiskLevel, ThreatType};
+
+    #[test]
+    fn test_event_hashes_url_and_names_threats() {
+        let result = SecurityScanResult {
+            url: "https://Login.Example.tk/verify?token=secret".to_string(),
+            is_safe: false,
+            threat_score: 65,
+            risk_level: SecurityRiskLevel::High,
+            threats_detected: vec![ThreatType::SuspiciousTld, ThreatType::Phishing],
+            warnings: vec![],
+            scan_timestamp: Utc::now(),
+            scan_duration_ms: 120,
+        };
+
+        let event =
+            SecurityScanEvent::from_result(&result, "Login.Example.tk", ScanOutcome::Blocked);
+        assert_eq!(event.url_hash.len(), 64);
+        assert!(!event.url_hash.contains("secret"));
+        assert_eq!(event.url_hash, hash_url(&result.url));
+        assert_eq!(event.domain, "login.example.tk");
+        assert_eq!(event.threats, vec!["SuspiciousTld", "Phishing"]);
+        assert_eq!(event.duration_ms, 120);
+        assert_eq!(event.outcome.as_str(), "blocked");
+    }
+}
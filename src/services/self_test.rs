@@ -0,0 +1,418 @@
+// End-to-end smoke test of the link lifecycle against the configured dependencies
+// Backs `qck-backend-core --self-test` (exit code 1 on failure) and POST /v1/admin/self-test.
+// A throwaway user creates a link with only the local security checks, the link is resolved
+// like a redirect, its cache entries and click counter are checked, one click sync runs, and
+// the user (links cascade) and every Redis key the run touched are removed again. Steps after
+// a failure are skipped; cleanup always runs once the user exists.
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use serde::Serialize;
+use std::fmt;
+use std::future::Future;
+use std::time::Instant;
+use tracing::{info, warn};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    models::{
+        link::{Link, QuickLinkRequest},
+        user::{NewUser, OnboardingStatus, User},
+    },
+    schema::{links, users},
+    services::{
+        click_counter::{click_counter_key, CLICK_COUNTER},
+        link::{
+            cache_keys_for_code, cached_code_meta, invalidate_code_cache,
+            sync_click_counts_to_database, LinkService,
+        },
+    },
+};
+
+/// Destination of the test link; never requested, only validated and stored
+const SELF_TEST_DESTINATION: &str = "https://example.com/qck-self-test";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SelfTestStatus {
+    Passed,
+    Failed,
+    /// Not run because an earlier step failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SelfTestStep {
+    pub name: String,
+    pub status: SelfTestStatus,
+    pub duration_ms: u64,
+    pub error: Option<String>,
+}
+
+/// Pass/fail report with timings per step
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SelfTestReport {
+    /// True only if every step passed
+    pub passed: bool,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub steps: Vec<SelfTestStep>,
+}
+
+impl fmt::Display for SelfTestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = if self.passed { "PASSED" } else { "FAILED" };
+        writeln!(f, "Self-test {} in {}ms", verdict, self.duration_ms)?;
+        for step in &self.steps {
+            let label = match step.status {
+                SelfTestStatus::Passed => "PASS",
+                SelfTestStatus::Failed => "FAIL",
+                SelfTestStatus::Skipped => "SKIP",
+            };
+            write!(
+                f,
+                "  {:<4}  {:<16} {:>6}ms",
+                label, step.name, step.duration_ms
+            )?;
+            if let Some(error) = &step.error {
+                write!(f, "  {}", error)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Records steps as they run; once one fails the rest are skipped
+struct SelfTestRun {
+    started_at: DateTime<Utc>,
+    start: Instant,
+    steps: Vec<SelfTestStep>,
+}
+
+impl SelfTestRun {
+    fn new() -> Self {
+        Self {
+            started_at: Utc::now(),
+            start: Instant::now(),
+            steps: Vec::new(),
+        }
+    }
+
+    fn failed(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|step| step.status != SelfTestStatus::Passed)
+    }
+
+    /// Run `check` unless an earlier step failed
+    async fn step<T, F, Fut>(&mut self, name: &str, check: F) -> Option<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        if self.failed() {
+            self.record(name, SelfTestStatus::Skipped, 0, None);
+            return None;
+        }
+        self.always(name, check).await
+    }
+
+    /// Run `check` even if an earlier step failed
+    async fn always<T, F, Fut>(&mut self, name: &str, check: F) -> Option<T>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, String>>,
+    {
+        let start = Instant::now();
+        let outcome = check().await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        match outcome {
+            Ok(value) => {
+                self.record(name, SelfTestStatus::Passed, duration_ms, None);
+                Some(value)
+            },
+            Err(error) => {
+                warn!("Self-test step {} failed: {}", name, error);
+                self.record(name, SelfTestStatus::Failed, duration_ms, Some(error));
+                None
+            },
+        }
+    }
+
+    fn record(
+        &mut self,
+        name: &str,
+        status: SelfTestStatus,
+        duration_ms: u64,
+        error: Option<String>,
+    ) {
+        self.steps.push(SelfTestStep {
+            name: name.to_string(),
+            status,
+            duration_ms,
+            error,
+        });
+    }
+
+    fn finish(self) -> SelfTestReport {
+        SelfTestReport {
+            passed: !self.steps.is_empty() && !self.failed(),
+            started_at: self.started_at,
+            duration_ms: self.start.elapsed().as_millis() as u64,
+            steps: self.steps,
+        }
+    }
+}
+
+/// Run the whole lifecycle once and report every step
+pub async fn run_self_test(state: &AppState) -> SelfTestReport {
+    let mut run = SelfTestRun::new();
+
+    // LinkService needs ClickHouse for its security service
+    let link_service = run
+        .step("dependencies", || async {
+            match state.clickhouse_analytics {
+                Some(_) => Ok(LinkService::new(state).with_local_scans_only()),
+                None => Err("ClickHouse is not configured".to_string()),
+            }
+        })
+        .await;
+    let Some(link_service) = link_service else {
+        return run.finish();
+    };
+
+    let user = run.step("create_user", || create_user(state)).await;
+    let Some(user) = user else {
+        return run.finish();
+    };
+
+    let link = run
+        .step("create_link", || async {
+            let response = link_service
+                .quick_create_link(
+                    &user,
+                    QuickLinkRequest {
+                        url: SELF_TEST_DESTINATION.to_string(),
+                    },
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            let short_code = response
+                .short_url
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            link_service
+                .get_link(&short_code)
+                .await
+                .map_err(|e| format!("Created link {} not found: {}", short_code, e))
+        })
+        .await;
+
+    if let Some(link) = &link {
+        run.step("resolve_redirect", || async {
+            let resolved = link_service
+                .resolve_redirect(&link.short_code)
+                .await
+                .map_err(|e| e.to_string())?;
+            if resolved.link_id != link.id || resolved.destination != link.original_url {
+                return Err(format!(
+                    "Resolved to {} ({}), expected {} ({})",
+                    resolved.destination, resolved.link_id, link.original_url, link.id
+                ));
+            }
+            Ok(())
+        })
+        .await;
+
+        run.step("verify_cache", || verify_cache(state, link)).await;
+
+        run.step("count_click", || async {
+            CLICK_COUNTER
+                .flush(&state.redis_pool)
+                .await
+                .map_err(|e| format!("Click flush failed: {}", e))?;
+            let pending = state
+                .redis_pool
+                .get::<i64>(&click_counter_key(&link.short_code))
+                .await
+                .map_err(|e| e.to_string())?
+                .unwrap_or(0);
+            if pending < 1 {
+                return Err("Redirect was not counted in Redis".to_string());
+            }
+            Ok(())
+        })
+        .await;
+
+        run.step("sync_clicks", || async {
+            sync_click_counts_to_database(&state.redis_pool, &state.diesel_pool)
+                .await
+                .map_err(|e| format!("Click sync failed: {}", e))?;
+            let mut conn = state.diesel_pool.get().await.map_err(|e| e.to_string())?;
+            let click_count: i64 = links::table
+                .find(link.id)
+                .select(links::click_count)
+                .first(&mut conn)
+                .await
+                .map_err(|e| e.to_string())?;
+            if click_count < 1 {
+                return Err("click_count is still 0 after the sync".to_string());
+            }
+            Ok(())
+        })
+        .await;
+    }
+
+    run.always("cleanup", || cleanup(state, &user, link.as_ref()))
+        .await;
+
+    let report = run.finish();
+    info!(
+        "Self-test {} in {}ms",
+        if report.passed { "passed" } else { "failed" },
+        report.duration_ms
+    );
+    report
+}
+
+async fn create_user(state: &AppState) -> Result<User, String> {
+    let mut conn = state.diesel_pool.get().await.map_err(|e| e.to_string())?;
+    let new_user = NewUser {
+        email: format!("self-test-{}@example.invalid", Uuid::new_v4().simple()),
+        // Not a bcrypt hash, so no password ever matches
+        password_hash: "!".to_string(),
+        email_verified: true,
+        subscription_tier: "free".to_string(),
+        full_name: "Self-Test".to_string(),
+        company_name: None,
+        onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+    };
+    User::create(&mut conn, new_user)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// The redirect record and the code index are in Redis and point at the link
+async fn verify_cache(state: &AppState, link: &Link) -> Result<(), String> {
+    let [_, redirect_key, _] = cache_keys_for_code(&link.short_code);
+    let record = state
+        .redis_pool
+        .get::<String>(&redirect_key)
+        .await
+        .map_err(|e| e.to_string())?;
+    if record.is_none() {
+        return Err(format!("{} is not cached", redirect_key));
+    }
+
+    let meta = cached_code_meta(&state.redis_pool, &link.short_code)
+        .await
+        .map_err(|e| e.to_string())?;
+    match meta {
+        Some(meta) if meta.link_id == link.id => Ok(()),
+        Some(meta) => Err(format!("Code index points at {}", meta.link_id)),
+        None => Err("Code index is not cached".to_string()),
+    }
+}
+
+/// Drop the link's cache entries and click counter and delete the user; links cascade
+async fn cleanup(state: &AppState, user: &User, link: Option<&Link>) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    if let Some(link) = link {
+        if let Err(e) = invalidate_code_cache(&state.redis_pool, &link.short_code).await {
+            errors.push(e.to_string());
+        }
+        if let Err(e) = state
+            .redis_pool
+            .del(&click_counter_key(&link.short_code))
+            .await
+        {
+            errors.push(e.to_string());
+        }
+    }
+
+    let deleted = match state.diesel_pool.get().await {
+        Ok(mut conn) => diesel::delete(users::table.find(user.id))
+            .execute(&mut conn)
+            .await
+            .map_err(|e| e.to_string()),
+        Err(e) => Err(e.to_string()),
+    };
+    if let Err(e) = deleted {
+        errors.push(format!("User {} not deleted: {}", user.id, e));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_steps_after_a_failure_are_skipped() {
+        let mut run = SelfTestRun::new();
+        assert_eq!(
+            run.step("first", || async { Ok::<_, String>(1) }).await,
+            Some(1)
+        );
+        assert_eq!(
+            run.step("second", || async { Err::<(), _>("boom".to_string()) })
+                .await,
+            None
+        );
+        let ran = std::cell::Cell::new(false);
+        let third = run
+            .step("third", || async {
+                ran.set(true);
+                Ok::<_, String>(())
+            })
+            .await;
+        assert_eq!(third, None);
+        assert!(!ran.get());
+        assert_eq!(
+            run.always("cleanup", || async { Ok::<_, String>(()) })
+                .await,
+            Some(())
+        );
+
+        let report = run.finish();
+        assert!(!report.passed);
+        let statuses: Vec<_> = report.steps.iter().map(|step| step.status).collect();
+        assert_eq!(
+            statuses,
+            [
+                SelfTestStatus::Passed,
+                SelfTestStatus::Failed,
+                SelfTestStatus::Skipped,
+                SelfTestStatus::Passed
+            ]
+        );
+        assert_eq!(report.steps[1].error.as_deref(), Some("boom"));
+
+        let text = report.to_string();
+        assert!(text.starts_with("Self-test FAILED"));
+        assert!(text.contains("FAIL  second"));
+        assert!(text.contains("SKIP  third"));
+    }
+
+    #[tokio::test]
+    async fn test_report_passes_only_when_every_step_passes() {
+        let mut run = SelfTestRun::new();
+        run.step("only", || async { Ok::<_, String>(()) }).await;
+        assert!(run.finish().passed);
+
+        assert!(!SelfTestRun::new().finish().passed);
+    }
+}
@@ -0,0 +1,375 @@
+// Signed service-to-service requests
+// Another service (e.g. qck-cloud calling a split core deployment) signs each request with a
+// named HMAC secret from SERVICE_AUTH_KEYS. The signature covers method, path, timestamp, nonce
+// and body; `require_service_auth` checks it, rejects timestamps outside the allowed clock skew
+// and remembers nonces in Redis so a captured request cannot be replayed.
+
+use std::collections::HashMap;
+
+use axum::http::HeaderMap;
+use chrono::Utc;
+use ring::hmac;
+use subtle::ConstantTimeEq;
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{app_config::ServiceAuthConfig, db::RedisPool};
+
+/// Name of the key the request is signed with
+pub const KEY_ID_HEADER: &str = "X-Service-Key-Id";
+/// Unix timestamp (seconds) the request was signed at
+pub const TIMESTAMP_HEADER: &str = "X-Service-Timestamp";
+/// Unique per request; a nonce is accepted once per key
+pub const NONCE_HEADER: &str = "X-Service-Nonce";
+/// `sha256=<hex hmac>` of the canonical request, see `sign_request`
+pub const SIGNATURE_HEADER: &str = "X-Service-Signature";
+
+/// Redis keys of nonces already seen, `{prefix}{key_id}:{nonce}`
+const NONCE_KEY_PREFIX: &str = "service_auth:nonce:";
+/// Accepted nonce lengths; the signer sends a 32-character UUID
+const NONCE_LENGTH: std::ops::RangeInclusive<usize> = 16..=64;
+
+// =============================================================================
+// ERROR TYPES
+// =============================================================================
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ServiceAuthError {
+    #[error("Missing {0} header")]
+    MissingHeader(&'static str),
+
+    #[error("Unknown service key")]
+    UnknownKey,
+
+    #[error("Invalid {0} header")]
+    InvalidHeader(&'static str),
+
+    #[error("Request timestamp is outside the allowed clock skew")]
+    ClockSkew,
+
+    #[error("Invalid service signature")]
+    InvalidSignature,
+
+    #[error("Request was already used")]
+    Replayed,
+}
+
+// =============================================================================
+// SIGNING
+// =============================================================================
+
+/// HMAC-SHA256 signature over `"{METHOD}\n{path}\n{timestamp}\n{nonce}\n{body}"`, formatted for
+/// the signature header. `path` includes the query string, and is the path core receives: without
+/// a `PUBLIC_BASE_PATH` prefix the reverse proxy strips.
+pub fn sign_request(
+    secret: &str,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    nonce: &str,
+    body: &[u8],
+) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    format!(
+        "sha256={}",
+        hex_digest(&key, method, path, timestamp, nonce, body)
+    )
+}
+
+fn hex_digest(
+    key: &hmac::Key,
+    method: &str,
+    path: &str,
+    timestamp: i64,
+    nonce: &str,
+    body: &[u8],
+) -> String {
+    let mut context = hmac::Context::with_key(key);
+    context.update(method.to_ascii_uppercase().as_bytes());
+    context.update(b"\n");
+    context.update(path.as_bytes());
+    context.update(b"\n");
+    context.update(timestamp.to_string().as_bytes());
+    context.update(b"\n");
+    context.update(nonce.as_bytes());
+    context.update(b"\n");
+    context.update(body);
+
+    context
+        .sign()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Signs outgoing requests; for services calling routes behind `require_service_auth`
+#[derive(Clone)]
+pub struct ServiceSigner {
+    key_id: String,
+    secret: String,
+}
+
+impl ServiceSigner {
+    pub fn new(key_id: impl Into<String>, secret: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            secret: secret.into(),
+        }
+    }
+
+    /// Headers to add to a request, signed now with a fresh nonce
+    pub fn sign(&self, method: &str, path: &str, body: &[u8]) -> [(&'static str, String); 4] {
+        self.sign_at(
+            method,
+            path,
+            body,
+            Utc::now().timestamp(),
+            &Uuid::new_v4().simple().to_string(),
+        )
+    }
+
+    /// Headers for a request signed at `timestamp` with `nonce`
+    pub fn sign_at(
+        &self,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        timestamp: i64,
+        nonce: &str,
+    ) -> [(&'static str, String); 4] {
+        [
+            (KEY_ID_HEADER, self.key_id.clone()),
+            (TIMESTAMP_HEADER, timestamp.to_string()),
+            (NONCE_HEADER, nonce.to_string()),
+            (
+                SIGNATURE_HEADER,
+                sign_request(&self.secret, method, path, timestamp, nonce, body),
+            ),
+        ]
+    }
+}
+
+// =============================================================================
+// VERIFICATION
+// =============================================================================
+
+/// Service whose signature was accepted; added to the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServiceCaller {
+    /// Name of the key the request was signed with
+    pub key_id: String,
+}
+
+/// A request whose signature and timestamp check out; its nonce is not yet claimed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedRequest {
+    pub key_id: String,
+    pub nonce: String,
+}
+
+/// Checks signatures against the configured keys
+pub struct ServiceVerifier {
+    keys: HashMap<String, hmac::Key>,
+    max_clock_skew_seconds: u64,
+}
+
+impl ServiceVerifier {
+    pub fn new(config: &ServiceAuthConfig) -> Self {
+        Self {
+            keys: config
+                .keys
+                .iter()
+                .map(|key| {
+                    (
+                        key.name.clone(),
+                        hmac::Key::new(hmac::HMAC_SHA256, key.secret.as_bytes()),
+                    )
+                })
+                .collect(),
+            max_clock_skew_seconds: config.max_clock_skew_seconds,
+        }
+    }
+
+    /// Check the signature headers of a request received at `now` (Unix seconds)
+    pub fn verify(
+        &self,
+        headers: &HeaderMap,
+        method: &str,
+        path: &str,
+        body: &[u8],
+        now: i64,
+    ) -> Result<VerifiedRequest, ServiceAuthError> {
+        let key_id = header_str(headers, KEY_ID_HEADER)?;
+        let timestamp = header_str(headers, TIMESTAMP_HEADER)?;
+        let nonce = header_str(headers, NONCE_HEADER)?;
+        let signature = header_str(headers, SIGNATURE_HEADER)?;
+
+        let key = self.keys.get(key_id).ok_or(ServiceAuthError::UnknownKey)?;
+        let timestamp: i64 = timestamp
+            .parse()
+            .map_err(|_| ServiceAuthError::InvalidHeader(TIMESTAMP_HEADER))?;
+        if now.abs_diff(timestamp) > self.max_clock_skew_seconds {
+            return Err(ServiceAuthError::ClockSkew);
+        }
+        let valid_nonce = NONCE_LENGTH.contains(&nonce.len())
+            && nonce
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_');
+        if !valid_nonce {
+            return Err(ServiceAuthError::InvalidHeader(NONCE_HEADER));
+        }
+
+        let expected = hex_digest(key, method, path, timestamp, nonce, body);
+        let matches = signature
+            .strip_prefix("sha256=")
+            .is_some_and(|digest| bool::from(digest.as_bytes().ct_eq(expected.as_bytes())));
+        if !matches {
+            return Err(ServiceAuthError::InvalidSignature);
+        }
+
+        Ok(VerifiedRequest {
+            key_id: key_id.to_string(),
+            nonce: nonce.to_string(),
+        })
+    }
+
+    /// Record the request's nonce; false if it was seen before. Nonces are kept until their
+    /// timestamp could no longer pass the clock skew check.
+    pub async fn claim_nonce(
+        &self,
+        redis_pool: &RedisPool,
+        request: &VerifiedRequest,
+    ) -> Result<bool, redis::RedisError> {
+        redis_pool
+            .set_nx_with_expiry(
+                &format!("{}{}:{}", NONCE_KEY_PREFIX, request.key_id, request.nonce),
+                "1".to_string(),
+                self.nonce_ttl_seconds(),
+            )
+            .await
+    }
+
+    /// A timestamp is accepted `max_clock_skew_seconds` either side of now
+    fn nonce_ttl_seconds(&self) -> usize {
+        usize::try_from(
+            self.max_clock_skew_seconds
+                .saturating_mul(2)
+                .saturating_add(1),
+        )
+        .unwrap_or(usize::MAX)
+    }
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a str, ServiceAuthError> {
+    headers
+        .get(name)
+        .ok_or(ServiceAuthError::MissingHeader(name))?
+        .to_str()
+        .map_err(|_| ServiceAuthError::InvalidHeader(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app_config::ServiceKey;
+
+    const SECRET: &str = "cloud-secret-at-least-32-bytes-long!!";
+    const NOW: i64 = 1_750_000_000;
+    const NONCE: &str = "0f8e3a7c9b2d4e6f8a1c3e5b7d9f2a4c";
+
+    fn verifier() -> ServiceVerifier {
+        ServiceVerifier::new(&ServiceAuthConfig {
+            keys: vec![ServiceKey {
+                name: "cloud".to_string(),
+                secret: SECRET.to_string(),
+            }],
+            max_clock_skew_seconds: 300,
+        })
+    }
+
+    fn signed(timestamp: i64, body: &[u8]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in ServiceSigner::new("cloud", SECRET).sign_at(
+            "post",
+            "/v1/internal/links?sync=1",
+            body,
+            timestamp,
+            NONCE,
+        ) {
+            headers.insert(name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    fn verify(
+        headers: &HeaderMap,
+        path: &str,
+        body: &[u8],
+    ) -> Result<VerifiedRequest, ServiceAuthError> {
+        verifier().verify(headers, "POST", path, body, NOW)
+    }
+
+    #[test]
+    fn test_signed_request_is_accepted() {
+        let body = br#"{"url":"https://example.com"}"#;
+        assert_eq!(
+            verify(&signed(NOW, body), "/v1/internal/links?sync=1", body),
+            Ok(VerifiedRequest {
+                key_id: "cloud".to_string(),
+                nonce: NONCE.to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_clock_skew_is_rejected() {
+        let path = "/v1/internal/links?sync=1";
+        // Either side of now, up to the allowed skew
+        assert!(verify(&signed(NOW - 300, b""), path, b"").is_ok());
+        assert!(verify(&signed(NOW + 300, b""), path, b"").is_ok());
+        assert_eq!(
+            verify(&signed(NOW - 301, b""), path, b""),
+            Err(ServiceAuthError::ClockSkew)
+        );
+        assert_eq!(
+            verify(&signed(NOW + 301, b""), path, b""),
+            Err(ServiceAuthError::ClockSkew)
+        );
+    }
+
+    #[test]
+    fn test_tampered_request_is_rejected() {
+        let headers = signed(NOW, b"{}");
+        assert_eq!(
+            verify(&headers, "/v1/internal/links?sync=1", b"{\"admin\":true}"),
+            Err(ServiceAuthError::InvalidSignature)
+        );
+        assert_eq!(
+            verify(&headers, "/v1/internal/links?sync=0", b"{}"),
+            Err(ServiceAuthError::InvalidSignature)
+        );
+
+        // The signature binds the timestamp, so it cannot be moved forward
+        let mut moved = headers.clone();
+        moved.insert(TIMESTAMP_HEADER, (NOW + 1).to_string().parse().unwrap());
+        assert_eq!(
+            verify(&moved, "/v1/internal/links?sync=1", b"{}"),
+            Err(ServiceAuthError::InvalidSignature)
+        );
+
+        let mut unknown = headers.clone();
+        unknown.insert(KEY_ID_HEADER, "other".parse().unwrap());
+        assert_eq!(
+            verify(&unknown, "/v1/internal/links?sync=1", b"{}"),
+            Err(ServiceAuthError::UnknownKey)
+        );
+
+        let mut missing = headers;
+        missing.remove(NONCE_HEADER);
+        assert_eq!(
+            verify(&missing, "/v1/internal/links?sync=1", b"{}"),
+            Err(ServiceAuthError::MissingHeader(NONCE_HEADER))
+        );
+    }
+}
@@ -13,9 +13,18 @@ use thiserror::Error;
 use tracing::{error, info, instrument, warn};
 
 use crate::{
+    app::AppState,
     app_config::{CONFIG, SECONDS_PER_DAY},
     db::{DieselPool, RedisPool},
-    utils::base62::{Base62Encoder, Base62Error},
+    models::link::lower,
+    services::background_tasks::TASK_REGISTRY,
+    utils::{
+        base62::{
+            build_alphabet, encode_with_alphabet, random_with_alphabet, Base62Encoder, Base62Error,
+            DEFAULT_ALPHABET,
+        },
+        data_files,
+    },
 };
 
 // =============================================================================
@@ -32,6 +41,16 @@ const REDIS_COLLISION_CHECK_TTL: usize = 300;
 const REDIS_RESERVATION_TTL: usize = 60;
 /// High collision threshold (triggers length increase)
 const HIGH_COLLISION_THRESHOLD: f64 = 0.01; // 1% collision rate
+/// Redis list shared by all replicas holding pre-generated `code:reserved_at` entries
+const REDIS_CODE_POOL_KEY: &str = "shortcode:pool";
+/// Lock so only one replica refills the pool at a time
+const REDIS_CODE_POOL_LOCK: &str = "shortcode:pool:refill_lock";
+/// How often the replenisher checks the pool level
+const POOL_REFILL_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Pool hits and misses across all generators in this process (generators are per request)
+static POOL_HITS: AtomicU64 = AtomicU64::new(0);
+static POOL_MISSES: AtomicU64 = AtomicU64::new(0);
 
 // =============================================================================
 // ERROR TYPES
@@ -117,6 +136,13 @@ pub struct GenerationStats {
     pub reserved_codes_count: usize,
     pub collision_rate: f64,
     pub current_counter: u64,
+    pub alphabet_size: usize,
+    /// Attempts that used a longer code after repeated collisions
+    pub length_escalations: u64,
+    /// Codes currently waiting in the pre-generated pool
+    pub pool_size: usize,
+    /// Share of `generate_unique_code` calls served from the pool
+    pub pool_hit_rate: f64,
 }
 
 // =============================================================================
@@ -126,17 +152,22 @@ pub struct GenerationStats {
 pub struct ShortCodeGenerator {
     pool: DieselPool,
     redis_pool: Option<RedisPool>,
-    encoder: Base62Encoder,
+    alphabet: Vec<u8>, // Configured alphabet, ambiguous characters already removed if requested
     min_length: usize,
     current_length: AtomicU64, // Dynamic length based on collisions (replaces default_length)
     max_length: usize,
     max_retries: usize,
+    escalate_after: usize, // Collisions at one length before an attempt uses length+1
     batch_size: usize,
+    pool_target_size: usize,      // Codes the replenisher keeps in the pool (0 disables it)
+    pool_refill_threshold: usize, // Refill once the pool drops below this
+    pool_reservation_ttl: u64,    // Seconds before unused pooled codes are recycled
     reserved_codes: HashSet<String>,
     profanity_list: HashSet<String>,
     counter: Arc<AtomicU64>, // Atomic counter for sequential generation
     collision_count: Arc<AtomicU64>, // Track collisions for rate calculation
     generation_count: Arc<AtomicU64>, // Track total generations
+    length_escalations: Arc<AtomicU64>, // Attempts escalated to a longer code
     pre_generated_pool: Arc<tokio::sync::RwLock<Vec<String>>>, // Pre-generated code pool for high traffic
 }
 
@@ -163,20 +194,27 @@ impl ShortCodeGenerator {
         Self {
             pool,
             redis_pool,
-            encoder: Base62Encoder::with_constraints(
-                config.short_code_min_length,
-                config.short_code_max_length,
-            ),
+            // Validated when the config loads; the fallback only guards against direct construction
+            alphabet: build_alphabet(
+                &config.short_code_alphabet,
+                config.short_code_exclude_ambiguous,
+            )
+            .unwrap_or_else(|_| DEFAULT_ALPHABET.as_bytes().to_vec()),
             min_length: config.short_code_min_length,
             current_length: AtomicU64::new(config.short_code_default_length as u64),
             max_length: config.short_code_max_length,
             max_retries: config.short_code_max_retries,
+            escalate_after: config.short_code_escalate_after,
             batch_size: config.short_code_batch_size,
+            pool_target_size: config.short_code_pool_size,
+            pool_refill_threshold: config.short_code_pool_refill_threshold,
+            pool_reservation_ttl: config.short_code_pool_reservation_ttl,
             reserved_codes,
             profanity_list,
             counter: Arc::new(AtomicU64::new(initial_counter)),
             collision_count: Arc::new(AtomicU64::new(0)),
             generation_count: Arc::new(AtomicU64::new(0)),
+            length_escalations: Arc::new(AtomicU64::new(0)),
             pre_generated_pool: Arc::new(tokio::sync::RwLock::new(Vec::new())),
         }
     }
@@ -186,8 +224,8 @@ impl ShortCodeGenerator {
         let mut reserved = HashSet::new();
 
         // Try to load from JSON file
-        let json_path = "data/reserved_words.json";
-        match std::fs::read_to_string(json_path) {
+        let json_path = data_files::data_file_path(data_files::RESERVED_WORDS_FILE);
+        match std::fs::read_to_string(&json_path) {
             Ok(content) => {
                 match serde_json::from_str::<ReservedWordsConfig>(&content) {
                     Ok(config) => {
@@ -219,7 +257,8 @@ impl ShortCodeGenerator {
             },
             Err(e) => {
                 warn!(
-                    "Failed to read reserved words file: {}. Using fallback list.",
+                    "Failed to read {}: {}. Using fallback list.",
+                    json_path.display(),
                     e
                 );
                 Self::load_fallback_reserved_words(&mut reserved);
@@ -234,8 +273,8 @@ impl ShortCodeGenerator {
         let mut profanity = HashSet::new();
 
         // Try to load from JSON file
-        let json_path = "data/profanity_list.json";
-        match std::fs::read_to_string(json_path) {
+        let json_path = data_files::data_file_path(data_files::PROFANITY_LIST_FILE);
+        match std::fs::read_to_string(&json_path) {
             Ok(content) => {
                 match serde_json::from_str::<ProfanityConfig>(&content) {
                     Ok(config) => {
@@ -264,7 +303,11 @@ impl ShortCodeGenerator {
                 }
             },
             Err(e) => {
-                warn!("Failed to read profanity file: {}. Using fallback list.", e);
+                warn!(
+                    "Failed to read {}: {}. Using fallback list.",
+                    json_path.display(),
+                    e
+                );
                 Self::load_fallback_profanity(&mut profanity);
             },
         }
@@ -300,34 +343,154 @@ impl ShortCodeGenerator {
     }
 
     /// Pre-generate codes for high-traffic periods
-    pub async fn refill_code_pool(&self, size: usize) -> Result<(), ShortCodeError> {
+    /// Codes are reserved in `reserved_short_codes` first, so nothing else can claim them
+    /// while they wait; with Redis the pool is shared by all replicas.
+    pub async fn refill_code_pool(&self, size: usize) -> Result<usize, ShortCodeError> {
         let current_length = AtomicU64::load(&self.current_length, Ordering::Relaxed) as usize;
 
-        // Generate batch of codes
-        let new_codes = self.generate_batch_codes(size, current_length).await?;
+        let mut added = 0;
+        while added < size {
+            let wanted = std::cmp::min(size - added, self.batch_size);
+            let candidates = self.generate_batch_codes(wanted, current_length).await?;
+            if candidates.is_empty() {
+                break;
+            }
+
+            let reserved = self.reserve_pool_codes(&candidates).await?;
+            if reserved.is_empty() {
+                break;
+            }
+            added += reserved.len();
 
-        // Add to pool
-        let mut pool = self.pre_generated_pool.write().await;
-        pool.extend(new_codes);
+            if let Some(redis_pool) = &self.redis_pool {
+                let reserved_at = chrono::Utc::now().timestamp();
+                let entries: Vec<String> = reserved
+                    .iter()
+                    .map(|code| format!("{}:{}", code, reserved_at))
+                    .collect();
+                let mut conn = redis_pool
+                    .get_connection()
+                    .await
+                    .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+                redis::cmd("RPUSH")
+                    .arg(REDIS_CODE_POOL_KEY)
+                    .arg(entries)
+                    .query_async::<()>(&mut conn)
+                    .await
+                    .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+            } else {
+                self.pre_generated_pool.write().await.extend(reserved);
+            }
+        }
 
         info!(
             "Pre-generated code pool refilled with {} codes, total: {}",
-            size,
-            pool.len()
+            added,
+            self.get_pool_size().await
         );
-        Ok(())
+        Ok(added)
+    }
+
+    /// Top the shared pool back up to its target size once it drops below the refill threshold
+    pub async fn replenish_pool(&self) -> Result<usize, ShortCodeError> {
+        let Some(redis_pool) = &self.redis_pool else {
+            return Ok(0);
+        };
+        if self.pool_target_size == 0 {
+            return Ok(0);
+        }
+
+        self.drop_stale_pool_entries().await?;
+        let available = self.get_pool_size().await;
+        if available >= self.pool_refill_threshold {
+            return Ok(0);
+        }
+
+        // Another replica may already be refilling
+        let locked = redis_pool
+            .set_nx_with_expiry(REDIS_CODE_POOL_LOCK, "1".to_string(), 60)
+            .await
+            .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+        if !locked {
+            return Ok(0);
+        }
+
+        let result = self
+            .refill_code_pool(self.pool_target_size.saturating_sub(available))
+            .await;
+        let _ = redis_pool.del(REDIS_CODE_POOL_LOCK).await;
+        result
+    }
+
+    /// Delete reservations older than the reservation TTL so unused codes return to circulation
+    pub async fn recycle_expired_reservations(&self) -> Result<usize, ShortCodeError> {
+        use crate::schema::reserved_short_codes::dsl::*;
+
+        let mut conn = self.pool.get().await.map_err(|e| {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UnableToSendCommand,
+                Box::new(e.to_string()),
+            )
+        })?;
+
+        let cutoff = chrono::Utc::now() - chrono::Duration::seconds(self.pool_reservation_ttl as i64);
+        let recycled = diesel::delete(reserved_short_codes.filter(reserved_at.lt(cutoff)))
+            .execute(&mut conn)
+            .await?;
+        Ok(recycled)
     }
 
     /// Get a code from the pre-generated pool if available
     pub async fn get_from_pool(&self) -> Option<String> {
-        let mut pool = self.pre_generated_pool.write().await;
-        pool.pop()
+        let Some(redis_pool) = &self.redis_pool else {
+            return self.pre_generated_pool.write().await.pop();
+        };
+
+        let mut conn = match redis_pool.get_connection().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                warn!("Short code pool unavailable: {}", e);
+                return None;
+            },
+        };
+
+        loop {
+            let entry: Option<String> = match redis::cmd("LPOP")
+                .arg(REDIS_CODE_POOL_KEY)
+                .query_async(&mut conn)
+                .await
+            {
+                Ok(entry) => entry,
+                Err(e) => {
+                    warn!("Failed to pop from short code pool: {}", e);
+                    return None;
+                },
+            };
+
+            match parse_pool_entry(&entry?) {
+                // Reservations past the TTL may already have been recycled
+                Some((code, reserved_at)) if !self.is_reservation_expired(reserved_at) => {
+                    return Some(code.to_string())
+                },
+                _ => continue,
+            }
+        }
     }
 
     /// Get the current pool size
     pub async fn get_pool_size(&self) -> usize {
-        let pool = self.pre_generated_pool.read().await;
-        pool.len()
+        let Some(redis_pool) = &self.redis_pool else {
+            return self.pre_generated_pool.read().await.len();
+        };
+
+        match redis_pool.get_connection().await {
+            Ok(mut conn) => redis::cmd("LLEN")
+                .arg(REDIS_CODE_POOL_KEY)
+                .query_async::<usize>(&mut conn)
+                .await
+                .unwrap_or(0),
+            Err(_) => 0,
+        }
     }
 
     /// Generate a unique short code with collision detection
@@ -336,15 +499,76 @@ impl ShortCodeGenerator {
         // Try to get from pre-generated pool first (for high-traffic optimization)
         if let Some(code) = self.get_from_pool().await {
             info!("Using pre-generated code from pool: {}", code);
+            AtomicU64::fetch_add(&POOL_HITS, 1, Ordering::Relaxed);
             AtomicU64::fetch_add(&self.generation_count, 1, Ordering::Relaxed);
             return Ok(code);
         }
+        AtomicU64::fetch_add(&POOL_MISSES, 1, Ordering::Relaxed);
 
         // Get current length (may have been increased due to collisions)
         let current_length = AtomicU64::load(&self.current_length, Ordering::Relaxed) as usize;
         self.generate_unique_code_with_length(current_length).await
     }
 
+    /// Insert reservations, returning only the codes this call actually reserved
+    async fn reserve_pool_codes(&self, codes: &[String]) -> Result<Vec<String>, ShortCodeError> {
+        use crate::schema::reserved_short_codes::dsl::*;
+
+        let mut conn = self.pool.get().await.map_err(|e| {
+            diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UnableToSendCommand,
+                Box::new(e.to_string()),
+            )
+        })?;
+
+        let rows: Vec<_> = codes.iter().map(|c| code.eq(c)).collect();
+        let reserved = diesel::insert_into(reserved_short_codes)
+            .values(&rows)
+            .on_conflict_do_nothing()
+            .returning(code)
+            .get_results::<String>(&mut conn)
+            .await?;
+        Ok(reserved)
+    }
+
+    /// Pop expired entries off the head of the shared pool (oldest entries are at the head)
+    async fn drop_stale_pool_entries(&self) -> Result<(), ShortCodeError> {
+        let Some(redis_pool) = &self.redis_pool else {
+            return Ok(());
+        };
+        let mut conn = redis_pool
+            .get_connection()
+            .await
+            .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+
+        loop {
+            let head: Option<String> = redis::cmd("LINDEX")
+                .arg(REDIS_CODE_POOL_KEY)
+                .arg(0)
+                .query_async(&mut conn)
+                .await
+                .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+
+            match head.as_deref().and_then(parse_pool_entry) {
+                Some((_, reserved_at)) if !self.is_reservation_expired(reserved_at) => {
+                    return Ok(())
+                },
+                None if head.is_none() => return Ok(()),
+                _ => {
+                    redis::cmd("LPOP")
+                        .arg(REDIS_CODE_POOL_KEY)
+                        .query_async::<Option<String>>(&mut conn)
+                        .await
+                        .map_err(|e| ShortCodeError::RedisError(e.to_string()))?;
+                },
+            }
+        }
+    }
+
+    fn is_reservation_expired(&self, reserved_at: i64) -> bool {
+        chrono::Utc::now().timestamp() - reserved_at >= self.pool_reservation_ttl as i64
+    }
+
     /// Generate a unique short code with custom length
     #[instrument(skip(self))]
     pub async fn generate_unique_code_with_length(
@@ -360,22 +584,24 @@ impl ShortCodeGenerator {
         }
 
         let mut attempts = 0;
+        let mut collisions = 0;
         AtomicU64::fetch_add(&self.generation_count, 1, Ordering::Relaxed);
 
         while attempts < self.max_retries {
+            // Adaptive length: after repeated collisions, try a longer code for this attempt
+            let attempt_length =
+                escalated_length(length, collisions, self.escalate_after, self.max_length);
+            if attempt_length > length {
+                AtomicU64::fetch_add(&self.length_escalations, 1, Ordering::Relaxed);
+            }
+
             // Strategy: Use counter for first 3 attempts, then random
             let candidate = if attempts < 3 {
                 // Counter-based generation for performance
-                self.generate_from_counter(length).await?
+                self.generate_from_counter(attempt_length).await?
             } else {
                 // Random generation as fallback
-                // Increase length dynamically if collision rate is high
-                let dynamic_length = if attempts > 5 {
-                    std::cmp::min(length + (attempts - 5) / 2, self.max_length)
-                } else {
-                    length
-                };
-                self.generate_random_code(dynamic_length)
+                self.generate_random_code(attempt_length)
             };
 
             // Check if code is reserved
@@ -384,9 +610,9 @@ impl ShortCodeGenerator {
                 continue;
             }
 
-            // Check for profanity
+            // Check for profanity; regenerate without counting it as a collision
             if self.contains_profanity(&candidate) {
-                warn!("Generated code contains profanity: {}", candidate);
+                warn!("Generated code contains profanity, regenerating");
                 attempts += 1;
                 continue;
             }
@@ -433,6 +659,7 @@ impl ShortCodeGenerator {
                     candidate,
                     attempts + 1
                 );
+                collisions += 1;
                 attempts += 1;
                 continue;
             }
@@ -448,7 +675,7 @@ impl ShortCodeGenerator {
     /// Generate code from atomic counter
     async fn generate_from_counter(&self, min_length: usize) -> Result<String, ShortCodeError> {
         let counter_value = AtomicU64::fetch_add(&self.counter, 1, Ordering::SeqCst);
-        let mut code = self.encoder.encode(counter_value);
+        let mut code = encode_with_alphabet(counter_value, &self.alphabet);
 
         // Ensure minimum length by adding random prefix if needed
         if code.len() < min_length {
@@ -462,19 +689,7 @@ impl ShortCodeGenerator {
 
     /// Generate random prefix for padding
     fn generate_random_prefix(&self, length: usize) -> String {
-        // Use the Base62Encoder's generate_random method
-        self.encoder.generate_random(length).unwrap_or_else(|_| {
-            // Fallback if encoder fails
-            let mut rng = thread_rng();
-            (0..length)
-                .map(|_| {
-                    let idx = rng.gen_range(0..62);
-                    // Use the same alphabet as base62.rs
-                    let alphabet = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
-                    alphabet.chars().nth(idx).unwrap()
-                })
-                .collect()
-        })
+        random_with_alphabet(length, &self.alphabet)
     }
 
     /// Check collision rate and adjust default length if needed
@@ -553,25 +768,15 @@ impl ShortCodeGenerator {
         }
     }
 
-    /// Generate a random Base62 code of specified length
+    /// Generate a random code of specified length from the configured alphabet
     pub fn generate_random_code(&self, length: usize) -> String {
-        // Use our Base62Encoder for consistent generation
-        self.encoder.generate_random(length).unwrap_or_else(|_| {
-            // Fallback to manual generation if encoder fails
-            let mut rng = thread_rng();
-            let alphabet = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
-            (0..length)
-                .map(|_| {
-                    let idx = rng.gen_range(0..62);
-                    alphabet.chars().nth(idx).unwrap()
-                })
-                .collect()
-        })
+        random_with_alphabet(length, &self.alphabet)
     }
 
-    /// Check if a code is in the reserved list
+    /// Check if a code is in the reserved list or a reserved path segment
     fn is_reserved_code(&self, code: &str) -> bool {
         self.reserved_codes.contains(&code.to_lowercase())
+            || crate::utils::custom_alias_validator::is_reserved_path_segment(code)
     }
 
     /// Check if code contains profanity
@@ -634,9 +839,25 @@ impl ShortCodeGenerator {
         use diesel::dsl::exists;
         use diesel::select;
 
-        let code_exists: bool = select(exists(
-            links.filter(short_code.eq(code).or(custom_alias.eq(code))),
-        ))
+        // Codes held by the pre-generated pool or by a landing page count as taken
+        let code_exists: bool = select(
+            exists(
+                links.filter(
+                    short_code
+                        .eq(code)
+                        .or(lower(custom_alias).eq(code.to_lowercase())),
+                ),
+            )
+            .or(exists(
+                crate::schema::reserved_short_codes::table
+                    .filter(crate::schema::reserved_short_codes::code.eq(code)),
+            ))
+            .or(exists(crate::schema::pages::table.filter(
+                crate::schema::pages::short_code
+                    .eq(code)
+                    .or(crate::schema::pages::short_code.eq(code.to_lowercase())),
+            ))),
+        )
         .get_result(&mut conn)
         .await?;
 
@@ -810,8 +1031,11 @@ impl ShortCodeGenerator {
         while candidates.len() < count * 2 && attempts < max_total_attempts {
             let candidate = self.generate_random_code(length);
 
-            // Skip if reserved or duplicate in batch
-            if !self.is_reserved_code(&candidate) && !candidates.contains(&candidate) {
+            // Skip if reserved, profane or duplicate in batch
+            if !self.is_reserved_code(&candidate)
+                && !self.contains_profanity(&candidate)
+                && !candidates.contains(&candidate)
+            {
                 candidates.push(candidate);
             }
             attempts += 1;
@@ -932,19 +1156,45 @@ impl ShortCodeGenerator {
             .load::<String>(&mut conn)
             .await?;
 
-        // Also check custom aliases
+        // Also check custom aliases, which match in any case
+        let lowercase_codes: Vec<String> = codes.iter().map(|c| c.to_lowercase()).collect();
         let existing_aliases: Vec<Option<String>> = links
-            .select(custom_alias)
-            .filter(custom_alias.eq_any(codes))
+            .select(lower(custom_alias))
+            .filter(lower(custom_alias).eq_any(&lowercase_codes))
             .load::<Option<String>>(&mut conn)
             .await?;
 
-        let existing_aliases: Vec<String> = existing_aliases.into_iter().flatten().collect();
+        let mut existing_aliases: Vec<String> = existing_aliases.into_iter().flatten().collect();
+
+        // Codes already held by the pre-generated pool
+        existing_aliases.extend(
+            crate::schema::reserved_short_codes::table
+                .select(crate::schema::reserved_short_codes::code)
+                .filter(crate::schema::reserved_short_codes::code.eq_any(codes))
+                .load::<String>(&mut conn)
+                .await?,
+        );
+
+        // Landing page codes share the namespace
+        existing_aliases.extend(
+            crate::schema::pages::table
+                .select(crate::schema::pages::short_code)
+                .filter(
+                    crate::schema::pages::short_code
+                        .eq_any(codes)
+                        .or(crate::schema::pages::short_code.eq_any(&lowercase_codes)),
+                )
+                .load::<String>(&mut conn)
+                .await?,
+        );
 
         // Filter out codes that exist
         let mut unique_codes = Vec::new();
         for code in codes {
-            if !existing_codes.contains(code) && !existing_aliases.contains(code) {
+            if !existing_codes.contains(code)
+                && !existing_aliases.contains(code)
+                && !existing_aliases.contains(&code.to_lowercase())
+            {
                 unique_codes.push(code.clone());
             }
         }
@@ -1004,9 +1254,9 @@ impl ShortCodeGenerator {
             .map(|(_, count)| *count)
             .unwrap_or(0);
 
-        let max_combinations = 62u64.pow(current_length as u32);
-        let utilization_percentage = if max_combinations > 0 {
-            (current_length_count as f64 / max_combinations as f64) * 100.0
+        let max_combinations = (self.alphabet.len() as f64).powi(current_length as i32);
+        let utilization_percentage = if max_combinations > 0.0 {
+            (current_length_count as f64 / max_combinations) * 100.0
         } else {
             0.0
         };
@@ -1028,10 +1278,92 @@ impl ShortCodeGenerator {
             reserved_codes_count: self.reserved_codes.len(),
             collision_rate,
             current_counter: AtomicU64::load(&self.counter, Ordering::Relaxed),
+            alphabet_size: self.alphabet.len(),
+            length_escalations: AtomicU64::load(&self.length_escalations, Ordering::Relaxed),
+            pool_size: self.get_pool_size().await,
+            pool_hit_rate: pool_hit_rate(),
         })
     }
 }
 
+/// Split a `code:reserved_at` pool entry
+fn parse_pool_entry(entry: &str) -> Option<(&str, i64)> {
+    let (code, reserved_at) = entry.rsplit_once(':')?;
+    Some((code, reserved_at.parse().ok()?))
+}
+
+/// Share of code requests served from the pool since startup
+fn pool_hit_rate() -> f64 {
+    let hits = AtomicU64::load(&POOL_HITS, Ordering::Relaxed);
+    let total = hits + AtomicU64::load(&POOL_MISSES, Ordering::Relaxed);
+    if total > 0 {
+        hits as f64 / total as f64
+    } else {
+        0.0
+    }
+}
+
+/// Keep the shared short code pool topped up and recycle stale reservations
+pub fn spawn_short_code_pool_task(state: AppState) {
+    if state.config.short_code_pool_size == 0 {
+        info!("Short code pool disabled (SHORT_CODE_POOL_SIZE=0)");
+        return;
+    }
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("short_code_pool", redis_pool, move |reporter| {
+        let generator = ShortCodeGenerator::with_redis(
+            state.diesel_pool.clone(),
+            Some(state.redis_pool.clone()),
+        );
+        async move {
+            let mut interval = tokio::time::interval(POOL_REFILL_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+                let mut failed = false;
+
+                match generator.recycle_expired_reservations().await {
+                    Ok(0) => {},
+                    Ok(recycled) => info!("Recycled {} expired short code reservations", recycled),
+                    Err(e) => {
+                        error!("Short code reservation recycling failed: {}", e);
+                        reporter.failure(&e);
+                        failed = true;
+                    },
+                }
+
+                match generator.replenish_pool().await {
+                    Ok(0) => {},
+                    Ok(added) => info!("Short code pool replenished with {} codes", added),
+                    Err(e) => {
+                        error!("Short code pool replenishment failed: {}", e);
+                        reporter.failure(&e);
+                        failed = true;
+                    },
+                }
+
+                if !failed {
+                    reporter.success();
+                }
+            }
+        }
+    });
+}
+
+/// Length for the next attempt: one longer for every `escalate_after` collisions, capped at `max_length`
+fn escalated_length(
+    length: usize,
+    collisions: usize,
+    escalate_after: usize,
+    max_length: usize,
+) -> usize {
+    std::cmp::min(length + collisions / escalate_after.max(1), max_length).max(length)
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -1074,6 +1406,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_parse_pool_entry() {
+        assert_eq!(parse_pool_entry("aB3xK9q:1700000000"), Some(("aB3xK9q", 1700000000)));
+        assert_eq!(parse_pool_entry("aB3xK9q"), None);
+        assert_eq!(parse_pool_entry("aB3xK9q:soon"), None);
+    }
+
+    #[test]
+    fn test_escalated_length() {
+        assert_eq!(escalated_length(6, 0, 2, 12), 6);
+        assert_eq!(escalated_length(6, 1, 2, 12), 6);
+        assert_eq!(escalated_length(6, 2, 2, 12), 7);
+        assert_eq!(escalated_length(6, 5, 2, 12), 8);
+        // Never past the configured maximum
+        assert_eq!(escalated_length(11, 6, 2, 12), 12);
+        assert_eq!(escalated_length(12, 6, 2, 12), 12);
+    }
+
     #[tokio::test]
     async fn test_reserved_codes() {
         // This would require a mock pool - skipping for now
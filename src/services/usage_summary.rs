@@ -0,0 +1,330 @@
+// Monthly account usage summaries
+// Backs GET /v1/analytics/summary and the opt-in digest email sent on the 1st of each month
+
+use chrono::{Datelike, NaiveDate, Utc};
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+    app::AppState,
+    db::{DieselPool, StatsSource},
+    models::{
+        analytics::{SummaryPeriod, TopLinkSummary, UsageSummaryResponse, SUMMARY_TOP_LINKS},
+        notification_preferences::NotificationKind,
+        user::User,
+    },
+    schema::{links, users},
+    services::{
+        background_tasks::TASK_REGISTRY,
+        clickhouse_analytics::{stats_source_for, ClickHouseAnalyticsService},
+        email::EmailService,
+    },
+    utils::{service_error::ServiceError, timezone::start_of_day},
+};
+
+/// How often the digest task checks for pending digests
+const DIGEST_CHECK_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Users claimed per digest batch
+const DIGEST_BATCH_SIZE: i64 = 100;
+
+/// Days into the month during which an interrupted digest run is resumed
+const DIGEST_CATCH_UP_DAYS: u32 = 3;
+
+pub struct UsageSummaryService {
+    diesel_pool: DieselPool,
+    analytics: Option<Arc<ClickHouseAnalyticsService>>,
+    raw_retention_days: u32,
+}
+
+impl UsageSummaryService {
+    pub fn new(state: &AppState) -> Self {
+        Self {
+            diesel_pool: state.diesel_pool.clone(),
+            analytics: state.clickhouse_analytics.clone(),
+            raw_retention_days: state.config.clickhouse.raw_retention_days,
+        }
+    }
+
+    /// A user's totals for one month of days in `tz`; clicks come from a single ClickHouse
+    /// aggregate query
+    pub async fn summarize(
+        &self,
+        user_id: Uuid,
+        period: SummaryPeriod,
+        today: NaiveDate,
+        tz: Tz,
+    ) -> Result<UsageSummaryResponse, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let (start, end) = period_bounds(period, tz);
+
+        // Deleted links still count: they may have been clicked during the month
+        let owned: Vec<(Uuid, String)> = links::table
+            .filter(links::user_id.eq(user_id))
+            .select((links::id, links::short_code))
+            .load(&mut conn)
+            .await?;
+
+        let links_created: i64 = links::table
+            .filter(links::user_id.eq(user_id))
+            .filter(links::created_at.ge(start))
+            .filter(links::created_at.lt(end))
+            .count()
+            .get_result(&mut conn)
+            .await?;
+
+        let mut summary = UsageSummaryResponse::empty(period, links_created);
+        summary.timezone = tz.name().to_string();
+        let Some(ref analytics) = self.analytics else {
+            return Ok(summary);
+        };
+
+        let link_ids: Vec<Uuid> = owned.iter().map(|(id, _)| *id).collect();
+        let source = stats_source_for(period.first_day(), today, self.raw_retention_days);
+        if source == StatsSource::Rollup {
+            summary.timezone = Tz::UTC.name().to_string();
+        }
+        let clicks = analytics
+            .get_usage_clicks(&link_ids, period.first_day(), period.last_day(), source, tz)
+            .await
+            .map_err(|e| {
+                error!("Failed to summarize usage for {}: {}", user_id, e);
+                ServiceError::InternalError
+            })?;
+
+        let short_codes: HashMap<Uuid, String> = owned.into_iter().collect();
+        summary.total_clicks = clicks.total_clicks;
+        summary.unique_visitors = clicks.unique_visitors;
+        summary.top_links = clicks
+            .links
+            .into_iter()
+            .filter_map(|(link_id, clicks)| {
+                Some(TopLinkSummary {
+                    short_code: short_codes.get(&link_id)?.clone(),
+                    link_id,
+                    clicks,
+                })
+            })
+            .take(SUMMARY_TOP_LINKS)
+            .collect();
+        summary.top_referrer = clicks.top_referrer;
+        summary.top_country = clicks.top_country;
+
+        Ok(summary)
+    }
+
+    /// Opted-in users that have not yet been sent a digest for `period`
+    pub async fn pending_digest_batch(
+        &self,
+        period: SummaryPeriod,
+        limit: i64,
+    ) -> Result<Vec<User>, ServiceError> {
+        let mut conn = self.get_conn().await?;
+
+        let batch = users::table
+            .filter(users::monthly_digest_enabled.eq(true))
+            .filter(users::is_active.eq(true))
+            .filter(
+                users::last_digest_period
+                    .is_null()
+                    .or(users::last_digest_period.ne(period.to_string())),
+            )
+            .order(users::id.asc())
+            .limit(limit)
+            .select(User::as_select())
+            .load(&mut conn)
+            .await?;
+
+        Ok(batch)
+    }
+
+    /// Record that `period`'s digest is being sent to a user.
+    /// Returns `false` when another run already claimed it or the user opted out meanwhile.
+    pub async fn claim_digest(
+        &self,
+        user_id: Uuid,
+        period: SummaryPeriod,
+    ) -> Result<bool, ServiceError> {
+        let mut conn = self.get_conn().await?;
+        let period = period.to_string();
+
+        let claimed = diesel::update(
+            users::table
+                .find(user_id)
+                .filter(users::monthly_digest_enabled.eq(true))
+                .filter(
+                    users::last_digest_period
+                        .is_null()
+                        .or(users::last_digest_period.ne(&period)),
+                ),
+        )
+        .set(users::last_digest_period.eq(Some(&period)))
+        .execute(&mut conn)
+        .await?;
+
+        Ok(claimed == 1)
+    }
+
+    /// Send every pending digest for `period`. Safe to re-run after a restart:
+    /// users are claimed one at a time, so finished users are skipped and none is mailed twice.
+    pub async fn send_pending_digests(
+        &self,
+        email_service: &EmailService,
+        period: SummaryPeriod,
+        today: NaiveDate,
+    ) -> Result<usize, ServiceError> {
+        let mut sent = 0;
+
+        loop {
+            let batch = self.pending_digest_batch(period, DIGEST_BATCH_SIZE).await?;
+            if batch.is_empty() {
+                return Ok(sent);
+            }
+
+            for user in batch {
+                // Opted out through the notification settings: claim the period so the user
+                // leaves the pending batch, but send nothing
+                if !user.wants_notification(NotificationKind::MonthlySummary) {
+                    self.claim_digest(user.id, period).await?;
+                    continue;
+                }
+
+                // Summarize before claiming so a ClickHouse outage leaves the user pending.
+                // Digests go out as the UTC month ends, when some zones are still in it,
+                // so they count UTC days
+                let summary = self.summarize(user.id, period, today, Tz::UTC).await?;
+                if !self.claim_digest(user.id, period).await? {
+                    continue;
+                }
+
+                match email_service
+                    .send_monthly_digest(&user.email, &user.full_name, &summary)
+                    .await
+                {
+                    Ok(()) => sent += 1,
+                    Err(e) => warn!("Failed to send {} digest to {}: {}", period, user.id, e),
+                }
+            }
+        }
+    }
+
+    async fn get_conn(
+        &self,
+    ) -> Result<
+        bb8::PooledConnection<
+            '_,
+            diesel_async::pooled_connection::AsyncDieselConnectionManager<
+                diesel_async::AsyncPgConnection,
+            >,
+        >,
+        ServiceError,
+    > {
+        self.diesel_pool
+            .get()
+            .await
+            .map_err(|e| ServiceError::DatabaseError(e.to_string()))
+    }
+}
+
+/// Instants bounding a month in `tz`: [first day 00:00, first day of next month 00:00)
+fn period_bounds(period: SummaryPeriod, tz: Tz) -> (chrono::DateTime<Utc>, chrono::DateTime<Utc>) {
+    let next_month = period.last_day().succ_opt().unwrap_or(period.last_day());
+    (
+        start_of_day(period.first_day(), tz),
+        start_of_day(next_month, tz),
+    )
+}
+
+/// Month whose digest is due on `today`, if any. Runs on the 1st and resumes
+/// during the first few days so a restart mid-run still finishes the batch.
+pub fn digest_period_due(today: NaiveDate) -> Option<SummaryPeriod> {
+    (today.day() <= DIGEST_CATCH_UP_DAYS).then(|| SummaryPeriod::previous(today))
+}
+
+/// Send last month's usage digest to opted-in users at the start of each month
+pub fn spawn_monthly_digest_task(state: AppState) {
+    if !state.config.email.monthly_digest_enabled {
+        info!("Monthly usage digest disabled (MONTHLY_DIGEST_ENABLED=false)");
+        return;
+    }
+
+    let redis_pool = state.redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("monthly_digest", redis_pool, move |reporter| {
+        let state = state.clone();
+        async move {
+            let service = UsageSummaryService::new(&state);
+            let mut interval = tokio::time::interval(DIGEST_CHECK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                let today = Utc::now().date_naive();
+                let Some(period) = digest_period_due(today) else {
+                    reporter.success();
+                    continue;
+                };
+
+                match service
+                    .send_pending_digests(&state.email_service, period, today)
+                    .await
+                {
+                    Ok(sent) => {
+                        if sent > 0 {
+                            info!("Sent {} monthly usage digests for {}", sent, period);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("Monthly usage digest run for {} failed: {}", period, e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_digest_period_due() {
+        assert_eq!(
+            digest_period_due(day("2024-07-01")).map(|p| p.to_string()),
+            Some("2024-06".to_string())
+        );
+        assert_eq!(
+            digest_period_due(day("2025-01-03")).map(|p| p.to_string()),
+            Some("2024-12".to_string())
+        );
+        assert_eq!(digest_period_due(day("2024-07-04")), None);
+    }
+
+    #[test]
+    fn test_period_bounds_cover_whole_month() {
+        let (start, end) = period_bounds(SummaryPeriod::parse("2024-12").unwrap(), Tz::UTC);
+        assert_eq!(start.to_rfc3339(), "2024-12-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+
+        let (start, end) = period_bounds(
+            SummaryPeriod::parse("2024-12").unwrap(),
+            Tz::America__New_York,
+        );
+        assert_eq!(start.to_rfc3339(), "2024-12-01T05:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2025-01-01T05:00:00+00:00");
+    }
+}
@@ -0,0 +1,198 @@
+// Outbound webhook delivery
+// Signs JSON payloads with HMAC-SHA256 and retries failed deliveries with
+// exponential backoff. Callers spawn deliveries so they never block a request.
+
+use crate::utils::outbound_proxy;
+use chrono::Utc;
+use reqwest::Client;
+use ring::hmac;
+use serde::Serialize;
+use std::time::Duration;
+use thiserror::Error;
+use tracing::{info, instrument, warn};
+use uuid::Uuid;
+
+/// Header carrying `sha256=<hex hmac>` of `"{timestamp}.{body}"`
+pub const SIGNATURE_HEADER: &str = "X-QCK-Signature";
+/// Unix timestamp included in the signed content, so receivers can reject replays
+pub const TIMESTAMP_HEADER: &str = "X-QCK-Timestamp";
+/// Event name, e.g. `security.url_blocked`
+pub const EVENT_HEADER: &str = "X-QCK-Event";
+/// Unique id shared by all attempts of one delivery
+pub const DELIVERY_HEADER: &str = "X-QCK-Delivery";
+
+// =============================================================================
+// ERROR TYPES
+// =============================================================================
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("Failed to serialize webhook payload: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    #[error("Webhook request failed: {0}")]
+    Http(#[from] reqwest::Error),
+
+    #[error("Webhook endpoint responded with status {0}")]
+    Status(u16),
+}
+
+impl WebhookError {
+    /// Client errors (other than 429) mean the receiver rejected the payload; retrying won't help
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            WebhookError::Serialization(_) => false,
+            WebhookError::Http(_) => true,
+            WebhookError::Status(status) => *status == 429 || *status >= 500,
+        }
+    }
+}
+
+// =============================================================================
+// SIGNING
+// =============================================================================
+
+/// HMAC-SHA256 signature over `"{timestamp}.{body}"`, formatted for the signature header
+pub fn sign_payload(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    let mut context = hmac::Context::with_key(&key);
+    context.update(timestamp.to_string().as_bytes());
+    context.update(b".");
+    context.update(body);
+
+    let digest: String = context
+        .sign()
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("sha256={}", digest)
+}
+
+// =============================================================================
+// WEBHOOK SENDER
+// =============================================================================
+
+/// Delivers signed webhook payloads with retry
+#[derive(Clone)]
+pub struct WebhookSender {
+    client: Client,
+    max_retries: u32,
+    retry_delay: Duration,
+}
+
+impl WebhookSender {
+    pub fn new() -> Self {
+        Self {
+            client: outbound_proxy::apply(Client::builder())
+                .timeout(Duration::from_secs(10))
+                .user_agent("QCK-Webhooks/1.0")
+                .build()
+                .unwrap_or_default(),
+            max_retries: 3,
+            retry_delay: Duration::from_secs(1),
+        }
+    }
+
+    /// Set maximum delivery attempts
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries.max(1);
+        self
+    }
+
+    /// Set base delay between attempts (doubled after each failure)
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Send a single signed delivery attempt
+    pub async fn send<T: Serialize>(
+        &self,
+        url: &str,
+        secret: &str,
+        event: &str,
+        delivery_id: Uuid,
+        payload: &T,
+    ) -> Result<(), WebhookError> {
+        let body = serde_json::to_vec(payload)?;
+        let timestamp = Utc::now().timestamp();
+
+        let response = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event)
+            .header(DELIVERY_HEADER, delivery_id.to_string())
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, &body))
+            .body(body)
+            .send()
+            .await?;
+
+        if response.status().is_success() {
+            Ok(())
+        } else {
+            Err(WebhookError::Status(response.status().as_u16()))
+        }
+    }
+
+    /// Deliver a payload, retrying transient failures with exponential backoff
+    #[instrument(skip(self, secret, payload), fields(event = %event))]
+    pub async fn deliver<T: Serialize>(
+        &self,
+        url: &str,
+        secret: &str,
+        event: &str,
+        payload: &T,
+    ) -> Result<(), WebhookError> {
+        let delivery_id = Uuid::new_v4();
+        let mut attempt = 1;
+
+        loop {
+            match self.send(url, secret, event, delivery_id, payload).await {
+                Ok(()) => return Ok(()),
+                Err(e) if !e.is_retryable() || attempt >= self.max_retries => {
+                    warn!(
+                        "Webhook delivery {} failed after {} attempt(s): {}",
+                        delivery_id, attempt, e
+                    );
+                    return Err(e);
+                },
+                Err(e) => {
+                    warn!(
+                        "Webhook delivery {} attempt {} failed: {}",
+                        delivery_id, attempt, e
+                    );
+
+                    let delay = self.backoff_delay(attempt);
+                    info!("Retrying webhook delivery in {:?} (with jitter)", delay);
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                },
+            }
+        }
+    }
+
+    /// Exponential backoff capped at 60 seconds, plus 0-25% jitter
+    fn backoff_delay(&self, attempt: u32) -> Duration {
+        use rand::Rng;
+
+        let max_delay = Duration::from_secs(60);
+        let exp = 2_u32.checked_pow(attempt - 1).unwrap_or(u32::MAX);
+        let base_delay = self
+            .retry_delay
+            .checked_mul(exp)
+            .unwrap_or(max_delay)
+            .min(max_delay);
+
+        let jitter_millis = rand::thread_rng().gen_range(0..=(base_delay.as_millis() / 4) as u64);
+        base_delay + Duration::from_millis(jitter_millis)
+    }
+}
+
+impl Default for WebhookSender {
+    fn default() -> Self {
+        Self::new()
+    }
+}
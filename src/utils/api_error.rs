@@ -0,0 +1,388 @@
+// Unified error envelope returned by every JSON endpoint
+// ServiceError, AuthError, LinkError and validator errors all render through ApiError
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{ValidationErrors, ValidationErrorsKind};
+
+use crate::middleware::request_id::current_request_id;
+
+/// Error body returned by all JSON endpoints
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[schema(example = json!({
+    "code": "VALIDATION_ERROR",
+    "message": "URL must be at most 2048 bytes (got 2301)",
+    "details": [{
+        "field": "url",
+        "code": "length",
+        "message": "URL must be at most 2048 bytes (got 2301)",
+        "params": { "current": 2301, "max": 2048 }
+    }],
+    "request_id": "01J9Z6Q4T8X2M5K7C3V1B0N6HD"
+}))]
+pub struct ApiError {
+    /// HTTP status, sent as the response status only
+    #[serde(skip)]
+    pub status: StatusCode,
+    /// Seconds until the client may retry, sent as the `Retry-After` header
+    #[serde(skip)]
+    pub retry_after: Option<u64>,
+    /// Stable machine-readable code, e.g. `VALIDATION_ERROR`
+    #[schema(example = "VALIDATION_ERROR")]
+    pub code: String,
+    /// Human-readable description; auth validation failures list every problem as
+    /// `field: message`, comma-separated
+    #[schema(example = "password: Password must contain an uppercase letter")]
+    pub message: String,
+    /// Per-field problems; empty unless the request failed validation
+    /// A field with several problems appears once per problem
+    pub details: Vec<FieldError>,
+    /// Matches the `X-Request-Id` response header
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Security incident recorded for this error, e.g. on `TOKEN_REUSE_DETECTED`;
+    /// listed at `GET /v1/auth/security-events`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub incident_id: Option<Uuid>,
+}
+
+/// A single invalid input field
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[schema(example = json!({
+    "field": "custom_alias",
+    "code": "length",
+    "message": "Custom alias must be 3-50 characters",
+    "params": { "min": 3, "max": 50, "value": "ab" }
+}))]
+pub struct FieldError {
+    /// Field path, e.g. `email` or `links[2].url`
+    pub field: String,
+    /// Validator code, e.g. `length` or `email`
+    /// Password policy failures use `too_short`, `missing_uppercase`, `missing_lowercase`,
+    /// `missing_digit` and `missing_special`
+    #[schema(example = "missing_uppercase")]
+    pub code: String,
+    pub message: String,
+    /// Values behind the failure, e.g. `current` and `max` for an over-long URL
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    #[schema(value_type = Object)]
+    pub params: serde_json::Map<String, serde_json::Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            retry_after: None,
+            code: code.to_string(),
+            message: message.into(),
+            details: Vec::new(),
+            request_id: current_request_id(),
+            incident_id: None,
+        }
+    }
+
+    pub fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "BAD_REQUEST", message)
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNAUTHORIZED, "UNAUTHORIZED", message)
+    }
+
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::FORBIDDEN, "FORBIDDEN", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "NOT_FOUND", message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "INTERNAL_ERROR", message)
+    }
+
+    /// Single-field validation failure for checks that run outside `validator`
+    pub fn invalid_field(field: &str, code: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "VALIDATION_ERROR",
+            message.clone(),
+        )
+        .with_details(vec![FieldError {
+            field: field.to_string(),
+            code: code.to_string(),
+            message,
+            params: serde_json::Map::new(),
+        }])
+    }
+
+    /// Override the status, e.g. to report validation failures as 400 where an endpoint always has
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_retry_after(mut self, seconds: u64) -> Self {
+        self.retry_after = Some(seconds);
+        self
+    }
+
+    pub fn with_incident_id(mut self, incident_id: Option<Uuid>) -> Self {
+        self.incident_id = incident_id;
+        self
+    }
+
+    pub fn with_details(mut self, details: Vec<FieldError>) -> Self {
+        self.details = details;
+        self
+    }
+
+    /// Use the details joined as `field: message` for the message, the format auth
+    /// endpoints returned before `details` existed
+    pub fn with_field_summary(mut self) -> Self {
+        if !self.details.is_empty() {
+            self.message = self
+                .details
+                .iter()
+                .map(|d| format!("{}: {}", d.field, d.message))
+                .collect::<Vec<_>>()
+                .join(", ");
+        }
+        self
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.status;
+        let retry_after = self.retry_after;
+        let mut response = (status, Json(self)).into_response();
+        if let Some(seconds) = retry_after {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(seconds));
+        }
+        response
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        let mut details = Vec::new();
+        collect_field_errors(&errors, "", &mut details);
+        details.sort_by(|a, b| a.field.cmp(&b.field));
+
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "VALIDATION_ERROR",
+            "Request validation failed",
+        )
+        .with_details(details)
+    }
+}
+
+impl From<crate::models::link::TagError> for ApiError {
+    fn from(error: crate::models::link::TagError) -> Self {
+        ApiError::invalid_field(&error.field(), error.code(), error.to_string())
+    }
+}
+
+impl From<crate::models::link::ReferrerDomainError> for ApiError {
+    fn from(error: crate::models::link::ReferrerDomainError) -> Self {
+        ApiError::invalid_field(&error.field(), error.code(), error.to_string())
+    }
+}
+
+impl From<crate::models::link::LinkRequestError> for ApiError {
+    fn from(error: crate::models::link::LinkRequestError) -> Self {
+        let mut api_error = ApiError::invalid_field(error.field(), error.code(), error.to_string());
+        if let crate::models::link::LinkRequestError::UrlTooLong { current, max } = error {
+            api_error.details[0].params = serde_json::Map::from_iter([
+                ("current".to_string(), current.into()),
+                ("max".to_string(), max.into()),
+            ]);
+        }
+        api_error
+    }
+}
+
+/// Flatten nested struct and list errors into dotted field paths
+fn collect_field_errors(errors: &ValidationErrors, prefix: &str, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = if prefix.is_empty() {
+            field.to_string()
+        } else {
+            format!("{}.{}", prefix, field)
+        };
+        match kind {
+            ValidationErrorsKind::Field(field_errors) => {
+                out.extend(field_errors.iter().map(|e| {
+                    FieldError {
+                        field: path.clone(),
+                        code: e.code.to_string(),
+                        message: e
+                            .message
+                            .as_ref()
+                            .map(|m| m.to_string())
+                            .unwrap_or_else(|| format!("{} is invalid", path)),
+                        params: serde_json::Map::new(),
+                    }
+                }));
+            },
+            ValidationErrorsKind::Struct(nested) => collect_field_errors(nested, &path, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, nested) in items {
+                    collect_field_errors(nested, &format!("{}[{}]", path, index), out);
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use validator::Validate;
+
+    #[derive(Validate)]
+    struct Item {
+        #[validate(url(message = "Must be a valid URL"))]
+        url: String,
+    }
+
+    #[derive(Validate)]
+    struct Payload {
+        #[validate(email)]
+        email: String,
+        #[validate]
+        items: Vec<Item>,
+    }
+
+    #[test]
+    fn test_validation_errors_become_field_details() {
+        let payload = Payload {
+            email: "not-an-email".to_string(),
+            items: vec![
+                Item {
+                    url: "https://example.com".to_string(),
+                },
+                Item {
+                    url: "nope".to_string(),
+                },
+            ],
+        };
+        let error = ApiError::from(payload.validate().unwrap_err());
+
+        assert_eq!(error.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.code, "VALIDATION_ERROR");
+        assert_eq!(
+            error.details,
+            vec![
+                FieldError {
+                    field: "email".to_string(),
+                    code: "email".to_string(),
+                    message: "email is invalid".to_string(),
+                    params: serde_json::Map::new(),
+                },
+                FieldError {
+                    field: "items[1].url".to_string(),
+                    code: "url".to_string(),
+                    message: "Must be a valid URL".to_string(),
+                    params: serde_json::Map::new(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_field_summary_message() {
+        let error = ApiError::from(
+            Payload {
+                email: "not-an-email".to_string(),
+                items: vec![Item {
+                    url: "nope".to_string(),
+                }],
+            }
+            .validate()
+            .unwrap_err(),
+        )
+        .with_field_summary();
+
+        assert_eq!(
+            error.message,
+            "email: email is invalid, items[0].url: Must be a valid URL"
+        );
+        assert_eq!(error.details.len(), 2);
+
+        let error = ApiError::bad_request("Bad input").with_field_summary();
+        assert_eq!(error.message, "Bad input");
+    }
+
+    #[test]
+    fn test_tag_error_names_index() {
+        let error = ApiError::from(crate::models::link::TagError::TooLong { index: 3 });
+
+        assert_eq!(error.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(error.details.len(), 1);
+        assert_eq!(error.details[0].field, "tags[3]");
+        assert_eq!(error.details[0].code, "length");
+    }
+
+    #[test]
+    fn test_url_too_long_reports_current_and_max() {
+        let error = ApiError::from(crate::models::link::LinkRequestError::UrlTooLong {
+            current: 2049,
+            max: 2048,
+        });
+        let body = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(error.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["details"][0]["field"], "url");
+        assert_eq!(body["details"][0]["code"], "length");
+        assert_eq!(body["details"][0]["params"]["current"], 2049);
+        assert_eq!(body["details"][0]["params"]["max"], 2048);
+    }
+
+    #[test]
+    fn test_link_limit_reports_current_and_limit() {
+        let error = ApiError::from(crate::utils::service_error::ServiceError::LinkLimitReached {
+            current: 1000,
+            limit: 1000,
+        });
+        let body = serde_json::to_value(&error).unwrap();
+
+        assert_eq!(error.status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "LINK_LIMIT_REACHED");
+        assert_eq!(body["details"][0]["code"], "limit_reached");
+        assert_eq!(body["details"][0]["params"]["current"], 1000);
+        assert_eq!(body["details"][0]["params"]["limit"], 1000);
+    }
+
+    #[test]
+    fn test_envelope_shape() {
+        let body = serde_json::to_value(ApiError::not_found("Link not found")).unwrap();
+
+        assert_eq!(body["code"], "NOT_FOUND");
+        assert_eq!(body["message"], "Link not found");
+        assert_eq!(body["details"], serde_json::json!([]));
+        assert!(body.get("status").is_none());
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let response = ApiError::new(StatusCode::TOO_MANY_REQUESTS, "RATE_LIMITED", "Slow down")
+            .with_retry_after(30)
+            .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "30");
+    }
+}
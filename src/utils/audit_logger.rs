@@ -1,10 +1,34 @@
 // DEV-114: Audit logging for all CRUD operations
+// Entries go to tracing and, once `AuditLogger::init` has run, to the audit_logs table.
+// Writes are queued and batched by a background task so handlers never wait on them.
+
 use chrono::{DateTime, Utc};
+use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
-use tracing::{info, warn};
+use tokio::sync::mpsc;
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::{
+    db::DieselPool,
+    models::audit_log::NewAuditLogRecord,
+    services::audit_log::AuditLogService,
+    utils::{
+        auth_errors::{AuthAuditEntry, AuthEventType},
+        service_error::ServiceError,
+    },
+};
+
+/// Entries buffered before new ones are dropped (with a warning)
+const AUDIT_QUEUE_CAPACITY: usize = 10_000;
+
+/// Max entries written per INSERT
+const AUDIT_BATCH_SIZE: usize = 100;
+
+/// Queue feeding the database writer; unset until `AuditLogger::init` runs
+static AUDIT_SINK: OnceCell<mpsc::Sender<AuditLog>> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AuditAction {
     LinkCreated,
     LinkRead,
@@ -16,16 +40,120 @@ pub enum AuditAction {
     LinkAccessed,
     LinkExpired,
     LinkPasswordFailed,
+    LinkPasswordLockout,
+    DomainBlocked,
+    DomainUnblocked,
+    DomainTrusted,
+    DomainUntrusted,
+    IpDenied,
+    IpUndenied,
+    LinkQuarantined,
+    LinkAppealSubmitted,
+    LinkAppealResolved,
+    LinkTransferred,
+    LinksPaused,
+    LinksResumed,
+    UserImpersonated,
+    ImpersonatedRequest,
+    UserDeactivated,
+    AccountExported,
+    AccountImported,
+    OrganizationCreated,
+    OrganizationMemberInvited,
+    OrganizationMemberJoined,
+    OrganizationMemberRemoved,
+    PageCreated,
+    PageUpdated,
+    PageDeleted,
+    LoginSuccess,
+    LoginFailed,
+    LoginRateLimited,
+    AccountLocked,
+    AccountUnlocked,
+    PasswordReset,
+    EmailVerified,
+    SessionsRevoked,
+}
+
+impl AuditAction {
+    /// Name stored in `audit_logs.action` (same as the serialized variant)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::LinkCreated => "LinkCreated",
+            AuditAction::LinkRead => "LinkRead",
+            AuditAction::LinkUpdated => "LinkUpdated",
+            AuditAction::LinkDeleted => "LinkDeleted",
+            AuditAction::LinkPermanentlyDeleted => "LinkPermanentlyDeleted",
+            AuditAction::BulkLinksDeleted => "BulkLinksDeleted",
+            AuditAction::BulkStatusUpdated => "BulkStatusUpdated",
+            AuditAction::LinkAccessed => "LinkAccessed",
+            AuditAction::LinkExpired => "LinkExpired",
+            AuditAction::LinkPasswordFailed => "LinkPasswordFailed",
+            AuditAction::LinkPasswordLockout => "LinkPasswordLockout",
+            AuditAction::DomainBlocked => "DomainBlocked",
+            AuditAction::DomainUnblocked => "DomainUnblocked",
+            AuditAction::DomainTrusted => "DomainTrusted",
+            AuditAction::DomainUntrusted => "DomainUntrusted",
+            AuditAction::IpDenied => "IpDenied",
+            AuditAction::IpUndenied => "IpUndenied",
+            AuditAction::LinkQuarantined => "LinkQuarantined",
+            AuditAction::LinkAppealSubmitted => "LinkAppealSubmitted",
+            AuditAction::LinkAppealResolved => "LinkAppealResolved",
+            AuditAction::LinkTransferred => "LinkTransferred",
+            AuditAction::LinksPaused => "LinksPaused",
+            AuditAction::LinksResumed => "LinksResumed",
+            AuditAction::UserImpersonated => "UserImpersonated",
+            AuditAction::ImpersonatedRequest => "ImpersonatedRequest",
+            AuditAction::UserDeactivated => "UserDeactivated",
+            AuditAction::AccountExported => "AccountExported",
+            AuditAction::AccountImported => "AccountImported",
+            AuditAction::OrganizationCreated => "OrganizationCreated",
+            AuditAction::OrganizationMemberInvited => "OrganizationMemberInvited",
+            AuditAction::OrganizationMemberJoined => "OrganizationMemberJoined",
+            AuditAction::OrganizationMemberRemoved => "OrganizationMemberRemoved",
+            AuditAction::PageCreated => "PageCreated",
+            AuditAction::PageUpdated => "PageUpdated",
+            AuditAction::PageDeleted => "PageDeleted",
+            AuditAction::LoginSuccess => "LoginSuccess",
+            AuditAction::LoginFailed => "LoginFailed",
+            AuditAction::LoginRateLimited => "LoginRateLimited",
+            AuditAction::AccountLocked => "AccountLocked",
+            AuditAction::AccountUnlocked => "AccountUnlocked",
+            AuditAction::PasswordReset => "PasswordReset",
+            AuditAction::EmailVerified => "EmailVerified",
+            AuditAction::SessionsRevoked => "SessionsRevoked",
+        }
+    }
+
+    /// Parse a stored/queried action name
+    pub fn parse(name: &str) -> Option<Self> {
+        serde_json::from_value(serde_json::Value::String(name.to_string())).ok()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl From<&AuthEventType> for AuditAction {
+    fn from(event: &AuthEventType) -> Self {
+        match event {
+            AuthEventType::LoginSuccess => AuditAction::LoginSuccess,
+            AuthEventType::LoginFailed => AuditAction::LoginFailed,
+            AuthEventType::LoginRateLimited => AuditAction::LoginRateLimited,
+            AuthEventType::AccountLocked => AuditAction::AccountLocked,
+            AuthEventType::AccountUnlocked => AuditAction::AccountUnlocked,
+            AuthEventType::PasswordReset => AuditAction::PasswordReset,
+            AuthEventType::EmailVerified => AuditAction::EmailVerified,
+            AuthEventType::SessionsRevoked => AuditAction::SessionsRevoked,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuditLog {
     pub id: Uuid,
     pub action: AuditAction,
-    pub user_id: Uuid,
+    pub user_id: Option<Uuid>,
     pub resource_id: Option<String>,
     pub resource_type: String,
-    pub details: Option<String>,
+    pub details: Option<serde_json::Value>,
     pub ip_address: Option<String>,
     pub user_agent: Option<String>,
     pub timestamp: DateTime<Utc>,
@@ -34,6 +162,19 @@ pub struct AuditLog {
 pub struct AuditLogger;
 
 impl AuditLogger {
+    /// Start persisting audit entries to Postgres. Call once at startup;
+    /// before this (and in tests) entries only go to tracing.
+    pub fn init(diesel_pool: DieselPool) {
+        let (tx, rx) = mpsc::channel(AUDIT_QUEUE_CAPACITY);
+        if AUDIT_SINK.set(tx).is_err() {
+            warn!("Audit log writer already initialized");
+            return;
+        }
+
+        tokio::spawn(run_writer(AuditLogService::new(diesel_pool), rx));
+        info!("Audit log writer started");
+    }
+
     /// Log an audit event for link operations
     pub async fn log_link_action(
         action: AuditAction,
@@ -41,30 +182,79 @@ impl AuditLogger {
         link_id: Option<String>,
         details: Option<String>,
     ) {
+        Self::log_resource_action(action, user_id, "link", link_id, details).await;
+    }
+
+    /// Write a link entry to the audit_logs table before returning, for entries that must
+    /// exist before the caller goes on (the snapshot taken ahead of a permanent delete)
+    pub async fn log_link_action_now(
+        diesel_pool: &DieselPool,
+        action: AuditAction,
+        user_id: Uuid,
+        link_id: Uuid,
+        details: serde_json::Value,
+    ) -> Result<(), ServiceError> {
         let audit_log = AuditLog {
             id: Uuid::new_v4(),
             action,
-            user_id,
-            resource_id: link_id,
+            user_id: Some(user_id),
+            resource_id: Some(link_id.to_string()),
             resource_type: "link".to_string(),
-            details,
-            ip_address: None, // Would be passed from request context
-            user_agent: None, // Would be passed from request context
+            details: Some(details),
+            ip_address: None,
+            user_agent: None,
             timestamp: Utc::now(),
         };
+        Self::trace(&audit_log);
 
-        // Log to tracing system (in production, this would also write to database/queue)
-        let json_log = serde_json::to_string(&audit_log).unwrap_or_else(|e| {
-            warn!("Failed to serialize audit log: {}", e);
-            format!("{:?}", audit_log)
-        });
+        AuditLogService::new(diesel_pool.clone())
+            .insert_batch(vec![NewAuditLogRecord::from(audit_log)])
+            .await
+            .map(|_| ())
+    }
 
-        info!(target: "audit", "{}", json_log);
+    /// Log an audit event for any resource type (links, blocklist entries, ...)
+    pub async fn log_resource_action(
+        action: AuditAction,
+        user_id: Uuid,
+        resource_type: &str,
+        resource_id: Option<String>,
+        details: Option<String>,
+    ) {
+        Self::record(AuditLog {
+            id: Uuid::new_v4(),
+            action,
+            user_id: Some(user_id),
+            resource_id,
+            resource_type: resource_type.to_string(),
+            details: details.map(serde_json::Value::String),
+            ip_address: None, // Would be passed from request context
+            user_agent: None, // Would be passed from request context
+            timestamp: Utc::now(),
+        });
+    }
 
-        // TODO: In production, also write to:
-        // - Database audit table
-        // - Message queue for processing
-        // - External audit service
+    /// Log something an anonymous visitor did to a link (e.g. a wrong unlock password).
+    /// Recorded under the link owner so it shows up in their audit trail.
+    pub fn log_visitor_action(
+        action: AuditAction,
+        owner_id: Uuid,
+        link_id: Uuid,
+        ip_address: &str,
+        user_agent: Option<&str>,
+        details: serde_json::Value,
+    ) {
+        Self::record(AuditLog {
+            id: Uuid::new_v4(),
+            action,
+            user_id: Some(owner_id),
+            resource_id: Some(link_id.to_string()),
+            resource_type: "link".to_string(),
+            details: Some(details),
+            ip_address: Some(ip_address.to_string()),
+            user_agent: user_agent.map(str::to_string),
+            timestamp: Utc::now(),
+        });
     }
 
     /// Log bulk operations
@@ -74,19 +264,86 @@ impl AuditLogger {
         affected_ids: Vec<String>,
         details: Option<String>,
     ) {
-        let audit_log = AuditLog {
+        Self::record(AuditLog {
             id: Uuid::new_v4(),
             action,
-            user_id,
+            user_id: Some(user_id),
             resource_id: Some(format!("{} links", affected_ids.len())),
             resource_type: "bulk_links".to_string(),
-            details: details.or_else(|| Some(format!("Affected IDs: {:?}", affected_ids))),
+            details: Some(serde_json::json!({
+                "message": details,
+                "affected_ids": affected_ids,
+            })),
             ip_address: None,
             user_agent: None,
             timestamp: Utc::now(),
-        };
+        });
+    }
+
+    /// Log a request an admin made while impersonating a user
+    /// Recorded under the impersonated user, so it shows up in their own audit trail,
+    /// with the admin in the details
+    pub fn log_impersonated_request(
+        user_id: Uuid,
+        impersonator_id: Uuid,
+        method: &str,
+        path: &str,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) {
+        Self::record(AuditLog {
+            id: Uuid::new_v4(),
+            action: AuditAction::ImpersonatedRequest,
+            user_id: Some(user_id),
+            resource_id: Some(user_id.to_string()),
+            resource_type: "user".to_string(),
+            details: Some(serde_json::json!({
+                "impersonator_id": impersonator_id,
+                "method": method,
+                "path": path,
+            })),
+            ip_address,
+            user_agent,
+            timestamp: Utc::now(),
+        });
+    }
+
+    /// Log an authentication event (login, lockout, ...)
+    pub fn log_auth_event(entry: &AuthAuditEntry) {
+        let mut details = serde_json::json!({ "email": entry.email });
+        if let Some(ref data) = entry.additional_data {
+            details["data"] = data.clone();
+        }
+
+        Self::record(AuditLog {
+            id: Uuid::new_v4(),
+            action: AuditAction::from(&entry.event_type),
+            user_id: entry
+                .user_id
+                .as_deref()
+                .and_then(|id| Uuid::parse_str(id).ok()),
+            resource_id: entry.user_id.clone(),
+            resource_type: "user".to_string(),
+            details: Some(details),
+            ip_address: Some(entry.ip_address.clone()),
+            user_agent: entry.user_agent.clone(),
+            timestamp: entry.timestamp,
+        });
+    }
 
-        let json_log = serde_json::to_string(&audit_log).unwrap_or_else(|e| {
+    /// Emit to tracing and queue for the database writer without waiting
+    fn record(audit_log: AuditLog) {
+        Self::trace(&audit_log);
+
+        if let Some(sink) = AUDIT_SINK.get() {
+            if let Err(e) = sink.try_send(audit_log) {
+                warn!("Audit log queue unavailable, entry not persisted: {}", e);
+            }
+        }
+    }
+
+    fn trace(audit_log: &AuditLog) {
+        let json_log = serde_json::to_string(audit_log).unwrap_or_else(|e| {
             warn!("Failed to serialize audit log: {}", e);
             format!("{:?}", audit_log)
         });
@@ -94,3 +351,20 @@ impl AuditLogger {
         info!(target: "audit", "{}", json_log);
     }
 }
+
+/// Drain the queue in batches into the audit_logs table
+async fn run_writer(service: AuditLogService, mut rx: mpsc::Receiver<AuditLog>) {
+    let mut batch = Vec::with_capacity(AUDIT_BATCH_SIZE);
+
+    while rx.recv_many(&mut batch, AUDIT_BATCH_SIZE).await > 0 {
+        let records: Vec<NewAuditLogRecord> =
+            batch.drain(..).map(NewAuditLogRecord::from).collect();
+        let count = records.len();
+
+        if let Err(e) = service.insert_batch(records).await {
+            error!("Failed to persist {} audit log entries: {}", count, e);
+        }
+    }
+
+    warn!("Audit log writer stopped");
+}
@@ -1,13 +1,12 @@
 // Authentication-specific error handling utilities
 // DEV-102: Login API with comprehensive error handling
 
-use axum::{
-    http::StatusCode,
-    response::{IntoResponse, Json},
-};
+use axum::{http::StatusCode, response::IntoResponse};
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::utils::api_error::ApiError;
+
 /// Authentication-specific errors
 #[derive(Error, Debug)]
 pub enum AuthError {
@@ -45,22 +44,6 @@ pub enum AuthError {
     InternalError,
 }
 
-/// Standard authentication response structure
-#[derive(Debug, Serialize)]
-pub struct AuthErrorResponse {
-    pub success: bool,
-    pub error: ErrorDetail,
-    pub message: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ErrorDetail {
-    pub code: String,
-    pub description: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub retry_after: Option<u64>,
-}
-
 impl AuthError {
     /// Convert to HTTP status code
     pub fn status_code(&self) -> StatusCode {
@@ -110,20 +93,19 @@ impl AuthError {
     }
 }
 
+impl From<AuthError> for ApiError {
+    fn from(error: AuthError) -> Self {
+        let api_error = ApiError::new(error.status_code(), error.error_code(), error.to_string());
+        match error.retry_after() {
+            Some(seconds) => api_error.with_retry_after(seconds),
+            None => api_error,
+        }
+    }
+}
+
 impl IntoResponse for AuthError {
     fn into_response(self) -> axum::response::Response {
-        let status = self.status_code();
-        let response = AuthErrorResponse {
-            success: false,
-            error: ErrorDetail {
-                code: self.error_code().to_string(),
-                description: self.to_string(),
-                retry_after: self.retry_after(),
-            },
-            message: self.to_string(),
-        };
-
-        (status, Json(response)).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -141,6 +123,19 @@ pub fn log_auth_failure(
         error_code = error.error_code(),
         "Authentication failure"
     );
+
+    let event_type = match error {
+        AuthError::RateLimited { .. } => AuthEventType::LoginRateLimited,
+        _ => AuthEventType::LoginFailed,
+    };
+    crate::utils::audit_logger::AuditLogger::log_auth_event(&create_auth_audit_entry(
+        event_type,
+        None,
+        user_email,
+        ip_address,
+        user_agent,
+        Some(serde_json::json!({ "error_code": error.error_code() })),
+    ));
 }
 
 /// Helper function to create audit log entry for authentication events
@@ -169,12 +164,13 @@ pub enum AuthEventType {
     LoginFailed,
     LoginRateLimited,
     AccountLocked,
-    // TODO: Implement audit logging for AccountUnlocked events
     AccountUnlocked,
     // TODO: Implement audit logging for PasswordReset events
     PasswordReset,
     // TODO: Implement audit logging for EmailVerified events
     EmailVerified,
+    /// Signed out of every device (`POST /v1/auth/sessions/revoke-all`)
+    SessionsRevoked,
 }
 
 #[derive(Debug, Serialize)]
@@ -9,6 +9,15 @@ use thiserror::Error;
 const BASE62_ALPHABET: &[u8] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
 const BASE: u64 = 62;
 
+/// Base62 alphabet as a string, the default short code alphabet
+pub const DEFAULT_ALPHABET: &str = "0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// Characters easily confused when read aloud or in some fonts (0/O, 1/l/I)
+pub const AMBIGUOUS_CHARS: &str = "0O1lI";
+
+/// Smallest alphabet accepted for short codes
+pub const MIN_ALPHABET_SIZE: usize = 10;
+
 /// Lookup table for fast decoding (maps ASCII byte to Base62 value)
 /// -1 means invalid character
 static DECODE_TABLE: [i8; 256] = {
@@ -268,6 +277,60 @@ pub fn generate_random(length: usize) -> Result<String, Base62Error> {
     Base62Encoder::new().generate_random(length)
 }
 
+/// Deduplicated alphabet for short codes, optionally without ambiguous characters
+pub fn build_alphabet(chars: &str, exclude_ambiguous: bool) -> Result<Vec<u8>, String> {
+    if let Some(c) = chars.chars().find(|c| !c.is_ascii_alphanumeric()) {
+        return Err(format!(
+            "invalid character '{}': only A-Z, a-z and 0-9 are allowed",
+            c
+        ));
+    }
+
+    let mut alphabet: Vec<u8> = Vec::with_capacity(chars.len());
+    for b in chars.bytes() {
+        if exclude_ambiguous && AMBIGUOUS_CHARS.as_bytes().contains(&b) {
+            continue;
+        }
+        if !alphabet.contains(&b) {
+            alphabet.push(b);
+        }
+    }
+
+    if alphabet.len() < MIN_ALPHABET_SIZE {
+        return Err(format!(
+            "alphabet has {} usable characters, at least {} are required",
+            alphabet.len(),
+            MIN_ALPHABET_SIZE
+        ));
+    }
+    Ok(alphabet)
+}
+
+/// Encode a value in an arbitrary alphabet (most significant digit first)
+pub fn encode_with_alphabet(mut value: u64, alphabet: &[u8]) -> String {
+    let base = alphabet.len() as u64;
+    let mut digits = Vec::new();
+    loop {
+        digits.push(alphabet[(value % base) as usize]);
+        value /= base;
+        if value == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).expect("alphabet is ASCII")
+}
+
+/// Random string of `length` characters drawn from `alphabet`
+pub fn random_with_alphabet(length: usize, alphabet: &[u8]) -> String {
+    use rand::{thread_rng, Rng};
+
+    let mut rng = thread_rng();
+    (0..length)
+        .map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char)
+        .collect()
+}
+
 // =============================================================================
 // TESTS
 // =============================================================================
@@ -277,6 +340,38 @@ mod tests {
     use super::*;
     use std::time::Instant;
 
+    #[test]
+    fn test_build_alphabet() {
+        let full = build_alphabet(DEFAULT_ALPHABET, false).unwrap();
+        assert_eq!(full, BASE62_ALPHABET);
+
+        let unambiguous = build_alphabet(DEFAULT_ALPHABET, true).unwrap();
+        assert_eq!(unambiguous.len(), 62 - AMBIGUOUS_CHARS.len());
+        assert!(!unambiguous
+            .iter()
+            .any(|b| AMBIGUOUS_CHARS.as_bytes().contains(b)));
+
+        assert_eq!(build_alphabet("abcabcdefghijk", false).unwrap().len(), 11);
+        assert!(build_alphabet("abc-def_ghijk", false).is_err());
+        assert!(build_alphabet("0O1lIabcdefg", true).is_err());
+    }
+
+    #[test]
+    fn test_encode_with_alphabet() {
+        let base62 = DEFAULT_ALPHABET.as_bytes();
+        for value in [0, 61, 62, 3843, 56800235583, u64::MAX] {
+            assert_eq!(encode_with_alphabet(value, base62), encode(value));
+        }
+
+        let alphabet = build_alphabet(DEFAULT_ALPHABET, true).unwrap();
+        let code = encode_with_alphabet(1_000_000, &alphabet);
+        assert!(code.bytes().all(|b| alphabet.contains(&b)));
+
+        let random = random_with_alphabet(12, &alphabet);
+        assert_eq!(random.len(), 12);
+        assert!(random.bytes().all(|b| alphabet.contains(&b)));
+    }
+
     #[test]
     fn test_encode_decode_basic() {
         let encoder = Base62Encoder::new();
@@ -0,0 +1,388 @@
+// Domain, TLD, address range and keyword lists shared by URL validation and security scanning
+// Built once from the blocked domains list (see `data_files`), or from small built-in lists
+// when none could be loaded, and handed to both `UrlValidator` and `DomainSecurityService`.
+
+use crate::utils::data_files::{self, BlockedDomainsConfig};
+use ipnetwork::IpNetwork;
+use once_cell::sync::Lazy;
+use std::collections::HashSet;
+use std::net::IpAddr;
+use std::sync::Arc;
+use tracing::warn;
+
+// Built-in lists, only used when no blocked domains list could be loaded
+const FALLBACK_SHORTENERS: &[&str] = &[
+    "bit.ly",
+    "tinyurl.com",
+    "goo.gl",
+    "ow.ly",
+    "is.gd",
+    "buff.ly",
+];
+const FALLBACK_LOCAL_ADDRESSES: &[&str] = &["localhost", "127.0.0.1", "0.0.0.0", "::1"];
+const FALLBACK_BLOCKED_TLDS: &[&str] = &["test", "localhost", "local", "internal"];
+const FALLBACK_SUSPICIOUS_TLDS: &[&str] = &["tk", "ml", "cf", "ga", "onion"];
+const FALLBACK_PHISHING_KEYWORDS: &[&str] = &[
+    "download-free",
+    "click-here-now",
+    "limited-time-offer",
+    "verify-account",
+    "suspend-account",
+    "update-payment",
+    "confirm-identity",
+    "security-alert",
+    "unusual-activity",
+    "prize-winner",
+    "congratulations-winner",
+];
+const FALLBACK_THREAT_KEYWORDS: &[(&str, u8)] = &[
+    ("secure", 10),
+    ("verify", 15),
+    ("update", 10),
+    ("suspended", 20),
+    ("urgent", 15),
+    ("confirm", 10),
+    ("validate", 10),
+    ("unlock", 15),
+    ("refund", 10),
+    ("winner", 20),
+    ("prize", 20),
+    ("free", 5),
+];
+const FALLBACK_PRIVATE_RANGES: &[&str] = &[
+    "10.0.0.0/8",
+    "172.16.0.0/12",
+    "192.168.0.0/16",
+    "169.254.0.0/16",
+    "127.0.0.0/8",
+    "fc00::/7",
+    "fe80::/10",
+];
+
+static SHARED: Lazy<Arc<BlocklistRegistry>> = Lazy::new(|| {
+    Arc::new(match data_files::blocked_domains() {
+        Some(config) => BlocklistRegistry::from_config(&config),
+        None => BlocklistRegistry::fallback(),
+    })
+});
+
+/// Category of a blocked domain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockedDomainCategory {
+    Shortener,
+    LocalAddress,
+    Malicious,
+}
+
+#[derive(Debug)]
+pub struct BlocklistRegistry {
+    shorteners: HashSet<String>,
+    local_addresses: HashSet<String>,
+    malicious: HashSet<String>,
+    blocked_tlds: HashSet<String>,
+    suspicious_tlds: Vec<String>,
+    phishing_keywords: Vec<String>,
+    threat_keywords: Vec<(String, u8)>,
+    private_ranges: Vec<IpNetwork>,
+}
+
+impl BlocklistRegistry {
+    /// The registry built from the process-wide blocked domains list
+    pub fn shared() -> Arc<Self> {
+        SHARED.clone()
+    }
+
+    pub fn from_config(config: &BlockedDomainsConfig) -> Self {
+        let categories = &config.blocked_domains;
+        let mut registry = Self::build(
+            &categories.url_shorteners.domains,
+            &categories.local_addresses.domains,
+            &categories.malicious.domains,
+            &config.blocked_tlds.tlds,
+            &config.suspicious_tlds.tlds,
+            &config.suspicious_patterns.phishing_indicators,
+            &config.private_ip_ranges.ranges,
+        );
+        if let Some(keywords) = &config.threat_keywords {
+            registry.threat_keywords = keywords
+                .weights
+                .iter()
+                .map(|(keyword, weight)| (keyword.trim().to_lowercase(), *weight))
+                .filter(|(keyword, _)| !keyword.is_empty())
+                .collect();
+        }
+        registry
+    }
+
+    pub fn fallback() -> Self {
+        Self::build(
+            FALLBACK_SHORTENERS,
+            FALLBACK_LOCAL_ADDRESSES,
+            &[] as &[&str],
+            FALLBACK_BLOCKED_TLDS,
+            FALLBACK_SUSPICIOUS_TLDS,
+            FALLBACK_PHISHING_KEYWORDS,
+            FALLBACK_PRIVATE_RANGES,
+        )
+    }
+
+    fn build<S: AsRef<str>>(
+        shorteners: &[S],
+        local_addresses: &[S],
+        malicious: &[S],
+        blocked_tlds: &[S],
+        suspicious_tlds: &[S],
+        phishing_keywords: &[S],
+        private_ranges: &[S],
+    ) -> Self {
+        let set = |entries: &[S]| entries.iter().map(|e| normalize_host(e.as_ref())).collect();
+
+        let private_ranges = private_ranges
+            .iter()
+            .filter_map(|range| match range.as_ref().parse::<IpNetwork>() {
+                Ok(network) => Some(network),
+                Err(e) => {
+                    warn!(
+                        "Ignoring invalid private IP range {}: {}",
+                        range.as_ref(),
+                        e
+                    );
+                    None
+                },
+            })
+            .collect();
+
+        Self {
+            shorteners: set(shorteners),
+            local_addresses: set(local_addresses),
+            malicious: set(malicious),
+            blocked_tlds: set(blocked_tlds),
+            suspicious_tlds: suspicious_tlds
+                .iter()
+                .map(|tld| {
+                    normalize_host(tld.as_ref())
+                        .trim_start_matches('.')
+                        .to_string()
+                })
+                .filter(|tld| !tld.is_empty())
+                .collect(),
+            phishing_keywords: phishing_keywords
+                .iter()
+                .map(|keyword| keyword.as_ref().trim().to_lowercase())
+                .filter(|keyword| !keyword.is_empty())
+                .collect(),
+            threat_keywords: FALLBACK_THREAT_KEYWORDS
+                .iter()
+                .map(|(keyword, weight)| (keyword.to_string(), *weight))
+                .collect(),
+            private_ranges,
+        }
+    }
+
+    /// Category the host is blocked under; subdomains of a listed domain are blocked too
+    pub fn blocked_category(&self, host: &str) -> Option<BlockedDomainCategory> {
+        let host = normalize_host(host);
+        if matches_domain(&self.shorteners, &host) {
+            Some(BlockedDomainCategory::Shortener)
+        } else if matches_domain(&self.local_addresses, &host) {
+            Some(BlockedDomainCategory::LocalAddress)
+        } else if matches_domain(&self.malicious, &host) {
+            Some(BlockedDomainCategory::Malicious)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_blocked_domain(&self, host: &str) -> bool {
+        self.blocked_category(host).is_some()
+    }
+
+    pub fn is_shortener(&self, host: &str) -> bool {
+        matches_domain(&self.shorteners, &normalize_host(host))
+    }
+
+    /// The host's TLD, if it is blocked
+    pub fn blocked_tld(&self, host: &str) -> Option<String> {
+        let host = normalize_host(host);
+        let tld = host.rsplit('.').next()?;
+        self.blocked_tlds.contains(tld).then(|| tld.to_string())
+    }
+
+    /// Suspicious TLDs may span labels (e.g. `com.ru`)
+    pub fn has_suspicious_tld(&self, host: &str) -> bool {
+        let host = normalize_host(host);
+        self.suspicious_tlds
+            .iter()
+            .any(|tld| host.ends_with(&format!(".{}", tld)))
+    }
+
+    pub fn phishing_keywords(&self) -> &[String] {
+        &self.phishing_keywords
+    }
+
+    /// First phishing keyword found in the (case-insensitive) text
+    pub fn phishing_keyword(&self, text: &str) -> Option<&str> {
+        let text = text.to_lowercase();
+        self.phishing_keywords
+            .iter()
+            .find(|keyword| text.contains(keyword.as_str()))
+            .map(String::as_str)
+    }
+
+    /// Sum of the weights of every threat keyword the domain contains, capped at 100
+    pub fn threat_keyword_score(&self, domain: &str) -> u8 {
+        let domain = domain.to_lowercase();
+        self.threat_keywords
+            .iter()
+            .filter(|(keyword, _)| domain.contains(keyword.as_str()))
+            .fold(0u8, |score, (_, weight)| score.saturating_add(*weight))
+            .min(100)
+    }
+
+    /// Whether the host is an IP literal inside one of the private ranges
+    pub fn is_private_address(&self, host: &str) -> bool {
+        match normalize_host(host).parse::<IpAddr>() {
+            Ok(ip) => self.private_ranges.iter().any(|range| range.contains(ip)),
+            Err(_) => false,
+        }
+    }
+}
+
+/// Lowercase, without IPv6 brackets or a trailing dot. Anything after the host is dropped so
+/// a path like `example.com/file.zip` is never read as a `.zip` domain.
+fn normalize_host(host: &str) -> String {
+    let host = host.trim();
+    let host = host.split(['/', '?', '#']).next().unwrap_or(host);
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .trim_end_matches('.')
+        .to_lowercase()
+}
+
+fn matches_domain(domains: &HashSet<String>, host: &str) -> bool {
+    if domains.contains(host) {
+        return true;
+    }
+    // Parent domains of the host, e.g. `bit.ly` for `www.bit.ly`
+    host.match_indices('.')
+        .any(|(i, _)| domains.contains(&host[i + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bundled() -> BlocklistRegistry {
+        let path = data_files::data_file_path(data_files::BLOCKED_DOMAINS_FILE);
+        let config: BlockedDomainsConfig =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        BlocklistRegistry::from_config(&config)
+    }
+
+    #[test]
+    fn test_bundled_categories_are_consulted() {
+        let registry = bundled();
+
+        assert_eq!(
+            registry.blocked_category("rebrand.ly"),
+            Some(BlockedDomainCategory::Shortener)
+        );
+        assert_eq!(
+            registry.blocked_category("www.cutt.ly"),
+            Some(BlockedDomainCategory::Shortener)
+        );
+        assert_eq!(
+            registry.blocked_category("[::1]"),
+            Some(BlockedDomainCategory::LocalAddress)
+        );
+        assert_eq!(
+            registry.blocked_tld("service.invalid").as_deref(),
+            Some("invalid")
+        );
+        assert!(registry.has_suspicious_tld("login.example.com.ru"));
+        assert_eq!(
+            registry.phishing_keyword("https://x.com/Urgent-Action-Required"),
+            Some("urgent-action-required")
+        );
+        assert!(registry.is_private_address("172.20.1.1"));
+        assert!(registry.is_private_address("[fe80::1]"));
+    }
+
+    #[test]
+    fn test_domains_match_whole_labels_only() {
+        let registry = bundled();
+
+        // `t.co` is a listed shortener, but not a suffix of microsoft.com's labels
+        assert!(registry.is_shortener("t.co"));
+        assert!(!registry.is_shortener("microsoft.com"));
+        assert!(!registry.is_blocked_domain("notbit.ly"));
+        assert!(!registry.has_suspicious_tld("stock.ml.example.com"));
+        assert!(!registry.is_private_address("10.example.com"));
+    }
+
+    #[test]
+    fn test_shortener_lookalikes_are_not_shorteners() {
+        let registry = bundled();
+
+        for host in [
+            "institut.co",
+            "habit.lyrics.com",
+            "bit.ly.mycompany.com",
+            "notbit.ly.example.org",
+        ] {
+            assert!(!registry.is_shortener(host), "{} flagged", host);
+        }
+        for host in ["bit.ly", "go.bit.ly", "BIT.LY.", "a.b.t.co"] {
+            assert!(registry.is_shortener(host), "{} missed", host);
+        }
+    }
+
+    #[test]
+    fn test_tld_checks_only_read_the_host() {
+        let registry = bundled();
+
+        assert!(registry.has_suspicious_tld("files.zip"));
+        assert!(!registry.has_suspicious_tld("example.com/file.zip"));
+        assert!(!registry.has_suspicious_tld("example.com/setup.exe"));
+        assert!(!registry.has_suspicious_tld("example.com?download=a.tk"));
+        assert!(registry.blocked_tld("example.com/readme.test").is_none());
+        // File extensions are not TLDs
+        assert!(!registry.has_suspicious_tld("setup.exe"));
+    }
+
+    #[test]
+    fn test_threat_keyword_weights() {
+        let registry = bundled();
+        assert_eq!(registry.threat_keyword_score("secure-verify.example"), 25);
+        assert_eq!(registry.threat_keyword_score("example.com"), 0);
+
+        // Operators can retune or drop weights without a rebuild
+        let path = data_files::data_file_path(data_files::BLOCKED_DOMAINS_FILE);
+        let mut config: BlockedDomainsConfig =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        config.threat_keywords = serde_json::from_value(serde_json::json!({
+            "description": "",
+            "weights": { "Verify": 40 }
+        }))
+        .unwrap();
+        let tuned = BlocklistRegistry::from_config(&config);
+        assert_eq!(tuned.threat_keyword_score("secure-verify.example"), 40);
+
+        // Lists without the section keep the built-in weights
+        config.threat_keywords = None;
+        let defaults = BlocklistRegistry::from_config(&config);
+        assert_eq!(defaults.threat_keyword_score("secure-verify.example"), 25);
+    }
+
+    #[test]
+    fn test_fallback_lists() {
+        let registry = BlocklistRegistry::fallback();
+
+        assert!(registry.is_shortener("bit.ly"));
+        assert!(registry.is_blocked_domain("localhost"));
+        assert!(!registry.is_blocked_domain("rebrand.ly"));
+        assert!(registry.blocked_tld("db.internal").is_some());
+        assert!(registry.is_private_address("192.168.0.10"));
+        assert!(registry.phishing_keyword("/verify-account").is_some());
+    }
+}
@@ -6,9 +6,40 @@ lazy_static! {
     static ref ALIAS_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9][a-zA-Z0-9_-]*$").unwrap();
 }
 
+/// Root path segments served by fixed routes or commonly probed by browsers and crawlers.
+/// They are never looked up as short codes and can't be taken as aliases.
+pub const RESERVED_PATH_SEGMENTS: &[&str] = &[
+    "favicon.ico",
+    "robots.txt",
+    "apple-touch-icon.png",
+    "apple-touch-icon-precomposed.png",
+    "sitemap.xml",
+    "security.txt",
+    ".well-known",
+    "v1",
+    "api",
+    "docs",
+    "health",
+    "static",
+    "assets",
+];
+
+/// Whether a root path segment is reserved (case-insensitive)
+pub fn is_reserved_path_segment(segment: &str) -> bool {
+    RESERVED_PATH_SEGMENTS
+        .iter()
+        .any(|reserved| reserved.eq_ignore_ascii_case(segment))
+}
+
 pub struct CustomAliasValidator;
 
 impl CustomAliasValidator {
+    /// Canonical stored form: aliases are matched case-insensitively, so they are kept
+    /// trimmed and lowercase
+    pub fn normalize(alias: &str) -> String {
+        alias.trim().to_lowercase()
+    }
+
     /// Validate a custom alias according to business rules
     pub fn validate(alias: &str) -> Result<(), String> {
         // Length validation
@@ -39,6 +70,10 @@ impl CustomAliasValidator {
             return Err("Custom alias cannot end with a special character".to_string());
         }
 
+        if is_reserved_path_segment(alias) {
+            return Err(format!("Custom alias '{}' is reserved", alias));
+        }
+
         Ok(())
     }
 }
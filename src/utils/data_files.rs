@@ -0,0 +1,325 @@
+// Data files shipped with the service (blocked domains, reserved words, profanity list)
+// Files are read from DATA_DIR, an absolute path so the working directory of the container
+// doesn't matter. The blocked domains list can instead come from BLOCKED_DOMAINS_JSON (inline)
+// or BLOCKED_DOMAINS_URL; the URL is only fetched by `load_blocked_domains` at startup.
+
+use crate::app_config::{AppConfig, Environment};
+use crate::utils::outbound_proxy;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use tracing::{error, info};
+
+pub const BLOCKED_DOMAINS_FILE: &str = "blocked_domains.json";
+pub const RESERVED_WORDS_FILE: &str = "reserved_words.json";
+pub const PROFANITY_LIST_FILE: &str = "profanity_list.json";
+
+/// DATA_DIR when unset, where the container image puts the data files
+#[cfg(not(test))]
+pub const DEFAULT_DATA_DIR: &str = "/app/data";
+#[cfg(test)]
+pub const DEFAULT_DATA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data");
+
+/// How long startup waits for BLOCKED_DOMAINS_URL
+const BLOCKED_DOMAINS_FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// =============================================================================
+// BLOCKED DOMAINS FORMAT
+// =============================================================================
+
+/// Contents of blocked_domains.json
+#[derive(Debug, Deserialize)]
+pub struct BlockedDomainsConfig {
+    pub blocked_domains: BlockedDomainCategories,
+    pub blocked_tlds: BlockedTlds,
+    /// Missing from lists written before the section existed
+    #[serde(default)]
+    pub suspicious_tlds: SuspiciousTlds,
+    pub suspicious_patterns: SuspiciousPatterns,
+    pub private_ip_ranges: PrivateIpRanges,
+    /// Built-in weights are used when the section is missing
+    #[serde(default)]
+    pub threat_keywords: Option<ThreatKeywords>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockedDomainCategories {
+    pub url_shorteners: DomainCategory,
+    pub local_addresses: DomainCategory,
+    pub malicious: DomainCategory,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DomainCategory {
+    pub description: String,
+    pub domains: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BlockedTlds {
+    pub description: String,
+    pub tlds: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SuspiciousTlds {
+    pub description: String,
+    pub tlds: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuspiciousPatterns {
+    pub phishing_indicators: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrivateIpRanges {
+    pub description: String,
+    pub ranges: Vec<String>,
+}
+
+/// Score added to a domain's threat score for each keyword it contains
+#[derive(Debug, Deserialize)]
+pub struct ThreatKeywords {
+    pub description: String,
+    pub weights: HashMap<String, u8>,
+}
+
+impl BlockedDomainsConfig {
+    /// Domains from every category
+    pub fn domains(&self) -> impl Iterator<Item = &String> {
+        let categories = &self.blocked_domains;
+        categories
+            .url_shorteners
+            .domains
+            .iter()
+            .chain(categories.local_addresses.domains.iter())
+            .chain(categories.malicious.domains.iter())
+    }
+}
+
+// =============================================================================
+// BLOCKED DOMAINS SOURCE
+// =============================================================================
+
+/// Where the blocked domains list in use came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlocklistSource {
+    Inline,
+    Url,
+    File,
+    /// No source could be loaded; callers use their small built-in lists
+    Fallback,
+}
+
+struct LoadedBlocklist {
+    source: BlocklistSource,
+    config: Option<Arc<BlockedDomainsConfig>>,
+}
+
+static BLOCKED_DOMAINS: Lazy<RwLock<Option<LoadedBlocklist>>> = Lazy::new(|| RwLock::new(None));
+
+/// Path of a data file under DATA_DIR
+/// Read from the environment rather than CONFIG so validators work without a full config
+pub fn data_file_path(name: &str) -> PathBuf {
+    data_dir().join(name)
+}
+
+fn data_dir() -> PathBuf {
+    PathBuf::from(std::env::var("DATA_DIR").unwrap_or_else(|_| DEFAULT_DATA_DIR.to_string()))
+}
+
+/// Load the blocked domains list at startup, trying the inline value, the URL and then
+/// DATA_DIR in turn. Replaces anything loaded before.
+pub async fn load_blocked_domains(config: &AppConfig) -> BlocklistSource {
+    let mut loaded =
+        from_inline(&config.blocked_domains_json).map(|list| (BlocklistSource::Inline, list));
+    if loaded.is_none() {
+        loaded = from_url(&config.blocked_domains_url)
+            .await
+            .map(|list| (BlocklistSource::Url, list));
+    }
+    if loaded.is_none() {
+        loaded = from_file(Path::new(&config.data_dir)).map(|list| (BlocklistSource::File, list));
+    }
+    store(loaded)
+}
+
+/// The blocked domains list, or None when only the fallback lists are available.
+/// Without a startup load the inline value and DATA_DIR are tried on first use.
+pub fn blocked_domains() -> Option<Arc<BlockedDomainsConfig>> {
+    if let Some(loaded) = BLOCKED_DOMAINS.read().unwrap().as_ref() {
+        return loaded.config.clone();
+    }
+
+    let inline = std::env::var("BLOCKED_DOMAINS_JSON").unwrap_or_default();
+    let loaded = from_inline(&inline)
+        .map(|list| (BlocklistSource::Inline, list))
+        .or_else(|| from_file(&data_dir()).map(|list| (BlocklistSource::File, list)));
+    store(loaded);
+    BLOCKED_DOMAINS
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|loaded| loaded.config.clone())
+}
+
+/// Source of the loaded blocked domains list; None before anything asked for it
+pub fn blocklist_source() -> Option<BlocklistSource> {
+    BLOCKED_DOMAINS
+        .read()
+        .unwrap()
+        .as_ref()
+        .map(|loaded| loaded.source)
+}
+
+/// Production refuses to start on the fallback list unless ALLOW_FALLBACK_BLOCKLIST is set
+pub fn check_blocklist_source(
+    source: BlocklistSource,
+    environment: &Environment,
+    allow_fallback: bool,
+) -> Result<(), String> {
+    if source == BlocklistSource::Fallback
+        && *environment == Environment::Production
+        && !allow_fallback
+    {
+        return Err(
+            "no blocked domains list could be loaded from BLOCKED_DOMAINS_JSON, \
+             BLOCKED_DOMAINS_URL or DATA_DIR; set ALLOW_FALLBACK_BLOCKLIST=true to start with \
+             the built-in fallback list"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+fn store(loaded: Option<(BlocklistSource, BlockedDomainsConfig)>) -> BlocklistSource {
+    let loaded = match loaded {
+        Some((source, config)) => {
+            info!(
+                "Loaded {} blocked domains and {} blocked TLDs ({:?})",
+                config.domains().count(),
+                config.blocked_tlds.tlds.len(),
+                source
+            );
+            LoadedBlocklist {
+                source,
+                config: Some(Arc::new(config)),
+            }
+        },
+        None => {
+            error!(
+                "No blocked domains list could be loaded; URL validation is using the small \
+                 built-in fallback list. Set DATA_DIR, BLOCKED_DOMAINS_JSON or BLOCKED_DOMAINS_URL"
+            );
+            LoadedBlocklist {
+                source: BlocklistSource::Fallback,
+                config: None,
+            }
+        },
+    };
+    let source = loaded.source;
+    *BLOCKED_DOMAINS.write().unwrap() = Some(loaded);
+    source
+}
+
+fn parse(json: &str, origin: &str) -> Option<BlockedDomainsConfig> {
+    match serde_json::from_str(json) {
+        Ok(config) => Some(config),
+        Err(e) => {
+            error!("Failed to parse blocked domains from {}: {}", origin, e);
+            None
+        },
+    }
+}
+
+fn from_inline(json: &str) -> Option<BlockedDomainsConfig> {
+    if json.trim().is_empty() {
+        return None;
+    }
+    parse(json, "BLOCKED_DOMAINS_JSON")
+}
+
+async fn from_url(url: &str) -> Option<BlockedDomainsConfig> {
+    if url.is_empty() {
+        return None;
+    }
+
+    let client = outbound_proxy::apply(reqwest::Client::builder())
+        .timeout(BLOCKED_DOMAINS_FETCH_TIMEOUT)
+        .user_agent("QCK-Backend/1.0")
+        .build()
+        .unwrap_or_default();
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status());
+    let body = match response {
+        Ok(response) => response.text().await,
+        Err(e) => Err(e),
+    };
+    match body {
+        Ok(body) => parse(&body, "BLOCKED_DOMAINS_URL"),
+        Err(e) => {
+            // The URL itself may carry a signature, so only the variable is named
+            error!(
+                "Failed to fetch blocked domains from BLOCKED_DOMAINS_URL: {}",
+                e.without_url()
+            );
+            None
+        },
+    }
+}
+
+fn from_file(data_dir: &Path) -> Option<BlockedDomainsConfig> {
+    let path = data_dir.join(BLOCKED_DOMAINS_FILE);
+    match std::fs::read_to_string(&path) {
+        Ok(content) => parse(&content, &path.display().to_string()),
+        Err(e) => {
+            error!("Failed to read {}: {}", path.display(), e);
+            None
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo_data_dir() -> PathBuf {
+        PathBuf::from(DEFAULT_DATA_DIR)
+    }
+
+    #[test]
+    fn test_bundled_file_parses() {
+        let config = from_file(&repo_data_dir()).unwrap();
+        assert!(config.domains().any(|domain| domain == "bit.ly"));
+        assert!(!config.blocked_tlds.tlds.is_empty());
+    }
+
+    #[test]
+    fn test_missing_file_and_bad_inline_value_are_rejected() {
+        assert!(from_file(Path::new("/nonexistent/qck-data")).is_none());
+        assert!(from_inline("").is_none());
+        assert!(from_inline("{\"blocked_domains\": []}").is_none());
+
+        let bundled = std::fs::read_to_string(repo_data_dir().join(BLOCKED_DOMAINS_FILE)).unwrap();
+        assert!(from_inline(&bundled).is_some());
+    }
+
+    #[test]
+    fn test_fallback_refused_only_in_production() {
+        let fallback = BlocklistSource::Fallback;
+        assert!(check_blocklist_source(fallback, &Environment::Production, false).is_err());
+        assert!(check_blocklist_source(fallback, &Environment::Production, true).is_ok());
+        assert!(check_blocklist_source(fallback, &Environment::Staging, false).is_ok());
+        assert!(
+            check_blocklist_source(BlocklistSource::File, &Environment::Production, false).is_ok()
+        );
+    }
+}
@@ -0,0 +1,158 @@
+// Redis lock shared by every replica
+// Acquired with SET NX PX under a per-holder ownership token; renewing and releasing
+// only touch the key while it still holds that token, so an expired holder can never
+// extend or delete the lock another instance has taken over since.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use redis::RedisError;
+use uuid::Uuid;
+
+use crate::db::RedisPool;
+
+/// Prefix of every lock key
+const LOCK_KEY_PREFIX: &str = "lock:";
+
+/// Extend the expiry only if the caller still owns the lock
+const RENEW_SCRIPT: &str = r#"
+    if redis.call('GET', KEYS[1]) == ARGV[1] then
+        return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+    end
+    return 0
+"#;
+
+/// Delete the lock only if the caller still owns it
+const RELEASE_SCRIPT: &str = r#"
+    if redis.call('GET', KEYS[1]) == ARGV[1] then
+        return redis.call('DEL', KEYS[1])
+    end
+    return 0
+"#;
+
+/// A named lock held by at most one owner across all instances until it expires
+pub struct DistributedLock {
+    redis_pool: RedisPool,
+    name: String,
+    key: String,
+    token: String,
+    ttl: Duration,
+    held: AtomicBool,
+}
+
+impl DistributedLock {
+    /// Lock `name` on behalf of `owner` (the instance id); the lock expires `ttl` after the
+    /// last acquire or renew
+    pub fn new(redis_pool: RedisPool, name: &str, owner: &str, ttl: Duration) -> Self {
+        Self {
+            redis_pool,
+            name: name.to_string(),
+            key: lock_key(name),
+            // Unique per lock object, so two processes on one host never share ownership
+            token: format!("{}:{}", owner, Uuid::new_v4().simple()),
+            ttl,
+            held: AtomicBool::new(false),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Whether the last acquire or renew succeeded
+    pub fn is_held(&self) -> bool {
+        self.held.load(Ordering::Acquire)
+    }
+
+    /// Take the lock if nobody holds it; renews it instead when we already do
+    pub async fn try_acquire(&self) -> Result<bool, RedisError> {
+        if self.is_held() && self.renew().await? {
+            return Ok(true);
+        }
+
+        let mut conn = self.redis_pool.get_connection().await?;
+        let result: Option<String> = redis::cmd("SET")
+            .arg(&self.key)
+            .arg(&self.token)
+            .arg("NX")
+            .arg("PX")
+            .arg(self.ttl_millis())
+            .query_async(&mut conn)
+            .await?;
+
+        let acquired = result.is_some();
+        self.held.store(acquired, Ordering::Release);
+        Ok(acquired)
+    }
+
+    /// Push the expiry out by another `ttl`; false once the lock expired or changed hands
+    pub async fn renew(&self) -> Result<bool, RedisError> {
+        let mut conn = self.redis_pool.get_connection().await?;
+        let renewed: i64 = redis::Script::new(RENEW_SCRIPT)
+            .key(&self.key)
+            .arg(&self.token)
+            .arg(self.ttl_millis())
+            .invoke_async(&mut conn)
+            .await?;
+
+        let renewed = renewed == 1;
+        self.held.store(renewed, Ordering::Release);
+        Ok(renewed)
+    }
+
+    /// Give the lock up early; false if it was no longer ours
+    pub async fn release(&self) -> Result<bool, RedisError> {
+        self.held.store(false, Ordering::Release);
+        let mut conn = self.redis_pool.get_connection().await?;
+        let deleted: i64 = redis::Script::new(RELEASE_SCRIPT)
+            .key(&self.key)
+            .arg(&self.token)
+            .invoke_async(&mut conn)
+            .await?;
+        Ok(deleted == 1)
+    }
+
+    /// Instance currently holding the lock `name`, if any
+    pub async fn holder(redis_pool: &RedisPool, name: &str) -> Result<Option<String>, RedisError> {
+        let token: Option<String> = redis_pool.get(&lock_key(name)).await?;
+        Ok(token.as_deref().map(|token| owner_of(token).to_string()))
+    }
+
+    fn ttl_millis(&self) -> u64 {
+        self.ttl.as_millis().max(1) as u64
+    }
+}
+
+/// Redis key of the lock `name`
+pub fn lock_key(name: &str) -> String {
+    format!("{}{}", LOCK_KEY_PREFIX, name)
+}
+
+/// Owner part of an ownership token (`{owner}:{unique}`)
+fn owner_of(token: &str) -> &str {
+    token
+        .rsplit_once(':')
+        .map(|(owner, _)| owner)
+        .unwrap_or(token)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owner_of_token() {
+        assert_eq!(owner_of("api-7d9f:0f3a"), "api-7d9f");
+        // Instance ids may themselves contain colons
+        assert_eq!(owner_of("10.0.0.5:8080:0f3a"), "10.0.0.5:8080");
+        assert_eq!(owner_of("legacy"), "legacy");
+        assert_eq!(
+            lock_key("task:click_count_sync"),
+            "lock:task:click_count_sync"
+        );
+    }
+}
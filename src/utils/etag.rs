@@ -0,0 +1,101 @@
+// Conditional GET helpers
+// Weak ETags let polling dashboards revalidate with If-None-Match and receive 304 instead of a full body
+
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+/// Clients must revalidate on every poll but never share cached bodies
+pub const REVALIDATE_CACHE_CONTROL: &str = "private, max-age=0, must-revalidate";
+
+/// Weak ETag derived from a fingerprint of the resource state
+pub fn weak_etag(fingerprint: &str) -> String {
+    let digest = Sha256::digest(fingerprint.as_bytes());
+    let hex: String = digest[..12].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("W/\"{}\"", hex)
+}
+
+/// Whether `If-None-Match` matches `etag` using weak comparison (RFC 9110 §13.1.2)
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let etag = strip_weak(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .any(|candidate| candidate == "*" || strip_weak(candidate) == etag)
+}
+
+/// Empty 304 carrying the validator and cache policy
+pub fn not_modified(etag: &str) -> Response {
+    with_etag(StatusCode::NOT_MODIFIED.into_response(), etag)
+}
+
+/// Attach `ETag` and `Cache-Control` to a successful response
+pub fn with_etag(mut response: Response, etag: &str) -> Response {
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        headers.insert(header::ETAG, value);
+    }
+    headers.insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(REVALIDATE_CACHE_CONTROL),
+    );
+    response
+}
+
+fn strip_weak(tag: &str) -> &str {
+    tag.strip_prefix("W/").unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(if_none_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::IF_NONE_MATCH,
+            HeaderValue::from_str(if_none_match).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn test_weak_etag_is_stable_and_state_sensitive() {
+        let etag = weak_etag("2024-01-01T00:00:00Z|3");
+        assert!(etag.starts_with("W/\"") && etag.ends_with('"'));
+        assert_eq!(etag, weak_etag("2024-01-01T00:00:00Z|3"));
+        assert_ne!(etag, weak_etag("2024-01-01T00:00:00Z|4"));
+    }
+
+    #[test]
+    fn test_if_none_match_uses_weak_comparison() {
+        let etag = weak_etag("state");
+        let strong = etag.trim_start_matches("W/").to_string();
+
+        assert!(if_none_match(&headers(&etag), &etag));
+        assert!(if_none_match(&headers(&strong), &etag));
+        assert!(if_none_match(
+            &headers(&format!("\"other\", {}", etag)),
+            &etag
+        ));
+        assert!(if_none_match(&headers("*"), &etag));
+        assert!(!if_none_match(&headers("W/\"other\""), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn test_not_modified_carries_validators() {
+        let response = not_modified("W/\"abc\"");
+        assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(response.headers()[header::ETAG], "W/\"abc\"");
+        assert_eq!(
+            response.headers()[header::CACHE_CONTROL],
+            REVALIDATE_CACHE_CONTROL
+        );
+    }
+}
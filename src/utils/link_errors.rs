@@ -4,12 +4,13 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
-use serde::Serialize;
 use thiserror::Error;
 
-use crate::{services::short_code::ShortCodeError, utils::url_validator::UrlValidationError};
+use crate::{
+    services::short_code::ShortCodeError,
+    utils::{api_error::ApiError, url_validator::UrlValidationError},
+};
 
 // =============================================================================
 // ERROR TYPES
@@ -74,6 +75,9 @@ pub enum LinkError {
     #[error("Invalid request: {0}")]
     BadRequest(String),
 
+    #[error("Idempotency conflict: {0}")]
+    IdempotencyConflict(String),
+
     #[error("Internal server error")]
     InternalError,
 
@@ -157,34 +161,10 @@ impl From<diesel::result::Error> for LinkError {
     }
 }
 
-impl From<validator::ValidationErrors> for LinkError {
-    fn from(err: validator::ValidationErrors) -> Self {
-        let messages: Vec<String> = err
-            .field_errors()
-            .iter()
-            .flat_map(|(field, errors)| {
-                errors
-                    .iter()
-                    .map(move |e| format!("{}: {}", field, e.message.as_ref().unwrap_or(&e.code)))
-            })
-            .collect();
-
-        LinkError::ValidationError(messages.join(", "))
-    }
-}
-
 // =============================================================================
 // ERROR RESPONSE
 // =============================================================================
 
-#[derive(Debug, Serialize)]
-pub struct LinkErrorResponse {
-    pub error: String,
-    pub code: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub details: Option<serde_json::Value>,
-}
-
 impl LinkError {
     /// Get HTTP status code for error
     pub fn status_code(&self) -> StatusCode {
@@ -199,7 +179,9 @@ impl LinkError {
 
             LinkError::NotFound => StatusCode::NOT_FOUND,
 
-            LinkError::AliasExists(_) | LinkError::ReservedAlias(_) => StatusCode::CONFLICT,
+            LinkError::AliasExists(_)
+            | LinkError::ReservedAlias(_)
+            | LinkError::IdempotencyConflict(_) => StatusCode::CONFLICT,
 
             LinkError::Expired => StatusCode::GONE,
 
@@ -235,37 +217,26 @@ impl LinkError {
             LinkError::MetadataExtractionError(_) => "METADATA_EXTRACTION_ERROR",
             LinkError::SubscriptionLimitExceeded(_) => "SUBSCRIPTION_LIMIT_EXCEEDED",
             LinkError::BadRequest(_) => "BAD_REQUEST",
+            LinkError::IdempotencyConflict(_) => "IDEMPOTENCY_CONFLICT",
             LinkError::InternalError => "INTERNAL_ERROR",
             LinkError::ServiceUnavailable => "SERVICE_UNAVAILABLE",
         }
     }
+}
 
-    /// Create error response
-    pub fn to_response(&self) -> LinkErrorResponse {
-        let details = match self {
-            LinkError::RateLimitExceeded { retry_after } => {
-                Some(serde_json::json!({ "retry_after": retry_after }))
-            },
-            LinkError::ValidationError(msg) => {
-                Some(serde_json::json!({ "validation_errors": msg }))
-            },
-            _ => None,
-        };
-
-        LinkErrorResponse {
-            error: self.to_string(),
-            code: self.error_code().to_string(),
-            details,
+impl From<LinkError> for ApiError {
+    fn from(err: LinkError) -> Self {
+        let error = ApiError::new(err.status_code(), err.error_code(), err.to_string());
+        match err {
+            LinkError::RateLimitExceeded { retry_after } => error.with_retry_after(retry_after),
+            _ => error,
         }
     }
 }
 
 impl IntoResponse for LinkError {
     fn into_response(self) -> Response {
-        let status = self.status_code();
-        let body = self.to_response();
-
-        (status, Json(body)).into_response()
+        ApiError::from(self).into_response()
     }
 }
 
@@ -327,14 +298,12 @@ mod tests {
 
     #[test]
     fn test_error_response() {
-        let error = LinkError::RateLimitExceeded { retry_after: 60 };
-        let response = error.to_response();
-
-        assert_eq!(response.code, "RATE_LIMIT_EXCEEDED");
-        assert!(response.details.is_some());
+        let error = ApiError::from(LinkError::RateLimitExceeded { retry_after: 60 });
 
-        let details = response.details.unwrap();
-        assert_eq!(details["retry_after"], 60);
+        assert_eq!(error.code, "RATE_LIMIT_EXCEEDED");
+        assert_eq!(error.status, StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(error.retry_after, Some(60));
+        assert!(error.details.is_empty());
     }
 
     #[test]
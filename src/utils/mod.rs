@@ -1,24 +1,35 @@
 // Utility modules for QCK Backend
 
+pub mod api_error;
 pub mod audit_logger;
 pub mod auth_errors;
 pub mod base62;
+pub mod blocklist_registry;
 pub mod custom_alias_validator;
+pub mod data_files;
 pub mod device_fingerprint;
+pub mod distributed_lock;
+pub mod etag;
 pub mod link_errors;
+pub mod outbound_proxy;
 pub mod password;
+pub mod safe_browsing;
+pub mod safe_http;
+pub mod safe_redirect;
 pub mod security_scanner;
 pub mod service_error;
+pub mod timezone;
+pub mod ttl_cache;
 pub mod url_validator;
 pub mod urlhaus_client;
 pub mod validation;
 
+pub use api_error::{ApiError, FieldError};
 pub use auth_errors::{
-    create_auth_audit_entry, log_auth_failure, AuthAuditEntry, AuthError, AuthErrorResponse,
-    AuthEventType,
+    create_auth_audit_entry, log_auth_failure, AuthAuditEntry, AuthError, AuthEventType,
 };
 pub use device_fingerprint::generate_device_fingerprint;
-pub use link_errors::{LinkError, LinkErrorResponse, LinkResult};
+pub use link_errors::{LinkError, LinkResult};
 pub use password::{hash_password, verify_password, PasswordError};
 pub use security_scanner::{
     DomainSecurityService, SecurityError, SecurityRiskLevel,
@@ -26,7 +37,7 @@ pub use security_scanner::{
     UrlPatternAnalyzer,
 };
 pub use url_validator::{
-    normalize_url, normalize_url_async, MetadataError, NormalizedUrl, RiskLevel,
+    host_to_unicode, normalize_url, normalize_url_async, MetadataError, NormalizedUrl, RiskLevel,
     SecurityScanResult, SecurityScanner, UrlMetadata, UrlValidationError, UrlValidator,
     ValidationError,
 };
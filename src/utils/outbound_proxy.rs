@@ -0,0 +1,82 @@
+// Outbound proxy for external fetches
+// Installed once at startup from OUTBOUND_PROXY_URL / OUTBOUND_NO_PROXY; every client
+// that talks to the outside world (threat feeds, email, webhooks) goes through `apply`
+// so instances behind a corporate proxy keep working. Fetches of user-supplied URLs
+// (metadata, content scans) use `apply_to_user_fetch`, which OUTBOUND_PROXY_USER_FETCHES=false
+// turns into direct, DNS-pinned connections.
+
+use crate::app_config::ProxyConfig;
+use once_cell::sync::OnceCell;
+use reqwest::{ClientBuilder, NoProxy, Proxy};
+use tracing::{info, warn};
+
+struct InstalledProxy {
+    proxy: Proxy,
+    user_fetches: bool,
+}
+
+static OUTBOUND_PROXY: OnceCell<InstalledProxy> = OnceCell::new();
+
+/// Install the proxy used by `apply`. Without a proxy URL this is a no-op.
+/// Only the first call takes effect.
+pub fn install(config: &ProxyConfig) -> Result<(), reqwest::Error> {
+    let Some(proxy) = build_proxy(config)? else {
+        return Ok(());
+    };
+
+    let installed = InstalledProxy {
+        proxy,
+        user_fetches: config.user_fetches,
+    };
+    if OUTBOUND_PROXY.set(installed).is_ok() {
+        info!(
+            "Outbound requests use proxy {} (bypassed for: {})",
+            config.redacted_url(),
+            if config.no_proxy.is_empty() {
+                "none"
+            } else {
+                &config.no_proxy
+            }
+        );
+        if config.user_fetches {
+            warn!(
+                "User-supplied URLs are fetched through the proxy, which resolves them again, so \
+                 DNS rebinding protection relies on the proxy not reaching internal networks; set \
+                 OUTBOUND_PROXY_USER_FETCHES=false to connect to them directly"
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Whether an outbound proxy has been installed
+pub fn is_installed() -> bool {
+    OUTBOUND_PROXY.get().is_some()
+}
+
+/// Route a client through the installed proxy.
+/// With nothing installed the builder is returned unchanged.
+pub fn apply(builder: ClientBuilder) -> ClientBuilder {
+    match OUTBOUND_PROXY.get() {
+        Some(installed) => builder.proxy(installed.proxy.clone()),
+        None => builder,
+    }
+}
+
+/// Proxy settings for a client fetching a user-supplied URL.
+/// Uses the installed proxy unless user fetches were opted out; a direct client
+/// ignores proxy variables so the validated address is the one connected to.
+pub fn apply_to_user_fetch(builder: ClientBuilder) -> ClientBuilder {
+    match OUTBOUND_PROXY.get() {
+        Some(installed) if installed.user_fetches => builder.proxy(installed.proxy.clone()),
+        _ => builder.no_proxy(),
+    }
+}
+
+fn build_proxy(config: &ProxyConfig) -> Result<Option<Proxy>, reqwest::Error> {
+    if config.url.is_empty() {
+        return Ok(None);
+    }
+    let proxy = Proxy::all(config.url.as_str())?.no_proxy(NoProxy::from_string(&config.no_proxy));
+    Ok(Some(proxy))
+}
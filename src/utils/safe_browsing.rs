@@ -0,0 +1,398 @@
+// Google Safe Browsing v4 Lookup API client
+// https://developers.google.com/safe-browsing/v4/lookup-api
+
+use crate::db::RedisPool;
+use crate::utils::outbound_proxy;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::{debug, warn};
+
+/// Maximum number of threat entries allowed in a single threatMatches:find request
+const MAX_ENTRIES_PER_REQUEST: usize = 500;
+
+/// Redis key prefix for cached verdicts
+const CACHE_KEY_PREFIX: &str = "safe_browsing:";
+
+/// Minimum backoff after the first failed request (per the v4 spec: 15 minutes)
+const MIN_BACKOFF_SECONDS: u64 = 15 * 60;
+
+/// Maximum backoff between retries (per the v4 spec: 24 hours)
+const MAX_BACKOFF_SECONDS: u64 = 24 * 60 * 60;
+
+/// Threat types we ask the API about
+const THREAT_TYPES: [&str; 4] = [
+    "MALWARE",
+    "SOCIAL_ENGINEERING",
+    "UNWANTED_SOFTWARE",
+    "POTENTIALLY_HARMFUL_APPLICATION",
+];
+
+// =============================================================================
+// ERROR TYPES
+// =============================================================================
+
+#[derive(Debug, Error)]
+pub enum SafeBrowsingError {
+    #[error("Network error: {0}")]
+    Network(#[from] reqwest::Error),
+
+    #[error("Safe Browsing quota exceeded, backing off for {0}s")]
+    QuotaExceeded(u64),
+
+    #[error("Safe Browsing is in backoff for another {0}s")]
+    BackingOff(u64),
+
+    #[error("Safe Browsing API returned HTTP {0}")]
+    Api(u16),
+}
+
+// =============================================================================
+// DATA STRUCTURES
+// =============================================================================
+
+/// Runtime configuration for the Safe Browsing client
+#[derive(Debug, Clone)]
+pub struct SafeBrowsingConfig {
+    pub api_key: String,
+    pub api_url: String,
+    pub client_id: String,
+    pub client_version: String,
+    /// TTL for "no match" verdicts; the Lookup API only returns durations for matches
+    pub negative_cache_seconds: u64,
+}
+
+impl SafeBrowsingConfig {
+    /// Build from the global security config; `None` when the integration is disabled
+    pub fn from_app_config(config: &crate::app_config::SecurityConfig) -> Option<Self> {
+        if !config.safe_browsing_enabled || config.safe_browsing_api_key.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            api_key: config.safe_browsing_api_key.clone(),
+            api_url: config.safe_browsing_api_url.clone(),
+            client_id: "qck-backend".to_string(),
+            client_version: env!("CARGO_PKG_VERSION").to_string(),
+            negative_cache_seconds: config.safe_browsing_negative_cache_seconds,
+        })
+    }
+}
+
+/// A single threat match reported by the API
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SafeBrowsingThreat {
+    Malware,
+    SocialEngineering,
+    UnwantedSoftware,
+    PotentiallyHarmfulApplication,
+}
+
+impl SafeBrowsingThreat {
+    fn from_api(value: &str) -> Option<Self> {
+        match value {
+            "MALWARE" => Some(Self::Malware),
+            "SOCIAL_ENGINEERING" => Some(Self::SocialEngineering),
+            "UNWANTED_SOFTWARE" => Some(Self::UnwantedSoftware),
+            "POTENTIALLY_HARMFUL_APPLICATION" => Some(Self::PotentiallyHarmfulApplication),
+            _ => None,
+        }
+    }
+
+    fn as_api(&self) -> &'static str {
+        match self {
+            Self::Malware => "MALWARE",
+            Self::SocialEngineering => "SOCIAL_ENGINEERING",
+            Self::UnwantedSoftware => "UNWANTED_SOFTWARE",
+            Self::PotentiallyHarmfulApplication => "POTENTIALLY_HARMFUL_APPLICATION",
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FindRequest<'a> {
+    client: ClientInfo<'a>,
+    threat_info: ThreatInfo<'a>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ClientInfo<'a> {
+    client_id: &'a str,
+    client_version: &'a str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreatInfo<'a> {
+    threat_types: &'a [&'a str],
+    platform_types: [&'a str; 1],
+    threat_entry_types: [&'a str; 1],
+    threat_entries: Vec<ThreatEntry<'a>>,
+}
+
+#[derive(Serialize)]
+struct ThreatEntry<'a> {
+    url: &'a str,
+}
+
+#[derive(Deserialize, Default)]
+struct FindResponse {
+    #[serde(default)]
+    matches: Vec<ThreatMatch>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ThreatMatch {
+    threat_type: String,
+    threat: MatchedEntry,
+    cache_duration: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct MatchedEntry {
+    url: String,
+}
+
+#[derive(Debug, Default)]
+struct BackoffState {
+    consecutive_errors: u32,
+    retry_after: Option<Instant>,
+}
+
+// =============================================================================
+// SAFE BROWSING CLIENT
+// =============================================================================
+
+pub struct SafeBrowsingClient {
+    config: SafeBrowsingConfig,
+    http_client: reqwest::Client,
+    backoff: Arc<Mutex<BackoffState>>,
+}
+
+impl SafeBrowsingClient {
+    pub fn new(config: SafeBrowsingConfig) -> Self {
+        let http_client = outbound_proxy::apply(reqwest::Client::builder())
+            .timeout(Duration::from_secs(5))
+            .user_agent("QCK-SecurityScanner/1.0")
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            config,
+            http_client,
+            backoff: Arc::new(Mutex::new(BackoffState::default())),
+        }
+    }
+
+    /// Look up a single URL. Returns the (possibly empty) list of matched threats.
+    pub async fn check_url(
+        &self,
+        url: &str,
+        cache: Option<&RedisPool>,
+    ) -> Result<Vec<SafeBrowsingThreat>, SafeBrowsingError> {
+        let mut verdicts = self.lookup(&[url.to_string()], cache).await?;
+        Ok(verdicts.remove(url).unwrap_or_default())
+    }
+
+    /// Look up a batch of URLs, consulting the Redis cache first and splitting
+    /// cache misses into requests of at most 500 entries.
+    pub async fn lookup(
+        &self,
+        urls: &[String],
+        cache: Option<&RedisPool>,
+    ) -> Result<HashMap<String, Vec<SafeBrowsingThreat>>, SafeBrowsingError> {
+        let mut verdicts = HashMap::with_capacity(urls.len());
+        let mut pending = Vec::new();
+
+        for url in urls {
+            if verdicts.contains_key(url) || pending.contains(url) {
+                continue;
+            }
+            match Self::get_cached(url, cache).await {
+                Some(threats) => {
+                    verdicts.insert(url.clone(), threats);
+                },
+                None => pending.push(url.clone()),
+            }
+        }
+
+        if pending.is_empty() {
+            return Ok(verdicts);
+        }
+
+        self.check_backoff().await?;
+
+        for chunk in pending.chunks(MAX_ENTRIES_PER_REQUEST) {
+            let matches = self.find_threat_matches(chunk).await?;
+
+            for url in chunk {
+                let url_matches: Vec<&ThreatMatch> =
+                    matches.iter().filter(|m| &m.threat.url == url).collect();
+
+                let threats: Vec<SafeBrowsingThreat> = url_matches
+                    .iter()
+                    .filter_map(|m| SafeBrowsingThreat::from_api(&m.threat_type))
+                    .collect();
+
+                // Respect the shortest cache duration returned for this URL
+                let ttl = url_matches
+                    .iter()
+                    .filter_map(|m| m.cache_duration.as_deref().and_then(parse_duration))
+                    .min()
+                    .unwrap_or(self.config.negative_cache_seconds);
+
+                Self::set_cached(url, &threats, ttl, cache).await;
+                verdicts.insert(url.clone(), threats);
+            }
+        }
+
+        Ok(verdicts)
+    }
+
+    async fn find_threat_matches(
+        &self,
+        urls: &[String],
+    ) -> Result<Vec<ThreatMatch>, SafeBrowsingError> {
+        let body = FindRequest {
+            client: ClientInfo {
+                client_id: &self.config.client_id,
+                client_version: &self.config.client_version,
+            },
+            threat_info: ThreatInfo {
+                threat_types: &THREAT_TYPES,
+                platform_types: ["ANY_PLATFORM"],
+                threat_entry_types: ["URL"],
+                threat_entries: urls.iter().map(|url| ThreatEntry { url }).collect(),
+            },
+        };
+
+        let response = match self
+            .http_client
+            .post(&self.config.api_url)
+            .query(&[("key", self.config.api_key.as_str())])
+            .json(&body)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                self.record_failure().await;
+                return Err(e.into());
+            },
+        };
+
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let wait = self.record_failure().await;
+            warn!("Safe Browsing quota exceeded, backing off for {}s", wait);
+            return Err(SafeBrowsingError::QuotaExceeded(wait));
+        }
+        if !status.is_success() {
+            if status.is_server_error() {
+                self.record_failure().await;
+            }
+            return Err(SafeBrowsingError::Api(status.as_u16()));
+        }
+
+        let parsed: FindResponse = response.json().await?;
+        self.record_success().await;
+        Ok(parsed.matches)
+    }
+
+    async fn check_backoff(&self) -> Result<(), SafeBrowsingError> {
+        let state = self.backoff.lock().await;
+        if let Some(retry_after) = state.retry_after {
+            let now = Instant::now();
+            if retry_after > now {
+                return Err(SafeBrowsingError::BackingOff((retry_after - now).as_secs()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Exponential backoff per the v4 spec: MIN_WAIT * 2^(N-1) * (1 + RAND), capped at 24h
+    async fn record_failure(&self) -> u64 {
+        let mut state = self.backoff.lock().await;
+        state.consecutive_errors = state.consecutive_errors.saturating_add(1);
+
+        let exponent = state.consecutive_errors.saturating_sub(1).min(10);
+        let base = MIN_BACKOFF_SECONDS.saturating_mul(1 << exponent);
+        let jittered = (base as f64 * (1.0 + fastrand::f64())) as u64;
+        let wait = jittered.min(MAX_BACKOFF_SECONDS);
+
+        state.retry_after = Some(Instant::now() + Duration::from_secs(wait));
+        wait
+    }
+
+    async fn record_success(&self) {
+        let mut state = self.backoff.lock().await;
+        state.consecutive_errors = 0;
+        state.retry_after = None;
+    }
+
+    async fn get_cached(url: &str, cache: Option<&RedisPool>) -> Option<Vec<SafeBrowsingThreat>> {
+        let cache = cache?;
+        match cache.get::<String>(&cache_key(url)).await {
+            Ok(Some(value)) => Some(
+                value
+                    .split(',')
+                    .filter_map(SafeBrowsingThreat::from_api)
+                    .collect(),
+            ),
+            Ok(None) => None,
+            Err(e) => {
+                debug!("Safe Browsing cache read failed: {}", e);
+                None
+            },
+        }
+    }
+
+    async fn set_cached(
+        url: &str,
+        threats: &[SafeBrowsingThreat],
+        ttl_seconds: u64,
+        cache: Option<&RedisPool>,
+    ) {
+        let Some(cache) = cache else {
+            return;
+        };
+        if ttl_seconds == 0 {
+            return;
+        }
+
+        let value = threats
+            .iter()
+            .map(|t| t.as_api())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        if let Err(e) = cache
+            .set_with_expiry(&cache_key(url), value, ttl_seconds as usize)
+            .await
+        {
+            debug!("Safe Browsing cache write failed: {}", e);
+        }
+    }
+}
+
+/// Hash URLs for cache keys so arbitrary user input never lands in a Redis key verbatim
+fn cache_key(url: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(url.as_bytes());
+    format!("{}{:x}", CACHE_KEY_PREFIX, hasher.finalize())
+}
+
+/// Parse protobuf Duration JSON strings such as "300s" or "1.5s"
+fn parse_duration(value: &str) -> Option<u64> {
+    value
+        .strip_suffix('s')
+        .and_then(|secs| secs.parse::<f64>().ok())
+        .map(|secs| secs.max(0.0) as u64)
+}
@@ -0,0 +1,298 @@
+// SSRF-safe outbound HTTP for user-supplied URLs
+// Every hop is resolved, checked against non-public address ranges and then
+// requested with the validated IP pinned, so DNS rebinding can't swap it out.
+// Behind an outbound proxy the checks still run, but the proxy resolves the host
+// again, so pinning only holds for direct connections: hosts in OUTBOUND_NO_PROXY,
+// or every host with OUTBOUND_PROXY_USER_FETCHES=false
+
+use crate::utils::outbound_proxy;
+use reqwest::{header, redirect, Method, Response, StatusCode};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use thiserror::Error;
+use tracing::debug;
+use url::Url;
+
+/// Upper bound on DNS resolution for a single hop
+const DNS_TIMEOUT: Duration = Duration::from_secs(3);
+
+// =============================================================================
+// ERROR TYPES
+// =============================================================================
+
+#[derive(Debug, Error)]
+pub enum SafeHttpError {
+    #[error("Invalid URL: {0}")]
+    InvalidUrl(String),
+
+    #[error("Unsupported URL scheme: {0}")]
+    UnsupportedScheme(String),
+
+    #[error("Destination address {0} is not publicly routable")]
+    BlockedAddress(IpAddr),
+
+    #[error("DNS resolution failed for {0}")]
+    DnsResolutionFailed(String),
+
+    #[error("DNS resolution timed out for {0}")]
+    DnsTimeout(String),
+
+    #[error("Too many redirects (max {0})")]
+    TooManyRedirects(usize),
+
+    #[error("Redirect to {0} was blocked")]
+    RedirectBlocked(String),
+
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+impl SafeHttpError {
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            SafeHttpError::DnsTimeout(_) => true,
+            SafeHttpError::Http(e) => e.is_timeout(),
+            _ => false,
+        }
+    }
+}
+
+// =============================================================================
+// ADDRESS CLASSIFICATION
+// =============================================================================
+
+/// `true` only for globally routable unicast addresses.
+/// Private, loopback, link-local, CGNAT, multicast, documentation and reserved
+/// ranges are rejected, as are IPv6 forms that embed such an IPv4 address.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_public_ipv4(v4),
+        IpAddr::V6(v6) => is_public_ipv6(v6),
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, c, _] = ip.octets();
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local() // 169.254.0.0/16, includes cloud metadata endpoints
+        || ip.is_multicast()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || a == 0 // 0.0.0.0/8 "this network"
+        || (a == 100 && (64..=127).contains(&b)) // 100.64.0.0/10 carrier-grade NAT
+        || (a == 192 && b == 0 && c == 0) // 192.0.0.0/24 IETF protocol assignments
+        || (a == 198 && (b == 18 || b == 19)) // 198.18.0.0/15 benchmarking
+        || a >= 240) // 240.0.0.0/4 reserved
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    // IPv4-mapped (::ffff:a.b.c.d) is judged by the embedded IPv4 address
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_public_ipv4(v4);
+    }
+
+    let segments = ip.segments();
+
+    // Deprecated IPv4-compatible form (::a.b.c.d)
+    if segments[..6].iter().all(|&s| s == 0) {
+        return !ip.is_unspecified() && !ip.is_loopback() && is_public_ipv4(embedded_v4(ip));
+    }
+
+    // NAT64 well-known prefix 64:ff9b::/96 reaches the embedded IPv4 address
+    if segments[0] == 0x64 && segments[1] == 0xff9b && segments[2..6].iter().all(|&s| s == 0) {
+        return is_public_ipv4(embedded_v4(ip));
+    }
+
+    // 6to4 (2002::/16) embeds an IPv4 address in bits 16..48
+    if segments[0] == 0x2002 {
+        let [hi, lo] = [segments[1], segments[2]];
+        let v4 = Ipv4Addr::new((hi >> 8) as u8, hi as u8, (lo >> 8) as u8, lo as u8);
+        return is_public_ipv4(v4);
+    }
+
+    !(ip.is_unspecified()
+        || ip.is_loopback()
+        || ip.is_multicast() // ff00::/8
+        || (segments[0] & 0xfe00) == 0xfc00 // fc00::/7 unique local
+        || (segments[0] & 0xffc0) == 0xfe80 // fe80::/10 link-local
+        || (segments[0] & 0xffc0) == 0xfec0 // fec0::/10 site-local (deprecated)
+        || (segments[0] == 0x2001 && segments[1] == 0x0db8) // 2001:db8::/32 documentation
+        || (segments[0] == 0x2001 && segments[1] == 0x0000) // 2001::/32 Teredo
+        || (segments[0] == 0x0100 && segments[1..4].iter().all(|&s| s == 0))) // 100::/64 discard
+}
+
+fn embedded_v4(ip: Ipv6Addr) -> Ipv4Addr {
+    let octets = ip.octets();
+    Ipv4Addr::new(octets[12], octets[13], octets[14], octets[15])
+}
+
+// =============================================================================
+// RESOLUTION
+// =============================================================================
+
+/// Resolve a host and make sure every address it resolves to is public.
+/// Rejecting the whole answer if any record is private stops attackers from
+/// mixing a public and a private record and hoping the client picks the latter.
+pub async fn resolve_public_addrs(host: &str, port: u16) -> Result<Vec<SocketAddr>, SafeHttpError> {
+    let bare_host = host.trim_start_matches('[').trim_end_matches(']');
+
+    if let Ok(ip) = bare_host.parse::<IpAddr>() {
+        if !is_public_ip(ip) {
+            return Err(SafeHttpError::BlockedAddress(ip));
+        }
+        return Ok(vec![SocketAddr::new(ip, port)]);
+    }
+
+    let addrs: Vec<SocketAddr> =
+        match tokio::time::timeout(DNS_TIMEOUT, tokio::net::lookup_host((bare_host, port))).await {
+            Ok(Ok(addrs)) => addrs.collect(),
+            Ok(Err(_)) => return Err(SafeHttpError::DnsResolutionFailed(host.to_string())),
+            Err(_) => return Err(SafeHttpError::DnsTimeout(host.to_string())),
+        };
+
+    if addrs.is_empty() {
+        return Err(SafeHttpError::DnsResolutionFailed(host.to_string()));
+    }
+
+    if let Some(blocked) = addrs.iter().find(|addr| !is_public_ip(addr.ip())) {
+        return Err(SafeHttpError::BlockedAddress(blocked.ip()));
+    }
+
+    Ok(addrs)
+}
+
+/// Validate that a URL is fetchable: http(s) with a host that resolves only to public addresses
+pub async fn validate_url(url: &Url) -> Result<Vec<SocketAddr>, SafeHttpError> {
+    match url.scheme() {
+        "http" | "https" => {},
+        other => return Err(SafeHttpError::UnsupportedScheme(other.to_string())),
+    }
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| SafeHttpError::InvalidUrl(url.to_string()))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| SafeHttpError::InvalidUrl(url.to_string()))?;
+
+    resolve_public_addrs(host, port).await
+}
+
+// =============================================================================
+// SAFE HTTP CLIENT
+// =============================================================================
+
+/// HTTP client for fetching user-supplied URLs.
+/// Redirects are followed manually so each hop goes through the same checks.
+#[derive(Debug, Clone)]
+pub struct SafeHttpClient {
+    timeout: Duration,
+    user_agent: String,
+    max_redirects: usize,
+}
+
+impl SafeHttpClient {
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            user_agent: user_agent.into(),
+            max_redirects: 3,
+        }
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_max_redirects(mut self, max_redirects: usize) -> Self {
+        self.max_redirects = max_redirects;
+        self
+    }
+
+    pub async fn get(&self, url: &str) -> Result<Response, SafeHttpError> {
+        self.request(Method::GET, url).await
+    }
+
+    pub async fn head(&self, url: &str) -> Result<Response, SafeHttpError> {
+        self.request(Method::HEAD, url).await
+    }
+
+    /// Send a request, following up to `max_redirects` redirects.
+    /// The returned response is never a redirect.
+    pub async fn request(&self, method: Method, url: &str) -> Result<Response, SafeHttpError> {
+        let mut current = Url::parse(url).map_err(|e| SafeHttpError::InvalidUrl(e.to_string()))?;
+
+        for hop in 0..=self.max_redirects {
+            let addrs = match validate_url(&current).await {
+                Ok(addrs) => addrs,
+                // A redirect into private space is reported distinctly from a bad initial URL
+                Err(SafeHttpError::BlockedAddress(ip)) if hop > 0 => {
+                    debug!("Blocked redirect hop to {} ({})", current, ip);
+                    return Err(SafeHttpError::RedirectBlocked(current.to_string()));
+                },
+                Err(e) => return Err(e),
+            };
+
+            let response = self
+                .pinned_client(&current, &addrs)?
+                .request(method.clone(), current.clone())
+                .send()
+                .await?;
+
+            if !is_followable_redirect(response.status()) {
+                return Ok(response);
+            }
+
+            let Some(next) = response
+                .headers()
+                .get(header::LOCATION)
+                .and_then(|location| location.to_str().ok())
+                .and_then(|location| current.join(location).ok())
+            else {
+                // Redirect without a usable Location header - hand it back as-is
+                return Ok(response);
+            };
+
+            current = next;
+        }
+
+        Err(SafeHttpError::TooManyRedirects(self.max_redirects))
+    }
+
+    /// One-off client that can only connect to the addresses we just validated
+    fn pinned_client(
+        &self,
+        url: &Url,
+        addrs: &[SocketAddr],
+    ) -> Result<reqwest::Client, SafeHttpError> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(&self.user_agent)
+            .redirect(redirect::Policy::none());
+
+        // The configured proxy, or direct if user fetches were opted out of it
+        builder = outbound_proxy::apply_to_user_fetch(builder);
+
+        // IP literals are connected to directly; hostnames are pinned to the validated addresses
+        if let Some(url::Host::Domain(domain)) = url.host() {
+            builder = builder.resolve_to_addrs(domain, addrs);
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+fn is_followable_redirect(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::MOVED_PERMANENTLY
+            | StatusCode::FOUND
+            | StatusCode::SEE_OTHER
+            | StatusCode::TEMPORARY_REDIRECT
+            | StatusCode::PERMANENT_REDIRECT
+    )
+}
@@ -0,0 +1,107 @@
+// Open-redirect protection for Location headers and links we render on our own domain
+// Anything sent to a browser as a place to go next must be a path on this origin or the
+// destination the link was created with. Browsers treat `//host`, `/\host` and paths with
+// tabs or newlines stripped out as other origins, so those are rejected rather than cleaned up.
+
+use url::Url;
+
+/// Placeholder origin used to resolve relative paths the way a browser would
+const INTERNAL_ORIGIN: &str = "https://internal.invalid/";
+
+/// Return `target` if it is safe to redirect or link to from one of our pages: a
+/// same-origin relative path, or exactly the link's own destination (validated when the
+/// link was created). Returns `None` for anything that could leave the origin.
+pub fn safe_internal_redirect(target: &str, link_destination: Option<&str>) -> Option<String> {
+    if is_same_origin_path(target) {
+        return Some(target.to_string());
+    }
+
+    link_destination
+        .filter(|destination| *destination == target)
+        .filter(|destination| is_http_destination(destination))
+        .map(str::to_string)
+}
+
+/// `/path` on this origin; never `//host`, a backslash, or characters browsers strip
+fn is_same_origin_path(target: &str) -> bool {
+    let Some(rest) = target.strip_prefix('/') else {
+        return false;
+    };
+    if rest.starts_with('/') || target.contains('\\') {
+        return false;
+    }
+    if target.chars().any(|c| c.is_control() || c.is_whitespace()) {
+        return false;
+    }
+
+    // Resolve it like a browser would and make sure it stays on the same origin
+    let Ok(base) = Url::parse(INTERNAL_ORIGIN) else {
+        return false;
+    };
+    base.join(target)
+        .is_ok_and(|resolved| resolved.origin() == base.origin())
+}
+
+/// Absolute http(s) URL with a host
+fn is_http_destination(destination: &str) -> bool {
+    Url::parse(destination).is_ok_and(|url| {
+        matches!(url.scheme(), "http" | "https") && url.host_str().is_some_and(|h| !h.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_same_origin_paths() {
+        for path in ["/", "/abc123", "/abc123/unlock", "/v1/docs/", "/a?b=c#d"] {
+            assert_eq!(
+                safe_internal_redirect(path, None).as_deref(),
+                Some(path),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_rejects_other_origins() {
+        for target in [
+            "//evil.com",
+            "//evil.com/abc123",
+            "///evil.com",
+            "https:evil.com",
+            "https://evil.com",
+            "/\\evil.com",
+            "\\\\evil.com",
+            "/\\/evil.com",
+            "/abc\\..\\..\\/evil.com",
+            "/\t/evil.com",
+            "/\n/evil.com",
+            " //evil.com",
+            "javascript:alert(1)",
+            "evil.com",
+            "",
+        ] {
+            assert_eq!(safe_internal_redirect(target, None), None, "{:?}", target);
+        }
+    }
+
+    #[test]
+    fn test_allows_only_the_links_own_destination() {
+        let destination = "https://example.com/landing?utm=qck";
+        assert_eq!(
+            safe_internal_redirect(destination, Some(destination)).as_deref(),
+            Some(destination)
+        );
+        assert_eq!(
+            safe_internal_redirect("https://evil.com", Some(destination)),
+            None
+        );
+        assert_eq!(
+            safe_internal_redirect("javascript:alert(1)", Some("javascript:alert(1)")),
+            None
+        );
+    }
+}
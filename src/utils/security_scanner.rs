@@ -1,13 +1,21 @@
 // DEV-104: URL Security Scanning
 // Comprehensive security scanning to prevent malicious links from being shortened
 
-use crate::db::ClickHouseClient;
+use crate::app_config::AppConfig;
+use crate::db::{ClickHouseClient, RedisPool};
+use crate::services::security_telemetry::{ScanOutcome, ScanTelemetry, SecurityScanEvent};
+use crate::utils::blocklist_registry::{BlockedDomainCategory, BlocklistRegistry};
+use crate::utils::outbound_proxy;
+use crate::utils::safe_browsing::{SafeBrowsingClient, SafeBrowsingConfig, SafeBrowsingThreat};
+use crate::utils::safe_http::{SafeHttpClient, SafeHttpError};
+use crate::utils::url_validator::{host_to_unicode, MAX_URL_LENGTH};
 use crate::utils::urlhaus_client::UrlhausClient;
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use chrono::{DateTime, Utc};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::collections::HashSet;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -100,12 +108,139 @@ pub struct SecurityWarning {
     pub severity: SecurityRiskLevel,
 }
 
+// =============================================================================
+// OPERATOR BLOCKLIST
+// =============================================================================
+
+/// Operator-managed entries from the `blocked_domains` table.
+/// Shared by every DomainSecurityService and swapped out by the blocklist refresh task.
+#[derive(Debug, Default, Clone)]
+pub struct OperatorBlocklist {
+    domains: HashMap<String, String>, // domain -> reason
+    tlds: HashMap<String, String>,    // tld -> reason
+}
+
+impl OperatorBlocklist {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_domain(&mut self, domain: &str, reason: &str) {
+        self.domains
+            .insert(domain.to_lowercase(), reason.to_string());
+    }
+
+    pub fn insert_tld(&mut self, tld: &str, reason: &str) {
+        self.tlds
+            .insert(tld.trim_start_matches('.').to_lowercase(), reason.to_string());
+    }
+
+    pub fn len(&self) -> usize {
+        self.domains.len() + self.tlds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the block reason if the domain, one of its parent domains, or its TLD is listed
+    pub fn matches(&self, domain: &str) -> Option<&str> {
+        let domain = domain.trim_end_matches('.').to_lowercase();
+
+        let mut candidate = domain.as_str();
+        loop {
+            if let Some(reason) = self.domains.get(candidate) {
+                return Some(reason);
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => break,
+            }
+        }
+
+        domain
+            .rsplit('.')
+            .next()
+            .and_then(|tld| self.tlds.get(tld))
+            .map(|reason| reason.as_str())
+    }
+}
+
+static OPERATOR_BLOCKLIST: Lazy<Arc<RwLock<OperatorBlocklist>>> =
+    Lazy::new(|| Arc::new(RwLock::new(OperatorBlocklist::new())));
+
+/// Replace the in-memory operator blocklist (called by the blocklist refresh task)
+pub async fn replace_operator_blocklist(blocklist: OperatorBlocklist) {
+    *OPERATOR_BLOCKLIST.write().await = blocklist;
+}
+
+// =============================================================================
+// TRUSTED DOMAINS
+// =============================================================================
+
+/// Domains whose URLs are accepted whatever they score, unless they are also blocked.
+/// Filled from SECURITY_TRUSTED_DOMAINS and the operator-managed `trusted_domains` table.
+#[derive(Debug, Default, Clone)]
+pub struct TrustedDomains {
+    domains: HashMap<String, String>, // domain -> reason
+}
+
+impl TrustedDomains {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, domain: &str, reason: &str) {
+        self.domains.insert(
+            domain.trim().trim_matches('.').to_lowercase(),
+            reason.to_string(),
+        );
+    }
+
+    pub fn len(&self) -> usize {
+        self.domains.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.domains.is_empty()
+    }
+
+    /// Returns the trust reason if the domain or one of its parent domains is listed
+    pub fn matches(&self, domain: &str) -> Option<&str> {
+        let domain = domain.trim_end_matches('.').to_lowercase();
+
+        let mut candidate = domain.as_str();
+        loop {
+            if let Some(reason) = self.domains.get(candidate) {
+                return Some(reason);
+            }
+            match candidate.split_once('.') {
+                Some((_, parent)) => candidate = parent,
+                None => return None,
+            }
+        }
+    }
+}
+
+static OPERATOR_TRUSTED_DOMAINS: Lazy<Arc<RwLock<TrustedDomains>>> =
+    Lazy::new(|| Arc::new(RwLock::new(TrustedDomains::new())));
+
+/// Replace the in-memory operator trusted domains (called by the blocklist refresh task)
+pub async fn replace_operator_trusted_domains(trusted: TrustedDomains) {
+    *OPERATOR_TRUSTED_DOMAINS.write().await = trusted;
+}
+
 // =============================================================================
 // DOMAIN SECURITY SERVICE
 // =============================================================================
 
+/// Threat score at or above which a URL is blocked, unless configured otherwise
+pub const DEFAULT_BLOCK_THRESHOLD: u8 = 41;
+
 pub struct DomainSecurityService {
-    blacklist: Arc<RwLock<HashSet<String>>>,
+    blocklist: Arc<BlocklistRegistry>,
+    operator_blocklist: Arc<RwLock<OperatorBlocklist>>,
+    block_threshold: u8,
     reputation_cache: Arc<RwLock<HashMap<String, ReputationScore>>>,
     threat_intel_client: Arc<ThreatIntelClient>,
     homograph_detector: HomographDetector,
@@ -113,79 +248,37 @@ pub struct DomainSecurityService {
 
 impl DomainSecurityService {
     pub fn new() -> Self {
-        let mut blacklist = HashSet::new();
-
-        // Load from JSON file (same pattern as url_validator.rs)
-        let json_path = "data/blocked_domains.json";
-        match std::fs::read_to_string(json_path) {
-            Ok(content) => {
-                if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
-                    // Load URL shorteners
-                    if let Some(shorteners) =
-                        json["blocked_domains"]["url_shorteners"]["domains"].as_array()
-                    {
-                        for domain in shorteners {
-                            if let Some(d) = domain.as_str() {
-                                blacklist.insert(d.to_string());
-                            }
-                        }
-                    }
-
-                    // Load local addresses
-                    if let Some(local) =
-                        json["blocked_domains"]["local_addresses"]["domains"].as_array()
-                    {
-                        for domain in local {
-                            if let Some(d) = domain.as_str() {
-                                blacklist.insert(d.to_string());
-                            }
-                        }
-                    }
-
-                    // Load malicious domains
-                    if let Some(malicious) =
-                        json["blocked_domains"]["malicious"]["domains"].as_array()
-                    {
-                        for domain in malicious {
-                            if let Some(d) = domain.as_str() {
-                                blacklist.insert(d.to_string());
-                            }
-                        }
-                    }
-
-                    // Load blocked TLDs
-                    if let Some(tlds) = json["blocked_tlds"]["tlds"].as_array() {
-                        for tld in tlds {
-                            if let Some(t) = tld.as_str() {
-                                blacklist.insert(t.to_string());
-                            }
-                        }
-                    }
-
-                    tracing::info!("Loaded {} blocked domains from JSON", blacklist.len());
-                }
-            },
-            Err(e) => {
-                tracing::warn!(
-                    "Failed to load blocked_domains.json: {}, using fallback list",
-                    e
-                );
-                // Fallback to minimal hardcoded list if JSON fails
-                let fallback = ["bit.ly", "tinyurl.com", "localhost", "127.0.0.1"];
-                for domain in &fallback {
-                    blacklist.insert(domain.to_string());
-                }
-            },
-        }
+        Self::with_registry(BlocklistRegistry::shared())
+    }
 
+    pub fn with_registry(blocklist: Arc<BlocklistRegistry>) -> Self {
         Self {
-            blacklist: Arc::new(RwLock::new(blacklist)),
+            threat_intel_client: Arc::new(ThreatIntelClient::with_registry(blocklist.clone())),
+            blocklist,
+            operator_blocklist: OPERATOR_BLOCKLIST.clone(),
+            block_threshold: DEFAULT_BLOCK_THRESHOLD,
             reputation_cache: Arc::new(RwLock::new(HashMap::new())),
-            threat_intel_client: Arc::new(ThreatIntelClient::new()),
             homograph_detector: HomographDetector::new(),
         }
     }
 
+    /// Block domains scoring at or above `threshold` (clamped to 1..=100)
+    pub fn with_block_threshold(mut self, threshold: u8) -> Self {
+        self.block_threshold = threshold.clamp(1, 100);
+        self
+    }
+
+    /// Whether the domain is on the static or operator blocklist
+    pub async fn is_blocked(&self, domain: &str) -> bool {
+        self.blocklist.is_blocked_domain(domain)
+            || self
+                .operator_blocklist
+                .read()
+                .await
+                .matches(domain)
+                .is_some()
+    }
+
     pub async fn check_domain_reputation(
         &self,
         domain: &str,
@@ -196,10 +289,9 @@ impl DomainSecurityService {
         let mut threat_score = 0u8;
 
         // 1. Check static blacklist
-        let blacklist = self.blacklist.read().await;
-        if blacklist.contains(domain) {
+        if let Some(category) = self.blocklist.blocked_category(domain) {
             // Check if it's a URL shortener or other blocked domain
-            if self.is_url_shortener(domain) {
+            if category == BlockedDomainCategory::Shortener {
                 threats_detected.push(ThreatType::ShortenerChaining);
                 threat_score += 50; // Lower score for shorteners
                 warnings.push(format!(
@@ -227,17 +319,33 @@ impl DomainSecurityService {
                 scan_duration_ms: start_time.elapsed().as_millis() as u64,
             });
         }
-        drop(blacklist);
+
+        // 1b. Check operator-managed blocklist (domain, parent domain, or TLD)
+        let operator_blocklist = self.operator_blocklist.read().await;
+        if let Some(reason) = operator_blocklist.matches(domain) {
+            warnings.push(format!("Domain {} is blocked by operator: {}", domain, reason));
+            return Ok(SecurityScanResult {
+                url: domain.to_string(),
+                is_safe: false,
+                threat_score: 100,
+                risk_level: SecurityRiskLevel::Critical,
+                threats_detected: vec![ThreatType::Malware],
+                warnings,
+                scan_timestamp: Utc::now(),
+                scan_duration_ms: start_time.elapsed().as_millis() as u64,
+            });
+        }
+        drop(operator_blocklist);
 
         // 2. Check for URL shortener domains
-        if self.is_url_shortener(domain) {
+        if self.blocklist.is_shortener(domain) {
             threats_detected.push(ThreatType::ShortenerChaining);
             threat_score += 30;
             warnings.push("URL shortener detected - potential chain risk".to_string());
         }
 
         // 3. Check for suspicious TLD
-        if self.has_suspicious_tld(domain) {
+        if self.blocklist.has_suspicious_tld(domain) {
             threats_detected.push(ThreatType::SuspiciousTld);
             threat_score += 20;
             warnings.push(format!("Suspicious TLD detected in domain: {}", domain));
@@ -309,7 +417,7 @@ impl DomainSecurityService {
 
         Ok(SecurityScanResult {
             url: domain.to_string(),
-            is_safe: threat_score < 41, // Medium risk and below is considered safe
+            is_safe: threat_score < self.block_threshold,
             threat_score,
             risk_level,
             threats_detected,
@@ -319,58 +427,6 @@ impl DomainSecurityService {
         })
     }
 
-    fn is_url_shortener(&self, domain: &str) -> bool {
-        let shorteners = [
-            "bit.ly",
-            "tinyurl.com",
-            "goo.gl",
-            "ow.ly",
-            "is.gd",
-            "buff.ly",
-            "t.co",
-            "short.link",
-            "tiny.cc",
-            "rb.gy",
-            "cutt.ly",
-            "shorturl.at",
-            "1url.com",
-            "2.gp",
-            "7.ly",
-            "0.gp",
-            "1-url.net",
-        ];
-
-        shorteners
-            .iter()
-            .any(|&shortener| domain.contains(shortener))
-    }
-
-    fn has_suspicious_tld(&self, domain: &str) -> bool {
-        let suspicious_tlds = [
-            ".tk",
-            ".ml",
-            ".cf",
-            ".ga",
-            ".click",
-            ".download",
-            ".zip",
-            ".exe",
-            ".scr",
-            ".bat",
-            ".cmd",
-            ".pif",
-            ".com.ru",
-            ".co.cc",
-            ".bit",
-            ".onion",
-            ".i2p",
-            ".exit",
-            ".darkweb",
-        ];
-
-        suspicious_tlds.iter().any(|&tld| domain.ends_with(tld))
-    }
-
     fn calculate_risk_level(&self, score: u8) -> SecurityRiskLevel {
         match score {
             0..=20 => SecurityRiskLevel::Safe,
@@ -389,45 +445,15 @@ impl DomainSecurityService {
 
 pub struct UrlPatternAnalyzer {
     suspicious_patterns: Vec<Regex>,
-    phishing_keywords: HashSet<String>,
+    blocklist: Arc<BlocklistRegistry>,
 }
 
 impl UrlPatternAnalyzer {
     pub fn new() -> Self {
-        let mut phishing_keywords = HashSet::new();
-
-        // Common phishing keywords
-        let keywords = [
-            "verify-account",
-            "suspend-account",
-            "update-payment",
-            "confirm-identity",
-            "security-alert",
-            "unusual-activity",
-            "prize-winner",
-            "congratulations-winner",
-            "limited-time-offer",
-            "click-here-now",
-            "download-free",
-            "urgent-action",
-            "verify-now",
-            "update-info",
-            "confirm-email",
-            "reset-password",
-            "account-locked",
-            "suspended-account",
-            "billing-issue",
-            "payment-failed",
-            "tax-refund",
-            "lottery-winner",
-            "free-money",
-            "inheritance-claim",
-        ];
-
-        for keyword in &keywords {
-            phishing_keywords.insert(keyword.to_string());
-        }
+        Self::with_registry(BlocklistRegistry::shared())
+    }
 
+    pub fn with_registry(blocklist: Arc<BlocklistRegistry>) -> Self {
         // Compile suspicious patterns
         let patterns = vec![
             // Multiple subdomains (could indicate subdomain takeover)
@@ -445,7 +471,7 @@ impl UrlPatternAnalyzer {
 
         Self {
             suspicious_patterns: patterns,
-            phishing_keywords,
+            blocklist,
         }
     }
 
@@ -453,7 +479,7 @@ impl UrlPatternAnalyzer {
         let mut warnings = Vec::new();
 
         // ReDoS protection: Skip pattern analysis for overly long URLs
-        if url.len() > 2048 {
+        if url.len() > MAX_URL_LENGTH {
             warnings.push(SecurityWarning {
                 warning_type: ThreatType::SuspiciousRedirect,
                 message: "URL exceeds maximum safe length for analysis".to_string(),
@@ -465,8 +491,8 @@ impl UrlPatternAnalyzer {
         let url_lower = url.to_lowercase();
 
         // Check for phishing keywords
-        for keyword in &self.phishing_keywords {
-            if url_lower.contains(keyword) {
+        for keyword in self.blocklist.phishing_keywords() {
+            if url_lower.contains(keyword.as_str()) {
                 warnings.push(SecurityWarning {
                     warning_type: ThreatType::Phishing,
                     message: format!("Suspicious keyword detected: {}", keyword),
@@ -523,22 +549,33 @@ impl HomographDetector {
         Self { confusable_chars }
     }
 
+    /// Whether any single label of the host mixes Latin, Cyrillic or Greek letters.
+    /// Punycode labels are decoded first, so `xn--pple-43d.com` is judged as `аpple.com`.
+    pub fn has_mixed_scripts(domain: &str) -> bool {
+        host_to_unicode(domain).split('.').any(|label| {
+            let has_cyrillic = label
+                .chars()
+                .any(|c| ('\u{0400}'..='\u{04FF}').contains(&c));
+            let has_greek = label
+                .chars()
+                .any(|c| ('\u{0370}'..='\u{03FF}').contains(&c));
+            let has_latin = label.chars().any(|c| c.is_ascii_alphabetic());
+
+            [has_cyrillic, has_greek, has_latin]
+                .iter()
+                .filter(|&&x| x)
+                .count()
+                > 1
+        })
+    }
+
     pub fn has_homograph_attack(&self, domain: &str) -> bool {
-        // Check for mixed scripts
-        let has_cyrillic = domain
-            .chars()
-            .any(|c| ('\u{0400}'..='\u{04FF}').contains(&c));
-        let has_greek = domain
-            .chars()
-            .any(|c| ('\u{0370}'..='\u{03FF}').contains(&c));
-        let has_latin = domain.chars().any(|c| c.is_ascii_alphabetic());
-
-        // If multiple scripts are present, it's potentially suspicious
-        let script_count = [has_cyrillic, has_greek, has_latin]
-            .iter()
-            .filter(|&&x| x)
-            .count();
-        if script_count > 1 {
+        // URLs arrive punycoded; analyze the Unicode form the user would actually see
+        let domain = host_to_unicode(domain);
+        let domain = domain.as_str();
+
+        // If multiple scripts are present within a label, it's potentially suspicious
+        if Self::has_mixed_scripts(domain) {
             return true;
         }
 
@@ -561,24 +598,22 @@ impl HomographDetector {
 // =============================================================================
 
 pub struct ContentScanner {
-    client: reqwest::Client,
+    client: SafeHttpClient,
 }
 
 impl ContentScanner {
     pub fn new() -> Self {
         Self {
-            client: reqwest::Client::builder()
-                .timeout(Duration::from_secs(5))
-                .user_agent("QCK-SecurityScanner/1.0")
-                .redirect(reqwest::redirect::Policy::limited(3)) // Limit redirects
-                .build()
-                .unwrap_or_default(),
+            // Every hop is resolved and checked against private ranges (SSRF protection)
+            client: SafeHttpClient::new("QCK-SecurityScanner/1.0")
+                .with_timeout(Duration::from_secs(5))
+                .with_max_redirects(3),
         }
     }
 
     pub async fn scan_url_content(&self, url: &str) -> Result<ContentScanResult, SecurityError> {
         // Use HEAD request to avoid downloading full content
-        match self.client.head(url).send().await {
+        match self.client.head(url).await {
             Ok(response) => {
                 let mut threats = Vec::new();
                 let mut warnings = Vec::new();
@@ -647,13 +682,31 @@ impl ContentScanner {
                         threat_score: 10,
                         is_safe: true, // Don't block on timeout
                     })
-                } else if e.is_redirect() {
+                } else if let SafeHttpError::TooManyRedirects(_) = e {
                     Ok(ContentScanResult {
                         threats_detected: vec![ThreatType::SuspiciousRedirect],
                         warnings: vec!["Too many redirects".to_string()],
                         threat_score: 25,
                         is_safe: true,
                     })
+                } else if let SafeHttpError::RedirectBlocked(target) = e {
+                    // A public URL bouncing into private address space is an SSRF attempt
+                    Ok(ContentScanResult {
+                        threats_detected: vec![ThreatType::SuspiciousRedirect],
+                        warnings: vec![format!(
+                            "Redirects to a non-public address: {}",
+                            target
+                        )],
+                        threat_score: 50,
+                        is_safe: false,
+                    })
+                } else if let SafeHttpError::BlockedAddress(ip) = e {
+                    Ok(ContentScanResult {
+                        threats_detected: vec![ThreatType::SuspiciousRedirect],
+                        warnings: vec![format!("Resolves to a non-public address: {}", ip)],
+                        threat_score: 50,
+                        is_safe: false,
+                    })
                 } else {
                     // Other errors - don't block but log
                     Ok(ContentScanResult {
@@ -682,18 +735,24 @@ pub struct ContentScanResult {
 
 pub struct ThreatIntelClient {
     client: reqwest::Client,
+    blocklist: Arc<BlocklistRegistry>,
     cache: Arc<RwLock<HashMap<String, (u8, Instant)>>>,
     cache_ttl: Duration,
 }
 
 impl ThreatIntelClient {
     pub fn new() -> Self {
+        Self::with_registry(BlocklistRegistry::shared())
+    }
+
+    pub fn with_registry(blocklist: Arc<BlocklistRegistry>) -> Self {
         Self {
-            client: reqwest::Client::builder()
+            client: outbound_proxy::apply(reqwest::Client::builder())
                 .timeout(Duration::from_secs(10))
                 .user_agent("QCK-SecurityScanner/1.0")
                 .build()
                 .unwrap_or_default(),
+            blocklist,
             cache: Arc::new(RwLock::new(HashMap::new())),
             cache_ttl: Duration::from_secs(3600), // 1 hour cache
         }
@@ -778,27 +837,8 @@ impl ThreatIntelClient {
             score += 10; // Excessive hyphens (common in phishing)
         }
 
-        // 6. Suspicious keyword detection
-        let keywords = [
-            ("secure", 10),
-            ("verify", 15),
-            ("update", 10),
-            ("suspended", 20),
-            ("urgent", 15),
-            ("confirm", 10),
-            ("validate", 10),
-            ("unlock", 15),
-            ("refund", 10),
-            ("winner", 20),
-            ("prize", 20),
-            ("free", 5),
-        ];
-
-        for (keyword, weight) in &keywords {
-            if domain_lower.contains(keyword) {
-                score += weight;
-            }
-        }
+        // 6. Suspicious keyword detection (weights come from the blocked domains list)
+        score = score.saturating_add(self.blocklist.threat_keyword_score(&domain_lower));
 
         // 7. Known brand impersonation patterns
         if self.detect_brand_impersonation(&domain_lower) {
@@ -806,12 +846,8 @@ impl ThreatIntelClient {
         }
 
         // 8. TLD risk assessment
-        let high_risk_tlds = [".tk", ".ml", ".ga", ".cf", ".click", ".download", ".stream"];
-        for tld in &high_risk_tlds {
-            if domain_lower.ends_with(tld) {
-                score += 20;
-                break;
-            }
+        if self.blocklist.has_suspicious_tld(&domain_lower) {
+            score += 20;
         }
 
         // Cap at 100
@@ -859,17 +895,8 @@ impl ThreatIntelClient {
     }
 
     fn contains_homographs(&self, domain: &str) -> bool {
-        // Check for mixed scripts (simplified)
-        let has_cyrillic = domain
-            .chars()
-            .any(|c| ('\u{0400}'..='\u{04FF}').contains(&c));
-        let has_latin = domain.chars().any(|c| c.is_ascii_alphabetic());
-        let has_greek = domain
-            .chars()
-            .any(|c| ('\u{0370}'..='\u{03FF}').contains(&c));
-
         // Mixed scripts are suspicious
-        (has_cyrillic && has_latin) || (has_greek && has_latin)
+        HomographDetector::has_mixed_scripts(domain)
     }
 
     fn detect_brand_impersonation(&self, domain: &str) -> bool {
@@ -901,18 +928,6 @@ impl ThreatIntelClient {
     // See Linear EPIC for implementation roadmap
     // ==========================================================================
 
-    /// Google Safe Browsing API integration
-    /// Cost: ~$0.001 per query, ~$1000/month for 1M queries
-    /// TODO: Implement when budget allows (see Linear EPIC)
-    pub async fn check_google_safe_browsing(&self, _url: &str) -> Result<bool, SecurityError> {
-        // Stub: Returns false (safe) to avoid blocking legitimate URLs
-        // In production with API key:
-        // - Check URL against Google's malware/phishing database
-        // - Cache results for 30 minutes
-        // - Implement exponential backoff for rate limits
-        Ok(false)
-    }
-
     /// VirusTotal API integration  
     /// Cost: $10,000+/year for commercial use
     /// TODO: Implement when budget allows (see Linear EPIC)
@@ -940,78 +955,107 @@ impl ThreatIntelClient {
 }
 
 // =============================================================================
-// MAIN SECURITY SERVICE
+// EXTERNAL THREAT LOOKUPS
 // =============================================================================
 
-pub struct SecurityService {
-    domain_security: DomainSecurityService,
-    pattern_analyzer: UrlPatternAnalyzer,
+// Shared Safe Browsing client so quota backoff state survives across requests
+static SAFE_BROWSING_CLIENT: Lazy<Option<Arc<SafeBrowsingClient>>> = Lazy::new(|| {
+    SafeBrowsingConfig::from_app_config(&crate::CONFIG.security)
+        .map(|config| Arc::new(SafeBrowsingClient::new(config)))
+});
+
+/// The networked part of a full scan, run after the local checks pass
+#[async_trait]
+pub trait ThreatLookup: Send + Sync {
+    /// Look the URL up and merge what is found into `scan_result`. A lookup that fails
+    /// or times out adds nothing.
+    async fn check(
+        &self,
+        url_str: &str,
+        redis_pool: Option<&RedisPool>,
+        scan_result: &mut SecurityScanResult,
+    );
+}
+
+/// URLhaus, Google Safe Browsing (when configured) and a HEAD request to the URL
+pub struct NetworkThreatLookup {
     content_scanner: ContentScanner,
     urlhaus_client: Arc<UrlhausClient>,
+    safe_browsing: Option<Arc<SafeBrowsingClient>>,
+    safe_browsing_score: u8,
 }
 
-impl SecurityService {
+impl NetworkThreatLookup {
     pub fn new(clickhouse_client: Arc<ClickHouseClient>) -> Self {
         Self {
-            domain_security: DomainSecurityService::new(),
-            pattern_analyzer: UrlPatternAnalyzer::new(),
             content_scanner: ContentScanner::new(),
             urlhaus_client: Arc::new(UrlhausClient::new(clickhouse_client)),
+            safe_browsing: SAFE_BROWSING_CLIENT.clone(),
+            safe_browsing_score: crate::CONFIG.security.safe_browsing_threat_score,
         }
     }
 
-    pub async fn comprehensive_security_scan(
-        &self,
-        url_str: &str,
-    ) -> Result<SecurityScanResult, SecurityError> {
-        let start_time = std::time::Instant::now();
-
-        // ReDoS protection: Limit URL length before any regex operations
-        const MAX_URL_LENGTH: usize = 2048;
-        if url_str.len() > MAX_URL_LENGTH {
-            return Err(SecurityError::SuspiciousPattern(format!(
-                "URL too long: {} bytes (max: {})",
-                url_str.len(),
-                MAX_URL_LENGTH
-            )));
-        }
-
-        let url = Url::parse(url_str).map_err(|_| SecurityError::InternalError)?;
-
-        let domain = url.host_str().ok_or(SecurityError::InternalError)?;
-
-        // 1. Domain reputation check
-        let mut scan_result = self.domain_security.check_domain_reputation(domain).await?;
+    /// Override the Safe Browsing client (e.g. to point at a different endpoint)
+    pub fn with_safe_browsing(mut self, client: Arc<SafeBrowsingClient>, score: u8) -> Self {
+        self.safe_browsing = Some(client);
+        self.safe_browsing_score = score.min(100);
+        self
+    }
 
-        // 2. URL pattern analysis
-        let pattern_warnings = self.pattern_analyzer.analyze_suspicious_patterns(url_str);
-        for warning in pattern_warnings {
-            if !scan_result.threats_detected.contains(&warning.warning_type) {
-                scan_result.threats_detected.push(warning.warning_type);
-            }
-            scan_result.warnings.push(warning.message);
+    /// Google Safe Browsing v4 lookup.
+    /// Returns an empty list when the integration is disabled or the URL is clean.
+    pub async fn check_google_safe_browsing(
+        &self,
+        url: &str,
+        redis_pool: Option<&RedisPool>,
+    ) -> Result<Vec<ThreatType>, SecurityError> {
+        let Some(client) = self.safe_browsing.as_ref() else {
+            return Ok(Vec::new());
+        };
 
-            // Adjust threat score based on pattern warnings
-            let pattern_score = match warning.severity {
-                SecurityRiskLevel::Low => 5,
-                SecurityRiskLevel::Medium => 15,
-                SecurityRiskLevel::High => 25,
-                SecurityRiskLevel::Critical => 40,
-                SecurityRiskLevel::Safe => 0,
+        let matches = client
+            .check_url(url, redis_pool)
+            .await
+            .map_err(|e| SecurityError::ThreatIntelError(e.to_string()))?;
+
+        let mut threats = Vec::new();
+        for threat in matches {
+            let threat_type = match threat {
+                SafeBrowsingThreat::SocialEngineering => ThreatType::Phishing,
+                SafeBrowsingThreat::Malware
+                | SafeBrowsingThreat::UnwantedSoftware
+                | SafeBrowsingThreat::PotentiallyHarmfulApplication => ThreatType::Malware,
             };
-            scan_result.threat_score = (scan_result.threat_score + pattern_score).min(100);
+            if !threats.contains(&threat_type) {
+                threats.push(threat_type);
+            }
         }
 
+        Ok(threats)
+    }
+}
+
+#[async_trait]
+impl ThreatLookup for NetworkThreatLookup {
+    async fn check(
+        &self,
+        url_str: &str,
+        redis_pool: Option<&RedisPool>,
+        scan_result: &mut SecurityScanResult,
+    ) {
         // 3. URLhaus threat intelligence check (FREE)
         // Check against known malware/phishing URLs from abuse.ch
         match tokio::time::timeout(
             Duration::from_secs(2),
-            self.urlhaus_client.check_url(url_str),
+            self.urlhaus_client.check_url(url_str, redis_pool),
         )
         .await
         {
-            Ok(Ok(is_malicious)) => {
-                if is_malicious {
+            Ok(Ok(check)) => {
+                if check.feed_is_stale() {
+                    tracing::debug!("URLhaus verdict for {} comes from a stale feed", url_str);
+                }
+                if check.is_threat {
                     scan_result.threats_detected.push(ThreatType::Malware);
                     scan_result.threat_score = (scan_result.threat_score + 50).min(100);
                     scan_result
@@ -1028,10 +1072,13 @@ impl SecurityService {
         }
 
         // Also check domain reputation in URLhaus
-        if let Some(domain) = url.host_str() {
+        if let Some(domain) = Url::parse(url_str)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        {
             match tokio::time::timeout(
                 Duration::from_secs(1),
-                self.urlhaus_client.check_domain(domain),
+                self.urlhaus_client.check_domain(&domain),
             )
             .await
             {
@@ -1055,6 +1102,38 @@ impl SecurityService {
             }
         }
 
+        // Google Safe Browsing (only when configured with an API key)
+        if self.safe_browsing.is_some() {
+            match tokio::time::timeout(
+                Duration::from_secs(3),
+                self.check_google_safe_browsing(url_str, redis_pool),
+            )
+            .await
+            {
+                Ok(Ok(threats)) if !threats.is_empty() => {
+                    for threat in threats {
+                        if !scan_result.threats_detected.contains(&threat) {
+                            scan_result.threats_detected.push(threat);
+                        }
+                    }
+                    scan_result.threat_score = scan_result
+                        .threat_score
+                        .saturating_add(self.safe_browsing_score)
+                        .min(100);
+                    scan_result
+                        .warnings
+                        .push("URL flagged by Google Safe Browsing".to_string());
+                },
+                Ok(Ok(_)) => {},
+                Ok(Err(e)) => {
+                    tracing::debug!("Safe Browsing check error: {}", e);
+                },
+                Err(_) => {
+                    tracing::debug!("Safe Browsing check timed out");
+                },
+            }
+        }
+
         // 4. Content scanning (lightweight HEAD request only)
         // Only scan if not already high risk
         if scan_result.threat_score < 60 {
@@ -1083,6 +1162,250 @@ impl SecurityService {
                 },
             }
         }
+    }
+}
+
+/// Records each URL it is asked about and finds nothing, so scans stay offline (TEST_MODE)
+#[derive(Debug, Default)]
+pub struct RecordingThreatLookup {
+    checked: std::sync::Mutex<Vec<String>>,
+}
+
+impl RecordingThreatLookup {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// URLs looked up so far, oldest first
+    pub fn checked_urls(&self) -> Vec<String> {
+        self.checked
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+#[async_trait]
+impl ThreatLookup for RecordingThreatLookup {
+    async fn check(
+        &self,
+        url_str: &str,
+        _redis_pool: Option<&RedisPool>,
+        _scan_result: &mut SecurityScanResult,
+    ) {
+        self.checked
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(url_str.to_string());
+    }
+}
+
+/// The lookups for AppState: the recorder when TEST_MODE is set, the network otherwise
+pub fn configured_threat_lookup(config: &AppConfig) -> Arc<dyn ThreatLookup> {
+    if config.test_mode {
+        Arc::new(RecordingThreatLookup::new())
+    } else {
+        Arc::new(NetworkThreatLookup::new(
+            crate::db::clickhouse_client::create_clickhouse_client(),
+        ))
+    }
+}
+
+// =============================================================================
+// MAIN SECURITY SERVICE
+// =============================================================================
+
+pub struct SecurityService {
+    domain_security: DomainSecurityService,
+    pattern_analyzer: UrlPatternAnalyzer,
+    threat_lookup: Arc<dyn ThreatLookup>,
+    block_threshold: u8,
+    trusted_domains: TrustedDomains,
+    operator_trusted_domains: Arc<RwLock<TrustedDomains>>,
+    telemetry: ScanTelemetry,
+    redis_pool: Option<RedisPool>,
+}
+
+impl SecurityService {
+    pub fn new(clickhouse_client: Arc<ClickHouseClient>) -> Self {
+        let security = &crate::CONFIG.security;
+
+        let mut trusted_domains = TrustedDomains::new();
+        for domain in &security.trusted_domains {
+            trusted_domains.insert(domain, "listed in SECURITY_TRUSTED_DOMAINS");
+        }
+
+        let threat_lookup = Arc::new(NetworkThreatLookup::new(clickhouse_client.clone()));
+        Self {
+            telemetry: ScanTelemetry::new(
+                clickhouse_client.clone(),
+                security.scan_telemetry_sample_rate,
+            ),
+            ..Self::with_lookup(clickhouse_client, threat_lookup)
+        }
+        .with_block_threshold(security.block_threshold)
+        .with_trusted_domains(trusted_domains)
+    }
+
+    /// Service that reads no global configuration: the default block threshold, no trusted
+    /// domains from SECURITY_TRUSTED_DOMAINS and no scan telemetry. `threat_lookup` runs the
+    /// external part of full scans.
+    pub fn with_lookup(
+        clickhouse_client: Arc<ClickHouseClient>,
+        threat_lookup: Arc<dyn ThreatLookup>,
+    ) -> Self {
+        Self {
+            domain_security: DomainSecurityService::new(),
+            pattern_analyzer: UrlPatternAnalyzer::new(),
+            threat_lookup,
+            block_threshold: DEFAULT_BLOCK_THRESHOLD,
+            trusted_domains: TrustedDomains::new(),
+            operator_trusted_domains: OPERATOR_TRUSTED_DOMAINS.clone(),
+            telemetry: ScanTelemetry::new(clickhouse_client, 0.0),
+            redis_pool: None,
+        }
+    }
+
+    /// Block URLs scoring at or above `threshold` (clamped to 1..=100)
+    pub fn with_block_threshold(mut self, threshold: u8) -> Self {
+        self.block_threshold = threshold.clamp(1, 100);
+        self.domain_security = self.domain_security.with_block_threshold(threshold);
+        self
+    }
+
+    /// Replace the configured trusted domains (operator-managed entries still apply)
+    pub fn with_trusted_domains(mut self, trusted_domains: TrustedDomains) -> Self {
+        self.trusted_domains = trusted_domains;
+        self
+    }
+
+    /// Why the domain is trusted; blocklist entries take precedence over trust
+    async fn trusted_reason(&self, domain: &str) -> Option<String> {
+        let reason = match self.trusted_domains.matches(domain) {
+            Some(reason) => reason.to_string(),
+            None => self
+                .operator_trusted_domains
+                .read()
+                .await
+                .matches(domain)?
+                .to_string(),
+        };
+
+        if self.domain_security.is_blocked(domain).await {
+            return None;
+        }
+        Some(reason)
+    }
+
+    /// Use Redis to cache external threat intelligence verdicts
+    pub fn with_redis_cache(mut self, redis_pool: RedisPool) -> Self {
+        self.redis_pool = Some(redis_pool);
+        self
+    }
+
+    /// Replace the external lookups, e.g. with the TEST_MODE recorder
+    pub fn with_threat_lookup(mut self, threat_lookup: Arc<dyn ThreatLookup>) -> Self {
+        self.threat_lookup = threat_lookup;
+        self
+    }
+
+    /// Scan a URL and record the (sampled) result to ClickHouse without waiting on it
+    pub async fn comprehensive_security_scan(
+        &self,
+        url_str: &str,
+    ) -> Result<SecurityScanResult, SecurityError> {
+        let (scan_result, outcome) = self.scan(url_str, true).await?;
+
+        if let Some(domain) = Url::parse(url_str)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+        {
+            self.telemetry.record(SecurityScanEvent::from_result(
+                &scan_result,
+                &domain,
+                outcome,
+            ));
+        }
+
+        Ok(scan_result)
+    }
+
+    /// Local checks only (blocklists, domain reputation, URL patterns), without the external
+    /// lookups and content fetch. For callers that run the full scan afterwards; not recorded
+    /// to telemetry.
+    pub async fn quick_security_scan(
+        &self,
+        url_str: &str,
+    ) -> Result<SecurityScanResult, SecurityError> {
+        self.scan(url_str, false).await.map(|(result, _)| result)
+    }
+
+    async fn scan(
+        &self,
+        url_str: &str,
+        external: bool,
+    ) -> Result<(SecurityScanResult, ScanOutcome), SecurityError> {
+        let start_time = std::time::Instant::now();
+
+        // ReDoS protection: Limit URL length before any regex operations
+        if url_str.len() > MAX_URL_LENGTH {
+            return Err(SecurityError::SuspiciousPattern(format!(
+                "URL too long: {} bytes (max: {})",
+                url_str.len(),
+                MAX_URL_LENGTH
+            )));
+        }
+
+        let url = Url::parse(url_str).map_err(|_| SecurityError::InternalError)?;
+
+        let domain = url.host_str().ok_or(SecurityError::InternalError)?;
+
+        // 1. Domain reputation check
+        let mut scan_result = self.domain_security.check_domain_reputation(domain).await?;
+
+        // 2. URL pattern analysis
+        let pattern_warnings = self.pattern_analyzer.analyze_suspicious_patterns(url_str);
+        for warning in pattern_warnings {
+            if !scan_result.threats_detected.contains(&warning.warning_type) {
+                scan_result.threats_detected.push(warning.warning_type);
+            }
+            scan_result.warnings.push(warning.message);
+
+            // Adjust threat score based on pattern warnings
+            let pattern_score = match warning.severity {
+                SecurityRiskLevel::Low => 5,
+                SecurityRiskLevel::Medium => 15,
+                SecurityRiskLevel::High => 25,
+                SecurityRiskLevel::Critical => 40,
+                SecurityRiskLevel::Safe => 0,
+            };
+            scan_result.threat_score = (scan_result.threat_score + pattern_score).min(100);
+        }
+
+        // Trusted domains skip the external lookups and are never blocked. The local score is
+        // still logged so operators can see what the allowlist overrides.
+        if let Some(reason) = self.trusted_reason(domain).await {
+            tracing::info!(
+                "Trusted domain {} ({}) scored {}; accepting without external checks",
+                domain,
+                reason,
+                scan_result.threat_score
+            );
+            scan_result.risk_level = self
+                .domain_security
+                .calculate_risk_level(scan_result.threat_score);
+            scan_result.is_safe = true;
+            scan_result.scan_duration_ms = start_time.elapsed().as_millis() as u64;
+            scan_result.url = url_str.to_string();
+            return Ok((scan_result, ScanOutcome::Trusted));
+        }
+
+        // 3-4. Threat intelligence and content checks over the network
+        if external {
+            self.threat_lookup
+                .check(url_str, self.redis_pool.as_ref(), &mut scan_result)
+                .await;
+        }
 
         // 5. Update risk level and safety based on final score
         scan_result.risk_level = match scan_result.threat_score {
@@ -1094,12 +1417,18 @@ impl SecurityService {
             _ => SecurityRiskLevel::Critical, // Fallback for impossible values > 100
         };
 
-        scan_result.is_safe = scan_result.threat_score < 41;
+        scan_result.is_safe = scan_result.threat_score < self.block_threshold;
         scan_result.scan_duration_ms = start_time.elapsed().as_millis() as u64;
         scan_result.url = url_str.to_string();
 
-        Ok(scan_result)
+        let outcome = if scan_result.is_safe {
+            ScanOutcome::Allowed
+        } else {
+            ScanOutcome::Blocked
+        };
+        Ok((scan_result, outcome))
     }
+
 }
 
 impl Default for SecurityService {
@@ -1137,6 +1466,24 @@ mod tests {
             .contains(&ThreatType::ShortenerChaining));
     }
 
+    #[tokio::test]
+    async fn test_block_threshold_is_configurable() {
+        // A suspicious TLD scores at least 20 without being blocklisted
+        let strict = DomainSecurityService::new().with_block_threshold(20);
+        let result = strict.check_domain_reputation("files.tk").await.unwrap();
+        assert!(result.threat_score >= 20);
+        assert!(!result.is_safe);
+
+        let lenient = DomainSecurityService::new().with_block_threshold(100);
+        let result = lenient.check_domain_reputation("files.tk").await.unwrap();
+        assert!(result.is_safe);
+
+        // Blocklist entries stay blocked whatever the threshold
+        let result = lenient.check_domain_reputation("bit.ly").await.unwrap();
+        assert!(!result.is_safe);
+        assert!(lenient.is_blocked("bit.ly").await);
+    }
+
     #[test]
     fn test_url_pattern_analyzer() {
         let analyzer = UrlPatternAnalyzer::new();
@@ -1154,6 +1501,76 @@ mod tests {
         assert!(warnings.is_empty() || warnings.len() <= 1); // Might trigger pattern rules but should be minimal
     }
 
+    #[tokio::test]
+    async fn test_shortener_lookalikes_are_not_scored() {
+        let service = DomainSecurityService::new();
+
+        for domain in ["institut.co", "habit.lyrics.com", "bit.ly.mycompany.com"] {
+            let result = service.check_domain_reputation(domain).await.unwrap();
+            assert!(
+                !result
+                    .threats_detected
+                    .contains(&ThreatType::ShortenerChaining),
+                "{} flagged as a shortener",
+                domain
+            );
+        }
+
+        let subdomain = service.check_domain_reputation("go.bit.ly").await.unwrap();
+        assert!(subdomain
+            .threats_detected
+            .contains(&ThreatType::ShortenerChaining));
+    }
+
+    #[tokio::test]
+    async fn test_scanner_consults_registry_categories() {
+        let config: crate::utils::data_files::BlockedDomainsConfig =
+            serde_json::from_value(serde_json::json!({
+                "blocked_domains": {
+                    "url_shorteners": { "description": "", "domains": ["sho.rt"] },
+                    "local_addresses": { "description": "", "domains": [] },
+                    "malicious": { "description": "", "domains": ["evil.example.org"] }
+                },
+                "blocked_tlds": { "description": "", "tlds": [] },
+                "suspicious_tlds": { "description": "", "tlds": ["sus"] },
+                "suspicious_patterns": { "phishing_indicators": ["claim-your-reward"] },
+                "private_ip_ranges": { "description": "", "ranges": [] }
+            }))
+            .unwrap();
+        let registry = Arc::new(BlocklistRegistry::from_config(&config));
+        let service = DomainSecurityService::with_registry(registry.clone());
+
+        let shortener = service.check_domain_reputation("sho.rt").await.unwrap();
+        assert!(shortener
+            .threats_detected
+            .contains(&ThreatType::ShortenerChaining));
+
+        let malicious = service
+            .check_domain_reputation("www.evil.example.org")
+            .await
+            .unwrap();
+        assert!(malicious.threats_detected.contains(&ThreatType::Malware));
+
+        let suspicious = service.check_domain_reputation("shop.sus").await.unwrap();
+        assert!(suspicious
+            .threats_detected
+            .contains(&ThreatType::SuspiciousTld));
+
+        // Lists not in this registry no longer apply
+        let unlisted = service.check_domain_reputation("bit.ly").await.unwrap();
+        assert!(unlisted.is_safe);
+
+        let analyzer = UrlPatternAnalyzer::with_registry(registry);
+        assert!(analyzer
+            .analyze_suspicious_patterns("https://example.com/claim-your-reward")
+            .iter()
+            .any(|w| w.warning_type == ThreatType::Phishing));
+        assert!(!analyzer
+            .analyze_suspicious_patterns("https://example.com/verify-account")
+            .iter()
+            .any(|w| w.warning_type == ThreatType::Phishing));
+    }
+
     #[test]
     fn test_homograph_detector() {
         let detector = HomographDetector::new();
@@ -1185,4 +1602,24 @@ mod tests {
         assert!(!result.is_safe);
         assert!(result.threats_detected.len() > 1);
     }
+
+    #[tokio::test]
+    async fn test_only_full_scans_reach_threat_lookup() {
+        // Never contacted: the recorder stands in for every lookup and telemetry is off
+        let clickhouse_client = Arc::new(ClickHouseClient::new("http://127.0.0.1:1", "test"));
+        let lookup = Arc::new(RecordingThreatLookup::new());
+        let service = SecurityService::with_lookup(clickhouse_client, lookup.clone());
+
+        service
+            .quick_security_scan("https://example.com/quick")
+            .await
+            .unwrap();
+        let result = service
+            .comprehensive_security_scan("https://example.com/full")
+            .await
+            .unwrap();
+
+        assert!(result.is_safe);
+        assert_eq!(lookup.checked_urls(), vec!["https://example.com/full"]);
+    }
 }
@@ -2,11 +2,11 @@
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
-    Json,
 };
-use serde_json::json;
 use thiserror::Error;
 
+use crate::utils::api_error::{ApiError, FieldError};
+
 #[derive(Error, Debug)]
 pub enum ServiceError {
     #[error("Database error: {0}")]
@@ -21,6 +21,9 @@ pub enum ServiceError {
     #[error("Alias already exists")]
     AliasAlreadyExists,
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("Link expired")]
     Expired,
 
@@ -33,12 +36,18 @@ pub enum ServiceError {
     #[error("Too many links")]
     TooManyLinks,
 
+    #[error("Link limit reached ({current}/{limit})")]
+    LinkLimitReached { current: u64, limit: u64 },
+
     #[error("Cache error: {0}")]
     CacheError(String),
 
     #[error("Unauthorized")]
     Unauthorized,
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Internal server error")]
     InternalError,
 
@@ -47,42 +56,109 @@ pub enum ServiceError {
 
     #[error("Password required")]
     PasswordRequired,
+
+    #[error("Link quarantined")]
+    Quarantined,
 }
 
-impl IntoResponse for ServiceError {
-    fn into_response(self) -> Response {
-        let (status, error_message) = match self {
-            ServiceError::DatabaseError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ServiceError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
-            ServiceError::NotFound => (StatusCode::NOT_FOUND, "Resource not found".to_string()),
-            ServiceError::AliasAlreadyExists => {
-                (StatusCode::CONFLICT, "Alias already exists".to_string())
+impl ServiceError {
+    /// HTTP status code for the error
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            ServiceError::ValidationError(_) | ServiceError::TooManyLinks => {
+                StatusCode::BAD_REQUEST
             },
-            ServiceError::Expired => (StatusCode::GONE, "Link has expired".to_string()),
-            ServiceError::Inactive => (StatusCode::GONE, "Link is inactive".to_string()),
-            ServiceError::SubscriptionLimitExceeded(msg) => (StatusCode::PAYMENT_REQUIRED, msg),
-            ServiceError::TooManyLinks => (
-                StatusCode::BAD_REQUEST,
-                "Too many links in request".to_string(),
-            ),
-            ServiceError::CacheError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
-            ServiceError::Unauthorized => (StatusCode::UNAUTHORIZED, "Unauthorized".to_string()),
-            ServiceError::InternalError => (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                "Internal server error".to_string(),
+            ServiceError::NotFound => StatusCode::NOT_FOUND,
+            ServiceError::AliasAlreadyExists | ServiceError::Conflict(_) => StatusCode::CONFLICT,
+            ServiceError::Expired | ServiceError::Inactive => StatusCode::GONE,
+            ServiceError::SubscriptionLimitExceeded(_) => StatusCode::PAYMENT_REQUIRED,
+            ServiceError::Unauthorized | ServiceError::PasswordRequired => StatusCode::UNAUTHORIZED,
+            ServiceError::Forbidden(_)
+            | ServiceError::SecurityBlocked(_)
+            | ServiceError::Quarantined
+            | ServiceError::LinkLimitReached { .. } => StatusCode::FORBIDDEN,
+            ServiceError::DatabaseError(_)
+            | ServiceError::CacheError(_)
+            | ServiceError::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Machine-readable error code for API responses
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            ServiceError::DatabaseError(_) => "DATABASE_ERROR",
+            ServiceError::ValidationError(_) => "VALIDATION_ERROR",
+            ServiceError::NotFound => "NOT_FOUND",
+            ServiceError::AliasAlreadyExists => "ALIAS_EXISTS",
+            ServiceError::Conflict(_) => "CONFLICT",
+            ServiceError::Expired => "LINK_EXPIRED",
+            ServiceError::Inactive => "LINK_INACTIVE",
+            ServiceError::SubscriptionLimitExceeded(_) => "SUBSCRIPTION_LIMIT_EXCEEDED",
+            ServiceError::TooManyLinks => "TOO_MANY_LINKS",
+            ServiceError::LinkLimitReached { .. } => "LINK_LIMIT_REACHED",
+            ServiceError::CacheError(_) => "CACHE_ERROR",
+            ServiceError::Unauthorized => "UNAUTHORIZED",
+            ServiceError::Forbidden(_) => "FORBIDDEN",
+            ServiceError::InternalError => "INTERNAL_ERROR",
+            ServiceError::SecurityBlocked(_) => "SECURITY_BLOCKED",
+            ServiceError::PasswordRequired => "PASSWORD_REQUIRED",
+            ServiceError::Quarantined => "LINK_QUARANTINED",
+        }
+    }
+
+    /// Client-facing message; variant payloads are used where they describe the problem
+    fn message(self) -> String {
+        match self {
+            ServiceError::DatabaseError(msg)
+            | ServiceError::ValidationError(msg)
+            | ServiceError::Conflict(msg)
+            | ServiceError::SubscriptionLimitExceeded(msg)
+            | ServiceError::CacheError(msg)
+            | ServiceError::Forbidden(msg)
+            | ServiceError::SecurityBlocked(msg) => msg,
+            ServiceError::NotFound => "Resource not found".to_string(),
+            ServiceError::AliasAlreadyExists => "Alias already exists".to_string(),
+            ServiceError::Expired => "Link has expired".to_string(),
+            ServiceError::Inactive => "Link is inactive".to_string(),
+            ServiceError::TooManyLinks => "Too many links in request".to_string(),
+            ServiceError::LinkLimitReached { current, limit } => format!(
+                "Link limit reached: {} of {} links in use. Delete links to create new ones",
+                current, limit
             ),
-            ServiceError::SecurityBlocked(msg) => (StatusCode::FORBIDDEN, msg),
-            ServiceError::PasswordRequired => {
-                (StatusCode::UNAUTHORIZED, "Password required".to_string())
+            ServiceError::Unauthorized => "Unauthorized".to_string(),
+            ServiceError::InternalError => "Internal server error".to_string(),
+            ServiceError::PasswordRequired => "Password required".to_string(),
+            ServiceError::Quarantined => {
+                "Link has been quarantined for security review".to_string()
             },
-        };
+        }
+    }
+}
 
-        let body = Json(json!({
-            "error": error_message,
-            "status": status.as_u16()
-        }));
+impl From<ServiceError> for ApiError {
+    fn from(error: ServiceError) -> Self {
+        let status = error.status_code();
+        let code = error.error_code();
+        // Clients read the cap from `details` rather than parsing the message
+        let details = match error {
+            ServiceError::LinkLimitReached { current, limit } => vec![FieldError {
+                field: "links".to_string(),
+                code: "limit_reached".to_string(),
+                message: "Link limit reached".to_string(),
+                params: serde_json::Map::from_iter([
+                    ("current".to_string(), current.into()),
+                    ("limit".to_string(), limit.into()),
+                ]),
+            }],
+            _ => Vec::new(),
+        };
+        ApiError::new(status, code, error.message()).with_details(details)
+    }
+}
 
-        (status, body).into_response()
+impl IntoResponse for ServiceError {
+    fn into_response(self) -> Response {
+        ApiError::from(self).into_response()
     }
 }
 
@@ -114,6 +190,24 @@ impl From<crate::utils::url_validator::UrlValidationError> for ServiceError {
     }
 }
 
+impl From<crate::models::link::TagError> for ServiceError {
+    fn from(error: crate::models::link::TagError) -> Self {
+        ServiceError::ValidationError(format!("{}: {}", error.field(), error))
+    }
+}
+
+impl From<crate::models::link::ReferrerDomainError> for ServiceError {
+    fn from(error: crate::models::link::ReferrerDomainError) -> Self {
+        ServiceError::ValidationError(format!("{}: {}", error.field(), error))
+    }
+}
+
+impl From<crate::models::link::LinkRequestError> for ServiceError {
+    fn from(error: crate::models::link::LinkRequestError) -> Self {
+        ServiceError::ValidationError(error.to_string())
+    }
+}
+
 impl From<crate::services::short_code::ShortCodeError> for ServiceError {
     fn from(error: crate::services::short_code::ShortCodeError) -> Self {
         match error {
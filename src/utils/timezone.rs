@@ -0,0 +1,120 @@
+// IANA timezones for analytics
+// Stats are bucketed by calendar day in the user's zone (profile `timezone`, or the `tz`
+// query parameter), so "clicks per day" matches the days the user lives in.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+use crate::{db::DieselPool, schema::users, utils::service_error::ServiceError};
+
+/// Zone for users who never picked one
+pub const DEFAULT_TIMEZONE: &str = "UTC";
+
+/// An IANA timezone name such as `Asia/Tokyo`; names are case-sensitive
+pub fn parse_timezone(name: &str) -> Result<Tz, String> {
+    name.parse::<Tz>().map_err(|_| {
+        format!(
+            "Unknown timezone `{}`; expected an IANA name such as Europe/Berlin",
+            name
+        )
+    })
+}
+
+/// Calendar day of `at` in `tz`
+pub fn date_in(at: DateTime<Utc>, tz: Tz) -> NaiveDate {
+    at.with_timezone(&tz).date_naive()
+}
+
+/// Today in `tz`
+pub fn today_in(tz: Tz) -> NaiveDate {
+    date_in(Utc::now(), tz)
+}
+
+/// The instant `day` begins in `tz`. Where a DST change skips midnight, the day begins at
+/// the first hour that exists.
+pub fn start_of_day(day: NaiveDate, tz: Tz) -> DateTime<Utc> {
+    let midnight = day.and_time(NaiveTime::MIN);
+    (0..=3)
+        .find_map(|hour| {
+            tz.from_local_datetime(&(midnight + chrono::Duration::hours(hour)))
+                .earliest()
+        })
+        .map(|start| start.with_timezone(&Utc))
+        .unwrap_or_else(|| midnight.and_utc())
+}
+
+/// Zone for a stats request: the `tz` query parameter when given, else the user's profile
+/// setting. An unknown `tz` is a validation error.
+pub async fn resolve_timezone(
+    diesel_pool: &DieselPool,
+    user_id: Uuid,
+    requested: Option<&str>,
+) -> Result<Tz, ServiceError> {
+    if let Some(name) = requested {
+        return parse_timezone(name).map_err(ServiceError::ValidationError);
+    }
+
+    let mut conn = diesel_pool
+        .get()
+        .await
+        .map_err(|e| ServiceError::DatabaseError(e.to_string()))?;
+    let name: String = users::table
+        .find(user_id)
+        .select(users::timezone)
+        .first(&mut conn)
+        .await
+        .optional()?
+        .unwrap_or_else(|| DEFAULT_TIMEZONE.to_string());
+
+    // Stored names were validated when saved
+    Ok(parse_timezone(&name).unwrap_or(Tz::UTC))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Asia/Tokyo"), Ok(Tz::Asia__Tokyo));
+        assert_eq!(parse_timezone("UTC"), Ok(Tz::UTC));
+        for name in [
+            "",
+            "asia/tokyo",
+            "Mars/Olympus_Mons",
+            "UTC+9",
+            "Asia/Tokyo'",
+        ] {
+            assert!(parse_timezone(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn test_days_follow_the_zone() {
+        // 20:00 UTC is already the next morning in Tokyo
+        let at = Utc.with_ymd_and_hms(2024, 6, 30, 20, 0, 0).unwrap();
+        assert_eq!(date_in(at, Tz::UTC), day("2024-06-30"));
+        assert_eq!(date_in(at, Tz::Asia__Tokyo), day("2024-07-01"));
+
+        assert_eq!(
+            start_of_day(day("2024-07-01"), Tz::Asia__Tokyo),
+            Utc.with_ymd_and_hms(2024, 6, 30, 15, 0, 0).unwrap()
+        );
+        assert_eq!(
+            start_of_day(day("2024-07-01"), Tz::UTC),
+            Utc.with_ymd_and_hms(2024, 7, 1, 0, 0, 0).unwrap()
+        );
+        // Santiago skipped from 00:00 to 01:00 on 2024-09-08
+        assert_eq!(
+            start_of_day(day("2024-09-08"), Tz::America__Santiago),
+            Utc.with_ymd_and_hms(2024, 9, 8, 4, 0, 0).unwrap()
+        );
+    }
+}
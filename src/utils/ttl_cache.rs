@@ -0,0 +1,104 @@
+// Per-process cache of values read from Redis on hot paths
+// Entries are trusted for a fixed TTL. Stale entries are kept so callers can fall back to the
+// last known value when Redis is unavailable; they are only dropped once the cache outgrows
+// its entry cap.
+
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// A value as last seen in Redis
+struct CachedValue<V> {
+    value: V,
+    fetched_at: Instant,
+}
+
+pub struct TtlCache<K, V> {
+    entries: Mutex<HashMap<K, CachedValue<V>>>,
+    ttl: Duration,
+    max_entries: usize,
+}
+
+impl<K: Eq + Hash, V: Clone> TtlCache<K, V> {
+    /// Cache trusting entries for `ttl`; stale entries are dropped once it holds
+    /// more than `max_entries` keys
+    pub fn new(ttl: Duration, max_entries: usize) -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            ttl,
+            max_entries,
+        }
+    }
+
+    /// The cached value and whether it is still within the TTL
+    pub fn get<Q>(&self, key: &Q) -> Option<(V, bool)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + Hash + ?Sized,
+    {
+        self.lock()
+            .get(key)
+            .map(|entry| (entry.value.clone(), entry.fetched_at.elapsed() < self.ttl))
+    }
+
+    pub fn insert(&self, key: K, value: V) {
+        self.insert_with(key, |_| value);
+    }
+
+    /// Store the value `update` derives from the previous one (fresh or stale).
+    /// Returns the value now cached.
+    pub fn insert_with(&self, key: K, update: impl FnOnce(Option<&V>) -> V) -> V {
+        let mut entries = self.lock();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.fetched_at.elapsed() < self.ttl);
+        }
+
+        let value = update(entries.get(&key).map(|entry| &entry.value));
+        entries.insert(
+            key,
+            CachedValue {
+                value: value.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        value
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<K, CachedValue<V>>> {
+        // Entries are replaced whole, so a poisoned map is still consistent
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_entries_dropped_when_full() {
+        let cache = TtlCache::new(Duration::ZERO, 2);
+        cache.insert("a", 1);
+        cache.insert("b", 2);
+        assert_eq!(cache.get("a"), Some((1, false)));
+
+        // Replacing an existing key never prunes
+        cache.insert("b", 3);
+        assert_eq!(cache.get("a"), Some((1, false)));
+
+        cache.insert("c", 4);
+        assert_eq!(cache.get("a"), None);
+        assert_eq!(cache.get("c"), Some((4, false)));
+    }
+
+    #[test]
+    fn test_insert_with_sees_previous_value() {
+        let cache = TtlCache::new(Duration::from_secs(60), 10);
+        let keep_max =
+            |new: u64| move |previous: Option<&u64>| previous.map_or(new, |&p| p.max(new));
+        assert_eq!(cache.insert_with("k", keep_max(5)), 5);
+        assert_eq!(cache.insert_with("k", keep_max(3)), 5);
+        assert_eq!(cache.get("k"), Some((5, true)));
+    }
+}
@@ -5,12 +5,15 @@ use lazy_static::lazy_static;
 use regex::Regex;
 use scraper::{Html, Selector};
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Arc;
 use std::time::Duration;
 use thiserror::Error;
-use tracing::{error, info, warn};
 use url::Url;
 
+use crate::utils::blocklist_registry::BlocklistRegistry;
+use crate::utils::safe_http::{is_public_ip, resolve_public_addrs, SafeHttpError, SafeHttpClient};
+
 // =============================================================================
 // STATIC REGEX PATTERNS
 // =============================================================================
@@ -23,49 +26,6 @@ lazy_static! {
             .expect("Invalid domain pattern regex");
 }
 
-// =============================================================================
-// JSON CONFIGURATION STRUCTURES
-// =============================================================================
-
-/// Configuration structure for blocked domains JSON
-#[derive(Debug, Deserialize)]
-struct BlockedDomainsConfig {
-    blocked_domains: BlockedDomainCategories,
-    blocked_tlds: BlockedTlds,
-    suspicious_patterns: SuspiciousPatterns,
-    private_ip_ranges: PrivateIpRanges,
-}
-
-#[derive(Debug, Deserialize)]
-struct BlockedDomainCategories {
-    url_shorteners: DomainCategory,
-    local_addresses: DomainCategory,
-    malicious: DomainCategory,
-}
-
-#[derive(Debug, Deserialize)]
-struct DomainCategory {
-    description: String,
-    domains: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct BlockedTlds {
-    description: String,
-    tlds: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct SuspiciousPatterns {
-    phishing_indicators: Vec<String>,
-}
-
-#[derive(Debug, Deserialize)]
-struct PrivateIpRanges {
-    description: String,
-    ranges: Vec<String>,
-}
-
 // =============================================================================
 // ERROR TYPES
 // =============================================================================
@@ -170,23 +130,40 @@ impl From<ValidationError> for UrlValidationError {
 pub struct NormalizedUrl {
     pub original: String,
     pub normalized: String,
+    /// Canonical ASCII (punycode) host, as stored
     pub domain: String,
+    /// Host decoded to Unicode, for analysis and display only
+    pub unicode_domain: String,
     pub path: String,
     pub scheme: String,
 }
 
 impl NormalizedUrl {
     pub fn from(url: Url) -> Self {
+        let domain = url.host_str().unwrap_or("").to_string();
         Self {
             original: url.to_string(),
             normalized: url.to_string(),
-            domain: url.host_str().unwrap_or("").to_string(),
+            unicode_domain: host_to_unicode(&domain),
+            domain,
             path: url.path().to_string(),
             scheme: url.scheme().to_string(),
         }
     }
 }
 
+/// Decode a punycode (`xn--`) host to Unicode. Hosts that fail to decode are returned unchanged.
+pub fn host_to_unicode(host: &str) -> String {
+    if !host.split('.').any(|label| label.starts_with("xn--")) {
+        return host.to_string();
+    }
+
+    match idna::domain_to_unicode(host) {
+        (unicode, Ok(())) => unicode,
+        (_, Err(_)) => host.to_string(),
+    }
+}
+
 /// URL metadata extracted from pages for DEV-116
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct UrlMetadata {
@@ -206,6 +183,9 @@ pub enum MetadataError {
     #[error("Network error: {0}")]
     NetworkError(#[from] reqwest::Error),
 
+    #[error("Fetch blocked: {0}")]
+    Blocked(#[from] crate::utils::safe_http::SafeHttpError),
+
     #[error("Parsing error: {0}")]
     ParseError(String),
 
@@ -213,48 +193,132 @@ pub enum MetadataError {
     Timeout,
 }
 
+impl MetadataError {
+    /// Whether the destination host failed to answer in time (DNS or HTTP)
+    pub fn is_timeout(&self) -> bool {
+        use crate::utils::safe_http::SafeHttpError;
+
+        match self {
+            MetadataError::Timeout => true,
+            MetadataError::NetworkError(e) | MetadataError::Blocked(SafeHttpError::Http(e)) => {
+                e.is_timeout()
+            },
+            MetadataError::Blocked(SafeHttpError::DnsTimeout(_)) => true,
+            _ => false,
+        }
+    }
+}
+
 // =============================================================================
 // CONSTANTS
 // =============================================================================
 
-const MAX_URL_LENGTH: usize = 8192;
-
-// Blacklisted domains (example list - expand as needed)
-const BLACKLISTED_DOMAINS: &[&str] = &[
-    "bit.ly", // Prevent URL shortener chains
-    "tinyurl.com",
-    "goo.gl",
-    "ow.ly",
-    "is.gd",
-    "buff.ly",
-    "localhost",
-    "127.0.0.1",
-    "0.0.0.0",
-    "::1",
-];
-
-// Suspicious patterns that might indicate phishing or malware
-const SUSPICIOUS_PATTERNS: &[&str] = &[
-    "download-free",
-    "click-here-now",
-    "limited-time-offer",
-    "verify-account",
-    "suspend-account",
-    "update-payment",
-    "confirm-identity",
-    "security-alert",
-    "unusual-activity",
-    "prize-winner",
-    "congratulations-winner",
-];
-
-// Private network ranges (RFC 1918)
-const PRIVATE_IP_RANGES: &[&str] = &[
-    "10.", "172.16.", "172.17.", "172.18.", "172.19.", "172.20.", "172.21.", "172.22.", "172.23.",
-    "172.24.", "172.25.", "172.26.", "172.27.", "172.28.", "172.29.", "172.30.", "172.31.",
-    "192.168.", "169.254.", // Link-local
-    "fc00::", "fd00::", // IPv6 private
-];
+/// Longest destination URL accepted anywhere, in bytes: link request validation, this
+/// validator, the security scanner and the `links.original_url` constraint all use it.
+/// `MAX_URL_LENGTH` in the environment can lower the request limit but not raise it.
+pub const MAX_URL_LENGTH: usize = 2048;
+
+/// Most query parameter names one user may have stripped from their destination URLs
+pub const MAX_STRIP_QUERY_PARAMS: usize = 50;
+
+/// Longest query parameter name (or `prefix*` pattern) in a strip list
+const MAX_STRIP_QUERY_PARAM_LENGTH: usize = 64;
+
+// =============================================================================
+// QUERY CANONICALIZATION
+// =============================================================================
+
+/// Canonical form of a destination URL's query string, so the same destination is stored the
+/// same way however the parameters arrived:
+/// - empty `&&` segments are dropped
+/// - `utm_*` names are lowercased (`UTM_Source` becomes `utm_source`); other names keep their case
+/// - names matching `strip_params` are removed; see `query_param_matches`
+/// - a name given more than once keeps its last value
+/// - parameters are sorted by decoded name
+///
+/// Names and values keep their original percent-encoding. Returns `None` when no parameter is
+/// left.
+pub fn canonicalize_query(query: &str, strip_params: &[String]) -> Option<String> {
+    let mut params: BTreeMap<String, String> = BTreeMap::new();
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (raw_name, raw_value) = match pair.split_once('=') {
+            Some((name, value)) => (name, Some(value)),
+            None => (pair, None),
+        };
+        let mut name = url::form_urlencoded::parse(raw_name.as_bytes())
+            .next()
+            .map(|(name, _)| name.into_owned())
+            .unwrap_or_default();
+        let mut raw_name = raw_name.to_string();
+        if name.to_ascii_lowercase().starts_with("utm_") {
+            name = name.to_ascii_lowercase();
+            raw_name = raw_name.to_ascii_lowercase();
+        }
+        if strip_params
+            .iter()
+            .any(|pattern| query_param_matches(pattern, &name))
+        {
+            continue;
+        }
+
+        let segment = match raw_value {
+            Some(value) => format!("{}={}", raw_name, value),
+            None => raw_name,
+        };
+        params.insert(name, segment);
+    }
+
+    if params.is_empty() {
+        return None;
+    }
+    Some(params.into_values().collect::<Vec<_>>().join("&"))
+}
+
+/// Whether a strip-list entry matches a decoded parameter name, ignoring case.
+/// An entry ending in `*` matches every name starting with the rest (`utm_*`).
+pub fn query_param_matches(pattern: &str, name: &str) -> bool {
+    match pattern.strip_suffix('*') {
+        Some(prefix) => name
+            .get(..prefix.len())
+            .is_some_and(|start| start.eq_ignore_ascii_case(prefix)),
+        None => pattern.eq_ignore_ascii_case(name),
+    }
+}
+
+/// Validate a user's strip list: parameter names or `prefix*` patterns made of letters, digits,
+/// `-`, `_` and `.`. Returns the list lowercased and without duplicates, in the order given.
+pub fn parse_strip_query_params(params: &[String]) -> Result<Vec<String>, String> {
+    let mut parsed: Vec<String> = Vec::new();
+    for param in params {
+        let param = param.trim().to_ascii_lowercase();
+        let name = param.strip_suffix('*').unwrap_or(&param);
+        if name.is_empty() || param.len() > MAX_STRIP_QUERY_PARAM_LENGTH {
+            return Err(format!(
+                "Query parameter names must be 1 to {} characters",
+                MAX_STRIP_QUERY_PARAM_LENGTH
+            ));
+        }
+        if !name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(format!(
+                "'{}' may only contain letters, digits, '-', '_' and '.', with an optional trailing '*'",
+                param
+            ));
+        }
+        if !parsed.contains(&param) {
+            parsed.push(param);
+        }
+    }
+    if parsed.len() > MAX_STRIP_QUERY_PARAMS {
+        return Err(format!(
+            "At most {} query parameters can be stripped",
+            MAX_STRIP_QUERY_PARAMS
+        ));
+    }
+    Ok(parsed)
+}
 
 // =============================================================================
 // ENHANCED URL VALIDATOR (DEV-116)
@@ -263,10 +327,11 @@ const PRIVATE_IP_RANGES: &[&str] = &[
 /// Enhanced URL validator implementing DEV-116 requirements
 pub struct UrlValidator {
     allowed_schemes: HashSet<String>,
-    blocked_domains: HashSet<String>,
-    blocked_tlds: HashSet<String>,
+    blocklist: Arc<BlocklistRegistry>,
     ip_regex: Regex,
     localhost_regex: Regex,
+    /// Query parameters removed during normalization (the link owner's strip list)
+    strip_params: Vec<String>,
 }
 
 impl Default for UrlValidator {
@@ -276,95 +341,32 @@ impl Default for UrlValidator {
 }
 
 impl UrlValidator {
-    /// Create new UrlValidator with configuration loaded from JSON
+    /// Create new UrlValidator using the shared blocklist
     pub fn new() -> Self {
+        Self::with_registry(BlocklistRegistry::shared())
+    }
+
+    pub fn with_registry(blocklist: Arc<BlocklistRegistry>) -> Self {
         let mut allowed_schemes = HashSet::new();
         allowed_schemes.insert("http".to_string());
         allowed_schemes.insert("https".to_string());
 
-        // Load configuration from JSON file
-        let (blocked_domains, blocked_tlds) = Self::load_blocked_domains_config();
-
         Self {
             allowed_schemes,
-            blocked_domains,
-            blocked_tlds,
+            blocklist,
             ip_regex: Regex::new(r"^(\d{1,3}\.){3}\d{1,3}$").unwrap(),
             localhost_regex: Regex::new(
                 r"^(localhost|127\.|192\.168\.|10\.|172\.(1[6-9]|2[0-9]|3[01])\.)",
             )
             .unwrap(),
+            strip_params: Vec::new(),
         }
     }
 
-    /// Load blocked domains configuration from JSON file
-    fn load_blocked_domains_config() -> (HashSet<String>, HashSet<String>) {
-        let mut blocked_domains = HashSet::new();
-        let mut blocked_tlds = HashSet::new();
-
-        // Try to load from JSON file
-        let json_path = "data/blocked_domains.json";
-        match std::fs::read_to_string(json_path) {
-            Ok(content) => {
-                match serde_json::from_str::<BlockedDomainsConfig>(&content) {
-                    Ok(config) => {
-                        // Load blocked domains from all categories
-                        for domain in config
-                            .blocked_domains
-                            .url_shorteners
-                            .domains
-                            .iter()
-                            .chain(config.blocked_domains.local_addresses.domains.iter())
-                            .chain(config.blocked_domains.malicious.domains.iter())
-                        {
-                            blocked_domains.insert(domain.to_lowercase());
-                        }
-
-                        // Load blocked TLDs
-                        for tld in config.blocked_tlds.tlds {
-                            blocked_tlds.insert(tld.to_lowercase());
-                        }
-
-                        info!(
-                            "Loaded {} blocked domains and {} blocked TLDs from JSON",
-                            blocked_domains.len(),
-                            blocked_tlds.len()
-                        );
-                    },
-                    Err(e) => {
-                        error!("Failed to parse blocked domains JSON: {}", e);
-                        Self::load_fallback_blocked_domains(
-                            &mut blocked_domains,
-                            &mut blocked_tlds,
-                        );
-                    },
-                }
-            },
-            Err(e) => {
-                warn!(
-                    "Failed to read blocked domains file: {}. Using fallback list.",
-                    e
-                );
-                Self::load_fallback_blocked_domains(&mut blocked_domains, &mut blocked_tlds);
-            },
-        }
-
-        (blocked_domains, blocked_tlds)
-    }
-
-    /// Load fallback blocked domains if JSON file is not available
-    fn load_fallback_blocked_domains(domains: &mut HashSet<String>, tlds: &mut HashSet<String>) {
-        // Fallback URL shorteners and local addresses
-        for domain in BLACKLISTED_DOMAINS {
-            domains.insert(domain.to_string());
-        }
-
-        // Fallback blocked TLDs
-        for tld in ["test", "localhost", "local", "internal"] {
-            tlds.insert(tld.to_string());
-        }
-
-        warn!("Using fallback blocked domains list");
+    /// Remove these query parameters (names or `prefix*` patterns) when normalizing
+    pub fn with_strip_params(mut self, strip_params: &[String]) -> Self {
+        self.strip_params = strip_params.to_vec();
+        self
     }
 
     /// Main validation method implementing DEV-116 requirements
@@ -423,19 +425,17 @@ impl UrlValidator {
         let host = url.host_str().ok_or(ValidationError::MissingHost)?;
 
         // Check blocked domains
-        if self.blocked_domains.contains(host) {
+        if self.blocklist.is_blocked_domain(host) {
             return Err(ValidationError::BlockedDomain(host.to_string()));
         }
 
         // Check blocked TLDs
-        if let Some(tld) = self.extract_tld(host) {
-            if self.blocked_tlds.contains(&tld) {
-                return Err(ValidationError::BlockedTld(tld));
-            }
+        if let Some(tld) = self.blocklist.blocked_tld(host) {
+            return Err(ValidationError::BlockedTld(tld));
         }
 
         // Check for private/local IPs
-        if self.is_private_or_local_ip(host) {
+        if self.blocklist.is_private_address(host) || self.is_private_or_local_ip(host) {
             return Err(ValidationError::PrivateIp);
         }
 
@@ -500,6 +500,12 @@ impl UrlValidator {
             }
         }
 
+        // Sorted, deduplicated query without stripped parameters
+        if let Some(query) = normalized.query() {
+            let canonical = canonicalize_query(query, &self.strip_params);
+            normalized.set_query(canonical.as_deref());
+        }
+
         // Remove trailing slash for root paths
         if normalized.path() == "/"
             && normalized.query().is_none()
@@ -508,30 +514,32 @@ impl UrlValidator {
             normalized.set_path("");
         }
 
-        // Remove empty query parameters
-        if let Some(query) = normalized.query() {
-            if query.is_empty() {
-                normalized.set_query(None);
-            }
-        }
-
         // Remove fragment for normalization
         normalized.set_fragment(None);
 
+        // Url already serializes IDN hosts as punycode; that ASCII form is the canonical one
+        let domain = normalized.host_str().unwrap_or("").to_string();
+
         Ok(NormalizedUrl {
             original: url.to_string(),
             normalized: normalized.to_string(),
-            domain: normalized.host_str().unwrap_or("").to_string(),
+            unicode_domain: host_to_unicode(&domain),
+            domain,
             path: normalized.path().to_string(),
             scheme: normalized.scheme().to_string(),
         })
     }
 
-    fn extract_tld(&self, host: &str) -> Option<String> {
-        host.split('.').last().map(|s| s.to_lowercase())
-    }
-
     fn is_private_or_local_ip(&self, host: &str) -> bool {
+        // IPv4 and bracketed IPv6 literals are checked against every non-public range
+        if let Ok(ip) = host
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .parse::<std::net::IpAddr>()
+        {
+            return !is_public_ip(ip);
+        }
+
         // Check if it's an IP address
         if self.ip_regex.is_match(host) {
             return self.is_private_ip_range(host);
@@ -556,25 +564,21 @@ impl UrlValidator {
     }
 
     async fn validate_dns_resolution(&self, host: &str) -> Result<(), ValidationError> {
-        use tokio::net::lookup_host;
-
-        match tokio::time::timeout(Duration::from_secs(5), lookup_host(format!("{}:80", host)))
-            .await
-        {
-            Ok(Ok(_)) => Ok(()),
-            Ok(Err(_)) => Err(ValidationError::DnsResolutionFailed),
-            Err(_) => Err(ValidationError::DnsTimeout),
+        // Hostnames that resolve into private space are rejected just like private IP literals
+        match resolve_public_addrs(host, 80).await {
+            Ok(_) => Ok(()),
+            Err(SafeHttpError::BlockedAddress(_)) => Err(ValidationError::PrivateIp),
+            Err(SafeHttpError::DnsTimeout(_)) => Err(ValidationError::DnsTimeout),
+            Err(_) => Err(ValidationError::DnsResolutionFailed),
         }
     }
 
     /// Extract metadata from URL (DEV-116 requirement)
     pub async fn extract_metadata(&self, url: &str) -> Result<UrlMetadata, MetadataError> {
-        let client = reqwest::Client::builder()
-            .timeout(Duration::from_secs(10))
-            .user_agent("QCK-Bot/1.0")
-            .build()?;
+        // Resolves and pins public addresses for every hop (SSRF protection)
+        let client = SafeHttpClient::new("QCK-Bot/1.0").with_timeout(Duration::from_secs(10));
 
-        let response = client.get(url).send().await?;
+        let response = client.get(url).await?;
 
         if !response.status().is_success() {
             return Err(MetadataError::HttpError(response.status().as_u16()));
@@ -736,12 +740,8 @@ impl UrlValidator {
     /// Check if domain is blacklisted
     fn check_blacklist(url: &Url) -> Result<(), UrlValidationError> {
         if let Some(host) = url.host_str() {
-            let host_lower = host.to_lowercase();
-
-            for blacklisted in BLACKLISTED_DOMAINS {
-                if host_lower.contains(blacklisted) {
-                    return Err(UrlValidationError::BlacklistedDomain(host.to_string()));
-                }
+            if BlocklistRegistry::shared().is_blocked_domain(host) {
+                return Err(UrlValidationError::BlacklistedDomain(host.to_string()));
             }
         }
         Ok(())
@@ -749,23 +749,17 @@ impl UrlValidator {
 
     /// Check for suspicious patterns in URL
     fn check_suspicious_patterns(url_str: &str) -> Result<(), UrlValidationError> {
-        let url_lower = url_str.to_lowercase();
-
-        for pattern in SUSPICIOUS_PATTERNS {
-            if url_lower.contains(pattern) {
-                return Err(UrlValidationError::SuspiciousPattern(pattern.to_string()));
-            }
+        match BlocklistRegistry::shared().phishing_keyword(url_str) {
+            Some(keyword) => Err(UrlValidationError::SuspiciousPattern(keyword.to_string())),
+            None => Ok(()),
         }
-        Ok(())
     }
 
     /// Check if URL points to private network
     fn check_private_networks(url: &Url) -> Result<(), UrlValidationError> {
         if let Some(host) = url.host_str() {
-            for private_range in PRIVATE_IP_RANGES {
-                if host.starts_with(private_range) {
-                    return Err(UrlValidationError::PrivateNetwork(host.to_string()));
-                }
+            if BlocklistRegistry::shared().is_private_address(host) {
+                return Err(UrlValidationError::PrivateNetwork(host.to_string()));
             }
         }
         Ok(())
@@ -822,8 +816,12 @@ impl UrlValidator {
 }
 
 /// Normalize URL for consistent storage - Async version for proper validation
-pub async fn normalize_url_async(url_str: &str) -> Result<String, UrlValidationError> {
-    let validator = UrlValidator::new();
+/// `strip_params` are query parameters the link owner wants removed (see `canonicalize_query`)
+pub async fn normalize_url_async(
+    url_str: &str,
+    strip_params: &[String],
+) -> Result<String, UrlValidationError> {
+    let validator = UrlValidator::new().with_strip_params(strip_params);
     let normalized = validator
         .validate_and_normalize(url_str)
         .await
@@ -832,11 +830,11 @@ pub async fn normalize_url_async(url_str: &str) -> Result<String, UrlValidationE
 }
 
 /// Normalize URL for consistent storage - Sync version using tokio's block_on
-pub fn normalize_url(url_str: &str) -> Result<String, UrlValidationError> {
+pub fn normalize_url(url_str: &str, strip_params: &[String]) -> Result<String, UrlValidationError> {
     // Use tokio's Handle to run async in sync context
     // This is safe because we're already in a tokio runtime context when handlers call this
     let handle = tokio::runtime::Handle::current();
-    handle.block_on(normalize_url_async(url_str))
+    handle.block_on(normalize_url_async(url_str, strip_params))
 }
 
 // =============================================================================
@@ -919,26 +917,17 @@ impl SecurityScanner {
 
     /// Check if URL is from known URL shortener
     fn is_url_shortener(url: &Url) -> bool {
-        if let Some(host) = url.host_str() {
-            let shorteners = ["bit.ly", "tinyurl.com", "goo.gl", "ow.ly", "is.gd"];
-            shorteners.iter().any(|s| host.contains(s))
-        } else {
-            false
-        }
+        url.host_str()
+            .map(|host| BlocklistRegistry::shared().is_shortener(host))
+            .unwrap_or(false)
     }
 
     /// Check for homograph attacks using lookalike characters
     fn has_homograph_risk(url: &Url) -> bool {
-        if let Some(host) = url.host_str() {
-            // Check for mixed scripts (simplified check)
-            let has_cyrillic = host.chars().any(|c| ('\u{0400}'..='\u{04FF}').contains(&c));
-            let has_latin = host.chars().any(|c| c.is_ascii_alphabetic());
-
-            // If both scripts are present, it might be a homograph attack
-            has_cyrillic && has_latin
-        } else {
-            false
-        }
+        // Hosts arrive punycoded; the detector decodes them before checking scripts
+        url.host_str()
+            .map(crate::utils::security_scanner::HomographDetector::has_mixed_scripts)
+            .unwrap_or(false)
     }
 }
 
@@ -949,6 +938,7 @@ impl SecurityScanner {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::utils::data_files::BlockedDomainsConfig;
 
     // DEV-116 CRITICAL VERIFICATION TESTS
     #[tokio::test]
@@ -1095,13 +1085,15 @@ mod tests {
     async fn test_url_normalization() {
         // Test basic normalization
         assert_eq!(
-            normalize_url_async("https://example.com/").await.unwrap(),
+            normalize_url_async("https://example.com/", &[])
+                .await
+                .unwrap(),
             "https://example.com/" // Root path keeps trailing slash in URL string representation
         );
 
         // Test removal of default HTTP port
         assert_eq!(
-            normalize_url_async("http://example.com:80/path")
+            normalize_url_async("http://example.com:80/path", &[])
                 .await
                 .unwrap(),
             "http://example.com/path"
@@ -1109,7 +1101,7 @@ mod tests {
 
         // Test removal of default HTTPS port
         assert_eq!(
-            normalize_url_async("https://example.com:443/")
+            normalize_url_async("https://example.com:443/", &[])
                 .await
                 .unwrap(),
             "https://example.com/" // Default port removed, trailing slash remains for root
@@ -1117,13 +1109,136 @@ mod tests {
 
         // Test lowercase host normalization
         assert_eq!(
-            normalize_url_async("https://EXAMPLE.COM/Path")
+            normalize_url_async("https://EXAMPLE.COM/Path", &[])
                 .await
                 .unwrap(),
             "https://example.com/Path" // Host lowercased, path preserves case
         );
     }
 
+    #[test]
+    fn test_canonicalize_query() {
+        let none: &[String] = &[];
+
+        // Sorted by name; encoding and name case of other parameters are kept
+        assert_eq!(
+            canonicalize_query("b=2&a=1&Z=%20x", none).as_deref(),
+            Some("Z=%20x&a=1&b=2")
+        );
+        // Duplicate names collapse to the last value
+        assert_eq!(
+            canonicalize_query("utm_source=x&id=1&utm_source=y", none).as_deref(),
+            Some("id=1&utm_source=y")
+        );
+        // utm_* names are lowercased before duplicates are collapsed
+        assert_eq!(
+            canonicalize_query("UTM_Source=x&utm_source=y&UTM_MEDIUM=email", none).as_deref(),
+            Some("utm_medium=email&utm_source=y")
+        );
+        // Other names stay case-sensitive
+        assert_eq!(
+            canonicalize_query("ID=1&id=2", none).as_deref(),
+            Some("ID=1&id=2")
+        );
+        // Flags without a value and empty segments
+        assert_eq!(
+            canonicalize_query("&&debug&a=&", none).as_deref(),
+            Some("a=&debug")
+        );
+        assert_eq!(canonicalize_query("", none), None);
+        assert_eq!(canonicalize_query("&&", none), None);
+    }
+
+    #[test]
+    fn test_canonicalize_query_strips_params() {
+        let strip = vec!["fbclid".to_string(), "utm_*".to_string()];
+
+        assert_eq!(
+            canonicalize_query("id=7&FBCLID=abc&utm_source=x&UTM_Campaign=y", &strip).as_deref(),
+            Some("id=7")
+        );
+        assert_eq!(canonicalize_query("fbclid=abc&utm_source=x", &strip), None);
+        // Percent-encoded names are matched decoded
+        assert_eq!(
+            canonicalize_query("fb%63lid=abc&q=1", &strip).as_deref(),
+            Some("q=1")
+        );
+        // Exact entries don't match longer names
+        assert_eq!(
+            canonicalize_query("fbclid_keep=1", &strip).as_deref(),
+            Some("fbclid_keep=1")
+        );
+    }
+
+    #[test]
+    fn test_query_param_matches() {
+        assert!(query_param_matches("gclid", "GCLID"));
+        assert!(!query_param_matches("gclid", "gclid2"));
+        assert!(query_param_matches("utm_*", "utm_source"));
+        assert!(query_param_matches("utm_*", "UTM_"));
+        assert!(!query_param_matches("utm_*", "utm"));
+        assert!(!query_param_matches("utm_*", "xutm_source"));
+        // Multi-byte names never split inside a character
+        assert!(!query_param_matches("ab*", "aé"));
+    }
+
+    #[test]
+    fn test_parse_strip_query_params() {
+        assert_eq!(
+            parse_strip_query_params(&[
+                " FBCLID ".to_string(),
+                "utm_*".to_string(),
+                "fbclid".to_string(),
+            ])
+            .unwrap(),
+            vec!["fbclid".to_string(), "utm_*".to_string()]
+        );
+        assert!(parse_strip_query_params(&[]).unwrap().is_empty());
+
+        for bad in ["", "*", "a b", "a&b", "*utm", "ut*m_", &"x".repeat(65)] {
+            assert!(
+                parse_strip_query_params(&[bad.to_string()]).is_err(),
+                "{:?} should be rejected",
+                bad
+            );
+        }
+        let too_many: Vec<String> = (0..=MAX_STRIP_QUERY_PARAMS)
+            .map(|i| format!("p{}", i))
+            .collect();
+        assert!(parse_strip_query_params(&too_many).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_url_normalization_canonicalizes_query() {
+        assert_eq!(
+            normalize_url_async(
+                "https://Example.com:443/p?utm_source=a&b=2&utm_source=b#top",
+                &[]
+            )
+            .await
+            .unwrap(),
+            "https://example.com/p?b=2&utm_source=b"
+        );
+        assert_eq!(
+            normalize_url_async(
+                "https://example.com/p?fbclid=1&utm_medium=x",
+                &["fbclid".to_string(), "utm_*".to_string()]
+            )
+            .await
+            .unwrap(),
+            "https://example.com/p"
+        );
+        // The same destination written two ways is stored once
+        assert_eq!(
+            normalize_url_async("https://example.com/?b=1&a=2", &[])
+                .await
+                .unwrap(),
+            normalize_url_async("https://EXAMPLE.com/?a=2&b=1&", &[])
+                .await
+                .unwrap()
+        );
+    }
+
     #[test]
     fn test_safe_redirect() {
         let url = Url::parse("https://example.com/page").unwrap();
@@ -1203,6 +1318,40 @@ mod tests {
         ));
     }
 
+    #[tokio::test]
+    async fn test_url_length_limit_boundary() {
+        let validator = UrlValidator::new();
+        let prefix = "https://example.com/";
+
+        let at_limit = format!("{}{}", prefix, "a".repeat(MAX_URL_LENGTH - prefix.len()));
+        assert!(UrlValidator::validate_url(&at_limit).is_ok());
+        assert!(validator.validate_and_normalize(&at_limit).await.is_ok());
+
+        let over = format!(
+            "{}{}",
+            prefix,
+            "a".repeat(MAX_URL_LENGTH + 1 - prefix.len())
+        );
+        assert!(matches!(
+            UrlValidator::validate_url(&over),
+            Err(UrlValidationError::TooLong { current, max: MAX_URL_LENGTH })
+                if current == MAX_URL_LENGTH + 1
+        ));
+        assert!(matches!(
+            validator.validate_and_normalize(&over).await,
+            Err(ValidationError::TooLong { .. })
+        ));
+
+        // Non-ASCII path characters are percent-encoded before the check: 700 two-byte
+        // characters fit in the raw input but not once each becomes `%XX%XX`
+        let encoded_over = format!("{}{}", prefix, "é".repeat(700));
+        assert!(encoded_over.len() < MAX_URL_LENGTH);
+        assert!(matches!(
+            validator.validate_and_normalize(&encoded_over).await,
+            Err(ValidationError::TooLong { current, .. }) if current > MAX_URL_LENGTH
+        ));
+    }
+
     #[tokio::test]
     async fn test_extract_metadata() {
         let validator = UrlValidator::new();
@@ -1238,21 +1387,41 @@ mod tests {
         assert!(!validator.is_private_or_local_ip("example.com"));
     }
 
-    #[test]
-    fn test_tld_extraction() {
-        let validator = UrlValidator::new();
-
-        assert_eq!(
-            validator.extract_tld("example.com"),
-            Some("com".to_string())
-        );
-        assert_eq!(
-            validator.extract_tld("sub.example.co.uk"),
-            Some("uk".to_string())
-        );
-        assert_eq!(
-            validator.extract_tld("localhost"),
-            Some("localhost".to_string())
-        );
+    #[tokio::test]
+    async fn test_validator_consults_registry_categories() {
+        let config: BlockedDomainsConfig = serde_json::from_value(serde_json::json!({
+            "blocked_domains": {
+                "url_shorteners": { "description": "", "domains": ["sho.rt"] },
+                "local_addresses": { "description": "", "domains": [] },
+                "malicious": { "description": "", "domains": ["evil.example.org"] }
+            },
+            "blocked_tlds": { "description": "", "tlds": ["corp"] },
+            "suspicious_patterns": { "phishing_indicators": [] },
+            "private_ip_ranges": { "description": "", "ranges": ["8.8.8.0/24"] }
+        }))
+        .unwrap();
+        let registry = BlocklistRegistry::from_config(&config);
+        let validator = UrlValidator::with_registry(Arc::new(registry));
+
+        for (url, blocked) in [
+            ("https://sho.rt/x", "sho.rt"),
+            ("https://cdn.evil.example.org/", "cdn.evil.example.org"),
+        ] {
+            assert!(matches!(
+                validator.validate_and_normalize(url).await,
+                Err(ValidationError::BlockedDomain(host)) if host == blocked
+            ));
+        }
+        assert!(matches!(
+            validator.validate_and_normalize("https://intranet.corp/").await,
+            Err(ValidationError::BlockedTld(tld)) if tld == "corp"
+        ));
+        assert!(matches!(
+            validator.validate_and_normalize("http://8.8.8.8/").await,
+            Err(ValidationError::PrivateIp)
+        ));
+        // Only the registry's lists apply: the bundled shorteners aren't in this one
+        let shortener = validator.validate_and_normalize("https://bit.ly/x").await;
+        assert!(shortener.is_ok());
     }
 }
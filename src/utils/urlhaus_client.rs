@@ -1,14 +1,46 @@
 // URLhaus Threat Intelligence Client with ClickHouse backend
 // Free malicious URL database from abuse.ch
-
-use crate::db::ClickHouseClient;
+// The time of the last successful refresh is kept in Redis (`urlhaus:last_refresh`), shared
+// by every replica, so lookups and the health check can tell when the feed went stale.
+// Refreshes are conditional (ETag / Last-Modified kept in Redis) and the body is parsed as it
+// streams in. When the feed changed, only new URLs are inserted and delisted ones deleted, so
+// lookups never see a partially loaded table.
+
+use crate::db::{ClickHouseClient, RedisPool};
+use crate::services::background_tasks::TASK_REGISTRY;
+use crate::utils::outbound_proxy;
 use crate::CONFIG;
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use url::Url;
+use utoipa::ToSchema;
+
+/// Redis key holding the unix time of the last successful feed refresh
+const LAST_REFRESH_KEY: &str = "urlhaus:last_refresh";
+
+/// Redis key holding the validators of the last downloaded feed (JSON `FeedValidators`)
+const VALIDATORS_KEY: &str = "urlhaus:feed_validators";
+
+/// Redis key holding the outcome of the last update (JSON `FeedUpdateStats`)
+const UPDATE_STATS_KEY: &str = "urlhaus:feed_stats";
+
+/// Threats inserted, and delisted URLs deleted, per ClickHouse statement
+const FEED_WRITE_BATCH_SIZE: usize = 1000;
+
+/// How long a refresh time read from Redis is reused by lookups
+const FRESHNESS_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// Last refresh time read from Redis, and when it was read
+type CachedRefresh = Option<(Instant, Option<DateTime<Utc>>)>;
+static CACHED_REFRESH: Lazy<Mutex<CachedRefresh>> = Lazy::new(|| Mutex::new(None));
 
 // =============================================================================
 // ERROR TYPES
@@ -22,6 +54,9 @@ pub enum UrlhausError {
     #[error("Network error: {0}")]
     Network(#[from] reqwest::Error),
 
+    #[error("Redis error: {0}")]
+    Redis(#[from] redis::RedisError),
+
     #[error("Parse error: {0}")]
     Parse(String),
 }
@@ -44,6 +79,324 @@ pub struct UrlhausThreat {
     pub urlhaus_link: String,
 }
 
+/// What identifies the last downloaded copy of the feed
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct FeedValidators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+    /// SHA-256 of the feed's data lines; comment lines carry a generation time and
+    /// change on every download, so they are left out
+    content_hash: Option<String>,
+}
+
+/// What a feed update did
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedUpdateOutcome {
+    /// The server answered 304 Not Modified
+    NotModified,
+    /// Downloaded, but the entries matched the stored copy
+    Unchanged,
+    /// New entries were inserted and/or delisted ones deleted
+    Updated,
+}
+
+/// Result of one feed update, shared with every replica through Redis
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FeedUpdateStats {
+    pub outcome: FeedUpdateOutcome,
+    /// Entries in the lookup table after the update
+    pub entries: u64,
+    /// Bytes downloaded (0 when not modified)
+    pub feed_bytes: u64,
+    pub added: u64,
+    pub removed: u64,
+    pub duration_ms: u64,
+    pub finished_at: DateTime<Utc>,
+    /// When the entries last changed; carried over by updates that changed nothing
+    pub last_changed_at: Option<DateTime<Utc>>,
+}
+
+/// Threat feed state for operators, served by GET /v1/metrics/threat-feed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ThreatFeedMetrics {
+    pub timestamp: String,
+    pub enabled: bool,
+    /// None until the first successful refresh
+    pub last_refresh_at: Option<DateTime<Utc>>,
+    /// Never refreshed, or older than URLHAUS_STALE_AFTER_HOURS
+    pub stale: bool,
+    /// Last successful update by any replica; None before the first one or when Redis is
+    /// unreachable
+    pub last_update: Option<FeedUpdateStats>,
+}
+
+/// New and delisted entries between the stored table and a downloaded feed
+#[derive(Debug, Default)]
+struct FeedDiff {
+    added: Vec<UrlhausThreat>,
+    removed: Vec<String>,
+}
+
+impl FeedDiff {
+    /// Compare by URL; a URL listed twice in the feed is inserted once
+    fn between(stored: &HashSet<String>, feed: Vec<UrlhausThreat>) -> Self {
+        let mut listed = HashSet::with_capacity(feed.len());
+        let mut added = Vec::new();
+        for threat in feed {
+            if listed.insert(threat.url.clone()) && !stored.contains(&threat.url) {
+                added.push(threat);
+            }
+        }
+        let removed = stored
+            .iter()
+            .filter(|url| !listed.contains(*url))
+            .cloned()
+            .collect();
+        Self { added, removed }
+    }
+}
+
+/// Incremental parser fed with body chunks as they arrive; only a partial line and the
+/// parsed entries are held in memory
+struct FeedParser {
+    partial: Vec<u8>,
+    threats: Vec<UrlhausThreat>,
+    hasher: Sha256,
+    bytes: u64,
+    online: usize,
+    max_online: usize,
+}
+
+impl FeedParser {
+    fn new(max_online: usize) -> Self {
+        Self {
+            partial: Vec::new(),
+            threats: Vec::new(),
+            hasher: Sha256::new(),
+            bytes: 0,
+            online: 0,
+            max_online,
+        }
+    }
+
+    /// Whether URLHAUS_MAX_CACHE_SIZE online entries were read; the rest is not needed
+    fn is_full(&self) -> bool {
+        self.online >= self.max_online
+    }
+
+    fn push(&mut self, chunk: &[u8]) -> Result<(), UrlhausError> {
+        self.bytes += chunk.len() as u64;
+        self.partial.extend_from_slice(chunk);
+        let Some(end) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(());
+        };
+        let rest = self.partial.split_off(end + 1);
+        let complete = std::mem::replace(&mut self.partial, rest);
+        for line in complete.split(|b| *b == b'\n') {
+            self.line(line)?;
+        }
+        Ok(())
+    }
+
+    /// Entries read and the hash of their lines
+    fn finish(mut self) -> Result<(Vec<UrlhausThreat>, String, u64), UrlhausError> {
+        let last = std::mem::take(&mut self.partial);
+        self.line(&last)?;
+        let hash = format!("{:x}", self.hasher.finalize());
+        Ok((self.threats, hash, self.bytes))
+    }
+
+    fn line(&mut self, line: &[u8]) -> Result<(), UrlhausError> {
+        let line = String::from_utf8_lossy(line);
+        let line = line.trim_end_matches('\r');
+        if line.starts_with('#') || line.is_empty() || self.is_full() {
+            return Ok(());
+        }
+
+        let threat = UrlhausClient::parse_csv_line(line)?;
+        self.hasher.update(line.as_bytes());
+        self.hasher.update(b"\n");
+        if threat.url_status == "online" {
+            self.online += 1;
+        }
+        self.threats.push(threat);
+        Ok(())
+    }
+}
+
+/// Validators of the last downloaded feed
+async fn load_validators(redis_pool: &RedisPool) -> FeedValidators {
+    match redis_pool.get::<String>(VALIDATORS_KEY).await {
+        Ok(Some(json)) => serde_json::from_str(&json).unwrap_or_default(),
+        Ok(None) => FeedValidators::default(),
+        Err(e) => {
+            warn!(
+                "URLhaus feed validators unavailable, downloading in full: {}",
+                e
+            );
+            FeedValidators::default()
+        },
+    }
+}
+
+async fn store_json<T: Serialize>(
+    redis_pool: &RedisPool,
+    key: &str,
+    value: &T,
+) -> Result<(), redis::RedisError> {
+    let json = serde_json::to_string(value).unwrap_or_default();
+    let mut conn = redis_pool.get_connection().await?;
+    redis::cmd("SET")
+        .arg(key)
+        .arg(json)
+        .query_async::<()>(&mut conn)
+        .await
+}
+
+/// Outcome of the last feed update by any replica
+pub async fn last_feed_update(
+    redis_pool: &RedisPool,
+) -> Result<Option<FeedUpdateStats>, redis::RedisError> {
+    let json: Option<String> = redis_pool.get(UPDATE_STATS_KEY).await?;
+    Ok(json.and_then(|json| serde_json::from_str(&json).ok()))
+}
+
+/// Feed freshness and the last update, for GET /v1/metrics/threat-feed
+pub async fn threat_feed_metrics(redis_pool: &RedisPool) -> ThreatFeedMetrics {
+    let freshness = feed_freshness(redis_pool).await;
+    let last_update = last_feed_update(redis_pool).await;
+    if let Some(e) = freshness.as_ref().err().or(last_update.as_ref().err()) {
+        warn!("URLhaus feed state unavailable: {}", e);
+    }
+    let freshness = freshness.ok();
+
+    ThreatFeedMetrics {
+        timestamp: Utc::now().to_rfc3339(),
+        enabled: CONFIG.security.urlhaus_enabled,
+        last_refresh_at: freshness.as_ref().and_then(|f| f.last_refresh_at),
+        stale: freshness.is_none_or(|f| f.stale),
+        last_update: last_update.ok().flatten(),
+    }
+}
+
+/// How old the local copy of the feed is
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeedFreshness {
+    /// None until the first successful refresh
+    pub last_refresh_at: Option<DateTime<Utc>>,
+    pub age_seconds: Option<i64>,
+    /// Never refreshed, or older than URLHAUS_STALE_AFTER_HOURS
+    pub stale: bool,
+}
+
+impl FeedFreshness {
+    pub fn evaluate(
+        last_refresh_at: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        stale_after: chrono::Duration,
+    ) -> Self {
+        let age = last_refresh_at.map(|at| now - at);
+        Self {
+            last_refresh_at,
+            age_seconds: age.map(|age| age.num_seconds().max(0)),
+            stale: age.is_none_or(|age| age > stale_after),
+        }
+    }
+}
+
+/// Result of a URL lookup
+#[derive(Debug, Clone)]
+pub struct UrlhausCheck {
+    pub is_threat: bool,
+    /// None when no Redis pool was given or Redis is unreachable
+    pub feed: Option<FeedFreshness>,
+}
+
+impl UrlhausCheck {
+    /// A clean result from a stale feed may miss newer threats
+    pub fn feed_is_stale(&self) -> bool {
+        self.feed.as_ref().is_some_and(|feed| feed.stale)
+    }
+}
+
+/// Feed age reported as stale, from URLHAUS_STALE_AFTER_HOURS
+fn stale_after() -> chrono::Duration {
+    chrono::Duration::hours(i64::from(CONFIG.security.urlhaus_stale_after_hours))
+}
+
+/// Record a successful feed refresh for every replica
+pub async fn record_feed_refresh(
+    redis_pool: &RedisPool,
+    at: DateTime<Utc>,
+) -> Result<(), redis::RedisError> {
+    let mut conn = redis_pool.get_connection().await?;
+    redis::cmd("SET")
+        .arg(LAST_REFRESH_KEY)
+        .arg(at.timestamp())
+        .query_async::<()>(&mut conn)
+        .await?;
+    *CACHED_REFRESH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((Instant::now(), Some(at)));
+    Ok(())
+}
+
+/// When the feed was last refreshed by any replica
+pub async fn last_feed_refresh(
+    redis_pool: &RedisPool,
+) -> Result<Option<DateTime<Utc>>, redis::RedisError> {
+    let timestamp: Option<i64> = redis_pool.get(LAST_REFRESH_KEY).await?;
+    Ok(timestamp.and_then(|ts| DateTime::from_timestamp(ts, 0)))
+}
+
+/// Current feed age, read from Redis
+pub async fn feed_freshness(redis_pool: &RedisPool) -> Result<FeedFreshness, redis::RedisError> {
+    let last_refresh_at = last_feed_refresh(redis_pool).await?;
+    Ok(FeedFreshness::evaluate(
+        last_refresh_at,
+        Utc::now(),
+        stale_after(),
+    ))
+}
+
+/// Feed age for lookups, reading Redis at most once per FRESHNESS_CACHE_TTL
+async fn cached_feed_freshness(redis_pool: &RedisPool) -> Option<FeedFreshness> {
+    let cached = *CACHED_REFRESH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((read_at, last_refresh_at)) = cached {
+        if read_at.elapsed() < FRESHNESS_CACHE_TTL {
+            return Some(FeedFreshness::evaluate(
+                last_refresh_at,
+                Utc::now(),
+                stale_after(),
+            ));
+        }
+    }
+
+    let last_refresh_at = match last_feed_refresh(redis_pool).await {
+        Ok(last_refresh_at) => last_refresh_at,
+        Err(e) => {
+            tracing::debug!("URLhaus refresh time lookup failed: {}", e);
+            return None;
+        },
+    };
+    *CACHED_REFRESH
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some((Instant::now(), last_refresh_at));
+
+    let freshness = FeedFreshness::evaluate(last_refresh_at, Utc::now(), stale_after());
+    // Logged once per cache refresh rather than on every lookup
+    if freshness.stale {
+        warn!(
+            "URLhaus feed is stale (last refresh: {}); lookups may miss recent threats",
+            last_refresh_at.map_or_else(|| "never".to_string(), |at| at.to_rfc3339())
+        );
+    }
+    Some(freshness)
+}
+
 // =============================================================================
 // URLHAUS CLIENT
 // =============================================================================
@@ -56,7 +409,7 @@ pub struct UrlhausClient {
 impl UrlhausClient {
     /// Create new URLhaus client with shared ClickHouse backend
     pub fn new(clickhouse_client: Arc<ClickHouseClient>) -> Self {
-        let http_client = reqwest::Client::builder()
+        let http_client = outbound_proxy::apply(reqwest::Client::builder())
             .timeout(std::time::Duration::from_secs(30))
             .user_agent("QCK-URLhaus-Client/1.0")
             .build()
@@ -68,8 +421,22 @@ impl UrlhausClient {
         }
     }
 
-    /// Check if a URL is in the URLhaus threat database and get threat details
-    pub async fn check_url(&self, url: &str) -> Result<bool, UrlhausError> {
+    /// Check if a URL is in the URLhaus threat database.
+    /// With a Redis pool the result also reports how old the feed is.
+    pub async fn check_url(
+        &self,
+        url: &str,
+        redis_pool: Option<&RedisPool>,
+    ) -> Result<UrlhausCheck, UrlhausError> {
+        let feed = match redis_pool {
+            Some(redis_pool) => cached_feed_freshness(redis_pool).await,
+            None => None,
+        };
+        let is_threat = self.is_listed(url).await?;
+        Ok(UrlhausCheck { is_threat, feed })
+    }
+
+    async fn is_listed(&self, url: &str) -> Result<bool, UrlhausError> {
         // Validate URL format
         if Url::parse(url).is_err() {
             return Ok(false); // Invalid URL, not in threat DB
@@ -114,6 +481,7 @@ impl UrlhausClient {
     }
 
     /// Check if a domain is hosting malicious content
+    /// Delisted URLs are deleted by the updater, so every stored row is still listed
     pub async fn check_domain(&self, domain: &str) -> Result<u32, UrlhausError> {
         // Use parameterized query to prevent SQL injection
         let mut cursor = self
@@ -122,8 +490,7 @@ impl UrlhausClient {
             .query(
                 "SELECT COUNT(*) as count FROM urlhaus_threats 
                     WHERE url_host = ? 
-                      AND url_status = 'online'
-                      AND last_checked >= now() - INTERVAL 24 HOUR",
+                      AND url_status = 'online'",
             )
             .bind(domain)
             .fetch::<u32>()?;
@@ -132,40 +499,71 @@ impl UrlhausClient {
     }
 
     /// Update URLhaus threat database from abuse.ch feed
-    /// Always uses the online feed for simplicity
-    pub async fn update_from_feed(&self) -> Result<u32, UrlhausError> {
+    /// Always uses the online feed for simplicity. The download is skipped when the server
+    /// reports it unchanged, and the table is only written when its entries changed.
+    pub async fn update_from_feed(
+        &self,
+        redis_pool: &RedisPool,
+    ) -> Result<FeedUpdateStats, UrlhausError> {
         if !CONFIG.security.urlhaus_enabled {
             return Err(UrlhausError::Parse(
                 "URLhaus is disabled in configuration".to_string(),
             ));
         }
+        let started = Instant::now();
+        let previous = last_feed_update(redis_pool).await.ok().flatten();
+        let last_changed_at = previous.as_ref().and_then(|p| p.last_changed_at);
+
+        // An empty table (first run, or recreated by a migration) always needs the full feed
+        let stored_entries = self.count_entries().await?;
+        let validators = if stored_entries > 0 {
+            load_validators(redis_pool).await
+        } else {
+            FeedValidators::default()
+        };
 
         // Always use the online feed - it only contains currently active threats
         let feed_url = &CONFIG.security.urlhaus_feed_url;
         info!("Fetching URLhaus threat feed from {}", feed_url);
 
-        // Fetch the online threats CSV
-        let response = self.http_client.get(feed_url).send().await?;
-
-        let text = response.text().await?;
-        let mut threats = Vec::new();
-        let mut online_count = 0u32;
-
-        // Parse CSV and collect all threats first
-        for line in text.lines() {
-            if line.starts_with('#') || line.is_empty() {
-                continue;
-            }
-
-            // Parse CSV line (handle quoted fields properly)
-            let threat = self.parse_csv_line(line)?;
-            if threat.url_status == "online" {
-                online_count += 1;
-            }
-            threats.push(threat);
+        let mut request = self.http_client.get(feed_url);
+        if let Some(etag) = &validators.etag {
+            request = request.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &validators.last_modified {
+            request = request.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        let mut response = request.send().await?.error_for_status()?;
+
+        if response.status() == reqwest::StatusCode::NOT_MODIFIED {
+            info!("URLhaus feed not modified since the last download");
+            return Ok(FeedUpdateStats {
+                outcome: FeedUpdateOutcome::NotModified,
+                entries: stored_entries,
+                feed_bytes: 0,
+                added: 0,
+                removed: 0,
+                duration_ms: started.elapsed().as_millis() as u64,
+                finished_at: Utc::now(),
+                last_changed_at,
+            });
+        }
 
-            // Respect max cache size from config
-            if online_count >= CONFIG.security.urlhaus_max_cache_size as u32 {
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v: &reqwest::header::HeaderValue| v.to_str().ok())
+                .map(str::to_string)
+        };
+        let etag = header(ETAG);
+        let last_modified = header(LAST_MODIFIED);
+
+        // Parse as the body arrives instead of buffering the multi-megabyte feed
+        let mut parser = FeedParser::new(CONFIG.security.urlhaus_max_cache_size);
+        while let Some(chunk) = response.chunk().await? {
+            parser.push(&chunk)?;
+            if parser.is_full() {
                 info!(
                     "Reached max cache size limit: {}",
                     CONFIG.security.urlhaus_max_cache_size
@@ -173,43 +571,102 @@ impl UrlhausClient {
                 break;
             }
         }
+        let (threats, content_hash, feed_bytes) = parser.finish()?;
 
-        // Use atomic table swap to avoid security gap
-        // 1. Create temporary table with new data
-        self.clickhouse
+        let unchanged =
+            stored_entries > 0 && validators.content_hash.as_deref() == Some(content_hash.as_str());
+        let (added, removed, entries) = if unchanged {
+            (0, 0, stored_entries)
+        } else {
+            let stored = self.stored_urls().await?;
+            let diff = FeedDiff::between(&stored, threats);
+            let entries = (stored.len() + diff.added.len() - diff.removed.len()) as u64;
+
+            // Add before removing, so a URL is never missing while the table is rewritten
+            for chunk in diff.added.chunks(FEED_WRITE_BATCH_SIZE) {
+                self.insert_threats_to_table(chunk, "urlhaus_threats")
+                    .await?;
+            }
+            for chunk in diff.removed.chunks(FEED_WRITE_BATCH_SIZE) {
+                self.delete_urls(chunk).await?;
+            }
+            (diff.added.len() as u64, diff.removed.len() as u64, entries)
+        };
+
+        let validators = FeedValidators {
+            etag,
+            last_modified,
+            content_hash: Some(content_hash),
+        };
+        if let Err(e) = store_json(redis_pool, VALIDATORS_KEY, &validators).await {
+            warn!("Failed to store URLhaus feed validators: {}", e);
+        }
+
+        let changed = added > 0 || removed > 0;
+        info!(
+            "URLhaus update complete: {} entries ({} added, {} removed)",
+            entries, added, removed
+        );
+
+        Ok(FeedUpdateStats {
+            outcome: if changed {
+                FeedUpdateOutcome::Updated
+            } else {
+                FeedUpdateOutcome::Unchanged
+            },
+            entries,
+            feed_bytes,
+            added,
+            removed,
+            duration_ms: started.elapsed().as_millis() as u64,
+            finished_at: Utc::now(),
+            last_changed_at: if changed {
+                Some(Utc::now())
+            } else {
+                last_changed_at
+            },
+        })
+    }
+
+    /// Distinct URLs in the lookup table
+    async fn count_entries(&self) -> Result<u64, UrlhausError> {
+        let count = self
+            .clickhouse
             .client()
-            .query("CREATE TABLE IF NOT EXISTS urlhaus_threats_temp AS urlhaus_threats")
-            .execute()
+            .query("SELECT uniqExact(url) FROM urlhaus_threats")
+            .fetch_one::<u64>()
             .await?;
-        self.clickhouse
+        Ok(count)
+    }
+
+    /// Every URL in the lookup table
+    async fn stored_urls(&self) -> Result<HashSet<String>, UrlhausError> {
+        let mut cursor = self
+            .clickhouse
             .client()
-            .query("TRUNCATE TABLE urlhaus_threats_temp")
-            .execute()
-            .await?;
+            .query("SELECT DISTINCT url FROM urlhaus_threats")
+            .fetch::<String>()?;
 
-        // 2. Insert all new threats into temp table
-        for chunk in threats.chunks(1000) {
-            self.insert_threats_to_table(chunk, "urlhaus_threats_temp")
-                .await?;
+        let mut urls = HashSet::new();
+        while let Some(url) = cursor.next().await? {
+            urls.insert(url);
         }
+        Ok(urls)
+    }
 
-        // 3. Atomic swap: rename tables (nearly instantaneous)
-        self.clickhouse.client().query("RENAME TABLE urlhaus_threats TO urlhaus_threats_old, urlhaus_threats_temp TO urlhaus_threats").execute().await?;
-
-        // 4. Clean up old table
+    /// Delete delisted URLs
+    async fn delete_urls(&self, urls: &[String]) -> Result<(), UrlhausError> {
         self.clickhouse
             .client()
-            .query("DROP TABLE IF EXISTS urlhaus_threats_old")
+            .query("ALTER TABLE urlhaus_threats DELETE WHERE has(?, url)")
+            .bind(urls)
             .execute()
             .await?;
-
-        info!("URLhaus update complete: {} threats loaded", online_count);
-
-        Ok(online_count)
+        Ok(())
     }
 
     /// Parse CSV line from URLhaus feed with proper quote handling
-    fn parse_csv_line(&self, line: &str) -> Result<UrlhausThreat, UrlhausError> {
+    fn parse_csv_line(line: &str) -> Result<UrlhausThreat, UrlhausError> {
         // Proper CSV parser that handles quoted fields with commas
         let mut parts = Vec::new();
         let mut current = String::new();
@@ -341,51 +798,167 @@ impl UrlhausClient {
 // =============================================================================
 
 /// Spawn a background task to periodically update URLhaus data
-/// Updates based on configured interval (default: daily)
-pub fn spawn_urlhaus_updater() {
+/// Updates every URLHAUS_UPDATE_INTERVAL_HOURS (default: daily); started by the background
+/// task manager. A restart only downloads the feed again once the last refresh is due.
+pub fn spawn_urlhaus_updater(redis_pool: RedisPool) {
     if !CONFIG.security.urlhaus_enabled {
         info!("URLhaus threat intelligence is disabled in configuration");
         return;
     }
 
-    tokio::spawn(async move {
-        let clickhouse_client = crate::db::clickhouse_client::create_clickhouse_client();
-        let client = UrlhausClient::new(clickhouse_client);
+    let update_interval =
+        Duration::from_secs(u64::from(CONFIG.security.urlhaus_update_interval_hours) * 3600);
+
+    // The feed lands in shared ClickHouse tables, so one replica downloads it for all
+    let lock_pool = redis_pool.clone();
+    TASK_REGISTRY.spawn_exclusive("urlhaus_update", lock_pool, move |reporter| {
+        let redis_pool = redis_pool.clone();
+        async move {
+            let clickhouse_client = crate::db::clickhouse_client::create_clickhouse_client();
+            let client = UrlhausClient::new(clickhouse_client);
+
+            // Another replica or an earlier run may have refreshed recently; wait until due
+            let first_run = match last_feed_refresh(&redis_pool).await {
+                Ok(Some(last_refresh)) => {
+                    let age = (Utc::now() - last_refresh).to_std().unwrap_or_default();
+                    update_interval.saturating_sub(age)
+                },
+                _ => Duration::ZERO,
+            };
+            let mut interval =
+                tokio::time::interval_at(tokio::time::Instant::now() + first_run, update_interval);
+
+            loop {
+                interval.tick().await;
+                if !reporter.is_leader().await {
+                    continue;
+                }
+
+                info!("Starting scheduled URLhaus feed update...");
+                match client.update_from_feed(&redis_pool).await {
+                    Ok(stats) => {
+                        info!(
+                            "URLhaus update successful ({:?}) in {}ms: {} entries",
+                            stats.outcome, stats.duration_ms, stats.entries
+                        );
+                        // Not modified or unchanged still confirms the local copy is current
+                        if let Err(e) = record_feed_refresh(&redis_pool, stats.finished_at).await {
+                            warn!("Failed to record URLhaus refresh time: {}", e);
+                        }
+                        if let Err(e) = store_json(&redis_pool, UPDATE_STATS_KEY, &stats).await {
+                            warn!("Failed to record URLhaus update stats: {}", e);
+                        }
+                        reporter.success();
+                    },
+                    Err(e) => {
+                        error!("URLhaus update failed: {}", e);
+                        reporter.failure(e);
+                    },
+                }
+            }
+        }
+    });
+}
 
-        // Initial update on startup
-        info!("Running initial URLhaus feed update...");
-        match client.update_from_feed().await {
-            Ok(count) => {
-                info!(
-                    "Initial URLhaus update successful: {} threats loaded",
-                    count
-                );
-            },
-            Err(e) => {
-                error!("Initial URLhaus update failed: {}", e);
-            },
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = "################################################################\r
+# abuse.ch URLhaus Database Dump (CSV - online URLs only)      #\r
+# Last updated: 2025-09-29 10:00:00 (UTC)                      #\r
+# id,dateadded,url,url_status,last_online,threat,tags,urlhaus_link,reporter\r
+\"3001\",\"2025-09-28 09:00:00\",\"http://203.0.113.7/bins/x86\",\"online\",\"2025-09-29 09:00:00\",\"malware_download\",\"elf|mirai\",\"https://urlhaus.abuse.ch/url/3001/\",\"geenensp\"\r
+\"3002\",\"2025-09-28 10:00:00\",\"http://bad.example/payload.exe\",\"online\",\"2025-09-29 09:00:00\",\"malware_download\",\"exe\",\"https://urlhaus.abuse.ch/url/3002/\",\"anonymous\"\r
+";
+
+    fn parse_in_chunks(
+        feed: &str,
+        chunk_size: usize,
+        max_online: usize,
+    ) -> (Vec<UrlhausThreat>, String, u64) {
+        let mut parser = FeedParser::new(max_online);
+        for chunk in feed.as_bytes().chunks(chunk_size) {
+            parser.push(chunk).unwrap();
         }
+        parser.finish().unwrap()
+    }
 
-        // Then update based on configured interval
-        let update_interval_secs = CONFIG.security.urlhaus_update_interval_hours as u64 * 3600;
-        let mut interval =
-            tokio::time::interval(std::time::Duration::from_secs(update_interval_secs));
+    #[test]
+    fn test_feed_parser_handles_any_chunking() {
+        let (threats, hash, bytes) = parse_in_chunks(FEED, FEED.len(), 100);
+        assert_eq!(bytes, FEED.len() as u64);
+        assert_eq!(threats.len(), 2);
+        assert_eq!(threats[0].url, "http://203.0.113.7/bins/x86");
+        assert_eq!(threats[0].tags, vec!["elf", "mirai"]);
+        assert_eq!(threats[1].reporter, "anonymous");
+
+        // Lines split across chunks parse the same
+        for chunk_size in [1, 7, 64] {
+            let (chunked, chunked_hash, _) = parse_in_chunks(FEED, chunk_size, 100);
+            assert_eq!(chunked.len(), 2);
+            assert_eq!(chunked_hash, hash);
+        }
 
-        // Skip the first tick since we just did the initial update
-        interval.tick().await;
+        // A missing trailing newline still yields the last entry
+        let (threats, _, _) = parse_in_chunks(FEED.trim_end(), 16, 100);
+        assert_eq!(threats.len(), 2);
+    }
 
-        loop {
-            interval.tick().await;
+    #[test]
+    fn test_feed_hash_ignores_comment_lines() {
+        let (_, hash, _) = parse_in_chunks(FEED, 32, 100);
+        let regenerated = FEED.replace("10:00:00 (UTC)", "10:05:00 (UTC)");
+        assert_eq!(parse_in_chunks(&regenerated, 32, 100).1, hash);
 
-            info!("Starting scheduled URLhaus feed update...");
-            match client.update_from_feed().await {
-                Ok(count) => {
-                    info!("URLhaus daily update successful: {} threats loaded", count);
-                },
-                Err(e) => {
-                    error!("URLhaus daily update failed: {}", e);
-                },
-            }
-        }
-    });
+        let relisted = FEED.replace("payload.exe", "payload2.exe");
+        assert_ne!(parse_in_chunks(&relisted, 32, 100).1, hash);
+    }
+
+    #[test]
+    fn test_feed_parser_stops_at_max_cache_size() {
+        let (threats, _, _) = parse_in_chunks(FEED, 32, 1);
+        assert_eq!(threats.len(), 1);
+    }
+
+    #[test]
+    fn test_feed_diff() {
+        let (threats, _, _) = parse_in_chunks(FEED, FEED.len(), 100);
+        let mut feed = threats.clone();
+        // Listed twice in the feed
+        feed.push(threats[0].clone());
+
+        let stored: HashSet<String> = [
+            "http://bad.example/payload.exe".to_string(),
+            "http://delisted.example/".to_string(),
+        ]
+        .into();
+        let diff = FeedDiff::between(&stored, feed);
+        assert_eq!(diff.added.len(), 1);
+        assert_eq!(diff.added[0].url, "http://203.0.113.7/bins/x86");
+        assert_eq!(diff.removed, vec!["http://delisted.example/".to_string()]);
+
+        let stored: HashSet<String> = threats.iter().map(|t| t.url.clone()).collect();
+        let diff = FeedDiff::between(&stored, threats);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+    }
+
+    #[test]
+    fn test_feed_freshness() {
+        let now = Utc::now();
+        let stale_after = chrono::Duration::hours(48);
+
+        let never = FeedFreshness::evaluate(None, now, stale_after);
+        assert!(never.stale);
+        assert_eq!(never.age_seconds, None);
+
+        let recent =
+            FeedFreshness::evaluate(Some(now - chrono::Duration::hours(3)), now, stale_after);
+        assert!(!recent.stale);
+        assert_eq!(recent.age_seconds, Some(3 * 3600));
+
+        let old =
+            FeedFreshness::evaluate(Some(now - chrono::Duration::hours(49)), now, stale_after);
+        assert!(old.stale);
+    }
 }
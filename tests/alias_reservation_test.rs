@@ -0,0 +1,85 @@
+// Alias reservations taken by GET /v1/links/check-alias/{alias}?reserve=true
+// Run against the Postgres and Redis in .env.test
+
+use qck_backend_core::app::AppState;
+use qck_backend_core::services::link::{
+    alias_reservation_key, LinkService, ALIAS_RESERVATION_TTL_SECONDS,
+};
+use uuid::Uuid;
+
+mod common;
+use common::app_state;
+
+/// Seconds left on a Redis key
+async fn ttl(state: &AppState, key: &str) -> i64 {
+    let mut conn = state.redis_pool.get_connection().await.unwrap();
+    redis::cmd("TTL")
+        .arg(key)
+        .query_async(&mut conn)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_first_reservation_wins() {
+    let state = app_state().await;
+    let service = LinkService::new(&state);
+    let alias = format!("res{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+    assert!(service.reserve_alias(&alias, first).await.unwrap());
+    assert!(!service.reserve_alias(&alias, second).await.unwrap());
+
+    // Held for the reserver only, whatever the case it is asked in
+    assert!(!service.alias_reserved_by_other(&alias, first).await);
+    assert!(
+        service
+            .alias_reserved_by_other(&alias.to_uppercase(), second)
+            .await
+    );
+
+    let key = alias_reservation_key(&alias);
+    let remaining = ttl(&state, &key).await;
+    assert!(
+        remaining > 0 && remaining <= ALIAS_RESERVATION_TTL_SECONDS as i64,
+        "ttl {remaining}"
+    );
+
+    let _ = state.redis_pool.del(&key).await;
+}
+
+#[tokio::test]
+async fn test_reserving_again_refreshes_own_hold() {
+    let state = app_state().await;
+    let service = LinkService::new(&state);
+    let alias = format!("res{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let user = Uuid::new_v4();
+    let key = alias_reservation_key(&alias);
+
+    assert!(service.reserve_alias(&alias, user).await.unwrap());
+    let mut conn = state.redis_pool.get_connection().await.unwrap();
+    redis::cmd("EXPIRE")
+        .arg(&key)
+        .arg(5)
+        .query_async::<()>(&mut conn)
+        .await
+        .unwrap();
+
+    assert!(service.reserve_alias(&alias, user).await.unwrap());
+    assert!(ttl(&state, &key).await > 5);
+
+    let _ = state.redis_pool.del(&key).await;
+}
+
+#[tokio::test]
+async fn test_unreserved_alias_is_free_for_everyone() {
+    let state = app_state().await;
+    let service = LinkService::new(&state);
+    let alias = format!("res{}", &Uuid::new_v4().simple().to_string()[..8]);
+
+    assert!(
+        !service
+            .alias_reserved_by_other(&alias, Uuid::new_v4())
+            .await
+    );
+}
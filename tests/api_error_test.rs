@@ -0,0 +1,143 @@
+// Error envelope tests
+// Every error type renders as {code, message, details, request_id} with the status preserved
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Router,
+};
+use qck_backend_core::handlers::links::create_custom_link;
+use qck_backend_core::middleware::request_id_middleware;
+use qck_backend_core::utils::{service_error::ServiceError, ApiError, AuthError, LinkError};
+use tower::ServiceExt;
+
+fn app() -> Router {
+    Router::new()
+        .route(
+            "/link",
+            get(|| async { LinkError::AliasExists("promo".to_string()).into_response() }),
+        )
+        .route(
+            "/auth",
+            get(|| async {
+                AuthError::AccountLocked {
+                    retry_after_seconds: 900,
+                }
+                .into_response()
+            }),
+        )
+        .route(
+            "/service",
+            get(|| async { ServiceError::Quarantined.into_response() }),
+        )
+        .route(
+            "/field",
+            get(|| async {
+                ApiError::invalid_field("email", "email", "Invalid email format").into_response()
+            }),
+        )
+        .route("/links/custom", post(create_custom_link))
+        .layer(middleware::from_fn(request_id_middleware))
+}
+
+async fn get_path(path: &str, request_id: Option<&str>) -> Response {
+    let mut request = Request::builder().uri(path);
+    if let Some(id) = request_id {
+        request = request.header("x-request-id", id);
+    }
+    app()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body_json(response: Response) -> serde_json::Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[tokio::test]
+async fn test_link_error_envelope() {
+    let response = get_path("/link", None).await;
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let request_id = response.headers()["x-request-id"]
+        .to_str()
+        .unwrap()
+        .to_string();
+    let body = body_json(response).await;
+
+    assert_eq!(body["code"], "ALIAS_EXISTS");
+    assert_eq!(body["message"], "Custom alias already exists: promo");
+    assert_eq!(body["details"], serde_json::json!([]));
+    assert_eq!(body["request_id"], request_id.as_str());
+}
+
+#[tokio::test]
+async fn test_incoming_request_id_is_reused() {
+    let response = get_path("/service", Some("lb-trace-42")).await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response.headers()["x-request-id"], "lb-trace-42");
+
+    let body = body_json(response).await;
+    assert_eq!(body["code"], "LINK_QUARANTINED");
+    assert_eq!(body["request_id"], "lb-trace-42");
+}
+
+#[tokio::test]
+async fn test_malformed_request_id_is_replaced() {
+    let response = get_path("/service", Some("")).await;
+    let request_id = response.headers()["x-request-id"].to_str().unwrap();
+    assert!(uuid::Uuid::parse_str(request_id).is_ok());
+}
+
+#[tokio::test]
+async fn test_auth_error_sets_retry_after() {
+    let response = get_path("/auth", None).await;
+    assert_eq!(response.status(), StatusCode::LOCKED);
+    assert_eq!(response.headers()[header::RETRY_AFTER], "900");
+
+    let body = body_json(response).await;
+    assert_eq!(body["code"], "ACCOUNT_LOCKED");
+    assert!(body.get("success").is_none());
+}
+
+#[tokio::test]
+async fn test_field_errors_are_structured() {
+    let response = get_path("/field", None).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+
+    let body = body_json(response).await;
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    assert_eq!(
+        body["details"],
+        serde_json::json!([{
+            "field": "email",
+            "code": "email",
+            "message": "Invalid email format"
+        }])
+    );
+}
+
+#[tokio::test]
+async fn test_removed_custom_link_route_is_gone() {
+    let response = app()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/links/custom")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+
+    let body = body_json(response).await;
+    assert_eq!(body["code"], "ENDPOINT_REMOVED");
+    // Points integrators at the replacement
+    assert!(body["message"].as_str().unwrap().contains("custom_alias"));
+}
@@ -0,0 +1,103 @@
+// Operator blocklist and trusted domain matching and request normalization tests
+// Pure in-memory checks - no database or Redis required
+
+use qck_backend_core::models::blocked_domain::{BlockedEntryType, CreateBlockedDomainRequest};
+use qck_backend_core::models::trusted_domain::CreateTrustedDomainRequest;
+use qck_backend_core::utils::security_scanner::{OperatorBlocklist, TrustedDomains};
+
+fn request(pattern: &str, entry_type: BlockedEntryType) -> CreateBlockedDomainRequest {
+    CreateBlockedDomainRequest {
+        pattern: pattern.to_string(),
+        entry_type,
+        reason: "reported scam".to_string(),
+    }
+}
+
+#[test]
+fn test_domain_entry_matches_domain_and_subdomains() {
+    let mut blocklist = OperatorBlocklist::new();
+    blocklist.insert_domain("scam.example", "reported scam");
+
+    assert_eq!(blocklist.matches("scam.example"), Some("reported scam"));
+    assert_eq!(
+        blocklist.matches("login.scam.example"),
+        Some("reported scam")
+    );
+    assert_eq!(blocklist.matches("SCAM.EXAMPLE."), Some("reported scam"));
+    assert_eq!(blocklist.matches("notscam.example"), None);
+    assert_eq!(blocklist.matches("example"), None);
+}
+
+#[test]
+fn test_tld_entry_matches_any_domain_under_it() {
+    let mut blocklist = OperatorBlocklist::new();
+    blocklist.insert_tld("zip", "abused tld");
+
+    assert_eq!(blocklist.matches("files.zip"), Some("abused tld"));
+    assert_eq!(blocklist.matches("a.b.files.zip"), Some("abused tld"));
+    assert_eq!(blocklist.matches("zip.example.com"), None);
+    assert_eq!(blocklist.len(), 1);
+}
+
+#[test]
+fn test_pattern_normalization() {
+    assert_eq!(
+        request("https://Scam.Example/login", BlockedEntryType::Domain).normalized_pattern(),
+        Ok("scam.example".to_string())
+    );
+    assert_eq!(
+        request("*.scam.example", BlockedEntryType::Domain).normalized_pattern(),
+        Ok("scam.example".to_string())
+    );
+    assert_eq!(
+        request(".ZIP", BlockedEntryType::Tld).normalized_pattern(),
+        Ok("zip".to_string())
+    );
+}
+
+#[test]
+fn test_pattern_validation_rejects_mismatched_types() {
+    assert!(request("scam.example", BlockedEntryType::Tld)
+        .normalized_pattern()
+        .is_err());
+    assert!(request("localhost", BlockedEntryType::Domain)
+        .normalized_pattern()
+        .is_err());
+    assert!(request("bad_domain!.com", BlockedEntryType::Domain)
+        .normalized_pattern()
+        .is_err());
+}
+
+#[test]
+fn test_trusted_domain_matches_domain_and_subdomains() {
+    let mut trusted = TrustedDomains::new();
+    trusted.insert("corp.example.com", "customer domain");
+
+    assert_eq!(trusted.matches("corp.example.com"), Some("customer domain"));
+    assert_eq!(
+        trusted.matches("verify-account.corp.example.com."),
+        Some("customer domain")
+    );
+    assert_eq!(trusted.matches("corp.example.com.evil.tk"), None);
+    assert_eq!(trusted.matches("notcorp.example.com"), None);
+    assert_eq!(trusted.matches("example.com"), None);
+}
+
+#[test]
+fn test_trusted_domain_pattern_normalization() {
+    let request = |pattern: &str| CreateTrustedDomainRequest {
+        pattern: pattern.to_string(),
+        reason: "customer domain".to_string(),
+    };
+
+    assert_eq!(
+        request("https://Corp.Example.com/account/verify").normalized_pattern(),
+        Ok("corp.example.com".to_string())
+    );
+    assert_eq!(
+        request("*.corp.example.com").normalized_pattern(),
+        Ok("corp.example.com".to_string())
+    );
+    // Whole TLDs can't be trusted
+    assert!(request("com").normalized_pattern().is_err());
+}
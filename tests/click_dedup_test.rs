@@ -0,0 +1,63 @@
+// Repeat click detection tests
+// Run against the Redis in .env.test
+
+use qck_backend_core::db::{RedisConfig, RedisPool};
+use qck_backend_core::services::click_dedup::ClickDeduplicator;
+use std::net::IpAddr;
+use std::time::Duration;
+use uuid::Uuid;
+
+const SECRET: &str = "click-dedup-test-secret-0123456789abcdef";
+const USER_AGENT: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_5) Safari/605.1.15";
+
+async fn redis_pool() -> RedisPool {
+    dotenv::from_filename(".env.test").ok();
+    RedisPool::new(RedisConfig::from_env())
+        .await
+        .expect("Redis must be available for click dedup tests")
+}
+
+fn visitor_ip() -> IpAddr {
+    "198.51.100.23".parse().unwrap()
+}
+
+#[tokio::test]
+async fn test_repeat_click_within_window_is_flagged() {
+    let dedup = ClickDeduplicator::new(redis_pool().await, 30, SECRET);
+    let link_id = Uuid::new_v4();
+    let visitor = dedup.visitor_hash(visitor_ip(), USER_AGENT);
+
+    assert!(!dedup.is_repeat(link_id, &visitor).await);
+    assert!(dedup.is_repeat(link_id, &visitor).await);
+    assert!(dedup.is_repeat(link_id, &visitor).await);
+
+    // Other visitors and other links are counted independently
+    let other_visitor = dedup.visitor_hash(visitor_ip(), "curl/8.5.0");
+    assert!(!dedup.is_repeat(link_id, &other_visitor).await);
+    assert!(!dedup.is_repeat(Uuid::new_v4(), &visitor).await);
+}
+
+#[tokio::test]
+async fn test_click_after_window_counts_again() {
+    let dedup = ClickDeduplicator::new(redis_pool().await, 1, SECRET);
+    let link_id = Uuid::new_v4();
+    let visitor = dedup.visitor_hash(visitor_ip(), USER_AGENT);
+
+    assert!(!dedup.is_repeat(link_id, &visitor).await);
+    assert!(dedup.is_repeat(link_id, &visitor).await);
+
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!dedup.is_repeat(link_id, &visitor).await);
+}
+
+#[tokio::test]
+async fn test_disabled_window_counts_every_click() {
+    let dedup = ClickDeduplicator::new(redis_pool().await, 0, SECRET);
+    let link_id = Uuid::new_v4();
+    let visitor = dedup.visitor_hash(visitor_ip(), USER_AGENT);
+
+    assert!(!dedup.is_enabled());
+    for _ in 0..3 {
+        assert!(!dedup.is_repeat(link_id, &visitor).await);
+    }
+}
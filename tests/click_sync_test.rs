@@ -0,0 +1,121 @@
+// Click counter drain tests
+// Run against the Redis in .env.test; serial because the drain takes every `clicks:*` key
+
+use qck_backend_core::db::{RedisConfig, RedisPool};
+use qck_backend_core::services::click_counter::{
+    click_counter_key, drain_click_counters, requeue_click_counts,
+};
+use redis::AsyncCommands;
+use serial_test::serial;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+async fn redis_pool() -> RedisPool {
+    dotenv::from_filename(".env.test").ok();
+    RedisPool::new(RedisConfig::from_env())
+        .await
+        .expect("Redis must be available for click sync tests")
+}
+
+/// Short codes unique to one test run, so stray keys from other runs don't match
+fn test_codes(count: usize) -> Vec<String> {
+    let run = Uuid::new_v4().simple().to_string();
+    (0..count)
+        .map(|i| format!("t{}x{}", &run[..8], i))
+        .collect()
+}
+
+/// Drained counts for this test's codes only
+fn counts_for(drained: &HashMap<String, i64>, codes: &[String]) -> HashMap<String, i64> {
+    codes
+        .iter()
+        .filter_map(|code| drained.get(code).map(|clicks| (code.clone(), *clicks)))
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_drain_counts_regular_and_fallback_keys_once() {
+    let pool = redis_pool().await;
+    let codes = test_codes(3000);
+    let mut conn = pool.get_connection().await.unwrap();
+
+    let mut pipe = redis::pipe();
+    for (i, code) in codes.iter().enumerate() {
+        pipe.incr(click_counter_key(code), i as i64 + 1).ignore();
+        // Every tenth code also has counts from a failed database sync
+        if i % 10 == 0 {
+            pipe.incr(format!("clicks:fallback:{}", code), 1000)
+                .ignore();
+        }
+    }
+    pipe.query_async::<()>(&mut conn).await.unwrap();
+
+    let drained = counts_for(&drain_click_counters(&pool).await.unwrap(), &codes);
+    assert_eq!(drained.len(), codes.len());
+    for (i, code) in codes.iter().enumerate() {
+        let expected = i as i64 + 1 + if i % 10 == 0 { 1000 } else { 0 };
+        assert_eq!(drained[code], expected, "wrong count for {}", code);
+    }
+
+    // Every key was removed, so a second drain finds nothing for these codes
+    let again = counts_for(&drain_click_counters(&pool).await.unwrap(), &codes);
+    assert!(again.is_empty(), "{} codes counted twice", again.len());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_drain_loses_no_concurrent_increments() {
+    let pool = redis_pool().await;
+    let codes = test_codes(2000);
+    const ROUNDS: i64 = 5;
+
+    let writer = {
+        let pool = pool.clone();
+        let codes = codes.clone();
+        tokio::spawn(async move {
+            let mut conn = pool.get_connection().await.unwrap();
+            for _ in 0..ROUNDS {
+                for chunk in codes.chunks(250) {
+                    let mut pipe = redis::pipe();
+                    for code in chunk {
+                        pipe.incr(click_counter_key(code), 1).ignore();
+                    }
+                    pipe.query_async::<()>(&mut conn).await.unwrap();
+                    tokio::task::yield_now().await;
+                }
+            }
+        })
+    };
+
+    // Drain repeatedly while the writer is still incrementing
+    let mut totals: HashMap<String, i64> = HashMap::new();
+    while !writer.is_finished() {
+        for (code, clicks) in counts_for(&drain_click_counters(&pool).await.unwrap(), &codes) {
+            *totals.entry(code).or_insert(0) += clicks;
+        }
+    }
+    writer.await.unwrap();
+    for (code, clicks) in counts_for(&drain_click_counters(&pool).await.unwrap(), &codes) {
+        *totals.entry(code).or_insert(0) += clicks;
+    }
+
+    assert_eq!(totals.values().sum::<i64>(), ROUNDS * codes.len() as i64);
+    assert!(totals.values().all(|clicks| *clicks == ROUNDS));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_requeued_counts_are_drained_next_time() {
+    let pool = redis_pool().await;
+    let codes = test_codes(2);
+    let mut conn = pool.get_connection().await.unwrap();
+    let _: () = conn.incr(click_counter_key(&codes[0]), 3).await.unwrap();
+
+    let failed_batch = vec![(codes[0].clone(), 7), (codes[1].clone(), 2)];
+    requeue_click_counts(&pool, &failed_batch).await.unwrap();
+
+    let drained = counts_for(&drain_click_counters(&pool).await.unwrap(), &codes);
+    assert_eq!(drained[&codes[0]], 10);
+    assert_eq!(drained[&codes[1]], 2);
+}
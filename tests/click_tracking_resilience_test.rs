@@ -46,6 +46,7 @@ async fn setup_test_state() -> AppState {
         ),
         clickhouse_analytics: None, // Disabled for tests
         max_connections: 10,
+        health: Default::default(),
     }
 }
 
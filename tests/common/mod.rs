@@ -1,5 +1,6 @@
 // Common test utilities and helper structs
-// Shared across all test files to avoid duplication
+// Shared across all test files to avoid duplication; each test binary uses a subset
+#![allow(dead_code)]
 
 use axum::{
     body::Body,
@@ -8,14 +9,20 @@ use axum::{
     Router,
 };
 use diesel::prelude::*;
-use diesel_async::{AsyncConnection, AsyncPgConnection};
+use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
 use qck_backend_core::{
     app::AppState,
     config::rate_limit::RateLimitingConfig,
     db::{create_diesel_pool, DieselDatabaseConfig, DieselPool, RedisConfig, RedisPool},
+    models::{
+        organization::{NewOrganization, NewOrganizationMember, OrgRole, Organization},
+        user::{NewUser, OnboardingStatus, User},
+    },
     services::{
-        EmailService, JwtService, PasswordResetService, RateLimitService,
+        email::RecordingEmailTransport, EmailService, JwtService, PasswordResetService,
+        RateLimitService,
     },
+    utils::security_scanner::RecordingThreatLookup,
 };
 use serde::Serialize;
 use std::net::SocketAddr;
@@ -73,16 +80,20 @@ pub struct TestApp {
     pub diesel_pool: DieselPool,
     pub redis_pool: RedisPool,
     pub jwt_service: Arc<JwtService>,
+    /// Emails the app sent; nothing reaches Resend
+    pub emails: Arc<RecordingEmailTransport>,
+    /// URLs the full security scan looked up; nothing goes over the network
+    pub threat_lookups: Arc<RecordingThreatLookup>,
 }
 
 impl TestApp {
     /// Send a POST request
-    pub fn post(&self, uri: &str) -> TestRequest {
+    pub fn post(&self, uri: &str) -> TestRequest<'_> {
         TestRequest::new(self, "POST", uri)
     }
 
     /// Send a GET request
-    pub fn get(&self, uri: &str) -> TestRequest {
+    pub fn get(&self, uri: &str) -> TestRequest<'_> {
         TestRequest::new(self, "GET", uri)
     }
 }
@@ -112,12 +123,16 @@ impl<'a> TestRequest<'a> {
     /// Add JSON body to request
     pub fn json<T: Serialize>(mut self, body: &T) -> Self {
         let body_bytes = serde_json::to_vec(body).unwrap();
-        self.request = Request::builder()
-            .method(self.request.method().clone())
-            .uri(self.request.uri().clone())
-            .header("content-type", "application/json")
-            .body(Body::from(body_bytes))
-            .unwrap();
+        *self.request.body_mut() = Body::from(body_bytes);
+        self = self.header("content-type", "application/json");
+        self
+    }
+
+    /// Add a request header
+    pub fn header(mut self, name: &'static str, value: &str) -> Self {
+        self.request
+            .headers_mut()
+            .insert(name, value.parse().unwrap());
         self
     }
 
@@ -164,6 +179,32 @@ impl TestResponse {
         self.response.status()
     }
 
+    /// Get a response header as a string
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.response
+            .headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+    }
+
+    /// Full Set-Cookie header for the named cookie
+    pub fn set_cookie(&self, name: &str) -> Option<&str> {
+        self.response
+            .headers()
+            .get_all("set-cookie")
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .find(|v| v.starts_with(&format!("{}=", name)))
+    }
+
+    /// Value set for the named cookie
+    pub fn cookie(&self, name: &str) -> Option<String> {
+        self.set_cookie(name)
+            .and_then(|v| v.split(';').next())
+            .and_then(|pair| pair.split_once('='))
+            .map(|(_, value)| value.to_string())
+    }
+
     /// Parse JSON response
     pub async fn json<T: serde::de::DeserializeOwned>(self) -> T {
         let body = axum::body::to_bytes(self.response.into_body(), usize::MAX)
@@ -204,9 +245,13 @@ pub async fn setup_test_app() -> TestApp {
     // Get config
     let config = qck_backend_core::app_config::config();
 
-    // Create email service
-    let email_service =
-        Arc::new(EmailService::new(config.email.clone()).expect("Failed to create email service"));
+    // In-memory fakes, so tests need no Resend key and make no outbound scans
+    let emails = Arc::new(RecordingEmailTransport::new());
+    let threat_lookups = Arc::new(RecordingThreatLookup::new());
+    let email_service = Arc::new(
+        EmailService::with_transport(config.email.clone(), emails.clone())
+            .expect("Failed to create email service"),
+    );
 
     // Create password reset service
     let password_reset_service = Arc::new(PasswordResetService::new(diesel_pool.clone()));
@@ -220,15 +265,25 @@ pub async fn setup_test_app() -> TestApp {
         rate_limit_service,
         rate_limit_config: Arc::new(RateLimitingConfig::from_env()),
         email_service,
+        threat_lookup: threat_lookups.clone(),
         password_reset_service,
         clickhouse_analytics: None, // Disabled for tests
         max_connections: config.database.max_connections,
+        health: Default::default(),
     };
 
     // Build router with auth routes (public + protected)
     let app = Router::new()
         .nest("/v1/auth", qck_backend_core::handlers::public_auth_routes())
-        .nest("/v1/auth", qck_backend_core::handlers::protected_auth_routes())
+        .nest(
+            "/v1/auth",
+            qck_backend_core::handlers::protected_auth_routes().route_layer(
+                axum::middleware::from_fn_with_state(
+                    app_state.clone(),
+                    qck_backend_core::middleware::auth_middleware,
+                ),
+            ),
+        )
         .with_state(app_state);
 
     TestApp {
@@ -236,9 +291,71 @@ pub async fn setup_test_app() -> TestApp {
         diesel_pool,
         redis_pool,
         jwt_service,
+        emails,
+        threat_lookups,
     }
 }
 
+/// Full app state from `initialize_app_state`, against the Postgres and Redis in .env.test
+pub async fn app_state() -> AppState {
+    dotenv::from_filename(".env.test").ok();
+    qck_backend_core::initialize_app_state()
+        .await
+        .expect("Postgres and Redis must be available for this test")
+}
+
+/// A verified, onboarded free-tier user with a unique `{prefix}_...@example.com` email
+pub async fn create_test_user(conn: &mut AsyncPgConnection, prefix: &str) -> User {
+    let new_user = NewUser {
+        email: format!("{}_{}@example.com", prefix, Uuid::new_v4().simple()),
+        password_hash: "unused".to_string(),
+        full_name: "Test User".to_string(),
+        company_name: None,
+        email_verified: true,
+        subscription_tier: "free".to_string(),
+        onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+    };
+
+    User::create(conn, new_user).await.unwrap()
+}
+
+/// An organization created by `owner`, with `member` as a plain member
+pub async fn create_organization(
+    conn: &mut AsyncPgConnection,
+    owner: &User,
+    member: &User,
+) -> Organization {
+    use qck_backend_core::schema::{organization_members, organizations};
+
+    let organization = diesel::insert_into(organizations::table)
+        .values(NewOrganization {
+            name: "Test Org".to_string(),
+            created_by: owner.id,
+        })
+        .get_result::<Organization>(conn)
+        .await
+        .unwrap();
+
+    diesel::insert_into(organization_members::table)
+        .values(&vec![
+            NewOrganizationMember {
+                organization_id: organization.id,
+                user_id: owner.id,
+                role: OrgRole::Owner.as_str().to_string(),
+            },
+            NewOrganizationMember {
+                organization_id: organization.id,
+                user_id: member.id,
+                role: OrgRole::Member.as_str().to_string(),
+            },
+        ])
+        .execute(conn)
+        .await
+        .unwrap();
+
+    organization
+}
+
 /// Test setup for integration tests with real API
 pub struct TestSetup {
     pub api_port: u16,
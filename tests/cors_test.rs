@@ -0,0 +1,345 @@
+// CORS middleware tests
+// Covers preflight validation, credentialed origin reflection and per-route-group overrides
+
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    middleware,
+    response::Redirect,
+    routing::get,
+    Json, Router,
+};
+use qck_backend_core::app_config::{CorsConfig, CorsOrigin, CorsRouteOverride};
+use qck_backend_core::middleware::dynamic_cors_middleware;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const DASHBOARD: &str = "https://app.qck.sh";
+
+fn strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|v| v.to_string()).collect()
+}
+
+fn cors_config() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec![CorsOrigin::Exact(DASHBOARD.to_string())],
+        reflect_any_origin: false,
+        allow_credentials: true,
+        allowed_methods: strings(&["GET", "POST", "OPTIONS"]),
+        allowed_headers: strings(&["content-type", "authorization"]),
+        expose_headers: strings(&["X-RateLimit-Limit", "X-RateLimit-Remaining"]),
+        max_age_seconds: 600,
+        api: CorsRouteOverride {
+            enabled: true,
+            ..Default::default()
+        },
+        redirect: CorsRouteOverride {
+            enabled: true,
+            allowed_methods: Some(strings(&["GET", "HEAD"])),
+            ..Default::default()
+        },
+    }
+}
+
+fn app(config: CorsConfig) -> Router {
+    Router::new()
+        .route(
+            "/v1/links",
+            get(|| async { Json(serde_json::json!({ "links": [] })) }),
+        )
+        .route(
+            "/{short_code}",
+            get(|| async { Redirect::temporary("https://example.com/") }),
+        )
+        .layer(middleware::from_fn_with_state(
+            Arc::new(config),
+            dynamic_cors_middleware,
+        ))
+}
+
+fn preflight(uri: &str, origin: &str, method: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::OPTIONS)
+        .uri(uri)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, method)
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn get_with_origin(uri: &str, origin: &str) -> Request<Body> {
+    Request::builder()
+        .uri(uri)
+        .header(header::ORIGIN, origin)
+        .header(header::COOKIE, "session=abc")
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn header_value(response: &axum::response::Response, name: header::HeaderName) -> Option<&str> {
+    response.headers().get(name).map(|v| v.to_str().unwrap())
+}
+
+fn vary_values(response: &axum::response::Response) -> Vec<String> {
+    response
+        .headers()
+        .get_all(header::VARY)
+        .iter()
+        .map(|v| v.to_str().unwrap().to_lowercase())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_preflight_allowed() {
+    let response = app(cors_config())
+        .oneshot(preflight("/v1/links", DASHBOARD, "POST"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(DASHBOARD)
+    );
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_METHODS),
+        Some("GET, POST, OPTIONS")
+    );
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_MAX_AGE),
+        Some("600")
+    );
+    assert!(vary_values(&response).contains(&"access-control-request-method".to_string()));
+}
+
+#[tokio::test]
+async fn test_preflight_with_disallowed_method() {
+    let response = app(cors_config())
+        .oneshot(preflight("/v1/links", DASHBOARD, "DELETE"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_METHODS).is_none());
+}
+
+#[tokio::test]
+async fn test_preflight_with_disallowed_header() {
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/v1/links")
+        .header(header::ORIGIN, DASHBOARD)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .header(
+            header::ACCESS_CONTROL_REQUEST_HEADERS,
+            "content-type, x-custom",
+        )
+        .body(Body::empty())
+        .unwrap();
+
+    let response = app(cors_config()).oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_preflight_from_unknown_origin() {
+    let response = app(cors_config())
+        .oneshot(preflight("/v1/links", "https://evil.example", "GET"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+}
+
+#[tokio::test]
+async fn test_route_group_method_override() {
+    // POST is allowed on /v1 but the redirect group only allows GET and HEAD
+    let response = app(cors_config())
+        .oneshot(preflight("/abc123", DASHBOARD, "POST"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app(cors_config())
+        .oneshot(preflight("/abc123", DASHBOARD, "GET"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_METHODS),
+        Some("GET, HEAD")
+    );
+}
+
+#[tokio::test]
+async fn test_credentialed_request_with_reflected_origin() {
+    let config = CorsConfig {
+        allowed_origins: Vec::new(),
+        reflect_any_origin: true,
+        ..cors_config()
+    };
+    let origin = "https://preview-42.staging.qck.sh";
+
+    let response = app(config)
+        .oneshot(get_with_origin("/v1/links", origin))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    // Credentials require the concrete origin, never "*"
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(origin)
+    );
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+        Some("true")
+    );
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS),
+        Some("X-RateLimit-Limit, X-RateLimit-Remaining")
+    );
+    assert!(vary_values(&response).contains(&"origin".to_string()));
+}
+
+#[tokio::test]
+async fn test_unlisted_origin_gets_no_cors_headers() {
+    let response = app(cors_config())
+        .oneshot(get_with_origin("/v1/links", "https://evil.example"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS).is_none());
+    // Caches must still key on Origin
+    assert!(vary_values(&response).contains(&"origin".to_string()));
+}
+
+fn subdomain_config() -> CorsConfig {
+    CorsConfig {
+        allowed_origins: vec![
+            CorsOrigin::parse(DASHBOARD).unwrap(),
+            CorsOrigin::parse("https://*.qck.sh").unwrap(),
+            CorsOrigin::parse("https://*.vercel.app").unwrap(),
+        ],
+        ..cors_config()
+    }
+}
+
+#[tokio::test]
+async fn test_wildcard_subdomain_origin_is_reflected() {
+    let origin = "https://acme.qck.sh";
+
+    let response = app(subdomain_config())
+        .oneshot(preflight("/v1/links", origin, "POST"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NO_CONTENT);
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(origin)
+    );
+
+    let response = app(subdomain_config())
+        .oneshot(get_with_origin("/v1/links", origin))
+        .await
+        .unwrap();
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        Some(origin)
+    );
+    assert_eq!(
+        header_value(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+        Some("true")
+    );
+}
+
+#[tokio::test]
+async fn test_wildcard_subdomain_rejects_lookalikes() {
+    for origin in [
+        "https://evilqck.sh",
+        "https://qck.sh.evil.example",
+        "https://qck.sh",
+        "https://a.b.qck.sh",
+        "https://acme.qck.sh:8443",
+        "https://app-git-main-qck.vercel.app.evil.example",
+    ] {
+        let response = app(subdomain_config())
+            .oneshot(get_with_origin("/v1/links", origin))
+            .await
+            .unwrap();
+        assert!(
+            header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none(),
+            "{} should not be allowed",
+            origin
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_wildcard_subdomain_requires_matching_scheme() {
+    let response = app(subdomain_config())
+        .oneshot(preflight("/v1/links", "http://acme.qck.sh", "GET"))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+}
+
+#[test]
+fn test_origin_matching() {
+    let config = subdomain_config();
+
+    assert_eq!(config.allowed_origin(DASHBOARD), Some(DASHBOARD));
+    assert!(config
+        .allowed_origin("https://app-git-main-qck.vercel.app")
+        .is_some());
+    assert!(config.allowed_origin("https://ACME.qck.sh").is_some());
+    assert!(config.allowed_origin("https://-acme.qck.sh").is_none());
+    assert!(config.allowed_origin("https://.qck.sh").is_none());
+    assert!(config.allowed_origin("https://x@acme.qck.sh").is_none());
+    assert!(config.allowed_origin("null").is_none());
+
+    let with_port = CorsOrigin::parse("http://*.localhost:3000").unwrap();
+    assert!(with_port.matches("http://web.localhost:3000"));
+    assert!(!with_port.matches("http://web.localhost"));
+    assert!(!with_port.matches("http://web.localhost:3001"));
+}
+
+#[tokio::test]
+async fn test_exempt_route_group_is_untouched() {
+    let config = CorsConfig {
+        redirect: CorsRouteOverride::default(),
+        ..cors_config()
+    };
+
+    let response = app(config)
+        .oneshot(get_with_origin("/abc123", DASHBOARD))
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert!(header_value(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    assert!(vary_values(&response).is_empty());
+}
+
+#[test]
+fn test_policy_resolution() {
+    let config = cors_config();
+
+    let api = config.policy_for("/v1/links").unwrap();
+    assert_eq!(api.allowed_methods, config.allowed_methods.as_slice());
+    assert_eq!(api.max_age_seconds, 600);
+
+    // "/v1x" is a short code, not the API prefix
+    let redirect = config.policy_for("/v1x").unwrap();
+    assert_eq!(
+        redirect.allowed_methods,
+        strings(&["GET", "HEAD"]).as_slice()
+    );
+    assert_eq!(redirect.allowed_headers, config.allowed_headers.as_slice());
+}
@@ -0,0 +1,202 @@
+// CSRF protection for cookie-authenticated refresh and logout
+// Web clients must echo the csrf_token cookie in X-CSRF-Token; mobile clients sending
+// the refresh token in the JSON body are exempt
+
+use axum::http::StatusCode;
+use qck_backend_core::models::user::{NewUser, OnboardingStatus, User};
+use qck_backend_core::utils::hash_password;
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+
+mod common;
+use common::{setup_test_app, TestApp};
+
+const PASSWORD: &str = "SecureP@ssw0rd123!";
+
+/// Tokens and cookies handed out by a successful login
+struct Session {
+    access_token: String,
+    refresh_token: String,
+    csrf_token: String,
+}
+
+impl Session {
+    fn cookie_header(&self) -> String {
+        format!(
+            "refresh_token={}; csrf_token={}",
+            self.refresh_token, self.csrf_token
+        )
+    }
+}
+
+async fn create_test_user(app: &TestApp) -> User {
+    let mut conn = app.diesel_pool.get().await.unwrap();
+
+    let new_user = NewUser {
+        email: format!("csrf_{}@example.com", Uuid::new_v4().simple()),
+        password_hash: hash_password(PASSWORD).unwrap(),
+        full_name: "Test User".to_string(),
+        company_name: None,
+        email_verified: true,
+        subscription_tier: "free".to_string(),
+        onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+    };
+
+    User::create(&mut conn, new_user).await.unwrap()
+}
+
+async fn login(app: &TestApp, user: &User) -> Session {
+    let response = app
+        .post("/v1/auth/login")
+        .json(&json!({ "email": user.email, "password": PASSWORD }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let csrf_cookie = response
+        .set_cookie("csrf_token")
+        .expect("login sets a csrf_token cookie")
+        .to_string();
+    assert!(!csrf_cookie.contains("HttpOnly"));
+    assert!(response
+        .set_cookie("refresh_token")
+        .expect("login sets a refresh_token cookie")
+        .contains("HttpOnly"));
+
+    let csrf_token = response.cookie("csrf_token").unwrap();
+    let refresh_token = response.cookie("refresh_token").unwrap();
+    let body: serde_json::Value = response.json().await;
+
+    Session {
+        access_token: body["data"]["access_token"].as_str().unwrap().to_string(),
+        refresh_token,
+        csrf_token,
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_cookie_refresh_with_matching_csrf_header_rotates_token() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+
+    let response = app
+        .post("/v1/auth/refresh")
+        .header("cookie", &session.cookie_header())
+        .header("x-csrf-token", &session.csrf_token)
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let rotated = response
+        .cookie("csrf_token")
+        .expect("refresh sets a new csrf_token cookie");
+    assert_ne!(rotated, session.csrf_token);
+    assert!(response.cookie("refresh_token").is_some());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_cookie_refresh_without_csrf_header_is_rejected() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+
+    let response = app
+        .post("/v1/auth/refresh")
+        .header("cookie", &session.cookie_header())
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let body: serde_json::Value = response.json().await;
+    assert_eq!(body["code"], "CSRF_TOKEN_INVALID");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_cookie_refresh_with_wrong_csrf_header_is_rejected() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+
+    let response = app
+        .post("/v1/auth/refresh")
+        .header("cookie", &session.cookie_header())
+        .header("x-csrf-token", "forged-token")
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    // Without the csrf_token cookie, even a header is not enough
+    let response = app
+        .post("/v1/auth/refresh")
+        .header(
+            "cookie",
+            &format!("refresh_token={}", session.refresh_token),
+        )
+        .header("x-csrf-token", &session.csrf_token)
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_json_body_refresh_is_exempt() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+
+    let response = app
+        .post("/v1/auth/refresh")
+        .json(&json!({ "refresh_token": session.refresh_token }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_logout_with_refresh_cookie_requires_csrf_header() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+    let bearer = format!("Bearer {}", session.access_token);
+
+    let response = app
+        .post("/v1/auth/logout")
+        .header("authorization", &bearer)
+        .header("cookie", &session.cookie_header())
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .post("/v1/auth/logout")
+        .header("authorization", &bearer)
+        .header("cookie", &session.cookie_header())
+        .header("x-csrf-token", &session.csrf_token)
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.cookie("csrf_token").as_deref(), Some(""));
+    assert_eq!(response.cookie("refresh_token").as_deref(), Some(""));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_logout_without_cookies_is_exempt() {
+    let app = setup_test_app().await;
+    let user = create_test_user(&app).await;
+    let session = login(&app, &user).await;
+
+    let response = app
+        .post("/v1/auth/logout")
+        .header("authorization", &format!("Bearer {}", session.access_token))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
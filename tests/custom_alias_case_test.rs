@@ -0,0 +1,238 @@
+// Case-insensitive custom aliases
+// Run against the Postgres and Redis in .env.test; serial because the migration test drops and
+// recreates the alias indexes, and the click sync drains every pending counter
+
+use diesel::sql_types::{BigInt, Nullable, Text, Timestamptz, Uuid as SqlUuid};
+use diesel::QueryableByName;
+use diesel_async::{AsyncPgConnection, RunQueryDsl, SimpleAsyncConnection};
+use qck_backend_core::db::{create_diesel_pool, DieselDatabaseConfig, DieselPool};
+use qck_backend_core::models::link::CreateLinkRequest;
+use qck_backend_core::services::click_counter::click_counter_key;
+use qck_backend_core::services::link::{sync_click_counts_to_database, LinkService};
+use qck_backend_core::services::short_code::ShortCodeGenerator;
+use serial_test::serial;
+use uuid::Uuid;
+
+const MIGRATION_UP: &str =
+    include_str!("../migrations/diesel/2025-09-14-090000_normalize_custom_aliases/up.sql");
+const MIGRATION_DOWN: &str =
+    include_str!("../migrations/diesel/2025-09-14-090000_normalize_custom_aliases/down.sql");
+
+#[derive(QueryableByName)]
+struct AliasRow {
+    #[diesel(sql_type = Text)]
+    short_code: String,
+    #[diesel(sql_type = Nullable<Text>)]
+    custom_alias: Option<String>,
+}
+
+#[derive(QueryableByName)]
+struct ClickRow {
+    #[diesel(sql_type = BigInt)]
+    click_count: i64,
+}
+
+mod common;
+use common::{app_state, create_test_user};
+
+async fn diesel_pool() -> DieselPool {
+    dotenv::from_filename(".env.test").ok();
+    create_diesel_pool(DieselDatabaseConfig::default())
+        .await
+        .expect("Postgres must be available for alias tests")
+}
+
+/// Suffix unique to one test run, short enough for the 20-character short code column
+fn run_suffix() -> String {
+    Uuid::new_v4().simple().to_string()[..6].to_string()
+}
+
+async fn insert_link(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+    short_code: &str,
+    custom_alias: Option<&str>,
+    age_minutes: i64,
+) -> Result<Uuid, diesel::result::Error> {
+    #[derive(QueryableByName)]
+    struct IdRow {
+        #[diesel(sql_type = SqlUuid)]
+        id: Uuid,
+    }
+
+    let row: IdRow = diesel::sql_query(
+        "INSERT INTO links (user_id, short_code, original_url, custom_alias, created_at)
+         VALUES ($1, $2, 'https://example.com', $3, $4)
+         RETURNING id",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Text, _>(short_code)
+    .bind::<Nullable<Text>, _>(custom_alias)
+    .bind::<Timestamptz, _>(chrono::Utc::now() - chrono::Duration::minutes(age_minutes))
+    .get_result(conn)
+    .await?;
+    Ok(row.id)
+}
+
+async fn alias_row(conn: &mut AsyncPgConnection, id: Uuid) -> AliasRow {
+    diesel::sql_query("SELECT short_code, custom_alias FROM links WHERE id = $1")
+        .bind::<SqlUuid, _>(id)
+        .get_result(conn)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_mixed_case_alias_is_rejected_by_index() {
+    let pool = diesel_pool().await;
+    let mut conn = pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "alias").await;
+    let alias = format!("sale-{}", run_suffix());
+
+    insert_link(&mut conn, user.id, &alias, Some(&alias), 0)
+        .await
+        .unwrap();
+
+    let upper = alias.to_uppercase();
+    let duplicate = insert_link(&mut conn, user.id, &upper, Some(&upper), 0).await;
+    assert!(matches!(
+        duplicate,
+        Err(diesel::result::Error::DatabaseError(
+            diesel::result::DatabaseErrorKind::UniqueViolation,
+            _
+        ))
+    ));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_code_uniqueness_ignores_alias_case() {
+    let pool = diesel_pool().await;
+    let mut conn = pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "alias").await;
+    let suffix = run_suffix();
+    let alias = format!("promo-{}", suffix);
+    let generated = format!("Gen{}", suffix);
+
+    insert_link(&mut conn, user.id, &alias, Some(&alias), 0)
+        .await
+        .unwrap();
+    insert_link(&mut conn, user.id, &generated, None, 0)
+        .await
+        .unwrap();
+
+    let generator = ShortCodeGenerator::new(pool.clone());
+    // Any casing of an alias is taken
+    assert!(!generator
+        .is_code_unique(&format!("PROMO-{}", suffix))
+        .await
+        .unwrap());
+    // Generated codes stay case-sensitive
+    assert!(!generator.is_code_unique(&generated).await.unwrap());
+    assert!(generator
+        .is_code_unique(&generated.to_lowercase())
+        .await
+        .unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_alias_matching_generated_code_in_other_case_is_taken() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "alias").await;
+    let generated = format!("Gen{}", run_suffix());
+    insert_link(&mut conn, user.id, &generated, None, 0)
+        .await
+        .unwrap();
+
+    let request: CreateLinkRequest = serde_json::from_value(serde_json::json!({
+        "url": "https://example.com/alias-case",
+        "custom_alias": generated.to_lowercase(),
+    }))
+    .unwrap();
+    let response = LinkService::new(&state)
+        .with_local_scans_only()
+        .validate_link(&user, request, false)
+        .await
+        .unwrap();
+    assert!(!response.alias.unwrap().available);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_click_sync_counts_exact_short_code_over_alias() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "alias").await;
+    let suffix = run_suffix();
+    // Written before aliases were checked against generated codes in any case
+    let generated = format!("Hot{}", suffix);
+    let alias = generated.to_lowercase();
+    let generated_id = insert_link(&mut conn, user.id, &generated, None, 0)
+        .await
+        .unwrap();
+    let alias_id = insert_link(&mut conn, user.id, &alias, Some(&alias), 0)
+        .await
+        .unwrap();
+
+    state
+        .redis_pool
+        .set_with_expiry(&click_counter_key(&generated), "3".to_string(), 60)
+        .await
+        .unwrap();
+    sync_click_counts_to_database(&state.redis_pool, &state.diesel_pool)
+        .await
+        .unwrap();
+
+    let clicks = |id: Uuid| {
+        diesel::sql_query("SELECT click_count FROM links WHERE id = $1").bind::<SqlUuid, _>(id)
+    };
+    let generated_row: ClickRow = clicks(generated_id).get_result(&mut conn).await.unwrap();
+    let alias_row: ClickRow = clicks(alias_id).get_result(&mut conn).await.unwrap();
+    assert_eq!(generated_row.click_count, 3);
+    assert_eq!(alias_row.click_count, 0);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_migration_normalizes_existing_duplicates() {
+    let pool = diesel_pool().await;
+    let mut conn = pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "alias").await;
+    let suffix = run_suffix();
+
+    // Rows written before the migration, when aliases were stored as given
+    conn.batch_execute(MIGRATION_DOWN).await.unwrap();
+    let oldest = format!("Sale-{}", suffix);
+    let newer = format!("sale-{}", suffix);
+    let padded = format!("Trail-{} ", suffix);
+    let oldest_id = insert_link(&mut conn, user.id, &oldest, Some(&oldest), 10)
+        .await
+        .unwrap();
+    let newer_id = insert_link(&mut conn, user.id, &newer, Some(&newer), 5)
+        .await
+        .unwrap();
+    let padded_id = insert_link(&mut conn, user.id, &padded, Some(&padded), 0)
+        .await
+        .unwrap();
+
+    conn.batch_execute(MIGRATION_UP).await.unwrap();
+
+    // The oldest link keeps the alias; its short code stays mixed-case because the newer
+    // link already holds the lowercase form
+    let kept = alias_row(&mut conn, oldest_id).await;
+    assert_eq!(kept.custom_alias.as_deref(), Some(newer.as_str()));
+    assert_eq!(kept.short_code, oldest);
+
+    // The newer duplicate is no longer an alias but still resolves by its exact code
+    let demoted = alias_row(&mut conn, newer_id).await;
+    assert_eq!(demoted.custom_alias, None);
+    assert_eq!(demoted.short_code, newer);
+
+    let trimmed = format!("trail-{}", suffix);
+    let normalized = alias_row(&mut conn, padded_id).await;
+    assert_eq!(normalized.custom_alias.as_deref(), Some(trimmed.as_str()));
+    assert_eq!(normalized.short_code, trimmed);
+}
@@ -0,0 +1,162 @@
+// Distributed lock and exclusive background task tests
+// Run against the Redis in .env.test; every test uses its own lock names
+
+use qck_backend_core::db::{RedisConfig, RedisPool};
+use qck_backend_core::services::background_tasks::BackgroundTaskRegistry;
+use qck_backend_core::utils::distributed_lock::DistributedLock;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+async fn redis_pool() -> RedisPool {
+    dotenv::from_filename(".env.test").ok();
+    RedisPool::new(RedisConfig::from_env())
+        .await
+        .expect("Redis must be available for distributed lock tests")
+}
+
+fn lock_name() -> String {
+    format!("test:{}", Uuid::new_v4().simple())
+}
+
+#[tokio::test]
+async fn test_lock_is_exclusive_until_released() {
+    let pool = redis_pool().await;
+    let name = lock_name();
+    let ttl = Duration::from_secs(10);
+    let first = DistributedLock::new(pool.clone(), &name, "instance-a", ttl);
+    let second = DistributedLock::new(pool.clone(), &name, "instance-b", ttl);
+
+    assert!(first.try_acquire().await.unwrap());
+    assert!(!second.try_acquire().await.unwrap());
+    // Acquiring again as the holder renews instead of failing
+    assert!(first.try_acquire().await.unwrap());
+    assert_eq!(
+        DistributedLock::holder(&pool, &name)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("instance-a")
+    );
+
+    // Only the owner can release
+    assert!(!second.release().await.unwrap());
+    assert!(first.release().await.unwrap());
+    assert!(second.try_acquire().await.unwrap());
+    assert_eq!(
+        DistributedLock::holder(&pool, &name)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("instance-b")
+    );
+    second.release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_expired_lock_is_taken_over_and_cannot_be_renewed() {
+    let pool = redis_pool().await;
+    let name = lock_name();
+    let ttl = Duration::from_millis(300);
+    let first = DistributedLock::new(pool.clone(), &name, "instance-a", ttl);
+    let second = DistributedLock::new(pool.clone(), &name, "instance-b", ttl);
+
+    assert!(first.try_acquire().await.unwrap());
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert!(second.try_acquire().await.unwrap());
+    // The previous owner lost it and must not extend or delete the new owner's lock
+    assert!(!first.renew().await.unwrap());
+    assert!(!first.is_held());
+    assert!(!first.release().await.unwrap());
+    assert_eq!(
+        DistributedLock::holder(&pool, &name)
+            .await
+            .unwrap()
+            .as_deref(),
+        Some("instance-b")
+    );
+    second.release().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_renewal_keeps_lock_past_ttl() {
+    let pool = redis_pool().await;
+    let name = lock_name();
+    let ttl = Duration::from_millis(400);
+    let holder = DistributedLock::new(pool.clone(), &name, "instance-a", ttl);
+    let contender = DistributedLock::new(pool.clone(), &name, "instance-b", ttl);
+
+    assert!(holder.try_acquire().await.unwrap());
+    for _ in 0..5 {
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(holder.renew().await.unwrap());
+        assert!(!contender.try_acquire().await.unwrap());
+    }
+    holder.release().await.unwrap();
+    assert_eq!(DistributedLock::holder(&pool, &name).await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_exclusive_task_runs_on_one_instance() {
+    let pool = redis_pool().await;
+    // Task names are 'static in the registry
+    let task: &'static str = Box::leak(lock_name().into_boxed_str());
+
+    let mut registries = Vec::new();
+    let mut runs = Vec::new();
+    for instance in ["instance-a", "instance-b"] {
+        let registry = Arc::new(BackgroundTaskRegistry::new(instance.to_string()));
+        let count = Arc::new(AtomicU32::new(0));
+        let task_count = Arc::clone(&count);
+        registry.spawn_exclusive(task, pool.clone(), move |reporter| {
+            let count = Arc::clone(&task_count);
+            async move {
+                loop {
+                    if reporter.is_leader().await {
+                        count.fetch_add(1, Ordering::SeqCst);
+                        reporter.success();
+                    }
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                }
+            }
+        });
+        registries.push(registry);
+        runs.push(count);
+    }
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    let counts: Vec<u32> = runs
+        .iter()
+        .map(|count| count.load(Ordering::SeqCst))
+        .collect();
+    assert_eq!(
+        counts.iter().filter(|runs| **runs > 0).count(),
+        1,
+        "iterations per instance: {:?}",
+        counts
+    );
+
+    let leader = if counts[0] > 0 { 0 } else { 1 };
+    let status = registries[leader].status(task).unwrap();
+    assert!(status.exclusive && status.holds_lock);
+    assert!(!registries[1 - leader].status(task).unwrap().holds_lock);
+
+    // The metrics endpoint reports which instance holds the lock
+    let metrics = registries[1 - leader].metrics(&pool).await;
+    let holder = metrics
+        .lock_holders
+        .iter()
+        .find(|holder| holder.task == task)
+        .unwrap();
+    assert_eq!(
+        holder.instance_id.as_deref(),
+        Some(registries[leader].instance_id())
+    );
+
+    for registry in &registries {
+        registry.release_locks().await;
+    }
+}
@@ -0,0 +1,163 @@
+// IDN / punycode normalization tests
+// Cyrillic look-alike domains must be caught whether they arrive raw or punycoded
+
+use chrono::Utc;
+use qck_backend_core::models::link::Link;
+use qck_backend_core::utils::security_scanner::{
+    DomainSecurityService, HomographDetector, ThreatType,
+};
+use qck_backend_core::utils::{host_to_unicode, NormalizedUrl};
+use url::Url;
+use uuid::Uuid;
+
+// "аpple.com" with a Cyrillic "а" (U+0430)
+const CYRILLIC_APPLE: &str = "\u{0430}pple.com";
+const CYRILLIC_APPLE_PUNYCODE: &str = "xn--pple-43d.com";
+
+// "раураl.com" with Cyrillic "р", "а", "у"
+const CYRILLIC_PAYPAL: &str = "\u{0440}\u{0430}\u{0443}\u{0440}\u{0430}l.com";
+const CYRILLIC_PAYPAL_PUNYCODE: &str = "xn--l-7sba6dbr.com";
+
+fn link_to(url: &str) -> Link {
+    let now = Utc::now();
+    Link {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        short_code: "idn123".to_string(),
+        original_url: url.to_string(),
+        title: None,
+        description: None,
+        tags: None,
+        custom_alias: None,
+        is_active: true,
+        expires_at: None,
+        password_hash: None,
+        last_accessed_at: None,
+        og_image: None,
+        favicon_url: None,
+        processing_status: "completed".to_string(),
+        metadata_extracted_at: None,
+        utm_source: None,
+        utm_medium: None,
+        utm_campaign: None,
+        utm_term: None,
+        utm_content: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+        last_scanned_at: None,
+        threat_score: None,
+        quarantined: false,
+        quarantined_at: None,
+        quarantine_reason: None,
+        appeal_status: None,
+        appeal_message: None,
+        appealed_at: None,
+        risk_level: None,
+        threats_detected: None,
+        scan_warnings: None,
+        tracking_mode: "full".to_string(),
+        organization_id: None,
+        click_count: 0,
+        notes: None,
+        deactivation_reason: None,
+        path_passthrough: false,
+        allowed_referrers: Vec::new(),
+        blocked_referrers: Vec::new(),
+        referrer_strict: false,
+        expiry_policy: "fixed".to_string(),
+        sliding_window_days: None,
+    }
+}
+
+#[test]
+fn test_normalized_url_stores_punycode_and_decodes_for_analysis() {
+    for (raw, punycode) in [
+        (CYRILLIC_APPLE, CYRILLIC_APPLE_PUNYCODE),
+        (CYRILLIC_PAYPAL, CYRILLIC_PAYPAL_PUNYCODE),
+    ] {
+        let from_raw = NormalizedUrl::from(Url::parse(&format!("https://{}/", raw)).unwrap());
+        let from_puny = NormalizedUrl::from(Url::parse(&format!("https://{}/", punycode)).unwrap());
+
+        // Both inputs collapse to the same canonical ASCII form
+        assert_eq!(from_raw.domain, punycode);
+        assert_eq!(from_puny.domain, punycode);
+        assert_eq!(from_raw.normalized, from_puny.normalized);
+
+        // And both decode to the Unicode host for analysis
+        assert_eq!(from_raw.unicode_domain, raw);
+        assert_eq!(from_puny.unicode_domain, raw);
+    }
+}
+
+#[test]
+fn test_host_to_unicode_leaves_ascii_and_invalid_hosts_alone() {
+    assert_eq!(host_to_unicode("example.com"), "example.com");
+    assert_eq!(host_to_unicode("xn--e1afmkfd.xn--p1ai"), "пример.рф");
+    // Not valid punycode - returned unchanged rather than mangled
+    assert_eq!(host_to_unicode("xn--.com"), "xn--.com");
+}
+
+#[test]
+fn test_homograph_detected_in_raw_and_punycoded_form() {
+    let detector = HomographDetector::new();
+
+    for domain in [
+        CYRILLIC_APPLE,
+        CYRILLIC_APPLE_PUNYCODE,
+        CYRILLIC_PAYPAL,
+        CYRILLIC_PAYPAL_PUNYCODE,
+    ] {
+        assert!(
+            detector.has_homograph_attack(domain),
+            "{} should be flagged",
+            domain
+        );
+        assert!(HomographDetector::has_mixed_scripts(domain));
+    }
+
+    assert!(!detector.has_homograph_attack("apple.com"));
+}
+
+#[test]
+fn test_single_script_idn_is_not_flagged() {
+    // A fully Cyrillic domain is a legitimate IDN, not a look-alike
+    assert!(!HomographDetector::has_mixed_scripts("пример.рф"));
+    assert!(!HomographDetector::has_mixed_scripts(
+        "xn--e1afmkfd.xn--p1ai"
+    ));
+    // Scripts are judged per label, so a Cyrillic label under .com is fine too
+    assert!(!HomographDetector::has_mixed_scripts("xn--e1afmkfd.com"));
+}
+
+#[tokio::test]
+async fn test_domain_reputation_flags_punycoded_homograph() {
+    let service = DomainSecurityService::new();
+    let result = service
+        .check_domain_reputation(CYRILLIC_APPLE_PUNYCODE)
+        .await
+        .unwrap();
+
+    assert!(result
+        .threats_detected
+        .iter()
+        .any(|t| matches!(t, ThreatType::HomographAttack)));
+}
+
+#[test]
+fn test_link_response_display_host() {
+    let response = link_to(&format!("https://{}/login", CYRILLIC_APPLE_PUNYCODE))
+        .to_response("https://qck.sh");
+    assert_eq!(response.display_host, CYRILLIC_APPLE);
+    assert!(response.display_host_warning);
+    // The stored destination stays in canonical punycode form
+    assert!(response.original_url.contains(CYRILLIC_APPLE_PUNYCODE));
+
+    let plain = link_to("https://example.com/").to_response("https://qck.sh");
+    assert_eq!(plain.display_host, "example.com");
+    assert!(!plain.display_host_warning);
+
+    let idn = link_to("https://xn--e1afmkfd.xn--p1ai/").to_response("https://qck.sh");
+    assert_eq!(idn.display_host, "пример.рф");
+    assert!(!idn.display_host_warning);
+}
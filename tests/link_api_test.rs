@@ -1,8 +1,10 @@
 // DEV-105: Link Creation API Tests
 // Testing the complete link management flow
 
-use qck_backend_core::models::link::{CreateLinkRequest, LinkMetadata, LinkPagination};
-use qck_backend_core::utils::url_validator::{SecurityScanner, UrlValidator};
+use qck_backend_core::models::link::{
+    validate_tags, CreateLinkRequest, LinkMetadata, LinkPagination,
+};
+use qck_backend_core::utils::url_validator::{SecurityScanner, UrlValidator, MAX_URL_LENGTH};
 
 #[tokio::test]
 async fn test_url_validation() {
@@ -122,27 +124,27 @@ fn test_custom_validation() {
         password: None,
     };
 
-    assert!(request.validate_custom().is_err());
+    assert!(request.validate_custom(MAX_URL_LENGTH).is_err());
 
     // Add password, should pass
     request.password = Some("secret123".to_string());
-    assert!(request.validate_custom().is_ok());
+    assert!(request.validate_custom(MAX_URL_LENGTH).is_ok());
 
     // Test expired date
     request.expires_at = Some(Utc::now() - Duration::days(1));
-    assert!(request.validate_custom().is_err());
+    assert!(request.validate_custom(MAX_URL_LENGTH).is_err());
 
     // Future date should pass
     request.expires_at = Some(Utc::now() + Duration::days(7));
-    assert!(request.validate_custom().is_ok());
+    assert!(request.validate_custom(MAX_URL_LENGTH).is_ok());
 
     // Test too many tags
-    request.tags = (0..15).map(|i| format!("tag{}", i)).collect();
-    assert!(request.validate_custom().is_err());
+    request.tags = (0..21).map(|i| format!("tag{}", i)).collect();
+    assert!(validate_tags(&request.tags).is_err());
 
-    // 10 tags should be fine
-    request.tags = (0..10).map(|i| format!("tag{}", i)).collect();
-    assert!(request.validate_custom().is_ok());
+    // 20 tags should be fine
+    request.tags = (0..20).map(|i| format!("tag{}", i)).collect();
+    assert!(validate_tags(&request.tags).is_ok());
 }
 
 #[test]
@@ -0,0 +1,152 @@
+// Per-ID outcomes of bulk status updates
+// Run against the Postgres and Redis in .env.test
+
+use chrono::Utc;
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::app::AppState;
+use qck_backend_core::models::link::{BulkStatusItem, BulkStatusOutcome, BulkStatusResponse, Link};
+use qck_backend_core::models::organization::Organization;
+use qck_backend_core::models::user::User;
+use qck_backend_core::services::link::LinkService;
+use uuid::Uuid;
+
+mod common;
+use common::{app_state, create_organization, create_test_user};
+
+async fn create_link(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+    is_active: bool,
+) -> Link {
+    use qck_backend_core::schema::links::dsl;
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    diesel::insert_into(dsl::links)
+        .values((
+            dsl::user_id.eq(user_id),
+            dsl::organization_id.eq(organization_id),
+            dsl::short_code.eq(format!("bs{}", &suffix[..8])),
+            dsl::original_url.eq("https://example.com/bulk-status"),
+            dsl::is_active.eq(is_active),
+        ))
+        .get_result::<Link>(conn)
+        .await
+        .unwrap()
+}
+
+async fn cleanup(state: &AppState, organization: &Organization, users: &[&User]) {
+    use qck_backend_core::schema::{links, organizations, users};
+
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    for user in users {
+        let _ = diesel::delete(links::table.filter(links::user_id.eq(user.id)))
+            .execute(&mut conn)
+            .await;
+    }
+    let _ = diesel::delete(organizations::table.find(organization.id))
+        .execute(&mut conn)
+        .await;
+    for user in users {
+        let _ = diesel::delete(users::table.find(user.id))
+            .execute(&mut conn)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_mixed_batch_reports_each_id() {
+    use qck_backend_core::schema::links::dsl;
+
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "bulkstatus").await;
+    let stranger = create_test_user(&mut conn, "bulkstatus").await;
+    let organization = create_organization(&mut conn, &stranger, &user).await;
+
+    let active = create_link(&mut conn, user.id, None, true).await;
+    let inactive = create_link(&mut conn, user.id, None, false).await;
+    let deleted = create_link(&mut conn, user.id, None, true).await;
+    diesel::update(dsl::links.find(deleted.id))
+        .set(dsl::deleted_at.eq(Some(Utc::now())))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    let foreign = create_link(&mut conn, stranger.id, None, true).await;
+    let shared = create_link(&mut conn, stranger.id, Some(organization.id), true).await;
+    let missing = Uuid::new_v4();
+
+    let service = LinkService::new(&state);
+    let results = service
+        .bulk_update_status(
+            &user,
+            vec![
+                active.id,
+                inactive.id,
+                deleted.id,
+                foreign.id,
+                shared.id,
+                missing,
+                active.id,
+            ],
+            false,
+        )
+        .await
+        .unwrap();
+
+    let item = |id, outcome| BulkStatusItem { id, outcome };
+    assert_eq!(
+        results,
+        vec![
+            item(active.id, BulkStatusOutcome::Updated),
+            item(inactive.id, BulkStatusOutcome::AlreadyInState),
+            item(deleted.id, BulkStatusOutcome::NotFound),
+            // Another user's link reads the same as one that never existed
+            item(foreign.id, BulkStatusOutcome::NotFound),
+            item(shared.id, BulkStatusOutcome::Forbidden),
+            item(missing, BulkStatusOutcome::NotFound),
+        ]
+    );
+
+    let response = BulkStatusResponse::from(results);
+    assert_eq!((response.updated, response.skipped), (1, 5));
+    assert_eq!(
+        serde_json::to_value(&response.results[1]).unwrap()["outcome"],
+        "already_in_state"
+    );
+
+    // Only the caller's own live link changed
+    let still_active: Vec<Uuid> = dsl::links
+        .filter(dsl::id.eq_any([active.id, deleted.id, foreign.id, shared.id]))
+        .filter(dsl::is_active.eq(true))
+        .select(dsl::id)
+        .load(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(still_active.len(), 3);
+    assert!(!still_active.contains(&active.id));
+    drop(conn);
+
+    cleanup(&state, &organization, &[&user, &stranger]).await;
+}
+
+#[tokio::test]
+async fn test_empty_batch_reports_nothing() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "bulkstatus").await;
+    drop(conn);
+
+    let service = LinkService::new(&state);
+    assert!(service
+        .bulk_update_status(&user, Vec::new(), true)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let _ = diesel::delete(qck_backend_core::schema::users::table.find(user.id))
+        .execute(&mut conn)
+        .await;
+}
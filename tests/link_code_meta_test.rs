@@ -0,0 +1,158 @@
+// Short code → link id index (`code:{code}`) kept next to the link and redirect caches
+// Run against the Postgres and Redis in .env.test
+
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::app::AppState;
+use qck_backend_core::models::link::{CodeMeta, Link, RedirectRecord};
+use qck_backend_core::services::link::{
+    cache_keys_for_code, cache_link, cached_code_meta, invalidate_code_cache, LinkService,
+};
+use uuid::Uuid;
+
+mod common;
+use common::{app_state, create_test_user};
+
+/// A fresh active link with a custom alias, loaded back as a `Link`
+async fn create_link(conn: &mut AsyncPgConnection, user_id: Uuid) -> Link {
+    use qck_backend_core::schema::links::dsl;
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    diesel::insert_into(dsl::links)
+        .values((
+            dsl::user_id.eq(user_id),
+            dsl::short_code.eq(format!("cm{}", &suffix[..8])),
+            dsl::custom_alias.eq(format!("meta-{}", &suffix[..12])),
+            dsl::original_url.eq("https://example.com/code-meta"),
+        ))
+        .get_result::<Link>(conn)
+        .await
+        .unwrap()
+}
+
+async fn cleanup(state: &AppState, link: &Link) {
+    use qck_backend_core::schema::{links, users};
+
+    for code in std::iter::once(&link.short_code).chain(link.custom_alias.as_ref()) {
+        let _ = invalidate_code_cache(&state.redis_pool, code).await;
+    }
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let _ = diesel::delete(links::table.find(link.id))
+        .execute(&mut conn)
+        .await;
+    let _ = diesel::delete(users::table.find(link.user_id))
+        .execute(&mut conn)
+        .await;
+}
+
+#[tokio::test]
+async fn test_cache_link_writes_code_meta_for_code_and_alias() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "codemeta").await;
+    let link = create_link(&mut conn, user.id).await;
+    drop(conn);
+
+    cache_link(&state.redis_pool, &link).await.unwrap();
+
+    let expected = CodeMeta {
+        link_id: link.id,
+        user_id: user.id,
+        flags: RedirectRecord::ACTIVE,
+    };
+    for code in [&link.short_code, link.custom_alias.as_ref().unwrap()] {
+        assert_eq!(
+            cached_code_meta(&state.redis_pool, code).await.unwrap(),
+            Some(expected),
+            "{code}"
+        );
+    }
+
+    cleanup(&state, &link).await;
+}
+
+#[tokio::test]
+async fn test_partial_invalidations() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "codemeta").await;
+    let link = create_link(&mut conn, user.id).await;
+    drop(conn);
+
+    let [link_key, redirect_key, code_key] = cache_keys_for_code(&link.short_code);
+    cache_link(&state.redis_pool, &link).await.unwrap();
+
+    // Losing the full link entry leaves the index usable on its own
+    state.redis_pool.del(&link_key).await.unwrap();
+    assert_eq!(
+        cached_code_meta(&state.redis_pool, &link.short_code)
+            .await
+            .unwrap()
+            .map(|meta| meta.link_id),
+        Some(link.id)
+    );
+
+    // Losing only the index is a miss, and resolving it writes every entry again
+    state.redis_pool.del(&code_key).await.unwrap();
+    assert_eq!(
+        cached_code_meta(&state.redis_pool, &link.short_code)
+            .await
+            .unwrap(),
+        None
+    );
+    let meta = LinkService::new(&state)
+        .resolve_code_meta(&link.short_code)
+        .await
+        .unwrap();
+    assert_eq!(meta.link_id, link.id);
+    assert!(state
+        .redis_pool
+        .get::<String>(&link_key)
+        .await
+        .unwrap()
+        .is_some());
+    assert!(cached_code_meta(&state.redis_pool, &link.short_code)
+        .await
+        .unwrap()
+        .is_some());
+
+    // A hash missing fields is never trusted
+    state.redis_pool.del(&code_key).await.unwrap();
+    let mut redis_conn = state.redis_pool.get_connection().await.unwrap();
+    redis::cmd("HSET")
+        .arg(&code_key)
+        .arg("id")
+        .arg(link.id.to_string())
+        .query_async::<()>(&mut redis_conn)
+        .await
+        .unwrap();
+    assert_eq!(
+        cached_code_meta(&state.redis_pool, &link.short_code)
+            .await
+            .unwrap(),
+        None
+    );
+
+    // Invalidating a code drops all three entries together; the alias is untouched
+    cache_link(&state.redis_pool, &link).await.unwrap();
+    invalidate_code_cache(&state.redis_pool, &link.short_code)
+        .await
+        .unwrap();
+    for key in [&link_key, &redirect_key] {
+        assert_eq!(state.redis_pool.get::<String>(key).await.unwrap(), None);
+    }
+    assert_eq!(
+        cached_code_meta(&state.redis_pool, &link.short_code)
+            .await
+            .unwrap(),
+        None
+    );
+    assert!(
+        cached_code_meta(&state.redis_pool, link.custom_alias.as_ref().unwrap())
+            .await
+            .unwrap()
+            .is_some()
+    );
+
+    cleanup(&state, &link).await;
+}
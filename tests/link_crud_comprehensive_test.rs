@@ -18,6 +18,7 @@ type UpdateLinkRequest = qck_backend_core::models::link::UpdateLinkRequest;
 type LinkFilter = qck_backend_core::models::link::LinkFilter;
 type LinkPagination = qck_backend_core::models::link::LinkPagination;
 type LinkService = qck_backend_core::services::link::LinkService;
+type BulkStatusOutcome = qck_backend_core::models::link::BulkStatusOutcome;
 
 async fn setup_test_state() -> AppState {
     // Load environment for testing from parent directory
@@ -323,7 +324,20 @@ async fn test_bulk_update_status() {
         .bulk_update_status(&user, link_ids.clone(), false)
         .await;
     assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 5);
+    let results = result.unwrap();
+    assert_eq!(results.len(), 5);
+    assert!(results
+        .iter()
+        .all(|item| item.outcome == BulkStatusOutcome::Updated));
+
+    // Repeating the update changes nothing
+    let results = service
+        .bulk_update_status(&user, link_ids.clone(), false)
+        .await
+        .unwrap();
+    assert!(results
+        .iter()
+        .all(|item| item.outcome == BulkStatusOutcome::AlreadyInState));
 
     // Verify all links are inactive
     for link_id in &link_ids {
@@ -343,7 +357,10 @@ async fn test_bulk_update_status() {
         .bulk_update_status(&user, link_ids.clone(), true)
         .await;
     assert!(result.is_ok());
-    assert_eq!(result.unwrap(), 5);
+    assert!(result
+        .unwrap()
+        .iter()
+        .all(|item| item.outcome == BulkStatusOutcome::Updated));
 
     // Verify all links are active again
     for link_id in &link_ids {
@@ -541,7 +558,7 @@ async fn test_click_count_sync() {
 }
 
 // =============================================================================
-// PERMANENT DELETE TEST (Admin Only)
+// PERMANENT DELETE TEST
 // =============================================================================
 
 #[tokio::test]
@@ -565,9 +582,8 @@ async fn test_permanent_delete() {
     let created = service.create_link(&user, request).await.unwrap();
     let link_id = created.id;
 
-    // Permanently delete (simulating admin action)
-    let admin_id = Uuid::new_v4(); // Mock admin ID
-    let result = service.permanent_delete_link(link_id, admin_id).await;
+    // Permanently delete as the owner
+    let result = service.permanent_delete_link(&user, link_id).await;
     assert!(result.is_ok());
 
     // Verify link is completely gone from database
@@ -0,0 +1,113 @@
+// Expired link sweep
+// Run against the Postgres and Redis in .env.test; serial because the sweep takes every
+// expired link, not just this test's
+
+use diesel::sql_types::{Bool, Nullable, Text, Timestamptz, Uuid as SqlUuid};
+use diesel::QueryableByName;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::db::{
+    create_diesel_pool, DieselDatabaseConfig, DieselPool, RedisConfig, RedisPool,
+};
+use qck_backend_core::models::link::DEACTIVATION_REASON_EXPIRED;
+use qck_backend_core::services::link::deactivate_expired_links;
+use serial_test::serial;
+use uuid::Uuid;
+
+#[derive(QueryableByName)]
+struct StatusRow {
+    #[diesel(sql_type = Bool)]
+    is_active: bool,
+    #[diesel(sql_type = Nullable<Text>)]
+    deactivation_reason: Option<String>,
+}
+
+mod common;
+use common::create_test_user;
+
+async fn pools() -> (DieselPool, RedisPool) {
+    dotenv::from_filename(".env.test").ok();
+    let diesel_pool = create_diesel_pool(DieselDatabaseConfig::default())
+        .await
+        .expect("Postgres must be available for expiry sweep tests");
+    let redis_pool = RedisPool::new(RedisConfig::from_env())
+        .await
+        .expect("Redis must be available for expiry sweep tests");
+    (diesel_pool, redis_pool)
+}
+
+async fn insert_link(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+    short_code: &str,
+    expires_in_minutes: i64,
+) -> Uuid {
+    #[derive(QueryableByName)]
+    struct IdRow {
+        #[diesel(sql_type = SqlUuid)]
+        id: Uuid,
+    }
+
+    let row: IdRow = diesel::sql_query(
+        "INSERT INTO links (user_id, short_code, original_url, expires_at)
+         VALUES ($1, $2, 'https://example.com', $3)
+         RETURNING id",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Text, _>(short_code)
+    .bind::<Timestamptz, _>(chrono::Utc::now() + chrono::Duration::minutes(expires_in_minutes))
+    .get_result(conn)
+    .await
+    .unwrap();
+    row.id
+}
+
+async fn status(conn: &mut AsyncPgConnection, id: Uuid) -> StatusRow {
+    diesel::sql_query("SELECT is_active, deactivation_reason FROM links WHERE id = $1")
+        .bind::<SqlUuid, _>(id)
+        .get_result(conn)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_sweep_deactivates_expired_links_and_drops_cache() {
+    let (diesel_pool, redis_pool) = pools().await;
+    let mut conn = diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "expiry").await;
+    let run = &Uuid::new_v4().simple().to_string()[..8];
+    let expired_code = format!("ex{}", run);
+    let live_code = format!("lv{}", run);
+
+    let expired_id = insert_link(&mut conn, user.id, &expired_code, -5).await;
+    let live_id = insert_link(&mut conn, user.id, &live_code, 60).await;
+    redis_pool
+        .set_with_expiry(
+            &format!("redirect:{}", expired_code),
+            "cached".to_string(),
+            3600,
+        )
+        .await
+        .unwrap();
+
+    let swept = deactivate_expired_links(&diesel_pool, &redis_pool)
+        .await
+        .unwrap();
+    assert!(swept >= 1);
+
+    let expired = status(&mut conn, expired_id).await;
+    assert!(!expired.is_active);
+    assert_eq!(
+        expired.deactivation_reason.as_deref(),
+        Some(DEACTIVATION_REASON_EXPIRED)
+    );
+    let cached: Option<String> = redis_pool
+        .get(&format!("redirect:{}", expired_code))
+        .await
+        .unwrap();
+    assert_eq!(cached, None);
+
+    let live = status(&mut conn, live_id).await;
+    assert!(live.is_active);
+    assert_eq!(live.deactivation_reason, None);
+}
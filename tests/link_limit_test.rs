@@ -0,0 +1,131 @@
+// MAX_LINKS_PER_USER: cached link counts, the 403 past the cap and the 80% warning
+// Run against the Postgres and Redis in .env.test
+
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::app::AppState;
+use qck_backend_core::models::link::{CreateLinkRequest, Link};
+use qck_backend_core::models::user::User;
+use qck_backend_core::services::link::{link_count_cache_key, LinkService, LinkUsage};
+use qck_backend_core::utils::service_error::ServiceError;
+use std::sync::Arc;
+use uuid::Uuid;
+
+mod common;
+use common::create_test_user;
+
+/// App state with MAX_LINKS_PER_USER set to `limit`
+async fn app_state(limit: u64) -> AppState {
+    let mut state = common::app_state().await;
+    let mut config = (*state.config).clone();
+    config.max_links_per_user = limit;
+    state.config = Arc::new(config);
+    state
+}
+
+async fn create_link(conn: &mut AsyncPgConnection, user_id: Uuid) -> Link {
+    use qck_backend_core::schema::links::dsl;
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    diesel::insert_into(dsl::links)
+        .values((
+            dsl::user_id.eq(user_id),
+            dsl::short_code.eq(format!("ll{}", &suffix[..8])),
+            dsl::original_url.eq("https://example.com/link-limit"),
+        ))
+        .get_result::<Link>(conn)
+        .await
+        .unwrap()
+}
+
+fn create_request() -> CreateLinkRequest {
+    serde_json::from_value(serde_json::json!({ "url": "https://example.com/over-the-limit" }))
+        .unwrap()
+}
+
+async fn cleanup(state: &AppState, user: &User) {
+    use qck_backend_core::schema::{links, users};
+
+    let _ = state.redis_pool.del(&link_count_cache_key(user.id)).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let _ = diesel::delete(links::table.filter(links::user_id.eq(user.id)))
+        .execute(&mut conn)
+        .await;
+    let _ = diesel::delete(users::table.find(user.id))
+        .execute(&mut conn)
+        .await;
+}
+
+#[test]
+fn test_warning_starts_at_eighty_percent() {
+    let usage = |current| LinkUsage { current, limit: 10 };
+    assert!(!usage(7).is_near_limit());
+    assert!(usage(8).is_near_limit());
+    assert!(!usage(9).is_reached());
+    assert!(usage(10).is_reached());
+    assert_eq!(usage(8).header_value(), "count=8, limit=10");
+}
+
+#[tokio::test]
+async fn test_create_past_limit_is_forbidden_until_a_link_is_deleted() {
+    let state = app_state(2).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "limit").await;
+    let first = create_link(&mut conn, user.id).await;
+    create_link(&mut conn, user.id).await;
+    drop(conn);
+
+    let service = LinkService::new(&state);
+    assert_eq!(
+        service.link_usage(user.id).await.unwrap(),
+        Some(LinkUsage {
+            current: 2,
+            limit: 2
+        })
+    );
+    assert_eq!(
+        state
+            .redis_pool
+            .get::<u64>(&link_count_cache_key(user.id))
+            .await
+            .unwrap(),
+        Some(2)
+    );
+
+    assert!(matches!(
+        service.create_link(&user, create_request()).await,
+        Err(ServiceError::LinkLimitReached {
+            current: 2,
+            limit: 2
+        })
+    ));
+
+    // Deleting drops the cached count, so the freed slot shows at once
+    service.delete_link(&user, first.id).await.unwrap();
+    assert_eq!(
+        service
+            .link_usage(user.id)
+            .await
+            .unwrap()
+            .map(|u| u.current),
+        Some(1)
+    );
+
+    cleanup(&state, &user).await;
+}
+
+#[tokio::test]
+async fn test_no_cap_by_default() {
+    let state = app_state(0).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "limit").await;
+    create_link(&mut conn, user.id).await;
+    drop(conn);
+
+    assert_eq!(
+        LinkService::new(&state).link_usage(user.id).await.unwrap(),
+        None
+    );
+
+    cleanup(&state, &user).await;
+}
@@ -0,0 +1,200 @@
+// Link ownership cache (`owner:{link_id}`) behind the stats route
+// Run against the Postgres and Redis in .env.test
+
+use diesel::prelude::*;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::app::AppState;
+use qck_backend_core::models::link::{Link, LinkOwner};
+use qck_backend_core::models::organization::Organization;
+use qck_backend_core::models::user::User;
+use qck_backend_core::services::link::{
+    cached_link_owner, owner_cache_key, LinkService, OWNER_CACHE_TTL_SECONDS,
+};
+use qck_backend_core::utils::service_error::ServiceError;
+use uuid::Uuid;
+
+mod common;
+use common::{app_state, create_organization, create_test_user};
+
+async fn create_link(
+    conn: &mut AsyncPgConnection,
+    user_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> Link {
+    use qck_backend_core::schema::links::dsl;
+
+    let suffix = Uuid::new_v4().simple().to_string();
+    diesel::insert_into(dsl::links)
+        .values((
+            dsl::user_id.eq(user_id),
+            dsl::organization_id.eq(organization_id),
+            dsl::short_code.eq(format!("oc{}", &suffix[..8])),
+            dsl::original_url.eq("https://example.com/owner-cache"),
+        ))
+        .get_result::<Link>(conn)
+        .await
+        .unwrap()
+}
+
+/// Seconds left on a Redis key
+async fn ttl(state: &AppState, key: &str) -> i64 {
+    let mut conn = state.redis_pool.get_connection().await.unwrap();
+    redis::cmd("TTL")
+        .arg(key)
+        .query_async(&mut conn)
+        .await
+        .unwrap()
+}
+
+async fn cleanup(state: &AppState, link: &Link, organization: &Organization, users: &[&User]) {
+    use qck_backend_core::schema::{links, organizations, users};
+
+    let _ = state.redis_pool.del(&owner_cache_key(link.id)).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let _ = diesel::delete(links::table.find(link.id))
+        .execute(&mut conn)
+        .await;
+    let _ = diesel::delete(organizations::table.find(organization.id))
+        .execute(&mut conn)
+        .await;
+    for user in users {
+        let _ = diesel::delete(users::table.find(user.id))
+            .execute(&mut conn)
+            .await;
+    }
+}
+
+#[tokio::test]
+async fn test_first_check_caches_owner_and_rejects_others() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let admin = create_test_user(&mut conn, "owner").await;
+    let member = create_test_user(&mut conn, "owner").await;
+    let stranger = create_test_user(&mut conn, "owner").await;
+    let organization = create_organization(&mut conn, &admin, &member).await;
+    let link = create_link(&mut conn, admin.id, Some(organization.id)).await;
+    drop(conn);
+
+    let service = LinkService::new(&state);
+    let owner = service
+        .assert_ownership_cached(link.id, member.id)
+        .await
+        .unwrap();
+    assert_eq!(owner, LinkOwner::from(&link));
+    assert_eq!(
+        cached_link_owner(&state.redis_pool, link.id).await.unwrap(),
+        Some(owner)
+    );
+
+    let ttl = ttl(&state, &owner_cache_key(link.id)).await;
+    assert!(
+        ttl > 0 && ttl <= OWNER_CACHE_TTL_SECONDS as i64,
+        "ttl {ttl}"
+    );
+    assert!(OWNER_CACHE_TTL_SECONDS <= 30);
+
+    // A cached owner still checks organization membership
+    assert!(matches!(
+        service.assert_ownership_cached(link.id, stranger.id).await,
+        Err(ServiceError::NotFound)
+    ));
+
+    cleanup(&state, &link, &organization, &[&admin, &member, &stranger]).await;
+}
+
+#[tokio::test]
+async fn test_transfer_invalidates_cached_owner() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let admin = create_test_user(&mut conn, "owner").await;
+    let member = create_test_user(&mut conn, "owner").await;
+    let organization = create_organization(&mut conn, &admin, &member).await;
+    let link = create_link(&mut conn, admin.id, Some(organization.id)).await;
+    drop(conn);
+
+    let service = LinkService::new(&state);
+    service
+        .assert_ownership_cached(link.id, member.id)
+        .await
+        .unwrap();
+
+    // Moving the link to the admin's personal links drops the member's access at once
+    service.transfer_link(&admin, link.id, None).await.unwrap();
+    assert_eq!(
+        cached_link_owner(&state.redis_pool, link.id).await.unwrap(),
+        None
+    );
+    assert!(matches!(
+        service.assert_ownership_cached(link.id, member.id).await,
+        Err(ServiceError::NotFound)
+    ));
+    let owner = service
+        .assert_ownership_cached(link.id, admin.id)
+        .await
+        .unwrap();
+    assert_eq!(owner.organization_id, None);
+
+    cleanup(&state, &link, &organization, &[&admin, &member]).await;
+}
+
+#[tokio::test]
+async fn test_stale_owner_expires_after_missed_invalidation() {
+    use qck_backend_core::schema::links::dsl;
+
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let admin = create_test_user(&mut conn, "owner").await;
+    let member = create_test_user(&mut conn, "owner").await;
+    let organization = create_organization(&mut conn, &admin, &member).await;
+    let link = create_link(&mut conn, member.id, None).await;
+
+    let service = LinkService::new(&state);
+    service
+        .assert_ownership_cached(link.id, member.id)
+        .await
+        .unwrap();
+
+    // Change hands behind the cache's back, as a missed invalidation would
+    diesel::update(dsl::links.find(link.id))
+        .set((
+            dsl::user_id.eq(admin.id),
+            dsl::organization_id.eq(None::<Uuid>),
+        ))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    drop(conn);
+
+    // Within the TTL window the previous owner is still let through...
+    service
+        .assert_ownership_cached(link.id, member.id)
+        .await
+        .unwrap();
+
+    // ...but never past it; shorten the remaining TTL rather than wait it out
+    let key = owner_cache_key(link.id);
+    assert!(ttl(&state, &key).await <= OWNER_CACHE_TTL_SECONDS as i64);
+    let mut redis_conn = state.redis_pool.get_connection().await.unwrap();
+    redis::cmd("EXPIRE")
+        .arg(&key)
+        .arg(1)
+        .query_async::<()>(&mut redis_conn)
+        .await
+        .unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+
+    assert!(matches!(
+        service.assert_ownership_cached(link.id, member.id).await,
+        Err(ServiceError::NotFound)
+    ));
+    assert_eq!(
+        service
+            .assert_ownership_cached(link.id, admin.id)
+            .await
+            .unwrap()
+            .user_id,
+        admin.id
+    );
+
+    cleanup(&state, &link, &organization, &[&admin, &member]).await;
+}
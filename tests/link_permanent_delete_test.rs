@@ -0,0 +1,245 @@
+// Permanent link deletion
+// Run against the Postgres, Redis and ClickHouse in .env.test; serial because the rollup
+// refresh rewrites the daily rows of every link
+
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::IntoResponse;
+use axum::Extension;
+use diesel::sql_types::{Jsonb, Nullable, Text, Uuid as SqlUuid};
+use diesel::QueryableByName;
+use diesel_async::{AsyncPgConnection, RunQueryDsl};
+use qck_backend_core::app::AppState;
+use qck_backend_core::db::insert_link_events;
+use qck_backend_core::handlers::links::delete_link;
+use qck_backend_core::middleware::auth::AuthenticatedUser;
+use qck_backend_core::models::link::DeleteLinkQuery;
+use qck_backend_core::services::click_tracking::ClickEvent;
+use qck_backend_core::services::link::LinkService;
+use qck_backend_core::utils::service_error::ServiceError;
+use serial_test::serial;
+use uuid::Uuid;
+
+mod common;
+use common::create_test_user;
+
+/// Tables `delete_link_events` clears
+const CLICK_TABLES: [&str; 7] = [
+    "link_events",
+    "link_stats",
+    "link_stats_hourly",
+    "link_totals",
+    "link_daily_stats",
+    "link_daily_countries",
+    "link_daily_referrers",
+];
+
+#[derive(QueryableByName)]
+struct IdRow {
+    #[diesel(sql_type = SqlUuid)]
+    id: Uuid,
+}
+
+#[derive(QueryableByName)]
+struct AuditRow {
+    #[diesel(sql_type = Text)]
+    action: String,
+    #[diesel(sql_type = Nullable<Jsonb>)]
+    details: Option<serde_json::Value>,
+}
+
+async fn app_state() -> AppState {
+    let state = common::app_state().await;
+    assert!(
+        state.clickhouse_analytics.is_some(),
+        "ClickHouse must be configured for permanent delete tests"
+    );
+    state
+}
+
+async fn insert_link(conn: &mut AsyncPgConnection, user_id: Uuid, short_code: &str) -> Uuid {
+    let row: IdRow = diesel::sql_query(
+        "INSERT INTO links (user_id, short_code, original_url, title)
+         VALUES ($1, $2, 'https://example.com/legal', 'Legal request')
+         RETURNING id",
+    )
+    .bind::<SqlUuid, _>(user_id)
+    .bind::<Text, _>(short_code)
+    .get_result(conn)
+    .await
+    .unwrap();
+    row.id
+}
+
+fn click(link_id: Uuid) -> ClickEvent {
+    ClickEvent::new(
+        link_id,
+        "203.0.113.7".parse().unwrap(),
+        "Mozilla/5.0 (X11; Linux x86_64) Firefox/120.0",
+        Some("https://news.example.org/story"),
+        "GET",
+        3,
+        301,
+    )
+}
+
+/// Rows for `link_id` in each click table
+async fn click_rows(state: &AppState, link_id: Uuid) -> Vec<(&'static str, u64)> {
+    let clickhouse = state.clickhouse_analytics.as_ref().unwrap().client();
+    let mut rows = Vec::new();
+    for table in CLICK_TABLES {
+        let count = clickhouse
+            .client()
+            .query(&format!(
+                "SELECT count() FROM {}.{} WHERE link_id = '{}'",
+                clickhouse.database(),
+                table,
+                link_id
+            ))
+            .fetch_one::<u64>()
+            .await
+            .unwrap();
+        rows.push((table, count));
+    }
+    rows
+}
+
+#[tokio::test]
+#[serial]
+async fn test_permanent_delete_erases_link_clicks_and_cache() {
+    let state = app_state().await;
+    let analytics = state.clickhouse_analytics.clone().unwrap();
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "erase").await;
+    let short_code = format!("er{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let link_id = insert_link(&mut conn, user.id, &short_code).await;
+
+    // Clicks already in link_events, one still sitting in an insert buffer, and rollups
+    let clickhouse = analytics.client();
+    insert_link_events(clickhouse.client(), "link_events", &[click(link_id)])
+        .await
+        .unwrap();
+    insert_link_events(
+        clickhouse.client(),
+        "link_events_buffer1",
+        &[click(link_id)],
+    )
+    .await
+    .unwrap();
+    analytics
+        .refresh_daily_rollups(chrono::Utc::now().date_naive())
+        .await
+        .unwrap();
+    assert!(analytics.has_events(&link_id).await);
+
+    let cache_keys = [
+        format!("link:{}", short_code),
+        format!("redirect:{}", short_code),
+        format!("code:{}", short_code),
+    ];
+    for key in &cache_keys {
+        state
+            .redis_pool
+            .set_with_expiry(key, "cached".to_string(), 3600)
+            .await
+            .unwrap();
+    }
+
+    LinkService::new(&state)
+        .permanent_delete_link(&user, link_id)
+        .await
+        .unwrap();
+
+    let remaining: Vec<IdRow> = diesel::sql_query("SELECT id FROM links WHERE id = $1")
+        .bind::<SqlUuid, _>(link_id)
+        .load(&mut conn)
+        .await
+        .unwrap();
+    assert!(remaining.is_empty());
+
+    for (table, count) in click_rows(&state, link_id).await {
+        assert_eq!(count, 0, "{} still has rows for the link", table);
+    }
+
+    for key in &cache_keys {
+        let cached: Option<String> = state.redis_pool.get(key).await.unwrap();
+        assert_eq!(cached, None, "{} still cached", key);
+    }
+
+    // The snapshot outlives the link
+    let audit: AuditRow = diesel::sql_query(
+        "SELECT action, details FROM audit_logs WHERE resource_type = 'link' AND resource_id = $1",
+    )
+    .bind::<Text, _>(link_id.to_string())
+    .get_result(&mut conn)
+    .await
+    .unwrap();
+    assert_eq!(audit.action, "LinkPermanentlyDeleted");
+    let details = audit.details.unwrap();
+    assert_eq!(details["owner_id"], user.id.to_string());
+    assert_eq!(details["link"]["short_code"], short_code.as_str());
+    assert_eq!(details["link"]["title"], "Legal request");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_permanent_delete_requires_owner() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let owner = create_test_user(&mut conn, "erase").await;
+    let stranger = create_test_user(&mut conn, "erase").await;
+    let short_code = format!("ek{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let link_id = insert_link(&mut conn, owner.id, &short_code).await;
+
+    let result = LinkService::new(&state)
+        .permanent_delete_link(&stranger, link_id)
+        .await;
+    assert!(matches!(result, Err(ServiceError::NotFound)));
+
+    let remaining: Vec<IdRow> = diesel::sql_query("SELECT id FROM links WHERE id = $1")
+        .bind::<SqlUuid, _>(link_id)
+        .load(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_permanent_delete_forbidden_while_impersonating() {
+    let state = app_state().await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let owner = create_test_user(&mut conn, "erase").await;
+    let short_code = format!("ei{}", &Uuid::new_v4().simple().to_string()[..8]);
+    let link_id = insert_link(&mut conn, owner.id, &short_code).await;
+
+    let auth_user = AuthenticatedUser {
+        user_id: owner.id.to_string(),
+        token_id: Uuid::new_v4().to_string(),
+        email: owner.email.clone(),
+        subscription_tier: "free".to_string(),
+        permissions: Vec::new(),
+        exp: u64::MAX,
+        impersonator_id: Some(Uuid::new_v4().to_string()),
+    };
+    let mut headers = HeaderMap::new();
+    headers.insert("x-confirm-permanent-delete", "yes".parse().unwrap());
+
+    let response = delete_link(
+        State(state.clone()),
+        Extension(auth_user),
+        Path(link_id),
+        Query(DeleteLinkQuery { permanent: true }),
+        headers,
+    )
+    .await
+    .into_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let remaining: Vec<IdRow> = diesel::sql_query("SELECT id FROM links WHERE id = $1")
+        .bind::<SqlUuid, _>(link_id)
+        .load(&mut conn)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 1);
+}
@@ -0,0 +1,153 @@
+// Persisted link security scan tests
+// Covers how a scan result is stored on the link row and read back, without a database
+
+use chrono::Utc;
+use qck_backend_core::models::link::{Link, LinkScanUpdate, LinkSecurityResponse};
+use qck_backend_core::services::rate_limit::RateLimitConfig;
+use qck_backend_core::utils::security_scanner::{
+    SecurityRiskLevel, SecurityScanResult, ThreatType,
+};
+use uuid::Uuid;
+
+fn sample_link() -> Link {
+    let now = Utc::now();
+    Link {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        short_code: "sec123".to_string(),
+        original_url: "https://example.tk/offer".to_string(),
+        title: None,
+        description: None,
+        tags: None,
+        custom_alias: None,
+        is_active: true,
+        expires_at: None,
+        password_hash: None,
+        last_accessed_at: None,
+        og_image: None,
+        favicon_url: None,
+        processing_status: "completed".to_string(),
+        metadata_extracted_at: None,
+        utm_source: None,
+        utm_medium: None,
+        utm_campaign: None,
+        utm_term: None,
+        utm_content: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+        last_scanned_at: None,
+        threat_score: None,
+        quarantined: false,
+        quarantined_at: None,
+        quarantine_reason: None,
+        appeal_status: None,
+        appeal_message: None,
+        appealed_at: None,
+        risk_level: None,
+        threats_detected: None,
+        scan_warnings: None,
+        tracking_mode: "full".to_string(),
+        organization_id: None,
+        click_count: 0,
+        notes: None,
+        deactivation_reason: None,
+        path_passthrough: false,
+        allowed_referrers: Vec::new(),
+        blocked_referrers: Vec::new(),
+        referrer_strict: false,
+        expiry_policy: "fixed".to_string(),
+        sliding_window_days: None,
+    }
+}
+
+fn sample_scan() -> SecurityScanResult {
+    SecurityScanResult {
+        url: "https://example.tk/offer".to_string(),
+        is_safe: true,
+        threat_score: 20,
+        risk_level: SecurityRiskLevel::Safe,
+        threats_detected: vec![ThreatType::SuspiciousTld],
+        warnings: vec!["Suspicious TLD detected in domain: example.tk".to_string()],
+        scan_timestamp: Utc::now(),
+        scan_duration_ms: 12,
+    }
+}
+
+/// Apply a scan changeset to an in-memory link, as the UPDATE would
+fn apply(link: &mut Link, update: LinkScanUpdate) {
+    link.last_scanned_at = update.last_scanned_at;
+    link.threat_score = update.threat_score;
+    link.risk_level = update.risk_level;
+    link.threats_detected = update.threats_detected;
+    link.scan_warnings = update.scan_warnings;
+}
+
+#[test]
+fn test_scan_update_captures_full_result() {
+    let scan = sample_scan();
+    let update = LinkScanUpdate::from(&scan);
+
+    assert_eq!(update.last_scanned_at, Some(scan.scan_timestamp));
+    assert_eq!(update.threat_score, Some(20));
+    assert_eq!(update.risk_level.as_deref(), Some("Safe"));
+    assert_eq!(
+        update.threats_detected,
+        Some(serde_json::json!(["SuspiciousTld"]))
+    );
+    assert_eq!(
+        update.scan_warnings,
+        Some(serde_json::json!([
+            "Suspicious TLD detected in domain: example.tk"
+        ]))
+    );
+}
+
+#[test]
+fn test_security_response_reads_stored_scan() {
+    let mut link = sample_link();
+    apply(&mut link, LinkScanUpdate::from(&sample_scan()));
+
+    let response = LinkSecurityResponse::from_link(&link, true);
+    assert_eq!(response.link_id, link.id);
+    assert_eq!(response.threat_score, Some(20));
+    assert_eq!(response.risk_level.as_deref(), Some("Safe"));
+    assert_eq!(response.threats_detected, vec!["SuspiciousTld"]);
+    assert_eq!(response.warnings.len(), 1);
+    assert!(response.rescanned);
+    assert!(!response.is_quarantined);
+}
+
+#[test]
+fn test_never_scanned_link_has_empty_security() {
+    let link = sample_link();
+
+    let response = LinkSecurityResponse::from_link(&link, false);
+    assert!(response.threat_score.is_none());
+    assert!(response.threats_detected.is_empty());
+    assert!(response.warnings.is_empty());
+    assert!(response.scanned_at.is_none());
+
+    let summary = link.to_response("https://qck.sh").security;
+    assert!(summary.risk_level.is_none());
+    assert!(summary.threats_detected.is_empty());
+}
+
+#[test]
+fn test_link_response_includes_security_summary() {
+    let mut link = sample_link();
+    apply(&mut link, LinkScanUpdate::from(&sample_scan()));
+
+    let summary = link.to_response("https://qck.sh").security;
+    assert_eq!(summary.threat_score, Some(20));
+    assert_eq!(summary.risk_level.as_deref(), Some("Safe"));
+    assert_eq!(summary.threats_detected, vec!["SuspiciousTld"]);
+    assert!(summary.scanned_at.is_some());
+}
+
+#[test]
+fn test_link_rescan_rate_limit_is_strict() {
+    let config = RateLimitConfig::link_rescan();
+    assert!(config.max_requests <= 10);
+    assert!(config.max_requests < RateLimitConfig::default_api().max_requests);
+}
@@ -134,8 +134,7 @@ async fn test_login_invalid_credentials() {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let body: serde_json::Value = response.json().await;
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"]["code"], "INVALID_CREDENTIALS");
+    assert_eq!(body["code"], "INVALID_CREDENTIALS");
 }
 
 #[tokio::test]
@@ -157,8 +156,7 @@ async fn test_login_nonexistent_user() {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let body: serde_json::Value = response.json().await;
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"]["code"], "INVALID_CREDENTIALS");
+    assert_eq!(body["code"], "INVALID_CREDENTIALS");
 }
 
 #[tokio::test]
@@ -204,8 +202,7 @@ async fn test_login_email_not_verified() {
         // Should be forbidden if email verification is required
         assert_eq!(response.status(), StatusCode::FORBIDDEN);
         let body: serde_json::Value = response.json().await;
-        assert_eq!(body["success"], false);
-        assert_eq!(body["error"]["code"], "EMAIL_NOT_VERIFIED");
+        assert_eq!(body["code"], "EMAIL_NOT_VERIFIED");
     } else {
         // Should succeed if email verification is disabled
         assert_eq!(response.status(), StatusCode::OK);
@@ -319,11 +316,10 @@ async fn test_login_rate_limiting_per_ip() {
         .await;
 
     assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert!(response.header("retry-after").is_some());
 
     let body: serde_json::Value = response.json().await;
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"]["code"], "RATE_LIMITED");
-    assert!(body["error"]["retry_after"].is_number());
+    assert_eq!(body["code"], "RATE_LIMITED");
 }
 
 #[tokio::test]
@@ -377,9 +373,9 @@ async fn test_login_account_lockout() {
                 i + 1
             );
 
+            assert!(response.header("retry-after").is_some());
             let body: serde_json::Value = response.json().await;
-            assert_eq!(body["error"]["code"], "ACCOUNT_LOCKED");
-            assert!(body["error"]["retry_after"].is_number());
+            assert_eq!(body["code"], "ACCOUNT_LOCKED");
         }
 
         // Small delay to avoid hitting rate limits
@@ -400,8 +396,7 @@ async fn test_login_account_lockout() {
     assert_eq!(response.status(), StatusCode::LOCKED);
 
     let body: serde_json::Value = response.json().await;
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"]["code"], "ACCOUNT_LOCKED");
+    assert_eq!(body["code"], "ACCOUNT_LOCKED");
 }
 
 #[tokio::test]
@@ -540,8 +535,7 @@ async fn test_login_invalid_email_format() {
     assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
 
     let body: serde_json::Value = response.json().await;
-    assert_eq!(body["success"], false);
-    assert_eq!(body["error"]["code"], "INVALID_CREDENTIALS");
+    assert_eq!(body["code"], "INVALID_CREDENTIALS");
 }
 
 #[tokio::test]
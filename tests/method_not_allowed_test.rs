@@ -0,0 +1,168 @@
+// Fallback tests for unsupported methods and unmatched paths
+// Mirrors the ordering in main.rs: API groups, the JSON 405 fallback, the "/v1" guards,
+// then the redirect routes with their HTML 405 and the global 404 fallback.
+// The path-passthrough route answers like a short code that doesn't exist.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::OriginalUri,
+    http::{header, Request, StatusCode},
+    response::Response,
+    routing::{any, get, post},
+    Router,
+};
+use qck_backend_core::handlers::{fallback, redirect};
+use tower::ServiceExt;
+
+fn app() -> Router {
+    let links = Router::new()
+        .route(
+            "/links",
+            post(|| async { "created" }).get(|| async { "list" }),
+        )
+        .route("/links/check-alias/{alias}", get(|| async { "available" }))
+        .route(
+            "/links/{id}",
+            get(|| async { "link" }).delete(|| async { "deleted" }),
+        );
+    let redirects = Router::new()
+        .route("/{short_code}", get(|| async { "redirect" }))
+        .route("/{short_code}/unlock", post(|| async { "unlock" }))
+        .route(
+            "/{short_code}/{*sub_path}",
+            get(
+                |OriginalUri(uri): OriginalUri| async move { redirect::path_not_found(uri.path()) },
+            ),
+        )
+        .method_not_allowed_fallback(redirect::method_not_allowed);
+
+    Router::new()
+        .route("/v1/health", get(|| async { "ok" }))
+        .nest("/v1", links)
+        .method_not_allowed_fallback(fallback::api_method_not_allowed)
+        .route("/v1", any(fallback::not_found))
+        .route("/v1/{*path}", any(fallback::not_found))
+        .merge(redirects)
+        .fallback(fallback::not_found)
+}
+
+async fn send(method: &str, uri: &str) -> Response {
+    app()
+        .oneshot(
+            Request::builder()
+                .method(method)
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+async fn body_text(response: Response) -> String {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    String::from_utf8(bytes.to_vec()).unwrap()
+}
+
+fn allow(response: &Response) -> Vec<String> {
+    let mut methods: Vec<String> = response.headers()[header::ALLOW]
+        .to_str()
+        .unwrap()
+        .split(',')
+        .map(|m| m.trim().to_string())
+        .collect();
+    methods.sort();
+    methods
+}
+
+#[tokio::test]
+async fn test_api_405_uses_json_envelope_and_allow_header() {
+    for (method, uri, allowed) in [
+        ("DELETE", "/v1/links/check-alias/promo", vec!["GET", "HEAD"]),
+        ("PUT", "/v1/links", vec!["GET", "HEAD", "POST"]),
+        ("PATCH", "/v1/links/42", vec!["DELETE", "GET", "HEAD"]),
+        ("POST", "/v1/health", vec!["GET", "HEAD"]),
+    ] {
+        let response = send(method, uri).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{method} {uri}"
+        );
+        assert_eq!(allow(&response), allowed, "{method} {uri}");
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("application/json"));
+
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["code"], "METHOD_NOT_ALLOWED");
+        assert_eq!(
+            body["message"],
+            format!("{} is not supported on {}", method, uri)
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_redirect_405_stays_html() {
+    for (method, uri, allowed) in [
+        ("POST", "/abc123", vec!["GET", "HEAD"]),
+        ("GET", "/abc123/unlock", vec!["POST"]),
+        ("POST", "/abc123/docs/intro", vec!["GET", "HEAD"]),
+    ] {
+        let response = send(method, uri).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED,
+            "{method} {uri}"
+        );
+        assert_eq!(allow(&response), allowed, "{method} {uri}");
+        assert!(response.headers()[header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with("text/html"));
+        assert!(body_text(response).await.contains("Method Not Allowed"));
+    }
+}
+
+#[tokio::test]
+async fn test_unmatched_api_paths_return_json_404() {
+    for (method, uri) in [
+        ("GET", "/v1"),
+        ("GET", "/v1/"),
+        ("GET", "/v1/nope"),
+        ("GET", "/v1/links/42/unknown"),
+        // Not taken for a path-passthrough link
+        ("POST", "/v1/nope/deeper"),
+    ] {
+        let response = send(method, uri).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND, "{uri}");
+
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["code"], "ROUTE_NOT_FOUND", "{uri}");
+    }
+}
+
+#[tokio::test]
+async fn test_unmatched_public_paths_return_html_404() {
+    let response = send("GET", "/a/b/%3Cscript%3E").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let page = body_text(response).await;
+    assert!(page.contains("Link Not Found"));
+    assert!(!page.contains("<script>"));
+
+    // Short codes that start with "v1" are still short codes
+    assert_eq!(send("GET", "/v1abc").await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_supported_methods_are_unaffected() {
+    assert_eq!(send("POST", "/v1/links").await.status(), StatusCode::OK);
+    assert_eq!(
+        send("HEAD", "/v1/links/check-alias/promo").await.status(),
+        StatusCode::OK
+    );
+    assert_eq!(send("GET", "/abc123").await.status(), StatusCode::OK);
+}
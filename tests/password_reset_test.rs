@@ -0,0 +1,109 @@
+// Password reset flow: forgot-password email, reset with its token, log in again
+// Emails are captured by the test app, so no Resend key is needed
+
+use axum::http::StatusCode;
+use qck_backend_core::models::user::{NewUser, OnboardingStatus, User};
+use qck_backend_core::utils::hash_password;
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+
+mod common;
+use common::{setup_test_app, TestApp};
+
+async fn create_test_user(app: &TestApp, email: &str, password: &str) -> User {
+    let mut conn = app.diesel_pool.get().await.unwrap();
+    let new_user = NewUser {
+        email: email.to_string(),
+        password_hash: hash_password(password).unwrap(),
+        full_name: "Reset User".to_string(),
+        company_name: None,
+        email_verified: true,
+        subscription_tier: "free".to_string(),
+        onboarding_status: OnboardingStatus::Completed.as_str().to_string(),
+    };
+
+    User::create(&mut conn, new_user).await.unwrap()
+}
+
+/// The token from the `reset-password?token=...` link in an email body
+fn reset_token(html: &str) -> String {
+    let start = html.find("token=").expect("email has no reset link") + "token=".len();
+    html[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '_')
+        .collect()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_reset_email_token_changes_password() {
+    let app = setup_test_app().await;
+    let email = format!("reset_{}@example.com", Uuid::new_v4().simple());
+    create_test_user(&app, &email, "OldP@ssw0rd123!").await;
+
+    let response = app
+        .post("/v1/auth/forgot-password")
+        .json(&json!({ "email": &email }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let sent = app.emails.sent_to(&email);
+    assert_eq!(sent.len(), 1, "expected one reset email");
+    assert!(sent[0].subject.starts_with("Password Reset Request"));
+    let token = reset_token(&sent[0].html);
+    assert!(token.len() >= 32, "token {token:?}");
+
+    let new_password = "NewP@ssw0rd456!";
+    let response = app
+        .post("/v1/auth/reset-password")
+        .json(&json!({
+            "token": token,
+            "new_password": new_password,
+            "confirm_password": new_password
+        }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A password change notification follows the reset email
+    assert_eq!(app.emails.sent_to(&email).len(), 2);
+
+    let response = app
+        .post("/v1/auth/login")
+        .json(&json!({ "email": &email, "password": new_password, "remember_me": false }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The token is single-use
+    let response = app
+        .post("/v1/auth/reset-password")
+        .json(&json!({
+            "token": token,
+            "new_password": "OtherP@ssw0rd789!",
+            "confirm_password": "OtherP@ssw0rd789!"
+        }))
+        .send()
+        .await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unknown_email_gets_same_response_and_no_email() {
+    let app = setup_test_app().await;
+    let email = format!("nobody_{}@example.com", Uuid::new_v4().simple());
+
+    let response = app
+        .post("/v1/auth/forgot-password")
+        .json(&json!({ "email": &email }))
+        .send()
+        .await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await;
+    assert_eq!(body["success"], true);
+    assert!(app.emails.sent().is_empty());
+}
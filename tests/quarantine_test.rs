@@ -0,0 +1,120 @@
+// Link quarantine tests
+// Covers the API surface of a quarantined link without a database
+
+use axum::{http::StatusCode, response::IntoResponse};
+use chrono::Utc;
+use qck_backend_core::models::link::{Link, QuarantinedLinkResponse};
+use qck_backend_core::utils::service_error::ServiceError;
+use uuid::Uuid;
+
+fn sample_link() -> Link {
+    let now = Utc::now();
+    Link {
+        id: Uuid::new_v4(),
+        user_id: Uuid::new_v4(),
+        short_code: "abc123".to_string(),
+        original_url: "https://example.com/download".to_string(),
+        title: None,
+        description: None,
+        tags: None,
+        custom_alias: None,
+        is_active: true,
+        expires_at: None,
+        password_hash: None,
+        last_accessed_at: None,
+        og_image: None,
+        favicon_url: None,
+        processing_status: "completed".to_string(),
+        metadata_extracted_at: None,
+        utm_source: None,
+        utm_medium: None,
+        utm_campaign: None,
+        utm_term: None,
+        utm_content: None,
+        deleted_at: None,
+        created_at: now,
+        updated_at: now,
+        last_scanned_at: None,
+        threat_score: None,
+        quarantined: false,
+        quarantined_at: None,
+        quarantine_reason: None,
+        appeal_status: None,
+        appeal_message: None,
+        appealed_at: None,
+        risk_level: None,
+        threats_detected: None,
+        scan_warnings: None,
+        tracking_mode: "full".to_string(),
+        organization_id: None,
+        click_count: 0,
+        notes: None,
+        deactivation_reason: None,
+        path_passthrough: false,
+        allowed_referrers: Vec::new(),
+        blocked_referrers: Vec::new(),
+        referrer_strict: false,
+        expiry_policy: "fixed".to_string(),
+        sliding_window_days: None,
+    }
+}
+
+#[test]
+fn test_quarantined_error_is_forbidden() {
+    let response = ServiceError::Quarantined.into_response();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[test]
+fn test_link_response_reflects_quarantine() {
+    let mut link = sample_link();
+    let clean = link.to_response("https://qck.sh");
+    assert!(!clean.is_quarantined);
+    assert!(clean.metadata.is_safe);
+
+    link.quarantined = true;
+    link.threat_score = Some(95);
+    let quarantined = link.to_response("https://qck.sh");
+    assert!(quarantined.is_quarantined);
+    assert!(!quarantined.metadata.is_safe);
+}
+
+#[test]
+fn test_cached_link_without_scan_fields_still_deserializes() {
+    // Links cached in Redis before the quarantine columns existed must keep loading
+    let mut value = serde_json::to_value(sample_link()).unwrap();
+    let object = value.as_object_mut().unwrap();
+    for field in [
+        "last_scanned_at",
+        "threat_score",
+        "quarantined",
+        "quarantined_at",
+        "quarantine_reason",
+        "appeal_status",
+        "appeal_message",
+        "appealed_at",
+    ] {
+        object.remove(field);
+    }
+
+    let link: Link = serde_json::from_value(value).unwrap();
+    assert!(!link.quarantined);
+    assert!(link.threat_score.is_none());
+}
+
+#[test]
+fn test_admin_view_carries_appeal_details() {
+    let mut link = sample_link();
+    link.quarantined = true;
+    link.quarantine_reason = Some("Domain example.com is blocked by operator".to_string());
+    link.appeal_status = Some("pending".to_string());
+    link.appeal_message = Some("False positive".to_string());
+
+    let view = QuarantinedLinkResponse::from(link);
+    assert_eq!(view.appeal_status.as_deref(), Some("pending"));
+    assert_eq!(view.appeal_message.as_deref(), Some("False positive"));
+    assert!(view
+        .quarantine_reason
+        .unwrap()
+        .contains("blocked by operator"));
+}
@@ -0,0 +1,180 @@
+// Quick links from POST /v1/links/quick
+// Run against the Postgres and Redis in .env.test; the external lookups are replaced by a fake
+// that holds every lookup until the test releases it, then flags the URL
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use qck_backend_core::app::AppState;
+use qck_backend_core::db::RedisPool;
+use qck_backend_core::models::link::{Link, QuickLinkRequest};
+use qck_backend_core::models::user::User;
+use qck_backend_core::services::link::LinkService;
+use qck_backend_core::utils::security_scanner::{SecurityScanResult, ThreatLookup, ThreatType};
+use qck_backend_core::utils::service_error::ServiceError;
+use tokio::sync::Semaphore;
+
+mod common;
+use common::create_test_user;
+
+/// Waits for `release`, then records the URL and reports it as malware
+struct GatedLookup {
+    gate: Semaphore,
+    checked: Mutex<Vec<String>>,
+}
+
+impl GatedLookup {
+    fn new() -> Self {
+        Self {
+            gate: Semaphore::new(0),
+            checked: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Let every pending and future lookup through
+    fn release(&self) {
+        self.gate.add_permits(1024);
+    }
+
+    fn checked_urls(&self) -> Vec<String> {
+        self.checked.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl ThreatLookup for GatedLookup {
+    async fn check(
+        &self,
+        url_str: &str,
+        _redis_pool: Option<&RedisPool>,
+        scan_result: &mut SecurityScanResult,
+    ) {
+        let _permit = self.gate.acquire().await.unwrap();
+        self.checked.lock().unwrap().push(url_str.to_string());
+        scan_result.threats_detected.push(ThreatType::Malware);
+        scan_result.threat_score = 100;
+    }
+}
+
+/// App state using `lookup` for full scans, with QUICK_LINK_DEFERRED_SCAN set to `deferred`
+async fn app_state(lookup: Arc<GatedLookup>, deferred: bool) -> AppState {
+    let mut state = common::app_state().await;
+    let mut config = (*state.config).clone();
+    config.security.quick_link_deferred_scan = deferred;
+    state.config = Arc::new(config);
+    state.threat_lookup = lookup;
+    state
+}
+
+fn quick_request(url: &str) -> QuickLinkRequest {
+    QuickLinkRequest {
+        url: url.to_string(),
+    }
+}
+
+async fn load_link(state: &AppState, short_url: &str) -> Link {
+    use qck_backend_core::schema::links::dsl;
+
+    let short_code = short_url.rsplit('/').next().unwrap();
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    dsl::links
+        .filter(dsl::short_code.eq(short_code))
+        .first::<Link>(&mut conn)
+        .await
+        .unwrap()
+}
+
+async fn cleanup(state: &AppState, user: &User) {
+    use qck_backend_core::schema::{links, users};
+
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let _ = diesel::delete(links::table.filter(links::user_id.eq(user.id)))
+        .execute(&mut conn)
+        .await;
+    let _ = diesel::delete(users::table.find(user.id))
+        .execute(&mut conn)
+        .await;
+}
+
+#[tokio::test]
+async fn test_quick_link_is_active_and_titled_with_host() {
+    // Never released: the deferred scan stays pending for the whole test
+    let lookup = Arc::new(GatedLookup::new());
+    let state = app_state(lookup, true).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "quick").await;
+
+    let response = LinkService::new(&state)
+        .quick_create_link(&user, quick_request("https://example.com/docs"))
+        .await
+        .unwrap();
+
+    let link = load_link(&state, &response.short_url).await;
+    assert!(link.is_active);
+    assert_eq!(link.title.as_deref(), Some("example.com"));
+    assert!(!link.quarantined);
+
+    cleanup(&state, &user).await;
+}
+
+#[tokio::test]
+async fn test_full_scan_runs_up_front_without_deferral() {
+    let lookup = Arc::new(GatedLookup::new());
+    lookup.release();
+    let state = app_state(lookup.clone(), false).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "quick").await;
+    let url = "https://example.com/not-deferred";
+
+    let result = LinkService::new(&state)
+        .quick_create_link(&user, quick_request(url))
+        .await;
+    assert!(matches!(result, Err(ServiceError::SecurityBlocked(_))));
+    assert_eq!(lookup.checked_urls(), vec![url.to_string()]);
+
+    cleanup(&state, &user).await;
+}
+
+#[tokio::test]
+async fn test_deferred_scan_quarantines_flagged_link() {
+    let lookup = Arc::new(GatedLookup::new());
+    let state = app_state(lookup.clone(), true).await;
+    let mut conn = state.diesel_pool.get().await.unwrap();
+    let user = create_test_user(&mut conn, "quick").await;
+    let url = "https://example.com/deferred-scan";
+
+    // The lookup is held, so the response can only come from the quick scan
+    let response = tokio::time::timeout(
+        Duration::from_secs(10),
+        LinkService::new(&state).quick_create_link(&user, quick_request(url)),
+    )
+    .await
+    .expect("quick links must not wait for the external lookups")
+    .unwrap();
+    assert!(lookup.checked_urls().is_empty());
+
+    let link = load_link(&state, &response.short_url).await;
+    assert!(link.is_active);
+    assert!(!link.quarantined);
+
+    // Once the background scan gets its answer, record_deferred_scan stores it and
+    // quarantines the link
+    lookup.release();
+    let mut quarantined = link;
+    for _ in 0..50 {
+        quarantined = load_link(&state, &response.short_url).await;
+        if quarantined.quarantined {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert!(quarantined.quarantined);
+    assert_eq!(quarantined.threat_score, Some(100));
+    assert!(quarantined.last_scanned_at.is_some());
+    assert_eq!(lookup.checked_urls(), vec![url.to_string()]);
+
+    cleanup(&state, &user).await;
+}
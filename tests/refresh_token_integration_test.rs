@@ -519,6 +519,101 @@ async fn test_logout_revokes_all_tokens() {
     cleanup_test_user(&db_pool, user_uuid).await;
 }
 
+#[tokio::test]
+async fn test_revoke_all_sessions_keeps_current_lineage() {
+    let (db_pool, _redis_pool, jwt_service) = setup_test_env().await;
+
+    let user_uuid = create_test_user(&db_pool).await;
+    let user_id = user_uuid.to_string();
+
+    let current = jwt_service
+        .generate_refresh_token_with_device(&user_id, Some("device-1".to_string()), None, None)
+        .await
+        .expect("Failed to generate current token");
+    let other = jwt_service
+        .generate_refresh_token_with_device(&user_id, Some("device-2".to_string()), None, None)
+        .await
+        .expect("Failed to generate other token");
+
+    // The current session has rotated once; its lineage is what must survive
+    let (_, current, _) = jwt_service
+        .rotate_refresh_token(&current, Some("device-1".to_string()), None, None)
+        .await
+        .expect("Failed to rotate current token");
+
+    let revoked = jwt_service
+        .revoke_all_sessions(&user_id, Some(&current))
+        .await
+        .expect("Failed to revoke sessions");
+    assert_eq!(
+        revoked, 1,
+        "Only the other device's token should be revoked"
+    );
+
+    assert!(jwt_service.validate_refresh_token(&current).await.is_ok());
+    assert!(jwt_service.validate_refresh_token(&other).await.is_err());
+
+    // The kept session can still refresh
+    assert!(jwt_service
+        .rotate_refresh_token(&current, Some("device-1".to_string()), None, None)
+        .await
+        .is_ok());
+
+    cleanup_test_user(&db_pool, user_uuid).await;
+}
+
+#[tokio::test]
+async fn test_revoke_all_sessions_rejects_foreign_or_dead_current_token() {
+    let (db_pool, _redis_pool, jwt_service) = setup_test_env().await;
+
+    let user_uuid = create_test_user(&db_pool).await;
+    let user_id = user_uuid.to_string();
+    let stranger_uuid = create_test_user(&db_pool).await;
+
+    let token = jwt_service
+        .generate_refresh_token_with_device(&user_id, Some("device-1".to_string()), None, None)
+        .await
+        .expect("Failed to generate token");
+    let stranger_token = jwt_service
+        .generate_refresh_token_with_device(
+            &stranger_uuid.to_string(),
+            Some("device-9".to_string()),
+            None,
+            None,
+        )
+        .await
+        .expect("Failed to generate stranger token");
+
+    // Another user's token can't name the session to keep, and nothing is revoked
+    assert!(jwt_service
+        .revoke_all_sessions(&user_id, Some(&stranger_token))
+        .await
+        .is_err());
+    assert!(jwt_service.validate_refresh_token(&token).await.is_ok());
+
+    // Neither can a token already rotated away
+    let (_, rotated, _) = jwt_service
+        .rotate_refresh_token(&token, Some("device-1".to_string()), None, None)
+        .await
+        .expect("Failed to rotate token");
+    assert!(jwt_service
+        .revoke_all_sessions(&user_id, Some(&token))
+        .await
+        .is_err());
+    assert!(jwt_service.validate_refresh_token(&rotated).await.is_ok());
+
+    // Without a session to keep, everything goes
+    let revoked = jwt_service
+        .revoke_all_sessions(&user_id, None)
+        .await
+        .expect("Failed to revoke sessions");
+    assert_eq!(revoked, 1);
+    assert!(jwt_service.validate_refresh_token(&rotated).await.is_err());
+
+    cleanup_test_user(&db_pool, user_uuid).await;
+    cleanup_test_user(&db_pool, stranger_uuid).await;
+}
+
 /// Additional stress test with multiple concurrent requests
 #[tokio::test]
 #[ignore = "DEV-107: High concurrency token rotation control not yet implemented"]
@@ -423,3 +423,61 @@ async fn test_cleanup_expired_tokens() {
 
     cleanup_test_data(&pool, user_id).await;
 }
+
+#[tokio::test]
+async fn test_prune_batch_respects_cutoff_and_batch_size() {
+    use qck_backend_core::schema::refresh_tokens;
+
+    let pool = setup_test_pool().await;
+    let user_id = create_test_user(&pool).await;
+    let mut conn = pool.get().await.expect("Failed to get connection");
+
+    // Expired well before the cutoff
+    for i in 0..3 {
+        RefreshToken::store(
+            &mut conn,
+            user_id,
+            &format!("prune-old-{}-{}", user_id, i),
+            Utc::now() - Duration::hours(3),
+            Uuid::new_v4().to_string(),
+            DeviceInfo::default(),
+        )
+        .await
+        .expect("Failed to store old token");
+    }
+
+    // Expired, but still inside the grace period
+    RefreshToken::store(
+        &mut conn,
+        user_id,
+        &format!("prune-recent-{}", user_id),
+        Utc::now() - Duration::minutes(10),
+        Uuid::new_v4().to_string(),
+        DeviceInfo::default(),
+    )
+    .await
+    .expect("Failed to store recently expired token");
+
+    let cutoff = Utc::now() - Duration::hours(1);
+    let deleted = RefreshToken::prune_batch(&mut conn, cutoff, 2)
+        .await
+        .expect("Failed to prune");
+    assert_eq!(deleted, 2, "Should delete no more than the batch size");
+
+    // Other tests may leave old rows behind, so drain until a short batch
+    while RefreshToken::prune_batch(&mut conn, cutoff, 100)
+        .await
+        .expect("Failed to prune")
+        == 100
+    {}
+
+    let remaining: i64 = refresh_tokens::table
+        .filter(refresh_tokens::user_id.eq(user_id))
+        .count()
+        .get_result(&mut conn)
+        .await
+        .expect("Failed to count");
+    assert_eq!(remaining, 1, "Token inside the grace period should remain");
+
+    cleanup_test_data(&pool, user_id).await;
+}
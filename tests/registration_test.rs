@@ -84,7 +84,7 @@ async fn test_registration_with_existing_email() {
     assert_eq!(response.status(), StatusCode::CONFLICT);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["code"], "EMAIL_EXISTS");
     assert!(body["message"].as_str().unwrap().contains("already exists"));
 }
 
@@ -110,8 +110,27 @@ async fn test_registration_with_weak_password() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
-    assert!(body["message"].as_str().unwrap().contains("password"));
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    let codes: Vec<&str> = body["details"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["field"] == "password")
+        .map(|d| d["code"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            "too_short",
+            "missing_uppercase",
+            "missing_digit",
+            "missing_special"
+        ]
+    );
+    assert!(body["message"]
+        .as_str()
+        .unwrap()
+        .starts_with("password: Password must be at least 8 characters"));
 }
 
 #[tokio::test]
@@ -136,7 +155,8 @@ async fn test_registration_password_mismatch() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    assert_eq!(body["details"][0]["field"], "password_confirmation");
     assert_eq!(body["message"].as_str().unwrap(), "Passwords do not match");
 }
 
@@ -162,8 +182,9 @@ async fn test_registration_invalid_email() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
-    assert!(body["message"].as_str().unwrap().contains("email"));
+    assert_eq!(body["code"], "VALIDATION_ERROR");
+    assert_eq!(body["details"][0]["field"], "email");
+    assert_eq!(body["details"][0]["message"], "Invalid email format");
 }
 
 #[tokio::test]
@@ -188,7 +209,7 @@ async fn test_registration_terms_not_accepted() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["details"][0]["field"], "accept_terms");
     assert!(body["message"]
         .as_str()
         .unwrap()
@@ -256,7 +277,7 @@ async fn test_registration_rate_limiting() {
             );
 
             let body: serde_json::Value = response.json().await;
-            assert!(!body["success"].as_bool().unwrap());
+            assert_eq!(body["code"], "RATE_LIMITED");
             assert!(body["message"]
                 .as_str()
                 .unwrap()
@@ -289,7 +310,7 @@ async fn test_registration_empty_full_name() {
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["details"][0]["field"], "full_name");
     assert!(body["message"]
         .as_str()
         .unwrap()
@@ -367,6 +388,6 @@ async fn test_registration_email_case_insensitive() {
     assert_eq!(response.status(), StatusCode::CONFLICT);
 
     let body: serde_json::Value = response.json().await;
-    assert!(!body["success"].as_bool().unwrap());
+    assert_eq!(body["code"], "EMAIL_EXISTS");
     assert!(body["message"].as_str().unwrap().contains("already exists"));
 }
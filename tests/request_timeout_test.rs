@@ -0,0 +1,93 @@
+// Request timeout middleware tests
+// Runs the middleware over a small router with fast, slow and streaming handlers
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    middleware,
+    routing::get,
+    Router,
+};
+use futures_util::stream;
+use qck_backend_core::middleware::request_timeout::request_timeout_metrics;
+use qck_backend_core::middleware::{request_timeout_middleware, RouteTimeout};
+use std::convert::Infallible;
+use std::time::Duration;
+use tower::ServiceExt;
+
+fn app(group: &'static str, millis: u64) -> Router {
+    Router::new()
+        .route("/fast", get(|| async { "fast" }))
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "slow"
+            }),
+        )
+        .route(
+            "/stream",
+            get(|| async {
+                // Headers go out at once; the body trickles in over longer than the budget
+                let chunks = stream::unfold(0, |sent| async move {
+                    if sent == 3 {
+                        return None;
+                    }
+                    tokio::time::sleep(Duration::from_millis(300)).await;
+                    Some((Ok::<_, Infallible>(format!("chunk{}\n", sent)), sent + 1))
+                });
+                Body::from_stream(chunks)
+            }),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            RouteTimeout::new(group, millis),
+            request_timeout_middleware,
+        ))
+}
+
+async fn get_response(app: Router, uri: &str) -> axum::response::Response {
+    app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_fast_request_passes_through() {
+    let response = get_response(app("test_fast", 500), "/fast").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_slow_request_returns_504_envelope() {
+    let before = request_timeout_metrics()
+        .timeouts
+        .get("test_slow")
+        .copied()
+        .unwrap_or(0);
+
+    let response = get_response(app("test_slow", 500), "/slow").await;
+    assert_eq!(response.status(), StatusCode::GATEWAY_TIMEOUT);
+
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["code"], "REQUEST_TIMEOUT");
+    assert!(json["message"].is_string());
+
+    assert_eq!(request_timeout_metrics().timeouts["test_slow"], before + 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_zero_budget_disables_timeout() {
+    let response = get_response(app("test_disabled", 0), "/slow").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_streamed_body_is_not_cut_off() {
+    let response = get_response(app("test_stream", 500), "/stream").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The full body takes 900ms, past the 500ms budget
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "chunk0\nchunk1\nchunk2\n");
+}
@@ -0,0 +1,131 @@
+// Google Safe Browsing v4 Lookup API client tests
+// Runs against a mocked HTTP server - no API key or network access required
+
+use qck_backend_core::utils::safe_browsing::{
+    SafeBrowsingClient, SafeBrowsingConfig, SafeBrowsingError, SafeBrowsingThreat,
+};
+use serde_json::json;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn test_config(server: &MockServer) -> SafeBrowsingConfig {
+    SafeBrowsingConfig {
+        api_key: "test-key".to_string(),
+        api_url: format!("{}/v4/threatMatches:find", server.uri()),
+        client_id: "qck-test".to_string(),
+        client_version: "0.0.0".to_string(),
+        negative_cache_seconds: 60,
+    }
+}
+
+#[tokio::test]
+async fn test_lookup_hit_returns_threats() {
+    let server = MockServer::start().await;
+    let bad_url = "http://malware.testing.google.test/testing/malware/";
+
+    Mock::given(method("POST"))
+        .and(path("/v4/threatMatches:find"))
+        .and(query_param("key", "test-key"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "matches": [{
+                "threatType": "MALWARE",
+                "platformType": "ANY_PLATFORM",
+                "threatEntryType": "URL",
+                "threat": { "url": bad_url },
+                "cacheDuration": "300s"
+            }, {
+                "threatType": "SOCIAL_ENGINEERING",
+                "platformType": "ANY_PLATFORM",
+                "threatEntryType": "URL",
+                "threat": { "url": bad_url },
+                "cacheDuration": "300s"
+            }]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = SafeBrowsingClient::new(test_config(&server));
+    let threats = client.check_url(bad_url, None).await.unwrap();
+
+    assert_eq!(
+        threats,
+        vec![
+            SafeBrowsingThreat::Malware,
+            SafeBrowsingThreat::SocialEngineering
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_lookup_miss_returns_empty() {
+    let server = MockServer::start().await;
+
+    // The API returns an empty object when nothing matches
+    Mock::given(method("POST"))
+        .and(path("/v4/threatMatches:find"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = SafeBrowsingClient::new(test_config(&server));
+    let threats = client
+        .check_url("https://example.com/", None)
+        .await
+        .unwrap();
+
+    assert!(threats.is_empty());
+}
+
+#[tokio::test]
+async fn test_quota_exceeded_triggers_backoff() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v4/threatMatches:find"))
+        .respond_with(ResponseTemplate::new(429).set_body_json(json!({
+            "error": { "code": 429, "status": "RESOURCE_EXHAUSTED" }
+        })))
+        // Second call must be short-circuited by the backoff, not sent
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = SafeBrowsingClient::new(test_config(&server));
+
+    let first = client.check_url("https://example.com/", None).await;
+    match first {
+        Err(SafeBrowsingError::QuotaExceeded(wait)) => {
+            // Minimum wait is 15 minutes, jittered up to 2x
+            assert!((15 * 60..=30 * 60).contains(&wait));
+        },
+        other => panic!("expected QuotaExceeded, got {:?}", other),
+    }
+
+    let second = client.check_url("https://example.org/", None).await;
+    assert!(matches!(second, Err(SafeBrowsingError::BackingOff(_))));
+}
+
+#[tokio::test]
+async fn test_lookup_batches_large_requests() {
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST"))
+        .and(path("/v4/threatMatches:find"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({})))
+        // 501 URLs must be split into two requests of at most 500 entries
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let urls: Vec<String> = (0..501)
+        .map(|i| format!("https://example{}.com/", i))
+        .collect();
+
+    let client = SafeBrowsingClient::new(test_config(&server));
+    let verdicts = client.lookup(&urls, None).await.unwrap();
+
+    assert_eq!(verdicts.len(), 501);
+    assert!(verdicts.values().all(|threats| threats.is_empty()));
+}
@@ -0,0 +1,167 @@
+// SSRF protection tests for the safe_http helper
+// Address classification is pure; request tests use a local mock server,
+// which must be refused because it listens on loopback
+
+use qck_backend_core::utils::safe_http::{
+    is_public_ip, resolve_public_addrs, validate_url, SafeHttpClient, SafeHttpError,
+};
+use std::net::IpAddr;
+use url::Url;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn ip(s: &str) -> IpAddr {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_public_ipv4_allowed() {
+    for addr in [
+        "8.8.8.8",
+        "1.1.1.1",
+        "93.184.216.34",
+        "172.32.0.1",
+        "100.128.0.1",
+    ] {
+        assert!(is_public_ip(ip(addr)), "{} should be public", addr);
+    }
+}
+
+#[test]
+fn test_private_and_special_ipv4_blocked() {
+    for addr in [
+        "10.0.0.1",        // private
+        "10.255.255.255",  // private
+        "172.16.0.1",      // private
+        "172.31.255.255",  // private
+        "192.168.1.1",     // private
+        "127.0.0.1",       // loopback
+        "127.8.8.8",       // loopback
+        "169.254.169.254", // link-local / cloud metadata
+        "0.0.0.0",         // unspecified
+        "0.1.2.3",         // "this network"
+        "100.64.0.1",      // carrier-grade NAT
+        "100.127.255.255", // carrier-grade NAT
+        "192.0.0.1",       // IETF protocol assignments
+        "192.0.2.1",       // documentation
+        "198.18.0.1",      // benchmarking
+        "224.0.0.1",       // multicast
+        "239.255.255.250", // multicast
+        "240.0.0.1",       // reserved
+        "255.255.255.255", // broadcast
+    ] {
+        assert!(!is_public_ip(ip(addr)), "{} should be blocked", addr);
+    }
+}
+
+#[test]
+fn test_public_ipv6_allowed() {
+    for addr in ["2606:4700:4700::1111", "2001:4860:4860::8888"] {
+        assert!(is_public_ip(ip(addr)), "{} should be public", addr);
+    }
+}
+
+#[test]
+fn test_private_and_special_ipv6_blocked() {
+    for addr in [
+        "::",             // unspecified
+        "::1",            // loopback
+        "fc00::1",        // unique local
+        "fd12:3456::1",   // unique local
+        "fe80::1",        // link-local
+        "fec0::1",        // site-local
+        "ff02::1",        // multicast
+        "2001:db8::1",    // documentation
+        "2001:0:4136::1", // Teredo
+        "100::1",         // discard
+    ] {
+        assert!(!is_public_ip(ip(addr)), "{} should be blocked", addr);
+    }
+}
+
+#[test]
+fn test_ipv6_embedding_private_ipv4_blocked() {
+    for addr in [
+        "::ffff:127.0.0.1",       // IPv4-mapped loopback
+        "::ffff:169.254.169.254", // IPv4-mapped metadata endpoint
+        "::ffff:10.0.0.1",        // IPv4-mapped private
+        "::10.0.0.1",             // IPv4-compatible private
+        "64:ff9b::a00:1",         // NAT64 -> 10.0.0.1
+        "2002:a00:1::1",          // 6to4 -> 10.0.0.1
+        "2002:a9fe:a9fe::1",      // 6to4 -> 169.254.169.254
+    ] {
+        assert!(!is_public_ip(ip(addr)), "{} should be blocked", addr);
+    }
+}
+
+#[test]
+fn test_ipv6_embedding_public_ipv4_allowed() {
+    for addr in ["::ffff:8.8.8.8", "64:ff9b::808:808", "2002:808:808::1"] {
+        assert!(is_public_ip(ip(addr)), "{} should be public", addr);
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_rejects_private_literals() {
+    for host in ["127.0.0.1", "169.254.169.254", "[::1]", "[::ffff:10.0.0.1]"] {
+        match resolve_public_addrs(host, 80).await {
+            Err(SafeHttpError::BlockedAddress(_)) => {},
+            other => panic!("{} should be blocked, got {:?}", host, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_rejects_localhost_name() {
+    match resolve_public_addrs("localhost", 80).await {
+        Err(SafeHttpError::BlockedAddress(addr)) => assert!(addr.is_loopback()),
+        // Some sandboxes have no resolver entry for localhost at all
+        Err(SafeHttpError::DnsResolutionFailed(_)) => {},
+        other => panic!("localhost should be blocked, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_resolve_accepts_public_literal() {
+    let addrs = resolve_public_addrs("8.8.8.8", 443).await.unwrap();
+    assert_eq!(addrs.len(), 1);
+    assert_eq!(addrs[0].port(), 443);
+}
+
+#[tokio::test]
+async fn test_validate_url_rejects_non_http_schemes() {
+    for url in [
+        "ftp://8.8.8.8/file",
+        "file:///etc/passwd",
+        "gopher://8.8.8.8/",
+    ] {
+        match validate_url(&Url::parse(url).unwrap()).await {
+            Err(SafeHttpError::UnsupportedScheme(_)) => {},
+            other => panic!("{} should be rejected, got {:?}", url, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_client_never_contacts_loopback_server() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let client = SafeHttpClient::new("test");
+    let result = client.get(&server.uri()).await;
+
+    assert!(matches!(result, Err(SafeHttpError::BlockedAddress(_))));
+    server.verify().await;
+}
+
+#[tokio::test]
+async fn test_client_rejects_metadata_endpoint() {
+    let client = SafeHttpClient::new("test");
+    let result = client.get("http://169.254.169.254/latest/meta-data/").await;
+
+    assert!(matches!(result, Err(SafeHttpError::BlockedAddress(_))));
+}
@@ -0,0 +1,183 @@
+// Security response header tests
+// Runs the middleware over a small router covering API, redirect, error and docs responses
+
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Redirect},
+    routing::get,
+    Json, Router,
+};
+use qck_backend_core::app_config::{Environment, SecurityHeadersConfig};
+use qck_backend_core::middleware::security_headers_middleware;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+fn app(config: SecurityHeadersConfig) -> Router {
+    Router::new()
+        .route(
+            "/v1/health",
+            get(|| async { Json(serde_json::json!({ "status": "ok" })) }),
+        )
+        .route("/v1/docs/", get(|| async { Html("<html>swagger</html>") }))
+        .route(
+            "/v1/framed",
+            get(|| async { ([(header::X_FRAME_OPTIONS, "SAMEORIGIN")], "framed") }),
+        )
+        .route(
+            "/{short_code}",
+            get(|| async { Redirect::temporary("https://example.com/") }),
+        )
+        .route(
+            "/gone/page",
+            get(|| async { (StatusCode::GONE, Html("<html>Link expired</html>")).into_response() }),
+        )
+        .layer(middleware::from_fn_with_state(
+            Arc::new(config),
+            security_headers_middleware,
+        ))
+}
+
+async fn get_response(app: Router, uri: &str) -> axum::response::Response {
+    app.oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap()
+}
+
+fn header_value<'a>(
+    response: &'a axum::response::Response,
+    name: header::HeaderName,
+) -> Option<&'a str> {
+    response.headers().get(name).map(|v| v.to_str().unwrap())
+}
+
+fn assert_default_headers(response: &axum::response::Response) {
+    assert_eq!(
+        header_value(response, header::X_CONTENT_TYPE_OPTIONS),
+        Some("nosniff")
+    );
+    assert_eq!(
+        header_value(response, header::X_FRAME_OPTIONS),
+        Some("DENY")
+    );
+    assert_eq!(
+        header_value(response, header::CONTENT_SECURITY_POLICY),
+        Some(SecurityHeadersConfig::DEFAULT_CONTENT_SECURITY_POLICY)
+    );
+    assert_eq!(
+        header_value(response, header::REFERRER_POLICY),
+        Some("strict-origin-when-cross-origin")
+    );
+}
+
+#[tokio::test]
+async fn test_headers_on_api_response() {
+    let config = SecurityHeadersConfig::for_environment(&Environment::Development);
+    let response = get_response(app(config), "/v1/health").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_default_headers(&response);
+}
+
+#[tokio::test]
+async fn test_headers_on_redirect_response() {
+    let config = SecurityHeadersConfig::for_environment(&Environment::Development);
+    let response = get_response(app(config), "/abc123").await;
+
+    assert_eq!(response.status(), StatusCode::TEMPORARY_REDIRECT);
+    assert_default_headers(&response);
+}
+
+#[tokio::test]
+async fn test_headers_on_error_responses() {
+    let config = SecurityHeadersConfig::for_environment(&Environment::Development);
+
+    let response = get_response(app(config.clone()), "/gone/page").await;
+    assert_eq!(response.status(), StatusCode::GONE);
+    assert_default_headers(&response);
+
+    // Unmatched routes go through the fallback and still get headers
+    let response = get_response(app(config), "/no/such/route").await;
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    assert_default_headers(&response);
+}
+
+#[tokio::test]
+async fn test_hsts_only_in_production() {
+    let response = get_response(
+        app(SecurityHeadersConfig::for_environment(
+            &Environment::Staging,
+        )),
+        "/v1/health",
+    )
+    .await;
+    assert!(header_value(&response, header::STRICT_TRANSPORT_SECURITY).is_none());
+
+    let response = get_response(
+        app(SecurityHeadersConfig::for_environment(
+            &Environment::Production,
+        )),
+        "/v1/health",
+    )
+    .await;
+    assert_eq!(
+        header_value(&response, header::STRICT_TRANSPORT_SECURITY),
+        Some(SecurityHeadersConfig::DEFAULT_STRICT_TRANSPORT_SECURITY)
+    );
+}
+
+#[tokio::test]
+async fn test_docs_page_allows_same_origin_framing() {
+    let config = SecurityHeadersConfig::for_environment(&Environment::Development);
+    let response = get_response(app(config), "/v1/docs/").await;
+
+    assert_eq!(
+        header_value(&response, header::X_FRAME_OPTIONS),
+        Some("SAMEORIGIN")
+    );
+    let csp = header_value(&response, header::CONTENT_SECURITY_POLICY).unwrap();
+    assert!(csp.contains("frame-ancestors 'self'"));
+    assert!(csp.contains("https://unpkg.com"));
+}
+
+#[tokio::test]
+async fn test_individual_headers_can_be_disabled() {
+    let config = SecurityHeadersConfig {
+        frame_options: String::new(),
+        content_security_policy: String::new(),
+        ..SecurityHeadersConfig::for_environment(&Environment::Production)
+    };
+    let response = get_response(app(config), "/v1/health").await;
+
+    assert!(header_value(&response, header::X_FRAME_OPTIONS).is_none());
+    assert!(header_value(&response, header::CONTENT_SECURITY_POLICY).is_none());
+    assert_eq!(
+        header_value(&response, header::X_CONTENT_TYPE_OPTIONS),
+        Some("nosniff")
+    );
+    assert!(header_value(&response, header::STRICT_TRANSPORT_SECURITY).is_some());
+}
+
+#[tokio::test]
+async fn test_disabled_middleware_adds_nothing() {
+    let config = SecurityHeadersConfig {
+        enabled: false,
+        ..SecurityHeadersConfig::for_environment(&Environment::Production)
+    };
+    let response = get_response(app(config), "/v1/health").await;
+
+    assert!(header_value(&response, header::X_CONTENT_TYPE_OPTIONS).is_none());
+    assert!(header_value(&response, header::STRICT_TRANSPORT_SECURITY).is_none());
+}
+
+#[tokio::test]
+async fn test_handler_headers_are_not_overridden() {
+    let config = SecurityHeadersConfig::for_environment(&Environment::Development);
+    let response = get_response(app(config), "/v1/framed").await;
+
+    assert_eq!(
+        header_value(&response, header::X_FRAME_OPTIONS),
+        Some("SAMEORIGIN")
+    );
+}
@@ -0,0 +1,135 @@
+// Service-to-service request signing tests
+// Run against the Redis in .env.test, which keeps the nonces; every test signs with fresh nonces
+
+use std::sync::Arc;
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Extension,
+    http::{Request, StatusCode},
+    middleware,
+    response::Response,
+    routing::post,
+    Router,
+};
+use chrono::Utc;
+use qck_backend_core::app_config::{ServiceAuthConfig, ServiceKey};
+use qck_backend_core::db::{RedisConfig, RedisPool};
+use qck_backend_core::middleware::{require_service_auth, ServiceAuth};
+use qck_backend_core::services::service_auth::{ServiceCaller, ServiceSigner};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+const SECRET: &str = "cloud-secret-at-least-32-bytes-long!!";
+const PATH: &str = "/v1/internal/echo?source=cloud";
+
+async fn redis_pool() -> RedisPool {
+    dotenv::from_filename(".env.test").ok();
+    RedisPool::new(RedisConfig::from_env())
+        .await
+        .expect("Redis must be available for service auth tests")
+}
+
+async fn app() -> Router {
+    let config = ServiceAuthConfig {
+        keys: vec![ServiceKey {
+            name: "cloud".to_string(),
+            secret: SECRET.to_string(),
+        }],
+        max_clock_skew_seconds: 300,
+    };
+    let auth = Arc::new(ServiceAuth::new(&config, redis_pool().await));
+
+    // Nested like the real routers, so the signed path keeps the /v1 prefix
+    let internal = Router::new()
+        .route(
+            "/internal/echo",
+            post(
+                |Extension(caller): Extension<ServiceCaller>, body: String| async move {
+                    format!("{}:{}", caller.key_id, body)
+                },
+            ),
+        )
+        .route_layer(middleware::from_fn_with_state(auth, require_service_auth));
+    Router::new().nest("/v1", internal)
+}
+
+async fn send(app: Router, headers: &[(&'static str, String)], body: &str) -> Response {
+    let mut request = Request::builder().method("POST").uri(PATH);
+    for (name, value) in headers {
+        request = request.header(*name, value);
+    }
+    app.oneshot(request.body(Body::from(body.to_string())).unwrap())
+        .await
+        .unwrap()
+}
+
+async fn body_json(response: Response) -> serde_json::Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+fn signer() -> ServiceSigner {
+    ServiceSigner::new("cloud", SECRET)
+}
+
+#[tokio::test]
+async fn test_signed_request_reaches_handler_with_caller() {
+    let body = r#"{"link":"abc"}"#;
+    let headers = signer().sign("POST", PATH, body.as_bytes());
+
+    let response = send(app().await, &headers, body).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(&bytes[..], format!("cloud:{}", body).as_bytes());
+}
+
+#[tokio::test]
+async fn test_replayed_request_is_rejected() {
+    let app = app().await;
+    let headers = signer().sign("POST", PATH, b"{}");
+
+    let first = send(app.clone(), &headers, "{}").await;
+    assert_eq!(first.status(), StatusCode::OK);
+
+    let replay = send(app, &headers, "{}").await;
+    assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(body_json(replay).await["code"], "SERVICE_REQUEST_REPLAYED");
+}
+
+#[tokio::test]
+async fn test_request_outside_clock_skew_is_rejected() {
+    let headers = signer().sign_at(
+        "POST",
+        PATH,
+        b"{}",
+        Utc::now().timestamp() - 600,
+        &Uuid::new_v4().simple().to_string(),
+    );
+
+    let response = send(app().await, &headers, "{}").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(body_json(response).await["code"], "SERVICE_CLOCK_SKEW");
+}
+
+#[tokio::test]
+async fn test_unsigned_or_tampered_request_is_rejected() {
+    let app = app().await;
+
+    let unsigned = send(app.clone(), &[], "{}").await;
+    assert_eq!(unsigned.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(body_json(unsigned).await["code"], "SERVICE_AUTH_REQUIRED");
+
+    let headers = signer().sign("POST", PATH, b"{}");
+    let tampered = send(app.clone(), &headers, r#"{"admin":true}"#).await;
+    assert_eq!(tampered.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        body_json(tampered).await["code"],
+        "INVALID_SERVICE_SIGNATURE"
+    );
+
+    let wrong_key =
+        ServiceSigner::new("cloud", "another-secret-at-least-32-bytes!!").sign("POST", PATH, b"{}");
+    let response = send(app, &wrong_key, "{}").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
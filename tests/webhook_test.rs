@@ -0,0 +1,187 @@
+// Webhook delivery and security alert tests
+// Uses a local mock receiver to check signing, retries and alert payloads
+
+use chrono::Utc;
+use qck_backend_core::services::security_alerts::{
+    hash_user_id, SecurityAlertEvent, SecurityAlertService,
+};
+use qck_backend_core::services::webhook::{
+    sign_payload, WebhookError, WebhookSender, EVENT_HEADER, SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use qck_backend_core::utils::security_scanner::{
+    SecurityRiskLevel, SecurityScanResult, ThreatType,
+};
+use std::time::Duration;
+use uuid::Uuid;
+use wiremock::matchers::{header_exists, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn scan(is_safe: bool, threat_score: u8) -> SecurityScanResult {
+    SecurityScanResult {
+        url: "https://malicious.example.tk/login".to_string(),
+        is_safe,
+        threat_score,
+        risk_level: if is_safe {
+            SecurityRiskLevel::Medium
+        } else {
+            SecurityRiskLevel::Critical
+        },
+        threats_detected: vec![ThreatType::Phishing],
+        warnings: vec!["Known phishing domain".to_string()],
+        scan_timestamp: Utc::now(),
+        scan_duration_ms: 5,
+    }
+}
+
+fn fast_sender() -> WebhookSender {
+    WebhookSender::new()
+        .with_max_retries(3)
+        .with_retry_delay(Duration::from_millis(10))
+}
+
+#[test]
+fn test_signature_is_deterministic_and_keyed() {
+    let body = br#"{"event":"security.url_blocked"}"#;
+    let signature = sign_payload("secret", 1_700_000_000, body);
+
+    assert!(signature.starts_with("sha256="));
+    assert_eq!(signature.len(), "sha256=".len() + 64);
+    assert_eq!(signature, sign_payload("secret", 1_700_000_000, body));
+    assert_ne!(signature, sign_payload("other", 1_700_000_000, body));
+    assert_ne!(signature, sign_payload("secret", 1_700_000_001, body));
+}
+
+#[tokio::test]
+async fn test_delivery_is_signed() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/alerts"))
+        .and(header_exists(SIGNATURE_HEADER))
+        .and(header_exists(TIMESTAMP_HEADER))
+        .and(header_exists(EVENT_HEADER))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let payload = serde_json::json!({ "hello": "world" });
+    fast_sender()
+        .deliver(
+            &format!("{}/alerts", server.uri()),
+            "secret",
+            "test.event",
+            &payload,
+        )
+        .await
+        .unwrap();
+
+    // The receiver can verify the signature from the raw body and timestamp
+    let request = &server.received_requests().await.unwrap()[0];
+    let timestamp: i64 = request.headers[TIMESTAMP_HEADER]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(
+        request.headers[SIGNATURE_HEADER].to_str().unwrap(),
+        sign_payload("secret", timestamp, &request.body)
+    );
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(2)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+
+    fast_sender()
+        .deliver(
+            &server.uri(),
+            "secret",
+            "test.event",
+            &serde_json::json!({}),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400))
+        .mount(&server)
+        .await;
+
+    let result = fast_sender()
+        .deliver(
+            &server.uri(),
+            "secret",
+            "test.event",
+            &serde_json::json!({}),
+        )
+        .await;
+
+    assert!(matches!(result, Err(WebhookError::Status(400))));
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[test]
+fn test_alert_decision() {
+    let service = SecurityAlertService::new("https://alerts.example.com", "secret")
+        .with_threat_score_threshold(50);
+    let user_id = Uuid::new_v4();
+
+    let blocked = service.alert_for_scan(user_id, &scan(false, 90)).unwrap();
+    assert_eq!(blocked.event, SecurityAlertEvent::UrlBlocked);
+    assert_eq!(blocked.threat_score, 90);
+    assert_eq!(blocked.threats_detected, vec![ThreatType::Phishing]);
+
+    let risky = service.alert_for_scan(user_id, &scan(true, 55)).unwrap();
+    assert_eq!(risky.event, SecurityAlertEvent::HighRiskUrlAccepted);
+
+    assert!(service.alert_for_scan(user_id, &scan(true, 20)).is_none());
+}
+
+#[test]
+fn test_alert_hashes_user_id() {
+    let service = SecurityAlertService::new("https://alerts.example.com", "secret");
+    let user_id = Uuid::new_v4();
+
+    let alert = service.alert_for_scan(user_id, &scan(false, 100)).unwrap();
+    assert_eq!(alert.user_id_hash, hash_user_id(user_id));
+    assert_eq!(alert.user_id_hash.len(), 64);
+
+    let json = serde_json::to_string(&alert).unwrap();
+    assert!(!json.contains(&user_id.to_string()));
+    assert!(json.contains("\"event\":\"url_blocked\""));
+}
+
+#[tokio::test]
+async fn test_notify_does_not_wait_for_delivery() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(5)))
+        .mount(&server)
+        .await;
+
+    let service = SecurityAlertService::new(server.uri(), "secret");
+    let started = std::time::Instant::now();
+    service.notify(Uuid::new_v4(), &scan(false, 100));
+    assert!(started.elapsed() < Duration::from_millis(100));
+}
+
+#[test]
+fn test_disabled_without_webhook_url() {
+    let service = SecurityAlertService::new("", "");
+    assert!(!service.is_enabled());
+}